@@ -8,14 +8,31 @@ const FRONTEND_DIR: &str = "../frontend";
 fn main() {
     println!("cargo:rerun-if-changed={}/src", FRONTEND_DIR);
     println!("cargo:rerun-if-changed={}/index.html", FRONTEND_DIR);
+    println!("cargo:rerun-if-changed={}/sat.html", FRONTEND_DIR);
 
     build_frontend(FRONTEND_DIR);
 }
 
+/// builds the default web app into `dist` and the SAT demo with the `sat` feature into `dist/sat`, served under `/sat/`
+///
+/// the default build cleans `dist`, so it goes first
 fn build_frontend<P: AsRef<Path>>(source: P) {
     Command::new("trunk")
         .args(&["build", "--release"])
         .current_dir(source.as_ref())
         .status()
         .expect("Failed to build Frontend");
+    Command::new("trunk")
+        .args(&[
+            "build",
+            "--release",
+            "sat.html",
+            "--dist",
+            "dist/sat",
+            "--public-url",
+            "/sat/",
+        ])
+        .current_dir(source.as_ref())
+        .status()
+        .expect("Failed to build the SAT demo");
 }
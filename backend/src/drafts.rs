@@ -0,0 +1,216 @@
+//! Shared drafts relayed between their editors, see [`game::model::collab`]
+//!
+//! every draft id has a room keeping a copy of the draft, so editors joining later start from the draft as edited
+//! so far. Edits and cursor moves of an editor are checked against the copy of the room and relayed to the other
+//! editors of the room, the room is removed once its last editor leaves
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use game::dto::DraftMessageDto;
+use game::model::collab::{CellEdit, Cursor, EditorId, SharedDraft};
+use game::prelude::{Grid, Square, Tile};
+use rocket::tokio::sync::broadcast::{channel, Receiver, Sender};
+
+use crate::presence::is_valid_id;
+
+/// upper bound of the drafts edited at once, editors of new drafts beyond are rejected
+pub const MAX_ROOMS: usize = 10_000;
+
+/// messages buffered for a slow editor, editors falling further behind are disconnected
+const BUFFERED_MESSAGES: usize = 256;
+
+/// editor id of the copy of the room, editors are numbered from 1
+const ROOM_EDITOR: EditorId = 0;
+
+/// message relayed to the editors of a room together with the editor it originates from
+pub type Relayed = (EditorId, DraftMessageDto);
+
+#[derive(Debug)]
+struct Room {
+    draft: SharedDraft,
+    next_editor: EditorId,
+    editors: usize,
+    sender: Sender<Relayed>,
+}
+
+/// Rooms of the drafts currently edited, shared by the connections of the live listener
+#[derive(Debug, Default)]
+pub struct DraftRooms {
+    rooms: Mutex<HashMap<String, Room>>,
+}
+
+impl DraftRooms {
+    /// Joins the draft, opening it with the level unless other editors have opened it before
+    ///
+    /// returns the answer to the editor, its id and the messages relayed from the other editors
+    pub fn join(
+        &self,
+        id: &str,
+        level: Grid<Tile<Square>>,
+    ) -> Result<(DraftMessageDto, EditorId, Receiver<Relayed>), String> {
+        if !is_valid_id(id) {
+            return Err("invalid draft id".to_string());
+        }
+        let mut rooms = self.rooms.lock().unwrap_or_else(|e| e.into_inner());
+        if rooms.len() >= MAX_ROOMS && !rooms.contains_key(id) {
+            return Err("too many drafts are edited".to_string());
+        }
+        let room = rooms.entry(id.to_string()).or_insert_with(|| Room {
+            draft: SharedDraft::new(ROOM_EDITOR, level),
+            next_editor: ROOM_EDITOR + 1,
+            editors: 0,
+            sender: channel(BUFFERED_MESSAGES).0,
+        });
+        let editor = room.next_editor;
+        room.next_editor += 1;
+        room.editors += 1;
+        Ok((
+            DraftMessageDto::joined(editor, &room.draft),
+            editor,
+            room.sender.subscribe(),
+        ))
+    }
+
+    /// Relays an edit or cursor move of the editor to the other editors of the draft
+    ///
+    /// fails for other messages and for edits or cursors outside of the draft or of other editors
+    pub fn relay(&self, id: &str, from: EditorId, message: DraftMessageDto) -> Result<(), String> {
+        let mut rooms = self.rooms.lock().unwrap_or_else(|e| e.into_inner());
+        let room = rooms.get_mut(id).ok_or("unknown draft")?;
+        match &message {
+            DraftMessageDto::Edit(edit) => {
+                let edit = CellEdit::try_from(*edit)?;
+                if edit.version.editor != from {
+                    return Err("edits carry the version of their editor".to_string());
+                }
+                room.draft
+                    .apply(edit)
+                    .map_err(|_| "edit outside of the draft".to_string())?;
+            }
+            DraftMessageDto::Cursor(cursor) => {
+                let cursor = Cursor::from(*cursor);
+                if cursor.editor != from || room.draft.grid().get(cursor.index).is_none() {
+                    return Err("cursor outside of the draft".to_string());
+                }
+            }
+            _ => return Err("expected an edit or a cursor".to_string()),
+        }
+        // fails only without other editors to relay to
+        room.sender.send((from, message)).ok();
+        Ok(())
+    }
+
+    /// Leaves the draft, the other editors are told to remove the cursor of the editor
+    pub fn leave(&self, id: &str, editor: EditorId) {
+        let mut rooms = self.rooms.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(room) = rooms.get_mut(id) {
+            room.editors -= 1;
+            if room.editors == 0 {
+                rooms.remove(id);
+            } else {
+                room.sender
+                    .send((editor, DraftMessageDto::Left { editor }))
+                    .ok();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use game::dto::{CursorDto, DraftMessageDto};
+    use game::generator::fastgen::generate;
+    use game::model::collab::CellEdit;
+    use game::prelude::Coordinate;
+
+    use super::DraftRooms;
+
+    const ID: &str = "draft0001";
+
+    #[test]
+    fn edits_are_relayed_to_the_other_editors() {
+        let rooms = DraftRooms::default();
+        let grid = generate(Coordinate::new(3, 3), 1);
+        let (joined, ada, mut ada_inbox) = rooms.join(ID, grid.clone()).unwrap();
+        let mut draft = joined.to_draft().unwrap();
+        let index = Coordinate::new(1, 1);
+        let edit = draft.edit(index, grid[index].rotated_clockwise(1)).unwrap();
+        rooms
+            .relay(ID, ada, DraftMessageDto::Edit(edit.into()))
+            .unwrap();
+        // later editors join the draft as edited so far, not with their own level
+        let other = generate(Coordinate::new(3, 3), 2);
+        let (joined, bob, mut bob_inbox) = rooms.join(ID, other.clone()).unwrap();
+        assert_ne!(ada, bob);
+        assert_eq!(joined.to_draft().unwrap().grid(), draft.grid());
+        let cursor = CursorDto {
+            editor: bob,
+            row: 0,
+            column: 2,
+        };
+        rooms
+            .relay(ID, bob, DraftMessageDto::Cursor(cursor))
+            .unwrap();
+        assert_eq!(
+            ada_inbox.try_recv().unwrap(),
+            (ada, DraftMessageDto::Edit(edit.into()))
+        );
+        assert_eq!(
+            ada_inbox.try_recv().unwrap(),
+            (bob, DraftMessageDto::Cursor(cursor))
+        );
+        // editors receive what was relayed after they joined
+        assert_eq!(
+            bob_inbox.try_recv().unwrap(),
+            (bob, DraftMessageDto::Cursor(cursor))
+        );
+        drop(bob_inbox);
+        rooms.leave(ID, bob);
+        assert_eq!(
+            ada_inbox.try_recv().unwrap(),
+            (bob, DraftMessageDto::Left { editor: bob })
+        );
+        // the room is removed with its last editor, so the next editor opens the draft anew
+        rooms.leave(ID, ada);
+        let (joined, _, _) = rooms.join(ID, other.clone()).unwrap();
+        assert_eq!(joined.to_draft().unwrap().grid(), &other);
+    }
+
+    #[test]
+    fn foreign_and_misplaced_messages_are_rejected() {
+        let rooms = DraftRooms::default();
+        let grid = generate(Coordinate::new(2, 2), 1);
+        assert!(rooms.join("../draft", grid.clone()).is_err());
+        let (joined, editor, _) = rooms.join(ID, grid.clone()).unwrap();
+        let mut draft = joined.to_draft().unwrap();
+        let edit = draft
+            .edit(Coordinate::new(0, 0), grid[Coordinate::new(0, 0)])
+            .unwrap();
+        assert!(rooms
+            .relay(ID, editor + 1, DraftMessageDto::Edit(edit.into()))
+            .is_err());
+        let outside = CellEdit {
+            index: Coordinate::new(2, 0),
+            ..edit
+        };
+        assert!(rooms
+            .relay(ID, editor, DraftMessageDto::Edit(outside.into()))
+            .is_err());
+        let cursor = CursorDto {
+            editor,
+            row: -1,
+            column: 0,
+        };
+        assert!(rooms
+            .relay(ID, editor, DraftMessageDto::Cursor(cursor))
+            .is_err());
+        assert!(rooms
+            .relay(ID, editor, DraftMessageDto::join(&grid))
+            .is_err());
+        assert!(rooms
+            .relay("draft0002", editor, DraftMessageDto::Edit(edit.into()))
+            .is_err());
+    }
+}
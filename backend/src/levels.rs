@@ -0,0 +1,171 @@
+//! Levels shared by the players, stored in an SQLite database
+//!
+//! levels are stored as JSON of their [`LevelDto`] together with their difficulty and the content checksum of
+//! their tiles. Equal levels are stored once, so uploads can be retried and the same level uploaded from several
+//! devices keeps its id
+
+use std::path::Path;
+use std::sync::Mutex;
+
+use game::dto::SharedLevelDto;
+use game::model::symmetry::content_checksum;
+use game::prelude::{Difficulty, LevelDto};
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// upper bound of the levels listed by [`LevelStore::browse`]
+pub const BROWSE_LIMIT: usize = 100;
+
+/// Database of the shared levels, managed as rocket state
+#[derive(Debug)]
+pub struct LevelStore {
+    connection: Mutex<Connection>,
+}
+
+impl LevelStore {
+    /// Opens the database file, creating it and its table if missing
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        if let Some(directory) = path.as_ref().parent() {
+            std::fs::create_dir_all(directory).map_err(|e| e.to_string())?;
+        }
+        Connection::open(path)
+            .map_err(|e| e.to_string())
+            .and_then(LevelStore::with_connection)
+    }
+
+    fn with_connection(connection: Connection) -> Result<Self, String> {
+        connection
+            .execute(
+                "CREATE TABLE IF NOT EXISTS levels (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    checksum TEXT NOT NULL UNIQUE,
+                    difficulty TEXT NOT NULL,
+                    level TEXT NOT NULL
+                )",
+                [],
+            )
+            .map_err(|e| e.to_string())?;
+        Ok(LevelStore {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    /// Stores the level with its difficulty, returns the stored level
+    ///
+    /// a level with the same tiles is stored only once, its id and stored fields are returned instead
+    pub fn insert(
+        &self,
+        level: &LevelDto,
+        difficulty: Difficulty,
+    ) -> Result<SharedLevelDto, String> {
+        let checksum = format!("{:016x}", content_checksum(&level.to_grid()?));
+        let json = serde_json::to_string(level).map_err(|e| e.to_string())?;
+        let connection = self.connection.lock().unwrap_or_else(|e| e.into_inner());
+        connection
+            .execute(
+                "INSERT OR IGNORE INTO levels (checksum, difficulty, level) VALUES (?1, ?2, ?3)",
+                params![checksum, difficulty.to_string(), json],
+            )
+            .map_err(|e| e.to_string())?;
+        connection
+            .query_row(
+                "SELECT id, difficulty, level FROM levels WHERE checksum = ?1",
+                params![checksum],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .map_err(|e| e.to_string())
+            .and_then(shared_level)
+    }
+
+    /// The stored level with the id, if there is one
+    pub fn get(&self, id: u64) -> Result<Option<SharedLevelDto>, String> {
+        let connection = self.connection.lock().unwrap_or_else(|e| e.into_inner());
+        connection
+            .query_row(
+                "SELECT id, difficulty, level FROM levels WHERE id = ?1",
+                params![id as i64],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()
+            .map_err(|e| e.to_string())?
+            .map(shared_level)
+            .transpose()
+    }
+
+    /// Latest levels, of the difficulty if given, newest first and at most [`BROWSE_LIMIT`] of them
+    pub fn browse(&self, difficulty: Option<Difficulty>) -> Result<Vec<SharedLevelDto>, String> {
+        let connection = self.connection.lock().unwrap_or_else(|e| e.into_inner());
+        let mut statement = connection
+            .prepare(
+                "SELECT id, difficulty, level FROM levels
+                WHERE ?1 IS NULL OR difficulty = ?1
+                ORDER BY id DESC LIMIT ?2",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = statement
+            .query_map(
+                params![difficulty.map(|d| d.to_string()), BROWSE_LIMIT as i64],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .map_err(|e| e.to_string())?;
+        rows.map(|row| row.map_err(|e| e.to_string()).and_then(shared_level))
+            .collect()
+    }
+}
+
+/// level of a row of id, difficulty and JSON of the level
+fn shared_level((id, difficulty, json): (i64, String, String)) -> Result<SharedLevelDto, String> {
+    let level = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+    Ok(SharedLevelDto::new(id as u64, difficulty.parse()?, level))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use game::prelude::{generate, Coordinate, Difficulty, LevelDto};
+
+    use rusqlite::Connection;
+
+    use super::LevelStore;
+
+    /// store held in memory
+    fn store() -> LevelStore {
+        LevelStore::with_connection(Connection::open_in_memory().unwrap()).unwrap()
+    }
+
+    fn level(seed: u64) -> LevelDto {
+        LevelDto::from(&generate(Coordinate::new(4, 4), seed))
+    }
+
+    #[test]
+    fn equal_levels_are_stored_once() {
+        let store = store();
+        let first = store.insert(&level(1), Difficulty::Easy).unwrap();
+        let second = store.insert(&level(2), Difficulty::Easy).unwrap();
+        assert_ne!(first.id, second.id);
+        assert_eq!(store.insert(&level(1), Difficulty::Hard), Ok(first.clone()));
+        assert_eq!(store.get(first.id), Ok(Some(first)));
+        assert_eq!(store.get(42), Ok(None));
+    }
+
+    #[test]
+    fn levels_are_browsed_by_difficulty_newest_first() {
+        let store = store();
+        for (seed, difficulty) in [Difficulty::Easy, Difficulty::Hard, Difficulty::Easy]
+            .into_iter()
+            .enumerate()
+        {
+            store.insert(&level(seed as u64), difficulty).unwrap();
+        }
+        let ids = |difficulty| {
+            store
+                .browse(difficulty)
+                .unwrap()
+                .iter()
+                .map(|level| level.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids(None), vec![3, 2, 1]);
+        assert_eq!(ids(Some(Difficulty::Easy)), vec![3, 1]);
+        assert!(ids(Some(Difficulty::Medium)).is_empty());
+    }
+}
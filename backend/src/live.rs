@@ -0,0 +1,154 @@
+//! Runs streamed to the visualizer and shared drafts relayed between their editors over WebSockets
+//!
+//! rocket cannot upgrade connections to WebSockets, so both are served by a separate listener on the live port.
+//! For runs, see [`game::solver::snapshot`], a client sends a [`LiveRunRequestDto`] as JSON text message and
+//! receives every frame of the run as binary message, the server closes the connection after the last frame.
+//! Rejected requests are answered with the reason as text message. Runs are computed on the blocking thread pool
+//! and stop once the client disconnects.
+//!
+//! Editors of a shared draft connect to `/drafts/<id>` instead and exchange [`DraftMessageDto`]s as JSON text
+//! messages, see [`crate::drafts`]. Rejected joins are answered with the reason, editors sending rejected messages
+//! or lagging behind the others are disconnected
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use game::dto::{DraftMessageDto, LiveRunRequestDto};
+use game::solver::snapshot::SnapshotEncoder;
+use rocket::futures::{SinkExt, StreamExt};
+use rocket::tokio::net::{TcpListener, TcpStream};
+use rocket::tokio::sync::mpsc::{channel, Sender};
+use rocket::tokio::task::spawn_blocking;
+use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+use tokio_tungstenite::{accept_hdr_async, tungstenite::Message, WebSocketStream};
+
+use crate::drafts::DraftRooms;
+
+/// frames computed ahead of a slow client, the run pauses while the buffer is full
+const BUFFERED_FRAMES: usize = 64;
+
+/// Accepts visualizer and editor connections until the server shuts down
+pub async fn serve(address: SocketAddr, rooms: Arc<DraftRooms>) -> Result<(), String> {
+    let listener = TcpListener::bind(address)
+        .await
+        .map_err(|e| e.to_string())?;
+    loop {
+        let (stream, peer) = listener.accept().await.map_err(|e| e.to_string())?;
+        let rooms = rooms.clone();
+        rocket::tokio::spawn(async move {
+            if let Err(e) = connect(stream, rooms).await {
+                println!("live connection of {peer} failed: {e}");
+            }
+        });
+    }
+}
+
+// the error response of the handshake callback is given by tungstenite
+#[allow(clippy::result_large_err)]
+async fn connect(stream: TcpStream, rooms: Arc<DraftRooms>) -> Result<(), String> {
+    let mut path = String::new();
+    let socket = accept_hdr_async(stream, |request: &Request, response: Response| {
+        path = request.uri().path().to_string();
+        Ok(response)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    match path.strip_prefix("/drafts/") {
+        Some(id) => relay_draft(socket, id, &rooms).await,
+        None => stream_run(socket).await,
+    }
+}
+
+async fn stream_run(mut socket: WebSocketStream<TcpStream>) -> Result<(), String> {
+    let request = match socket.next().await {
+        Some(Ok(Message::Text(text))) => {
+            serde_json::from_str::<LiveRunRequestDto>(&text).map_err(|e| e.to_string())
+        }
+        _ => Err("expected a run request".to_string()),
+    };
+    let (sender, mut frames) = channel(BUFFERED_FRAMES);
+    drop(spawn_blocking(move || run(request, sender)));
+    while let Some(frame) = frames.recv().await {
+        let message = frame.map_or_else(Message::Text, Message::Binary);
+        socket.send(message).await.map_err(|e| e.to_string())?;
+    }
+    socket.close(None).await.map_err(|e| e.to_string())
+}
+
+/// sends the frames of the run until it ends, fails or the receiver is dropped
+fn run(request: Result<LiveRunRequestDto, String>, sender: Sender<Result<Vec<u8>, String>>) {
+    let snapshots = match request.and_then(|request| request.snapshots()) {
+        Ok(snapshots) => snapshots,
+        Err(e) => {
+            sender.blocking_send(Err(e)).ok();
+            return;
+        }
+    };
+    let mut encoder = SnapshotEncoder::new();
+    for snapshot in snapshots {
+        let frame = encoder.encode(&snapshot);
+        let failed = frame.is_err();
+        if sender.blocking_send(frame).is_err() || failed {
+            return;
+        }
+    }
+}
+
+/// relays the messages of the editor until it disconnects or sends a rejected message
+async fn relay_draft(
+    mut socket: WebSocketStream<TcpStream>,
+    id: &str,
+    rooms: &DraftRooms,
+) -> Result<(), String> {
+    let joined = match socket.next().await {
+        Some(Ok(Message::Text(text))) => serde_json::from_str::<DraftMessageDto>(&text)
+            .map_err(|e| e.to_string())
+            .and_then(|message| message.to_level())
+            .and_then(|level| rooms.join(id, level)),
+        _ => Err("expected to join the draft".to_string()),
+    };
+    let (joined, editor, mut relayed) = match joined {
+        Ok(joined) => joined,
+        Err(e) => {
+            socket.send(Message::Text(e)).await.ok();
+            return socket.close(None).await.map_err(|e| e.to_string());
+        }
+    };
+    let (mut sink, mut stream) = socket.split();
+    let mut messages = vec![joined];
+    let forward = rocket::tokio::spawn(async move {
+        loop {
+            for message in messages.drain(..) {
+                let text = serde_json::to_string(&message).expect("serializable");
+                if sink.send(Message::Text(text)).await.is_err() {
+                    return;
+                }
+            }
+            // editors lagging behind would miss edits, so they are disconnected
+            match relayed.recv().await {
+                Ok((from, message)) if from != editor => messages.push(message),
+                Ok(_) => {}
+                Err(_) => break,
+            }
+        }
+        sink.close().await.ok();
+    });
+    let mut result = Ok(());
+    while let Some(message) = stream.next().await {
+        let relayed = match message {
+            Ok(Message::Text(text)) => serde_json::from_str::<DraftMessageDto>(&text)
+                .map_err(|e| e.to_string())
+                .and_then(|message| rooms.relay(id, editor, message)),
+            Ok(Message::Close(_)) => break,
+            Ok(_) => Ok(()),
+            Err(e) => Err(e.to_string()),
+        };
+        if let Err(e) = relayed {
+            result = Err(e);
+            break;
+        }
+    }
+    rooms.leave(id, editor);
+    forward.abort();
+    result
+}
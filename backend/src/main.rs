@@ -2,9 +2,57 @@
 #[macro_use]
 extern crate rocket;
 
+mod drafts;
+mod levels;
+mod live;
+mod presence;
+
+use game::archive::{LevelArchive, EXTENSION};
+use game::dto::{
+    BenchReportDto, GenerateRequestDto, GeneratedLevelDto, PackDto, PresenceDto, SharedLevelDto,
+    SolutionsDto, SolveRequestDto, ValidationDto, MAX_GENERATED_SIZE,
+};
+use game::levelpack::{read_packed_level, write_packed_level};
+use game::model::parser::{parse_annotated_level, trim_trailing_newlines, unicode_to_tile};
+use game::prelude::{Difficulty, Grid, LevelDto, Square, Tile};
+use game::render::animation::solve_to_gif;
+use game::render::svg::{level_svg_with_solution, SvgOptions};
+use game::solver::cache::SolverCache;
+use game::solver::external::ExternalSolver;
+use rocket::fairing::AdHoc;
 use rocket::fs::NamedFile;
+use rocket::http::{ContentType, Status};
 use rocket::response::status::NotFound;
+use rocket::serde::json::Json;
+use rocket::tokio::task::spawn_blocking;
+use rocket::State;
+use std::net::SocketAddr;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use drafts::DraftRooms;
+use levels::LevelStore;
+use presence::{is_valid_id, PresenceRegistry};
+
+/// upper bound for the cell size of rendered clips and previews, in pixels
+const MAX_CELL_SIZE: usize = 64;
+
+/// upper bound of the pixels rendered for a clip, summed over its frames
+const MAX_GIF_PIXELS: usize = 1 << 30;
+
+/// upper bound of the rows and columns of levels solved by the external SAT solver, packed levels of this size
+/// fill the `limits.bytes` of 8 MiB suggested in `Rocket.toml`
+const MAX_EXTERNAL_SIZE: usize = 4096;
+
+/// players without a heartbeat for this long are no longer counted, the frontend sends one every 30 seconds
+const PRESENCE_TTL: Duration = Duration::from_secs(75);
+
+/// database of the shared levels, unless configured by `levels_database` in `Rocket.toml`
+const LEVELS_DATABASE: &str = "backend/data/levels.sqlite3";
+
+/// port of the WebSocket listener streaming live runs, unless configured by `live_port` in `Rocket.toml`
+const LIVE_PORT: u16 = 8739;
 
 async fn get_index() -> Result<NamedFile, NotFound<String>> {
     println!("getting index");
@@ -13,10 +61,16 @@ async fn get_index() -> Result<NamedFile, NotFound<String>> {
         .map_err(|e| NotFound(e.to_string()))
 }
 
+/// files of the web app, directories like `/sat/` of the SAT demo are answered with their `index.html`
 #[get("/<path..>")]
 async fn static_files(path: PathBuf) -> Result<NamedFile, NotFound<String>> {
     println!("getting static");
     let path = PathBuf::from("frontend/dist").join(path);
+    let path = if path.is_dir() {
+        path.join("index.html")
+    } else {
+        path
+    };
     match NamedFile::open(path).await {
         Ok(f) => Ok(f),
         Err(_) => get_index().await,
@@ -33,12 +87,415 @@ async fn data(path: PathBuf) -> Result<NamedFile, NotFound<String>> {
     }
 }
 
+/// Runs the computation on a thread for blocking work, so it does not stall the async workers
+async fn blocking<T: Send + 'static>(
+    computation: impl FnOnce() -> Result<T, (Status, String)> + Send + 'static,
+) -> Result<T, (Status, String)> {
+    spawn_blocking(computation)
+        .await
+        .map_err(|e| (Status::InternalServerError, e.to_string()))?
+}
+
+/// Renders a clip of the posted level being solved as animated GIF
+///
+/// levels of more than [`MAX_GENERATED_SIZE`] rows or columns and clips of more than [`MAX_GIF_PIXELS`] are
+/// rejected, a frame is rendered for every tile at most. Unsolvable levels are rejected, the solver cache is
+/// consulted first
+#[post("/api/gif?<cell_size>&<delay>", data = "<level>")]
+async fn gif(
+    cache: &State<SolverCache>,
+    level: String,
+    cell_size: Option<usize>,
+    delay: Option<u16>,
+) -> Result<(ContentType, Vec<u8>), (Status, String)> {
+    let (grid, _) = parse_annotated_level(trim_trailing_newlines(&level), unicode_to_tile)
+        .map_err(|e| (Status::BadRequest, e))?;
+    LevelDto::from(&grid)
+        .check_size(MAX_GENERATED_SIZE)
+        .map_err(|e| (Status::BadRequest, e))?;
+    let cell_size = cell_size.unwrap_or(32).clamp(1, MAX_CELL_SIZE);
+    let pixels = (grid.size() + 1) * grid.size() * cell_size * cell_size;
+    if pixels > MAX_GIF_PIXELS {
+        return Err((
+            Status::PayloadTooLarge,
+            "clip is too large, choose a smaller cell size".to_string(),
+        ));
+    }
+    let cache = cache.inner().clone();
+    blocking(move || {
+        if cache.get_or_compute(&grid).solutions == 0 {
+            return Err((Status::BadRequest, "level is unsolvable".to_string()));
+        }
+        solve_to_gif(&grid, cell_size, delay.unwrap_or(150))
+            .map(|gif| (ContentType::GIF, gif))
+            .map_err(|e| (Status::BadRequest, e))
+    })
+    .await
+}
+
+/// Generates the requested level, scrambled and together with its solution
+#[post("/api/generate", format = "json", data = "<request>")]
+fn generate_level(
+    request: Json<GenerateRequestDto>,
+) -> Result<Json<GeneratedLevelDto>, (Status, String)> {
+    request
+        .generate()
+        .map(Json)
+        .map_err(|e| (Status::BadRequest, e))
+}
+
+/// Lists the solutions of the posted level of at most [`MAX_GENERATED_SIZE`] rows and columns,
+/// up to the requested limit
+///
+/// JSON counterpart of the textual `POST /api/solve`, chosen by the content type
+#[post("/api/solve", format = "json", data = "<request>", rank = 1)]
+fn solve_json(request: Json<SolveRequestDto>) -> Result<Json<SolutionsDto>, (Status, String)> {
+    request
+        .solve()
+        .map(Json)
+        .map_err(|e| (Status::BadRequest, e))
+}
+
+/// Checks the posted level like before publishing it: solvability, uniqueness, difficulty,
+/// designer constraints and lints. The solver cache is consulted first, levels of more than
+/// [`MAX_GENERATED_SIZE`] rows or columns are rejected
+#[post("/api/validate", format = "json", data = "<level>")]
+fn validate_level(
+    cache: &State<SolverCache>,
+    level: Json<LevelDto>,
+) -> Result<Json<ValidationDto>, (Status, String)> {
+    level
+        .check_size(MAX_GENERATED_SIZE)
+        .map_err(|e| (Status::BadRequest, e))?;
+    let grid = level.to_grid().map_err(|e| (Status::BadRequest, e))?;
+    ValidationDto::new(&level, &cache.get_or_compute(&grid))
+        .map(Json)
+        .map_err(|e| (Status::BadRequest, e))
+}
+
+/// Solves the posted level, with the external SAT solver if one is configured
+///
+/// the solver is configured by `sat_solver` and `sat_solver_args` in `Rocket.toml`
+/// or the `INFINITY_LOOP_SAT_SOLVER` environment variable
+#[post("/api/solve", data = "<level>", rank = 2)]
+async fn solve(
+    solver: &State<Option<ExternalSolver>>,
+    level: String,
+) -> Result<String, (Status, String)> {
+    let (grid, _) = parse_annotated_level(trim_trailing_newlines(&level), unicode_to_tile)
+        .map_err(|e| (Status::BadRequest, e))?;
+    let solution = solve_with(solver.inner().clone(), grid).await?;
+    Ok(solution.to_string())
+}
+
+/// Solves the posted level in the packed binary format of level packs and responds in the same format
+///
+/// suited for huge levels, as the packed format is a fraction of the size of the textual one
+#[post("/api/solve/packed", data = "<level>")]
+async fn solve_packed(
+    solver: &State<Option<ExternalSolver>>,
+    level: Vec<u8>,
+) -> Result<(ContentType, Vec<u8>), (Status, String)> {
+    let grid = read_packed_level(level.as_slice()).map_err(|e| (Status::BadRequest, e))?;
+    let solution = solve_with(solver.inner().clone(), grid).await?;
+    let mut packed = Vec::with_capacity(level.len());
+    write_packed_level(&solution, &mut packed).map_err(|e| (Status::InternalServerError, e))?;
+    Ok((ContentType::Binary, packed))
+}
+
+/// Solves the level off the async workers, with the external SAT solver if one is configured
+///
+/// the external solver takes levels of up to [`MAX_EXTERNAL_SIZE`] rows and columns, the builtin solver of up to
+/// [`MAX_GENERATED_SIZE`]
+async fn solve_with(
+    solver: Option<ExternalSolver>,
+    grid: Grid<Tile<Square>>,
+) -> Result<Grid<Tile<Square>>, (Status, String)> {
+    let max = match solver {
+        Some(_) => MAX_EXTERNAL_SIZE,
+        None => MAX_GENERATED_SIZE,
+    };
+    if grid.rows() > max || grid.columns() > max {
+        return Err((
+            Status::BadRequest,
+            format!("levels have at most {max} rows and columns"),
+        ));
+    }
+    blocking(move || {
+        match solver {
+            Some(solver) => solver
+                .solve(&grid)
+                .map_err(|e| (Status::InternalServerError, e))?,
+            None => grid.solve().next(),
+        }
+        .ok_or_else(|| {
+            (
+                Status::UnprocessableEntity,
+                "level is unsolvable".to_string(),
+            )
+        })
+    })
+    .await
+}
+
+/// History of benchmark reports recorded by `game-cli bench-report --output backend/data/bench`, oldest first
+///
+/// unreadable reports are skipped
+#[get("/api/bench")]
+fn bench_history() -> Json<Vec<BenchReportDto>> {
+    let mut reports = std::fs::read_dir("backend/data/bench")
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| std::fs::read_to_string(entry.ok()?.path()).ok())
+        .filter_map(|json| rocket::serde::json::from_str::<BenchReportDto>(&json).ok())
+        .filter(|report| report.verify().is_ok())
+        .collect::<Vec<_>>();
+    reports.sort_by_key(|report| report.timestamp);
+    Json(reports)
+}
+
+/// The builtin levels compiled into the backend, with checksums to verify copies served by mirrors
+#[get("/api/packs/builtin")]
+fn builtin_pack(pack: &State<PackDto>) -> Json<PackDto> {
+    Json(pack.inner().clone())
+}
+
+/// Imports the posted `.ilpack` archive, it is served afterwards by its name
+///
+/// names are restricted to letters, digits, `-` and `_`, an archive of the same name is replaced
+#[post("/api/packs/import", data = "<archive>")]
+fn import_pack(archive: Vec<u8>) -> Result<Json<PackDto>, (Status, String)> {
+    let pack = LevelArchive::from_bytes(&archive)
+        .map_err(|e| (Status::BadRequest, e))?
+        .to_pack();
+    let path = pack_path(&pack.name).ok_or_else(|| {
+        (
+            Status::BadRequest,
+            format!("invalid pack name '{}'", pack.name),
+        )
+    })?;
+    std::fs::create_dir_all("backend/data/packs")
+        .and_then(|_| std::fs::write(path, &archive))
+        .map_err(|e| (Status::InternalServerError, e.to_string()))?;
+    Ok(Json(pack.to_dto()))
+}
+
+/// An imported level pack with checksums, see `POST /api/packs/import`
+#[get("/api/packs/<name>")]
+fn imported_pack(name: &str) -> Result<Json<PackDto>, (Status, String)> {
+    let path = pack_path(name).ok_or((Status::NotFound, format!("no pack '{name}'")))?;
+    let file =
+        std::fs::File::open(path).map_err(|_| (Status::NotFound, format!("no pack '{name}'")))?;
+    LevelArchive::load(std::io::BufReader::new(file))
+        .map(|archive| Json(archive.to_pack().to_dto()))
+        .map_err(|e| (Status::InternalServerError, e))
+}
+
+/// file of the imported pack, if the name is a valid file name not shadowed by the builtin pack
+fn pack_path(name: &str) -> Option<PathBuf> {
+    let valid = !name.is_empty()
+        && name != "builtin"
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    valid.then(|| PathBuf::from("backend/data/packs").join(format!("{name}.{EXTENSION}")))
+}
+
+/// Heartbeat of a browser tab, responds with the number of players online
+///
+/// the id is chosen randomly by the tab, see [`is_valid_id`]
+#[post("/api/presence/<id>")]
+fn heartbeat(
+    registry: &State<PresenceRegistry>,
+    id: &str,
+) -> Result<Json<PresenceDto>, (Status, String)> {
+    if !is_valid_id(id) {
+        return Err((Status::BadRequest, format!("invalid presence id '{id}'")));
+    }
+    Ok(Json(PresenceDto::new(
+        registry.heartbeat(id, Instant::now()),
+    )))
+}
+
+/// Number of players online, without sending a heartbeat
+#[get("/api/presence")]
+fn players_online(registry: &State<PresenceRegistry>) -> Json<PresenceDto> {
+    Json(PresenceDto::new(registry.players(Instant::now())))
+}
+
+/// Stores the posted level to share it across devices, responds with its id and difficulty
+///
+/// unsolvable levels and levels of more than [`MAX_GENERATED_SIZE`] rows or columns are rejected,
+/// a level with the same tiles as a stored one yields the stored one
+#[post("/api/levels", format = "json", data = "<level>")]
+fn upload_level(
+    store: &State<LevelStore>,
+    cache: &State<SolverCache>,
+    level: Json<LevelDto>,
+) -> Result<Json<SharedLevelDto>, (Status, String)> {
+    level
+        .check_size(MAX_GENERATED_SIZE)
+        .map_err(|e| (Status::BadRequest, e))?;
+    let grid = level.to_grid().map_err(|e| (Status::BadRequest, e))?;
+    let result = cache.get_or_compute(&grid);
+    if result.solutions == 0 {
+        return Err((Status::BadRequest, "level is unsolvable".to_string()));
+    }
+    store
+        .insert(&level, result.difficulty)
+        .map(Json)
+        .map_err(|e| (Status::InternalServerError, e))
+}
+
+/// The latest shared levels, newest first and of the difficulty if given, e.g. `?difficulty=hard`
+#[get("/api/levels?<difficulty>")]
+fn browse_levels(
+    store: &State<LevelStore>,
+    difficulty: Option<&str>,
+) -> Result<Json<Vec<SharedLevelDto>>, (Status, String)> {
+    let difficulty = difficulty
+        .map(str::parse::<Difficulty>)
+        .transpose()
+        .map_err(|e| (Status::BadRequest, e))?;
+    store
+        .browse(difficulty)
+        .map(Json)
+        .map_err(|e| (Status::InternalServerError, e))
+}
+
+/// A shared level by its id, see `POST /api/levels`
+#[get("/api/levels/<id>")]
+fn shared_level(
+    store: &State<LevelStore>,
+    id: u64,
+) -> Result<Json<SharedLevelDto>, (Status, String)> {
+    store
+        .get(id)
+        .map_err(|e| (Status::InternalServerError, e))?
+        .map(Json)
+        .ok_or((Status::NotFound, format!("no level {id}")))
+}
+
+/// Preview image of a shared level as SVG, with its solution drawn over it if `?solution=true`
+///
+/// the solution is looked up in the solver cache, levels without solution are rejected
+#[get("/api/levels/<id>/preview.svg?<cell_size>&<solution>")]
+fn shared_level_preview(
+    store: &State<LevelStore>,
+    cache: &State<SolverCache>,
+    id: u64,
+    cell_size: Option<usize>,
+    solution: Option<bool>,
+) -> Result<(ContentType, String), (Status, String)> {
+    let level = store
+        .get(id)
+        .map_err(|e| (Status::InternalServerError, e))?
+        .ok_or((Status::NotFound, format!("no level {id}")))?;
+    let grid = level
+        .level
+        .to_grid()
+        .map_err(|e| (Status::InternalServerError, e))?;
+    let options = SvgOptions {
+        cell_size: cell_size.unwrap_or(32).clamp(1, MAX_CELL_SIZE) as u32,
+        solution: solution.unwrap_or(false),
+        ..SvgOptions::default()
+    };
+    let solution = if options.solution {
+        let solution = cache.get_or_compute(&grid).solution.ok_or((
+            Status::UnprocessableEntity,
+            "level is unsolvable".to_string(),
+        ))?;
+        Some(solution)
+    } else {
+        None
+    };
+    level_svg_with_solution(&grid, solution.as_ref(), &options)
+        .map(|svg| (ContentType::SVG, svg))
+        .map_err(|e| (Status::BadRequest, e))
+}
+
 #[get("/")]
 async fn index() -> Result<NamedFile, NotFound<String>> {
     get_index().await
 }
 
+/// external SAT solver from the configuration, falling back to the environment
+fn configured_solver(rocket: &rocket::Rocket<rocket::Build>) -> Option<ExternalSolver> {
+    let figment = rocket.figment();
+    match figment.extract_inner::<PathBuf>("sat_solver") {
+        Ok(program) => Some(
+            ExternalSolver::new(program).with_args(
+                figment
+                    .extract_inner::<Vec<String>>("sat_solver_args")
+                    .unwrap_or_default(),
+            ),
+        ),
+        Err(_) => ExternalSolver::from_env(),
+    }
+}
+
+/// database of the shared levels from the configuration, falling back to [`LEVELS_DATABASE`]
+fn configured_level_store(rocket: &rocket::Rocket<rocket::Build>) -> LevelStore {
+    let path = rocket
+        .figment()
+        .extract_inner::<PathBuf>("levels_database")
+        .unwrap_or_else(|_| PathBuf::from(LEVELS_DATABASE));
+    LevelStore::open(&path)
+        .unwrap_or_else(|e| panic!("cannot open level database {}: {e}", path.display()))
+}
+
+/// streams live runs to the visualizer and relays shared drafts on the configured port, next to the address of rocket
+fn live_runs() -> AdHoc {
+    AdHoc::on_liftoff("Live runs", |rocket| {
+        let port = rocket
+            .figment()
+            .extract_inner::<u16>("live_port")
+            .unwrap_or(LIVE_PORT);
+        let address = SocketAddr::new(rocket.config().address, port);
+        Box::pin(async move {
+            rocket::tokio::spawn(async move {
+                if let Err(e) = live::serve(address, Arc::new(DraftRooms::default())).await {
+                    println!("live runs on {address} stopped: {e}");
+                }
+            });
+        })
+    })
+}
+
 #[launch]
 fn rocket() -> _ {
-    rocket::build().mount("/", routes![index, static_files, data])
+    let rocket = rocket::build();
+    let solver = configured_solver(&rocket);
+    let levels = configured_level_store(&rocket);
+    rocket
+        .manage(solver)
+        .manage(SolverCache::in_temp_dir())
+        .manage(PackDto::builtin())
+        .manage(PresenceRegistry::new(PRESENCE_TTL))
+        .manage(levels)
+        .attach(live_runs())
+        .mount(
+            "/",
+            routes![
+                index,
+                static_files,
+                data,
+                gif,
+                generate_level,
+                solve_json,
+                solve,
+                validate_level,
+                solve_packed,
+                bench_history,
+                builtin_pack,
+                import_pack,
+                imported_pack,
+                heartbeat,
+                players_online,
+                upload_level,
+                browse_levels,
+                shared_level,
+                shared_level_preview
+            ],
+        )
 }
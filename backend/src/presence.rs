@@ -0,0 +1,82 @@
+//! Players currently online, counted from the heartbeats of their browser tabs
+//!
+//! every tab sends a heartbeat with a random id while the start page is open,
+//! ids whose last heartbeat is older than the time to live are evicted
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// upper bound of the tracked ids, heartbeats of new ids beyond are not counted
+pub const MAX_PLAYERS: usize = 100_000;
+
+/// Shared registry of the last heartbeat of every id, managed as rocket state
+#[derive(Debug)]
+pub struct PresenceRegistry {
+    ttl: Duration,
+    last_seen: Mutex<HashMap<String, Instant>>,
+}
+
+impl PresenceRegistry {
+    pub fn new(ttl: Duration) -> Self {
+        PresenceRegistry {
+            ttl,
+            last_seen: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Records the heartbeat of the id and returns the number of players online
+    pub fn heartbeat(&self, id: &str, now: Instant) -> usize {
+        let mut last_seen = self.last_seen.lock().unwrap_or_else(|e| e.into_inner());
+        self.evict(&mut last_seen, now);
+        if last_seen.len() < MAX_PLAYERS || last_seen.contains_key(id) {
+            last_seen.insert(id.to_string(), now);
+        }
+        last_seen.len()
+    }
+
+    /// number of ids with a heartbeat within the time to live
+    pub fn players(&self, now: Instant) -> usize {
+        let mut last_seen = self.last_seen.lock().unwrap_or_else(|e| e.into_inner());
+        self.evict(&mut last_seen, now);
+        last_seen.len()
+    }
+
+    fn evict(&self, last_seen: &mut HashMap<String, Instant>, now: Instant) {
+        last_seen.retain(|_, &mut seen| now.saturating_duration_since(seen) < self.ttl);
+    }
+}
+
+/// ids are short and alphanumeric, so a heartbeat cannot store arbitrary data
+pub fn is_valid_id(id: &str) -> bool {
+    (8..=64).contains(&id.len()) && id.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::time::{Duration, Instant};
+
+    use super::{is_valid_id, PresenceRegistry};
+
+    #[test]
+    fn heartbeats_expire_after_their_time_to_live() {
+        let registry = PresenceRegistry::new(Duration::from_secs(60));
+        let start = Instant::now();
+        assert_eq!(registry.heartbeat("tab00001", start), 1);
+        assert_eq!(registry.heartbeat("tab00002", start), 2);
+        // repeated heartbeats count once and keep the player online
+        let later = start + Duration::from_secs(45);
+        assert_eq!(registry.heartbeat("tab00001", later), 2);
+        assert_eq!(registry.players(start + Duration::from_secs(60)), 1);
+        assert_eq!(registry.players(later + Duration::from_secs(60)), 0);
+    }
+
+    #[test]
+    fn ids_are_short_and_alphanumeric() {
+        assert!(is_valid_id("3f9a0c7d21b4e855"));
+        assert!(!is_valid_id("short"));
+        assert!(!is_valid_id("../../etc/passwd"));
+        assert!(!is_valid_id(&"a".repeat(65)));
+    }
+}
@@ -0,0 +1,243 @@
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use game::dto::{BenchReportDto, BenchResultDto};
+use game::prelude::{generate, rate, Coordinate, Difficulty, Grid, Square, Tile, WfcGenerator};
+use game::solver::propagationsolver::{
+    BranchHeuristic, FewestStates, MostConstrainedNeighbors, RandomChoice,
+};
+use game::solver::sat::solve_with_sat;
+
+use crate::{unexpected, Arguments};
+
+/// iterations of a benchmark are capped, so fast benchmarks finish quickly
+const MAX_ITERATIONS: u32 = 10_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Options {
+    output: Option<PathBuf>,
+    budget: Duration,
+}
+
+/// single benchmark, returns a value depending on its work, so the work is not optimized away
+struct Bench {
+    name: &'static str,
+    run: Box<dyn Fn(u64) -> usize>,
+}
+
+/// `bench-report [--output <directory>] [--budget <milliseconds>]`
+///
+/// prints the report as JSON and writes it into the directory, which collects the history served by the backend
+pub fn run(args: &[String]) -> Result<i32, String> {
+    let options = parse_options(args)?;
+    let report = bench_report(options.budget);
+    let json = serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?;
+    if let Some(directory) = options.output {
+        fs::create_dir_all(&directory)
+            .map_err(|e| format!("cannot create '{}': {e}", directory.display()))?;
+        let file = directory.join(format!(
+            "bench-{}-{}.json",
+            report.crate_version, report.timestamp
+        ));
+        fs::write(&file, &json).map_err(|e| format!("cannot write '{}': {e}", file.display()))?;
+    }
+    println!("{json}");
+    Ok(0)
+}
+
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut output = None;
+    let mut budget = Duration::from_millis(500);
+
+    let mut args = Arguments::new(args);
+    while let Some(arg) = args.next() {
+        match arg {
+            "-o" | "--output" => output = Some(PathBuf::from(args.value(arg)?)),
+            "--budget" => {
+                budget = Duration::from_millis(
+                    args.parse(arg, "budget must be a number of milliseconds")?,
+                )
+            }
+            other => Err(unexpected(other))?,
+        }
+    }
+
+    Ok(Options { output, budget })
+}
+
+/// scrambled levels to be solved, generated ahead of time so only solving is measured
+fn puzzles(size: usize) -> Vec<Grid<Tile<Square>>> {
+    (0..16)
+        .map(|seed| generate(Coordinate::new(size, size), seed).scramble(seed))
+        .collect()
+}
+
+/// scrambled levels requiring guesses, the solver explores most candidates on them
+fn hard_puzzles(size: usize) -> Vec<Grid<Tile<Square>>> {
+    (0..)
+        .map(|seed| generate(Coordinate::new(size, size), seed))
+        .filter(|level| rate(level) == Difficulty::Hard)
+        .take(16)
+        .zip(0..)
+        .map(|(level, seed)| level.scramble(seed))
+        .collect()
+}
+
+/// enumerating up to 100 solutions of hard levels, with or without learning from contradictions
+fn solve_hard(size: usize, learning: bool) -> Box<dyn Fn(u64) -> usize> {
+    let puzzles = hard_puzzles(size);
+    Box::new(move |seed| {
+        let puzzle = &puzzles[seed as usize % puzzles.len()];
+        if learning {
+            puzzle.solve_learning().take(100).count()
+        } else {
+            puzzle.solve().take(100).count()
+        }
+    })
+}
+
+/// solving with a branch heuristic, which is created for every run from its seed
+fn solve_with<H: BranchHeuristic<Square>>(
+    size: usize,
+    heuristic: impl Fn(u64) -> H + 'static,
+) -> Box<dyn Fn(u64) -> usize> {
+    let puzzles = puzzles(size);
+    Box::new(move |seed| {
+        puzzles[seed as usize % puzzles.len()]
+            .solve_with(heuristic(seed))
+            .next()
+            .map_or(0, |solution| solution.size())
+    })
+}
+
+fn suite() -> Vec<Bench> {
+    let solve = |size| {
+        let puzzles = puzzles(size);
+        Box::new(move |seed: u64| {
+            puzzles[seed as usize % puzzles.len()]
+                .solve()
+                .next()
+                .map_or(0, |solution| solution.size())
+        })
+    };
+    let sat = |size| {
+        let puzzles = puzzles(size);
+        Box::new(move |seed: u64| {
+            solve_with_sat(&puzzles[seed as usize % puzzles.len()])
+                .ok()
+                .flatten()
+                .map_or(0, |solution| solution.size())
+        })
+    };
+    let wfc = WfcGenerator::<Square>::with_all_tiles(10, 10);
+    vec![
+        Bench {
+            name: "fastgen 10x10",
+            run: Box::new(|seed| generate(Coordinate::new(10, 10), seed).size()),
+        },
+        Bench {
+            name: "fastgen 30x30",
+            run: Box::new(|seed| generate(Coordinate::new(30, 30), seed).size()),
+        },
+        Bench {
+            name: "wfc 10x10",
+            run: Box::new(move |seed| wfc.generate_seeded(seed).map_or(0, |level| level.size())),
+        },
+        Bench {
+            name: "solve 10x10",
+            run: solve(10),
+        },
+        Bench {
+            name: "solve 20x20",
+            run: solve(20),
+        },
+        Bench {
+            name: "solve 20x20 fewest states",
+            run: solve_with(20, |_| FewestStates),
+        },
+        Bench {
+            name: "solve 20x20 most constrained neighbors",
+            run: solve_with(20, |_| MostConstrainedNeighbors),
+        },
+        Bench {
+            name: "solve 20x20 random choice",
+            run: solve_with(20, RandomChoice::with_seed),
+        },
+        Bench {
+            name: "solve 20x20 hard",
+            run: solve_hard(20, false),
+        },
+        Bench {
+            name: "solve 20x20 hard with learning",
+            run: solve_hard(20, true),
+        },
+        Bench {
+            name: "sat 10x10",
+            run: sat(10),
+        },
+    ]
+}
+
+/// runs the benchmark repeatedly until the time budget is spent
+fn measure(bench: &Bench, budget: Duration) -> BenchResultDto {
+    let mut sink = 0;
+    let start = Instant::now();
+    let mut iterations = 0;
+    while iterations < MAX_ITERATIONS && (iterations == 0 || start.elapsed() < budget) {
+        sink ^= (bench.run)(iterations as u64);
+        iterations += 1;
+    }
+    let elapsed = start.elapsed();
+    // keeps the results alive without affecting the report
+    if sink == usize::MAX {
+        eprintln!("{sink}");
+    }
+    BenchResultDto {
+        name: bench.name.to_string(),
+        iterations,
+        nanoseconds: (elapsed.as_nanos() / iterations as u128) as u64,
+    }
+}
+
+fn bench_report(budget: Duration) -> BenchReportDto {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    let results = suite()
+        .iter()
+        .map(|bench| {
+            eprintln!("running {}", bench.name);
+            measure(bench, budget)
+        })
+        .collect();
+    BenchReportDto::new(timestamp, results)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::{path::PathBuf, time::Duration};
+
+    use crate::tests::args;
+
+    use super::{bench_report, parse_options, suite};
+
+    #[test]
+    fn options_are_parsed() {
+        let options = parse_options(&args(&["--output", "history", "--budget", "50"]))
+            .expect("valid options");
+        assert_eq!(options.output, Some(PathBuf::from("history")));
+        assert_eq!(options.budget, Duration::from_millis(50));
+    }
+
+    #[test]
+    fn report_contains_every_benchmark() {
+        let report = bench_report(Duration::ZERO);
+        assert!(report.verify().is_ok());
+        assert_eq!(report.results.len(), suite().len());
+        assert!(report.results.iter().all(|result| result.iterations == 1));
+    }
+}
@@ -0,0 +1,81 @@
+use std::{
+    fs::{self, File},
+    io::BufReader,
+    path::PathBuf,
+};
+
+use game::archive::LevelArchive;
+use game::render::book::Book;
+use game::render::theme::Theme;
+
+use crate::{is_option, unexpected, Arguments};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Options {
+    pack: PathBuf,
+    output: PathBuf,
+}
+
+/// `book <archive> -o <output>`
+///
+/// writes a printable puzzle book of the levels of the archive as PDF, see [`Book`]
+pub fn run(args: &[String]) -> Result<i32, String> {
+    let options = parse_options(args)?;
+    let file = File::open(&options.pack)
+        .map_err(|e| format!("cannot read '{}': {e}", options.pack.display()))?;
+    let archive = LevelArchive::load(BufReader::new(file))?;
+    let book = Book::from_pack(&archive.to_pack())?;
+    let document = book.to_pdf(&Theme::PRINT);
+    fs::write(&options.output, document.to_bytes())
+        .map_err(|e| format!("cannot write '{}': {e}", options.output.display()))?;
+    println!(
+        "wrote {} puzzles on {} pages into '{}'",
+        book.solutions.len(),
+        document.pages.len(),
+        options.output.display()
+    );
+    Ok(0)
+}
+
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut pack = None;
+    let mut output = None;
+
+    let mut args = Arguments::new(args);
+    while let Some(arg) = args.next() {
+        match arg {
+            "-o" | "--output" => output = Some(PathBuf::from(args.value(arg)?)),
+            path if !is_option(path) && pack.is_none() => pack = Some(PathBuf::from(path)),
+            other => Err(unexpected(other))?,
+        }
+    }
+
+    Ok(Options {
+        pack: pack.ok_or("missing archive")?,
+        output: output.ok_or("missing output file")?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::path::PathBuf;
+
+    use crate::tests::args;
+
+    use super::parse_options;
+
+    #[test]
+    fn options_are_parsed() {
+        let options =
+            parse_options(&args(&["levels.ilpack", "-o", "book.pdf"])).expect("valid options");
+        assert_eq!(options.pack, PathBuf::from("levels.ilpack"));
+        assert_eq!(options.output, PathBuf::from("book.pdf"));
+    }
+
+    #[test]
+    fn archive_and_output_are_required() {
+        assert!(parse_options(&args(&["levels.ilpack"])).is_err());
+        assert!(parse_options(&args(&["-o", "book.pdf"])).is_err());
+    }
+}
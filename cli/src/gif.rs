@@ -0,0 +1,80 @@
+use std::{fs, path::PathBuf};
+
+use game::model::parser::{parse_annotated_level, trim_trailing_newlines, unicode_to_tile};
+use game::render::animation::solve_to_gif;
+
+use crate::{is_option, unexpected, Arguments};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Options {
+    level: PathBuf,
+    output: PathBuf,
+    cell_size: usize,
+    delay: u16,
+}
+
+/// `gif <level-file> -o <output> [--cell-size <pixels>] [--delay <milliseconds>]`
+///
+/// writes an animated GIF of the level being solved
+pub fn run(args: &[String]) -> Result<i32, String> {
+    let options = parse_options(args)?;
+    let data = fs::read_to_string(&options.level)
+        .map_err(|e| format!("cannot read '{}': {e}", options.level.display()))?;
+    let (grid, _) = parse_annotated_level(trim_trailing_newlines(&data), unicode_to_tile)?;
+    let gif = solve_to_gif(&grid, options.cell_size, options.delay)?;
+    fs::write(&options.output, gif)
+        .map_err(|e| format!("cannot write '{}': {e}", options.output.display()))?;
+    Ok(0)
+}
+
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut level = None;
+    let mut output = None;
+    let mut cell_size = 32;
+    let mut delay = 150;
+
+    let mut args = Arguments::new(args);
+    while let Some(arg) = args.next() {
+        match arg {
+            "-o" | "--output" => output = Some(PathBuf::from(args.value(arg)?)),
+            "--cell-size" => cell_size = args.parse(arg, "cell size must be a positive number")?,
+            "--delay" => delay = args.parse(arg, "delay must be a number of milliseconds")?,
+            path if !is_option(path) && level.is_none() => level = Some(PathBuf::from(path)),
+            other => Err(unexpected(other))?,
+        }
+    }
+
+    if cell_size == 0 {
+        Err("cell size must be a positive number")?;
+    }
+    Ok(Options {
+        level: level.ok_or("missing level file")?,
+        output: output.ok_or("missing output file")?,
+        cell_size,
+        delay,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::path::PathBuf;
+
+    use crate::tests::args;
+
+    use super::parse_options;
+
+    #[test]
+    fn options_are_parsed() {
+        let options = parse_options(&args(&["level.txt", "-o", "out.gif", "--delay", "50"]))
+            .expect("valid options");
+        assert_eq!(options.level, PathBuf::from("level.txt"));
+        assert_eq!(options.output, PathBuf::from("out.gif"));
+        assert_eq!(options.delay, 50);
+    }
+
+    #[test]
+    fn output_is_required() {
+        assert!(parse_options(&args(&["level.txt"])).is_err());
+    }
+}
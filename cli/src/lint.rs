@@ -0,0 +1,60 @@
+use std::{fs::File, io::BufReader, path::PathBuf};
+
+use game::lint::lint;
+use game::model::parser::{read_annotated_level, unicode_to_tile};
+
+use crate::{is_option, unexpected, Arguments};
+
+/// `lint <level-file>...`
+///
+/// prints every warning prefixed with its level file, returns exit code 1 if any level has warnings
+pub fn run(args: &[String]) -> Result<i32, String> {
+    let levels = parse_options(args)?;
+    let mut warnings = 0;
+    for path in &levels {
+        let file =
+            File::open(path).map_err(|e| format!("cannot read '{}': {e}", path.display()))?;
+        let (grid, _) = read_annotated_level(BufReader::new(file), unicode_to_tile)
+            .map_err(|e| format!("{}: {e}", path.display()))?;
+        for warning in lint(&grid) {
+            println!("{}: {warning}", path.display());
+            warnings += 1;
+        }
+    }
+    println!("{warnings} warnings in {} levels", levels.len());
+    Ok(if warnings == 0 { 0 } else { 1 })
+}
+
+fn parse_options(args: &[String]) -> Result<Vec<PathBuf>, String> {
+    let mut levels = vec![];
+    for arg in Arguments::new(args) {
+        match arg {
+            path if !is_option(path) => levels.push(PathBuf::from(path)),
+            option => Err(unexpected(option))?,
+        }
+    }
+    if levels.is_empty() {
+        Err("missing level files")?;
+    }
+    Ok(levels)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::path::PathBuf;
+
+    use crate::tests::args;
+
+    use super::parse_options;
+
+    #[test]
+    fn level_files_are_parsed() {
+        assert_eq!(
+            parse_options(&args(&["a.level", "b.level"])),
+            Ok(vec![PathBuf::from("a.level"), PathBuf::from("b.level")])
+        );
+        assert!(parse_options(&[]).is_err());
+        assert!(parse_options(&args(&["--fix"])).is_err());
+    }
+}
@@ -0,0 +1,139 @@
+use std::{env, process::exit, slice::Iter, str::FromStr};
+
+mod bench;
+mod book;
+mod gif;
+mod lint;
+mod pack;
+mod solve;
+mod svg;
+mod validate;
+
+const USAGE: &str = "usage: game-cli <command> [options]
+
+commands:
+    validate-dir <path> [--format json|junit] [--min-difficulty <difficulty>] [--max-difficulty <difficulty>] [--no-cache]
+        validates every level file (*.level, *.txt) in the directory and prints a report
+        difficulties: easy, medium, hard
+        solver results are cached on disk unless --no-cache is given
+    gif <level-file> -o <output> [--cell-size <pixels>] [--delay <milliseconds>]
+        renders an animated GIF of the level being solved
+    svg <level-file> -o <output> [--cell-size <pixels>] [--stroke-width <units>] [--solution]
+        renders a standalone SVG image of the level, a cell measures 100 units
+        --solution draws the solution of the level over it
+    solve <level-file> [--sat-solver <path> [--solver-arg <arg>]...]
+        prints a solution of the level, found by the external SAT solver if one is given
+        the solver defaults to the INFINITY_LOOP_SAT_SOLVER environment variable, then the builtin solver
+        minisat requires the arguments /dev/stdin /dev/stdout
+    bench-report [--output <directory>] [--budget <milliseconds>]
+        times level generation and solving and prints the report as JSON
+        reports written to backend/data/bench are served as history by the backend
+    pack <level-file>... -o <archive> [--name <name>] [--author <author>] [--thumbnails] [--dedup]
+        bundles the levels into an .ilpack archive, optionally with rendered thumbnails
+        --dedup leaves out levels equal up to turning, mirroring and tile rotations to an earlier level
+    unpack <archive> -o <directory>
+        writes every level of the archive as level file, together with its thumbnail and replay
+    book <archive> -o <output>
+        writes a printable PDF puzzle book with a section per difficulty and an appendix of solutions
+    lint <level-file>...
+        warns about trivial empty regions, symmetric shapes, excessive crosses and ambiguous tiles";
+
+/// exit code for invalid usage, distinct from failing validations
+const USAGE_ERROR: i32 = 2;
+
+/// Arguments of a subcommand, options and positional arguments in the given order
+///
+/// subcommands match every argument and take the values of their options with [`Arguments::value`]
+pub struct Arguments<'a>(Iter<'a, String>);
+
+impl<'a> Arguments<'a> {
+    pub fn new(args: &'a [String]) -> Self {
+        Arguments(args.iter())
+    }
+
+    /// Value following the option
+    pub fn value(&mut self, option: &str) -> Result<&'a str, String> {
+        self.0
+            .next()
+            .map(String::as_str)
+            .ok_or_else(|| format!("missing value for option '{option}'"))
+    }
+
+    /// Value following the option, fails with the message unless it parses
+    pub fn parse<T: FromStr>(&mut self, option: &str, message: &str) -> Result<T, String> {
+        self.value(option)?.parse().map_err(|_| message.to_string())
+    }
+}
+
+impl<'a> Iterator for Arguments<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(String::as_str)
+    }
+}
+
+/// positional arguments never start with a dash
+pub fn is_option(argument: &str) -> bool {
+    argument.starts_with('-')
+}
+
+/// error of an argument the subcommand does not expect, an unknown option or a surplus positional argument
+pub fn unexpected(argument: &str) -> String {
+    if is_option(argument) {
+        format!("unknown option '{argument}'")
+    } else {
+        format!("unexpected argument '{argument}'")
+    }
+}
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let result = match args.first().map(String::as_str) {
+        Some("validate-dir") => validate::run(&args[1..]),
+        Some("bench-report") => bench::run(&args[1..]),
+        Some("gif") => gif::run(&args[1..]),
+        Some("svg") => svg::run(&args[1..]),
+        Some("solve") => solve::run(&args[1..]),
+        Some("pack") => pack::pack(&args[1..]),
+        Some("unpack") => pack::unpack(&args[1..]),
+        Some("book") => book::run(&args[1..]),
+        Some("lint") => lint::run(&args[1..]),
+        _ => Err(USAGE.to_string()),
+    };
+    match result {
+        Ok(code) => exit(code),
+        Err(message) => {
+            eprintln!("{message}");
+            exit(USAGE_ERROR)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{unexpected, Arguments};
+
+    /// arguments of a subcommand as given on the command line
+    pub fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn options_take_the_following_value() {
+        let args = args(&["-o", "out.svg", "--cell-size", "x", "--delay"]);
+        let mut arguments = Arguments::new(&args);
+        assert_eq!(arguments.next(), Some("-o"));
+        assert_eq!(arguments.value("-o"), Ok("out.svg"));
+        assert_eq!(arguments.next(), Some("--cell-size"));
+        assert!(arguments
+            .parse::<u32>("--cell-size", "not a number")
+            .is_err());
+        assert_eq!(arguments.next(), Some("--delay"));
+        assert!(arguments.value("--delay").is_err());
+        assert_eq!(arguments.next(), None);
+        assert_eq!(unexpected("--fix"), "unknown option '--fix'");
+        assert_eq!(unexpected("b.level"), "unexpected argument 'b.level'");
+    }
+}
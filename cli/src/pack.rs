@@ -0,0 +1,219 @@
+use std::{
+    fs::{self, File},
+    io::{BufReader, BufWriter},
+    path::{Path, PathBuf},
+};
+
+use game::archive::{LevelArchive, EXTENSION};
+use game::levelpack::LevelPack;
+use game::model::parser::{read_annotated_level, unicode_to_tile, write_annotated_level};
+use game::model::tile::glyph;
+use game::render::animation::encode_gif;
+
+use crate::{is_option, unexpected, Arguments};
+
+/// pixels per tile of rendered thumbnails
+const THUMBNAIL_CELL_SIZE: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PackOptions {
+    levels: Vec<PathBuf>,
+    output: PathBuf,
+    name: Option<String>,
+    author: String,
+    thumbnails: bool,
+    dedup: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct UnpackOptions {
+    archive: PathBuf,
+    output: PathBuf,
+}
+
+/// `pack <level-file>... -o <archive> [--name <name>] [--author <author>] [--thumbnails] [--dedup]`
+///
+/// bundles the levels in the given order into an `.ilpack` archive, the name defaults to the name of the archive.
+/// With `--dedup`, levels equal up to symmetry to an earlier level are left out
+pub fn pack(args: &[String]) -> Result<i32, String> {
+    let options = parse_pack_options(args)?;
+    let name = options.name.clone().unwrap_or_else(|| {
+        options
+            .output
+            .file_stem()
+            .map_or_else(String::new, |stem| stem.to_string_lossy().into_owned())
+    });
+    let mut pack = LevelPack::new(&name, &options.author);
+    for path in &options.levels {
+        let file =
+            File::open(path).map_err(|e| format!("cannot read '{}': {e}", path.display()))?;
+        let (grid, _) = read_annotated_level(BufReader::new(file), unicode_to_tile)
+            .map_err(|e| format!("{}: {e}", path.display()))?;
+        pack.push(grid);
+    }
+    if options.dedup {
+        let removed = pack.dedup();
+        if removed > 0 {
+            eprintln!("left out {removed} levels equal up to symmetry to an earlier level");
+        }
+    }
+
+    let mut archive = LevelArchive::from(pack);
+    if options.thumbnails {
+        for archived in &mut archive.levels {
+            archived.thumbnail = Some(encode_gif(
+                &[(archived.level.grid.clone(), None)],
+                THUMBNAIL_CELL_SIZE,
+                0,
+            )?);
+        }
+    }
+
+    let file = File::create(&options.output)
+        .map_err(|e| format!("cannot write '{}': {e}", options.output.display()))?;
+    archive.save(BufWriter::new(file))?;
+    println!(
+        "packed {} levels into '{}'",
+        archive.levels.len(),
+        options.output.display()
+    );
+    Ok(0)
+}
+
+/// `unpack <archive> -o <directory>`
+///
+/// writes every level of the archive as level file, together with its thumbnail and replay
+pub fn unpack(args: &[String]) -> Result<i32, String> {
+    let options = parse_unpack_options(args)?;
+    let file = File::open(&options.archive)
+        .map_err(|e| format!("cannot read '{}': {e}", options.archive.display()))?;
+    let archive = LevelArchive::load(BufReader::new(file))?;
+    fs::create_dir_all(&options.output)
+        .map_err(|e| format!("cannot create '{}': {e}", options.output.display()))?;
+
+    for (index, archived) in archive.levels.iter().enumerate() {
+        let path = |extension| options.output.join(format!("{index:04}.{extension}"));
+        let level = path("level");
+        let file = File::create(&level).map_err(|e| cannot_write(&level, e))?;
+        write_annotated_level(&archived.level.grid, &[], BufWriter::new(file), glyph)?;
+        if let Some(thumbnail) = &archived.thumbnail {
+            let image = path("gif");
+            fs::write(&image, thumbnail).map_err(|e| cannot_write(&image, e))?;
+        }
+        if let Some(replay) = &archived.replay {
+            let json = path("json");
+            let replay = serde_json::to_string_pretty(replay).map_err(|e| e.to_string())?;
+            fs::write(&json, replay).map_err(|e| cannot_write(&json, e))?;
+        }
+    }
+    println!(
+        "unpacked {} levels of '{}' by '{}' into '{}'",
+        archive.levels.len(),
+        archive.name,
+        archive.author,
+        options.output.display()
+    );
+    Ok(0)
+}
+
+fn cannot_write(path: &Path, error: std::io::Error) -> String {
+    format!("cannot write '{}': {error}", path.display())
+}
+
+fn parse_pack_options(args: &[String]) -> Result<PackOptions, String> {
+    let mut levels = vec![];
+    let mut output = None;
+    let mut name = None;
+    let mut author = String::new();
+    let mut thumbnails = false;
+    let mut dedup = false;
+
+    let mut args = Arguments::new(args);
+    while let Some(arg) = args.next() {
+        match arg {
+            "-o" | "--output" => output = Some(PathBuf::from(args.value(arg)?)),
+            "--name" => name = Some(args.value(arg)?.to_string()),
+            "--author" => author = args.value(arg)?.to_string(),
+            "--thumbnails" => thumbnails = true,
+            "--dedup" => dedup = true,
+            path if !is_option(path) => levels.push(PathBuf::from(path)),
+            other => Err(unexpected(other))?,
+        }
+    }
+
+    if levels.is_empty() {
+        Err("missing level files")?;
+    }
+    let output: PathBuf = output.ok_or("missing output file")?;
+    if output.extension().and_then(|e| e.to_str()) != Some(EXTENSION) {
+        Err(format!("output file must have the extension .{EXTENSION}"))?;
+    }
+    Ok(PackOptions {
+        levels,
+        output,
+        name,
+        author,
+        thumbnails,
+        dedup,
+    })
+}
+
+fn parse_unpack_options(args: &[String]) -> Result<UnpackOptions, String> {
+    let mut archive = None;
+    let mut output = None;
+
+    let mut args = Arguments::new(args);
+    while let Some(arg) = args.next() {
+        match arg {
+            "-o" | "--output" => output = Some(PathBuf::from(args.value(arg)?)),
+            path if !is_option(path) && archive.is_none() => archive = Some(PathBuf::from(path)),
+            other => Err(unexpected(other))?,
+        }
+    }
+
+    Ok(UnpackOptions {
+        archive: archive.ok_or("missing archive")?,
+        output: output.ok_or("missing output directory")?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::path::PathBuf;
+
+    use crate::tests::args;
+
+    use super::{parse_pack_options, parse_unpack_options};
+
+    #[test]
+    fn pack_options_are_parsed() {
+        let options = parse_pack_options(&args(&[
+            "a.level",
+            "b.level",
+            "-o",
+            "pack.ilpack",
+            "--author",
+            "me",
+            "--thumbnails",
+            "--dedup",
+        ]))
+        .expect("valid options");
+        assert_eq!(
+            options.levels,
+            vec![PathBuf::from("a.level"), PathBuf::from("b.level")]
+        );
+        assert_eq!(options.author, "me");
+        assert!(options.thumbnails);
+        assert!(options.dedup);
+        assert!(parse_pack_options(&args(&["a.level", "-o", "pack.zip"])).is_err());
+    }
+
+    #[test]
+    fn unpack_options_are_parsed() {
+        let options =
+            parse_unpack_options(&args(&["pack.ilpack", "-o", "levels"])).expect("valid options");
+        assert_eq!(options.archive, PathBuf::from("pack.ilpack"));
+        assert_eq!(options.output, PathBuf::from("levels"));
+    }
+}
@@ -0,0 +1,105 @@
+use std::{
+    fs::File,
+    io::{self, BufReader, Write},
+    path::PathBuf,
+};
+
+use game::model::parser::{read_annotated_level, unicode_to_tile, write_annotated_level};
+use game::model::tile::glyph;
+use game::solver::external::{ExternalSolver, SOLVER_ENV};
+
+use crate::{is_option, unexpected, Arguments};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Options {
+    level: PathBuf,
+    solver: Option<ExternalSolver>,
+}
+
+/// `solve <level-file> [--sat-solver <path> [--solver-arg <arg>]...]`
+///
+/// prints the solved level, returns exit code 1 if the level is unsolvable
+pub fn run(args: &[String]) -> Result<i32, String> {
+    let options = parse_options(args)?;
+    let file = File::open(&options.level)
+        .map_err(|e| format!("cannot read '{}': {e}", options.level.display()))?;
+    let (grid, _) = read_annotated_level(BufReader::new(file), unicode_to_tile)?;
+    let solution = match options.solver.or_else(ExternalSolver::from_env) {
+        Some(solver) => solver.solve(&grid)?,
+        None => grid.solve().next(),
+    };
+    match solution {
+        Some(solution) => {
+            let mut stdout = io::stdout().lock();
+            write_annotated_level(&solution, &[], &mut stdout, glyph)?;
+            writeln!(stdout).map_err(|e| format!("writing error: {e}"))?;
+            Ok(0)
+        }
+        None => {
+            eprintln!("level is unsolvable");
+            Ok(1)
+        }
+    }
+}
+
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut level = None;
+    let mut program = None;
+    let mut solver_args = vec![];
+
+    let mut args = Arguments::new(args);
+    while let Some(arg) = args.next() {
+        match arg {
+            "--sat-solver" => program = Some(args.value(arg)?.to_string()),
+            "--solver-arg" => solver_args.push(args.value(arg)?.to_string()),
+            path if !is_option(path) && level.is_none() => level = Some(PathBuf::from(path)),
+            other => Err(unexpected(other))?,
+        }
+    }
+
+    if program.is_none() && !solver_args.is_empty() {
+        Err(format!(
+            "solver arguments require --sat-solver, {SOLVER_ENV} takes no arguments"
+        ))?;
+    }
+    Ok(Options {
+        level: level.ok_or("missing level file")?,
+        solver: program.map(|program| ExternalSolver::new(program).with_args(solver_args)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::path::PathBuf;
+
+    use game::solver::external::ExternalSolver;
+
+    use crate::tests::args;
+
+    use super::parse_options;
+
+    #[test]
+    fn options_are_parsed() {
+        let options = parse_options(&args(&[
+            "level.txt",
+            "--sat-solver",
+            "minisat",
+            "--solver-arg",
+            "/dev/stdin",
+            "--solver-arg",
+            "/dev/stdout",
+        ]))
+        .expect("valid options");
+        assert_eq!(options.level, PathBuf::from("level.txt"));
+        assert_eq!(
+            options.solver,
+            Some(ExternalSolver::new("minisat").with_args(["/dev/stdin", "/dev/stdout"]))
+        );
+    }
+
+    #[test]
+    fn solver_arguments_require_solver() {
+        assert!(parse_options(&args(&["level.txt", "--solver-arg", "-q"])).is_err());
+    }
+}
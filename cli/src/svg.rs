@@ -0,0 +1,89 @@
+use std::{fs, path::PathBuf};
+
+use game::model::parser::{parse_annotated_level, trim_trailing_newlines, unicode_to_tile};
+use game::render::svg::{level_svg, SvgOptions};
+
+use crate::{is_option, unexpected, Arguments};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Options {
+    level: PathBuf,
+    output: PathBuf,
+    svg: SvgOptions,
+}
+
+/// `svg <level-file> -o <output> [--cell-size <pixels>] [--stroke-width <units>] [--solution]`
+///
+/// writes a standalone SVG image of the level, optionally with its solution drawn over it
+pub fn run(args: &[String]) -> Result<i32, String> {
+    let options = parse_options(args)?;
+    let data = fs::read_to_string(&options.level)
+        .map_err(|e| format!("cannot read '{}': {e}", options.level.display()))?;
+    let (grid, _) = parse_annotated_level(trim_trailing_newlines(&data), unicode_to_tile)?;
+    let svg = level_svg(&grid, &options.svg)?;
+    fs::write(&options.output, svg)
+        .map_err(|e| format!("cannot write '{}': {e}", options.output.display()))?;
+    Ok(0)
+}
+
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut level = None;
+    let mut output = None;
+    let mut svg = SvgOptions::default();
+
+    let mut args = Arguments::new(args);
+    while let Some(arg) = args.next() {
+        match arg {
+            "-o" | "--output" => output = Some(PathBuf::from(args.value(arg)?)),
+            "--cell-size" => {
+                svg.cell_size = args.parse(arg, "cell size must be a positive number")?
+            }
+            "--stroke-width" => {
+                svg.stroke_width = args.parse(arg, "stroke width must be a positive number")?
+            }
+            "--solution" => svg.solution = true,
+            path if !is_option(path) && level.is_none() => level = Some(PathBuf::from(path)),
+            other => Err(unexpected(other))?,
+        }
+    }
+
+    if svg.cell_size == 0 {
+        Err("cell size must be a positive number")?;
+    }
+    if svg.stroke_width == 0 {
+        Err("stroke width must be a positive number")?;
+    }
+    Ok(Options {
+        level: level.ok_or("missing level file")?,
+        output: output.ok_or("missing output file")?,
+        svg,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::path::PathBuf;
+
+    use crate::tests::args;
+
+    use super::parse_options;
+
+    #[test]
+    fn options_are_parsed() {
+        let options = parse_options(&args(&[
+            "level.txt",
+            "-o",
+            "out.svg",
+            "--stroke-width",
+            "12",
+            "--solution",
+        ]))
+        .expect("valid options");
+        assert_eq!(options.level, PathBuf::from("level.txt"));
+        assert_eq!(options.output, PathBuf::from("out.svg"));
+        assert_eq!(options.svg.stroke_width, 12);
+        assert!(options.svg.solution);
+        assert!(parse_options(&args(&["level.txt", "-o", "out.svg", "--cell-size", "0"])).is_err());
+    }
+}
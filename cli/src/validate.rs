@@ -0,0 +1,302 @@
+use std::{
+    fs,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+
+use game::batch::map_pack;
+use game::generator::levelstream::find_builtin_near_duplicate;
+use game::model::{
+    constraint::validate,
+    parser::{parse_annotated_level, trim_trailing_newlines, unicode_to_tile},
+};
+use game::prelude::Difficulty;
+use game::solver::cache::{SolverCache, SolverResult};
+
+use crate::{is_option, unexpected, Arguments};
+
+/// file extensions recognized as level files
+const LEVEL_EXTENSIONS: [&str; 2] = ["level", "txt"];
+
+/// levels at least this similar to a builtin level are rejected as near duplicates
+const NEAR_DUPLICATE_SIMILARITY: f32 = 0.9;
+
+/// machine-readable output formats of the report
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Json,
+    Junit,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Options {
+    directory: PathBuf,
+    format: Format,
+    difficulty: RangeInclusive<Difficulty>,
+    cache: Option<SolverCache>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct LevelReport {
+    file: String,
+    difficulty: Option<String>,
+    failures: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct Report {
+    passed: usize,
+    failed: usize,
+    levels: Vec<LevelReport>,
+}
+
+/// `validate-dir <path> [--format json|junit] [--min-difficulty <d>] [--max-difficulty <d>] [--no-cache]`
+///
+/// prints the report to stdout, returns exit code 1 if any level fails validation
+pub fn run(args: &[String]) -> Result<i32, String> {
+    let options = parse_options(args)?;
+    let report = validate_directory(&options)?;
+    match options.format {
+        Format::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?
+        ),
+        Format::Junit => println!("{}", to_junit(&report)),
+    }
+    Ok(if report.failed == 0 { 0 } else { 1 })
+}
+
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut directory = None;
+    let mut format = Format::Json;
+    let mut min = Difficulty::Easy;
+    let mut max = Difficulty::Hard;
+    let mut cache = Some(SolverCache::in_temp_dir());
+
+    let mut args = Arguments::new(args);
+    while let Some(arg) = args.next() {
+        match arg {
+            "--format" => {
+                format = match args.value(arg)? {
+                    "json" => Format::Json,
+                    "junit" => Format::Junit,
+                    other => Err(format!("unknown format '{other}'"))?,
+                }
+            }
+            "--min-difficulty" => min = args.value(arg)?.parse()?,
+            "--max-difficulty" => max = args.value(arg)?.parse()?,
+            "--no-cache" => cache = None,
+            path if !is_option(path) && directory.is_none() => {
+                directory = Some(PathBuf::from(path))
+            }
+            other => Err(unexpected(other))?,
+        }
+    }
+
+    Ok(Options {
+        directory: directory.ok_or("missing directory")?,
+        format,
+        difficulty: min..=max,
+        cache,
+    })
+}
+
+fn validate_directory(options: &Options) -> Result<Report, String> {
+    let mut files = fs::read_dir(&options.directory)
+        .map_err(|e| format!("cannot read '{}': {e}", options.directory.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| is_level_file(path))
+        .collect::<Vec<_>>();
+    // stable report order independent of the file system
+    files.sort();
+
+    let sources = files
+        .iter()
+        .map(|path| (path.display().to_string(), fs::read_to_string(path)))
+        .collect::<Vec<_>>();
+    // solving dominates, so the levels are validated on all cores
+    let levels = map_pack(
+        &sources,
+        |(file, data)| match data {
+            Ok(data) => validate_level(
+                file.clone(),
+                data,
+                &options.difficulty,
+                options.cache.as_ref(),
+            ),
+            Err(e) => LevelReport {
+                file: file.clone(),
+                difficulty: None,
+                failures: vec![format!("cannot read file: {e}")],
+            },
+        },
+        |finished, total| eprintln!("validated {finished}/{total}"),
+    );
+    let failed = levels.iter().filter(|l| !l.failures.is_empty()).count();
+
+    Ok(Report {
+        passed: levels.len() - failed,
+        failed,
+        levels,
+    })
+}
+
+fn is_level_file(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| LEVEL_EXTENSIONS.contains(&e))
+}
+
+/// checks that the level parses, satisfies its constraints, has a unique solution, a difficulty in range
+/// and is no near duplicate of a builtin level
+///
+/// solver results are looked up in the cache first, if given
+fn validate_level(
+    file: String,
+    data: &str,
+    range: &RangeInclusive<Difficulty>,
+    cache: Option<&SolverCache>,
+) -> LevelReport {
+    let (grid, constraints) =
+        match parse_annotated_level(trim_trailing_newlines(data), unicode_to_tile) {
+            Ok(level) => level,
+            Err(e) => {
+                return LevelReport {
+                    file,
+                    difficulty: None,
+                    failures: vec![e],
+                }
+            }
+        };
+
+    let mut failures = vec![];
+    if let Err(violations) = validate(&grid, &constraints) {
+        failures.extend(violations.into_iter().map(|(constraint, coordinate)| {
+            format!("constraint '{constraint}' violated at {coordinate}")
+        }));
+    }
+    if let Some((index, similarity)) = find_builtin_near_duplicate(&grid, NEAR_DUPLICATE_SIMILARITY)
+    {
+        failures.push(format!(
+            "near duplicate of builtin level {} ({:.0}% similar)",
+            index + 1,
+            similarity * 100.0
+        ));
+    }
+    let SolverResult {
+        solutions,
+        difficulty,
+        ..
+    } = match cache {
+        Some(cache) => cache.get_or_compute(&grid),
+        None => SolverResult::compute(&grid),
+    };
+    match solutions {
+        0 => failures.push("level is unsolvable".into()),
+        1 => {}
+        _ => failures.push("level has multiple solutions".into()),
+    }
+    if !range.contains(&difficulty) {
+        failures.push(format!(
+            "difficulty {difficulty} outside of {}..={}",
+            range.start(),
+            range.end()
+        ));
+    }
+
+    LevelReport {
+        file,
+        difficulty: Some(difficulty.to_string()),
+        failures,
+    }
+}
+
+/// renders the report as JUnit XML, one test case per level
+fn to_junit(report: &Report) -> String {
+    let cases = report
+        .levels
+        .iter()
+        .map(|level| {
+            let failures = level
+                .failures
+                .iter()
+                .map(|f| format!("\n      <failure message=\"{}\"/>", escape_xml(f)))
+                .collect::<String>();
+            format!(
+                "    <testcase classname=\"validate-dir\" name=\"{}\">{failures}\n    </testcase>\n",
+                escape_xml(&level.file)
+            )
+        })
+        .collect::<String>();
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites>\n  <testsuite name=\"validate-dir\" tests=\"{}\" failures=\"{}\">\n{cases}  </testsuite>\n</testsuites>",
+        report.levels.len(),
+        report.failed
+    )
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+
+    use game::prelude::Difficulty;
+
+    use super::{escape_xml, validate_level};
+
+    const ALL: std::ops::RangeInclusive<Difficulty> = Difficulty::Easy..=Difficulty::Hard;
+
+    #[test]
+    fn unique_level_passes() {
+        let report = validate_level("unique".into(), "┏┓\n┗┛\n", &ALL, None);
+        assert_eq!(report.failures, Vec::<String>::new());
+        assert_eq!(report.difficulty, Some("easy".into()));
+    }
+
+    #[test]
+    fn unsolvable_level_fails() {
+        let report = validate_level("unsolvable".into(), "╹", &ALL, None);
+        assert_eq!(report.failures, vec!["level is unsolvable".to_string()]);
+    }
+
+    #[test]
+    fn difficulty_out_of_range_fails() {
+        let report = validate_level(
+            "easy".into(),
+            "┏┓\n┗┛",
+            &(Difficulty::Hard..=Difficulty::Hard),
+            None,
+        );
+        assert_eq!(report.failures.len(), 1);
+    }
+
+    #[test]
+    fn violated_constraint_fails() {
+        let report = validate_level("constrained".into(), "# cell 0 0 ╋\n┏┓\n┗┛", &ALL, None);
+        assert_eq!(report.failures.len(), 1);
+    }
+
+    #[test]
+    fn builtin_level_fails_as_near_duplicate() {
+        let report = validate_level("builtin".into(), "┗┳┓\n┏┻┛", &ALL, None);
+        assert_eq!(
+            report.failures,
+            vec!["near duplicate of builtin level 1 (100% similar)".to_string()]
+        );
+    }
+
+    #[test]
+    fn xml_special_characters_are_escaped() {
+        assert_eq!(escape_xml("<a & 'b'>"), "&lt;a &amp; &apos;b&apos;&gt;");
+    }
+}
@@ -0,0 +1,10 @@
+#!/bin/sh
+# builds the optimized web bundle and reports its size in bytes as JSON
+set -e
+cd "$(dirname "$0")"
+trunk build --release >&2
+wasm=$(ls dist/*.wasm | head -n 1)
+js=$(ls dist/*.js | head -n 1)
+printf '{"wasm": %s, "wasm_gzip": %s, "js": %s, "js_gzip": %s}\n' \
+  "$(wc -c < "$wasm")" "$(gzip -9 -c "$wasm" | wc -c)" \
+  "$(wc -c < "$js")" "$(gzip -9 -c "$js" | wc -c)"
@@ -1,10 +1,35 @@
 use yew::prelude::*;
 use yew::{html, Callback, Properties};
 
-use game::model::tile::{Square, Tile};
+use game::prelude::{Square, Tile};
 
+use crate::components::board::flow::FLOW_STEP;
 use crate::components::utils::tile_image::TileImage;
 
+/// reason a cell is highlighted, each kind is styled by its own class
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum HighlightKind {
+    /// the tile is part of a conflict making the level unsolvable, see [`game::solver::conflict`]
+    Conflict,
+    /// the tile is the subject of the latest hint
+    Hint,
+    /// the tile is locked in its rotation, see [`game::model::lock`]
+    Locked,
+    /// another editor of the shared draft selected the tile, see [`game::model::collab`]
+    RemoteCursor,
+}
+
+impl HighlightKind {
+    pub fn class(self) -> &'static str {
+        match self {
+            HighlightKind::Conflict => "conflict",
+            HighlightKind::Hint => "hint",
+            HighlightKind::Locked => "locked",
+            HighlightKind::RemoteCursor => "remote-cursor",
+        }
+    }
+}
+
 #[derive(Properties, PartialEq, Clone)]
 pub struct CellComponentProps {
     pub tile: Tile<Square>,
@@ -14,22 +39,49 @@ pub struct CellComponentProps {
     pub on_click: Callback<MouseEvent>,
     #[prop_or_default]
     pub on_wheel: Callback<WheelEvent>,
+    #[prop_or_default]
+    pub on_context_menu: Callback<MouseEvent>,
+    #[prop_or_default]
+    pub on_mouse_enter: Callback<MouseEvent>,
+    /// number of links from the origin of the energy flow, if the energy reaches this cell
+    #[prop_or_default]
+    pub flow: Option<usize>,
+    /// explanation shown when hovering the cell, e.g. of a hint
+    #[prop_or_default]
+    pub tooltip: Option<String>,
+    /// every kind is styled at once, e.g. a hinted tile of a conflict
+    #[prop_or_default]
+    pub highlights: Vec<HighlightKind>,
 }
 
 /// A tile representation in a level board.
-/// Allows custom onclick and onwheel actions,
-/// which is mainly used for turning and changing tiles
+/// Allows custom onclick, onwheel and oncontextmenu actions,
+/// which is mainly used for turning, changing and inspecting tiles
 #[function_component(CellComponent)]
 pub fn cell_component(props: &CellComponentProps) -> Html {
     let (row, column) = (props.row_number, props.column_number);
     let cell_tile = props.tile;
+    // the glow reaches cells farther from the origin later
+    let (flow_class, flow_style) = match props.flow {
+        Some(distance) => (" flow", format!("--flow-delay: {}ms", distance * FLOW_STEP)),
+        None => ("", String::new()),
+    };
+    let highlight_class = props
+        .highlights
+        .iter()
+        .map(|kind| format!(" {}", kind.class()))
+        .collect::<String>();
 
     html! {
         <div
             id={format!("cell-r-{}-c-{}", row, column)}
-            class={format!("cell row-{} col-{}", row, column)}
+            class={format!("cell row-{} col-{}{}{}", row, column, flow_class, highlight_class)}
+            style={flow_style}
+            title={props.tooltip.clone()}
             onclick={props.on_click.clone()}
             onwheel={props.on_wheel.clone()}
+            oncontextmenu={props.on_context_menu.clone()}
+            onmouseenter={props.on_mouse_enter.clone()}
             >
             <TileImage tile={cell_tile} />
         </div>
@@ -0,0 +1,85 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use yew::prelude::*;
+
+use game::model::connectivity::{distances_from, Connectivity};
+use game::prelude::{Coordinate, Grid, Square, Tile};
+
+/// delay between the glow of consecutive tiles along a connection, in milliseconds
+pub const FLOW_STEP: usize = 120;
+
+/// state carried between renders, so turning a tile only recomputes what it affects
+struct Flow {
+    grid: Grid<Tile<Square>>,
+    connectivity: Connectivity,
+    origin: Coordinate<isize>,
+    distances: Grid<Option<usize>>,
+}
+
+impl Flow {
+    fn new(grid: &Grid<Tile<Square>>, origin: Coordinate<isize>) -> Self {
+        Flow {
+            grid: grid.clone(),
+            connectivity: Connectivity::new(grid),
+            origin,
+            distances: distances_from(grid, origin),
+        }
+    }
+
+    /// brings the state up to date with the grid and origin
+    fn update(&mut self, grid: &Grid<Tile<Square>>, origin: Coordinate<isize>) {
+        if grid.dimensions() != self.grid.dimensions() {
+            *self = Flow::new(grid, origin);
+            return;
+        }
+        let changed = grid
+            .coordinates()
+            .filter(|&c| grid[c] != self.grid[c])
+            .collect::<Vec<_>>();
+        let before = self.connectivity.component(self.origin);
+        for &index in &changed {
+            self.connectivity.update(grid, index);
+        }
+        let after = self.connectivity.component(origin);
+        // the glow only follows the component of the origin, other components are left alone
+        let affects_origin = changed.iter().any(|&index| {
+            self.distances[index].is_some() || self.connectivity.component(index) == after
+        });
+        if origin != self.origin || before.is_none() || affects_origin {
+            self.distances = distances_from(grid, origin);
+        }
+        self.grid = grid.clone();
+        self.origin = origin;
+    }
+}
+
+/// first tile with connections, where the energy flows from unless the player chooses another origin
+pub fn default_origin(grid: &Grid<Tile<Square>>) -> Coordinate<isize> {
+    grid.positions()
+        .map(Coordinate::from)
+        .find(|&c| grid[c] != Tile::NO_CONNECTIONS)
+        .unwrap_or_default()
+}
+
+/// number of links from the origin to every tile connected to it, recomputed incrementally as tiles turn
+///
+/// no tile is reached while the animation is disabled
+pub fn use_energy_flow(
+    grid: &Grid<Tile<Square>>,
+    origin: Coordinate<isize>,
+    enabled: bool,
+) -> Grid<Option<usize>> {
+    let flow: Rc<RefCell<Option<Flow>>> = use_mut_ref(|| None);
+    let mut flow = flow.borrow_mut();
+    if !enabled {
+        *flow = None;
+    } else if let Some(flow) = flow.as_mut() {
+        flow.update(grid, origin);
+    } else {
+        *flow = Some(Flow::new(grid, origin));
+    }
+    flow.as_ref()
+        .map(|flow| flow.distances.clone())
+        .unwrap_or_else(|| grid.map(|_| None))
+}
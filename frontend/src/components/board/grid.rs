@@ -7,8 +7,19 @@ pub struct GridComponentProps {
     pub children: Children,
     #[prop_or(use_state_eq(|| "".to_string()))]
     pub overlay_message: UseStateHandle<String>,
+    /// draws arrows at the edges of a board whose connections wrap around
+    #[prop_or(false)]
+    pub torus: bool,
 }
 
+/// edges of the board with the arrow pointing across the wrap
+const TORUS_ARROWS: [(&str, &str); 4] = [
+    ("torus-arrow-up", "⇡"),
+    ("torus-arrow-down", "⇣"),
+    ("torus-arrow-left", "⇠"),
+    ("torus-arrow-right", "⇢"),
+];
+
 /// A complete representation of the grid in the game model.
 /// Allows overlay messages to display over the grid if needed.
 #[function_component(GridComponent)]
@@ -20,6 +31,12 @@ pub fn grid_component(props: &GridComponentProps) -> Html {
         <>
             { for children.iter() }
 
+            {
+                for TORUS_ARROWS.iter().filter(|_| props.torus).map(|(edge, arrow)| html! {
+                    <div class={classes!("torus-arrow", *edge)}>{ *arrow }</div>
+                })
+            }
+
             {
                 if !overlay_message.trim().is_empty() {
                     html!{
@@ -0,0 +1,154 @@
+use game::prelude::Coordinate;
+
+/// position on the screen in pixels, `x` grows to the right and `y` downwards
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point {
+    pub fn new(x: f64, y: f64) -> Self {
+        Point { x, y }
+    }
+}
+
+/// Maps between screen points and the cells of a board
+///
+/// cells of `cell_size` pixels are separated by `gap` pixels, the top left cell starts at `origin`
+/// and `zoom` scales cells and gaps alike. Rows run along `y`, columns along `x`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoardLayout {
+    pub cell_size: f64,
+    pub gap: f64,
+    pub origin: Point,
+    pub zoom: f64,
+}
+
+impl Default for BoardLayout {
+    fn default() -> Self {
+        BoardLayout::new(1.0, 0.0)
+    }
+}
+
+impl BoardLayout {
+    /// Layout starting at the top left corner of the screen without zoom
+    pub fn new(cell_size: f64, gap: f64) -> Self {
+        BoardLayout {
+            cell_size,
+            gap,
+            origin: Point::default(),
+            zoom: 1.0,
+        }
+    }
+
+    /// Largest layout of a board of the dimensions fitting into the given width and height
+    pub fn fit(dimensions: Coordinate<usize>, width: f64, height: f64, gap: f64) -> Self {
+        let (rows, columns) = dimensions.map(|n| n.max(1) as f64).to_tuple();
+        let cell_size = ((width + gap) / columns).min((height + gap) / rows) - gap;
+        BoardLayout::new(cell_size.max(0.0), gap)
+    }
+
+    pub fn with_origin(self, origin: Point) -> Self {
+        BoardLayout { origin, ..self }
+    }
+
+    pub fn with_zoom(self, zoom: f64) -> Self {
+        BoardLayout { zoom, ..self }
+    }
+
+    /// distance in pixels between the top left corners of neighboring cells
+    fn pitch(&self) -> f64 {
+        (self.cell_size + self.gap) * self.zoom
+    }
+
+    /// top left corner of the cell
+    pub fn cell_origin(&self, coordinate: Coordinate<isize>) -> Point {
+        let pitch = self.pitch();
+        Point::new(
+            self.origin.x + coordinate.column as f64 * pitch,
+            self.origin.y + coordinate.row as f64 * pitch,
+        )
+    }
+
+    pub fn cell_center(&self, coordinate: Coordinate<isize>) -> Point {
+        let corner = self.cell_origin(coordinate);
+        let half = self.cell_size * self.zoom / 2.0;
+        Point::new(corner.x + half, corner.y + half)
+    }
+
+    /// Cell under the point, `None` if the point lies in a gap between cells
+    ///
+    /// coordinates outside the board are returned as well, checking them against the grid is up to the caller
+    pub fn coordinate_at(&self, point: Point) -> Option<Coordinate<isize>> {
+        let pitch = self.pitch();
+        if pitch <= 0.0 {
+            return None;
+        }
+        let locate = |offset: f64| {
+            let index = (offset / pitch).floor();
+            (offset - index * pitch < self.cell_size * self.zoom).then_some(index as isize)
+        };
+        Some(Coordinate::new(
+            locate(point.y - self.origin.y)?,
+            locate(point.x - self.origin.x)?,
+        ))
+    }
+
+    /// width and height of a board of the dimensions in pixels
+    pub fn board_size(&self, dimensions: Coordinate<usize>) -> Point {
+        let extent = |cells: usize| (cells as f64 * self.pitch() - self.gap * self.zoom).max(0.0);
+        Point::new(extent(dimensions.column), extent(dimensions.row))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use game::prelude::Coordinate;
+
+    use super::{BoardLayout, Point};
+
+    #[test]
+    fn cell_centers_map_back_to_their_cell() {
+        let layout = BoardLayout::new(40.0, 4.0)
+            .with_origin(Point::new(12.0, -7.0))
+            .with_zoom(1.5);
+        for row in -2..5 {
+            for column in -2..5 {
+                let coordinate = Coordinate::new(row, column);
+                assert_eq!(
+                    layout.coordinate_at(layout.cell_center(coordinate)),
+                    Some(coordinate)
+                );
+                assert_eq!(
+                    layout.coordinate_at(layout.cell_origin(coordinate)),
+                    Some(coordinate)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn gaps_belong_to_no_cell() {
+        let layout = BoardLayout::new(10.0, 2.0);
+        assert_eq!(
+            layout.coordinate_at(Point::new(9.9, 5.0)),
+            Some(Coordinate::new(0, 0))
+        );
+        assert_eq!(layout.coordinate_at(Point::new(11.0, 5.0)), None);
+        assert_eq!(
+            layout.coordinate_at(Point::new(12.0, 5.0)),
+            Some(Coordinate::new(0, 1))
+        );
+    }
+
+    #[test]
+    fn fitted_boards_fill_the_tighter_side() {
+        let dimensions = Coordinate::new(3, 5);
+        let layout = BoardLayout::fit(dimensions, 500.0, 600.0, 4.0);
+        let size = layout.board_size(dimensions);
+        assert!((size.x - 500.0).abs() < 1e-9);
+        assert!(size.y <= 600.0);
+    }
+}
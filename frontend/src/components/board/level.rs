@@ -1,16 +1,17 @@
+use wasm_bindgen::{prelude::Closure, JsCast};
 use yew::prelude::*;
 use yew::{html, Callback};
 
-use game::model::{
-    coordinate::Coordinate,
-    gameboard::GameBoard,
-    grid::Grid,
-    tile::{Square, Tile},
-};
+use game::prelude::{Coordinate, Grid, Overlay, Square, Tile};
 
-use crate::components::board::{cell::CellComponent, grid::GridComponent, row::RowComponent};
+use crate::components::board::{
+    cell::{CellComponent, HighlightKind},
+    flow::{default_origin, use_energy_flow},
+    grid::GridComponent,
+    row::RowComponent,
+};
 use crate::components::reducers::board_reducer::{BoardAction, Level};
-use crate::helper::local_storage::save_level;
+use crate::helper::local_storage::retrieve_energy_flow;
 
 #[derive(Properties, PartialEq, Clone)]
 pub struct LevelProps {
@@ -24,6 +25,18 @@ pub struct LevelProps {
     pub can_turn: bool,
     #[prop_or(false)]
     pub can_change: bool,
+    /// clicks lock and unlock tiles instead of turning them
+    #[prop_or(false)]
+    pub can_lock: bool,
+    /// receives the coordinate of right-clicked cells
+    #[prop_or_default]
+    pub on_inspect: Option<Callback<Coordinate<isize>>>,
+    /// receives the coordinate of hovered cells
+    #[prop_or_default]
+    pub on_hover: Option<Callback<Coordinate<isize>>>,
+    /// highlighted cells in addition to the highlights of the board, e.g. the tiles of a conflict
+    #[prop_or_default]
+    pub highlights: Overlay<HighlightKind>,
 }
 
 /// A playable level supporting mouse actions.
@@ -35,23 +48,37 @@ pub fn level_component(props: &LevelProps) -> html {
         index: Coordinate<isize>,
         can_change: bool,
         head_message: UseStateHandle<String>,
+        flow_origin: Option<UseStateHandle<Option<Coordinate<isize>>>>,
     ) -> Callback<MouseEvent> {
-        Callback::from(move |_| {
+        Callback::from(move |e: MouseEvent| {
+            if let Some(flow_origin) = flow_origin.as_ref().filter(|_| e.shift_key()) {
+                log::debug!("Energy flows from {:?}.", index.to_tuple());
+                flow_origin.set(Some(index));
+                return;
+            }
             log::debug!(
                 "Tile with coordinate {:?} has been clicked.",
                 index.to_tuple()
             );
             log::debug!("can change? {}", can_change);
-            if can_change || !level.data.is_solved() {
+            if can_change || !level.is_won() {
                 level.dispatch(BoardAction::TurnCell(index));
-                save_level(&level.data);
-                log::debug!("saving level now");
             } else {
                 head_message.set(String::from("The level is already solved"));
             }
         })
     }
 
+    fn dispatch_toggle_lock(
+        board: UseReducerHandle<Level<Grid<Tile<Square>>>>,
+        index: Coordinate<isize>,
+    ) -> Callback<MouseEvent> {
+        Callback::from(move |_| {
+            log::debug!("Lock of tile {:?} toggled.", index.to_tuple());
+            board.dispatch(BoardAction::ToggleLock(index));
+        })
+    }
+
     fn dispatch_change_cell(
         board: UseReducerHandle<Level<Grid<Tile<Square>>>>,
         index: Coordinate<isize>,
@@ -62,8 +89,16 @@ pub fn level_component(props: &LevelProps) -> html {
                 index.to_tuple()
             );
             board.dispatch(BoardAction::ChangeTileShape(index));
-            save_level(&board.data);
-            log::debug!("saving level now");
+        })
+    }
+
+    fn dispatch_inspect_cell(
+        on_inspect: Callback<Coordinate<isize>>,
+        index: Coordinate<isize>,
+    ) -> Callback<MouseEvent> {
+        Callback::from(move |e: MouseEvent| {
+            e.prevent_default();
+            on_inspect.emit(index);
         })
     }
 
@@ -72,9 +107,54 @@ pub fn level_component(props: &LevelProps) -> html {
     let (height, width) = level_grid.dimensions().to_tuple();
     let (height, width) = (height as isize, width as isize);
 
+    let energy_flow = *use_state(retrieve_energy_flow);
+    let flow_origin = use_state_eq(|| None::<Coordinate<isize>>);
+    // a chosen origin only applies to the level it was chosen on
+    {
+        let flow_origin = flow_origin.clone();
+        use_effect_with_deps(
+            move |_| {
+                flow_origin.set(None);
+                || {}
+            },
+            board.id,
+        );
+    }
+    let flow = use_energy_flow(
+        &level_grid,
+        flow_origin.unwrap_or_else(|| default_origin(&level_grid)),
+        energy_flow,
+    );
+
+    // remove the highlights of the board once their time is up
+    {
+        let expiry = board.next_expiry();
+        let board = board.clone();
+        use_effect_with_deps(
+            move |&expiry| {
+                if let Some(expiry) = expiry {
+                    let delay = (expiry - board.platform.now()).max(0.0) as i32;
+                    let expire = Closure::<dyn Fn()>::new(move || {
+                        board.dispatch(BoardAction::ExpireHighlights);
+                    });
+                    web_sys::window()
+                        .unwrap()
+                        .set_timeout_with_callback_and_timeout_and_arguments_0(
+                            expire.as_ref().unchecked_ref(),
+                            delay,
+                        )
+                        .ok();
+                    expire.forget();
+                }
+                || {}
+            },
+            expiry,
+        );
+    }
+
     if props.can_complete {
         let overlay_message = props.overlay_message.clone();
-        if !board.data.is_solved() {
+        if !board.is_won() {
             overlay_message.set(String::from(""));
         } else {
             overlay_message.set(String::from("-LEVEL COMPLETED-"));
@@ -83,7 +163,7 @@ pub fn level_component(props: &LevelProps) -> html {
 
     html! {
         <div class="game-board">
-            <GridComponent overlay_message={props.overlay_message.clone()}>
+            <GridComponent overlay_message={props.overlay_message.clone()} torus={level_grid.is_torus()}>
                 {
                     (0..height).into_iter().map(| row | {
                         html!{
@@ -92,19 +172,30 @@ pub fn level_component(props: &LevelProps) -> html {
                                     (0..width).into_iter().map(| column | {
                                         let index = Coordinate { row, column };
                                         let tile = *level_grid.get(index).unwrap();
+                                        let mut highlights = board.highlight_kinds(index);
+                                        highlights.extend(props.highlights.get(index));
+                                        if board.locks.contains(index) {
+                                            highlights.push(HighlightKind::Locked);
+                                        }
                                         html!{
                                             <CellComponent
                                                 key={column}
                                                 tile={tile}
                                                 row_number={row}
                                                 column_number={column}
+                                                flow={flow[index]}
+                                                tooltip={board.hint_tooltip(index)}
+                                                highlights={highlights}
                                                 on_click={
-                                                    if props.can_turn {
+                                                    if props.can_lock {
+                                                        dispatch_toggle_lock(board.clone(), index)
+                                                    } else if props.can_turn {
                                                         dispatch_turn_cell(
                                                             board.clone(),
                                                             index,
                                                             props.can_change,
-                                                            props.head_message.clone()
+                                                            props.head_message.clone(),
+                                                            energy_flow.then(|| flow_origin.clone())
                                                         )
                                                     } else {
                                                         Callback::from(|_|{})
@@ -117,6 +208,18 @@ pub fn level_component(props: &LevelProps) -> html {
                                                         Callback::from(|_|{})
                                                     }
                                                 }
+                                                on_context_menu={
+                                                    match &props.on_inspect {
+                                                        Some(on_inspect) => dispatch_inspect_cell(on_inspect.clone(), index),
+                                                        None => Callback::from(|_|{}),
+                                                    }
+                                                }
+                                                on_mouse_enter={
+                                                    match &props.on_hover {
+                                                        Some(on_hover) => on_hover.reform(move |_| index),
+                                                        None => Callback::from(|_|{}),
+                                                    }
+                                                }
                                             ></CellComponent>
                                         }
                                     }).collect::<Html>()
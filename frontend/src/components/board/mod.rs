@@ -1,4 +1,7 @@
 pub mod cell;
+pub mod flow;
 pub mod grid;
+pub mod layout;
 pub mod level;
+pub mod polygon;
 pub mod row;
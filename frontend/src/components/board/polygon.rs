@@ -0,0 +1,276 @@
+use yew::prelude::*;
+use yew::{html, Callback, Properties};
+
+use game::prelude::{
+    Coordinate, GameBoard, Geometry, Grid, Hex, LevelDto, Tile, Triangle, WfcGenerator,
+};
+use game::render::svg::{
+    hex_outline, hex_tile_shapes, triangle_outline, triangle_tile_shapes, HEX_UNITS, TRIANGLE_UNITS,
+};
+use game::render::theme::{Theme, CELL_UNITS};
+
+use crate::components::board::grid::GridComponent;
+use crate::components::utils::tile_image::shape_element;
+
+/// Level of hexagonal or triangular tiles
+///
+/// square levels are played with the board reducer, see [`crate::components::board::level::LevelComponent`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum PolygonLevel {
+    Hex(Grid<Tile<Hex>>),
+    Triangle(Grid<Tile<Triangle>>),
+}
+
+impl PolygonLevel {
+    /// reads the tiles in the geometry recorded with the level
+    pub fn from_dto(dto: &LevelDto) -> Result<Self, String> {
+        match dto.to_geometry()? {
+            Geometry::Hex => dto.to_tiles().map(PolygonLevel::Hex),
+            Geometry::Triangle => dto.to_tiles().map(PolygonLevel::Triangle),
+            Geometry::Square => Err("square levels are played on the board page".to_string()),
+        }
+    }
+
+    pub fn to_dto(&self) -> LevelDto {
+        match self {
+            PolygonLevel::Hex(grid) => LevelDto::from_tiles(grid),
+            PolygonLevel::Triangle(grid) => LevelDto::from_tiles(grid),
+        }
+    }
+
+    /// Generates a scrambled level with a unique solution deterministically
+    pub fn generate(
+        geometry: Geometry,
+        dimension: Coordinate<usize>,
+        seed: u64,
+    ) -> Result<Self, String> {
+        let (rows, columns) = dimension.to_tuple();
+        match geometry {
+            Geometry::Hex => WfcGenerator::with_all_tiles(columns, rows)
+                .generate_unique(seed)
+                .map(PolygonLevel::Hex),
+            Geometry::Triangle => WfcGenerator::with_all_tiles(columns, rows)
+                .generate_unique(seed)
+                .map(PolygonLevel::Triangle),
+            Geometry::Square => Err("square levels are generated by the board".to_string()),
+        }
+    }
+
+    pub fn geometry(&self) -> Geometry {
+        match self {
+            PolygonLevel::Hex(_) => Geometry::Hex,
+            PolygonLevel::Triangle(_) => Geometry::Triangle,
+        }
+    }
+
+    /// turns the tile clockwise, positions outside the level are ignored
+    pub fn rotate(&self, index: Coordinate<isize>) -> Self {
+        match self {
+            PolygonLevel::Hex(grid) => PolygonLevel::Hex(
+                grid.rotate_clockwise(index)
+                    .unwrap_or_else(|_| grid.clone()),
+            ),
+            PolygonLevel::Triangle(grid) => PolygonLevel::Triangle(
+                grid.rotate_clockwise(index)
+                    .unwrap_or_else(|_| grid.clone()),
+            ),
+        }
+    }
+
+    pub fn is_solved(&self) -> bool {
+        match self {
+            PolygonLevel::Hex(grid) => grid.is_solved(),
+            PolygonLevel::Triangle(grid) => grid.is_solved(),
+        }
+    }
+
+    /// width and height of the board in the units of the cells
+    pub fn size(&self) -> (u32, u32) {
+        match self {
+            PolygonLevel::Hex(grid) if !grid.as_slice().is_empty() => {
+                let (rows, columns) = (grid.rows() as u32, grid.columns() as u32);
+                (
+                    columns * CELL_UNITS + (rows - 1) * CELL_UNITS / 2,
+                    (rows - 1) * HEX_UNITS * 3 / 4 + HEX_UNITS,
+                )
+            }
+            PolygonLevel::Triangle(grid) if !grid.as_slice().is_empty() => (
+                (grid.columns() as u32 + 1) * CELL_UNITS / 2,
+                grid.rows() as u32 * TRIANGLE_UNITS,
+            ),
+            _ => (0, 0),
+        }
+    }
+}
+
+/// top left corner of the hexagonal cell, every row is shifted half a cell to the right of the row above
+fn hex_position(index: Coordinate<isize>) -> (isize, isize) {
+    let (width, height) = (CELL_UNITS as isize, HEX_UNITS as isize);
+    (
+        index.column * width + index.row * width / 2,
+        index.row * height * 3 / 4,
+    )
+}
+
+/// top left corner of the triangular cell, neighboring triangles of a row overlap by half a cell
+fn triangle_position(index: Coordinate<isize>) -> (isize, isize) {
+    (
+        index.column * CELL_UNITS as isize / 2,
+        index.row * TRIANGLE_UNITS as isize,
+    )
+}
+
+/// the `points` attribute of an SVG polygon
+fn points(outline: &[(u32, u32)]) -> String {
+    outline
+        .iter()
+        .map(|(x, y)| format!("{x},{y}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct HexCellProps {
+    pub tile: Tile<Hex>,
+    pub index: Coordinate<isize>,
+    #[prop_or_default]
+    pub on_click: Callback<MouseEvent>,
+}
+
+/// A hexagonal tile drawn inside the SVG of its board
+#[function_component(HexCellComponent)]
+pub fn hex_cell_component(props: &HexCellProps) -> Html {
+    let (x, y) = hex_position(props.index);
+
+    html! {
+        <g class="polygon-cell"
+            transform={format!("translate({x} {y})")}
+            onclick={props.on_click.clone()}>
+            <polygon class="cell-outline" points={points(&hex_outline())} />
+            { for hex_tile_shapes(props.tile, &Theme::DEFAULT).into_iter().map(shape_element) }
+        </g>
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct TriangleCellProps {
+    pub tile: Tile<Triangle>,
+    pub index: Coordinate<isize>,
+    #[prop_or_default]
+    pub on_click: Callback<MouseEvent>,
+}
+
+/// A triangular tile drawn inside the SVG of its board, pointing up or down depending on its position
+#[function_component(TriangleCellComponent)]
+pub fn triangle_cell_component(props: &TriangleCellProps) -> Html {
+    let (x, y) = triangle_position(props.index);
+    let points_up = props.index.points_up();
+
+    html! {
+        <g class="polygon-cell"
+            transform={format!("translate({x} {y})")}
+            onclick={props.on_click.clone()}>
+            <polygon class="cell-outline" points={points(&triangle_outline(points_up))} />
+            {
+                for triangle_tile_shapes(props.tile, points_up, &Theme::DEFAULT)
+                    .into_iter()
+                    .map(shape_element)
+            }
+        </g>
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct PolygonLevelProps {
+    pub level: UseStateHandle<PolygonLevel>,
+    #[prop_or(use_state_eq(|| "".to_string()))]
+    pub overlay_message: UseStateHandle<String>,
+}
+
+/// A playable level of hexagonal or triangular tiles.
+/// The cells are drawn by the component of the geometry of the level.
+#[function_component(PolygonLevelComponent)]
+pub fn polygon_level_component(props: &PolygonLevelProps) -> Html {
+    let level = props.level.clone();
+    let turn = |index: Coordinate<isize>| {
+        let level = level.clone();
+        Callback::from(move |_: MouseEvent| {
+            log::debug!(
+                "Tile with coordinate {:?} has been clicked.",
+                index.to_tuple()
+            );
+            if !level.is_solved() {
+                level.set(level.rotate(index));
+            }
+        })
+    };
+
+    // an empty level is awaiting its generation, its overlay is left to the page
+    let overlay_message = props.overlay_message.clone();
+    if level.size() != (0, 0) {
+        let message = if level.is_solved() {
+            "-LEVEL COMPLETED-"
+        } else {
+            ""
+        };
+        overlay_message.set(String::from(message));
+    }
+
+    let cells = match &*level {
+        PolygonLevel::Hex(grid) => grid
+            .coordinates()
+            .map(|index| {
+                html! {
+                    <HexCellComponent tile={grid[index]} index={index} on_click={turn(index)} />
+                }
+            })
+            .collect::<Html>(),
+        PolygonLevel::Triangle(grid) => grid
+            .coordinates()
+            .map(|index| {
+                html! {
+                    <TriangleCellComponent tile={grid[index]} index={index} on_click={turn(index)} />
+                }
+            })
+            .collect::<Html>(),
+    };
+    let (width, height) = level.size();
+
+    html! {
+        <div class="game-board">
+            <GridComponent overlay_message={props.overlay_message.clone()}>
+                <svg class="polygon-board" viewBox={format!("0 0 {width} {height}")}>
+                    {cells}
+                </svg>
+            </GridComponent>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use game::prelude::{Coordinate, Geometry, Grid, LevelDto, Square, Tile};
+
+    use super::PolygonLevel;
+
+    #[test]
+    fn levels_are_read_in_their_geometry() {
+        for geometry in [Geometry::Hex, Geometry::Triangle] {
+            let level = PolygonLevel::generate(geometry, Coordinate::new(3, 3), 0)
+                .expect("generated level");
+            assert_eq!(level.geometry(), geometry);
+            assert_eq!(PolygonLevel::from_dto(&level.to_dto()), Ok(level));
+        }
+        let square = LevelDto::from(&Grid::<Tile<Square>>::EMPTY);
+        assert!(PolygonLevel::from_dto(&square).is_err());
+    }
+
+    #[test]
+    fn boards_enclose_their_cells() {
+        let level = PolygonLevel::generate(Geometry::Hex, Coordinate::new(2, 3), 0)
+            .expect("generated level");
+        // the second row is shifted by half a cell
+        assert_eq!(level.size(), (350, 201));
+    }
+}
@@ -0,0 +1,136 @@
+use yew::prelude::*;
+use yew::{html, Callback};
+
+use game::dto::BenchReportDto;
+
+use crate::helper::api::{ApiClient, ApiError};
+use crate::helper::local_storage::change_screen;
+use crate::helper::screen::Screen;
+
+/// size of a chart in svg units
+const CHART_WIDTH: f64 = 300.0;
+const CHART_HEIGHT: f64 = 60.0;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct BenchPageProps {
+    pub screen: UseStateHandle<Screen>,
+}
+
+/// admin page charting the benchmark reports recorded with `game-cli bench-report`
+///
+/// functionality
+/// - one chart per benchmark with the mean time of every recorded report, oldest first
+/// - the latest mean time of every benchmark
+#[function_component(BenchPage)]
+pub fn bench_page(props: &BenchPageProps) -> Html {
+    let reports = use_state(|| None::<Result<Vec<BenchReportDto>, ApiError>>);
+
+    {
+        let reports = reports.clone();
+        use_effect_with_deps(
+            move |_| {
+                wasm_bindgen_futures::spawn_local(async move {
+                    reports.set(Some(ApiClient::default().bench_history().await));
+                });
+                || {}
+            },
+            (),
+        );
+    }
+
+    let to_settings: Callback<MouseEvent> = {
+        let screen = props.screen.clone();
+        Callback::from(move |_| {
+            change_screen(screen.clone(), Screen::Settings);
+        })
+    };
+
+    let content = match &*reports {
+        None => html! { <p>{"Loading benchmark reports"}</p> },
+        Some(Err(error)) => html! { <p>{error.to_string()}</p> },
+        Some(Ok(reports)) if reports.is_empty() => html! {
+            <p>{"No reports recorded yet, run game-cli bench-report --output backend/data/bench"}</p>
+        },
+        Some(Ok(reports)) => html! {
+            <>
+                <p>
+                    {
+                        reports
+                            .iter()
+                            .map(|report| report.crate_version.as_str())
+                            .collect::<Vec<_>>()
+                            .join(" → ")
+                    }
+                </p>
+                { for benchmark_names(reports).into_iter().map(|name| chart(reports, &name)) }
+            </>
+        },
+    };
+
+    html! {
+        <div class="container">
+            <div id="bench-page" class="page-container">
+                <div class="page-title">{"#benchmarks"}</div>
+                <div class="page-content">
+                    {content}
+                </div>
+            </div>
+            <div class="controller">
+                <button onclick={to_settings}>
+                    {"-back-"}
+                </button>
+            </div>
+        </div>
+    }
+}
+
+/// names of all benchmarks in order of their first appearance
+fn benchmark_names(reports: &[BenchReportDto]) -> Vec<String> {
+    let mut names: Vec<String> = vec![];
+    for result in reports.iter().flat_map(|report| &report.results) {
+        if !names.contains(&result.name) {
+            names.push(result.name.clone());
+        }
+    }
+    names
+}
+
+/// line chart of the mean times of the benchmark, scaled to the slowest report
+fn chart(reports: &[BenchReportDto], name: &str) -> Html {
+    let times = reports
+        .iter()
+        .enumerate()
+        .filter_map(|(index, report)| report.nanoseconds(name).map(|time| (index, time)))
+        .collect::<Vec<_>>();
+    let slowest = times
+        .iter()
+        .map(|&(_, time)| time)
+        .max()
+        .unwrap_or(1)
+        .max(1) as f64;
+    let step = CHART_WIDTH / (reports.len().max(2) - 1) as f64;
+    let points = times
+        .iter()
+        .map(|&(index, time)| {
+            let x = index as f64 * step;
+            let y = CHART_HEIGHT - time as f64 / slowest * (CHART_HEIGHT - 5.0);
+            format!("{x:.1},{y:.1}")
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    let latest = times
+        .last()
+        .map(|&(_, time)| format!("{:.3} ms", time as f64 / 1_000_000.0))
+        .unwrap_or_default();
+
+    html! {
+        <div class="bench-chart">
+            <div class="bench-name">{format!("{name}: {latest}")}</div>
+            <svg
+                viewBox={format!("0 0 {CHART_WIDTH} {CHART_HEIGHT}")}
+                preserveAspectRatio="none">
+                <polyline class="bench-line" points={points} />
+            </svg>
+        </div>
+    }
+}
@@ -1,33 +1,51 @@
 use crate::helper::screen::Screen;
 use yew::prelude::*;
-use yew::{events::Event, html, Callback};
+use yew::{html, Callback};
 
-use wasm_bindgen::JsCast;
+#[cfg(feature = "sat")]
 use web_sys::HtmlInputElement;
+#[cfg(feature = "sat")]
+use yew::events::Event;
+
+use wasm_bindgen::{prelude::Closure, JsCast};
 
 pub struct Comp;
 
-use game::model::gameboard::GameBoard;
-use game::model::{
-    grid::Grid,
-    tile::{Square, Tile},
-};
+use game::prelude::{Grid, Square, Tile, WinRule};
 
+#[cfg(feature = "sat")]
 use game::model::cnf;
 
 use crate::components::board::level::LevelComponent;
-use crate::components::reducers::board_reducer::{BoardAction, Level};
+use crate::components::reducers::board_events::use_board_events;
+use crate::components::reducers::board_reducer::{
+    prepare_next_level, BoardAction, Level, SOLVE_STEP_DELAY,
+};
+use crate::components::utils::level_summary::LevelSummaryComponent;
+use crate::components::utils::slider::SliderComponent;
+use crate::components::utils::time_travel::TimeTravelPanel;
 
 use crate::helper::local_storage::change_screen;
+use crate::helper::platform::use_platform;
+use crate::helper::session::use_session;
+
+/// delay in milliseconds after rendering a level until the next one is prepared
+const PRECOMPUTE_DELAY: i32 = 500;
+
+/// fraction of the unsolved tiles solved by the partial solve button
+const PARTIAL_SOLVE_FRACTION: f32 = 0.25;
 
 /// the props are used to initialize the board page
 ///
 /// level_grid: level that is being played
+/// win_rule: rule of the level itself, e.g. chosen in the editor, otherwise the rule chosen in the settings
 /// screen: used to change screens away from board or to another level
 /// head_message: can be used to show information to user
 #[derive(Properties, PartialEq, Clone)]
 pub struct BoardPageProps {
     pub level_grid: Grid<Tile<Square>>,
+    #[prop_or_default]
+    pub win_rule: Option<WinRule>,
     pub screen: UseStateHandle<Screen>,
     pub head_message: UseStateHandle<String>,
     pub cnf: UseStateHandle<String>,
@@ -39,11 +57,86 @@ pub struct BoardPageProps {
 /// functinality
 /// - click and turn tiles
 /// - hinting: shortly highlight a tile to help solve level
-/// - solving the level
+/// - undo and redo rotations
+/// - solving the level, animated tile by tile with adjustable speed
+/// - solving a quarter of the unsolved tiles
 /// - load the next level
+/// - summary of the solved level, to replay, share or continue with the next level
 #[function_component(BoardPage)]
 pub fn board_page_component(props: &BoardPageProps) -> Html {
-    let board = use_reducer_eq(Level::set_grid(props.level_grid.clone()));
+    let board = use_reducer_eq(Level::set_level(
+        props.level_grid.clone(),
+        props.win_rule,
+        use_platform(),
+    ));
+    use_board_events(&board);
+    let summary = use_session(&board);
+
+    // prepare the next level in a timeout while the player is busy with the current one,
+    // so neither the reducer nor loading the next level blocks, even for large boards
+    {
+        let level_id = board.id;
+        let board = board.clone();
+        use_effect_with_deps(
+            move |_| {
+                let precompute = Closure::<dyn Fn()>::new(move || {
+                    if board.next.is_none() {
+                        let next = prepare_next_level(&board);
+                        board.dispatch(BoardAction::NextLevelPrepared(Box::new(next)));
+                    }
+                });
+                web_sys::window()
+                    .unwrap()
+                    .set_timeout_with_callback_and_timeout_and_arguments_0(
+                        precompute.as_ref().unchecked_ref(),
+                        PRECOMPUTE_DELAY,
+                    )
+                    .ok();
+                precompute.forget();
+                || {}
+            },
+            level_id,
+        );
+    }
+
+    // perform the queued rotations of the solve animation one at a time
+    {
+        let pending = board.pending.len();
+        let board = board.clone();
+        use_effect_with_deps(
+            move |&pending| {
+                if pending > 0 {
+                    let delay = board.step_delay as i32;
+                    let step = Closure::<dyn Fn()>::new(move || {
+                        board.dispatch(BoardAction::SolveStep);
+                    });
+                    web_sys::window()
+                        .unwrap()
+                        .set_timeout_with_callback_and_timeout_and_arguments_0(
+                            step.as_ref().unchecked_ref(),
+                            delay,
+                        )
+                        .ok();
+                    step.forget();
+                }
+                || {}
+            },
+            pending,
+        );
+    }
+
+    // rotations of the solve animation per second
+    let solve_speed = use_state(|| (1000 / SOLVE_STEP_DELAY) as isize);
+    {
+        let board = board.clone();
+        use_effect_with_deps(
+            move |&speed| {
+                board.dispatch(BoardAction::SetSolveSpeed(1000 / speed.max(1) as u32));
+                || {}
+            },
+            *solve_speed,
+        );
+    }
 
     let hint_onclick: Callback<MouseEvent> = {
         let board = board.clone();
@@ -53,37 +146,35 @@ pub fn board_page_component(props: &BoardPageProps) -> Html {
         })
     };
 
-    let solve_onclick: Callback<MouseEvent> = {
+    let undo_onclick: Callback<MouseEvent> = {
         let board = board.clone();
         Callback::from(move |_| {
-            log::info!("[Button click] Solve.");
-            board.dispatch(BoardAction::SolveLevel);
+            log::info!("[Button click] Undo.");
+            board.dispatch(BoardAction::Undo);
         })
     };
 
-    let solve_onclick_input: Callback<MouseEvent> = {
+    let redo_onclick: Callback<MouseEvent> = {
+        let board = board.clone();
+        Callback::from(move |_| {
+            log::info!("[Button click] Redo.");
+            board.dispatch(BoardAction::Redo);
+        })
+    };
+
+    let solve_onclick: Callback<MouseEvent> = {
         let board = board.clone();
-        let literals = props.literals.clone();
-        //let message = props.message.clone();
-        //let level_grid = board.level_grid.clone();
         Callback::from(move |_| {
             log::info!("[Button click] Solve.");
-            board.dispatch(BoardAction::SolveLevelInput(literals.to_string()));
-            // let msg = match level_grid.is_solved() {
-            //     true => String::from("The level is solved"),
-            //     false => String::from("This did not solve the level"),
-            // };
-            //message.set(msg);
+            board.dispatch(BoardAction::SolveLevel);
         })
     };
 
-    let generate_cnf: Callback<MouseEvent> = {
+    let solve_partial_onclick: Callback<MouseEvent> = {
         let board = board.clone();
-        let cnf = props.cnf.clone();
-        let level_grid = board.data.clone();
         Callback::from(move |_| {
-            log::info!("[Button click] Generate cnf.");
-            cnf.set(cnf::level_to_cnf(&level_grid.clone()).unwrap());
+            log::info!("[Button click] Solve partially.");
+            board.dispatch(BoardAction::SolvePartial(PARTIAL_SOLVE_FRACTION));
         })
     };
 
@@ -92,7 +183,7 @@ pub fn board_page_component(props: &BoardPageProps) -> Html {
         let head_message = props.head_message.clone();
         Callback::from(move |_| {
             log::info!("[Button click] Next.");
-            if board.data.is_solved() {
+            if board.is_won() {
                 board.dispatch(BoardAction::NextLevel);
             } else {
                 head_message.set(String::from("Solve the level to unlock a new level."));
@@ -100,6 +191,31 @@ pub fn board_page_component(props: &BoardPageProps) -> Html {
         })
     };
 
+    let summary_overlay = match &*summary {
+        Some(solved) => {
+            let on_replay: Callback<MouseEvent> = {
+                let board = board.clone();
+                let start = solved.start.clone();
+                Callback::from(move |_| {
+                    log::info!("[Button click] Replay.");
+                    board.dispatch(BoardAction::Replay(start.clone()));
+                })
+            };
+            let on_close: Callback<MouseEvent> = {
+                let summary = summary.clone();
+                Callback::from(move |_| summary.set(None))
+            };
+            html! {
+                <LevelSummaryComponent
+                    summary={solved.clone()}
+                    on_replay={on_replay}
+                    on_next={next_onclick.clone()}
+                    on_close={on_close}/>
+            }
+        }
+        None => html! {},
+    };
+
     let to_preview: Callback<MouseEvent> = {
         let screen = props.screen.clone();
         Callback::from(move |_| {
@@ -108,19 +224,16 @@ pub fn board_page_component(props: &BoardPageProps) -> Html {
         })
     };
 
-    let on_input: Callback<Event> = {
-        log::info!("[Button click] onInput");
-        let literals = props.literals.clone();
-        Callback::from(move |e: Event| {
-            let target = e.target();
-            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
-            log::info!("[Button click] onInput {}", input.clone().unwrap().value());
-            literals.set(input.unwrap().value());
-        })
+    let time_travel = if cfg!(debug_assertions) {
+        html! { <TimeTravelPanel board={board.clone()}/> }
+    } else {
+        html! {}
     };
 
     html! {
         <div class="container">
+            {time_travel}
+            {summary_overlay}
             <LevelComponent
                 board={board.clone()}
                 can_turn=true
@@ -133,25 +246,30 @@ pub fn board_page_component(props: &BoardPageProps) -> Html {
 
                 </button>
                 <button
-                    onclick={solve_onclick}>
-                    {"-solve-"}
+                    onclick={undo_onclick}
+                    disabled={!board.history.can_undo()}>
+                    {"-undo-"}
                 </button>
                 <button
-                    onclick={generate_cnf}>
-                    {"-generate cnf-"}
+                    onclick={redo_onclick}
+                    disabled={!board.history.can_redo()}>
+                    {"-redo-"}
+                </button>
+                <button
+                    onclick={solve_onclick}>
+                    {"-solve-"}
                 </button>
-
-                <input
-                    onchange={on_input}
-                    id="my-input"
-                    type="text"
-                    placeholder="Put the literals in DIMACS format here"
-                />
-
                 <button
-                    onclick={solve_onclick_input}>
-                    {"-solve with DIMACS input-"}
+                    onclick={solve_partial_onclick}>
+                    {"-solve 25%-"}
                 </button>
+                <SliderComponent
+                    id="solve-speed-slider"
+                    label="#solve speed"
+                    min=1
+                    max=20
+                    value={solve_speed}/>
+                {sat_controls(&board, &props.screen, &props.head_message, &props.cnf, &props.literals)}
                 <button
                     onclick={next_onclick}>
                     {"-next-"}
@@ -166,3 +284,109 @@ pub fn board_page_component(props: &BoardPageProps) -> Html {
         </div>
     }
 }
+
+/// SAT tools: show the CNF of the level and solve it with the embedded SAT solver or a model in DIMACS format
+#[cfg(feature = "sat")]
+fn sat_controls(
+    board: &UseReducerHandle<Level<Grid<Tile<Square>>>>,
+    screen: &UseStateHandle<Screen>,
+    head_message: &UseStateHandle<String>,
+    cnf: &UseStateHandle<String>,
+    literals: &UseStateHandle<String>,
+) -> Html {
+    let solve_onclick_input: Callback<MouseEvent> = {
+        let board = board.clone();
+        let head_message = head_message.clone();
+        let literals = literals.clone();
+        Callback::from(move |_| {
+            log::info!("[Button click] Solve.");
+            match board.data.solve_with_input(&literals) {
+                Ok(_) => board.dispatch(BoardAction::SolveLevelInput(literals.to_string())),
+                Err(error) => {
+                    head_message.set(format!("The model does not solve the level: {error}"))
+                }
+            }
+        })
+    };
+
+    let solve_onclick_sat: Callback<MouseEvent> = {
+        let board = board.clone();
+        Callback::from(move |_| {
+            log::info!("[Button click] Solve with SAT.");
+            board.dispatch(BoardAction::SolveLevelSat);
+        })
+    };
+
+    let generate_cnf: Callback<MouseEvent> = {
+        let board = board.clone();
+        let cnf = cnf.clone();
+        let level_grid = board.data.clone();
+        Callback::from(move |_| {
+            log::info!("[Button click] Generate cnf.");
+            cnf.set(cnf::level_to_cnf(&level_grid.clone()).unwrap());
+        })
+    };
+
+    let to_explorer: Callback<MouseEvent> = {
+        let board = board.clone();
+        let screen = screen.clone();
+        Callback::from(move |_| {
+            log::info!("[Button click] Explore cnf.");
+            change_screen(screen.clone(), Screen::Cnf(board.data.clone()));
+        })
+    };
+
+    let on_input: Callback<Event> = {
+        log::info!("[Button click] onInput");
+        let literals = literals.clone();
+        Callback::from(move |e: Event| {
+            let target = e.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+            log::info!("[Button click] onInput {}", input.clone().unwrap().value());
+            literals.set(input.unwrap().value());
+        })
+    };
+
+    html! {
+        <>
+            <button
+                onclick={generate_cnf}>
+                {"-generate cnf-"}
+            </button>
+
+            <button
+                onclick={to_explorer}>
+                {"-explore cnf-"}
+            </button>
+
+            <button
+                onclick={solve_onclick_sat}>
+                {"-solve with SAT-"}
+            </button>
+
+            <input
+                onchange={on_input}
+                id="my-input"
+                type="text"
+                placeholder="Put the literals in DIMACS format here"
+            />
+
+            <button
+                onclick={solve_onclick_input}>
+                {"-solve with DIMACS input-"}
+            </button>
+        </>
+    }
+}
+
+/// builds without the `sat` feature leave the SAT tools out
+#[cfg(not(feature = "sat"))]
+fn sat_controls(
+    _board: &UseReducerHandle<Level<Grid<Tile<Square>>>>,
+    _screen: &UseStateHandle<Screen>,
+    _head_message: &UseStateHandle<String>,
+    _cnf: &UseStateHandle<String>,
+    _literals: &UseStateHandle<String>,
+) -> Html {
+    html! {}
+}
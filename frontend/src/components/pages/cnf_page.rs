@@ -0,0 +1,124 @@
+use yew::prelude::*;
+use yew::{html, Callback};
+
+use game::model::cnf::{clauses_to_dimacs, level_to_clause_groups};
+use game::prelude::{Coordinate, Grid, Square, Tile};
+
+use crate::components::utils::tile_image::TileImage;
+use crate::helper::local_storage::change_screen;
+use crate::helper::screen::Screen;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct CnfPageProps {
+    pub level_grid: Grid<Tile<Square>>,
+    pub screen: UseStateHandle<Screen>,
+}
+
+/// developer page explaining the CNF encoding of a level
+///
+/// functionality
+/// - show the clauses in DIMACS format grouped per tile, border and adjacency
+/// - hovering a group highlights the constrained cells on the grid
+#[function_component(CnfPage)]
+pub fn cnf_page(props: &CnfPageProps) -> Html {
+    // index of the hovered clause group
+    let hovered = use_state_eq(|| None::<usize>);
+
+    let level_grid = &props.level_grid;
+    let groups = level_to_clause_groups(level_grid);
+    let highlighted = hovered
+        .and_then(|index| groups.get(index))
+        .map(|(group, _)| group.cells())
+        .unwrap_or_default();
+    let header = format!(
+        "p cnf {} {}",
+        level_grid.rows() * level_grid.columns() * 4,
+        groups
+            .iter()
+            .map(|(_, clauses)| clauses.len())
+            .sum::<usize>()
+    );
+    let (height, width) = level_grid.dimensions().to_tuple();
+    let (height, width) = (height as isize, width as isize);
+
+    let to_level: Callback<MouseEvent> = {
+        let screen = props.screen.clone();
+        let level_grid = level_grid.clone();
+        Callback::from(move |_| {
+            log::info!("[Button click] Back to level");
+            change_screen(screen.clone(), Screen::Level(level_grid.clone(), None));
+        })
+    };
+
+    let to_title: Callback<MouseEvent> = {
+        let screen = props.screen.clone();
+        Callback::from(move |_| {
+            change_screen(screen.clone(), Screen::Title);
+        })
+    };
+
+    html! {
+        <div class="container viz-page">
+            <div class="game-board">
+                {
+                    for (0..height).map(|row| html! {
+                        <div class="cell-row">
+                            {
+                                for (0..width).map(|column| {
+                                    let index = Coordinate { row, column };
+                                    let class = if highlighted.contains(&index) {
+                                        "cell cnf-highlight"
+                                    } else {
+                                        "cell"
+                                    };
+                                    html! {
+                                        <div class={class}>
+                                            <TileImage tile={level_grid[index]} />
+                                        </div>
+                                    }
+                                })
+                            }
+                        </div>
+                    })
+                }
+            </div>
+            <div class="cnf-groups">
+                <pre class="cnf-header">{header}</pre>
+                {
+                    for groups.iter().enumerate().map(|(index, (group, clauses))| {
+                        let onmouseenter = {
+                            let hovered = hovered.clone();
+                            Callback::from(move |_: MouseEvent| hovered.set(Some(index)))
+                        };
+                        let onmouseleave = {
+                            let hovered = hovered.clone();
+                            Callback::from(move |_: MouseEvent| hovered.set(None))
+                        };
+                        let class = if *hovered == Some(index) {
+                            "cnf-group cnf-group-hovered"
+                        } else {
+                            "cnf-group"
+                        };
+                        html! {
+                            <div
+                                class={class}
+                                onmouseenter={onmouseenter}
+                                onmouseleave={onmouseleave}>
+                                <div class="cnf-group-title">{group.to_string()}</div>
+                                <pre>{clauses_to_dimacs(clauses)}</pre>
+                            </div>
+                        }
+                    })
+                }
+            </div>
+            <div class="controller">
+                <button onclick={to_level}>
+                    {"-back-"}
+                </button>
+                <button onclick={to_title}>
+                    {"-home-"}
+                </button>
+            </div>
+        </div>
+    }
+}
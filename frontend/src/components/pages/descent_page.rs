@@ -0,0 +1,240 @@
+use gloo_timers::callback::Interval;
+use yew::prelude::*;
+use yew::{html, Callback};
+
+use game::descent::{Descent, RunResult};
+use game::prelude::Grid;
+use game::scoring::Scoring;
+
+use crate::components::board::level::LevelComponent;
+use crate::components::reducers::board_events::{use_board_events, BoardEvent};
+use crate::components::reducers::board_reducer::{BoardAction, Level};
+use crate::helper::local_storage::{
+    change_screen, retrieve_descent_leaderboard, save_descent_leaderboard,
+};
+use crate::helper::platform::use_platform;
+use crate::helper::screen::Screen;
+use crate::helper::session::minutes_and_seconds;
+
+/// milliseconds between two updates of the clock
+const CLOCK_INTERVAL: u32 = 1000;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct DescentPageProps {
+    pub screen: UseStateHandle<Screen>,
+    pub head_message: UseStateHandle<String>,
+}
+
+/// this page plays endless descent runs against the clock, see [`game::descent`]
+///
+/// functionality
+/// - click and turn tiles, every solved level is followed by a smaller and harder one
+/// - the run ends when the time is up and enters the leaderboard
+/// - start a new run
+#[function_component(DescentPage)]
+pub fn descent_page(props: &DescentPageProps) -> Html {
+    let platform = use_platform();
+    let descent = {
+        let platform = platform.clone();
+        use_state_eq(move || Descent::new(platform.random(u64::MAX)))
+    };
+    // start of the current level and the time of the latest tick of the clock
+    let started = {
+        let platform = platform.clone();
+        use_state_eq(move || platform.now())
+    };
+    let now = {
+        let platform = platform.clone();
+        use_state_eq(move || platform.now())
+    };
+    let start_level = use_state_eq(|| Grid::EMPTY);
+    let rotations = use_mut_ref(|| 0_u64);
+    let place = use_state_eq(|| None::<usize>);
+    let overlay_message = use_state_eq(|| String::from(""));
+    let board = use_reducer_eq(Level::set_grid(Grid::EMPTY, platform.clone()));
+    use_board_events(&board);
+
+    let current = (*now - *started).max(0.0) as u64;
+    let over = descent.is_over(current);
+
+    // generate the level of the current depth
+    {
+        let board = board.clone();
+        let started = started.clone();
+        let start_level = start_level.clone();
+        let rotations = rotations.clone();
+        let platform = platform.clone();
+        use_effect_with_deps(
+            move |descent: &Descent| {
+                let level = descent.level();
+                board.dispatch(BoardAction::ReplaceGrid(level.clone()));
+                start_level.set(level);
+                *rotations.borrow_mut() = 0;
+                // the time of the generation is not charged to the player
+                started.set(platform.now());
+                || {}
+            },
+            *descent,
+        );
+    }
+
+    {
+        let now = now.clone();
+        let platform = platform.clone();
+        use_effect_with_deps(
+            move |_| {
+                let interval = Interval::new(CLOCK_INTERVAL, move || now.set(platform.now()));
+                move || drop(interval)
+            },
+            (),
+        );
+    }
+
+    // count the rotations and descend once the level is solved in time
+    {
+        let descent = descent.clone();
+        let started = started.clone();
+        let serial = board.events.serial;
+        let board = board.clone();
+        let platform = platform.clone();
+        use_effect_with_deps(
+            move |_| {
+                for event in &board.events.events {
+                    match event {
+                        BoardEvent::Rotated(_) => *rotations.borrow_mut() += 1,
+                        BoardEvent::Solved {
+                            assisted: false, ..
+                        } => {
+                            let now = platform.now();
+                            let scoring = Scoring {
+                                rotations: *rotations.borrow(),
+                                hints: board.hints_given.len() as u32,
+                                elapsed_milliseconds: (now - *started).max(0.0) as u64,
+                                ..Scoring::new(&start_level, &board.data).unwrap_or_default()
+                            };
+                            if let Ok(next) = descent.descend(&scoring) {
+                                started.set(now);
+                                descent.set(next);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                || {}
+            },
+            serial,
+        );
+    }
+
+    // enter the finished run on the leaderboard
+    {
+        let place = place.clone();
+        let overlay_message = overlay_message.clone();
+        let result = descent.result();
+        use_effect_with_deps(
+            move |&over| {
+                if over {
+                    let mut leaderboard = retrieve_descent_leaderboard();
+                    place.set(leaderboard.insert(result));
+                    save_descent_leaderboard(&leaderboard);
+                    overlay_message.set(String::from("-TIME IS UP-"));
+                } else {
+                    overlay_message.set(String::from(""));
+                }
+                || {}
+            },
+            over,
+        );
+    }
+
+    let restart_onclick: Callback<MouseEvent> = {
+        let descent = descent.clone();
+        let started = started.clone();
+        let place = place.clone();
+        Callback::from(move |_| {
+            log::info!("[Button click] New descent run.");
+            started.set(platform.now());
+            place.set(None);
+            descent.set(Descent::new(platform.random(u64::MAX)));
+        })
+    };
+
+    let to_title: Callback<MouseEvent> = {
+        let screen = props.screen.clone();
+        Callback::from(move |_| {
+            change_screen(screen.clone(), Screen::Title);
+        })
+    };
+
+    let status = if over {
+        match *place {
+            Some(place) => format!("depth {} - place {place}", descent.depth),
+            None => format!("depth {}", descent.depth),
+        }
+    } else {
+        format!(
+            "depth {} - {} left",
+            descent.depth,
+            minutes_and_seconds(descent.remaining_milliseconds(current))
+        )
+    };
+
+    let leaderboard = retrieve_descent_leaderboard()
+        .runs()
+        .iter()
+        .map(|run| html! { <li>{run_entry(run)}</li> })
+        .collect::<Html>();
+
+    html! {
+        <div class="container viz-page">
+            <div class="game-board">
+                <LevelComponent
+                    board={board.clone()}
+                    can_turn={!over}
+                    can_change=false
+                    head_message={props.head_message.clone()}
+                    overlay_message={overlay_message.clone()}/>
+                <div class="level-title">{status}</div>
+            </div>
+            <div class="controller">
+                <ol class="descent-leaderboard">
+                    {leaderboard}
+                </ol>
+                <button onclick={restart_onclick}>
+                    {"-new run-"}
+                </button>
+                <button onclick={to_title}>
+                    {"-home-"}
+                </button>
+            </div>
+        </div>
+    }
+}
+
+/// line of the leaderboard, e.g. `depth 5 - 3200 points - 4:01`
+fn run_entry(run: &RunResult) -> String {
+    format!(
+        "depth {} - {} points - {}",
+        run.depth,
+        run.score,
+        minutes_and_seconds(run.elapsed_milliseconds)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+
+    use game::descent::RunResult;
+
+    use super::run_entry;
+
+    #[test]
+    fn entries_show_depth_score_and_time() {
+        let run = RunResult {
+            depth: 5,
+            score: 3200,
+            elapsed_milliseconds: 241_000,
+        };
+        assert_eq!(run_entry(&run), "depth 5 - 3200 points - 4:01");
+    }
+}
@@ -1,26 +1,235 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use futures::channel::mpsc::{unbounded, UnboundedSender};
 use yew::prelude::*;
 use yew::{html, Callback};
 
-use game::generator::fastgen::generate;
-use game::model::{coordinate::Coordinate, gameboard::GameBoard};
+use game::dto::{DraftMessageDto, LevelDto};
+use game::lint::lint;
+use game::model::collab::{CellEdit, SharedDraft};
+use game::prelude::{generate, Coordinate, GameBoard, Grid, Overlay, Square, Tile, WinRule};
+use game::solver::conflict::{find_conflict, Conflict};
+use game::solver::hint::remaining_states;
+#[cfg(feature = "sat")]
+use game::solver::sat::solve_with_sat;
 
-use crate::components::board::level::LevelComponent;
+use crate::components::board::{cell::HighlightKind, level::LevelComponent};
+use crate::components::reducers::board_events::use_board_events;
 use crate::components::reducers::board_reducer::{BoardAction, Level};
-use crate::helper::local_storage::{change_screen, save_editor_level};
+use crate::components::utils::time_travel::TimeTravelPanel;
+use crate::helper::api::ApiClient;
+use crate::helper::local_storage::{change_screen, next_level_name, save_editor_level};
+use crate::helper::platform::{use_platform, PlatformHandle};
 use crate::helper::screen::Screen;
+use crate::helper::share::{copy_to_clipboard, draft_link, share_link};
 
 #[derive(Properties, PartialEq, Clone)]
 pub struct EditorPageProps {
     pub screen: UseStateHandle<Screen>,
     pub head_message: UseStateHandle<String>,
+    /// id of the shared draft joined on opening the editor
+    #[prop_or_default]
+    pub draft: Option<String>,
+}
+
+/// shared draft edited together with other editors
+#[derive(Default)]
+struct DraftSession {
+    /// copy of the draft, once the backend answered the join
+    draft: Option<SharedDraft>,
+    /// sender of the messages to the other editors, dropping it leaves the draft
+    sender: Option<UnboundedSender<DraftMessageDto>>,
 }
 
 #[function_component(EditorPage)]
 pub fn editor_page_component(props: &EditorPageProps) -> Html {
     let new_grid = generate(Coordinate { row: 5, column: 5 }, 99);
-    let board = use_reducer_eq(Level::set_grid(new_grid));
+    let platform = use_platform();
+    let board = use_reducer_eq(Level::set_grid(new_grid, platform.clone()));
+    use_board_events(&board);
 
     let level_grid = board.data.clone();
+    let locked_grid = board.data.with_locks(&board.locks);
+
+    // rule the level is saved and played with
+    let win_rule = use_state(WinRule::default);
+
+    // shared draft edited with other editors, the copy is set once the backend answers the join
+    let session = use_mut_ref(DraftSession::default);
+    let draft_id = use_state_eq(|| None::<String>);
+    let remote_cursors = use_state_eq(Vec::<Coordinate<isize>>::new);
+
+    {
+        let join = join_draft(
+            &session,
+            &board,
+            &draft_id,
+            &remote_cursors,
+            &props.head_message,
+        );
+        let session = session.clone();
+        use_effect_with_deps(
+            move |draft: &Option<String>| {
+                if let Some(id) = draft {
+                    join.emit(id.clone());
+                }
+                // leaving the editor leaves the draft
+                move || drop(session.take())
+            },
+            props.draft.clone(),
+        );
+    }
+
+    // local changes of the tiles are sent as edits, the dimensions of shared drafts are fixed
+    {
+        let session = session.clone();
+        let grid = board.data.clone();
+        let board = board.clone();
+        let head_message = props.head_message.clone();
+        use_effect_with_deps(
+            move |grid: &Grid<Tile<Square>>| {
+                if let DraftSession {
+                    draft: Some(draft),
+                    sender: Some(sender),
+                } = &mut *session.borrow_mut()
+                {
+                    if grid.dimensions() != draft.grid().dimensions() {
+                        head_message.set(String::from("The size of a shared draft is fixed"));
+                        board.dispatch(BoardAction::ReplaceGrid(draft.grid().clone()));
+                    } else {
+                        for index in grid.coordinates() {
+                            if grid[index] != draft.grid()[index] {
+                                if let Ok(edit) = draft.edit(index, grid[index]) {
+                                    sender
+                                        .unbounded_send(DraftMessageDto::Edit(edit.into()))
+                                        .ok();
+                                }
+                            }
+                        }
+                    }
+                }
+                || {}
+            },
+            grid,
+        );
+    }
+
+    let on_hover: Callback<Coordinate<isize>> = {
+        let session = session.clone();
+        Callback::from(move |index: Coordinate<isize>| {
+            if let DraftSession {
+                draft: Some(draft),
+                sender: Some(sender),
+            } = &*session.borrow()
+            {
+                let cursor = draft.move_cursor(index);
+                sender
+                    .unbounded_send(DraftMessageDto::Cursor(cursor.into()))
+                    .ok();
+            }
+        })
+    };
+
+    let collaborate_onclick: Callback<MouseEvent> = {
+        let join = join_draft(
+            &session,
+            &board,
+            &draft_id,
+            &remote_cursors,
+            &props.head_message,
+        );
+        let session = session.clone();
+        let joined = draft_id.is_some();
+        let platform = platform.clone();
+        let head_message = props.head_message.clone();
+        Callback::from(move |_| {
+            if joined {
+                log::info!("[Button click] Leave shared draft.");
+                session.take();
+                return;
+            }
+            log::info!("[Button click] Collaborate.");
+            let id = format!("{:016x}", platform.random(u64::MAX));
+            let copied = draft_link(&id)
+                .and_then(|link| copy_to_clipboard(&link).map_err(|e| format!("{e:?}")));
+            head_message.set(match copied {
+                Ok(()) => "Invite link copied, send it to the other editors".to_string(),
+                Err(e) => format!("Copying the invite link failed: {e}"),
+            });
+            join.emit(id);
+        })
+    };
+
+    let toggle_win_rule: Callback<MouseEvent> = {
+        let win_rule = win_rule.clone();
+        Callback::from(move |_| {
+            let position = WinRule::ALL.iter().position(|&rule| rule == *win_rule);
+            let rule = WinRule::ALL[position.map_or(0, |p| (p + 1) % WinRule::ALL.len())];
+            log::info!("[Button click] Win rule: {}", rule);
+            win_rule.set(rule);
+        })
+    };
+
+    // clicks lock and unlock tiles instead of turning them
+    let lock_mode = use_state(|| false);
+
+    let toggle_lock_mode: Callback<MouseEvent> = {
+        let lock_mode = lock_mode.clone();
+        Callback::from(move |_| {
+            log::info!("[Button click] Lock mode: {}", !*lock_mode);
+            lock_mode.set(!*lock_mode);
+        })
+    };
+
+    // warnings about the level, shown before it is saved
+    let warnings = use_state(Vec::<String>::new);
+
+    let lint_onclick: Callback<MouseEvent> = {
+        let board = board.clone();
+        let warnings = warnings.clone();
+        Callback::from(move |_| {
+            log::info!("[Button click] Lint.");
+            warnings.set(lint(&board.data).iter().map(ToString::to_string).collect());
+        })
+    };
+
+    // explanation why the level is unsolvable, shown in a modal until the level changes
+    let conflict = use_state_eq(|| None::<Conflict>);
+    {
+        let conflict = conflict.clone();
+        use_effect_with_deps(
+            move |_| {
+                conflict.set(None);
+                || {}
+            },
+            board.data.clone(),
+        );
+    }
+
+    let explain_onclick: Callback<MouseEvent> = {
+        let board = board.clone();
+        let conflict = conflict.clone();
+        let head_message = props.head_message.clone();
+        Callback::from(move |_| {
+            log::info!("[Button click] Explain why unsolvable.");
+            match find_conflict(&board.data) {
+                None => head_message.set(String::from("The level is solvable")),
+                Some(found) => conflict.set(Some(found)),
+            }
+        })
+    };
+
+    // cell whose remaining states are shown in the inspector
+    let inspected = use_state(|| None::<Coordinate<isize>>);
+
+    let on_inspect: Callback<Coordinate<isize>> = {
+        let inspected = inspected.clone();
+        Callback::from(move |index: Coordinate<isize>| {
+            log::info!("[Right click] Inspect cell {}", index);
+            inspected.set(Some(index));
+        })
+    };
 
     let clear_onclick: Callback<MouseEvent> = {
         let board = board.clone();
@@ -46,14 +255,23 @@ pub fn editor_page_component(props: &EditorPageProps) -> Html {
         })
     };
 
+    let generate_torus_onclick: Callback<MouseEvent> = {
+        let board = board.clone();
+        Callback::from(move |_| {
+            log::info!("[Button click] Generate torus.");
+            board.dispatch(BoardAction::GenerateTorus);
+        })
+    };
+
     let check_cps_onclick: Callback<MouseEvent> = {
         // let board = board.clone();
         let head_message = props.head_message.clone();
+        let rule = *win_rule;
         Callback::from(move |_| {
             log::info!("[Button click] Check with CPS.");
             log::info!("Current grid\n{}", level_grid.to_string());
 
-            let solution_num = level_grid.solve().count();
+            let solution_num = locked_grid.solve().count();
             log::info!(
                 "Is valid grid? {}",
                 match solution_num {
@@ -64,12 +282,17 @@ pub fn editor_page_component(props: &EditorPageProps) -> Html {
 
             let msg = match solution_num {
                 0 => String::from("The level is not valid"),
+                _ if !has_solution_with(&level_grid, rule) => {
+                    format!("The level has no solution with the win rule {rule}")
+                }
                 n => format!("The level is valid and has {} possible solutions", n),
             };
             head_message.set(msg);
         })
     };
 
+    let sat_check = sat_check_button(&board.data, &props.head_message, &platform);
+
     let check_solved_onclick: Callback<MouseEvent> = {
         let board = board.clone();
         let head_message = props.head_message.clone();
@@ -88,6 +311,22 @@ pub fn editor_page_component(props: &EditorPageProps) -> Html {
         })
     };
 
+    let undo_onclick: Callback<MouseEvent> = {
+        let board = board.clone();
+        Callback::from(move |_| {
+            log::info!("[Button click] Undo.");
+            board.dispatch(BoardAction::Undo);
+        })
+    };
+
+    let redo_onclick: Callback<MouseEvent> = {
+        let board = board.clone();
+        Callback::from(move |_| {
+            log::info!("[Button click] Redo.");
+            board.dispatch(BoardAction::Redo);
+        })
+    };
+
     let shuffle_tile_rotations_onclick: Callback<MouseEvent> = {
         let board = board.clone();
         Callback::from(move |_| {
@@ -100,14 +339,15 @@ pub fn editor_page_component(props: &EditorPageProps) -> Html {
         let screen = props.screen.clone();
         let grid = board.data.clone();
         let head_message = props.head_message.clone();
+        let rule = *win_rule;
         Callback::from(move |_| {
             log::info!("[Button click] Play custom grid.");
             log::info!("Current grid\n{}", grid.to_string());
-            if grid.solve().count() != 0 {
-                change_screen(screen.clone(), Screen::Level(grid.clone()));
+            if has_solution_with(&grid, rule) {
+                change_screen(screen.clone(), Screen::Level(grid.clone(), Some(rule)));
             } else {
-                head_message.set(String::from(
-                    "The level is not valid and thus not playable.",
+                head_message.set(format!(
+                    "The level has no solution with the win rule {rule} and thus is not playable."
                 ));
             }
         })
@@ -181,13 +421,70 @@ pub fn editor_page_component(props: &EditorPageProps) -> Html {
         })
     };
 
+    // name the level is saved under, saving again replaces the level of this name
+    let level_name = use_state(next_level_name);
+
     let save_onclick: Callback<MouseEvent> = {
         let board = board.clone();
         let head_message = props.head_message.clone();
+        let warnings = warnings.clone();
+        let rule = *win_rule;
+        let name = (*level_name).clone();
         Callback::from(move |_| {
             log::info!("[Button click] Save level.");
-            save_editor_level(&board.data);
-            head_message.set(String::from("Saved level"));
+            if has_solution_with(&board.data, rule) {
+                save_editor_level(&name, &board.data, rule);
+                let lints = lint(&board.data);
+                head_message.set(match lints.len() {
+                    0 => format!("Saved level as {name}"),
+                    n => format!("Saved level as {name} with {n} warnings"),
+                });
+                warnings.set(lints.iter().map(ToString::to_string).collect());
+            } else {
+                head_message.set(format!(
+                    "The level has no solution with the win rule {rule} and thus is not saved."
+                ));
+            }
+        })
+    };
+
+    let share_onclick: Callback<MouseEvent> = {
+        let board = board.clone();
+        let head_message = props.head_message.clone();
+        let rule = *win_rule;
+        Callback::from(move |_| {
+            log::info!("[Button click] Share level.");
+            if !has_solution_with(&board.data, rule) {
+                head_message.set(format!(
+                    "The level has no solution with the win rule {rule} and thus is not shared."
+                ));
+                return;
+            }
+            let level = LevelDto {
+                win_rule: Some(rule.to_string()),
+                ..LevelDto::from(&board.data)
+            };
+            let head_message = head_message.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                head_message.set(match ApiClient::default().upload_level(&level).await {
+                    Ok(shared) => format!("Shared as {} level #{}", shared.difficulty, shared.id),
+                    Err(e) => e.to_string(),
+                });
+            });
+        })
+    };
+
+    let copy_link_onclick: Callback<MouseEvent> = {
+        let board = board.clone();
+        let head_message = props.head_message.clone();
+        Callback::from(move |_| {
+            log::info!("[Button click] Copy share link.");
+            let copied = share_link(&board.data)
+                .and_then(|link| copy_to_clipboard(&link).map_err(|e| format!("{e:?}")));
+            head_message.set(match copied {
+                Ok(()) => "Share link copied".to_string(),
+                Err(e) => format!("Copying the share link failed: {e}"),
+            });
         })
     };
 
@@ -207,8 +504,34 @@ pub fn editor_page_component(props: &EditorPageProps) -> Html {
         })
     };
 
+    let inspection = match *inspected {
+        Some(index) => describe_remaining_states(&board.data, index),
+        None => String::from("Right-click a cell to see what the solver thinks"),
+    };
+
+    // a conflict of the level before a resize lies partly outside and is dropped
+    let mut highlights = (*conflict)
+        .as_ref()
+        .and_then(|conflict| {
+            let tiles = conflict.tiles.iter().map(|&c| (c, HighlightKind::Conflict));
+            Overlay::from_annotations(board.data.dimensions(), tiles).ok()
+        })
+        .unwrap_or_else(|| Overlay::for_grid(&board.data));
+    for &index in remote_cursors.iter() {
+        if highlights.get(index).is_none() {
+            highlights.insert(index, HighlightKind::RemoteCursor).ok();
+        }
+    }
+
+    let time_travel = if cfg!(debug_assertions) {
+        html! { <TimeTravelPanel board={board.clone()}/> }
+    } else {
+        html! {}
+    };
+
     html! {
         <div class="container editor-page">
+            {time_travel}
             <section class="controller">
                 <ul style="list-style-type: none">
                     <li><button
@@ -236,8 +559,19 @@ pub fn editor_page_component(props: &EditorPageProps) -> Html {
                 can_complete=false
                 can_turn=true
                 can_change=true
+                can_lock={*lock_mode}
+                on_inspect={on_inspect}
+                on_hover={on_hover}
+                highlights={highlights}
                 head_message={props.head_message.clone()} />
 
+            {conflict_modal(&board, &conflict)}
+
+            <p class="inspector">{inspection}</p>
+            <ul class="lint">
+                { for warnings.iter().map(|warning| html! { <li>{warning}</li> }) }
+            </ul>
+
             <div class="controller">
                 <button
                     onclick={generate_fast_gen_onclick}
@@ -245,13 +579,26 @@ pub fn editor_page_component(props: &EditorPageProps) -> Html {
                 <button
                     onclick={generate_wfc_onclick}
                     >{"-Generate with WFC-"}</button>
+                <button
+                    onclick={generate_torus_onclick}
+                    >{"-Generate torus-"}</button>
                 <p style="text-align:center;margin:20px">{"____"}</p>
+                <button
+                    onclick={toggle_win_rule}
+                    >{format!("-Win: {}-", *win_rule)}</button>
                 <button
                     onclick={check_cps_onclick}
                     >{"-Check validity-"}</button>
+                {sat_check}
                 <button
                     onclick={check_solved_onclick}
                     >{"-Check if solved-"}</button>
+                <button
+                    onclick={lint_onclick}
+                    >{"-Lint-"}</button>
+                <button
+                    onclick={explain_onclick}
+                    >{"-Explain why unsolvable-"}</button>
                 <p style="text-align:center;margin:20px">{"____"}</p>
                 <button
                     onclick={clear_onclick}
@@ -259,10 +606,30 @@ pub fn editor_page_component(props: &EditorPageProps) -> Html {
                 <button
                     onclick={shuffle_tile_rotations_onclick}
                     >{"-Shuffle tile rotations-"}</button>
+                <button
+                    onclick={toggle_lock_mode}
+                    >{if *lock_mode { "-Lock tiles: on-" } else { "-Lock tiles: off-" }}</button>
+                <button
+                    onclick={undo_onclick}
+                    disabled={!board.history.can_undo()}
+                    >{"-Undo-"}</button>
+                <button
+                    onclick={redo_onclick}
+                    disabled={!board.history.can_redo()}
+                    >{"-Redo-"}</button>
                 <p style="text-align:center;margin:20px">{"____"}</p>
                 <button
                     onclick={save_onclick}
                     >{"-Save-"}</button>
+                <button
+                    onclick={share_onclick}
+                    >{"-Share-"}</button>
+                <button
+                    onclick={copy_link_onclick}
+                    >{"-Copy link-"}</button>
+                <button
+                    onclick={collaborate_onclick}
+                    >{if draft_id.is_some() { "-Leave shared draft-" } else { "-Collaborate-" }}</button>
                 <button
                     onclick={play_onclick}
                     >{"-Play-"}</button>
@@ -276,3 +643,231 @@ pub fn editor_page_component(props: &EditorPageProps) -> Html {
         </div>
     }
 }
+
+/// checks the level with the embedded SAT solver and compares its time to the propagation solver
+#[cfg(feature = "sat")]
+fn sat_check_button(
+    level_grid: &Grid<Tile<Square>>,
+    head_message: &UseStateHandle<String>,
+    platform: &PlatformHandle,
+) -> Html {
+    let onclick: Callback<MouseEvent> = {
+        let level_grid = level_grid.clone();
+        let head_message = head_message.clone();
+        let platform = platform.clone();
+        Callback::from(move |_| {
+            log::info!("[Button click] Check with SAT solver.");
+
+            let start = platform.now();
+            let sat = solve_with_sat(&level_grid);
+            let sat_time = platform.now() - start;
+
+            let start = platform.now();
+            let propagation = level_grid.solve().next().is_some();
+            let propagation_time = platform.now() - start;
+            log::info!("SAT solver {sat_time} ms, propagation solver {propagation_time} ms");
+
+            let msg = match sat {
+                Err(e) => format!("The SAT solver failed: {e}"),
+                Ok(solution) => format!(
+                    "SAT solver: level is {} ({sat_time:.0} ms), propagation solver: level is {} ({propagation_time:.0} ms)",
+                    validity(solution.is_some()),
+                    validity(propagation)
+                ),
+            };
+            head_message.set(msg);
+        })
+    };
+
+    html! {
+        <button
+            onclick={onclick}
+            >{"-Check validity with SAT Solver-"}</button>
+    }
+}
+
+#[cfg(feature = "sat")]
+fn validity(solvable: bool) -> &'static str {
+    if solvable {
+        "valid"
+    } else {
+        "not valid"
+    }
+}
+
+/// builds without the `sat` feature leave the SAT solver out, the SAT demo built with it is loaded on demand
+#[cfg(not(feature = "sat"))]
+fn sat_check_button(
+    _level_grid: &Grid<Tile<Square>>,
+    _head_message: &UseStateHandle<String>,
+    _platform: &PlatformHandle,
+) -> Html {
+    let onclick = Callback::from(|_| {
+        log::info!("[Button click] Open SAT demo.");
+        if let Some(window) = web_sys::window() {
+            window.location().set_href("/sat/").ok();
+        }
+    });
+
+    html! {
+        <button
+            onclick={onclick}
+            >{"-Open SAT demo-"}</button>
+    }
+}
+
+/// joins the shared draft of the id with the grid of the board, until the backend closes it or the editor leaves it
+///
+/// the board follows the edits of the other editors, whose cursors are highlighted
+fn join_draft(
+    session: &Rc<RefCell<DraftSession>>,
+    board: &UseReducerHandle<Level<Grid<Tile<Square>>>>,
+    draft_id: &UseStateHandle<Option<String>>,
+    remote_cursors: &UseStateHandle<Vec<Coordinate<isize>>>,
+    head_message: &UseStateHandle<String>,
+) -> Callback<String> {
+    let session = session.clone();
+    let board = board.clone();
+    let draft_id = draft_id.clone();
+    let remote_cursors = remote_cursors.clone();
+    let head_message = head_message.clone();
+    Callback::from(move |id: String| {
+        let (sender, outgoing) = unbounded();
+        let grid = board.data.clone();
+        let session = session.clone();
+        let board = board.clone();
+        let draft_id = draft_id.clone();
+        let remote_cursors = remote_cursors.clone();
+        let head_message = head_message.clone();
+        draft_id.set(Some(id.clone()));
+        *session.borrow_mut() = DraftSession {
+            draft: None,
+            sender: Some(sender),
+        };
+        wasm_bindgen_futures::spawn_local(async move {
+            let on_message = |message: DraftMessageDto| {
+                let mut session = session.borrow_mut();
+                if let DraftMessageDto::Joined { .. } = message {
+                    match message.to_draft() {
+                        Ok(draft) => {
+                            board.dispatch(BoardAction::ReplaceGrid(draft.grid().clone()));
+                            session.draft = Some(draft);
+                        }
+                        Err(e) => log::warn!("Joining the shared draft failed: {e}"),
+                    }
+                    return;
+                }
+                let draft = match session.draft.as_mut() {
+                    Some(draft) => draft,
+                    None => return,
+                };
+                match message {
+                    DraftMessageDto::Edit(edit) => {
+                        if let Ok(edit) = CellEdit::try_from(edit) {
+                            if draft.apply(edit) == Ok(true) {
+                                board.dispatch(BoardAction::SetTile(edit.index, edit.tile));
+                            }
+                        }
+                    }
+                    DraftMessageDto::Cursor(cursor) => draft.apply_cursor(cursor.into()),
+                    DraftMessageDto::Left { editor } => draft.leave(editor),
+                    _ => {}
+                }
+                remote_cursors.set(draft.cursors().map(|cursor| cursor.index).collect());
+            };
+            let result = ApiClient::default()
+                .edit_draft(&id, &grid, outgoing, on_message)
+                .await;
+            // the editor may have joined another draft meanwhile
+            let mut session = session.borrow_mut();
+            if session
+                .sender
+                .as_ref()
+                .map_or(true, UnboundedSender::is_closed)
+            {
+                *session = DraftSession::default();
+                draft_id.set(None);
+                remote_cursors.set(Vec::new());
+            }
+            head_message.set(match result {
+                Ok(()) => String::from("Left the shared draft"),
+                Err(e) => format!("The shared draft was closed: {e}"),
+            });
+        });
+    })
+}
+
+/// lists the conflicting tiles of an unsolvable level and offers their fixes, applied with a click
+fn conflict_modal(
+    board: &UseReducerHandle<Level<Grid<Tile<Square>>>>,
+    conflict: &UseStateHandle<Option<Conflict>>,
+) -> Html {
+    let found = match &**conflict {
+        None => return html! {},
+        Some(found) => found,
+    };
+
+    let close_onclick: Callback<MouseEvent> = {
+        let conflict = conflict.clone();
+        Callback::from(move |_| conflict.set(None))
+    };
+
+    let fixes = if found.fixes.is_empty() {
+        html! { <p>{"No single change of a tile solves the level."}</p> }
+    } else {
+        let fixes = found.fixes.iter().map(|&fix| {
+            let onclick = {
+                let board = board.clone();
+                Callback::from(move |_| {
+                    log::info!("[Button click] Fix tile {}", fix.coordinate);
+                    board.dispatch(BoardAction::SetTile(fix.coordinate, fix.tile));
+                })
+            };
+            html! {
+                <li><button onclick={onclick}>{fix.describe(&board.data)}</button></li>
+            }
+        });
+        html! {
+            <>
+                <p>{"Each of these changes makes the level solvable:"}</p>
+                <ul>{ for fixes }</ul>
+            </>
+        }
+    };
+
+    html! {
+        <div class="modal">
+            <p>{"The level is unsolvable, these tiles cannot be solved together:"}</p>
+            <ul>
+                { for found.describe(&board.data).into_iter().map(|tile| html! { <li>{tile}</li> }) }
+            </ul>
+            {fixes}
+            <button onclick={close_onclick}>{"-close-"}</button>
+        </div>
+    }
+}
+
+/// explains which states the solver still considers for the cell after all logical deductions
+fn describe_remaining_states(grid: &Grid<Tile<Square>>, index: Coordinate<isize>) -> String {
+    let remaining = remaining_states(grid);
+    match remaining.get(index) {
+        None => format!("Cell {index} is outside of the level"),
+        Some(states) => {
+            let glyphs = states
+                .iter()
+                .map(|tile| tile.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            match states.len() {
+                0 => format!("Cell {index} has no state left, the level is unsolvable"),
+                1 => format!("Cell {index} is determined: {glyphs}"),
+                _ => format!("Cell {index} can still be {glyphs}"),
+            }
+        }
+    }
+}
+
+/// whether one of the solutions of the level satisfies the win rule
+fn has_solution_with(grid: &Grid<Tile<Square>>, rule: WinRule) -> bool {
+    grid.solve().any(|solution| rule.is_met_by(&solution))
+}
@@ -3,18 +3,19 @@ use yew::{html, Callback};
 
 use rand::Rng;
 
-use crate::components::board::level::StatelessLevelComponent;
 use crate::components::reducers::preview_reducer::{PreviewAction, PreviewState};
+use crate::components::utils::continue_card::ContinueCard;
+use crate::components::utils::thumbnail::Thumbnail;
 
 use crate::helper::local_storage::{
-    change_screen, retrieve_editor_level, retrieve_preview_level_count, save_preview_level_count,
+    change_screen, delete_level, list_levels, rename_level, retrieve_campaign_progress,
+    retrieve_preview_level_count, save_preview_level_count,
 };
+use crate::helper::platform::use_platform;
 use crate::helper::screen::Screen;
 
-use game::generator::fastgen::generate;
-use game::model::coordinate::Coordinate;
-use game::model::grid::Grid;
-use game::model::tile::{Square, Tile};
+use game::campaign::CHAPTERS;
+use game::prelude::{generate, Coordinate, Grid, Square, Tile, WinRule};
 
 /// the props are used to initialize the preview component
 ///
@@ -29,10 +30,12 @@ pub struct LevelPreviewPageProps {
 /// this component can be used to preview levels
 ///
 /// functinality
+/// - play the levels of the campaign chapters unlocked so far
 /// - choose a specific level to play
 /// - choose a random level to play
 /// - load additional levels
-/// - show a previously saved level (from editor)
+/// - show, rename and delete the levels saved in the editor
+/// - continue the level left unsolved
 #[function_component(LevelPreviewPage)]
 pub fn level_preview_page_component(props: &LevelPreviewPageProps) -> Html {
     let generate_nr = retrieve_preview_level_count();
@@ -42,9 +45,17 @@ pub fn level_preview_page_component(props: &LevelPreviewPageProps) -> Html {
         .map(|index| generate(*props.dimension, index))
         .collect::<Vec<_>>();
 
-    let saved_level = retrieve_editor_level();
+    let saved_levels = use_state(list_levels);
 
-    let reducer = use_reducer(PreviewState::set(generated_levels));
+    let campaign = retrieve_campaign_progress();
+    let chapter_levels = use_state(|| {
+        CHAPTERS
+            .iter()
+            .map(|chapter| chapter.all_levels().collect::<Vec<_>>())
+            .collect::<Vec<_>>()
+    });
+
+    let reducer = use_reducer(PreviewState::set(generated_levels, use_platform()));
 
     let load_more_levels: Callback<MouseEvent> = {
         let reducer = reducer.clone();
@@ -59,14 +70,13 @@ pub fn level_preview_page_component(props: &LevelPreviewPageProps) -> Html {
     };
 
     let pick_random_level: Callback<MouseEvent> = {
-        let level_count = level_count.clone();
         let screen = props.screen.clone();
         let reducer = reducer.clone();
         Callback::from(move |_| {
             log::info!("picking random level");
-            let num = rand::thread_rng().gen_range(0..*level_count);
+            let num = rand::thread_rng().gen_range(0..reducer.extracted_levels.len());
             let level = reducer.extracted_levels[num].clone();
-            change_screen(screen.clone(), Screen::Level(level));
+            change_screen(screen.clone(), Screen::Level(level, None));
         })
     };
 
@@ -86,43 +96,146 @@ pub fn level_preview_page_component(props: &LevelPreviewPageProps) -> Html {
         })
     };
 
+    let rename_onclick = |name: String| {
+        let saved_levels = saved_levels.clone();
+        Callback::from(move |e: MouseEvent| {
+            e.stop_propagation();
+            log::info!("[Button click] Rename {}", name);
+            let renamed = web_sys::window()
+                .and_then(|window| {
+                    window
+                        .prompt_with_message_and_default("Name of the level", &name)
+                        .ok()
+                })
+                .flatten();
+            if let Some(renamed) = renamed {
+                if let Err(e) = rename_level(&name, renamed.trim()) {
+                    log::warn!("Renaming the level failed: {e}");
+                }
+                saved_levels.set(list_levels());
+            }
+        })
+    };
+
+    let delete_onclick = |name: String| {
+        let saved_levels = saved_levels.clone();
+        Callback::from(move |e: MouseEvent| {
+            e.stop_propagation();
+            log::info!("[Button click] Delete {}", name);
+            delete_level(&name);
+            saved_levels.set(list_levels());
+        })
+    };
+
     fn to_level_action(
         level_grid: Grid<Tile<Square>>,
+        rule: Option<WinRule>,
         screen: UseStateHandle<Screen>,
     ) -> Callback<MouseEvent> {
         Callback::from(move |_| {
-            change_screen(screen.clone(), Screen::Level(level_grid.clone()));
+            change_screen(screen.clone(), Screen::Level(level_grid.clone(), rule));
         })
     }
 
     html! {
         <>
-            if saved_level != Grid::EMPTY {
+            <ContinueCard screen={props.screen.clone()}/>
+            if !saved_levels.is_empty() {
                 <div id="saved-level-container">
                     <div id="saved-level">
-                        <div
-                            class="level-container"
-                            onclick={to_level_action(
-                                saved_level.clone(),
-                                props.screen.clone())}>
-                            <StatelessLevelComponent level_grid={saved_level.clone()} />
-                            <div class="level-title">{"Saved"}</div>
-                        </div>
+                        {
+                            saved_levels.iter().map(|saved| {
+                                html!{
+                                    <div
+                                        class="level-container"
+                                        onclick={to_level_action(
+                                            saved.grid.clone(),
+                                            saved.rule,
+                                            props.screen.clone())}>
+                                        <Thumbnail level_grid={saved.grid.clone()} />
+                                        <div class="level-title">{&saved.name}</div>
+                                        <div class="saved-level-actions">
+                                            <button onclick={rename_onclick(saved.name.clone())}>
+                                                {"rename"}
+                                            </button>
+                                            <button onclick={delete_onclick(saved.name.clone())}>
+                                                {"delete"}
+                                            </button>
+                                        </div>
+                                    </div>
+                                }
+                            }).collect::<Html>()
+                        }
                     </div>
                 </div>
             }
+            <div id="chapters">
+                {
+                    chapter_levels.iter().enumerate().map(|(chapter_index, levels)| {
+                        let chapter = CHAPTERS[chapter_index];
+                        let unlocked = campaign.is_unlocked(chapter_index);
+                        html!{
+                            <div class={classes!("chapter", (!unlocked).then(|| "locked"))}>
+                                <div class="chapter-title">
+                                    {format!(
+                                        "{} - {}/{}",
+                                        chapter.name,
+                                        campaign.finished(chapter_index),
+                                        chapter.levels)}
+                                </div>
+                                if !unlocked {
+                                    <div class="chapter-lock">
+                                        {format!(
+                                            "Finish {} levels of {} to unlock",
+                                            chapter.unlock,
+                                            CHAPTERS[chapter_index - 1].name)}
+                                    </div>
+                                }
+                                <div class="chapter-levels">
+                                    {
+                                        levels.iter().enumerate().map(|(level_index, level_grid)| {
+                                            let completed = campaign
+                                                .completion(chapter_index, level_index)
+                                                .is_some();
+                                            html!{
+                                                <div
+                                                    class={classes!(
+                                                        "level-container",
+                                                        completed.then(|| "completed"))}
+                                                    onclick={unlocked.then(|| to_level_action(
+                                                        level_grid.clone(),
+                                                        None,
+                                                        props.screen.clone()))}>
+                                                    <Thumbnail level_grid={level_grid.clone()} />
+                                                    <div class="level-title">
+                                                        {format!(
+                                                            "#{}{}",
+                                                            level_index + 1,
+                                                            if completed { " ✓" } else { "" })}
+                                                    </div>
+                                                </div>
+                                            }
+                                        }).collect::<Html>()
+                                    }
+                                </div>
+                            </div>
+                        }
+                    }).collect::<Html>()
+                }
+            </div>
+            <div class="chapter-title">{"Free play"}</div>
             <div id="container">
                 <div id="preview-container">
                     {
-                        (0..*level_count).into_iter().map( | level_index | {
-                            let level_grid = reducer.extracted_levels[level_index].clone();
+                        reducer.extracted_levels.iter().enumerate().map( | (level_index, level_grid) | {
                             html!{
                                 <div
                                     class="level-container"
                                     onclick={to_level_action(
                                         level_grid.clone(),
+                                        None,
                                         props.screen.clone())}>
-                                    <StatelessLevelComponent level_grid={level_grid.clone()} />
+                                    <Thumbnail level_grid={level_grid.clone()} />
                                     <div class="level-title">
                                         {format!("#{}", level_index + 1)}
                                     </div>
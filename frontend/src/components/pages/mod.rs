@@ -1,8 +1,15 @@
 pub mod page_router;
+pub mod settings_page;
 pub mod start_page;
 pub mod text_page;
 
+pub mod bench_page;
 pub mod board_page;
+#[cfg(feature = "sat")]
+pub mod cnf_page;
+pub mod descent_page;
 pub mod editor_page;
 pub mod level_preview;
+pub mod polygon_page;
+pub mod practice_page;
 pub mod visualizer_page;
@@ -2,17 +2,40 @@ use wasm_bindgen::prelude::Closure;
 use wasm_bindgen::JsCast;
 use yew::prelude::*;
 
+use crate::components::pages::bench_page::BenchPage;
 use crate::components::pages::board_page::BoardPage;
+#[cfg(feature = "sat")]
+use crate::components::pages::cnf_page::CnfPage;
+use crate::components::pages::descent_page::DescentPage;
 use crate::components::pages::editor_page::EditorPage;
 use crate::components::pages::level_preview::LevelPreviewPage;
+use crate::components::pages::polygon_page::PolygonPage;
+use crate::components::pages::practice_page::PracticePage;
+use crate::components::pages::settings_page::SettingsPage;
 use crate::components::pages::start_page::StartPage;
 use crate::components::pages::text_page::TextPage;
 use crate::components::pages::visualizer_page::VisualizerPage;
+use crate::components::reducers::board_events::{handle_board_event, BoardEventChannel};
+use crate::components::utils::celebration::{Celebration, CelebrationState};
 
-use crate::helper::local_storage::{change_screen, retrieve_screen};
+use crate::helper::local_storage::{change_screen, retrieve_screen, save_screen};
+use crate::helper::platform::PlatformHandle;
 use crate::helper::screen::Screen;
+use crate::helper::share::{draft_from_link, level_from_link};
 
-use game::model::coordinate::Coordinate;
+use game::prelude::Coordinate;
+
+/// screen the app opens with: the board page for links to a shared level, the editor for links to a shared draft,
+/// otherwise the saved screen
+fn initial_screen(draft: Option<&String>) -> Screen {
+    let screen = match (level_from_link(), draft) {
+        (Some(level), _) => Screen::Level(level, None),
+        (None, Some(_)) => Screen::Editor,
+        (None, None) => return retrieve_screen(),
+    };
+    save_screen(&screen);
+    screen
+}
 
 /// This page is used as a router which directs to the correct page depending on
 /// which screen is required
@@ -25,7 +48,28 @@ pub fn page_router() -> Html {
     let bottom_message_timeout_id = use_state(|| -2_i32);
 
     let dimension = use_state(|| Coordinate::new(5_usize, 5_usize));
-    let screen = use_state(retrieve_screen);
+    // shared draft of the link the app was opened with, joined by the editor
+    let draft = use_state(draft_from_link);
+    let screen = {
+        let draft = (*draft).clone();
+        use_state(move || initial_screen(draft.as_ref()))
+    };
+    let celebration = use_state_eq(CelebrationState::default);
+    let board_events = {
+        let celebration = celebration.clone();
+        use_state(move || {
+            BoardEventChannel::from(move |event| {
+                if let Some(streak) = handle_board_event(event) {
+                    // the handle captured here is never rerendered, so its state is stale
+                    celebration.set(CelebrationState {
+                        streak,
+                        serial: js_sys::Date::now() as u64,
+                    });
+                }
+            })
+        })
+    };
+    let platform = use_state(PlatformHandle::default);
 
     let to_title: Callback<MouseEvent> = {
         let screen = screen.clone();
@@ -123,7 +167,8 @@ pub fn page_router() -> Html {
     }
 
     html! {
-        <>
+        <ContextProvider<PlatformHandle> context={(*platform).clone()}>
+        <ContextProvider<BoardEventChannel> context={(*board_events).clone()}>
             <div
                 id="title"
                 onclick={to_title}>
@@ -132,6 +177,8 @@ pub fn page_router() -> Html {
             <div id="head-message" hidden=true>
                 {(*head_message).clone()}
             </div>
+            // filled by the platform, read out by screen readers
+            <div id="announcement" class="visually-hidden" aria-live="polite"></div>
                 {
                     match &*screen {
                         Screen::Title => {
@@ -152,13 +199,15 @@ pub fn page_router() -> Html {
                             html! {
                                 <EditorPage
                                     screen={screen.clone()}
+                                    draft={(*draft).clone()}
                                     head_message={head_message}/>
                             }
                         },
-                        Screen::Level(user_grid) => {
+                        Screen::Level(user_grid, rule) => {
                             html! {
                                 <BoardPage
                                     level_grid={user_grid.clone()}
+                                    win_rule={*rule}
                                     screen={screen.clone()}
                                     head_message={head_message}
                                     cnf={bottom_message.clone()}
@@ -172,6 +221,60 @@ pub fn page_router() -> Html {
                                     screen={screen.clone()}/>
                             }
                         },
+                        Screen::Polygon(geometry) => {
+                            html!{
+                                <PolygonPage
+                                    screen={screen.clone()}
+                                    geometry={*geometry}/>
+                            }
+                        },
+                        Screen::Practice => {
+                            html!{
+                                <PracticePage
+                                    screen={screen.clone()}
+                                    head_message={head_message}/>
+                            }
+                        },
+                        Screen::Descent => {
+                            html!{
+                                <DescentPage
+                                    screen={screen.clone()}
+                                    head_message={head_message}/>
+                            }
+                        },
+                        #[cfg(feature = "sat")]
+                        Screen::Cnf(level_grid) => {
+                            html!{
+                                <CnfPage
+                                    level_grid={level_grid.clone()}
+                                    screen={screen.clone()}/>
+                            }
+                        },
+                        // without the SAT tools the encoding is unavailable, so fall back to the level
+                        #[cfg(not(feature = "sat"))]
+                        Screen::Cnf(user_grid) => {
+                            html! {
+                                <BoardPage
+                                    level_grid={user_grid.clone()}
+                                    screen={screen.clone()}
+                                    head_message={head_message}
+                                    cnf={bottom_message.clone()}
+                                    literals={use_state_eq(|| "".to_string())}/>
+                            }
+                        },
+                        Screen::Bench => {
+                            html!{
+                                <BenchPage
+                                    screen={screen.clone()}/>
+                            }
+                        },
+                        Screen::Settings => {
+                            html!{
+                                <SettingsPage
+                                    screen={screen.clone()}
+                                    head_message={head_message}/>
+                            }
+                        },
                         Screen::Help => {
                             let content = html!{
                                 <>
@@ -225,6 +328,8 @@ pub fn page_router() -> Html {
                 <div id="bottom-message" hidden=false>
                 {(*bottom_message).clone()}
             </div>
-        </>
+            <Celebration celebration={*celebration}/>
+        </ContextProvider<BoardEventChannel>>
+        </ContextProvider<PlatformHandle>>
     }
 }
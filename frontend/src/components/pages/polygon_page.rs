@@ -0,0 +1,84 @@
+use yew::prelude::*;
+use yew::{html, Callback};
+
+use game::prelude::{Coordinate, Geometry, Grid};
+
+use crate::components::board::polygon::{PolygonLevel, PolygonLevelComponent};
+use crate::helper::local_storage::change_screen;
+use crate::helper::platform::use_platform;
+use crate::helper::screen::Screen;
+
+/// rows and columns of hexagonal and triangular levels
+const POLYGON_SIZE: usize = 4;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct PolygonPageProps {
+    pub screen: UseStateHandle<Screen>,
+    pub geometry: Geometry,
+}
+
+/// this page plays levels of hexagonal or triangular tiles
+///
+/// functionality
+/// - click and turn tiles
+/// - load a new level of the geometry
+#[function_component(PolygonPage)]
+pub fn polygon_page(props: &PolygonPageProps) -> Html {
+    let platform = use_platform();
+    let seed = use_state_eq(|| platform.random(10000));
+    let level = use_state_eq(|| PolygonLevel::Hex(Grid::EMPTY));
+    let overlay_message = use_state_eq(|| String::from(""));
+
+    {
+        let level = level.clone();
+        let overlay_message = overlay_message.clone();
+        use_effect_with_deps(
+            move |&(geometry, seed)| {
+                let dimension = Coordinate::new(POLYGON_SIZE, POLYGON_SIZE);
+                match PolygonLevel::generate(geometry, dimension, seed) {
+                    Ok(generated) => level.set(generated),
+                    Err(error) => {
+                        log::warn!("Generating a {geometry} level failed: {error}");
+                        overlay_message.set(String::from("No level could be generated"));
+                    }
+                }
+                || {}
+            },
+            (props.geometry, *seed),
+        );
+    }
+
+    let new_onclick: Callback<MouseEvent> = {
+        let seed = seed.clone();
+        Callback::from(move |_| {
+            log::info!("[Button click] New polygon level.");
+            seed.set(platform.random(10000));
+        })
+    };
+
+    let to_title: Callback<MouseEvent> = {
+        let screen = props.screen.clone();
+        Callback::from(move |_| {
+            change_screen(screen.clone(), Screen::Title);
+        })
+    };
+
+    html! {
+        <div class="container viz-page">
+            <div class="game-board">
+                <PolygonLevelComponent
+                    level={level.clone()}
+                    overlay_message={overlay_message.clone()}/>
+                <div class="level-title">{format!("{} #{}", props.geometry, *seed)}</div>
+            </div>
+            <div class="controller">
+                <button onclick={new_onclick}>
+                    {"-new level-"}
+                </button>
+                <button onclick={to_title}>
+                    {"-home-"}
+                </button>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,116 @@
+use yew::prelude::*;
+use yew::{html, Callback};
+
+use game::generator::practice::PracticeLevels;
+use game::prelude::{Coordinate, EnumSet, Grid, Square, Tile};
+
+use crate::components::board::level::LevelComponent;
+use crate::components::reducers::board_events::use_board_events;
+use crate::components::reducers::board_reducer::{BoardAction, Level};
+use crate::components::utils::tile_selector::TileSelector;
+use crate::helper::local_storage::change_screen;
+use crate::helper::platform::use_platform;
+use crate::helper::screen::Screen;
+
+/// rows and columns of practice levels, small enough to solve a lot of them in a row
+const PRACTICE_SIZE: usize = 4;
+
+/// fixed seed, so every player practices on the same sequence of levels
+const PRACTICE_SEED: u64 = 0;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct PracticePageProps {
+    pub screen: UseStateHandle<Screen>,
+    pub head_message: UseStateHandle<String>,
+}
+
+/// this page drills the recognition of patterns with endless small levels
+///
+/// functionality
+/// - choose the tile shapes the levels are built from
+/// - click and turn tiles
+/// - load the next level of the sequence
+#[function_component(PracticePage)]
+pub fn practice_page(props: &PracticePageProps) -> Html {
+    let shapes: UseStateHandle<EnumSet<Tile<Square>>> = use_state_eq(|| EnumSet::FULL);
+    let index = use_state_eq(|| 0_usize);
+    let overlay_message = use_state_eq(|| String::from(""));
+    let board = use_reducer_eq(Level::set_grid(Grid::EMPTY, use_platform()));
+    use_board_events(&board);
+
+    // restart the sequence whenever the shapes change
+    {
+        let index = index.clone();
+        use_effect_with_deps(
+            move |_| {
+                index.set(0);
+                || {}
+            },
+            *shapes,
+        );
+    }
+
+    {
+        let board = board.clone();
+        let overlay_message = overlay_message.clone();
+        use_effect_with_deps(
+            move |(shapes, index)| {
+                let dimension = Coordinate::new(PRACTICE_SIZE, PRACTICE_SIZE);
+                match PracticeLevels::new(dimension, *shapes, PRACTICE_SEED).nth(*index) {
+                    Some(level) => {
+                        overlay_message.set(String::from(""));
+                        board.dispatch(BoardAction::ReplaceGrid(level));
+                    }
+                    None => {
+                        overlay_message.set(String::from("No level fits these shapes"));
+                        board.dispatch(BoardAction::ReplaceGrid(Grid::EMPTY));
+                    }
+                }
+                || {}
+            },
+            (*shapes, *index),
+        );
+    }
+
+    let next_onclick: Callback<MouseEvent> = {
+        let index = index.clone();
+        Callback::from(move |_| {
+            log::info!("[Button click] Next practice level.");
+            index.set(*index + 1);
+        })
+    };
+
+    let to_title: Callback<MouseEvent> = {
+        let screen = props.screen.clone();
+        Callback::from(move |_| {
+            change_screen(screen.clone(), Screen::Title);
+        })
+    };
+
+    html! {
+        <div class="container viz-page">
+            <div class="controller">
+                <div class="selector-controller">
+                    <TileSelector tile_set={shapes.clone()} />
+                </div>
+            </div>
+            <div class="game-board">
+                <LevelComponent
+                    board={board.clone()}
+                    can_turn=true
+                    can_change=false
+                    head_message={props.head_message.clone()}
+                    overlay_message={overlay_message.clone()}/>
+                <div class="level-title">{format!("practice #{}", *index + 1)}</div>
+            </div>
+            <div class="controller">
+                <button onclick={next_onclick}>
+                    {"-next-"}
+                </button>
+                <button onclick={to_title}>
+                    {"-home-"}
+                </button>
+            </div>
+        </div>
+    }
+}
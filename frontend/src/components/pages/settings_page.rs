@@ -0,0 +1,144 @@
+use yew::prelude::*;
+use yew::{html, Callback};
+
+use game::prelude::WinRule;
+
+use crate::helper::local_storage::{
+    change_screen, retrieve_energy_flow, retrieve_offline_mode, retrieve_win_rule,
+    save_energy_flow, save_offline_mode, save_win_rule,
+};
+use crate::helper::screen::Screen;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct SettingsPageProps {
+    pub screen: UseStateHandle<Screen>,
+    pub head_message: UseStateHandle<String>,
+}
+
+/// This page contains the user preferences
+///
+/// - offline mode: disables all calls to the backend, the game remains fully playable
+/// - energy flow: a glow travels along the connected tiles of the board
+/// - win rule: matching connections, a single loop or all sources connected decide when a level is solved
+#[function_component(SettingsPage)]
+pub fn settings_page(props: &SettingsPageProps) -> Html {
+    let offline_mode = use_state(retrieve_offline_mode);
+
+    let toggle_offline_mode: Callback<MouseEvent> = {
+        let offline_mode = offline_mode.clone();
+        let head_message = props.head_message.clone();
+        Callback::from(move |_| {
+            let offline = !*offline_mode;
+            log::info!("[Button click] offline mode: {}", offline);
+            save_offline_mode(offline);
+            offline_mode.set(offline);
+            head_message.set(String::from(if offline {
+                "Offline mode enabled"
+            } else {
+                "Offline mode disabled"
+            }));
+        })
+    };
+
+    let energy_flow = use_state(retrieve_energy_flow);
+
+    let toggle_energy_flow: Callback<MouseEvent> = {
+        let energy_flow = energy_flow.clone();
+        Callback::from(move |_| {
+            let enabled = !*energy_flow;
+            log::info!("[Button click] energy flow: {}", enabled);
+            save_energy_flow(enabled);
+            energy_flow.set(enabled);
+        })
+    };
+
+    let win_rule = use_state(retrieve_win_rule);
+
+    let toggle_win_rule: Callback<MouseEvent> = {
+        let win_rule = win_rule.clone();
+        Callback::from(move |_| {
+            let position = WinRule::ALL.iter().position(|&rule| rule == *win_rule);
+            let rule = WinRule::ALL[position.map_or(0, |p| (p + 1) % WinRule::ALL.len())];
+            log::info!("[Button click] win rule: {}", rule);
+            save_win_rule(rule);
+            win_rule.set(rule);
+        })
+    };
+
+    let to_bench: Callback<MouseEvent> = {
+        let screen = props.screen.clone();
+        Callback::from(move |_| {
+            change_screen(screen.clone(), Screen::Bench);
+        })
+    };
+
+    // admin tools are only offered in debug builds
+    let bench_button = if cfg!(debug_assertions) {
+        html! {
+            <button onclick={to_bench}>
+                {"-benchmarks-"}
+            </button>
+        }
+    } else {
+        html! {}
+    };
+
+    let to_title: Callback<MouseEvent> = {
+        let screen = props.screen.clone();
+        Callback::from(move |_| {
+            change_screen(screen.clone(), Screen::Title);
+        })
+    };
+
+    html! {
+        <div class="container">
+            <div id="settings-page" class="page-container">
+                <div class="page-title">{"#settings"}</div>
+                <div class="page-content">
+                    <p>
+                        {"Offline mode disables all requests to the server. Levels are
+                        generated and solved in the browser, so the game stays fully
+                        playable without a network."}
+                    </p>
+                    <p>
+                        {"Energy flow lets a glow travel along the connected tiles of the
+                        board. Shift-click a tile to let the energy flow from there."}
+                    </p>
+                    <p>
+                        {"The win rule decides when a level counts as solved. Single loop
+                        requires all tiles to form one connected network instead of several
+                        disjoint loops, all sources connected requires all dead ends to be
+                        linked with each other."}
+                    </p>
+                </div>
+            </div>
+            <div class="controller">
+                <button onclick={toggle_offline_mode}>
+                    {
+                        if *offline_mode {
+                            "-offline mode: on-"
+                        } else {
+                            "-offline mode: off-"
+                        }
+                    }
+                </button>
+                <button onclick={toggle_energy_flow}>
+                    {
+                        if *energy_flow {
+                            "-energy flow: on-"
+                        } else {
+                            "-energy flow: off-"
+                        }
+                    }
+                </button>
+                <button onclick={toggle_win_rule}>
+                    {format!("-win: {}-", *win_rule)}
+                </button>
+                {bench_button}
+                <button onclick={to_title}>
+                    {"-back-"}
+                </button>
+            </div>
+        </div>
+    }
+}
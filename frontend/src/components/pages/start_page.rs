@@ -1,7 +1,11 @@
 use yew::prelude::*;
 use yew::{html, Callback};
 
-use crate::helper::local_storage::change_screen;
+use game::prelude::Geometry;
+
+use crate::components::utils::continue_card::ContinueCard;
+use crate::components::utils::presence_counter::PresenceCounter;
+use crate::helper::local_storage::{change_screen, retrieve_stats};
 use crate::helper::screen::Screen;
 
 #[derive(Properties, PartialEq, Clone)]
@@ -12,9 +16,13 @@ pub struct StartPageProps {
 
 /// This page is the starting or home page that links to
 /// - preview
+/// - practice
+/// - endless descent
+/// - hexagonal and triangular levels
 /// - editor
 /// - WFC visualizer
 /// - help
+/// - settings
 /// - credits
 #[function_component(StartPage)]
 pub fn start_page(props: &StartPageProps) -> html {
@@ -27,6 +35,34 @@ pub fn start_page(props: &StartPageProps) -> html {
         })
     };
 
+    let to_practice: Callback<MouseEvent> = {
+        let screen = screen.clone();
+        Callback::from(move |_| {
+            change_screen(screen.clone(), Screen::Practice);
+        })
+    };
+
+    let to_descent: Callback<MouseEvent> = {
+        let screen = screen.clone();
+        Callback::from(move |_| {
+            change_screen(screen.clone(), Screen::Descent);
+        })
+    };
+
+    let to_hex: Callback<MouseEvent> = {
+        let screen = screen.clone();
+        Callback::from(move |_| {
+            change_screen(screen.clone(), Screen::Polygon(Geometry::Hex));
+        })
+    };
+
+    let to_triangle: Callback<MouseEvent> = {
+        let screen = screen.clone();
+        Callback::from(move |_| {
+            change_screen(screen.clone(), Screen::Polygon(Geometry::Triangle));
+        })
+    };
+
     let to_editor: Callback<MouseEvent> = {
         let screen = screen.clone();
         Callback::from(move |_| {
@@ -48,6 +84,13 @@ pub fn start_page(props: &StartPageProps) -> html {
         })
     };
 
+    let to_settings: Callback<MouseEvent> = {
+        let screen = screen.clone();
+        Callback::from(move |_| {
+            change_screen(screen.clone(), Screen::Settings);
+        })
+    };
+
     let to_credit: Callback<MouseEvent> = {
         let screen = screen;
         Callback::from(move |_| {
@@ -62,12 +105,37 @@ pub fn start_page(props: &StartPageProps) -> html {
         })
     };
 
+    let stats = retrieve_stats();
+    let streak = if stats.best_streak > 0 {
+        html! {
+            <p class="streak">
+                {format!("streak: {} - best: {}", stats.streak, stats.best_streak)}
+            </p>
+        }
+    } else {
+        html! {}
+    };
+
     html! {
         <div class="container">
+            {streak}
+            <ContinueCard screen={props.screen.clone()}/>
             <div id="start-menu">
                 <button onclick={to_preview}>
                     {"-play-"}
                 </button>
+                <button onclick={to_practice}>
+                    {"-practice-"}
+                </button>
+                <button onclick={to_descent}>
+                    {"-descent-"}
+                </button>
+                <button onclick={to_hex}>
+                    {"-hex-"}
+                </button>
+                <button onclick={to_triangle}>
+                    {"-triangles-"}
+                </button>
                 <button onclick={to_editor}>
                     {"-editor-"}
                 </button>
@@ -77,6 +145,9 @@ pub fn start_page(props: &StartPageProps) -> html {
                 <button onclick={to_help}>
                     {"-help-"}
                 </button>
+                <button onclick={to_settings}>
+                    {"-settings-"}
+                </button>
                 <button onclick={to_credit}>
                     {"-credits-"}
                 </button>
@@ -84,6 +155,7 @@ pub fn start_page(props: &StartPageProps) -> html {
                     {"-exit-"}
                 </button>
             </div>
+            <PresenceCounter/>
         </div>
     }
 }
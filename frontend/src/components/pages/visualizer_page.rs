@@ -2,45 +2,54 @@ use wasm_bindgen::{prelude::*, JsCast};
 use yew::prelude::*;
 use yew::{html, Callback};
 
-use game::core::{enummap::EnumMap, enumset::EnumSet};
-use game::generator::wfc::WfcGenerator;
-use game::model::tile::{Square, Tile};
-use game::solver::propagationsolver::SentinelGrid;
+use game::dto::{LiveRunRequestDto, VERSION};
+use game::generator::wfc::WfcState;
+use game::prelude::{EnumSet, Grid, Square, Tile, WfcGenerator};
+use game::solver::propagationsolver::Superposition;
 
 use crate::components::board::level::StatelessLevelComponent;
 use crate::components::utils::{slider::SliderComponent, tile_selector::TileSelector};
+use crate::helper::api::ApiClient;
 use crate::helper::local_storage::change_screen;
+use crate::helper::platform::use_platform;
 use crate::helper::screen::Screen;
 
-type GameGrid = SentinelGrid<EnumSet<Tile<Square>>>;
-type Weights = EnumMap<Tile<Square>, usize>;
-
 const LOG_PREFIX: &str = "#viz";
 const DEFAULT_WIDTH: isize = 10;
 const DEFAULT_HEIGHT: isize = 10;
 const DEFAULT_SPEED: isize = 80;
 
+/// rows and columns of runs computed by the backend, larger than the browser generates smoothly
+const DEFAULT_SERVER_SIZE: isize = 40;
+const MAX_SERVER_SIZE: isize = 100;
+
 const PASS_LIMIT: usize = 40000;
 const PROP_LIMIT: usize = 1000;
 
-/// get new board with the given generator,
-/// and return the grid and weight after 1 step
-fn get_new_board(wfc_generator: &WfcGenerator) -> (GameGrid, Weights) {
-    let (sentinel_grid, weights) = wfc_generator.init_board();
-    wfc_generator.iteration_step(sentinel_grid, weights)
+/// interval between rendered frames while playing at high speeds, 60fps
+const FRAME_MILLIS: i32 = 16;
+
+/// share of a frame spent generating at full speed, the rest is left for rendering
+const FRAME_BUDGET_MILLIS: f64 = 10.0;
+
+/// starts a generation with the given generator and returns its state after 1 step
+fn get_new_board(wfc_generator: &WfcGenerator) -> WfcState {
+    wfc_generator.step_budgeted(wfc_generator.start(), 0.0, js_sys::Date::now)
 }
 
-/// gets the next step of the generation
-fn get_next_step(
-    wfc_generator: WfcGenerator,
-    sentinel_grid: GameGrid,
-    weights: Weights,
-) -> (GameGrid, Weights) {
-    let (mut new_grid, mut new_weights) = (sentinel_grid, weights);
-    if WfcGenerator::is_all_collapsed(&new_grid) {
-        (new_grid, new_weights) = wfc_generator.init_board();
-    }
-    wfc_generator.iteration_step(new_grid, new_weights)
+/// advances the generation for at most the budget, a finished generation starts over
+fn get_next_steps(wfc_generator: &WfcGenerator, state: WfcState, budget: f64) -> WfcState {
+    let state = if state.is_finished() {
+        wfc_generator.start()
+    } else {
+        state
+    };
+    wfc_generator.step_budgeted(state, budget, js_sys::Date::now)
+}
+
+/// level of the snapshot, uncollapsed tiles are empty
+fn collapsed(snapshot: &Grid<Superposition<Square>>) -> Grid<Tile<Square>> {
+    snapshot.map(|set| set.unwrap_if_singleton().unwrap_or(Tile::NO_CONNECTIONS))
 }
 
 #[derive(Properties, PartialEq, Clone)]
@@ -62,19 +71,20 @@ pub fn wfc_board_component(props: &VisualizerPageProps) -> Html {
         use_state(|| DEFAULT_HEIGHT),
         use_state(|| DEFAULT_SPEED),
     );
+    let server_size = use_state(|| DEFAULT_SERVER_SIZE);
     let is_playing = use_state(|| false);
     let interval_id = use_state(|| 0);
+    let platform = use_platform();
 
     // Initialize the generator with all tiles.
     let wfc_generator =
         WfcGenerator::with_all_tiles(DEFAULT_WIDTH as usize, DEFAULT_HEIGHT as usize);
-    let (sentinel_grid, weights) = get_new_board(&wfc_generator);
+    let wfc_state = get_new_board(&wfc_generator);
 
     let available_tiles: UseStateHandle<EnumSet<Tile<Square>>> = use_state_eq(|| EnumSet::FULL);
     let wfc_generator = use_state_eq(|| wfc_generator);
-    let sentinel_grid = use_state_eq(|| sentinel_grid);
-    let weights = use_state_eq(|| weights);
-    let level_grid = use_state_eq(|| WfcGenerator::extract_grid(&sentinel_grid));
+    let wfc_state = use_state_eq(|| wfc_state);
+    let level_grid = use_state_eq(|| wfc_state.grid());
 
     // Get all config, including size, tile selection and updates the generator.
     // And directly initiate a new level generation.
@@ -84,8 +94,7 @@ pub fn wfc_board_component(props: &VisualizerPageProps) -> Html {
         let available_tiles = available_tiles.clone();
         let wfc_generator = wfc_generator.clone();
         let level_grid = level_grid.clone();
-        let sentinel_grid = sentinel_grid.clone();
-        let weights = weights.clone();
+        let wfc_state = wfc_state.clone();
         let (width_value, height_value) = (width_value.clone(), height_value.clone());
         let (width, height) = (*width_value as usize, *height_value as usize);
         Callback::from(move |_| {
@@ -96,18 +105,17 @@ pub fn wfc_board_component(props: &VisualizerPageProps) -> Html {
             );
             let new_generator =
                 WfcGenerator::new(width, height, *available_tiles, PASS_LIMIT, PROP_LIMIT);
-            let (new_grid, new_weights) = get_new_board(&new_generator);
+            let new_state = get_new_board(&new_generator);
 
-            if new_grid.to_string().trim() == "" {
+            if new_state.board().to_string().trim() == "" {
                 overlay_message.set(String::from("combination invaild"));
             } else {
                 overlay_message.set(String::from(""));
             }
 
-            level_grid.set(WfcGenerator::extract_grid(&new_grid));
+            level_grid.set(new_state.grid());
             wfc_generator.set(new_generator);
-            sentinel_grid.set(new_grid);
-            weights.set(new_weights);
+            wfc_state.set(new_state);
         })
     };
 
@@ -115,18 +123,13 @@ pub fn wfc_board_component(props: &VisualizerPageProps) -> Html {
     let next_onclick: Callback<MouseEvent> = {
         let wfc_generator = wfc_generator.clone();
         let level_grid = level_grid.clone();
-        let sentinel_grid = sentinel_grid.clone();
-        let weights = weights.clone();
+        let wfc_state = wfc_state.clone();
         Callback::from(move |_| {
             log::debug!("{LOG_PREFIX} [Button click] next");
-            let (new_grid, new_weights) = get_next_step(
-                (*wfc_generator).clone(),
-                (*sentinel_grid).clone(),
-                (*weights).clone(),
-            );
-            level_grid.set(WfcGenerator::extract_grid(&new_grid));
-            sentinel_grid.set(new_grid);
-            weights.set(new_weights);
+            // a budget of 0 performs a single step
+            let new_state = get_next_steps(&wfc_generator, (*wfc_state).clone(), 0.0);
+            level_grid.set(new_state.grid());
+            wfc_state.set(new_state);
         })
     };
 
@@ -135,8 +138,7 @@ pub fn wfc_board_component(props: &VisualizerPageProps) -> Html {
         let interval_id = interval_id;
         let wfc_generator = wfc_generator;
         let level_grid = level_grid.clone();
-        let sentinel_grid = sentinel_grid;
-        let weights = weights;
+        let wfc_state = wfc_state;
 
         let is_playing = is_playing.clone();
         let speed_value = speed_value.clone();
@@ -151,32 +153,23 @@ pub fn wfc_board_component(props: &VisualizerPageProps) -> Html {
                 log::debug!("{LOG_PREFIX} [Button click] play: interval started");
                 is_playing.set(true);
 
-                let (new_grid, new_weights) = get_next_step(
-                    (*wfc_generator).clone(),
-                    (*sentinel_grid).clone(),
-                    (*weights).clone(),
-                );
+                // slow speeds step once per interval, high speeds render at 60fps
+                // and generate as many steps per frame as fit into a budget growing with the speed
+                let speed = (3 * (100 - *speed_value as i32)).max(FRAME_MILLIS);
+                let budget = FRAME_BUDGET_MILLIS * *speed_value as f64 / 100.0;
 
                 {
                     let wfc_generator = wfc_generator.clone();
                     let level_grid = level_grid.clone();
-                    let sentinel_grid = sentinel_grid.clone();
-                    let weights = weights.clone();
-                    let mut new_grid = new_grid;
-                    let mut new_weights = new_weights;
+                    let wfc_state = wfc_state.clone();
+                    let mut new_state = (*wfc_state).clone();
 
                     let iteration_closure = Closure::<dyn FnMut()>::new(move || {
-                        (new_grid, new_weights) = get_next_step(
-                            (*wfc_generator).clone(),
-                            new_grid.clone(),
-                            new_weights.clone(),
-                        );
-                        level_grid.set(WfcGenerator::extract_grid(&new_grid));
-                        sentinel_grid.set(new_grid.clone());
-                        weights.set(new_weights.clone());
+                        new_state = get_next_steps(&wfc_generator, new_state.clone(), budget);
+                        level_grid.set(new_state.grid());
+                        wfc_state.set(new_state.clone());
                     });
 
-                    let speed = 3 * (100 - *speed_value as i32);
                     let window = web_sys::window().unwrap();
                     let id = window
                         .set_interval_with_callback_and_timeout_and_arguments_0(
@@ -193,6 +186,37 @@ pub fn wfc_board_component(props: &VisualizerPageProps) -> Html {
         })
     };
 
+    // Stream a generation of a large board computed by the backend.
+    let server_onclick: Callback<MouseEvent> = {
+        let overlay_message = overlay_message.clone();
+        let level_grid = level_grid.clone();
+        let server_size = server_size.clone();
+        Callback::from(move |_| {
+            let size = *server_size as usize;
+            log::debug!(
+                "{LOG_PREFIX} [Button click] server: live run with dimension ({size}, {size})"
+            );
+            let request = LiveRunRequestDto {
+                version: VERSION,
+                level: None,
+                rows: size,
+                columns: size,
+                seed: platform.random(u64::MAX),
+            };
+            let overlay_message = overlay_message.clone();
+            let level_grid = level_grid.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                overlay_message.set(String::from(""));
+                let run = ApiClient::default()
+                    .live_run(&request, |snapshot| level_grid.set(collapsed(snapshot)))
+                    .await;
+                if let Err(e) = run {
+                    overlay_message.set(e.to_string());
+                }
+            });
+        })
+    };
+
     // Go back to home page.
     let to_title: Callback<MouseEvent> = {
         let screen = props.screen.clone();
@@ -236,6 +260,16 @@ pub fn wfc_board_component(props: &VisualizerPageProps) -> Html {
                     </button>
                 </div>
 
+                <div class="flex-col">
+                    <SliderComponent id="slider-server" label="#server" value={server_size.clone()} max={MAX_SERVER_SIZE} min=10 />
+                    <button
+                        onclick={server_onclick.clone()}
+                        disabled={*is_playing}
+                    >
+                        {"-server run-"}
+                    </button>
+                </div>
+
                 <div class="flex-col">
                     <button
                         onclick={to_title.clone()}
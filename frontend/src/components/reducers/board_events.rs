@@ -0,0 +1,67 @@
+use yew::prelude::*;
+
+use game::prelude::{Coordinate, Grid, Square, Tile};
+
+use crate::components::reducers::board_reducer::Level;
+use crate::helper::local_storage::{retrieve_stats, save_stats};
+
+/// notable transitions of the board reducer
+///
+/// features like stats, achievements, sound and telemetry listen for these
+/// instead of hooking into the reducer internals
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub enum BoardEvent {
+    /// the level has been solved, `assisted` if the solver did the work
+    Solved { level: usize, assisted: bool },
+    /// a hint highlighted the tile at the coordinate
+    HintShown(Coordinate<isize>),
+    /// the player turned the tile at the coordinate
+    Rotated(Coordinate<isize>),
+    /// a new board has been generated
+    Generated { dimensions: Coordinate<usize> },
+}
+
+/// events emitted by the latest transition of the reducer
+///
+/// the serial number distinguishes consecutive transitions emitting equal events
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub struct Emitted {
+    pub serial: u64,
+    pub events: Vec<BoardEvent>,
+}
+
+/// channel for board events, provided as context by the page router
+pub type BoardEventChannel = Callback<BoardEvent>;
+
+/// forwards the events emitted by the board reducer to the [`BoardEventChannel`] in context
+///
+/// every event is delivered exactly once, even if the component rerenders
+pub fn use_board_events(board: &UseReducerHandle<Level<Grid<Tile<Square>>>>) {
+    let channel = use_context::<BoardEventChannel>();
+    let emitted = board.events.clone();
+    use_effect_with_deps(
+        move |_| {
+            if let Some(channel) = channel {
+                for event in emitted.events {
+                    channel.emit(event);
+                }
+            }
+            || {}
+        },
+        board.events.serial,
+    );
+}
+
+/// single entry point for all board event consumers
+///
+/// returns the extended streak, if the event completed a level without help
+pub fn handle_board_event(event: BoardEvent) -> Option<u32> {
+    log::info!("[Board event] {:?}", event);
+
+    let stats = retrieve_stats();
+    let updated = stats.record(event);
+    if updated != stats {
+        save_stats(&updated);
+    }
+    (updated.streak > stats.streak).then(|| updated.streak)
+}
@@ -1,164 +1,644 @@
 #![allow(dead_code)]
 
-use rand::Rng;
+use std::collections::VecDeque;
 use std::rc::Rc;
 use yew::prelude::*;
 
+use crate::components::board::cell::HighlightKind;
+use crate::components::reducers::board_events::{BoardEvent, Emitted};
 use crate::helper::level_randomizer::randomize_level;
-use crate::helper::local_storage::save_level;
-use wasm_bindgen::{prelude::*, JsCast};
-
-use game::generator::{fastgen::generate, wfc::WfcGenerator};
-use game::model::{
-    coordinate::Coordinate,
-    gameboard::GameBoard,
-    grid::Grid,
-    tile::{Square, Tile},
+use crate::helper::platform::PlatformHandle;
+
+use game::campaign::generate_campaign_level;
+use game::generator::fastgen::generate_torus;
+use game::model::history::History;
+use game::model::tile::glyph;
+use game::prelude::{
+    generate, get_hint, rate, rotation_distance, Coordinate, GameBoard, Grid, Hint, HintPolicy,
+    Overlay, Square, Tile, WfcGenerator, WinRule,
 };
-use game::solver::hint::{generate_solving_trace, get_hint};
+use game::solver::hint::{Describe, HintEngine};
+
+/// number of transitions that can be undone
+const HISTORY_SIZE: usize = 100;
 
-use game::core::finite::Finite;
+/// initial delay in milliseconds between the rotations of the solve animation
+pub const SOLVE_STEP_DELAY: u32 = 125;
+
+/// milliseconds a hint highlights its tile
+pub const HINT_HIGHLIGHT_DURATION: f64 = 500.0;
 
 /// reducer facilitates actions for both the board and the editor pages
 ///
 /// playing board actions:
-/// - TurnCell: turns the cell indicated through the coordinate clockwise
+/// - TurnCell: turns the cell indicated through the coordinate clockwise, unless it is locked
 /// - ReplaceGrid: replaces the current grid with a new one
-/// - NextLevel: generates the next level with dimension + 1 and sets it as the current level
-/// - GetHint: generates a hint and highlights the corresponding tile
-/// - SolveLevel: solves the level
+/// - Replay: restarts the current level from the given scrambled start, keeping its solution and the next level
+/// - NextLevel: sets the precomputed or otherwise freshly generated next level as the current level,
+///   of the difficulty the [`Platform`](crate::helper::platform::Platform) adjusts to the performance of the player
+/// - NextLevelPrepared: keeps the next level prepared ahead of time by [`prepare_next_level`], unless the player has
+///   left the level it follows
+/// - GetHint: if [`HintPolicy::DEFAULT`] allows another hint, generates a hint with the [`HintEngine`] of the level, highlights the corresponding tile for [`HINT_HIGHLIGHT_DURATION`] and explains it until the next rotation
+/// - ExpireHighlights: removes the highlights whose time is up, dispatched by the level component
+/// - SolveLevel: queues the rotations solving the level, tile by tile in reading order
+/// - SolveStep: performs the next queued rotation, dispatched by the page every [`Level::step_delay`]
+/// - SetSolveSpeed: changes the delay in milliseconds between queued rotations
+/// - SolvePartial: solves the given fraction of the unsolved tiles, preferring tiles the player has not turned recently
+/// - SolveLevelSat: like SolveLevel, but finds the solution with the embedded SAT solver
+/// - SolveLevelInput: solves the level with a model in DIMACS format of an external SAT solver
+/// - Undo: reverts the latest rotation, shuffle or editor edit
+/// - Redo: repeats the latest undone transition
+///
+/// debugging actions:
+/// - Restore: replaces the state with a previously recorded one without emitting events
+///
+/// notable transitions are announced as [`BoardEvent`]s, see [`Level::events`]
+///
+/// side effects go through the [`Platform`](crate::helper::platform::Platform) of the level
 ///
 /// editor board actions:
 /// - ChangeTileShape:
+/// - SetTile: replaces the tile, e.g. to apply a fix of a conflict
+/// - ToggleLock: locks the tile in its rotation or unlocks it, see [`game::model::lock`]
 /// - ChangeSize:
 /// - GenerateFastGen:
 /// - GenerateWFC:
+/// - GenerateTorus: generates a level whose edges wrap around, see [`Grid::into_torus`]
 /// - ShuffleTileRotations:
 /// - ClearGrid:
 pub enum BoardAction {
     // playing level actions
     TurnCell(Coordinate<isize>),
     ReplaceGrid(Grid<Tile<Square>>),
+    Replay(Grid<Tile<Square>>),
     NextLevel,
+    NextLevelPrepared(Box<Level<Grid<Tile<Square>>>>),
     GetHint,
+    ExpireHighlights,
     SolveLevel,
+    SolveStep,
+    SetSolveSpeed(u32),
+    SolvePartial(f32),
+    #[cfg(feature = "sat")]
+    SolveLevelSat,
+    #[cfg(feature = "sat")]
     SolveLevelInput(String),
+    Undo,
+    Redo,
 
     // Editor actions
     ChangeTileShape(Coordinate<isize>),
+    SetTile(Coordinate<isize>, Tile<Square>),
+    ToggleLock(Coordinate<isize>),
     ChangeSize(Coordinate<usize>),
     GenerateFastGen,
     GenerateWFC,
+    GenerateTorus,
     ShuffleTileRotations,
     ClearGrid,
+
+    // Debugging actions
+    Restore(Box<Level<Grid<Tile<Square>>>>),
+}
+
+/// highlight of a cell until the timestamp in milliseconds since the unix epoch, see [`Platform::now`](crate::helper::platform::Platform::now)
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Highlight {
+    pub kind: HighlightKind,
+    pub until: f64,
 }
 
 // reducer's state
 // is a functor
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+#[derive(Clone, PartialEq, Debug)]
 pub struct Level<A> {
     pub id: usize,
     pub data: A,
+    /// cached solution, only valid as long as the tiles are merely rotated
+    pub solution: Option<A>,
+    /// next level prepared in the background while the player is solving this one
+    pub next: Option<Box<Level<A>>>,
+    /// events emitted by the latest transition
+    pub events: Emitted,
+    /// previous data of revertible transitions
+    pub history: History<A>,
+    /// win rule of the level itself, e.g. chosen in the editor, overrides the rule chosen by the player
+    pub rule: Option<WinRule>,
+    /// rotations of the solve animation not yet performed, any other rotation cancels them
+    pub pending: VecDeque<Coordinate<isize>>,
+    /// delay in milliseconds between the rotations of the solve animation
+    pub step_delay: u32,
+    /// latest hint, explained in a tooltip of its tile until the next rotation
+    pub hint: Option<Hint>,
+    /// times of the hints given on this level, see [`Platform::now`](crate::helper::platform::Platform::now)
+    pub hints_given: Vec<u64>,
+    /// dead ends of the hint search, reused by further hints on the same level
+    pub hint_engine: HintEngine,
+    /// tiles turned by the player, each at most once, most recent last
+    pub turned: VecDeque<Coordinate<isize>>,
+    /// highlighted cells, at most one highlight of each kind per cell
+    pub highlights: Overlay<Vec<Highlight>>,
+    /// tiles fixed in their rotation, e.g. anchors placed in the editor
+    pub locks: Overlay<()>,
+    /// performs the side effects of the transitions
+    pub platform: PlatformHandle,
 }
 
 impl<A> Level<A> {
-    fn new(id: usize, data: A) -> Self {
-        Level { id, data }
+    pub fn new(id: usize, data: A, platform: PlatformHandle) -> Self {
+        Level {
+            id,
+            data,
+            solution: None,
+            next: None,
+            events: Emitted::default(),
+            history: History::new(HISTORY_SIZE),
+            rule: None,
+            pending: VecDeque::new(),
+            step_delay: SOLVE_STEP_DELAY,
+            hint: None,
+            hints_given: vec![],
+            hint_engine: HintEngine::default(),
+            turned: VecDeque::new(),
+            highlights: Overlay::default(),
+            locks: Overlay::default(),
+            platform,
+        }
     }
 }
 
-#[wasm_bindgen]
-pub fn highlight_cells(row: usize, column: usize) {
-    let window = web_sys::window().unwrap();
-    let document = window.document().unwrap();
-    let cell = document
-        .get_element_by_id(&format!("cell-r-{}-c-{}", row, column))
-        .unwrap();
-
-    let class_names = cell.get_attribute("class").unwrap();
+impl<A: Clone> Level<A> {
+    /// replaces the data, but keeps the cached solution and next level
+    ///
+    /// only valid for transitions that rotate tiles without changing their shape,
+    /// cancels the pending rotations of the solve animation and forgets the latest hint
+    fn with_data(&self, data: A) -> Self {
+        Level {
+            id: self.id,
+            data,
+            solution: self.solution.clone(),
+            next: self.next.clone(),
+            events: self.events.clone(),
+            history: self.history.clone(),
+            rule: self.rule,
+            pending: VecDeque::new(),
+            step_delay: self.step_delay,
+            hint: None,
+            hints_given: self.hints_given.clone(),
+            hint_engine: self.hint_engine.clone(),
+            turned: self.turned.clone(),
+            highlights: self.highlights.clone(),
+            locks: self.locks.clone(),
+            platform: self.platform.clone(),
+        }
+    }
 
-    // prevent re-highlighting if already highlighted
-    if class_names.contains("cell-hint-highlight") {
-        return;
+    /// replaces the data and drops everything derived from the previous one, but keeps the win rule and solve speed
+    ///
+    /// the locks are dropped as well, edits of the tiles keep them with [`Level::reset_keeping_locks`]
+    fn reset(&self, data: A) -> Self {
+        Level {
+            rule: self.rule,
+            step_delay: self.step_delay,
+            ..Level::new(self.id, data, self.platform.clone())
+        }
     }
 
-    let highlight_class_names = format!("{} {}", class_names, "cell-hint-highlight");
-    cell.set_class_name(&highlight_class_names);
-    let hl = Closure::<dyn Fn()>::new(move || {
-        cell.set_class_name(&class_names);
-    });
+    /// makes the transition to the level revertible by remembering the current data
+    fn remembering(&self, level: Level<A>) -> Self {
+        let mut history = self.history.clone();
+        history.record(self.data.clone());
+        Level { history, ..level }
+    }
 
-    window
-        .set_timeout_with_callback_and_timeout_and_arguments_0(hl.as_ref().unchecked_ref(), 500)
-        .ok();
-    hl.forget();
+    /// restores data of the history, the cached solution is dropped as editor edits may be reverted
+    fn traveled(&self, history: History<A>, data: A) -> Self {
+        Level {
+            solution: None,
+            history,
+            ..self.with_data(data)
+        }
+    }
 }
 
 impl Reducible for Level<Grid<Tile<Square>>> {
     type Action = BoardAction;
 
     fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let serial = self.events.serial;
+        let (level, events) = self.transition(action);
+        if events.is_empty() {
+            return level;
+        }
+        Level {
+            events: Emitted {
+                serial: serial + 1,
+                events,
+            },
+            ..(*level).clone()
+        }
+        .into()
+    }
+}
+
+impl Level<Grid<Tile<Square>>> {
+    /// rule of the level if it has one, otherwise the rule chosen by the player
+    pub fn win_rule(&self) -> WinRule {
+        self.rule.unwrap_or_else(|| self.platform.win_rule())
+    }
+
+    /// whether the level is solved by its win rule
+    pub fn is_won(&self) -> bool {
+        self.win_rule().is_met_by(&self.data)
+    }
+
+    /// explanation of the latest hint, if it points to the tile at the coordinate
+    pub fn hint_tooltip(&self, index: Coordinate<isize>) -> Option<String> {
+        self.hint
+            .filter(|hint| hint.coordinate == index)
+            .map(|hint| format!("turn to {}: {}", glyph(hint.suggested_tile), hint.reason))
+    }
+
+    /// kinds of the highlights of the cell
+    pub fn highlight_kinds(&self, index: Coordinate<isize>) -> Vec<HighlightKind> {
+        self.highlights
+            .get(index)
+            .map(|highlights| highlights.iter().map(|highlight| highlight.kind).collect())
+            .unwrap_or_default()
+    }
+
+    /// timestamp of the earliest expiring highlight
+    pub fn next_expiry(&self) -> Option<f64> {
+        self.highlights
+            .iter()
+            .flat_map(|(_, highlights)| highlights.iter().map(|highlight| highlight.until))
+            .reduce(f64::min)
+    }
+
+    /// highlights with the cell highlighted for the duration, replacing its highlight of the same kind
+    fn highlighting(
+        &self,
+        index: Coordinate<isize>,
+        kind: HighlightKind,
+        duration: f64,
+    ) -> Overlay<Vec<Highlight>> {
+        let mut highlights = self.highlights.clone();
+        highlights.resize(self.data.dimensions());
+        let mut cell = highlights.remove(index).unwrap_or_default();
+        cell.retain(|highlight| highlight.kind != kind);
+        cell.push(Highlight {
+            kind,
+            until: self.platform.now() + duration,
+        });
+        highlights.insert(index, cell).ok();
+        highlights
+    }
+
+    /// replaces the data like [`Level::reset`], but keeps the locks within the dimensions of the data
+    fn reset_keeping_locks(&self, data: Grid<Tile<Square>>) -> Self {
+        let mut locks = self.locks.clone();
+        locks.resize(data.dimensions());
+        Level {
+            locks,
+            ..self.reset(data)
+        }
+    }
+
+    /// cached solution or the first solution honoring the locks
+    fn find_solution(&self) -> Option<Grid<Tile<Square>>> {
+        self.solution.clone().or_else(|| {
+            self.data
+                .with_locks(&self.locks)
+                .solve()
+                .next()
+                .map(|solution| solution.tiles())
+        })
+    }
+
+    /// performs the action and reports the resulting events
+    fn transition(self: Rc<Self>, action: BoardAction) -> (Rc<Self>, Vec<BoardEvent>) {
         match action {
             BoardAction::TurnCell(index) => {
-                Level::new(self.id, self.data.rotate_clockwise(index).unwrap()).into()
+                let data = match self.data.with_locks(&self.locks).rotate_clockwise(index) {
+                    Ok(cells) => cells.tiles(),
+                    Err(error) => {
+                        log::debug!("Tile {index} is not turned: {error:?}");
+                        return (self, vec![]);
+                    }
+                };
+                self.platform.save(&data);
+                let mut events = vec![BoardEvent::Rotated(index)];
+                let rule = self.win_rule();
+                if rule.is_met_by(&data) && !rule.is_met_by(&self.data) {
+                    events.push(BoardEvent::Solved {
+                        level: self.id,
+                        assisted: false,
+                    });
+                }
+                let mut turned = self.turned.clone();
+                turned.retain(|&other| other != index);
+                if turned.len() == HISTORY_SIZE {
+                    turned.pop_front();
+                }
+                turned.push_back(index);
+                let level = Level {
+                    turned,
+                    ..self.remembering(self.with_data(data))
+                };
+                (level.into(), events)
+            }
+            BoardAction::ReplaceGrid(grid) => (self.reset(grid).into(), vec![]),
+            BoardAction::Replay(start) => {
+                // the start of another level does not match the cached solution
+                let solution = self
+                    .solution
+                    .clone()
+                    .filter(|solution| rotation_distance(&start, solution).is_some());
+                self.platform.save(&start);
+                let dimensions = start.dimensions();
+                let level = Level {
+                    solution,
+                    next: self.next.clone(),
+                    ..self.reset(start)
+                };
+                (level.into(), vec![BoardEvent::Generated { dimensions }])
             }
-            BoardAction::ReplaceGrid(grid) => Level::new(self.id, grid).into(),
             BoardAction::NextLevel => {
-                let data = randomize_level(generate(self.data.dimensions() + 1, self.id as u64));
-                save_level(&data);
-                Level::new(self.id + 1, data).into()
+                let next = match &self.next {
+                    Some(next) => (**next).clone(),
+                    None => prepare_next_level(&self),
+                };
+                self.platform.save(&next.data);
+                let dimensions = next.data.dimensions();
+                (next.into(), vec![BoardEvent::Generated { dimensions }])
+            }
+            BoardAction::NextLevelPrepared(next) => {
+                if self.next.is_some() || next.id != self.id + 1 {
+                    return (self, vec![]);
+                }
+                log::info!("Prepared level {}", next.id);
+                let level = Level {
+                    next: Some(next),
+                    ..(*self).clone()
+                };
+                (level.into(), vec![])
             }
             BoardAction::GetHint => {
-                let trace = generate_solving_trace(&self.data);
+                let now = self.platform.now() as u64;
+                if let Err(refusal) = HintPolicy::DEFAULT.check(&self.hints_given, now) {
+                    log::info!("Hint refused: {refusal}");
+                    self.platform.announce(&refusal.to_string());
+                    return (self, vec![]);
+                }
+                let mut hint_engine = self.hint_engine.clone();
+                let trace = hint_engine.solving_trace(&self.data);
                 log::info!("trace: {:?}", trace);
-                if let Ok(coordinate) = get_hint(&self.data, trace) {
-                    highlight_cells(coordinate.row as usize, coordinate.column as usize);
-                    log::info!("Highlighting: {}", coordinate);
+                match get_hint(&self.data, trace.clone()) {
+                    Ok(hint) => {
+                        let coordinate = hint.coordinate;
+                        let step = trace.iter().position(|&(c, _)| c == coordinate);
+                        if let Some(description) =
+                            step.and_then(|step| trace.describe(&self.data).get(step).cloned())
+                        {
+                            self.platform.announce(&description);
+                        }
+                        log::info!("Highlighting: {}", coordinate);
+                        let mut hints_given = self.hints_given.clone();
+                        hints_given.push(now);
+                        let level = Level {
+                            hint: Some(hint),
+                            hints_given,
+                            hint_engine,
+                            highlights: self.highlighting(
+                                coordinate,
+                                HighlightKind::Hint,
+                                HINT_HIGHLIGHT_DURATION,
+                            ),
+                            ..(*self).clone()
+                        };
+                        (level.into(), vec![BoardEvent::HintShown(coordinate)])
+                    }
+                    Err(_) => (self, vec![]),
                 }
-                self
             }
-            BoardAction::SolveLevel => match self.data.solve().next() {
-                None => self,
-                Some(solution) => {
-                    log::info!("solved level:\n{solution}");
-                    save_level(&solution);
-                    Level::new(self.id, solution).into()
+            BoardAction::ExpireHighlights => {
+                let now = self.platform.now();
+                let mut highlights = self.highlights.clone();
+                highlights.retain(|_, cell| {
+                    cell.retain(|highlight| highlight.until > now);
+                    !cell.is_empty()
+                });
+                if highlights == self.highlights {
+                    return (self, vec![]);
                 }
-            },
-            BoardAction::SolveLevelInput(input) => {
-                let solved_version = self.data.solve_with_input(&input);
-                log::info!("solved level:\n {}", solved_version);
-
-                let same = self
+                let level = Level {
+                    highlights,
+                    ..(*self).clone()
+                };
+                (level.into(), vec![])
+            }
+            BoardAction::SolveLevel => {
+                let solution = self.find_solution();
+                match solution {
+                    None => (self, vec![]),
+                    Some(solution) => {
+                        log::info!("solved level:\n{solution}");
+                        let pending = rotation_distance(&self.data, &solution)
+                            .map(|distance| distance.clicks().collect::<VecDeque<_>>())
+                            .unwrap_or_default();
+                        if pending.is_empty() {
+                            self.platform.save(&solution);
+                            let solved = BoardEvent::Solved {
+                                level: self.id,
+                                assisted: true,
+                            };
+                            return (self.with_data(solution).into(), vec![solved]);
+                        }
+                        let level = Level {
+                            solution: Some(solution),
+                            pending,
+                            ..(*self).clone()
+                        };
+                        (level.into(), vec![])
+                    }
+                }
+            }
+            BoardAction::SolveStep => {
+                let mut pending = self.pending.clone();
+                match pending.pop_front() {
+                    None => (self, vec![]),
+                    Some(index) => {
+                        let data = self.data.rotate_clockwise(index).unwrap();
+                        self.platform.save(&data);
+                        let rule = self.win_rule();
+                        let events = if rule.is_met_by(&data) && !rule.is_met_by(&self.data) {
+                            vec![BoardEvent::Solved {
+                                level: self.id,
+                                assisted: true,
+                            }]
+                        } else {
+                            vec![]
+                        };
+                        let level = Level {
+                            pending,
+                            ..self.with_data(data)
+                        };
+                        (level.into(), events)
+                    }
+                }
+            }
+            BoardAction::SolvePartial(fraction) => {
+                let solution = self.find_solution();
+                let solution = match solution {
+                    Some(solution) => solution,
+                    None => return (self, vec![]),
+                };
+                let mut unsolved = self
                     .data
-                    .clone()
-                    .into_iter()
-                    .zip(solved_version.clone().into_iter())
-                    .all(|(x, y)| check_match(x, y));
-                if solved_version.is_solved() && same {
-                    Level::new(self.id, solved_version).into()
+                    .coordinates()
+                    .filter(|&index| self.data[index] != solution[index])
+                    .collect::<Vec<_>>();
+                // untouched tiles first, then the tiles turned longest ago
+                unsolved.sort_by_key(|index| {
+                    self.turned
+                        .iter()
+                        .position(|turned| turned == index)
+                        .map_or(0, |position| position + 1)
+                });
+                let count = (fraction.clamp(0.0, 1.0) * unsolved.len() as f32).ceil() as usize;
+                let mut data = self.data.clone();
+                for &index in unsolved.iter().take(count) {
+                    data[index] = solution[index];
+                }
+                log::info!("Solved {count} of {} unsolved tiles", unsolved.len());
+                self.platform.save(&data);
+                let rule = self.win_rule();
+                let events = if rule.is_met_by(&data) && !rule.is_met_by(&self.data) {
+                    vec![BoardEvent::Solved {
+                        level: self.id,
+                        assisted: true,
+                    }]
                 } else {
-                    self
+                    vec![]
+                };
+                let level = Level {
+                    solution: Some(solution),
+                    ..self.with_data(data)
+                };
+                (self.remembering(level).into(), events)
+            }
+            BoardAction::SetSolveSpeed(step_delay) => {
+                let level = Level {
+                    step_delay,
+                    ..(*self).clone()
+                };
+                (level.into(), vec![])
+            }
+            #[cfg(feature = "sat")]
+            BoardAction::SolveLevelSat => match self.data.solve_with_sat() {
+                Ok(Some(solution)) => {
+                    let level = Level {
+                        solution: Some(solution),
+                        ..(*self).clone()
+                    };
+                    Rc::new(level).transition(BoardAction::SolveLevel)
+                }
+                Ok(None) => {
+                    log::info!("SAT solver: level is unsolvable");
+                    (self, vec![])
+                }
+                Err(error) => {
+                    log::error!("SAT solver: {error}");
+                    (self, vec![])
+                }
+            },
+            #[cfg(feature = "sat")]
+            BoardAction::SolveLevelInput(input) => match self.data.solve_with_input(&input) {
+                Ok(solution) => {
+                    log::info!("solved level:\n {}", solution);
+                    let solved = BoardEvent::Solved {
+                        level: self.id,
+                        assisted: true,
+                    };
+                    (self.with_data(solution).into(), vec![solved])
+                }
+                Err(error) => {
+                    log::warn!("DIMACS input: {error}");
+                    (self, vec![])
+                }
+            },
+            BoardAction::Undo => {
+                let mut history = self.history.clone();
+                match history.undo(self.data.clone()) {
+                    Some(data) => {
+                        self.platform.save(&data);
+                        (self.traveled(history, data).into(), vec![])
+                    }
+                    None => (self, vec![]),
+                }
+            }
+            BoardAction::Redo => {
+                let mut history = self.history.clone();
+                match history.redo(self.data.clone()) {
+                    Some(data) => {
+                        self.platform.save(&data);
+                        (self.traveled(history, data).into(), vec![])
+                    }
+                    None => (self, vec![]),
                 }
             }
 
             // Editor actions
             BoardAction::ChangeTileShape(index) => {
                 log::info!("Change tile shape");
-                Level::new(self.id, self.data.change_tile_shape(index).unwrap()).into()
+                let data = self.data.change_tile_shape(index).unwrap();
+                self.platform.save(&data);
+                (
+                    self.remembering(self.reset_keeping_locks(data)).into(),
+                    vec![],
+                )
+            }
+            BoardAction::SetTile(index, tile) => {
+                let mut data = self.data.clone();
+                match data.get_mut(index) {
+                    None => (self, vec![]),
+                    Some(cell) => {
+                        *cell = tile;
+                        self.platform.save(&data);
+                        (
+                            self.remembering(self.reset_keeping_locks(data)).into(),
+                            vec![],
+                        )
+                    }
+                }
+            }
+            BoardAction::ToggleLock(index) => {
+                let mut locks = self.locks.clone();
+                locks.resize(self.data.dimensions());
+                if locks.remove(index).is_none() && locks.insert(index, ()).is_err() {
+                    return (self, vec![]);
+                }
+                log::info!("Locks: {:?}", locks.coordinates().collect::<Vec<_>>());
+                // the cached solution may turn a newly locked tile
+                let level = Level {
+                    locks,
+                    solution: None,
+                    ..(*self).clone()
+                };
+                (level.into(), vec![])
+            }
+            BoardAction::ChangeSize(size) => {
+                let data = generate(size, self.platform.random(10000));
+                let generated = BoardEvent::Generated { dimensions: size };
+                (self.remembering(self.reset(data)).into(), vec![generated])
             }
-            BoardAction::ChangeSize(size) => Level::new(
-                self.id,
-                generate(size, rand::thread_rng().gen_range(0..10000)),
-            )
-            .into(),
             BoardAction::GenerateFastGen => {
-                let data = generate(
-                    self.data.dimensions(),
-                    rand::thread_rng().gen_range(0..10000),
-                );
+                let data = generate(self.data.dimensions(), self.platform.random(10000));
                 log::info!("Generated grid\n{data}");
-                Level::new(self.id, data).into()
+                let generated = BoardEvent::Generated {
+                    dimensions: data.dimensions(),
+                };
+                (self.remembering(self.reset(data)).into(), vec![generated])
             }
             BoardAction::GenerateWFC => {
                 let wfc_settings =
@@ -166,132 +646,670 @@ impl Reducible for Level<Grid<Tile<Square>>> {
                 let data = retry_until_ok(wfc_settings, WfcGenerator::generate);
 
                 log::info!("Generated grid\n{data}");
-                Level::new(self.id, data).into()
+                let generated = BoardEvent::Generated {
+                    dimensions: data.dimensions(),
+                };
+                (self.remembering(self.reset(data)).into(), vec![generated])
+            }
+            BoardAction::GenerateTorus => {
+                let data = generate_torus(self.data.dimensions(), self.platform.random(10000));
+                log::info!("Generated torus\n{data}");
+                let generated = BoardEvent::Generated {
+                    dimensions: data.dimensions(),
+                };
+                (self.remembering(self.reset(data)).into(), vec![generated])
             }
             BoardAction::ShuffleTileRotations => {
-                let data = randomize_level(self.data.clone());
-                save_level(&data);
+                let mut data = randomize_level(self.data.clone(), &self.platform);
+                // locked tiles keep their rotation
+                for index in self.locks.coordinates() {
+                    if let (Some(cell), Some(&tile)) = (data.get_mut(index), self.data.get(index)) {
+                        *cell = tile;
+                    }
+                }
+                self.platform.save(&data);
                 log::info!("Tile rotations shuffled\n{data}");
-                Level::new(self.id, data).into()
+                (self.remembering(self.with_data(data)).into(), vec![])
             }
             BoardAction::ClearGrid => {
                 let data = Grid::filled_with(self.data.dimensions(), Tile::NO_CONNECTIONS);
-                Level::new(self.id, data).into()
+                (self.remembering(self.reset(data)).into(), vec![])
+            }
+
+            // Debugging actions
+            BoardAction::Restore(level) => {
+                // keep the serial, so the recorded events are not delivered a second time
+                let events = Emitted {
+                    serial: self.events.serial,
+                    events: vec![],
+                };
+                (Level { events, ..*level }.into(), vec![])
             }
         }
     }
-}
 
-impl Level<Grid<Tile<Square>>> {
-    pub fn set_size(dimensions: Coordinate<usize>) -> impl Fn() -> Level<Grid<Tile<Square>>> {
-        move || Level {
-            id: 1,
-            data: generate(dimensions, 1),
-        }
+    pub fn set_size(
+        dimensions: Coordinate<usize>,
+        platform: PlatformHandle,
+    ) -> impl Fn() -> Level<Grid<Tile<Square>>> {
+        move || Level::new(1, generate(dimensions, 1), platform.clone())
     }
 
-    pub fn set_grid(grid: Grid<Tile<Square>>) -> impl Fn() -> Level<Grid<Tile<Square>>> {
+    pub fn set_grid(
+        grid: Grid<Tile<Square>>,
+        platform: PlatformHandle,
+    ) -> impl Fn() -> Level<Grid<Tile<Square>>> {
+        Level::set_level(grid, None, platform)
+    }
+
+    /// level with its own win rule, see [`Level::win_rule`]
+    pub fn set_level(
+        grid: Grid<Tile<Square>>,
+        rule: Option<WinRule>,
+        platform: PlatformHandle,
+    ) -> impl Fn() -> Level<Grid<Tile<Square>>> {
         move || Level {
-            id: 1,
-            data: grid.clone(),
+            rule,
+            ..Level::new(1, grid.clone(), platform.clone())
         }
     }
 }
 
-//checks if 2 tiles have the same playing piece on them, no matter the rotation
-pub fn check_match(first: Tile<Square>, second: Tile<Square>) -> bool {
-    match first.enum_to_index() {
-        0 => second.enum_to_index() == 0,
-        1 => {
-            second.enum_to_index() == 1
-                || second.enum_to_index() == 2
-                || second.enum_to_index() == 4
-                || second.enum_to_index() == 8
-        }
-        2 => {
-            second.enum_to_index() == 1
-                || second.enum_to_index() == 2
-                || second.enum_to_index() == 4
-                || second.enum_to_index() == 8
+/// generates the level following the given one together with its solution
+///
+/// expensive for large boards, pages prepare it ahead of time outside the reducer, see [`BoardAction::NextLevelPrepared`]
+pub fn prepare_next_level(level: &Level<Grid<Tile<Square>>>) -> Level<Grid<Tile<Square>>> {
+    let difficulty = level.platform.campaign_difficulty();
+    // levels only grow while the player keeps up, larger boards tend to be harder
+    let dimensions = if difficulty < rate(&level.data) {
+        level.data.dimensions()
+    } else {
+        level.data.dimensions() + 1
+    };
+    let solution = generate_campaign_level(dimensions, difficulty, level.id as u64);
+    Level {
+        id: level.id + 1,
+        data: randomize_level(solution.clone(), &level.platform),
+        solution: Some(solution),
+        next: None,
+        events: Emitted::default(),
+        history: History::new(HISTORY_SIZE),
+        rule: None,
+        pending: VecDeque::new(),
+        step_delay: level.step_delay,
+        hint: None,
+        hints_given: vec![],
+        hint_engine: HintEngine::default(),
+        turned: VecDeque::new(),
+        highlights: Overlay::default(),
+        locks: Overlay::default(),
+        platform: level.platform.clone(),
+    }
+}
+
+/// Retries an impure computation until it succeeds
+fn retry_until_ok<A, B, E, F: Fn(&A) -> Result<B, E>>(initial: A, computation: F) -> B {
+    loop {
+        match computation(&initial) {
+            Err(_) => continue,
+            Ok(value) => return value,
         }
-        4 => {
-            second.enum_to_index() == 1
-                || second.enum_to_index() == 2
-                || second.enum_to_index() == 4
-                || second.enum_to_index() == 8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+
+    use yew::Reducible;
+
+    use game::prelude::{
+        Coordinate, Difficulty, GameBoard, Grid, HintPolicy, Square, Tile, WinRule,
+    };
+    use game::{enumset, tile};
+
+    use crate::components::board::cell::HighlightKind;
+    use crate::components::reducers::board_events::BoardEvent;
+    use crate::helper::platform::{Platform, PlatformHandle};
+
+    use super::{BoardAction, Level, HINT_HIGHLIGHT_DURATION};
+
+    /// records all side effects instead of touching the DOM
+    #[derive(Default)]
+    struct FakePlatform {
+        clock: Cell<f64>,
+        saved: RefCell<Vec<Grid<Tile<Square>>>>,
+        draws: Cell<u64>,
+        rule: WinRule,
+        difficulty: Difficulty,
+        announced: RefCell<Vec<String>>,
+    }
+
+    impl Platform for FakePlatform {
+        fn save(&self, grid: &Grid<Tile<Square>>) {
+            self.saved.borrow_mut().push(grid.clone());
         }
-        8 => {
-            second.enum_to_index() == 1
-                || second.enum_to_index() == 2
-                || second.enum_to_index() == 4
-                || second.enum_to_index() == 8
+
+        fn now(&self) -> f64 {
+            self.clock.get()
         }
 
-        3 => {
-            second.enum_to_index() == 3
-                || second.enum_to_index() == 6
-                || second.enum_to_index() == 9
-                || second.enum_to_index() == 12
+        fn random(&self, upper: u64) -> u64 {
+            self.draws.set(self.draws.get() + 1);
+            self.draws.get() % upper
         }
-        6 => {
-            second.enum_to_index() == 3
-                || second.enum_to_index() == 6
-                || second.enum_to_index() == 9
-                || second.enum_to_index() == 12
+
+        fn win_rule(&self) -> WinRule {
+            self.rule
         }
-        9 => {
-            second.enum_to_index() == 3
-                || second.enum_to_index() == 6
-                || second.enum_to_index() == 9
-                || second.enum_to_index() == 12
+
+        fn campaign_difficulty(&self) -> Difficulty {
+            self.difficulty
         }
-        12 => {
-            second.enum_to_index() == 3
-                || second.enum_to_index() == 6
-                || second.enum_to_index() == 9
-                || second.enum_to_index() == 12
+
+        fn announce(&self, message: &str) {
+            self.announced.borrow_mut().push(message.to_string());
         }
+    }
 
-        5 => second.enum_to_index() == 5 || second.enum_to_index() == 10,
-        10 => second.enum_to_index() == 5 || second.enum_to_index() == 10,
+    fn level(tiles: Vec<Tile<Square>>) -> (Rc<FakePlatform>, Rc<Level<Grid<Tile<Square>>>>) {
+        let platform = Rc::new(FakePlatform::default());
+        let grid = Grid::new(Coordinate::new(1, tiles.len()), tiles);
+        let level = Level::new(1, grid, PlatformHandle(platform.clone()));
+        (platform, level.into())
+    }
 
-        7 => {
-            second.enum_to_index() == 7
-                || second.enum_to_index() == 11
-                || second.enum_to_index() == 13
-                || second.enum_to_index() == 14
-        }
-        11 => {
-            second.enum_to_index() == 7
-                || second.enum_to_index() == 11
-                || second.enum_to_index() == 13
-                || second.enum_to_index() == 14
+    #[test]
+    fn turn_cell_saves_and_reports_solved() {
+        let (platform, level) = level(vec![tile!(Square::Right), tile!(Square::Down)]);
+        let index = Coordinate::new(0, 1);
+        let level = level.reduce(BoardAction::TurnCell(index));
+
+        assert!(level.data.is_solved());
+        assert_eq!(platform.saved.borrow().as_slice(), &[level.data.clone()]);
+        assert_eq!(
+            level.events.events,
+            vec![
+                BoardEvent::Rotated(index),
+                BoardEvent::Solved {
+                    level: 1,
+                    assisted: false
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn locked_tiles_are_not_turned() {
+        let (platform, level) = level(vec![tile!(Square::Right), tile!(Square::Down)]);
+        let anchor = Coordinate::new(0, 0);
+        let level = level.reduce(BoardAction::ToggleLock(anchor));
+        assert!(level.locks.contains(anchor));
+
+        let turned = level.clone().reduce(BoardAction::TurnCell(anchor));
+        assert_eq!(turned.data, level.data);
+        assert!(turned.events.events.is_empty());
+        assert!(platform.saved.borrow().is_empty());
+
+        let shuffled = level.clone().reduce(BoardAction::ShuffleTileRotations);
+        assert_eq!(shuffled.data[anchor], level.data[anchor]);
+
+        let unlocked = level.reduce(BoardAction::ToggleLock(anchor));
+        assert!(unlocked.locks.is_empty());
+        let turned = unlocked.reduce(BoardAction::TurnCell(anchor));
+        assert_ne!(turned.data[anchor], tile!(Square::Right));
+    }
+
+    #[test]
+    fn disjoint_loops_do_not_win_single_loop_levels() {
+        let platform = Rc::new(FakePlatform {
+            rule: WinRule::SingleLoop,
+            ..FakePlatform::default()
+        });
+        let tiles = vec![
+            tile!(Square::Right),
+            tile!(Square::Left),
+            tile!(Square::Right),
+            tile!(Square::Down),
+        ];
+        let grid = Grid::new(Coordinate::new(1, tiles.len()), tiles);
+        let level = Rc::new(Level::new(1, grid, PlatformHandle(platform)));
+        let level = level.reduce(BoardAction::TurnCell(Coordinate::new(0, 3)));
+
+        assert!(level.data.is_solved());
+        assert!(!level.is_won());
+        assert_eq!(
+            level.events.events,
+            vec![BoardEvent::Rotated(Coordinate::new(0, 3))]
+        );
+    }
+
+    #[test]
+    fn rule_of_the_level_overrides_the_player() {
+        let tiles = vec![
+            tile!(Square::Right),
+            tile!(Square::Left),
+            tile!(Square::Right),
+            tile!(Square::Left),
+        ];
+        let grid = Grid::new(Coordinate::new(1, tiles.len()), tiles);
+        let platform = PlatformHandle(Rc::new(FakePlatform::default()));
+        let level = Level::set_level(grid, Some(WinRule::SingleLoop), platform)();
+
+        assert!(level.data.is_solved());
+        assert_eq!(level.win_rule(), WinRule::SingleLoop);
+        assert!(!level.is_won());
+    }
+
+    #[test]
+    fn generated_torus_levels_wrap_around() {
+        let (_, level) = level(vec![Tile::NO_CONNECTIONS; 4]);
+        let level = level.reduce(BoardAction::GenerateTorus);
+        assert!(level.data.is_torus());
+        assert_eq!(
+            level.events.events,
+            vec![BoardEvent::Generated {
+                dimensions: Coordinate::new(1, 4)
+            }]
+        );
+    }
+
+    #[test]
+    fn prepared_levels_are_kept_for_the_following_level_only() {
+        let (platform, level) = level(vec![tile!(Square::Up), tile!(Square::Down)]);
+        let grid = Grid::new(Coordinate::new(1, 2), vec![tile!(Square::Right); 2]);
+        let next = Level::new(level.id + 1, grid, PlatformHandle(platform.clone()));
+        let stale = Level {
+            id: next.id + 1,
+            ..next.clone()
+        };
+        let ignored = level
+            .clone()
+            .reduce(BoardAction::NextLevelPrepared(Box::new(stale)));
+        assert!(Rc::ptr_eq(&ignored, &level));
+
+        let prepared = level.reduce(BoardAction::NextLevelPrepared(Box::new(next.clone())));
+        assert!(prepared.events.events.is_empty());
+        let loaded = prepared.reduce(BoardAction::NextLevel);
+        assert_eq!(loaded.data, next.data);
+        assert_eq!(
+            platform.saved.borrow().as_slice(),
+            std::slice::from_ref(&next.data)
+        );
+        assert_eq!(
+            loaded.events.events,
+            vec![BoardEvent::Generated {
+                dimensions: next.data.dimensions()
+            }]
+        );
+    }
+
+    #[test]
+    fn get_hint_highlights_the_tile() {
+        let (platform, level) = level(vec![tile!(Square::Up), tile!(Square::Down)]);
+        let level = level.reduce(BoardAction::GetHint);
+
+        let highlighted = level.highlights.coordinates().collect::<Vec<_>>();
+        assert_eq!(highlighted.len(), 1);
+        assert_eq!(
+            level.highlight_kinds(highlighted[0]),
+            vec![HighlightKind::Hint]
+        );
+        assert_eq!(platform.announced.borrow().len(), 1);
+        assert_eq!(
+            level.events.events,
+            vec![BoardEvent::HintShown(highlighted[0])]
+        );
+
+        let tooltip = level.hint_tooltip(highlighted[0]).unwrap();
+        assert!(tooltip.contains("cannot point"), "{tooltip}");
+        let turned = level.reduce(BoardAction::TurnCell(highlighted[0]));
+        assert_eq!(turned.hint_tooltip(highlighted[0]), None);
+    }
+
+    #[test]
+    fn hints_follow_the_policy() {
+        let (platform, level) = level(vec![tile!(Square::Up), tile!(Square::Down)]);
+        let level = level.reduce(BoardAction::GetHint);
+        assert_eq!(level.hints_given, vec![0]);
+
+        let refused = level.clone().reduce(BoardAction::GetHint);
+        assert!(Rc::ptr_eq(&refused, &level));
+        assert_eq!(platform.announced.borrow().len(), 2);
+
+        let cooldown = HintPolicy::DEFAULT.cooldown_seconds as u64 * 1000;
+        platform.clock.set(cooldown as f64);
+        let level = level.reduce(BoardAction::GetHint);
+        assert_eq!(level.hints_given, vec![0, cooldown]);
+        assert_eq!(
+            level.events.events,
+            vec![BoardEvent::HintShown(level.hint.unwrap().coordinate)]
+        );
+    }
+
+    #[test]
+    fn highlights_expire_in_time() {
+        let (platform, level) = level(vec![tile!(Square::Up), tile!(Square::Down)]);
+        let level = level.reduce(BoardAction::GetHint);
+        assert_eq!(level.next_expiry(), Some(HINT_HIGHLIGHT_DURATION));
+
+        let early = level.clone().reduce(BoardAction::ExpireHighlights);
+        assert!(Rc::ptr_eq(&early, &level));
+
+        platform.clock.set(HINT_HIGHLIGHT_DURATION);
+        let expired = level.reduce(BoardAction::ExpireHighlights);
+        assert!(expired.highlights.is_empty());
+        assert_eq!(expired.next_expiry(), None);
+    }
+
+    #[test]
+    fn solve_level_is_assisted() {
+        let (platform, level) = level(vec![tile!(Square::Up), tile!(Square::Down)]);
+        let mut level = level.reduce(BoardAction::SolveLevel);
+        assert_eq!(level.pending.len(), 2);
+        assert!(platform.saved.borrow().is_empty());
+
+        while !level.pending.is_empty() {
+            assert!(!level.data.is_solved());
+            level = level.reduce(BoardAction::SolveStep);
         }
-        13 => {
-            second.enum_to_index() == 7
-                || second.enum_to_index() == 11
-                || second.enum_to_index() == 13
-                || second.enum_to_index() == 14
+
+        assert!(level.data.is_solved());
+        assert_eq!(platform.saved.borrow().len(), 2);
+        assert_eq!(
+            level.events.events,
+            vec![BoardEvent::Solved {
+                level: 1,
+                assisted: true
+            }]
+        );
+    }
+
+    #[cfg(feature = "sat")]
+    #[test]
+    fn solve_level_sat_queues_the_rotations() {
+        let (_, solvable) = level(vec![tile!(Square::Up), tile!(Square::Down)]);
+        let mut solving = solvable.reduce(BoardAction::SolveLevelSat);
+        assert_eq!(solving.pending.len(), 2);
+
+        while !solving.pending.is_empty() {
+            solving = solving.reduce(BoardAction::SolveStep);
         }
-        14 => {
-            second.enum_to_index() == 7
-                || second.enum_to_index() == 11
-                || second.enum_to_index() == 13
-                || second.enum_to_index() == 14
+        assert!(solving.data.is_solved());
+
+        let (_, unsolvable) = level(vec![tile!(Square::Up)]);
+        let unchanged = unsolvable.clone().reduce(BoardAction::SolveLevelSat);
+        assert_eq!(unchanged, unsolvable);
+    }
+
+    #[test]
+    fn solve_animation_turns_tiles_in_reading_order() {
+        let (_, level) = level(vec![
+            tile!(Square::Left),
+            tile!(Square::Up),
+            tile!(Square::Right),
+            tile!(Square::Left),
+        ]);
+        let level = level.reduce(BoardAction::SetSolveSpeed(40));
+        let solving = level.reduce(BoardAction::SolveLevel);
+
+        assert_eq!(solving.step_delay, 40);
+        let columns = solving.pending.iter().map(|index| index.column);
+        assert!(columns.clone().zip(columns.skip(1)).all(|(a, b)| a <= b));
+
+        let interrupted = solving
+            .reduce(BoardAction::SolveStep)
+            .reduce(BoardAction::TurnCell(Coordinate::new(0, 3)));
+        assert!(interrupted.pending.is_empty());
+        assert_eq!(interrupted.step_delay, 40);
+    }
+
+    #[test]
+    fn partial_solve_prefers_untouched_tiles() {
+        let (platform, level) = level(vec![
+            tile!(Square::Left),
+            tile!(Square::Right),
+            tile!(Square::Left),
+            tile!(Square::Right),
+        ]);
+        let turned = level.reduce(BoardAction::TurnCell(Coordinate::new(0, 0)));
+        let partial = turned.clone().reduce(BoardAction::SolvePartial(0.5));
+
+        let tiles = partial.data.as_slice();
+        assert_eq!(tiles[0], tile!(Square::Up));
+        assert_eq!(tiles[1..3], [tile!(Square::Left), tile!(Square::Right)]);
+        assert_eq!(tiles[3], tile!(Square::Right));
+        assert_eq!(partial.events.serial, turned.events.serial);
+        assert_eq!(partial.clone().reduce(BoardAction::Undo).data, turned.data);
+
+        let solved = partial.reduce(BoardAction::SolvePartial(1.0));
+        assert!(solved.data.is_solved());
+        assert_eq!(platform.saved.borrow().len(), 4);
+        assert_eq!(
+            solved.events.events,
+            vec![BoardEvent::Solved {
+                level: 1,
+                assisted: true
+            }]
+        );
+    }
+
+    #[test]
+    fn next_level_grows_while_the_player_keeps_up() {
+        let platform = Rc::new(FakePlatform {
+            difficulty: Difficulty::Hard,
+            ..FakePlatform::default()
+        });
+        let grid = Grid::new(Coordinate::new(1, 1), vec![Tile::NO_CONNECTIONS]);
+        let level = Rc::new(Level::new(1, grid, PlatformHandle(platform)));
+        let next = level.reduce(BoardAction::NextLevel);
+        assert_eq!(next.data.dimensions(), Coordinate::new(2, 2));
+        assert_eq!(next.id, 2);
+    }
+
+    #[test]
+    fn replay_restarts_the_level_with_its_solution() {
+        let (platform, level) = level(vec![tile!(Square::Right), tile!(Square::Down)]);
+        let start = level.data.clone();
+        let solved = Rc::new(Level {
+            solution: Some(Grid::new(
+                Coordinate::new(1, 2),
+                vec![tile!(Square::Right), tile!(Square::Left)],
+            )),
+            ..(*level).clone()
+        })
+        .reduce(BoardAction::TurnCell(Coordinate::new(0, 1)));
+        assert!(solved.is_won());
+
+        let replayed = solved.reduce(BoardAction::Replay(start.clone()));
+        assert_eq!(replayed.data, start);
+        assert!(replayed.solution.is_some());
+        assert!(!replayed.history.can_undo());
+        assert_eq!(platform.saved.borrow().last(), Some(&start));
+        assert_eq!(
+            replayed.events.events,
+            vec![BoardEvent::Generated {
+                dimensions: start.dimensions()
+            }]
+        );
+    }
+
+    #[test]
+    fn change_size_draws_seed_from_platform() {
+        let (platform, level) = level(vec![tile!(Square::Up), tile!(Square::Down)]);
+        let dimensions = Coordinate::new(3, 3);
+        let level = level.reduce(BoardAction::ChangeSize(dimensions));
+
+        assert_eq!(level.data.dimensions(), dimensions);
+        assert_eq!(platform.draws.get(), 1);
+        assert_eq!(
+            level.events.events,
+            vec![BoardEvent::Generated { dimensions }]
+        );
+    }
+
+    #[test]
+    fn rotations_and_editor_edits_are_revertible() {
+        let (platform, initial) = level(vec![tile!(Square::Up), tile!(Square::Down)]);
+        let turned = initial
+            .clone()
+            .reduce(BoardAction::TurnCell(Coordinate::new(0, 0)));
+        let cleared = turned.clone().reduce(BoardAction::ClearGrid);
+
+        let undone = cleared.clone().reduce(BoardAction::Undo);
+        assert_eq!(undone.data, turned.data);
+        let undone = undone.reduce(BoardAction::Undo);
+        assert_eq!(undone.data, initial.data);
+        assert!(!undone.history.can_undo());
+        assert_eq!(undone.clone().reduce(BoardAction::Undo).data, initial.data);
+
+        let redone = undone.reduce(BoardAction::Redo).reduce(BoardAction::Redo);
+        assert_eq!(redone.data, cleared.data);
+        assert!(!redone.history.can_redo());
+        assert_eq!(platform.saved.borrow().last(), Some(&cleared.data));
+    }
+
+    #[test]
+    fn set_tile_is_revertible() {
+        let (platform, initial) = level(vec![tile!(Square::Up), tile!(Square::Down)]);
+        let index = Coordinate::new(0, 1);
+        let set = initial
+            .clone()
+            .reduce(BoardAction::SetTile(index, Tile::ALL_CONNECTIONS));
+
+        assert_eq!(set.data[index], Tile::ALL_CONNECTIONS);
+        assert_eq!(platform.saved.borrow().last(), Some(&set.data));
+        assert_eq!(set.reduce(BoardAction::Undo).data, initial.data);
+
+        let outside = initial.clone().reduce(BoardAction::SetTile(
+            Coordinate::new(1, 0),
+            Tile::ALL_CONNECTIONS,
+        ));
+        assert_eq!(outside, initial);
+    }
+
+    #[test]
+    fn undo_and_redo_do_not_repeat_events() {
+        let (platform, level) = level(vec![tile!(Square::Right), tile!(Square::Down)]);
+        let solved = level.reduce(BoardAction::TurnCell(Coordinate::new(0, 1)));
+        assert_eq!(solved.events.serial, 1);
+
+        let undone = solved.clone().reduce(BoardAction::Undo);
+        assert!(!undone.data.is_solved());
+        assert_eq!(undone.events, solved.events);
+        assert_eq!(platform.saved.borrow().last(), Some(&undone.data));
+
+        let redone = undone.clone().reduce(BoardAction::Redo);
+        assert!(redone.data.is_solved());
+        assert_eq!(redone.events, solved.events);
+
+        let resolved = undone.reduce(BoardAction::TurnCell(Coordinate::new(0, 1)));
+        assert_eq!(resolved.events.serial, 2);
+        assert_eq!(resolved.events.events, solved.events.events);
+    }
+
+    #[test]
+    fn unsolvable_levels_emit_no_events() {
+        let (platform, level) = level(vec![tile!(Square::Up)]);
+        for action in [
+            BoardAction::SolveLevel,
+            BoardAction::SolvePartial(1.0),
+            BoardAction::GetHint,
+        ] {
+            let unchanged = level.clone().reduce(action);
+            assert!(Rc::ptr_eq(&unchanged, &level));
         }
+        assert!(platform.saved.borrow().is_empty());
+    }
 
-        15 => second.enum_to_index() == 15,
+    #[test]
+    fn solving_a_solved_level_reports_it_at_once() {
+        let (platform, level) = level(vec![tile!(Square::Right), tile!(Square::Left)]);
+        let level = level.reduce(BoardAction::SolveLevel);
 
-        _ => false,
+        assert!(level.pending.is_empty());
+        assert_eq!(
+            platform.saved.borrow().as_slice(),
+            std::slice::from_ref(&level.data)
+        );
+        assert_eq!(
+            level.events.events,
+            vec![BoardEvent::Solved {
+                level: 1,
+                assisted: true
+            }]
+        );
     }
-}
 
-/// Retries an impure computation until it succeeds
-fn retry_until_ok<A, B, E, F: Fn(&A) -> Result<B, E>>(initial: A, computation: F) -> B {
-    loop {
-        match computation(&initial) {
-            Err(_) => continue,
-            Ok(value) => return value,
+    #[test]
+    fn generators_report_the_generated_level() {
+        let (platform, level) = level(vec![Tile::NO_CONNECTIONS; 3]);
+        for action in [BoardAction::GenerateFastGen, BoardAction::GenerateWFC] {
+            let generated = level.clone().reduce(action);
+            assert_eq!(generated.events.serial, 1);
+            assert_eq!(
+                generated.events.events,
+                vec![BoardEvent::Generated {
+                    dimensions: Coordinate::new(1, 3)
+                }]
+            );
+            assert_eq!(generated.reduce(BoardAction::Undo).data, level.data);
         }
+        assert_eq!(platform.draws.get(), 1);
+    }
+
+    #[test]
+    fn locks_emit_no_events() {
+        let (_, level) = level(vec![tile!(Square::Right), tile!(Square::Down)]);
+        let locked = level.reduce(BoardAction::ToggleLock(Coordinate::new(0, 1)));
+        let unlocked = locked
+            .clone()
+            .reduce(BoardAction::ToggleLock(Coordinate::new(0, 1)));
+        let outside = unlocked
+            .clone()
+            .reduce(BoardAction::ToggleLock(Coordinate::new(1, 0)));
+
+        assert!(locked.locks.contains(Coordinate::new(0, 1)));
+        assert!(Rc::ptr_eq(&outside, &unlocked));
+        assert_eq!(outside.events.serial, 0);
+    }
+
+    #[cfg(feature = "sat")]
+    #[test]
+    fn solve_level_input_reports_assisted_solve() {
+        let (_, level) = level(vec![tile!(Square::Right), tile!(Square::Down)]);
+        let malformed = level
+            .clone()
+            .reduce(BoardAction::SolveLevelInput("1 x".into()));
+        assert!(Rc::ptr_eq(&malformed, &level));
+
+        let solved = level.reduce(BoardAction::SolveLevelInput(
+            "-1 2 -3 -4 -5 -6 -7 8 0".into(),
+        ));
+        assert!(solved.data.is_solved());
+        assert_eq!(
+            solved.events.events,
+            vec![BoardEvent::Solved {
+                level: 1,
+                assisted: true
+            }]
+        );
+    }
+
+    #[test]
+    fn clear_grid_has_no_side_effects() {
+        let (platform, level) = level(vec![tile!(Square::Up), tile!(Square::Down)]);
+        let level = level.reduce(BoardAction::ClearGrid);
+
+        assert!(level
+            .data
+            .as_slice()
+            .iter()
+            .all(|&t| t == Tile::NO_CONNECTIONS));
+        assert!(platform.saved.borrow().is_empty());
+        assert!(level.highlights.is_empty());
+        assert_eq!(level.events.serial, 0);
     }
 }
@@ -1,2 +1,3 @@
+pub mod board_events;
 pub mod board_reducer;
 pub mod preview_reducer;
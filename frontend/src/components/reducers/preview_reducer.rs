@@ -1,17 +1,18 @@
 use std::rc::Rc;
 use yew::prelude::*;
 
-use game::generator::fastgen::generate;
-use game::model::coordinate::Coordinate;
-use game::model::grid::Grid;
-use game::model::tile::{Square, Tile};
+use game::model::symmetry::distinct_up_to_symmetry;
+use game::prelude::{generate, Coordinate, Grid, Square, Tile};
 
 use crate::helper::level_randomizer::randomize_level;
+use crate::helper::platform::PlatformHandle;
 
 /// reducer facilitates loading more levels for the preview component
 ///
-/// the LoadNew action takes a number which indicates the amount of levels to load and
+/// the LoadNew action takes a number which indicates the amount of seeds to generate levels from and
 /// a coordinate which indicates needed dimension of the levels
+///
+/// levels equal up to symmetry to a previous level are left out, see [`Grid::canonical_form`]
 pub enum PreviewAction {
     LoadNew(usize, Coordinate<usize>),
 }
@@ -19,6 +20,9 @@ pub enum PreviewAction {
 #[derive(Clone, Debug, PartialEq, Eq, Default)]
 pub struct PreviewState {
     pub extracted_levels: Vec<Grid<Tile<Square>>>,
+    pub platform: PlatformHandle,
+    /// seed of the next generated level
+    pub next_seed: u64,
 }
 
 impl Reducible for PreviewState {
@@ -27,16 +31,18 @@ impl Reducible for PreviewState {
     fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
         match action {
             PreviewAction::LoadNew(level_number, dimension) => {
-                let generated_levels = (0..level_number as u64)
-                    .into_iter()
-                    .map(|index| randomize_level(generate(dimension, index)));
+                let next_seed = self.next_seed + level_number as u64;
+                let generated_levels = (self.next_seed..next_seed)
+                    .map(|seed| randomize_level(generate(dimension, seed), &self.platform));
                 Self {
-                    extracted_levels: self
-                        .extracted_levels
-                        .clone()
-                        .into_iter()
-                        .chain(generated_levels)
-                        .collect(),
+                    extracted_levels: distinct_up_to_symmetry(
+                        self.extracted_levels
+                            .iter()
+                            .cloned()
+                            .chain(generated_levels),
+                    ),
+                    platform: self.platform.clone(),
+                    next_seed,
                 }
                 .into()
             }
@@ -45,13 +51,19 @@ impl Reducible for PreviewState {
 }
 
 impl PreviewState {
-    pub fn set(extracted_levels: Vec<Grid<Tile<Square>>>) -> impl Fn() -> PreviewState {
+    /// levels generated from the seeds in ascending order, continued by [`PreviewAction::LoadNew`]
+    pub fn set(
+        extracted_levels: Vec<Grid<Tile<Square>>>,
+        platform: PlatformHandle,
+    ) -> impl Fn() -> PreviewState {
         move || PreviewState {
-            extracted_levels: extracted_levels
-                .clone()
-                .into_iter()
-                .map(randomize_level)
-                .collect(),
+            extracted_levels: distinct_up_to_symmetry(
+                extracted_levels
+                    .iter()
+                    .map(|level| randomize_level(level.clone(), &platform)),
+            ),
+            platform: platform.clone(),
+            next_seed: extracted_levels.len() as u64,
         }
     }
 }
@@ -0,0 +1,63 @@
+use yew::prelude::*;
+
+/// colors of the confetti pieces, cycled through
+const CONFETTI_COLORS: [&str; 4] = ["#f1f8ff", "#a9afdd", "#555a8d", "#f7c548"];
+
+/// streaks of at least this length are announced with a banner
+const BANNER_STREAK: u32 = 3;
+
+/// streak to celebrate, the serial restarts the animation for consecutive celebrations
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct CelebrationState {
+    pub streak: u32,
+    pub serial: u64,
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct CelebrationProps {
+    pub celebration: CelebrationState,
+}
+
+/// number of confetti pieces, escalating with the streak
+fn confetti_count(streak: u32) -> u32 {
+    (10 + 5 * streak).min(80)
+}
+
+/// confetti overlay celebrating a solved level, the longer the streak the more exuberant
+///
+/// purely decorative, it neither blocks input nor needs to be dismissed
+#[function_component(Celebration)]
+pub fn celebration(props: &CelebrationProps) -> Html {
+    let CelebrationState { streak, serial } = props.celebration;
+    if streak == 0 {
+        return html! {};
+    }
+
+    let pieces = (0..confetti_count(streak))
+        .map(|i| {
+            // spread pieces deterministically over the screen, no randomness needed for decoration
+            let left = (i * 37 + serial as u32 * 11) % 100;
+            let delay = (i * 53) % 700;
+            let color = CONFETTI_COLORS[i as usize % CONFETTI_COLORS.len()];
+            html! {
+                <span
+                    class="confetti"
+                    style={format!("left: {left}%; animation-delay: {delay}ms; background-color: {color};")}>
+                </span>
+            }
+        })
+        .collect::<Html>();
+
+    let banner = if streak >= BANNER_STREAK {
+        html! { <div class="celebration-banner">{format!("{streak} in a row!")}</div> }
+    } else {
+        html! {}
+    };
+
+    html! {
+        <div key={serial.to_string()} class="celebration">
+            {pieces}
+            {banner}
+        </div>
+    }
+}
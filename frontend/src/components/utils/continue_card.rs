@@ -0,0 +1,44 @@
+use yew::prelude::*;
+use yew::{html, Callback};
+
+use crate::components::utils::thumbnail::Thumbnail;
+use crate::helper::local_storage::{change_screen, retrieve_session};
+use crate::helper::screen::Screen;
+use crate::helper::session::minutes_and_seconds;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct ContinueCardProps {
+    pub screen: UseStateHandle<Screen>,
+}
+
+/// card leading back to the level the player left unsolved, with its rotations, moves and timer
+///
+/// renders nothing if there is no level in progress
+#[function_component(ContinueCard)]
+pub fn continue_card(props: &ContinueCardProps) -> Html {
+    let save = match retrieve_session() {
+        Some(save) => save,
+        None => return html! {},
+    };
+
+    let to_level: Callback<MouseEvent> = {
+        let screen = props.screen.clone();
+        let level = save.current.clone();
+        Callback::from(move |_| {
+            log::info!("[Button click] Continue");
+            change_screen(screen.clone(), Screen::Level(level.clone(), None));
+        })
+    };
+
+    html! {
+        <div id="continue-container">
+            <div class="level-container continue-card" onclick={to_level}>
+                <Thumbnail level_grid={save.current.clone()} />
+                <div class="level-title">{"Continue"}</div>
+                <div class="continue-progress">
+                    {format!("{} moves - {}", save.moves, minutes_and_seconds(save.elapsed_milliseconds))}
+                </div>
+            </div>
+        </div>
+    }
+}
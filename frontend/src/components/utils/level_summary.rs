@@ -0,0 +1,78 @@
+use yew::prelude::*;
+use yew::{html, Callback};
+
+use crate::helper::local_storage::retrieve_stats;
+use crate::helper::session::LevelSummary;
+use crate::helper::share::copy_to_clipboard;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct LevelSummaryProps {
+    pub summary: LevelSummary,
+    pub on_replay: Callback<MouseEvent>,
+    pub on_next: Callback<MouseEvent>,
+    pub on_close: Callback<MouseEvent>,
+}
+
+/// overlay summarizing the level just solved: time, moves against par, hints, difficulty, streak and best score
+///
+/// offers to replay the level, to copy its code for sharing and to continue with the next level
+#[function_component(LevelSummaryComponent)]
+pub fn level_summary(props: &LevelSummaryProps) -> Html {
+    let summary = &props.summary;
+    let stats = retrieve_stats();
+    let copied = use_state_eq(|| false);
+
+    let share_onclick: Callback<MouseEvent> = {
+        let code = summary.start.to_string();
+        let copied = copied.clone();
+        Callback::from(move |_| {
+            log::info!("[Button click] Share.");
+            match copy_to_clipboard(&code) {
+                Ok(()) => copied.set(true),
+                Err(error) => log::warn!("Copying the level code failed: {:?}", error),
+            }
+        })
+    };
+
+    let moves = if summary.assisted {
+        "solved by the solver".to_string()
+    } else if summary.par_known {
+        format!(
+            "{} moves - par {}",
+            summary.scoring.rotations, summary.scoring.par
+        )
+    } else {
+        format!("{} moves", summary.scoring.rotations)
+    };
+
+    let score = match (summary.score(), summary.best_score) {
+        (Some(score), _) if summary.is_best() => html! {
+            <div class="summary-best">{format!("score {score} - new best!")}</div>
+        },
+        (Some(score), Some(best)) => html! {
+            <div>{format!("score {score} - best {best}")}</div>
+        },
+        _ => html! {},
+    };
+
+    let share_label = if *copied { "-copied-" } else { "-share code-" };
+
+    html! {
+        <div class="level-summary">
+            <div class="summary-card">
+                <div class="summary-title">{"-LEVEL COMPLETED-"}</div>
+                <div>{format!("time {}", summary.elapsed_time())}</div>
+                <div>{moves}</div>
+                <div>{format!("{} hints - {}", summary.scoring.hints, summary.difficulty)}</div>
+                <div>{format!("streak {} - best streak {}", stats.streak, stats.best_streak)}</div>
+                {score}
+                <div class="summary-controls">
+                    <button onclick={props.on_replay.clone()}>{"-replay-"}</button>
+                    <button onclick={share_onclick}>{share_label}</button>
+                    <button onclick={props.on_next.clone()}>{"-next-"}</button>
+                    <button onclick={props.on_close.clone()}>{"-close-"}</button>
+                </div>
+            </div>
+        </div>
+    }
+}
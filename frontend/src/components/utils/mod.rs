@@ -1,4 +1,10 @@
+pub mod celebration;
+pub mod continue_card;
+pub mod level_summary;
+pub mod presence_counter;
 pub mod slider;
+pub mod thumbnail;
 pub mod tile_checkbox;
 pub mod tile_image;
 pub mod tile_selector;
+pub mod time_travel;
@@ -0,0 +1,68 @@
+use gloo_timers::callback::Interval;
+use yew::html;
+use yew::prelude::*;
+
+use crate::helper::api::ApiClient;
+use crate::helper::platform::use_platform;
+
+/// milliseconds between two heartbeats, well within the time to live of the backend
+const HEARTBEAT_INTERVAL: u32 = 30_000;
+
+/// counter of the players online, e.g. "42 people looping right now"
+///
+/// sends a heartbeat with a random id of this tab while shown and renders nothing
+/// in offline mode or when the backend is unreachable
+#[function_component(PresenceCounter)]
+pub fn presence_counter() -> Html {
+    let platform = use_platform();
+    let id = use_state(|| format!("{:016x}", platform.random(u64::MAX)));
+    let players = use_state_eq(|| None::<usize>);
+
+    {
+        let players = players.clone();
+        use_effect_with_deps(
+            move |id: &String| {
+                let send = {
+                    let id = id.clone();
+                    move || {
+                        let players = players.clone();
+                        let id = id.clone();
+                        wasm_bindgen_futures::spawn_local(async move {
+                            players.set(ApiClient::default().heartbeat(&id).await.ok());
+                        });
+                    }
+                };
+                send();
+                let interval = Interval::new(HEARTBEAT_INTERVAL, send);
+                move || drop(interval)
+            },
+            (*id).clone(),
+        );
+    }
+
+    match *players {
+        Some(players) if players > 0 => html! {
+            <p class="presence">{players_online(players)}</p>
+        },
+        _ => html! {},
+    }
+}
+
+fn players_online(players: usize) -> String {
+    match players {
+        1 => "1 person looping right now".to_string(),
+        players => format!("{players} people looping right now"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::players_online;
+
+    #[test]
+    fn counts_are_spelled_out() {
+        assert_eq!(players_online(1), "1 person looping right now");
+        assert_eq!(players_online(42), "42 people looping right now");
+    }
+}
@@ -0,0 +1,47 @@
+use yew::html;
+use yew::prelude::*;
+
+use game::prelude::{Grid, Square, Tile};
+use game::render::svg::tile_shapes;
+use game::render::theme::{Theme, CELL_UNITS};
+
+use crate::components::utils::tile_image::shape_element;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct ThumbnailProps {
+    pub level_grid: Grid<Tile<Square>>,
+}
+
+/// miniature picture of a level, e.g. for previews
+///
+/// draws the whole level as a single inline SVG with the shapes of the SVG renderer, without the cells,
+/// listeners and state of the interactive board
+#[function_component(Thumbnail)]
+pub fn thumbnail_component(props: &ThumbnailProps) -> Html {
+    let grid = &props.level_grid;
+    let dimensions = grid.dimensions();
+    let view_box = format!(
+        "0 0 {} {}",
+        dimensions.column.max(1) as u32 * CELL_UNITS,
+        dimensions.row.max(1) as u32 * CELL_UNITS
+    );
+
+    html! {
+        <svg class="thumbnail" viewBox={view_box}>
+            {
+                grid.coordinates().map(|c| {
+                    let transform = format!(
+                        "translate({} {})",
+                        c.column as u32 * CELL_UNITS,
+                        c.row as u32 * CELL_UNITS
+                    );
+                    html! {
+                        <g transform={transform}>
+                            { for tile_shapes(grid[c], &Theme::DEFAULT).into_iter().map(shape_element) }
+                        </g>
+                    }
+                }).collect::<Html>()
+            }
+        </svg>
+    }
+}
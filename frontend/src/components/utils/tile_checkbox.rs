@@ -2,7 +2,7 @@ use yew::html;
 use yew::prelude::*;
 
 use crate::components::utils::tile_image::TileImage;
-use game::model::tile::{Square, Tile};
+use game::prelude::{Square, Tile};
 
 #[derive(Properties, PartialEq, Clone)]
 pub struct TileCheckboxProps {
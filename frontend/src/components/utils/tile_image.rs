@@ -1,16 +1,9 @@
 use yew::html;
 use yew::prelude::*;
 
-use game::model::tile::{Square, Tile};
-
-const IMG_PATH: [&str; 6] = [
-    "data/tiles/0.svg",
-    "data/tiles/1.svg",
-    "data/tiles/2.svg",
-    "data/tiles/3.svg",
-    "data/tiles/4.svg",
-    "data/tiles/5.svg",
-];
+use game::prelude::{Square, Tile};
+use game::render::svg::{tile_shapes, Shape};
+use game::render::theme::{Theme, CELL_UNITS};
 
 #[derive(Properties, PartialEq, Clone)]
 pub struct TileImageProps {
@@ -18,44 +11,46 @@ pub struct TileImageProps {
 }
 
 /// image representation of tile in the game model
+///
+/// drawn as inline SVG with the shapes of the SVG renderer, styled by the custom properties of [`ThemeStyle`]
 #[function_component(TileImage)]
 pub fn tile_image_component(props: &TileImageProps) -> Html {
-    let tile = props.tile;
-    let cell_symbol = tile.to_string().chars().next().unwrap();
-    let cell_img = get_index(cell_symbol);
+    let view_box = format!("0 0 {CELL_UNITS} {CELL_UNITS}");
 
     html! {
         <div class="tile">
-            <img src={IMG_PATH[cell_img]}
-                style={format!("{}{}{}",
-                    "transform:rotate(",
-                    get_angle(cell_symbol),
-                    "deg);")}
-            />
+            <svg viewBox={view_box}>
+                { for tile_shapes(props.tile, &Theme::DEFAULT).into_iter().map(shape_element) }
+            </svg>
         </div>
     }
 }
 
-/// get the correct image for given tile
-fn get_index(cell_symbol: char) -> usize {
-    match cell_symbol {
-        ' ' => 0,
-        '╹' | '╺' | '╻' | '╸' => 1,
-        '┃' | '━' => 2,
-        '┗' | '┏' | '┛' | '┓' => 3,
-        '┣' | '┻' | '┫' | '┳' => 4,
-        '╋' => 5,
-        _ => 0,
+/// SVG element of the shape, drawn inside the `<svg>` of a tile or board
+pub fn shape_element(shape: Shape) -> Html {
+    match shape {
+        Shape::Line { x1, y1, x2, y2 } => html! {
+            <line class="connection"
+                x1={x1.to_string()}
+                y1={y1.to_string()}
+                x2={x2.to_string()}
+                y2={y2.to_string()}
+            />
+        },
+        Shape::Circle { cx, cy, r } => html! {
+            <circle class="connection"
+                cx={cx.to_string()}
+                cy={cy.to_string()}
+                r={r.to_string()}
+            />
+        },
     }
 }
 
-/// get the correct angle for image roation
-fn get_angle(cell_symbol: char) -> usize {
-    match cell_symbol {
-        ' ' | '╋' | '╹' | '┗' | '┣' => 0,
-        '╺' | '━' | '┏' | '┳' => 90,
-        '╻' | '┓' | '┫' => 180,
-        '╸' | '┛' | '┻' => 270,
-        _ => 0,
+/// stylesheet declaring the render tokens of the game as CSS custom properties, see [`Theme::css_properties`]
+#[function_component(ThemeStyle)]
+pub fn theme_style_component() -> Html {
+    html! {
+        <style>{Theme::DEFAULT.css_properties()}</style>
     }
 }
@@ -1,12 +1,9 @@
 use yew::html;
 use yew::prelude::*;
 
-use game::model::tile::{
-    Square::{self, Down, Left, Right, Up},
-    Tile,
-};
+use game::model::tile::Square::{self, Down, Left, Right, Up};
+use game::prelude::{EnumSet, Tile};
 
-use game::core::enumset::EnumSet;
 use game::{enumset, tile};
 
 use crate::components::utils::tile_checkbox::TileCheckbox;
@@ -65,11 +62,12 @@ pub fn tile_selector_component(props: &TileSelectorProps) -> Html {
         tile_4c.clone(),
     ];
 
-    let new_tile_set = tiles
-        .iter()
-        .filter(|tile| *(tile.state.clone()))
-        .map(|tile| tile.tile)
-        .fold(EnumSet::EMPTY, |acc, x| acc.union(get_all_roations(x)));
+    let new_tile_set = EnumSet::union_all(
+        tiles
+            .iter()
+            .filter(|tile| *(tile.state.clone()))
+            .map(|tile| get_all_roations(tile.tile)),
+    );
 
     tile_set.set(new_tile_set);
 
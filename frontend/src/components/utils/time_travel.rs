@@ -0,0 +1,154 @@
+use wasm_bindgen::{prelude::Closure, JsCast};
+use web_sys::KeyboardEvent;
+use yew::html;
+use yew::prelude::*;
+
+use game::prelude::{Grid, Square, Tile};
+
+use crate::components::reducers::board_reducer::{BoardAction, Level};
+
+/// key toggling the panel together with Alt and Shift
+const TOGGLE_KEY: &str = "T";
+
+/// recorded states of the board reducer
+///
+/// `cursor` points to the state currently shown on the board
+#[derive(Clone, Default)]
+struct History {
+    levels: Vec<Level<Grid<Tile<Square>>>>,
+    cursor: usize,
+}
+
+impl History {
+    /// appends the level after the cursor, discarding all states ahead of it
+    ///
+    /// returns `None` if the level is the current one, e.g. after traveling to it
+    fn recorded(&self, level: &Level<Grid<Tile<Square>>>) -> Option<Self> {
+        let current = self.levels.get(self.cursor);
+        if current.map_or(false, |current| {
+            current.id == level.id && current.data == level.data
+        }) {
+            return None;
+        }
+        let mut levels = self.levels.clone();
+        levels.truncate(self.cursor + 1);
+        levels.push(level.clone());
+        let cursor = levels.len() - 1;
+        Some(History { levels, cursor })
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct TimeTravelProps {
+    pub board: UseReducerHandle<Level<Grid<Tile<Square>>>>,
+}
+
+/// dev-only overlay for stepping backward and forward through the reducer states
+///
+/// each entry lists the board events of the transition leading to it
+///
+/// toggled with Alt + Shift + T
+#[function_component(TimeTravelPanel)]
+pub fn time_travel_panel_component(props: &TimeTravelProps) -> Html {
+    let board = props.board.clone();
+    let history = use_state(History::default);
+    let visible = use_state(|| false);
+
+    // record every state transition of the reducer
+    {
+        let history = history.clone();
+        use_effect_with_deps(
+            move |level| {
+                if let Some(recorded) = history.recorded(level) {
+                    history.set(recorded);
+                }
+                || {}
+            },
+            (*board).clone(),
+        );
+    }
+
+    // toggle the panel via keyboard shortcut
+    {
+        let shown = *visible;
+        let visible = visible.clone();
+        use_effect_with_deps(
+            move |&shown| {
+                let toggle = Closure::<dyn Fn(KeyboardEvent)>::new(move |e: KeyboardEvent| {
+                    if e.alt_key() && e.shift_key() && e.key() == TOGGLE_KEY {
+                        e.prevent_default();
+                        visible.set(!shown);
+                    }
+                });
+                let window = web_sys::window().unwrap();
+                window
+                    .add_event_listener_with_callback("keydown", toggle.as_ref().unchecked_ref())
+                    .ok();
+                move || {
+                    window
+                        .remove_event_listener_with_callback(
+                            "keydown",
+                            toggle.as_ref().unchecked_ref(),
+                        )
+                        .ok();
+                }
+            },
+            shown,
+        );
+    }
+
+    fn travel_to(
+        board: UseReducerHandle<Level<Grid<Tile<Square>>>>,
+        history: UseStateHandle<History>,
+        cursor: usize,
+    ) -> Callback<MouseEvent> {
+        Callback::from(move |_| {
+            if let Some(level) = history.levels.get(cursor) {
+                log::info!("[Time travel] to state {}", cursor);
+                board.dispatch(BoardAction::Restore(Box::new(level.clone())));
+                history.set(History {
+                    cursor,
+                    ..(*history).clone()
+                });
+            }
+        })
+    }
+
+    if !*visible {
+        return html! {};
+    }
+
+    let cursor = history.cursor;
+    let len = history.levels.len();
+
+    html! {
+        <div class="time-travel">
+            <div class="time-travel-controls">
+                <button
+                    disabled={cursor == 0}
+                    onclick={travel_to(board.clone(), history.clone(), cursor.saturating_sub(1))}>
+                    {"◀"}
+                </button>
+                <span>{format!("{}/{}", cursor + 1, len)}</span>
+                <button
+                    disabled={cursor + 1 >= len}
+                    onclick={travel_to(board.clone(), history.clone(), cursor + 1)}>
+                    {"▶"}
+                </button>
+            </div>
+            <ol>
+                {
+                    history.levels.iter().enumerate().map(|(index, level)| {
+                        html! {
+                            <li
+                                class={classes!((index == cursor).then(|| "time-travel-current"))}
+                                onclick={travel_to(board.clone(), history.clone(), index)}>
+                                {format!("level {} {:?}", level.id, level.events.events)}
+                            </li>
+                        }
+                    }).collect::<Html>()
+                }
+            </ol>
+        </div>
+    }
+}
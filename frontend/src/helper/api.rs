@@ -0,0 +1,364 @@
+use std::fmt::Display;
+
+use futures::channel::mpsc::UnboundedReceiver;
+use futures::future::{select, Either};
+use futures::{pin_mut, SinkExt, StreamExt};
+use gloo_net::http::{Method, Request, Response};
+use gloo_net::websocket::{futures::WebSocket, Message, WebSocketError};
+use gloo_timers::future::TimeoutFuture;
+
+use game::dto::{
+    BenchReportDto, DraftMessageDto, LevelDto, LiveRunRequestDto, PackDto, PresenceDto,
+    SharedLevelDto,
+};
+use game::prelude::{Difficulty, Grid, Square, Tile};
+use game::solver::propagationsolver::Superposition;
+use game::solver::snapshot::SnapshotDecoder;
+
+use crate::helper::local_storage::{retrieve_auth_token, retrieve_offline_mode};
+
+/// number of additional attempts after a failed request
+const RETRIES: u32 = 3;
+
+/// delay before the first retry in milliseconds, doubled for every further retry
+const INITIAL_BACKOFF: u32 = 250;
+
+/// port of the WebSocket listener of the backend streaming live runs and relaying shared drafts, see `live_port` in
+/// its `Rocket.toml`
+const LIVE_PORT: u16 = 8739;
+
+/// failures of backend calls, displayed as messages for the player
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApiError {
+    /// the player enabled the offline mode, no request was sent
+    Offline,
+    /// the backend was unreachable
+    Network,
+    /// the backend requires a valid auth token
+    Unauthorized,
+    /// the backend refused the request with an explanation
+    Rejected(String),
+    /// the backend failed to process a valid request
+    Server,
+    /// the response did not match the expected schema
+    Malformed,
+    /// the response failed the verification of its checksums
+    Tampered,
+}
+
+impl Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiError::Offline => write!(f, "Not available in offline mode"),
+            ApiError::Network => write!(f, "Server unreachable, check your connection"),
+            ApiError::Unauthorized => write!(f, "Please sign in again"),
+            ApiError::Rejected(reason) => write!(f, "Request rejected: {reason}"),
+            ApiError::Server => write!(f, "Server error, please try again later"),
+            ApiError::Malformed => write!(f, "Unexpected response from server"),
+            ApiError::Tampered => write!(f, "Level data has been modified, try another server"),
+        }
+    }
+}
+
+/// typed client for the backend api
+///
+/// every request is skipped in offline mode, carries the stored auth token and is retried
+/// with exponential backoff on network and server errors, therefore only idempotent
+/// endpoints may be wrapped
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiClient {
+    base_url: String,
+}
+
+impl Default for ApiClient {
+    /// backend serving the frontend
+    fn default() -> Self {
+        ApiClient::new("")
+    }
+}
+
+impl ApiClient {
+    pub fn new(base_url: &str) -> Self {
+        ApiClient {
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// `POST /api/gif`, animated clip of the level being solved
+    pub async fn solving_clip(
+        &self,
+        level: &Grid<Tile<Square>>,
+        cell_size: usize,
+        delay: u16,
+    ) -> Result<Vec<u8>, ApiError> {
+        let url = format!(
+            "{}/api/gif?cell_size={cell_size}&delay={delay}",
+            self.base_url
+        );
+        let level = level.to_string();
+        self.send(|| Request::new(&url).method(Method::POST).body(level.as_str()))
+            .await?
+            .binary()
+            .await
+            .map_err(|_| ApiError::Malformed)
+    }
+
+    /// `GET /api/packs/builtin`, builtin levels verified against the ones compiled into the frontend
+    ///
+    /// detects mirrors serving modified levels
+    pub async fn builtin_pack(&self) -> Result<Vec<Grid<Tile<Square>>>, ApiError> {
+        let url = format!("{}/api/packs/builtin", self.base_url);
+        let pack = self
+            .send(|| Request::new(&url))
+            .await?
+            .json::<PackDto>()
+            .await
+            .map_err(|_| ApiError::Malformed)?;
+        if pack.checksum != PackDto::builtin().checksum {
+            return Err(ApiError::Tampered);
+        }
+        pack.verify().map_err(|_| ApiError::Tampered)
+    }
+
+    /// `GET /api/bench`, recorded benchmark reports, oldest first
+    pub async fn bench_history(&self) -> Result<Vec<BenchReportDto>, ApiError> {
+        let url = format!("{}/api/bench", self.base_url);
+        self.send(|| Request::new(&url))
+            .await?
+            .json::<Vec<BenchReportDto>>()
+            .await
+            .map_err(|_| ApiError::Malformed)
+    }
+
+    /// `POST /api/presence/<id>`, heartbeat of this tab, returns the number of players online
+    ///
+    /// repeated heartbeats of the same id count once, so the request may be retried
+    pub async fn heartbeat(&self, id: &str) -> Result<usize, ApiError> {
+        let url = format!("{}/api/presence/{id}", self.base_url);
+        let presence = self
+            .send(|| Request::new(&url).method(Method::POST))
+            .await?
+            .json::<PresenceDto>()
+            .await
+            .map_err(|_| ApiError::Malformed)?;
+        presence.verify().map_err(|_| ApiError::Malformed)?;
+        Ok(presence.players)
+    }
+
+    /// `POST /api/levels`, shares the level across devices and returns it with its id and difficulty
+    ///
+    /// uploading an equal level again yields the stored one, so the request may be retried
+    pub async fn upload_level(&self, level: &LevelDto) -> Result<SharedLevelDto, ApiError> {
+        let url = format!("{}/api/levels", self.base_url);
+        let shared = self
+            .send(|| {
+                Request::new(&url)
+                    .method(Method::POST)
+                    .json(level)
+                    .expect("serializable level")
+            })
+            .await?
+            .json::<SharedLevelDto>()
+            .await
+            .map_err(|_| ApiError::Malformed)?;
+        shared.verify().map_err(|_| ApiError::Malformed)?;
+        Ok(shared)
+    }
+
+    /// `GET /api/levels`, the latest shared levels of the difficulty if given, newest first
+    pub async fn shared_levels(
+        &self,
+        difficulty: Option<Difficulty>,
+    ) -> Result<Vec<SharedLevelDto>, ApiError> {
+        let url = match difficulty {
+            Some(difficulty) => format!("{}/api/levels?difficulty={difficulty}", self.base_url),
+            None => format!("{}/api/levels", self.base_url),
+        };
+        let levels = self
+            .send(|| Request::new(&url))
+            .await?
+            .json::<Vec<SharedLevelDto>>()
+            .await
+            .map_err(|_| ApiError::Malformed)?;
+        levels
+            .iter()
+            .try_for_each(|level| level.verify().map(|_| ()))
+            .map_err(|_| ApiError::Malformed)?;
+        Ok(levels)
+    }
+
+    /// live run computed by the backend, calls `on_snapshot` with every snapshot until the run ends
+    ///
+    /// the run is streamed over a WebSocket on [`LIVE_PORT`] of the backend host and never retried, see [`live_url`]
+    pub async fn live_run<F: FnMut(&Grid<Superposition<Square>>)>(
+        &self,
+        request: &LiveRunRequestDto,
+        mut on_snapshot: F,
+    ) -> Result<(), ApiError> {
+        let (mut sink, mut stream) = self.live_socket("")?.split();
+        let request = serde_json::to_string(request).expect("serializable request");
+        sink.send(Message::Text(request))
+            .await
+            .map_err(|_| ApiError::Network)?;
+        let mut decoder = SnapshotDecoder::new();
+        while let Some(message) = stream.next().await {
+            match message {
+                Ok(Message::Bytes(frame)) => {
+                    on_snapshot(decoder.decode(&frame).map_err(|_| ApiError::Malformed)?)
+                }
+                Ok(Message::Text(reason)) => return Err(ApiError::Rejected(reason)),
+                Err(WebSocketError::ConnectionClose(event)) if event.was_clean => break,
+                Err(_) => return Err(ApiError::Network),
+            }
+        }
+        Ok(())
+    }
+
+    /// edits the shared draft together with the other editors, opening it with the grid unless it is open already
+    ///
+    /// sends the messages of `outgoing` and calls `on_message` with the messages of the other editors until the
+    /// backend or `outgoing` closes the draft. The draft is relayed over a WebSocket like [`ApiClient::live_run`]
+    pub async fn edit_draft<F: FnMut(DraftMessageDto)>(
+        &self,
+        id: &str,
+        grid: &Grid<Tile<Square>>,
+        outgoing: UnboundedReceiver<DraftMessageDto>,
+        mut on_message: F,
+    ) -> Result<(), ApiError> {
+        let (mut sink, mut stream) = self.live_socket(&format!("/drafts/{id}"))?.split();
+        let join = serde_json::to_string(&DraftMessageDto::join(grid)).expect("serializable join");
+        sink.send(Message::Text(join))
+            .await
+            .map_err(|_| ApiError::Network)?;
+        let sending = outgoing
+            .map(|message| {
+                let text = serde_json::to_string(&message).expect("serializable message");
+                Ok(Message::Text(text))
+            })
+            .forward(sink);
+        let receiving = async move {
+            while let Some(message) = stream.next().await {
+                match message {
+                    // rejections are answered with the reason instead of a message
+                    Ok(Message::Text(text)) => match serde_json::from_str(&text) {
+                        Ok(message) => on_message(message),
+                        Err(_) => return Err(ApiError::Rejected(text)),
+                    },
+                    Ok(Message::Bytes(_)) => return Err(ApiError::Malformed),
+                    Err(WebSocketError::ConnectionClose(event)) if event.was_clean => break,
+                    Err(_) => return Err(ApiError::Network),
+                }
+            }
+            Ok(())
+        };
+        pin_mut!(sending, receiving);
+        match select(sending, receiving).await {
+            Either::Left((sent, _)) => sent.map_err(|_| ApiError::Network),
+            Either::Right((received, _)) => received,
+        }
+    }
+
+    /// `GET /data/<path>`, static level data
+    pub async fn data(&self, path: &str) -> Result<String, ApiError> {
+        let url = format!("{}/data/{path}", self.base_url);
+        self.send(|| Request::new(&url))
+            .await?
+            .text()
+            .await
+            .map_err(|_| ApiError::Malformed)
+    }
+
+    /// WebSocket to the path of the live listener, see [`live_url`]
+    fn live_socket(&self, path: &str) -> Result<WebSocket, ApiError> {
+        if retrieve_offline_mode() {
+            return Err(ApiError::Offline);
+        }
+        let location = web_sys::window()
+            .map(|window| window.location())
+            .ok_or(ApiError::Network)?;
+        let (protocol, hostname) = location
+            .protocol()
+            .and_then(|protocol| Ok((protocol, location.hostname()?)))
+            .map_err(|_| ApiError::Network)?;
+        WebSocket::open(&(live_url(&self.base_url, &protocol, &hostname) + path))
+            .map_err(|_| ApiError::Network)
+    }
+
+    async fn send<F: Fn() -> Request>(&self, request: F) -> Result<Response, ApiError> {
+        if retrieve_offline_mode() {
+            return Err(ApiError::Offline);
+        }
+
+        let mut attempt = 0;
+        loop {
+            let result = match with_auth_token(request()).send().await {
+                Ok(response) => check_status(response).await,
+                Err(_) => Err(ApiError::Network),
+            };
+            match result {
+                Err(ApiError::Network | ApiError::Server) if attempt < RETRIES => {
+                    TimeoutFuture::new(INITIAL_BACKOFF << attempt).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+fn with_auth_token(request: Request) -> Request {
+    match retrieve_auth_token() {
+        Some(token) => request.header("Authorization", &format!("Bearer {token}")),
+        None => request,
+    }
+}
+
+async fn check_status(response: Response) -> Result<Response, ApiError> {
+    match response.status() {
+        200..=299 => Ok(response),
+        401 | 403 => Err(ApiError::Unauthorized),
+        400..=499 => Err(ApiError::Rejected(
+            response.text().await.unwrap_or_default(),
+        )),
+        _ => Err(ApiError::Server),
+    }
+}
+
+/// WebSocket URL of the live listener on [`LIVE_PORT`] of the backend
+///
+/// an absolute `base_url` names the backend, otherwise the page location given by its `protocol`, e.g. `https:`,
+/// and `hostname` does. Backends reached over https are reached over wss, so secure pages mix no content
+fn live_url(base_url: &str, protocol: &str, hostname: &str) -> String {
+    let (protocol, hostname) = match base_url.split_once("://") {
+        Some((scheme, rest)) => (scheme, rest.split(['/', ':']).next().unwrap_or_default()),
+        None => (protocol.trim_end_matches(':'), hostname),
+    };
+    let scheme = if protocol == "https" { "wss" } else { "ws" };
+    format!("{scheme}://{hostname}:{LIVE_PORT}")
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{live_url, LIVE_PORT};
+
+    #[test]
+    fn live_runs_follow_the_scheme_and_host_of_the_backend() {
+        assert_eq!(
+            live_url("", "https:", "example.org"),
+            format!("wss://example.org:{LIVE_PORT}")
+        );
+        assert_eq!(
+            live_url("", "http:", "localhost"),
+            format!("ws://localhost:{LIVE_PORT}")
+        );
+        assert_eq!(
+            live_url(
+                "https://backend.example.org:8000/game",
+                "http:",
+                "localhost"
+            ),
+            format!("wss://backend.example.org:{LIVE_PORT}")
+        );
+    }
+}
@@ -1,18 +1,18 @@
-use game::model::coordinate::Coordinate;
-use game::model::gameboard::GameBoard;
-use game::model::grid::Grid;
-use game::model::tile::{Square, Tile};
+use game::prelude::{Coordinate, GameBoard, Grid, Square, Tile};
 
-use rand::Rng;
+use crate::helper::platform::PlatformHandle;
 
 /// randomizes the given level until it is not in a solved state anymore
-pub fn randomize_level(mut level: Grid<Tile<Square>>) -> Grid<Tile<Square>> {
+pub fn randomize_level(
+    mut level: Grid<Tile<Square>>,
+    platform: &PlatformHandle,
+) -> Grid<Tile<Square>> {
     let dimension = level.dimensions();
     let mut is_solved = true;
     while is_solved {
         for row in 0..dimension.row {
             for col in 0..dimension.column {
-                let num = rand::thread_rng().gen_range(0..3);
+                let num = platform.random(3);
                 level = level
                     .rotate_clockwise_n_times(Coordinate::new(row as isize, col as isize), num)
                     .unwrap();
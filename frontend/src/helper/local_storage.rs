@@ -1,67 +1,401 @@
+//! application state saved in the local storage of the browser
+//!
+//! keys are namespaced by the part of the app they belong to, e.g. `settings/win rule`. The layout of the keys is
+//! versioned by [`STORAGE_VERSION`], [`migrate_storage`] upgrades the storage of earlier versions when the app starts
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::str::FromStr;
+
 use super::screen::Screen;
+use super::session::parse_legacy_session;
+use super::stats::Stats;
 
-use game::model::grid::Grid;
+use game::campaign::{CampaignProgress, LevelResult, WINDOW};
+use game::descent::Leaderboard;
 use game::model::parser::{parse_level, unicode_to_tile};
-use game::model::tile::{Square, Tile};
+use game::prelude::{Geometry, Grid, LevelDto, Square, Tile, WinRule};
+use game::savegame::SaveGame;
 use yew::prelude::*;
 
-pub const CURRENT_SCREEN: &str = "screen";
-pub const CURRENT_LEVEL: &str = "level";
-pub const SAVED_LEVEL: &str = "saved level";
-pub const PREVIEW_LEVELS: &str = "preview levels";
+/// version of the key layout, raised with every migration in [`MIGRATIONS`]
+pub const STORAGE_VERSION: u32 = 1;
+pub const STORAGE_VERSION_KEY: &str = "storage version";
+
+/// levels saved in the editor, each under its own key named after the level
+pub const EDITOR_LEVELS: &str = "editor levels/";
+
+pub const CURRENT_SCREEN: &str = "app/screen";
+pub const CURRENT_LEVEL: &str = "app/level";
+pub const CURRENT_LEVEL_WIN_RULE: &str = "app/level win rule";
+pub const SESSION: &str = "app/session";
+pub const AUTH_TOKEN: &str = "app/auth token";
+pub const PREVIEW_LEVELS: &str = "settings/preview levels";
+pub const OFFLINE_MODE: &str = "settings/offline mode";
+pub const ENERGY_FLOW: &str = "settings/energy flow";
+pub const WIN_RULE: &str = "settings/win rule";
+pub const STATS: &str = "campaign/stats";
+pub const CAMPAIGN_RESULTS: &str = "campaign/results";
+pub const CAMPAIGN_CHAPTERS: &str = "campaign/chapters";
+pub const DESCENT_LEADERBOARD: &str = "campaign/descent leaderboard";
+
+/// migrations of the storage, the migration at index `i` upgrades version `i` to version `i + 1`
+const MIGRATIONS: [fn(&mut dyn Store); STORAGE_VERSION as usize] = [namespace_keys];
+
+/// key value store holding the application state
+///
+/// implemented by the local storage of the browser and by maps, e.g. to test migrations
+pub trait Store {
+    fn read(&self, key: &str) -> Option<String>;
+    fn write(&mut self, key: &str, value: &str);
+    fn erase(&mut self, key: &str);
+    fn keys(&self) -> Vec<String>;
+}
+
+impl Store for web_sys::Storage {
+    fn read(&self, key: &str) -> Option<String> {
+        self.get_item(key).ok().flatten()
+    }
+
+    fn write(&mut self, key: &str, value: &str) {
+        self.set_item(key, value).unwrap();
+    }
+
+    fn erase(&mut self, key: &str) {
+        self.remove_item(key).unwrap();
+    }
+
+    fn keys(&self) -> Vec<String> {
+        (0..self.length().unwrap_or(0))
+            .filter_map(|index| self.key(index).ok().flatten())
+            .collect()
+    }
+}
+
+impl Store for BTreeMap<String, String> {
+    fn read(&self, key: &str) -> Option<String> {
+        self.get(key).cloned()
+    }
+
+    fn write(&mut self, key: &str, value: &str) {
+        self.insert(key.to_string(), value.to_string());
+    }
+
+    fn erase(&mut self, key: &str) {
+        self.remove(key);
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.keys().cloned().collect()
+    }
+}
+
+fn local_storage() -> web_sys::Storage {
+    web_sys::window().unwrap().local_storage().unwrap().unwrap()
+}
+
+/// saves the value in its textual form
+fn store<T: Display>(key: &str, value: &T) {
+    local_storage().write(key, value.to_string().as_str());
+}
+
+/// retrieves the value saved under the key, if there is one and it can be parsed
+fn load<T: FromStr>(key: &str) -> Option<T> {
+    local_storage().read(key)?.parse().ok()
+}
+
+/// upgrades the storage written by earlier versions of the app to [`STORAGE_VERSION`]
+pub fn migrate_storage() {
+    migrate(&mut local_storage());
+}
+
+/// applies the migrations from the version of the store on, stores without version have version 0
+///
+/// stores of later versions are left as they are
+pub fn migrate(store: &mut dyn Store) {
+    let version = store
+        .read(STORAGE_VERSION_KEY)
+        .and_then(|version| version.parse().ok())
+        .unwrap_or(0);
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        log::info!("migrating storage from version {from} to {}", from + 1);
+        migration(store);
+        store.write(STORAGE_VERSION_KEY, (from + 1).to_string().as_str());
+    }
+}
+
+/// version 1 moves the flat keys into their namespaces, the single editor level becomes a named level
+/// and the session becomes a [`SaveGame`]
+fn namespace_keys(store: &mut dyn Store) {
+    let moves = [
+        ("screen", CURRENT_SCREEN),
+        ("level", CURRENT_LEVEL),
+        ("level win rule", CURRENT_LEVEL_WIN_RULE),
+        ("auth token", AUTH_TOKEN),
+        ("preview levels", PREVIEW_LEVELS),
+        ("offline mode", OFFLINE_MODE),
+        ("energy flow", ENERGY_FLOW),
+        ("win rule", WIN_RULE),
+        ("stats", STATS),
+        ("campaign", CAMPAIGN_RESULTS),
+        ("descent leaderboard", DESCENT_LEADERBOARD),
+    ];
+    for (from, to) in moves {
+        if let Some(value) = store.read(from) {
+            store.write(to, value.as_str());
+            store.erase(from);
+        }
+    }
+    if let Some(session) = store.read("session") {
+        let save = SaveGame::from_json(&session).or_else(|_| parse_legacy_session(&session));
+        if let Ok(save) = save {
+            store.write(SESSION, save.to_json().as_str());
+        }
+        store.erase("session");
+    }
+    if let Some(level) = store.read("saved level") {
+        let rule = store
+            .read("saved level win rule")
+            .and_then(|rule| rule.parse().ok());
+        if let Ok(grid) = parse_level(level.as_str(), unicode_to_tile) {
+            put_level(store, "Own level 1", &grid, rule);
+        }
+        store.erase("saved level");
+        store.erase("saved level win rule");
+    }
+}
+
+/// saves whether the player disabled all backend calls
+pub fn save_offline_mode(offline: bool) {
+    store(OFFLINE_MODE, &offline);
+}
+
+/// retrieves whether the player disabled all backend calls
+///
+/// the game is fully playable without backend, therefore callers should silently skip
+/// any request if this returns true
+pub fn retrieve_offline_mode() -> bool {
+    load(OFFLINE_MODE).unwrap_or(false)
+}
+
+/// saves whether the energy-flow animation is shown on the board
+pub fn save_energy_flow(enabled: bool) {
+    store(ENERGY_FLOW, &enabled);
+}
+
+/// retrieves whether the energy-flow animation is shown on the board, off by default
+pub fn retrieve_energy_flow() -> bool {
+    load(ENERGY_FLOW).unwrap_or(false)
+}
+
+/// saves the rule deciding when a level counts as solved
+pub fn save_win_rule(rule: WinRule) {
+    store(WIN_RULE, &rule);
+}
+
+/// retrieves the rule deciding when a level counts as solved, matching connections by default
+pub fn retrieve_win_rule() -> WinRule {
+    load(WIN_RULE).unwrap_or_default()
+}
+
+/// saves the token authenticating the player against the backend
+pub fn save_auth_token(token: &str) {
+    store(AUTH_TOKEN, &token);
+}
+
+/// retrieves the token authenticating the player against the backend, if the player has one
+pub fn retrieve_auth_token() -> Option<String> {
+    local_storage().read(AUTH_TOKEN)
+}
+
+/// saves the statistics about the play of the player
+pub fn save_stats(stats: &Stats) {
+    store(STATS, stats);
+}
+
+/// retrieves the statistics about the play of the player, empty statistics if there are none
+pub fn retrieve_stats() -> Stats {
+    load(STATS).unwrap_or_default()
+}
+
+/// appends the result of a solved campaign level, only the latest [`WINDOW`] results are kept
+pub fn save_campaign_result(result: LevelResult) {
+    let mut history = retrieve_campaign_history();
+    history.push(result);
+    let start = history.len().saturating_sub(WINDOW);
+    let lines = history[start..]
+        .iter()
+        .map(LevelResult::to_string)
+        .collect::<Vec<_>>()
+        .join("\n");
+    store(CAMPAIGN_RESULTS, &lines);
+}
+
+/// retrieves the results of the latest campaign levels, oldest first, unreadable results are skipped
+pub fn retrieve_campaign_history() -> Vec<LevelResult> {
+    local_storage()
+        .read(CAMPAIGN_RESULTS)
+        .map(|lines| lines.lines().filter_map(|line| line.parse().ok()).collect())
+        .unwrap_or_default()
+}
+
+/// saves the completions of the chapter levels
+pub fn save_campaign_progress(progress: &CampaignProgress) {
+    store(CAMPAIGN_CHAPTERS, progress);
+}
+
+/// retrieves the completions of the chapter levels, none if there are none or they are unreadable
+pub fn retrieve_campaign_progress() -> CampaignProgress {
+    load(CAMPAIGN_CHAPTERS).unwrap_or_default()
+}
+
+/// saves the best endless descent runs
+pub fn save_descent_leaderboard(leaderboard: &Leaderboard) {
+    store(DESCENT_LEADERBOARD, leaderboard);
+}
+
+/// retrieves the best endless descent runs, an empty leaderboard if there are none
+pub fn retrieve_descent_leaderboard() -> Leaderboard {
+    load(DESCENT_LEADERBOARD).unwrap_or_default()
+}
+
+/// saves the level in progress as versioned [`SaveGame`]
+pub fn save_session(save: &SaveGame) {
+    store(SESSION, &save.to_json());
+}
+
+/// retrieves the level in progress, if the player left one unsolved
+pub fn retrieve_session() -> Option<SaveGame> {
+    SaveGame::from_json(&local_storage().read(SESSION)?).ok()
+}
+
+/// forgets the level in progress, e.g. after it has been solved
+pub fn clear_session() {
+    local_storage().erase(SESSION);
+}
 
 /// saves the number of levels that need to be loaded for the preview to the local storage
 pub fn save_preview_level_count(nr_levels: usize) {
-    let local_storage = web_sys::window().unwrap().local_storage().unwrap().unwrap();
-    local_storage
-        .set_item(PREVIEW_LEVELS, nr_levels.to_string().as_str())
-        .unwrap();
+    store(PREVIEW_LEVELS, &nr_levels);
 }
 
 /// retrieves the number of levels that need to be loaded for the preview from the local storage
 pub fn retrieve_preview_level_count() -> usize {
-    let local_storage = web_sys::window().unwrap().local_storage().unwrap().unwrap();
-    if let Ok(Some(nr_levels)) = local_storage.get_item(PREVIEW_LEVELS) {
-        nr_levels.parse().unwrap()
-    } else {
-        20
-    }
+    load(PREVIEW_LEVELS).unwrap_or(20)
 }
 
-/// saves a level from the editor to the local storage that can later be retrieved
-pub fn save_editor_level(grid: &Grid<Tile<Square>>) {
-    let local_storage = web_sys::window().unwrap().local_storage().unwrap().unwrap();
-    local_storage
-        .set_item(SAVED_LEVEL, grid.to_string().as_str())
-        .unwrap();
+/// level saved in the editor under its name
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct SavedLevel {
+    pub name: String,
+    pub grid: Grid<Tile<Square>>,
+    /// levels saved before the editor offered win rules have none
+    pub rule: Option<WinRule>,
 }
 
-/// retrieves a previously saved editor level from local storage
-pub fn retrieve_editor_level() -> Grid<Tile<Square>> {
-    let local_storage = web_sys::window().unwrap().local_storage().unwrap().unwrap();
-    if let Ok(Some(level)) = local_storage.get_item(SAVED_LEVEL) {
-        parse_level(level.as_str(), unicode_to_tile).unwrap()
-    } else {
-        Grid::EMPTY
+/// saves a level from the editor together with its win rule under its name, replacing a level of the same name
+pub fn save_editor_level(name: &str, grid: &Grid<Tile<Square>>, rule: WinRule) {
+    put_level(&mut local_storage(), name, grid, Some(rule));
+}
+
+/// levels saved in the editor ordered by name, unreadable levels are skipped
+pub fn list_levels() -> Vec<SavedLevel> {
+    levels(&local_storage())
+}
+
+/// forgets the level saved in the editor under the name
+pub fn delete_level(name: &str) {
+    local_storage().erase(&format!("{EDITOR_LEVELS}{name}"));
+}
+
+/// renames a level saved in the editor, fails if there is no such level or the new name is taken
+pub fn rename_level(from: &str, to: &str) -> Result<(), String> {
+    rename(&mut local_storage(), from, to)
+}
+
+/// first name of the form `Own level N` not taken by a saved level
+pub fn next_level_name() -> String {
+    let taken = list_levels()
+        .into_iter()
+        .map(|level| level.name)
+        .collect::<Vec<_>>();
+    (1..)
+        .map(|n| format!("Own level {n}"))
+        .find(|name| !taken.contains(name))
+        .unwrap()
+}
+
+/// saves the level as JSON of its [`LevelDto`]
+fn put_level(store: &mut dyn Store, name: &str, grid: &Grid<Tile<Square>>, rule: Option<WinRule>) {
+    let dto = LevelDto {
+        win_rule: rule.map(|rule| rule.to_string()),
+        ..LevelDto::from(grid)
+    };
+    let json = serde_json::to_string(&dto).expect("Logical error: level is not serializable");
+    store.write(&format!("{EDITOR_LEVELS}{name}"), json.as_str());
+}
+
+fn levels(store: &dyn Store) -> Vec<SavedLevel> {
+    let mut levels = store
+        .keys()
+        .into_iter()
+        .filter_map(|key| {
+            let name = key.strip_prefix(EDITOR_LEVELS)?.to_string();
+            let dto = serde_json::from_str::<LevelDto>(&store.read(&key)?).ok()?;
+            let rule = match dto.win_rule {
+                Some(_) => Some(dto.to_win_rule().ok()?),
+                None => None,
+            };
+            Some(SavedLevel {
+                name,
+                grid: dto.to_grid().ok()?,
+                rule,
+            })
+        })
+        .collect::<Vec<_>>();
+    levels.sort_by(|a, b| a.name.cmp(&b.name));
+    levels
+}
+
+fn rename(store: &mut dyn Store, from: &str, to: &str) -> Result<(), String> {
+    if to.trim().is_empty() {
+        return Err("the name must not be empty".to_string());
+    }
+    if from == to {
+        return Ok(());
+    }
+    let target = format!("{EDITOR_LEVELS}{to}");
+    if store.read(&target).is_some() {
+        return Err(format!("a level named {to} exists already"));
+    }
+    let source = format!("{EDITOR_LEVELS}{from}");
+    let level = store
+        .read(&source)
+        .ok_or_else(|| format!("there is no level named {from}"))?;
+    store.write(&target, level.as_str());
+    store.erase(&source);
+    Ok(())
+}
+
+/// saves the win rule belonging to a level, forgets it if the level has none
+fn save_level_win_rule(key: &str, rule: Option<WinRule>) {
+    match rule {
+        Some(rule) => store(key, &rule),
+        None => local_storage().erase(key),
     }
 }
 
 /// saves a playing level to the local storage that can later be retrieved
 pub fn save_level(grid: &Grid<Tile<Square>>) {
-    let local_storage = web_sys::window().unwrap().local_storage().unwrap().unwrap();
-    local_storage
-        .set_item(CURRENT_LEVEL, &grid.to_string())
-        .unwrap();
+    store(CURRENT_LEVEL, grid);
 }
 
 /// retrieves a previously saved playing level from local storage
 fn retrieve_level() -> Grid<Tile<Square>> {
-    let local_storage = web_sys::window().unwrap().local_storage().unwrap().unwrap();
-    if let Ok(Some(level)) = local_storage.get_item(CURRENT_LEVEL) {
-        parse_level(level.as_str(), unicode_to_tile).unwrap()
-    } else {
-        Grid::EMPTY
-    }
+    local_storage()
+        .read(CURRENT_LEVEL)
+        .and_then(|level| parse_level(level.as_str(), unicode_to_tile).ok())
+        .unwrap_or(Grid::EMPTY)
 }
 
 /// changes the screen of the app and saves it to the local storage
@@ -70,13 +404,16 @@ pub fn change_screen(screen: UseStateHandle<Screen>, to_screen: Screen) {
     screen.set(to_screen);
 }
 
-fn save_screen(saving_screen: &Screen) {
-    let local_storage = web_sys::window().unwrap().local_storage().unwrap().unwrap();
-    local_storage
-        .set_item(CURRENT_SCREEN, &saving_screen.to_string())
-        .unwrap();
-    if let Screen::Level(level) = saving_screen {
-        save_level(level);
+/// saves the screen to the local storage, so reloading the app returns to it
+pub fn save_screen(saving_screen: &Screen) {
+    store(CURRENT_SCREEN, saving_screen);
+    match saving_screen {
+        Screen::Level(level, rule) => {
+            save_level(level);
+            save_level_win_rule(CURRENT_LEVEL_WIN_RULE, *rule);
+        }
+        Screen::Cnf(level) => save_level(level),
+        _ => {}
     }
     log::info!("saved screen: {}", &saving_screen.to_string().as_str());
 }
@@ -85,17 +422,23 @@ fn save_screen(saving_screen: &Screen) {
 ///
 /// default screen is the title screen, in case retrieval fails
 pub fn retrieve_screen() -> Screen {
-    let local_storage = web_sys::window().unwrap().local_storage().unwrap().unwrap();
-    if let Ok(Some(saved_screen)) = local_storage.get_item(CURRENT_SCREEN) {
+    if let Some(saved_screen) = local_storage().read(CURRENT_SCREEN) {
         log::info!("retrieved old screen: {}", saved_screen);
         match saved_screen.as_str() {
-            "level" => Screen::Level(retrieve_level()),
+            "level" => Screen::Level(retrieve_level(), load(CURRENT_LEVEL_WIN_RULE)),
             "overview" => Screen::Overview,
             "title" => Screen::Title,
             "help" => Screen::Help,
             "credit" => Screen::Credit,
             "editor" => Screen::Editor,
             "visualizer" => Screen::Visualizer,
+            "settings" => Screen::Settings,
+            "practice" => Screen::Practice,
+            "descent" => Screen::Descent,
+            "hex" => Screen::Polygon(Geometry::Hex),
+            "triangle" => Screen::Polygon(Geometry::Triangle),
+            "cnf" => Screen::Cnf(retrieve_level()),
+            "bench" => Screen::Bench,
             _ => Screen::Title,
         }
     } else {
@@ -103,3 +446,72 @@ pub fn retrieve_screen() -> Screen {
         Screen::Title
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use std::collections::BTreeMap;
+
+    use game::prelude::{generate, Coordinate, WinRule};
+    use game::savegame::SaveGame;
+
+    use super::{
+        levels, migrate, put_level, rename, Store, CAMPAIGN_RESULTS, SESSION, STORAGE_VERSION,
+        STORAGE_VERSION_KEY, WIN_RULE,
+    };
+
+    #[test]
+    fn migration_namespaces_keys_and_names_the_editor_level() {
+        let level = generate(Coordinate::new(3, 3), 4);
+        let mut store = BTreeMap::new();
+        store.write("win rule", "single loop");
+        store.write("campaign", "easy 4 4 0 1000");
+        store.write("session", &format!("3 1200\n{level}"));
+        store.write("saved level", &level.to_string());
+        store.write("saved level win rule", "single loop");
+        migrate(&mut store);
+
+        assert_eq!(
+            store.read(STORAGE_VERSION_KEY),
+            Some(STORAGE_VERSION.to_string())
+        );
+        assert_eq!(store.read(WIN_RULE).as_deref(), Some("single loop"));
+        assert_eq!(
+            store.read(CAMPAIGN_RESULTS).as_deref(),
+            Some("easy 4 4 0 1000")
+        );
+        let save = SaveGame::from_json(&store.read(SESSION).unwrap()).unwrap();
+        assert_eq!((save.current, save.moves), (level.clone(), 3));
+        let saved = levels(&store);
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].name, "Own level 1");
+        assert_eq!(saved[0].grid, level);
+        assert_eq!(saved[0].rule, Some(WinRule::SingleLoop));
+        assert!(["win rule", "campaign", "session", "saved level"]
+            .iter()
+            .all(|key| store.read(key).is_none()));
+
+        let migrated = store.clone();
+        migrate(&mut store);
+        assert_eq!(store, migrated);
+    }
+
+    #[test]
+    fn levels_are_renamed_unless_the_name_is_taken() {
+        let mut store = BTreeMap::new();
+        put_level(&mut store, "b", &generate(Coordinate::new(2, 2), 1), None);
+        put_level(&mut store, "a", &generate(Coordinate::new(3, 3), 1), None);
+        let names = |store: &BTreeMap<String, String>| {
+            levels(store)
+                .into_iter()
+                .map(|level| level.name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&store), vec!["a", "b"]);
+        assert!(rename(&mut store, "a", "b").is_err());
+        assert!(rename(&mut store, "c", "d").is_err());
+        assert!(rename(&mut store, "a", " ").is_err());
+        assert_eq!(rename(&mut store, "a", "c"), Ok(()));
+        assert_eq!(names(&store), vec!["b", "c"]);
+    }
+}
@@ -1,3 +1,8 @@
+pub mod api;
 pub mod level_randomizer;
 pub mod local_storage;
+pub mod platform;
 pub mod screen;
+pub mod session;
+pub mod share;
+pub mod stats;
@@ -0,0 +1,105 @@
+use std::fmt::Debug;
+use std::ops::Deref;
+use std::rc::Rc;
+
+use rand::Rng;
+use yew::prelude::*;
+
+use game::campaign::next_difficulty;
+use game::prelude::{Difficulty, Grid, Square, Tile, WinRule};
+
+use crate::helper::local_storage::{retrieve_campaign_history, retrieve_win_rule, save_level};
+
+/// side effects required by the reducers
+///
+/// abstracts over the browser, so reducers can be tested without a DOM
+pub trait Platform {
+    /// persists the currently played level
+    fn save(&self, grid: &Grid<Tile<Square>>);
+
+    /// milliseconds since the unix epoch
+    fn now(&self) -> f64;
+
+    /// uniformly distributed random number in `0..upper`
+    fn random(&self, upper: u64) -> u64;
+
+    /// rule chosen by the player, deciding when a level counts as solved
+    fn win_rule(&self) -> WinRule;
+
+    /// difficulty of the next campaign level, adjusted to the recent performance of the player
+    fn campaign_difficulty(&self) -> Difficulty;
+
+    /// reads the message out to screen readers without showing it
+    fn announce(&self, message: &str);
+}
+
+/// the platform used in the browser
+pub struct WebPlatform;
+
+impl Platform for WebPlatform {
+    fn save(&self, grid: &Grid<Tile<Square>>) {
+        save_level(grid);
+    }
+
+    fn now(&self) -> f64 {
+        js_sys::Date::now()
+    }
+
+    fn random(&self, upper: u64) -> u64 {
+        rand::thread_rng().gen_range(0..upper)
+    }
+
+    fn win_rule(&self) -> WinRule {
+        retrieve_win_rule()
+    }
+
+    fn campaign_difficulty(&self) -> Difficulty {
+        next_difficulty(&retrieve_campaign_history())
+    }
+
+    fn announce(&self, message: &str) {
+        let document = web_sys::window().unwrap().document().unwrap();
+        if let Some(region) = document.get_element_by_id("announcement") {
+            region.set_text_content(Some(message));
+        }
+    }
+}
+
+/// shareable reference to a [`Platform`], provided as context by the page router
+///
+/// two handles are equal iff they refer to the same platform
+#[derive(Clone)]
+pub struct PlatformHandle(pub Rc<dyn Platform>);
+
+impl Default for PlatformHandle {
+    fn default() -> Self {
+        PlatformHandle(Rc::new(WebPlatform))
+    }
+}
+
+impl Deref for PlatformHandle {
+    type Target = dyn Platform;
+
+    fn deref(&self) -> &Self::Target {
+        &*self.0
+    }
+}
+
+impl PartialEq for PlatformHandle {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for PlatformHandle {}
+
+impl Debug for PlatformHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "PlatformHandle")
+    }
+}
+
+/// platform from context, falls back to the [`WebPlatform`]
+pub fn use_platform() -> PlatformHandle {
+    use_context::<PlatformHandle>().unwrap_or_default()
+}
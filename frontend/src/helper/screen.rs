@@ -1,28 +1,46 @@
-use game::model::{grid::Grid, tile::Square, tile::Tile};
+use game::prelude::{Geometry, Grid, Square, Tile, WinRule};
 use std::fmt::{Display, Formatter, Result};
 
 /// can be used to indicate which screen needs to be shown
 #[derive(PartialEq)]
 pub enum Screen {
-    Level(Grid<Tile<Square>>),
+    /// level together with its own win rule, the rule chosen in the settings if absent
+    Level(Grid<Tile<Square>>, Option<WinRule>),
     Overview,
     Title,
     Help,
     Credit,
     Editor,
     Visualizer,
+    Settings,
+    Practice,
+    /// time-boxed run through ever smaller and harder levels
+    Descent,
+    /// levels of hexagonal or triangular tiles
+    Polygon(Geometry),
+    /// explains the CNF encoding of the level
+    Cnf(Grid<Tile<Square>>),
+    /// admin page charting the recorded benchmark reports
+    Bench,
 }
 
 impl Display for Screen {
     fn fmt(&self, f: &mut Formatter) -> Result {
         let res = match &self {
-            Screen::Level(_) => "level",
+            // the geometry names the screen, so it can be restored
+            Screen::Polygon(geometry) => return write!(f, "{geometry}"),
+            Screen::Level(..) => "level",
             Screen::Overview => "overview",
             Screen::Title => "title",
             Screen::Help => "help",
             Screen::Credit => "credit",
             Screen::Editor => "editor",
             Screen::Visualizer => "visualizer",
+            Screen::Settings => "settings",
+            Screen::Practice => "practice",
+            Screen::Descent => "descent",
+            Screen::Cnf(_) => "cnf",
+            Screen::Bench => "bench",
         };
         write!(f, "{}", res)
     }
@@ -0,0 +1,292 @@
+use std::{cell::RefCell, rc::Rc};
+
+use yew::prelude::*;
+
+use game::campaign::{locate_level, LevelResult};
+use game::model::parser::{parse_level, unicode_to_tile};
+use game::prelude::{rate, Difficulty, Grid, Square, Tile};
+use game::savegame::SaveGame;
+use game::scoring::{minimal_rotations, Scoring};
+
+use crate::components::reducers::board_events::BoardEvent;
+use crate::components::reducers::board_reducer::Level;
+use crate::helper::local_storage::{
+    clear_session, retrieve_campaign_history, retrieve_campaign_progress, retrieve_session,
+    save_campaign_progress, save_campaign_result, save_session,
+};
+use crate::helper::platform::use_platform;
+
+/// milliseconds formatted as minutes and seconds, e.g. `1:23`
+pub fn minutes_and_seconds(milliseconds: u64) -> String {
+    let seconds = milliseconds / 1000;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// outcome of the level just solved, shown on the summary after solving
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct LevelSummary {
+    /// level as it was scrambled at the start, to replay or share it
+    pub start: Grid<Tile<Square>>,
+    pub difficulty: Difficulty,
+    /// rotations, hints and playing time, the par is only known for levels played from the start
+    pub scoring: Scoring,
+    pub par_known: bool,
+    /// whether the solver did the work
+    pub assisted: bool,
+    /// best score of the recent campaign levels of the same difficulty, before this one
+    pub best_score: Option<u32>,
+}
+
+impl LevelSummary {
+    /// playing time formatted as minutes and seconds
+    pub fn elapsed_time(&self) -> String {
+        minutes_and_seconds(self.scoring.elapsed_milliseconds)
+    }
+
+    /// score of the level, if the player solved it from the start without the solver
+    pub fn score(&self) -> Option<u32> {
+        (self.par_known && !self.assisted).then(|| self.scoring.score())
+    }
+
+    /// whether the score beats the recent campaign levels of the same difficulty
+    pub fn is_best(&self) -> bool {
+        match (self.score(), self.best_score) {
+            (Some(score), Some(best)) => score > best,
+            (Some(_), None) => true,
+            (None, _) => false,
+        }
+    }
+}
+
+/// reads a level in progress saved before save games, which only kept moves, playing time and the level
+///
+/// the first line holds moves and elapsed time, the following lines the level. The scrambled start was not kept,
+/// so the level as it was left counts as start
+pub fn parse_legacy_session(s: &str) -> Result<SaveGame, String> {
+    let invalid = || "parsing error: invalid session".to_string();
+    let (progress, level) = s.split_once('\n').ok_or_else(invalid)?;
+    let (moves, elapsed) = progress.split_once(' ').ok_or_else(invalid)?;
+    Ok(SaveGame {
+        moves: moves.parse().map_err(|_| invalid())?,
+        elapsed_milliseconds: elapsed.parse().map_err(|_| invalid())?,
+        ..SaveGame::new(parse_level(level, unicode_to_tile)?)
+    })
+}
+
+/// moves and start of the level currently played
+struct Progress {
+    level_id: usize,
+    /// level as it was scrambled when the player started playing
+    start: Grid<Tile<Square>>,
+    moves: u32,
+    /// hints used before the session was resumed
+    hints: u32,
+    /// milliseconds since the unix epoch, shifted back by the time of a resumed session
+    started: f64,
+    /// minimal rotations solving the level, unknown for levels without cached solution
+    par: Option<u64>,
+}
+
+impl Progress {
+    fn start(board: &Level<Grid<Tile<Square>>>, now: f64) -> Self {
+        Progress {
+            level_id: board.id,
+            start: board.data.clone(),
+            moves: 0,
+            hints: 0,
+            started: now,
+            par: board
+                .solution
+                .as_ref()
+                .and_then(|solution| minimal_rotations(&board.data, solution)),
+        }
+    }
+}
+
+/// persists the board as [`SaveGame`] after every move and forgets it once the level is solved
+///
+/// if the board starts with the level of the save game, its moves, hints and timer are resumed.
+/// Levels solved without help from the start count as campaign results, see [`game::campaign`],
+/// chapter levels solved without the solver complete the level in the campaign progress.
+/// Returns the [`LevelSummary`] of the solved level until the board changes to an unsolved level again
+pub fn use_session(
+    board: &UseReducerHandle<Level<Grid<Tile<Square>>>>,
+) -> UseStateHandle<Option<LevelSummary>> {
+    let platform = use_platform();
+    let summary = use_state_eq(|| None);
+    let progress = {
+        let board = board.clone();
+        let platform = platform.clone();
+        use_mut_ref(
+            move || match retrieve_session().filter(|save| save.current == board.data) {
+                Some(save) => Progress {
+                    level_id: board.id,
+                    par: board
+                        .solution
+                        .as_ref()
+                        .and_then(|solution| minimal_rotations(&save.start, solution)),
+                    start: save.start,
+                    moves: save.moves,
+                    hints: save.hints,
+                    started: platform.now() - save.elapsed_milliseconds as f64,
+                },
+                None => Progress::start(&board, platform.now()),
+            },
+        )
+    };
+
+    let serial = board.events.serial;
+    let board = board.clone();
+    {
+        let summary = summary.clone();
+        use_effect_with_deps(
+            move |_| {
+                summary.set(record(&progress, &board, platform.now()));
+                || {}
+            },
+            serial,
+        );
+    }
+    summary
+}
+
+/// updates the progress by the latest transition of the board, returns the summary if the level is solved
+fn record(
+    progress: &Rc<RefCell<Progress>>,
+    board: &Level<Grid<Tile<Square>>>,
+    now: f64,
+) -> Option<LevelSummary> {
+    let mut progress = progress.borrow_mut();
+    let restarted = board
+        .events
+        .events
+        .iter()
+        .any(|event| matches!(event, BoardEvent::Generated { .. }));
+    if progress.level_id != board.id || restarted {
+        *progress = Progress::start(board, now);
+    }
+    progress.moves += board
+        .events
+        .events
+        .iter()
+        .filter(|event| matches!(event, BoardEvent::Rotated(_)))
+        .count() as u32;
+
+    let elapsed = (now - progress.started).max(0.0) as u64;
+    let hints = progress.hints + board.hints_given.len() as u32;
+    if board.is_won() {
+        clear_session();
+        let unassisted = board.events.events.iter().any(|event| {
+            matches!(
+                event,
+                BoardEvent::Solved {
+                    assisted: false,
+                    ..
+                }
+            )
+        });
+        let difficulty = rate(&board.data);
+        let scoring = Scoring {
+            par: progress.par.unwrap_or_default(),
+            rotations: progress.moves as u64,
+            hints,
+            elapsed_milliseconds: elapsed,
+        };
+        let best_score = retrieve_campaign_history()
+            .iter()
+            .filter(|result| result.difficulty == difficulty)
+            .map(|result| result.scoring.score())
+            .max();
+        if progress.par.is_some() && unassisted {
+            save_campaign_result(LevelResult {
+                difficulty,
+                scoring,
+            });
+        }
+        let chapter_level = unassisted.then(|| locate_level(&progress.start)).flatten();
+        if let Some((chapter, level)) = chapter_level {
+            let mut campaign = retrieve_campaign_progress();
+            campaign.complete(chapter, level, scoring);
+            save_campaign_progress(&campaign);
+        }
+        return Some(LevelSummary {
+            start: progress.start.clone(),
+            difficulty,
+            scoring,
+            par_known: progress.par.is_some(),
+            assisted: !unassisted,
+            best_score,
+        });
+    }
+    if progress.moves > 0 {
+        save_session(&SaveGame {
+            current: board.data.clone(),
+            start: progress.start.clone(),
+            moves: progress.moves,
+            elapsed_milliseconds: elapsed,
+            hints,
+            ..SaveGame::new(Grid::EMPTY)
+        });
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+
+    use game::prelude::{generate, Coordinate, Difficulty};
+    use game::scoring::{Scoring, MAX_SCORE};
+
+    use super::{minutes_and_seconds, parse_legacy_session, LevelSummary};
+
+    #[test]
+    fn legacy_sessions_migrate_to_save_games() {
+        let level = generate(Coordinate::new(3, 4), 7);
+        let save = parse_legacy_session(&format!("12 83250\n{level}")).unwrap();
+        assert_eq!(save.current, level);
+        assert_eq!(save.start, level);
+        assert_eq!(
+            (save.moves, save.elapsed_milliseconds, save.hints),
+            (12, 83_250, 0)
+        );
+        assert!(parse_legacy_session("12\n┏┓\n┗┛").is_err());
+    }
+
+    #[test]
+    fn elapsed_time_shows_minutes_and_seconds() {
+        assert_eq!(minutes_and_seconds(83_250), "1:23");
+    }
+
+    #[test]
+    fn summary_scores_only_unassisted_levels_with_par() {
+        let summary = LevelSummary {
+            start: generate(Coordinate::new(2, 2), 1),
+            difficulty: Difficulty::Easy,
+            scoring: Scoring {
+                par: 4,
+                rotations: 4,
+                hints: 0,
+                elapsed_milliseconds: 61_000,
+            },
+            par_known: true,
+            assisted: false,
+            best_score: Some(MAX_SCORE - 1),
+        };
+        assert_eq!(summary.elapsed_time(), "1:01");
+        assert_eq!(summary.score(), Some(MAX_SCORE));
+        assert!(summary.is_best());
+
+        let resumed = LevelSummary {
+            par_known: false,
+            ..summary.clone()
+        };
+        assert_eq!(resumed.score(), None);
+        assert!(!resumed.is_best());
+
+        let assisted = LevelSummary {
+            assisted: true,
+            ..summary
+        };
+        assert_eq!(assisted.score(), None);
+    }
+}
@@ -0,0 +1,103 @@
+use js_sys::{Function, Reflect};
+use wasm_bindgen::{JsCast, JsValue};
+
+use game::prelude::{Grid, Square, Tile};
+
+/// query parameter of links carrying the code of a shared level, see [`Grid::to_url_code`]
+pub const LEVEL_PARAMETER: &str = "level";
+
+/// query parameter of links inviting to edit a shared draft, carrying the id of the draft
+pub const DRAFT_PARAMETER: &str = "draft";
+
+/// copies the text to the clipboard of the browser
+///
+/// goes through `navigator.clipboard.writeText`, fails if the browser offers no clipboard
+pub fn copy_to_clipboard(text: &str) -> Result<(), JsValue> {
+    let window = web_sys::window().ok_or_else(|| JsValue::from_str("no window"))?;
+    let navigator = Reflect::get(&window, &JsValue::from_str("navigator"))?;
+    let clipboard = Reflect::get(&navigator, &JsValue::from_str("clipboard"))?;
+    let write_text = Reflect::get(&clipboard, &JsValue::from_str("writeText"))?;
+    write_text
+        .dyn_into::<Function>()?
+        .call1(&clipboard, &JsValue::from_str(text))?;
+    Ok(())
+}
+
+/// link opening the level on the board page of this app
+pub fn share_link(grid: &Grid<Tile<Square>>) -> Result<String, String> {
+    Ok(format!(
+        "{}?{LEVEL_PARAMETER}={}",
+        page_address()?,
+        grid.to_url_code()?
+    ))
+}
+
+/// link opening the shared draft in the editor of this app
+pub fn draft_link(id: &str) -> Result<String, String> {
+    Ok(format!("{}?{DRAFT_PARAMETER}={id}", page_address()?))
+}
+
+fn page_address() -> Result<String, String> {
+    let location = web_sys::window()
+        .ok_or_else(|| "no window".to_string())?
+        .location();
+    location
+        .origin()
+        .and_then(|origin| Ok(origin + &location.pathname()?))
+        .map_err(|_| "no address".to_string())
+}
+
+/// level of the link the app was opened with, if any
+///
+/// the parameter is removed from the address, so reloading the page keeps the progress on the level
+pub fn level_from_link() -> Option<Grid<Tile<Square>>> {
+    Grid::from_url_code(&value_from_link(LEVEL_PARAMETER)?)
+        .map_err(|e| log::warn!("Opening the shared level failed: {e}"))
+        .ok()
+}
+
+/// id of the shared draft of the link the app was opened with, if any
+///
+/// the parameter is removed from the address like the one of [`level_from_link`]
+pub fn draft_from_link() -> Option<String> {
+    value_from_link(DRAFT_PARAMETER)
+}
+
+fn value_from_link(parameter: &str) -> Option<String> {
+    let window = web_sys::window()?;
+    let search = window.location().search().ok()?;
+    let value = parameter_value(&search, parameter)?.to_string();
+    let pathname = window.location().pathname().ok()?;
+    window
+        .history()
+        .and_then(|history| history.replace_state_with_url(&JsValue::NULL, "", Some(&pathname)))
+        .ok();
+    Some(value)
+}
+
+/// value of the parameter in the query string of a link, e.g. the code of the level in `?level=AAIAAmw5`
+fn parameter_value<'a>(search: &'a str, parameter: &str) -> Option<&'a str> {
+    search
+        .trim_start_matches('?')
+        .split('&')
+        .find_map(|pair| pair.strip_prefix(parameter)?.strip_prefix('='))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{parameter_value, DRAFT_PARAMETER, LEVEL_PARAMETER};
+
+    #[test]
+    fn level_codes_are_found_among_other_parameters() {
+        let level_code = |search| parameter_value(search, LEVEL_PARAMETER);
+        assert_eq!(level_code("?level=AAIAAmw5"), Some("AAIAAmw5"));
+        assert_eq!(level_code("?lang=en&level=AAIA-_w5"), Some("AAIA-_w5"));
+        assert_eq!(level_code("?levels=AAIA"), None);
+        assert_eq!(level_code(""), None);
+        assert_eq!(
+            parameter_value("?draft=3f9a0c7d21b4e855", DRAFT_PARAMETER),
+            Some("3f9a0c7d21b4e855")
+        );
+    }
+}
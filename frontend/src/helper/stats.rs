@@ -0,0 +1,104 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::components::reducers::board_events::BoardEvent;
+
+/// statistics about the play of the player, persisted in local storage
+///
+/// the streak counts consecutive levels solved without hints or the solver
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Hash)]
+pub struct Stats {
+    pub streak: u32,
+    pub best_streak: u32,
+    /// whether the player asked for a hint since the last solved or generated level
+    pub hinted: bool,
+}
+
+impl Stats {
+    /// updated statistics after the event
+    pub fn record(self, event: BoardEvent) -> Self {
+        match event {
+            BoardEvent::Solved { assisted, .. } if assisted || self.hinted => Stats {
+                streak: 0,
+                hinted: false,
+                ..self
+            },
+            BoardEvent::Solved { .. } => Stats {
+                streak: self.streak + 1,
+                best_streak: self.best_streak.max(self.streak + 1),
+                hinted: false,
+            },
+            BoardEvent::HintShown(_) => Stats {
+                hinted: true,
+                ..self
+            },
+            BoardEvent::Generated { .. } => Stats {
+                hinted: false,
+                ..self
+            },
+            BoardEvent::Rotated(_) => self,
+        }
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {}", self.streak, self.best_streak, self.hinted)
+    }
+}
+
+impl FromStr for Stats {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("parsing error: invalid stats '{s}'");
+        let mut fields = s.split(' ');
+        let mut next = || fields.next().ok_or_else(invalid);
+        Ok(Stats {
+            streak: next()?.parse().map_err(|_| invalid())?,
+            best_streak: next()?.parse().map_err(|_| invalid())?,
+            hinted: next()?.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use game::prelude::Coordinate;
+
+    use crate::components::reducers::board_events::BoardEvent;
+
+    use super::Stats;
+
+    const SOLVED: BoardEvent = BoardEvent::Solved {
+        level: 1,
+        assisted: false,
+    };
+
+    #[test]
+    fn unassisted_solves_extend_streak() {
+        let stats = Stats::default().record(SOLVED).record(SOLVED);
+        assert_eq!(stats.streak, 2);
+        assert_eq!(stats.best_streak, 2);
+    }
+
+    #[test]
+    fn hint_breaks_streak_but_keeps_best() {
+        let stats = Stats::default()
+            .record(SOLVED)
+            .record(BoardEvent::HintShown(Coordinate::new(0, 0)))
+            .record(SOLVED);
+        assert_eq!(stats.streak, 0);
+        assert_eq!(stats.best_streak, 1);
+    }
+
+    #[test]
+    fn display_then_parse_is_identity() {
+        let stats = Stats {
+            streak: 3,
+            best_streak: 7,
+            hinted: true,
+        };
+        assert_eq!(stats.to_string().parse(), Ok(stats));
+    }
+}
@@ -5,11 +5,14 @@ use yew::html;
 use yew::prelude::*;
 
 use crate::components::pages::page_router::PageRouter;
+use crate::components::utils::tile_image::ThemeStyle;
+use crate::helper::local_storage::migrate_storage;
 
 #[function_component(App)]
 fn app() -> Html {
     html! {
         <>
+            <ThemeStyle />
             <div id="title">{"Rusty infinity loop!"}</div>
             <PageRouter />
             <div id="footer">
@@ -24,5 +27,6 @@ fn app() -> Html {
 
 fn main() {
     wasm_logger::init(wasm_logger::Config::new(log::Level::Info));
+    migrate_storage();
     yew::start_app::<App>();
 }
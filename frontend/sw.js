@@ -0,0 +1,66 @@
+// service worker making the game fully playable without a network connection
+//
+// strategy
+// - precache the application shell on install
+// - answer same-origin GET requests cache-first and add missing responses to the cache
+// - never cache `/api/` requests, the frontend decides itself how to handle failed backend calls
+
+const CACHE_NAME = "infinity-loop-v3";
+
+const APP_SHELL = [
+  "./",
+  "index.html",
+  "manifest.json",
+  "icon.svg",
+];
+
+self.addEventListener("install", (event) => {
+  event.waitUntil(
+    caches
+      .open(CACHE_NAME)
+      .then((cache) => cache.addAll(APP_SHELL))
+      .then(() => self.skipWaiting())
+  );
+});
+
+// delete caches of previous versions
+self.addEventListener("activate", (event) => {
+  event.waitUntil(
+    caches
+      .keys()
+      .then((names) =>
+        Promise.all(
+          names
+            .filter((name) => name !== CACHE_NAME)
+            .map((name) => caches.delete(name))
+        )
+      )
+      .then(() => self.clients.claim())
+  );
+});
+
+self.addEventListener("fetch", (event) => {
+  const url = new URL(event.request.url);
+  if (
+    event.request.method !== "GET" ||
+    url.origin !== self.location.origin ||
+    url.pathname.startsWith("/api/")
+  ) {
+    return;
+  }
+
+  // trunk hashes the names of the wasm and js bundles, therefore they are cached on first use
+  event.respondWith(
+    caches.match(event.request).then(
+      (cached) =>
+        cached ||
+        fetch(event.request).then((response) => {
+          if (response.ok) {
+            const copy = response.clone();
+            caches.open(CACHE_NAME).then((cache) => cache.put(event.request, copy));
+          }
+          return response;
+        })
+    )
+  );
+});
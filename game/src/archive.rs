@@ -0,0 +1,376 @@
+//! Level archives are zip files bundling a [`LevelPack`] with thumbnails and replays as a single `.ilpack` file
+//!
+//! ## Layout
+//!
+//! | Entry                  | Content                                                             |
+//! |:-----------------------|:--------------------------------------------------------------------|
+//! | `manifest.json`        | name, author and every level with its entries, see [`Manifest`]    |
+//! | `levels/<n>.level`     | level in the packed format, see [`write_packed_level`]              |
+//! | `thumbnails/<n>.gif`   | optional preview image of the level                                 |
+//! | `replays/<n>.json`     | optional rotations of a player solving the level, see [`Replay`]    |
+//!
+//! `<n>` is the position of the level in the pack with four digits. Archives are read by the entries named in the manifest,
+//! so other entries are ignored
+
+use std::io::{Cursor, Read, Seek, Write};
+
+use serde::{Deserialize, Serialize};
+use zip::{write::FileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::levelpack::{read_packed_level, write_packed_level, LevelPack, PackedLevel};
+use crate::model::{
+    accesserror::AccessError,
+    coordinate::Coordinate,
+    gameboard::GameBoard,
+    grid::Grid,
+    symmetry::content_checksum,
+    tile::{Square, Tile},
+};
+
+/// file extension of level archives
+pub const EXTENSION: &str = "ilpack";
+
+/// current version of the manifest written by this crate
+pub const MANIFEST_VERSION: u32 = 1;
+
+const MANIFEST: &str = "manifest.json";
+
+/// Table of contents of an archive
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Manifest {
+    pub version: u32,
+    pub name: String,
+    pub author: String,
+    pub levels: Vec<ManifestLevel>,
+}
+
+/// Entries of a single level in an archive
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ManifestLevel {
+    /// entry of the packed level
+    pub level: String,
+    /// easy, medium or hard
+    pub difficulty: String,
+    /// [content checksum](content_checksum) of the level as 16 hexadecimal digits
+    pub checksum: String,
+    #[serde(default)]
+    pub thumbnail: Option<String>,
+    #[serde(default)]
+    pub replay: Option<String>,
+}
+
+/// Single rotation of a tile made by a player
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ReplayMove {
+    pub row: isize,
+    pub column: isize,
+    pub clockwise: bool,
+}
+
+/// Rotations of a player solving a level in the order they were made
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct Replay {
+    pub moves: Vec<ReplayMove>,
+    pub elapsed_milliseconds: u64,
+}
+
+impl Replay {
+    /// Performs the moves on the level
+    pub fn apply(&self, grid: &Grid<Tile<Square>>) -> Result<Grid<Tile<Square>>, AccessError> {
+        self.moves.iter().try_fold(grid.clone(), |grid, m| {
+            let index = Coordinate::new(m.row, m.column);
+            if m.clockwise {
+                grid.rotate_clockwise(index)
+            } else {
+                grid.rotate_counterclockwise(index)
+            }
+        })
+    }
+}
+
+/// Level of an archive together with its optional attachments
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ArchivedLevel {
+    pub level: PackedLevel,
+    /// encoded image, e.g. rendered with [`rasterize`](crate::render::raster::rasterize)
+    pub thumbnail: Option<Vec<u8>>,
+    pub replay: Option<Replay>,
+}
+
+/// Level pack with thumbnails and replays
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LevelArchive {
+    pub name: String,
+    pub author: String,
+    pub levels: Vec<ArchivedLevel>,
+}
+
+impl From<LevelPack> for LevelArchive {
+    fn from(pack: LevelPack) -> Self {
+        LevelArchive {
+            levels: pack
+                .iter()
+                .map(|level| ArchivedLevel {
+                    level: level.clone(),
+                    thumbnail: None,
+                    replay: None,
+                })
+                .collect(),
+            name: pack.name,
+            author: pack.author,
+        }
+    }
+}
+
+impl LevelArchive {
+    /// Level pack without thumbnails and replays
+    pub fn to_pack(&self) -> LevelPack {
+        let mut pack = LevelPack::new(&self.name, &self.author);
+        for archived in &self.levels {
+            pack.push_rated(archived.level.clone());
+        }
+        pack
+    }
+
+    /// Writes the archive in the `.ilpack` format
+    pub fn save<W: Write + Seek>(&self, writer: W) -> Result<(), String> {
+        let mut zip = ZipWriter::new(writer);
+        let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+        let writing_error = |e: zip::result::ZipError| format!("writing error: {e}");
+        let mut manifest = Manifest {
+            version: MANIFEST_VERSION,
+            name: self.name.clone(),
+            author: self.author.clone(),
+            levels: vec![],
+        };
+
+        for (index, archived) in self.levels.iter().enumerate() {
+            let level = format!("levels/{index:04}.level");
+            zip.start_file(&level, options).map_err(writing_error)?;
+            write_packed_level(&archived.level.grid, &mut zip)?;
+
+            let thumbnail = match &archived.thumbnail {
+                Some(image) => {
+                    let name = format!("thumbnails/{index:04}.gif");
+                    zip.start_file(&name, options).map_err(writing_error)?;
+                    write_entry(&mut zip, image)?;
+                    Some(name)
+                }
+                None => None,
+            };
+
+            let replay = match &archived.replay {
+                Some(replay) => {
+                    let name = format!("replays/{index:04}.json");
+                    zip.start_file(&name, options).map_err(writing_error)?;
+                    serde_json::to_writer(&mut zip, replay)
+                        .map_err(|e| format!("writing error: {e}"))?;
+                    Some(name)
+                }
+                None => None,
+            };
+
+            manifest.levels.push(ManifestLevel {
+                level,
+                difficulty: archived.level.difficulty.to_string(),
+                checksum: format!("{:016x}", content_checksum(&archived.level.grid)),
+                thumbnail,
+                replay,
+            });
+        }
+
+        zip.start_file(MANIFEST, options).map_err(writing_error)?;
+        serde_json::to_writer_pretty(&mut zip, &manifest)
+            .map_err(|e| format!("writing error: {e}"))?;
+        zip.finish().map_err(writing_error)?;
+        Ok(())
+    }
+
+    /// Reads an archive in the `.ilpack` format
+    ///
+    /// every level must match the checksum in the manifest
+    pub fn load<R: Read + Seek>(reader: R) -> Result<Self, String> {
+        let mut zip = ZipArchive::new(reader)
+            .map_err(|_| "parsing error: not a level archive".to_string())?;
+        let manifest: Manifest = serde_json::from_reader(entry(&mut zip, MANIFEST)?)
+            .map_err(|e| format!("parsing error: invalid manifest: {e}"))?;
+        if manifest.version != MANIFEST_VERSION {
+            return Err(format!(
+                "parsing error: unsupported manifest version {}, expected {MANIFEST_VERSION}",
+                manifest.version
+            ));
+        }
+
+        let mut levels = vec![];
+        for entries in &manifest.levels {
+            let grid = read_packed_level(entry(&mut zip, &entries.level)?)?;
+            if format!("{:016x}", content_checksum(&grid)) != entries.checksum {
+                return Err(format!(
+                    "parsing error: checksum mismatch of {}",
+                    entries.level
+                ));
+            }
+            let thumbnail = match &entries.thumbnail {
+                Some(name) => {
+                    let mut image = vec![];
+                    entry(&mut zip, name)?
+                        .read_to_end(&mut image)
+                        .map_err(|e| format!("reading error: {e}"))?;
+                    Some(image)
+                }
+                None => None,
+            };
+            let replay = match &entries.replay {
+                Some(name) => Some(
+                    serde_json::from_reader(entry(&mut zip, name)?)
+                        .map_err(|e| format!("parsing error: invalid replay {name}: {e}"))?,
+                ),
+                None => None,
+            };
+            levels.push(ArchivedLevel {
+                level: PackedLevel {
+                    grid,
+                    difficulty: entries.difficulty.parse()?,
+                },
+                thumbnail,
+                replay,
+            });
+        }
+
+        Ok(LevelArchive {
+            name: manifest.name,
+            author: manifest.author,
+            levels,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        let mut bytes = Cursor::new(vec![]);
+        self.save(&mut bytes)?;
+        Ok(bytes.into_inner())
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        LevelArchive::load(Cursor::new(bytes))
+    }
+}
+
+fn entry<'a, R: Read + Seek>(
+    zip: &'a mut ZipArchive<R>,
+    name: &str,
+) -> Result<zip::read::ZipFile<'a>, String> {
+    zip.by_name(name)
+        .map_err(|_| format!("parsing error: missing entry {name}"))
+}
+
+fn write_entry<W: Write>(writer: &mut W, bytes: &[u8]) -> Result<(), String> {
+    writer
+        .write_all(bytes)
+        .map_err(|e| format!("writing error: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::io::{Cursor, Write};
+
+    use zip::{write::FileOptions, ZipWriter};
+
+    use crate::generator::levelstream::builtin_levels;
+    use crate::levelpack::LevelPack;
+    use crate::model::{
+        gameboard::GameBoard,
+        grid::Grid,
+        tile::{Square, Tile},
+    };
+
+    use super::{LevelArchive, Replay, ReplayMove};
+
+    #[quickcheck]
+    fn save_then_load_is_identity(
+        levels: Vec<Grid<Tile<Square>>>,
+        thumbnails: Vec<Option<Vec<u8>>>,
+        elapsed: Vec<u64>,
+    ) -> bool {
+        let mut pack = LevelPack::new("pack", "author");
+        pack.extend(
+            levels
+                .into_iter()
+                .take(4)
+                .map(|grid| Grid::init(grid.dimensions().map(|x| x.min(6)), |c| grid[c])),
+        );
+        let mut archive = LevelArchive::from(pack);
+        for (index, archived) in archive.levels.iter_mut().enumerate() {
+            archived.thumbnail = thumbnails.get(index).cloned().flatten();
+            archived.replay = elapsed.get(index).map(|&elapsed_milliseconds| Replay {
+                moves: vec![],
+                elapsed_milliseconds,
+            });
+        }
+        archive
+            .to_bytes()
+            .and_then(|bytes| LevelArchive::from_bytes(&bytes))
+            == Ok(archive)
+    }
+
+    #[test]
+    fn replays_reproduce_the_solution() {
+        let solved = builtin_levels().next().expect("builtin level");
+        let moves = solved
+            .coordinates()
+            .map(|index| ReplayMove {
+                row: index.row,
+                column: index.column,
+                clockwise: false,
+            })
+            .collect::<Vec<_>>();
+        let mut scrambled = solved.clone();
+        for index in solved.coordinates() {
+            scrambled = scrambled.rotate_clockwise(index).unwrap();
+        }
+        let replay = Replay {
+            moves,
+            elapsed_milliseconds: 0,
+        };
+        assert_eq!(replay.apply(&scrambled), Ok(solved));
+    }
+
+    #[test]
+    fn tampered_levels_are_rejected() {
+        let mut pack = LevelPack::new("builtin", "");
+        pack.extend(builtin_levels().take(2));
+        let bytes = LevelArchive::from(pack).to_bytes().unwrap();
+
+        // rewrites the first level with the second one, keeping the manifest
+        let mut original = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+        let mut tampered = ZipWriter::new(Cursor::new(vec![]));
+        for index in 0..original.len() {
+            let file = original.by_index(index).unwrap();
+            let name = file.name().to_string();
+            drop(file);
+            let source = if name == "levels/0000.level" {
+                "levels/0001.level"
+            } else {
+                name.as_str()
+            };
+            let mut content = vec![];
+            std::io::copy(&mut original.by_name(source).unwrap(), &mut content).unwrap();
+            tampered.start_file(&name, FileOptions::default()).unwrap();
+            tampered.write_all(&content).unwrap();
+        }
+        let tampered = tampered.finish().unwrap().into_inner();
+
+        assert_eq!(
+            LevelArchive::from_bytes(&tampered),
+            Err("parsing error: checksum mismatch of levels/0000.level".into())
+        );
+    }
+
+    #[test]
+    fn other_files_are_rejected() {
+        assert_eq!(
+            LevelArchive::from_bytes(b"ILPK"),
+            Err("parsing error: not a level archive".into())
+        );
+    }
+}
@@ -0,0 +1,88 @@
+//! Scoring level packs on all cores
+//!
+//! levels are distributed across the threads of the global rayon pool, results keep the order of the levels.
+//! Native-only, as the web build has no threads
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rayon::prelude::*;
+
+use crate::model::{
+    grid::Grid,
+    tile::{Square, Tile},
+};
+use crate::solver::difficulty::{rate, Difficulty};
+
+/// level of a pack
+pub type Level = Grid<Tile<Square>>;
+
+/// Applies the function to all levels in parallel, the levels may be in any representation, e.g. unparsed files
+///
+/// `progress` is called with the number of finished levels and the number of all levels after every level.
+/// As levels finish on different threads, calls may overlap and arrive out of order
+pub fn map_pack<L, T, F, P>(levels: &[L], f: F, progress: P) -> Vec<T>
+where
+    L: Sync,
+    T: Send,
+    F: Fn(&L) -> T + Sync,
+    P: Fn(usize, usize) + Sync,
+{
+    let finished = AtomicUsize::new(0);
+    levels
+        .par_iter()
+        .map(|level| {
+            let result = f(level);
+            progress(finished.fetch_add(1, Ordering::Relaxed) + 1, levels.len());
+            result
+        })
+        .collect()
+}
+
+/// Rates the difficulty of all levels in parallel, see [`rate`]
+pub fn score_pack(levels: &[Level]) -> Vec<Difficulty> {
+    score_pack_with_progress(levels, |_, _| {})
+}
+
+/// Rates the difficulty of all levels in parallel and reports the progress, see [`map_pack`]
+pub fn score_pack_with_progress<P: Fn(usize, usize) + Sync>(
+    levels: &[Level],
+    progress: P,
+) -> Vec<Difficulty> {
+    map_pack(levels, rate, progress)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::sync::Mutex;
+
+    use crate::core::interval::Max;
+    use crate::generator::fastgen::generate;
+    use crate::model::coordinate::Coordinate;
+    use crate::solver::difficulty::rate;
+
+    use super::{score_pack, score_pack_with_progress};
+
+    #[quickcheck]
+    fn scores_agree_with_sequential_rating(dimension: Coordinate<Max<8>>, seeds: Vec<u64>) -> bool {
+        let levels = seeds
+            .into_iter()
+            .map(|seed| generate(dimension.map(Max::to_usize), seed))
+            .collect::<Vec<_>>();
+        score_pack(&levels) == levels.iter().map(rate).collect::<Vec<_>>()
+    }
+
+    #[test]
+    fn progress_is_reported_for_every_level() {
+        let levels = (0..20)
+            .map(|seed| generate(Coordinate::new(6, 6), seed))
+            .collect::<Vec<_>>();
+        let reported = Mutex::new(vec![]);
+        score_pack_with_progress(&levels, |finished, total| {
+            reported.lock().unwrap().push((finished, total))
+        });
+        let mut reported = reported.into_inner().unwrap();
+        reported.sort();
+        assert_eq!(reported, (1..=20).map(|n| (n, 20)).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,451 @@
+//! Chapters and dynamic difficulty of the campaign
+//!
+//! the campaign follows the performance of the player over the latest levels: solving close to par, fast and
+//! without hints raises the difficulty of the next level, many excess rotations or hints lower it.
+//! [`next_difficulty`] only depends on the given history, persisting the history is up to the frontend
+//!
+//! besides, the campaign is divided into [`CHAPTERS`] of levels generated from seeds, so they are not shipped
+//! with the binary. A chapter unlocks once enough levels of the previous chapter are finished, the best completion
+//! of every level is kept in [`CampaignProgress`]
+
+use std::collections::BTreeMap;
+use std::{fmt::Display, str::FromStr};
+
+use crate::generator::fastgen::generate;
+use crate::model::{
+    coordinate::Coordinate,
+    grid::Grid,
+    tile::{Square, Tile},
+};
+use crate::scoring::Scoring;
+use crate::solver::difficulty::{rate, Difficulty};
+
+/// number of latest results the difficulty is adjusted to, older results may be dropped
+pub const WINDOW: usize = 5;
+
+/// results at the current difficulty needed before adjusting it again
+pub const MIN_RESULTS: usize = 3;
+
+/// average rotations per rotation of par above which the player struggles
+const STRUGGLING_RATIO: f64 = 2.0;
+
+/// average rotations per rotation of par up to which the player masters the difficulty
+const MASTERY_RATIO: f64 = 1.25;
+
+/// average milliseconds per rotation of par up to which the player masters the difficulty
+const MASTERY_PACE: f64 = 3000.0;
+
+/// number of generated levels searched for one of the target difficulty
+const ATTEMPTS: u64 = 20;
+
+/// Chapters of the campaign in the order they are unlocked
+pub const CHAPTERS: [Chapter; 5] = [
+    Chapter {
+        name: "First loops",
+        dimension: Coordinate { row: 3, column: 3 },
+        seed: 1000,
+        levels: 8,
+        unlock: 0,
+    },
+    Chapter {
+        name: "Warming up",
+        dimension: Coordinate { row: 4, column: 4 },
+        seed: 2000,
+        levels: 10,
+        unlock: 5,
+    },
+    Chapter {
+        name: "Tangles",
+        dimension: Coordinate { row: 5, column: 5 },
+        seed: 3000,
+        levels: 10,
+        unlock: 6,
+    },
+    Chapter {
+        name: "Wide awake",
+        dimension: Coordinate { row: 6, column: 8 },
+        seed: 4000,
+        levels: 10,
+        unlock: 7,
+    },
+    Chapter {
+        name: "Endless knots",
+        dimension: Coordinate { row: 8, column: 10 },
+        seed: 5000,
+        levels: 12,
+        unlock: 8,
+    },
+];
+
+/// Chapter of the campaign, its levels are generated from consecutive seeds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Chapter {
+    pub name: &'static str,
+    pub dimension: Coordinate<usize>,
+    /// seed of the first level
+    pub seed: u64,
+    pub levels: usize,
+    /// finished levels of the previous chapter needed to unlock this chapter
+    pub unlock: usize,
+}
+
+impl Chapter {
+    /// level of the chapter at the index, if the chapter has as many levels
+    pub fn level(&self, index: usize) -> Option<Grid<Tile<Square>>> {
+        (index < self.levels).then(|| generate(self.dimension, self.seed + index as u64))
+    }
+
+    pub fn all_levels(&self) -> impl Iterator<Item = Grid<Tile<Square>>> + '_ {
+        (0..self.levels).filter_map(|index| self.level(index))
+    }
+}
+
+/// Chapter and index of the campaign level, the tiles of the level may be rotated in any way
+pub fn locate_level(level: &Grid<Tile<Square>>) -> Option<(usize, usize)> {
+    CHAPTERS
+        .iter()
+        .enumerate()
+        .filter(|(_, chapter)| chapter.dimension == level.dimensions())
+        .find_map(|(chapter_index, chapter)| {
+            chapter
+                .all_levels()
+                .position(|candidate| level.scramble_distance(&candidate).is_some())
+                .map(|index| (chapter_index, index))
+        })
+}
+
+/// Best completions of the levels of the [`CHAPTERS`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct CampaignProgress {
+    /// scoring by chapter and level index
+    completions: BTreeMap<(usize, usize), Scoring>,
+}
+
+impl CampaignProgress {
+    /// records the completion of a level, only the best score of a level is kept
+    pub fn complete(&mut self, chapter: usize, level: usize, scoring: Scoring) {
+        let best = self.completions.entry((chapter, level)).or_insert(scoring);
+        if scoring.score() > best.score() {
+            *best = scoring;
+        }
+    }
+
+    /// best completion of the level, if it has been finished
+    pub fn completion(&self, chapter: usize, level: usize) -> Option<Scoring> {
+        self.completions.get(&(chapter, level)).copied()
+    }
+
+    /// number of finished levels of the chapter
+    pub fn finished(&self, chapter: usize) -> usize {
+        self.completions
+            .range((chapter, 0)..(chapter + 1, 0))
+            .count()
+    }
+
+    /// the first chapter is always unlocked, the others once enough levels of the previous chapter are finished
+    pub fn is_unlocked(&self, chapter: usize) -> bool {
+        match chapter.checked_sub(1) {
+            None => true,
+            Some(previous) => CHAPTERS
+                .get(chapter)
+                .map_or(false, |c| self.finished(previous) >= c.unlock),
+        }
+    }
+}
+
+/// one completion per line: chapter, level, par, rotations, hints and elapsed milliseconds separated by spaces
+impl Display for CampaignProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let lines = self
+            .completions
+            .iter()
+            .map(|((chapter, level), scoring)| {
+                let Scoring {
+                    par,
+                    rotations,
+                    hints,
+                    elapsed_milliseconds,
+                } = scoring;
+                format!("{chapter} {level} {par} {rotations} {hints} {elapsed_milliseconds}")
+            })
+            .collect::<Vec<_>>();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl FromStr for CampaignProgress {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let completions = s
+            .lines()
+            .map(|line| {
+                let invalid = || format!("parsing error: invalid completion '{line}'");
+                let fields = line
+                    .split(' ')
+                    .map(|field| field.parse::<u64>().map_err(|_| invalid()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                match fields[..] {
+                    [chapter, level, par, rotations, hints, elapsed_milliseconds] => Ok((
+                        (chapter as usize, level as usize),
+                        Scoring {
+                            par,
+                            rotations,
+                            hints: u32::try_from(hints).map_err(|_| invalid())?,
+                            elapsed_milliseconds,
+                        },
+                    )),
+                    _ => Err(invalid()),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(CampaignProgress { completions })
+    }
+}
+
+/// Outcome of a solved campaign level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LevelResult {
+    pub difficulty: Difficulty,
+    pub scoring: Scoring,
+}
+
+impl LevelResult {
+    /// rotations per rotation of par, 1 when solved at par
+    fn rotation_ratio(&self) -> f64 {
+        self.scoring.rotations.max(1) as f64 / self.scoring.par.max(1) as f64
+    }
+
+    /// milliseconds per rotation of par
+    fn pace(&self) -> f64 {
+        self.scoring.elapsed_milliseconds as f64 / self.scoring.par.max(1) as f64
+    }
+}
+
+/// difficulty, par, rotations, hints and elapsed milliseconds separated by spaces, e.g. `medium 12 15 1 34000`
+impl Display for LevelResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Scoring {
+            par,
+            rotations,
+            hints,
+            elapsed_milliseconds,
+        } = self.scoring;
+        write!(
+            f,
+            "{} {par} {rotations} {hints} {elapsed_milliseconds}",
+            self.difficulty
+        )
+    }
+}
+
+impl FromStr for LevelResult {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("parsing error: invalid level result '{s}'");
+        let mut fields = s.split(' ');
+        let mut next = || fields.next().ok_or_else(invalid);
+        Ok(LevelResult {
+            difficulty: next()?.parse()?,
+            scoring: Scoring {
+                par: next()?.parse().map_err(|_| invalid())?,
+                rotations: next()?.parse().map_err(|_| invalid())?,
+                hints: next()?.parse().map_err(|_| invalid())?,
+                elapsed_milliseconds: next()?.parse().map_err(|_| invalid())?,
+            },
+        })
+    }
+}
+
+/// Difficulty of the next campaign level, given the results of the previous levels in chronological order
+///
+/// the difficulty of the latest level is kept until [`MIN_RESULTS`] results at that difficulty are known,
+/// then the averages over at most [`WINDOW`] of them decide:
+/// * hints in most levels or more than [`STRUGGLING_RATIO`] rotations per rotation of par lower the difficulty
+/// * no hints, at most [`MASTERY_RATIO`] rotations and [`MASTERY_PACE`] milliseconds per rotation of par raise it
+pub fn next_difficulty(history: &[LevelResult]) -> Difficulty {
+    let current = match history.last() {
+        Some(latest) => latest.difficulty,
+        None => return Difficulty::Easy,
+    };
+    let recent = history
+        .iter()
+        .rev()
+        .take_while(|result| result.difficulty == current)
+        .take(WINDOW)
+        .collect::<Vec<_>>();
+    if recent.len() < MIN_RESULTS {
+        return current;
+    }
+    let count = recent.len() as f64;
+    let hints = recent
+        .iter()
+        .map(|result| result.scoring.hints as u64)
+        .sum::<u64>();
+    let ratio = recent.iter().map(|r| r.rotation_ratio()).sum::<f64>() / count;
+    let pace = recent.iter().map(|r| r.pace()).sum::<f64>() / count;
+    if hints as f64 >= count / 2.0 || ratio > STRUGGLING_RATIO {
+        current.easier()
+    } else if hints == 0 && ratio <= MASTERY_RATIO && pace <= MASTERY_PACE {
+        current.harder()
+    } else {
+        current
+    }
+}
+
+/// Generates a level of the dimension, preferably of the difficulty
+///
+/// searches [`ATTEMPTS`] seeds derived from the given one, falls back to the level closest to the difficulty
+pub fn generate_campaign_level(
+    dimension: Coordinate<usize>,
+    difficulty: Difficulty,
+    seed: u64,
+) -> Grid<Tile<Square>> {
+    (0..ATTEMPTS)
+        .map(|attempt| generate(dimension, seed.wrapping_mul(ATTEMPTS).wrapping_add(attempt)))
+        .min_by_key(|level| distance(level, difficulty))
+        .unwrap_or_else(|| generate(dimension, seed))
+}
+
+/// number of steps between the difficulty of the level and the given one
+fn distance(level: &Grid<Tile<Square>>, difficulty: Difficulty) -> u32 {
+    (rate(level) as i32 - difficulty as i32).unsigned_abs()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::core::interval::{Interval, Max};
+    use crate::generator::fastgen::generate;
+    use crate::model::coordinate::Coordinate;
+    use crate::scoring::Scoring;
+    use crate::solver::difficulty::Difficulty;
+
+    use super::{
+        distance, generate_campaign_level, locate_level, next_difficulty, CampaignProgress,
+        LevelResult, ATTEMPTS, CHAPTERS, MIN_RESULTS, WINDOW,
+    };
+
+    fn result(difficulty: Difficulty, rotations: u64, hints: u32) -> LevelResult {
+        LevelResult {
+            difficulty,
+            scoring: Scoring {
+                par: 10,
+                rotations,
+                hints,
+                elapsed_milliseconds: 10000,
+            },
+        }
+    }
+
+    #[test]
+    fn campaign_starts_easy() {
+        assert_eq!(next_difficulty(&[]), Difficulty::Easy);
+    }
+
+    #[test]
+    fn difficulty_follows_performance() {
+        let mastered = vec![result(Difficulty::Medium, 10, 0); MIN_RESULTS];
+        assert_eq!(next_difficulty(&mastered), Difficulty::Hard);
+        assert_eq!(
+            next_difficulty(&mastered[1..]),
+            Difficulty::Medium,
+            "too few results to judge"
+        );
+
+        let struggling = vec![result(Difficulty::Medium, 30, 0); MIN_RESULTS];
+        assert_eq!(next_difficulty(&struggling), Difficulty::Easy);
+
+        let hinted = vec![result(Difficulty::Medium, 10, 1); MIN_RESULTS];
+        assert_eq!(next_difficulty(&hinted), Difficulty::Easy);
+
+        let mut changed = vec![result(Difficulty::Easy, 30, 2); WINDOW];
+        changed.push(result(Difficulty::Medium, 10, 0));
+        assert_eq!(next_difficulty(&changed), Difficulty::Medium);
+    }
+
+    #[quickcheck]
+    fn difficulty_changes_at_most_one_step(difficulty: Max<2>, rotations: Vec<(u8, u8)>) -> bool {
+        let difficulty = Difficulty::ALL[difficulty.to_usize()];
+        let history = rotations
+            .into_iter()
+            .map(|(rotations, hints)| result(difficulty, rotations as u64, hints as u32 % 3))
+            .collect::<Vec<_>>();
+        let next = next_difficulty(&history);
+        if history.is_empty() {
+            next == Difficulty::Easy
+        } else {
+            next == difficulty || next == difficulty.harder() || next == difficulty.easier()
+        }
+    }
+
+    #[quickcheck]
+    fn display_then_parse_is_identity(par: u64, rotations: u64, hints: u32, elapsed: u64) -> bool {
+        let result = LevelResult {
+            difficulty: Difficulty::Hard,
+            scoring: Scoring {
+                par,
+                rotations,
+                hints,
+                elapsed_milliseconds: elapsed,
+            },
+        };
+        result.to_string().parse() == Ok(result)
+    }
+
+    #[quickcheck]
+    fn campaign_levels_are_closest_to_the_difficulty(
+        dimension: Coordinate<Interval<1, 4>>,
+        difficulty: Max<2>,
+        seed: u64,
+    ) -> bool {
+        let dimension = dimension.map(Interval::to_usize);
+        let difficulty = Difficulty::ALL[difficulty.to_usize()];
+        let level = generate_campaign_level(dimension, difficulty, seed);
+        let first = generate(dimension, seed.wrapping_mul(ATTEMPTS));
+        level.dimensions() == dimension
+            && distance(&level, difficulty) <= distance(&first, difficulty)
+    }
+
+    #[test]
+    fn chapters_unlock_after_finishing_levels_of_the_previous_chapter() {
+        let mut progress = CampaignProgress::default();
+        assert!(progress.is_unlocked(0));
+        assert!(!progress.is_unlocked(1));
+        for level in 0..CHAPTERS[1].unlock {
+            assert!(!progress.is_unlocked(1));
+            progress.complete(0, level, Scoring::with_par(4));
+            progress.complete(0, level, Scoring::with_par(4));
+        }
+        assert_eq!(progress.finished(0), CHAPTERS[1].unlock);
+        assert!(progress.is_unlocked(1));
+        assert!(!progress.is_unlocked(2));
+        assert!(!progress.is_unlocked(CHAPTERS.len()));
+    }
+
+    #[test]
+    fn completions_keep_the_best_score() {
+        let mut progress = CampaignProgress::default();
+        let hinted = Scoring {
+            hints: 2,
+            ..Scoring::with_par(6)
+        };
+        progress.complete(2, 3, Scoring::with_par(6));
+        progress.complete(2, 3, hinted);
+        assert_eq!(progress.completion(2, 3), Some(Scoring::with_par(6)));
+        progress.complete(1, 0, hinted);
+        assert_eq!(progress.completion(1, 0), Some(hinted));
+        assert_eq!(progress.to_string().parse(), Ok(progress));
+        assert!("1 2 3".parse::<CampaignProgress>().is_err());
+        assert_eq!("".parse(), Ok(CampaignProgress::default()));
+    }
+
+    #[quickcheck]
+    fn scrambled_chapter_levels_are_located(chapter: u8, level: u8, seed: u64) -> bool {
+        let chapter = chapter as usize % CHAPTERS.len();
+        let level = level as usize % CHAPTERS[chapter].levels;
+        let scrambled = CHAPTERS[chapter].level(level).unwrap().scramble(seed);
+        locate_level(&scrambled) == Some((chapter, level))
+    }
+}
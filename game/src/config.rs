@@ -0,0 +1,111 @@
+//! Configuration shared by the generators and solvers of the game
+//!
+//! all randomness flows through the [`RngSource`] of a [`GameConfig`],
+//! so wasm, native binaries and tests control it in one place
+
+use std::fmt::{self, Debug};
+
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+/// Source of random numbers, hiding the concrete generator
+///
+/// implements [`RngCore`] and therefore [`rand::Rng`], so it can be passed to all functions ending in `_with`
+pub struct RngSource(Box<dyn RngCore>);
+
+impl RngSource {
+    pub fn new(rng: impl RngCore + 'static) -> Self {
+        RngSource(Box::new(rng))
+    }
+
+    /// same seed yields the same random numbers
+    pub fn seeded(seed: u64) -> Self {
+        RngSource::new(StdRng::seed_from_u64(seed))
+    }
+
+    /// seeded from the randomness of the operating system or the browser
+    pub fn from_entropy() -> Self {
+        RngSource::new(StdRng::from_entropy())
+    }
+
+    /// Draws a seed for the seeded APIs, e.g. [`crate::generator::fastgen::generate`]
+    pub fn next_seed(&mut self) -> u64 {
+        self.next_u64()
+    }
+}
+
+impl Debug for RngSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RngSource")
+    }
+}
+
+impl RngCore for RngSource {
+    fn next_u32(&mut self) -> u32 {
+        self.0.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.0.try_fill_bytes(dest)
+    }
+}
+
+/// Settings threaded through the game
+#[derive(Debug)]
+pub struct GameConfig {
+    pub rng: RngSource,
+}
+
+impl GameConfig {
+    pub fn new(rng: RngSource) -> Self {
+        GameConfig { rng }
+    }
+
+    /// reproducible configuration, e.g. for tests
+    pub fn seeded(seed: u64) -> Self {
+        GameConfig::new(RngSource::seeded(seed))
+    }
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        GameConfig::new(RngSource::from_entropy())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use rand::{rngs::mock::StepRng, Rng};
+
+    use super::*;
+    use crate::generator::fastgen::{generate, generate_with};
+    use crate::model::coordinate::Coordinate;
+
+    #[quickcheck]
+    fn seeded_sources_are_deterministic(seed: u64) -> bool {
+        let draw = |mut rng: RngSource| (0..8).map(|_| rng.gen::<u64>()).collect::<Vec<_>>();
+        draw(RngSource::seeded(seed)) == draw(RngSource::seeded(seed))
+    }
+
+    #[test]
+    fn injected_generator_is_used() {
+        let mut config = GameConfig::new(RngSource::new(StepRng::new(3, 2)));
+        assert_eq!(config.rng.next_seed(), 3);
+        assert_eq!(config.rng.next_seed(), 5);
+    }
+
+    #[quickcheck]
+    fn seeded_config_generates_like_seed(seed: u64) -> bool {
+        let dimension = Coordinate::new(5, 4);
+        let mut config = GameConfig::seeded(seed);
+        generate_with(dimension, &mut config.rng) == generate(dimension, seed)
+    }
+}
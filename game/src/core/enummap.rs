@@ -43,6 +43,20 @@ impl<K: Cardinality, V: Clone> EnumMap<K, V> {
     }
 }
 
+impl<K: Finite, V> EnumMap<K, V> {
+    /// Create a map associating every key with the computed value
+    pub fn from_fn<F: FnMut(K) -> V>(f: F) -> Self {
+        Self(
+            (0..K::CARDINALITY)
+                .map(K::unchecked_index_to_enum)
+                .map(f)
+                .map(Some)
+                .collect(),
+            PhantomData,
+        )
+    }
+}
+
 impl<K, V> EnumMap<K, V> {
     /// Wipes all stored associations
     pub fn clear(&mut self) {
@@ -85,6 +99,55 @@ impl<K: Finite, V> EnumMap<K, V> {
         self.0[key.enum_to_index() as usize].as_ref()
     }
 
+    /// Queries the associated value of the given key for modification
+    pub fn get_mut(&mut self, key: K) -> Option<&mut V> {
+        self.0[key.enum_to_index() as usize].as_mut()
+    }
+
+    /// Returns the associated value of the given key, inserting the computed value if there is none
+    pub fn get_or_insert_with<F: FnOnce() -> V>(&mut self, key: K, default: F) -> &mut V {
+        self.0[key.enum_to_index() as usize].get_or_insert_with(default)
+    }
+
+    /// Returns the slot of the given key for in-place manipulation
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+        let index = key.enum_to_index() as usize;
+        Entry {
+            key,
+            slot: &mut self.0[index],
+        }
+    }
+
+    /// Returns an iterator over all keys in the map in ascending order
+    pub fn keys(&self) -> impl Iterator<Item = K> + '_ {
+        self.0
+            .iter()
+            .enumerate()
+            .filter(|(_, value)| value.is_some())
+            .map(|(index, _)| K::unchecked_index_to_enum(index as u64))
+    }
+
+    /// Returns an iterator over all associated values in the map in ascending order of their keys
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.0.iter().flatten()
+    }
+
+    /// Returns an iterator over all associated values in the map for modification
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut V> {
+        self.0.iter_mut().flatten()
+    }
+
+    /// Keeps only the associations satisfying the predicate
+    pub fn retain<F: FnMut(K, &mut V) -> bool>(&mut self, mut predicate: F) {
+        for (index, slot) in self.0.iter_mut().enumerate() {
+            if let Some(value) = slot {
+                if !predicate(K::unchecked_index_to_enum(index as u64), value) {
+                    *slot = None;
+                }
+            }
+        }
+    }
+
     /// Returns a map containing every key present in both maps, favoring associated values of this map
     pub fn intersection(self, other: Self) -> Self {
         Self(
@@ -149,17 +212,62 @@ impl<K: Finite, V: Clone> EnumMap<K, V> {
     }
 }
 
+/// Looks up the value of a key that is known to be present
+///
+/// # Panics
+///
+/// if the key is not present, deliberately like [`std::collections::HashMap`], so a missing key surfaces at once
+/// instead of a made-up value. Maps created with [`EnumMap::from_fn`] contain every key, otherwise
+/// [`EnumMap::get`] checks the key and indexing mutably inserts the default value
 impl<K: Finite, V> Index<K> for EnumMap<K, V> {
-    type Output = Option<V>;
+    type Output = V;
 
     fn index(&self, index: K) -> &Self::Output {
-        &self.0[index.enum_to_index() as usize]
+        self.get(index).expect("key is present in EnumMap")
     }
 }
 
-impl<K: Finite, V> IndexMut<K> for EnumMap<K, V> {
+/// Inserts the default value if the key is not present, e.g. `counts[key] += 1`
+impl<K: Finite, V: Default> IndexMut<K> for EnumMap<K, V> {
     fn index_mut(&mut self, index: K) -> &mut Self::Output {
-        &mut self.0[index.enum_to_index() as usize]
+        self.get_or_insert_with(index, V::default)
+    }
+}
+
+/// Slot of a key in an [`EnumMap`], see [`EnumMap::entry`]
+pub struct Entry<'a, K, V> {
+    key: K,
+    slot: &'a mut Option<V>,
+}
+
+impl<'a, K: Copy, V> Entry<'a, K, V> {
+    pub fn key(&self) -> K {
+        self.key
+    }
+
+    /// Returns the associated value, inserting the given value if there is none
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.slot.get_or_insert(default)
+    }
+
+    /// Returns the associated value, inserting the computed value if there is none
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        self.slot.get_or_insert_with(default)
+    }
+
+    /// Modifies the associated value if there is one
+    pub fn and_modify<F: FnOnce(&mut V)>(self, f: F) -> Self {
+        if let Some(value) = self.slot.as_mut() {
+            f(value)
+        }
+        self
+    }
+}
+
+impl<'a, K: Copy, V: Default> Entry<'a, K, V> {
+    /// Returns the associated value, inserting the default value if there is none
+    pub fn or_default(self) -> &'a mut V {
+        self.or_insert_with(V::default)
     }
 }
 
@@ -172,7 +280,7 @@ pub struct Iter<K, V> {
     phantom: PhantomData<K>,
 }
 
-impl<K: Finite, V: Clone> IntoIterator for EnumMap<K, V> {
+impl<K: Finite, V> IntoIterator for EnumMap<K, V> {
     type Item = (K, V);
 
     type IntoIter = Iter<K, V>;
@@ -186,7 +294,7 @@ impl<K: Finite, V: Clone> IntoIterator for EnumMap<K, V> {
     }
 }
 
-impl<K: Finite, V: Clone> Iterator for Iter<K, V> {
+impl<K: Finite, V> Iterator for Iter<K, V> {
     type Item = (K, V);
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -198,10 +306,9 @@ impl<K: Finite, V: Clone> Iterator for Iter<K, V> {
         } else {
             let old_index = self.index;
             self.index += 1;
-            Some((
-                K::unchecked_index_to_enum(old_index as u64),
-                self.elements[old_index].clone().unwrap(),
-            ))
+            self.elements[old_index]
+                .take()
+                .map(|value| (K::unchecked_index_to_enum(old_index as u64), value))
         }
     }
 
@@ -221,7 +328,7 @@ impl<K: Finite, V: Clone> Iterator for Iter<K, V> {
     }
 }
 
-impl<K: Finite, V: Clone> FusedIterator for Iter<K, V> {}
+impl<K: Finite, V> FusedIterator for Iter<K, V> {}
 
 impl<K: Finite, V: Clone> FromIterator<(K, V)> for EnumMap<K, V> {
     fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
@@ -290,6 +397,39 @@ mod test {
         map.clone().into_iter().collect::<EnumMap<_, _>>() == map
     }
 
+    #[quickcheck]
+    fn from_fn_contains_every_key(key: Max<20>) -> bool {
+        let map = EnumMap::<Max<20>, usize>::from_fn(|k| k.to_usize() * 2);
+        map.len() == 21 && map[key] == key.to_usize() * 2
+    }
+
+    #[quickcheck]
+    fn index_mut_inserts_default(mut map: EnumMap<Max<20>, u32>, key: Max<20>) -> bool {
+        map.retain(|_, v| *v < u32::MAX);
+        let before = map.get(key).copied().unwrap_or_default();
+        map[key] += 1;
+        map[key] == before + 1
+    }
+
+    #[quickcheck]
+    fn entry_inserts_only_missing(mut map: EnumMap<Max<20>, u32>, key: Max<20>) -> bool {
+        let before = map.get(key).copied();
+        let value = *map.entry(key).and_modify(|v| *v /= 2).or_insert(7);
+        value == before.map_or(7, |v| v / 2) && map.get(key) == Some(&value)
+    }
+
+    #[quickcheck]
+    fn keys_and_values_match_iter(map: EnumMap<Max<20>, u32>) -> bool {
+        map.keys().zip(map.values().copied()).eq(map.iter())
+    }
+
+    #[quickcheck]
+    fn retain_keeps_satisfying(mut map: EnumMap<Max<20>, u32>) -> bool {
+        let expected = map.iter().filter(|(_, v)| v % 2 == 0).collect::<Vec<_>>();
+        map.retain(|_, v| *v % 2 == 0);
+        map.into_iter().collect::<Vec<_>>() == expected
+    }
+
     #[quickcheck]
     fn iter_size_hint_is_exact(map: EnumMap<Max<20>, u32>, random: usize) -> bool {
         let skip_distance = random.checked_rem(map.len() as usize).unwrap_or_default();
@@ -1,6 +1,6 @@
 use std::{
     cmp::Ordering,
-    fmt::Display,
+    fmt::{Debug, Display},
     hash::Hash,
     iter::FusedIterator,
     marker::PhantomData,
@@ -49,7 +49,6 @@ const CAPACITY: u64 = BitArray::BITS as u64;
 ///     `∀x, y : A, s1, s2 : EnumSet<A>. (s1 ≤ s2 ⟺ s1.enum_to_index() ≤ s2.enum_to_index()) ⟺ (x ≤ y ⟺ x.enum_to_index() ≤ y.enum_to_index())`
 ///
 /// Invariant #1 ensures canonical representation for easy equality checks
-#[derive(Debug)]
 pub struct EnumSet<A>(BitArray, PhantomData<A>); // alternative names: FiniteSet, FinSet
 
 // most derivable traits are independent of type [`A`]
@@ -87,6 +86,13 @@ impl<A> Hash for EnumSet<A> {
     }
 }
 
+/// lists the elements instead of the raw bit array, e.g. `{Up, Down}`
+impl<A: Debug + Finite> Debug for EnumSet<A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
 impl<A: Display + Finite> Display for EnumSet<A> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut string = self
@@ -202,6 +208,34 @@ impl<A: Cardinality> EnumSet<A> {
     pub fn complement(&mut self) {
         self.0 = !self.0 & Self::USED_BITS
     }
+
+    /// Returns a set containing every element present in all sets
+    ///
+    /// [`EnumSet::FULL`] for no sets, stops early once the intersection is empty
+    pub fn intersect_all<I: IntoIterator<Item = Self>>(sets: I) -> Self {
+        let mut intersection = Self::FULL;
+        for set in sets {
+            if intersection.is_empty() {
+                break;
+            }
+            intersection = intersection.intersection(set);
+        }
+        intersection
+    }
+
+    /// Returns a set containing any elements present in at least one set
+    ///
+    /// [`EnumSet::EMPTY`] for no sets, stops early once the union is full
+    pub fn union_all<I: IntoIterator<Item = Self>>(sets: I) -> Self {
+        let mut union = Self::EMPTY;
+        for set in sets {
+            if union == Self::FULL {
+                break;
+            }
+            union = union.union(set);
+        }
+        union
+    }
 }
 
 impl<A: Finite> EnumSet<A> {
@@ -277,6 +311,15 @@ impl<A: Finite> EnumSet<A> {
         }
     }
 
+    /// Retains only the elements satisfying the predicate
+    pub fn retain<F: FnMut(&A) -> bool>(&mut self, mut predicate: F) {
+        for element in self.iter() {
+            if !predicate(&element) {
+                self.remove(element);
+            }
+        }
+    }
+
     /// Returns an iterator over the elements in the set
     pub fn iter(&self) -> Self {
         *self
@@ -622,6 +665,24 @@ mod test {
         set.is_disjoint(!set)
     }
 
+    #[quickcheck]
+    fn n_ary_operations_agree_with_folds(sets: Vec<EnumSet<Square>>) -> bool {
+        EnumSet::intersect_all(sets.iter().copied())
+            == sets.iter().fold(EnumSet::FULL, |acc, &set| acc & set)
+            && EnumSet::union_all(sets.iter().copied())
+                == sets.iter().fold(EnumSet::EMPTY, |acc, &set| acc | set)
+    }
+
+    #[quickcheck]
+    fn retain_keeps_exactly_the_satisfying_elements(
+        set: EnumSet<Max<{ CAPACITY as usize - 1 }>>,
+        bound: Max<{ CAPACITY as usize - 1 }>,
+    ) -> bool {
+        let mut retained = set;
+        retained.retain(|&x| x < bound);
+        retained == set.filter(|&x| x < bound).collect()
+    }
+
     #[quickcheck]
     fn iterator_of_singleton_set_contains_the_single_element(element: EnumSet<bool>) -> bool {
         let mut iter = EnumSet::from(element).iter();
@@ -0,0 +1,30 @@
+/// Stable 64 bit FNV-1a hash of the little-endian bytes of the words
+///
+/// unlike [`std::hash::Hash`] the value is specified, so it can be persisted and compared across platforms
+pub fn fnv1a<I: IntoIterator<Item = u64>>(words: I) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    words
+        .into_iter()
+        .flat_map(u64::to_le_bytes)
+        .fold(OFFSET_BASIS, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(PRIME)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::fnv1a;
+
+    #[test]
+    fn fnv1a_of_nothing_is_offset_basis() {
+        assert_eq!(fnv1a([]), 0xcbf2_9ce4_8422_2325);
+    }
+
+    #[quickcheck]
+    fn fnv1a_depends_on_order(x: u64, y: u64) -> bool {
+        x == y || fnv1a([x, y]) != fnv1a([y, x])
+    }
+}
@@ -2,6 +2,8 @@ pub mod cardinality;
 pub mod enummap;
 pub mod enumset;
 pub mod finite;
+pub mod hash;
 pub mod interval;
 pub mod lattice;
 pub mod num;
+pub mod wideenumset;
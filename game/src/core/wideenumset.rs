@@ -0,0 +1,461 @@
+//! Multi-word variant of [`EnumSet`] for types with more inhabitants than bits in a single word
+//!
+//! the number of words would preferably be derived from [`A::CARDINALITY`](Cardinality::CARDINALITY),
+//! but computing array lengths from associated constants requires the unstable `generic_const_exprs`.
+//! Instead the number of words is given explicitly and checked at compile-time against the cardinality.
+//! [`EnumSet`] remains the fast special case of a single word
+
+use std::{
+    cmp::Ordering,
+    fmt::Debug,
+    hash::Hash,
+    iter::FusedIterator,
+    marker::PhantomData,
+    ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not, Sub, SubAssign},
+};
+
+use quickcheck::Arbitrary;
+
+use super::{cardinality::Cardinality, enumset::EnumSet, finite::Finite};
+
+/// runtime representation of a single word
+type BitArray = u64;
+
+/// number of elements stored per word
+const CAPACITY: u64 = BitArray::BITS as u64;
+
+/// Set data structure for storing elements of statically enumerable types, spread over `WORDS` bit arrays
+///
+/// Provides the same operations as [`EnumSet`], e.g. `WideEnumSet<[Tile<Square>; 2], 4>` stores pairs of tiles.
+/// Choosing too few words for the cardinality of `A` leads to a compile-time error:
+/// `WORDS * 64 ≥ A::CARDINALITY`
+///
+/// This struct deliberately does not implement the [`Default`] trait, instead use [`WideEnumSet::EMPTY`] or [`WideEnumSet::FULL`]
+///
+/// # Iterator
+///
+/// [`WideEnumSet`] is its own [`Iterator`] representation
+///
+/// # Invariants
+///
+/// 1. bits exceeding [`Cardinality`] of `A` are always set to 0
+/// 2. element `i` is stored in bit `i % 64` of word `i / 64`
+pub struct WideEnumSet<A, const WORDS: usize>([BitArray; WORDS], PhantomData<A>);
+
+impl<A, const WORDS: usize> Copy for WideEnumSet<A, WORDS> {}
+
+impl<A, const WORDS: usize> Clone for WideEnumSet<A, WORDS> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<A, const WORDS: usize> PartialEq for WideEnumSet<A, WORDS> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<A, const WORDS: usize> Eq for WideEnumSet<A, WORDS> {}
+
+impl<A, const WORDS: usize> PartialOrd for WideEnumSet<A, WORDS> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// compares the words as a single number, consistent with the order of [`EnumSet`]
+impl<A, const WORDS: usize> Ord for WideEnumSet<A, WORDS> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.iter().rev().cmp(other.0.iter().rev())
+    }
+}
+
+impl<A, const WORDS: usize> Hash for WideEnumSet<A, WORDS> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+/// lists the elements instead of the raw bit arrays, e.g. `{0, 70, 130}`
+impl<A: Debug + Finite, const WORDS: usize> Debug for WideEnumSet<A, WORDS> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<A, const WORDS: usize> WideEnumSet<A, WORDS> {
+    /// Indicates if the set contains 0 elements
+    pub fn is_empty(self) -> bool {
+        self.0.iter().all(|&word| word == BitArray::MIN)
+    }
+
+    /// Returns number of elements in the set
+    pub fn len(self) -> u32 {
+        self.0.iter().map(|word| word.count_ones()).sum()
+    }
+
+    /// Returns a set containing every element present in both sets
+    pub fn intersection(self, other: Self) -> Self {
+        self.zip_with(other, |x, y| x & y)
+    }
+
+    /// Returns a set containing any elements present in either set
+    pub fn union(self, other: Self) -> Self {
+        self.zip_with(other, |x, y| x | y)
+    }
+
+    /// Returns a set containing all elements in the first set without the elements in the second set
+    pub fn difference(self, other: Self) -> Self {
+        self.zip_with(other, |x, y| x & !y)
+    }
+
+    /// Returns a set containing all the elements that are contained in exactly one set
+    pub fn symmetric_difference(self, other: Self) -> Self {
+        self.zip_with(other, |x, y| x ^ y)
+    }
+
+    /// Indicates if the other set contains at least all elements of this one
+    pub fn is_subset(self, other: Self) -> bool {
+        self.difference(other).is_empty()
+    }
+
+    /// Indicates if this set contains at least all elements of the other one
+    pub fn is_superset(self, other: Self) -> bool {
+        other.is_subset(self)
+    }
+
+    /// Indicates if both sets share no common elements
+    pub fn is_disjoint(self, other: Self) -> bool {
+        self.intersection(other).is_empty()
+    }
+
+    /// combines the corresponding words of both sets
+    fn zip_with<F: Fn(BitArray, BitArray) -> BitArray>(self, other: Self, f: F) -> Self {
+        let mut words = self.0;
+        for (word, other) in words.iter_mut().zip(other.0) {
+            *word = f(*word, other);
+        }
+        Self(words, PhantomData)
+    }
+}
+
+impl<A: Cardinality, const WORDS: usize> WideEnumSet<A, WORDS> {
+    /// Bitmasks with the [`A:CARDINALITY`] least significant bits over all words set to 1
+    ///
+    /// fails to compile if the words cannot store all elements
+    const USED_BITS: [BitArray; WORDS] = {
+        if (WORDS as u64) * CAPACITY < A::CARDINALITY {
+            panic!("WideEnumSet has too few words for the cardinality of its elements")
+        }
+        let mut bits = [BitArray::MIN; WORDS];
+        let mut word = 0;
+        while word < WORDS {
+            let offset = word as u64 * CAPACITY;
+            bits[word] = if A::CARDINALITY >= offset + CAPACITY {
+                BitArray::MAX
+            } else if A::CARDINALITY <= offset {
+                BitArray::MIN
+            } else {
+                BitArray::MAX >> (offset + CAPACITY - A::CARDINALITY)
+            };
+            word += 1;
+        }
+        bits
+    };
+
+    /// Set containing 0 elements
+    ///
+    /// neutral element of the [`WideEnumSet::union`] monoid
+    pub const EMPTY: Self = {
+        // evaluated for the compile-time check of the capacity
+        let _ = Self::USED_BITS;
+        Self([BitArray::MIN; WORDS], PhantomData)
+    };
+
+    /// Set containing all possible elements
+    ///
+    /// neutral element of the [`WideEnumSet::intersection`] monoid
+    pub const FULL: Self = Self(Self::USED_BITS, PhantomData);
+
+    /// Returns a set containing all elements not in this set
+    ///
+    /// Mutable variant of [`WideEnumSet::not`]
+    pub fn complement(&mut self) {
+        *self = !*self
+    }
+}
+
+impl<A: Finite, const WORDS: usize> WideEnumSet<A, WORDS> {
+    /// word and bitmask of the element
+    fn locate(element: &A) -> (usize, BitArray) {
+        let index = element.enum_to_index();
+        ((index / CAPACITY) as usize, 1 << (index % CAPACITY))
+    }
+
+    /// Checks if the set contains a given element
+    pub fn contains(self, element: A) -> bool {
+        let (word, bit) = Self::locate(&element);
+        self.0[word] & bit != BitArray::MIN
+    }
+
+    /// Inserts given element into the set
+    ///
+    /// Immutable variant of [`WideEnumSet::insert`]
+    pub fn inserted(mut self, element: A) -> Self {
+        self.insert(element);
+        self
+    }
+
+    /// Removes given element from the set
+    ///
+    /// Immutable variant of [`WideEnumSet::remove`]
+    pub fn removed(mut self, element: A) -> Self {
+        self.remove(element);
+        self
+    }
+
+    /// Toggles given element in the set
+    ///
+    /// Immutable variant of [`WideEnumSet::toggle`]
+    pub fn toggled(mut self, element: A) -> Self {
+        self.toggle(element);
+        self
+    }
+
+    /// Inserts given element into the set and indicates if the set has changed
+    ///
+    /// Mutable variant of [`WideEnumSet::inserted`]
+    pub fn insert(&mut self, element: A) -> bool {
+        let (word, bit) = Self::locate(&element);
+        let old = self.0[word];
+        self.0[word] |= bit;
+        self.0[word] != old
+    }
+
+    /// Removes given element from the set and indicates if the set has changed
+    ///
+    /// Mutable variant of [`WideEnumSet::removed`]
+    pub fn remove(&mut self, element: A) -> bool {
+        let (word, bit) = Self::locate(&element);
+        let old = self.0[word];
+        self.0[word] &= !bit;
+        self.0[word] != old
+    }
+
+    /// Toggles given element in the set
+    ///
+    /// Mutable variant of [`WideEnumSet::toggled`]
+    pub fn toggle(&mut self, element: A) {
+        let (word, bit) = Self::locate(&element);
+        self.0[word] ^= bit;
+    }
+
+    /// Unwraps the only element of the set
+    ///
+    /// * returns `Some(e)` if set is a singleton
+    /// * returns `None` if set contains several elements or is empty
+    pub fn unwrap_if_singleton(self) -> Option<A> {
+        if self.len() == 1 {
+            self.iter().next()
+        } else {
+            None
+        }
+    }
+
+    /// Returns an iterator over the elements in the set
+    pub fn iter(&self) -> Self {
+        *self
+    }
+}
+
+impl<A: Finite, const WORDS: usize> Iterator for WideEnumSet<A, WORDS> {
+    type Item = A;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let word = self.0.iter().position(|&word| word != BitArray::MIN)?;
+        let bits = self.0[word];
+        self.0[word] = bits & (bits - 1); // delete least significant set bit
+        Some(A::unchecked_index_to_enum(
+            word as u64 * CAPACITY + bits.trailing_zeros() as u64,
+        ))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let size = ExactSizeIterator::len(self);
+        (size, Some(size))
+    }
+
+    fn count(self) -> usize {
+        self.len() as usize
+    }
+
+    fn last(mut self) -> Option<Self::Item> {
+        self.next_back()
+    }
+}
+
+impl<A: Finite, const WORDS: usize> ExactSizeIterator for WideEnumSet<A, WORDS> {
+    fn len(&self) -> usize {
+        self.0.iter().map(|word| word.count_ones() as usize).sum()
+    }
+}
+
+impl<A: Finite, const WORDS: usize> FusedIterator for WideEnumSet<A, WORDS> {}
+
+impl<A: Finite, const WORDS: usize> DoubleEndedIterator for WideEnumSet<A, WORDS> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let word = self.0.iter().rposition(|&word| word != BitArray::MIN)?;
+        let index = (BitArray::BITS - self.0[word].leading_zeros() - 1) as u64;
+        self.0[word] &= !(1 << index);
+        Some(A::unchecked_index_to_enum(word as u64 * CAPACITY + index))
+    }
+}
+
+impl<A: Finite, const WORDS: usize> FromIterator<A> for WideEnumSet<A, WORDS> {
+    fn from_iter<T: IntoIterator<Item = A>>(iter: T) -> Self {
+        iter.into_iter().fold(Self::EMPTY, Self::inserted)
+    }
+}
+
+impl<A: Finite, const WORDS: usize> Extend<A> for WideEnumSet<A, WORDS> {
+    fn extend<T: IntoIterator<Item = A>>(&mut self, iter: T) {
+        iter.into_iter().for_each(|e| {
+            self.insert(e);
+        })
+    }
+}
+
+impl<A: Cardinality, const WORDS: usize> Not for WideEnumSet<A, WORDS> {
+    type Output = Self;
+
+    /// Returns a set containing all elements not in this set
+    ///
+    /// Immutable variant of [`WideEnumSet::complement`]
+    fn not(self) -> Self::Output {
+        Self::FULL.difference(self)
+    }
+}
+
+impl<A: Finite, const WORDS: usize> BitOr for WideEnumSet<A, WORDS> {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.union(rhs)
+    }
+}
+
+impl<A: Finite, const WORDS: usize> BitOrAssign for WideEnumSet<A, WORDS> {
+    fn bitor_assign(&mut self, rhs: Self) {
+        *self = self.union(rhs)
+    }
+}
+
+impl<A: Finite, const WORDS: usize> BitAnd for WideEnumSet<A, WORDS> {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        self.intersection(rhs)
+    }
+}
+
+impl<A: Finite, const WORDS: usize> BitAndAssign for WideEnumSet<A, WORDS> {
+    fn bitand_assign(&mut self, rhs: Self) {
+        *self = self.intersection(rhs)
+    }
+}
+
+impl<A: Finite, const WORDS: usize> Sub for WideEnumSet<A, WORDS> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.difference(rhs)
+    }
+}
+
+impl<A: Finite, const WORDS: usize> SubAssign for WideEnumSet<A, WORDS> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = self.difference(rhs)
+    }
+}
+
+impl<A: Finite, const WORDS: usize> From<A> for WideEnumSet<A, WORDS> {
+    /// Set only containing the given element
+    fn from(element: A) -> Self {
+        Self::EMPTY.inserted(element)
+    }
+}
+
+impl<A: Finite, const WORDS: usize> From<EnumSet<A>> for WideEnumSet<A, WORDS> {
+    /// Widens the single-word set
+    fn from(set: EnumSet<A>) -> Self {
+        set.collect()
+    }
+}
+
+impl<A: Arbitrary + Finite, const WORDS: usize> Arbitrary for WideEnumSet<A, WORDS> {
+    fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+        Vec::arbitrary(g).into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::collections::BTreeSet;
+
+    use crate::core::{
+        cardinality::Cardinality, enumset::EnumSet, interval::Max, wideenumset::WideEnumSet,
+    };
+    use crate::model::tile::{Square, Tile};
+
+    /// 200 elements spread over 4 words, the last one partially used
+    type Wide = WideEnumSet<Max<199>, 4>;
+
+    #[quickcheck]
+    fn behaves_like_an_ordered_set(elements: Vec<Max<199>>, removed: Vec<Max<199>>) -> bool {
+        let mut set = elements.iter().copied().collect::<Wide>();
+        let mut model = elements.into_iter().collect::<BTreeSet<_>>();
+        for element in removed {
+            set.remove(element);
+            model.remove(&element);
+        }
+        set.len() as usize == model.len()
+            && set.iter().eq(model.iter().copied())
+            && set.rev().eq(model.iter().rev().copied())
+            && model.iter().all(|&element| set.contains(element))
+    }
+
+    #[quickcheck]
+    fn complement_stays_within_cardinality(set: Wide) -> bool {
+        let complement = !set;
+        complement.len() + set.len() == 200
+            && complement.is_disjoint(set)
+            && (complement | set) == Wide::FULL
+    }
+
+    #[quickcheck]
+    fn set_operations_agree_with_subsets(s1: Wide, s2: Wide) -> bool {
+        (s1 & s2).is_subset(s1)
+            && (s1 | s2).is_superset(s2)
+            && (s1 - s2).is_disjoint(s2)
+            && s1.symmetric_difference(s2) == (s1 | s2) - (s1 & s2)
+    }
+
+    #[quickcheck]
+    fn order_agrees_with_single_word_sets(s1: EnumSet<Square>, s2: EnumSet<Square>) -> bool {
+        let wide = |set| WideEnumSet::<Square, 2>::from(set);
+        Ord::cmp(&s1, &s2) == Ord::cmp(&wide(s1), &wide(s2))
+    }
+
+    #[test]
+    fn stores_pairs_of_tiles() {
+        let pairs = WideEnumSet::<[Tile<Square>; 2], 4>::FULL;
+        assert_eq!(pairs.len() as u64, <[Tile<Square>; 2]>::CARDINALITY);
+        let pair: [Tile<Square>; 2] = [Tile::NO_CONNECTIONS, Tile::ALL_CONNECTIONS];
+        assert_eq!(
+            WideEnumSet::<_, 4>::from(pair).unwrap_if_singleton(),
+            Some(pair)
+        );
+    }
+}
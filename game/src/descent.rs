@@ -0,0 +1,314 @@
+//! Endless descent: a time-boxed run through ever smaller but harder levels
+//!
+//! every level solved in a run is followed by a level one row and column smaller, with fewer walls, no dead ends
+//! from [`DENSE_DEPTH`] on and of a higher difficulty, see [`stage`]. Levels stay at [`MIN_SIZE`] once it is
+//! reached. A run starts with [`START_TIME`] on the clock and every solved level adds [`TIME_BONUS`], the run is
+//! over when the time is up. Finished runs are ranked on the [`Leaderboard`] by depth, then by score
+
+use std::{fmt::Display, str::FromStr};
+
+use crate::core::enumset::EnumSet;
+use crate::generator::levelstream::{generate_with_property, LevelProperty};
+use crate::model::{
+    coordinate::Coordinate,
+    gameboard::WinRule,
+    grid::Grid,
+    tile::{Square, Tile},
+};
+use crate::scoring::Scoring;
+use crate::solver::difficulty::Difficulty;
+
+/// rows and columns of the first level of a run
+pub const START_SIZE: usize = 8;
+
+/// rows and columns of the smallest levels
+pub const MIN_SIZE: usize = 4;
+
+/// depth from which levels have no dead ends
+pub const DENSE_DEPTH: u32 = 3;
+
+/// milliseconds on the clock at the start of a run
+pub const START_TIME: u64 = 120_000;
+
+/// milliseconds added to the clock for every solved level
+pub const TIME_BONUS: u64 = 30_000;
+
+/// runs kept on the leaderboard
+pub const LEADERBOARD_SIZE: usize = 10;
+
+/// Constraints of the level at the depth of a run
+///
+/// levels shrink by one row and column per depth down to [`MIN_SIZE`], while the share of walls drops by
+/// 10 percentage points per depth and the difficulty rises from easy over medium to hard
+pub fn stage(depth: u32) -> LevelProperty {
+    let size = START_SIZE.saturating_sub(depth as usize).max(MIN_SIZE);
+    let difficulty = match depth {
+        0 => Difficulty::Easy,
+        1..=2 => Difficulty::Medium,
+        _ => Difficulty::Hard,
+    };
+    let tiles = if depth < DENSE_DEPTH {
+        EnumSet::FULL
+    } else {
+        EnumSet::FULL
+            .iter()
+            .filter(|tile: &Tile<Square>| tile.0.len() != 1)
+            .collect()
+    };
+    LevelProperty {
+        dimension: Coordinate::new(size, size),
+        difficulty: Some(difficulty),
+        tiles,
+        max_wall_density: Some(30_u32.saturating_sub(10 * depth) as u8),
+        rule: WinRule::default(),
+    }
+}
+
+/// Run in progress
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Descent {
+    /// seed of the first level, the levels of a run only depend on it
+    pub seed: u64,
+    /// number of levels solved
+    pub depth: u32,
+    pub score: u32,
+    /// milliseconds spent on the solved levels
+    pub elapsed_milliseconds: u64,
+}
+
+impl Descent {
+    pub fn new(seed: u64) -> Self {
+        Descent {
+            seed,
+            depth: 0,
+            score: 0,
+            elapsed_milliseconds: 0,
+        }
+    }
+
+    /// Scrambled level at the current depth, generated on a best effort basis, see [`generate_with_property`]
+    pub fn level(&self) -> Grid<Tile<Square>> {
+        let seed = self.seed.wrapping_add(self.depth as u64);
+        generate_with_property(stage(self.depth), seed).scramble(seed)
+    }
+
+    /// milliseconds on the clock, including the bonus of the solved levels
+    pub fn time_limit(&self) -> u64 {
+        START_TIME + self.depth as u64 * TIME_BONUS
+    }
+
+    /// milliseconds left after playing the current level for the given time
+    pub fn remaining_milliseconds(&self, current_milliseconds: u64) -> u64 {
+        self.time_limit()
+            .saturating_sub(self.elapsed_milliseconds + current_milliseconds)
+    }
+
+    pub fn is_over(&self, current_milliseconds: u64) -> bool {
+        self.remaining_milliseconds(current_milliseconds) == 0
+    }
+
+    /// Run after solving the current level, deeper levels multiply their score by their depth
+    ///
+    /// fails if the level was solved after the time was up
+    pub fn descend(self, scoring: &Scoring) -> Result<Self, String> {
+        if self.is_over(scoring.elapsed_milliseconds) {
+            return Err("the time of the run is up".to_string());
+        }
+        Ok(Descent {
+            depth: self.depth + 1,
+            score: self
+                .score
+                .saturating_add(scoring.score().saturating_mul(self.depth + 1)),
+            elapsed_milliseconds: self.elapsed_milliseconds + scoring.elapsed_milliseconds,
+            ..self
+        })
+    }
+
+    pub fn result(&self) -> RunResult {
+        RunResult {
+            depth: self.depth,
+            score: self.score,
+            elapsed_milliseconds: self.elapsed_milliseconds,
+        }
+    }
+}
+
+/// Outcome of a finished run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct RunResult {
+    pub depth: u32,
+    pub score: u32,
+    pub elapsed_milliseconds: u64,
+}
+
+impl RunResult {
+    /// whether the run ranks above the other one: deeper, or as deep with a higher score, or faster
+    pub fn beats(&self, other: &RunResult) -> bool {
+        (self.depth, self.score, other.elapsed_milliseconds)
+            > (other.depth, other.score, self.elapsed_milliseconds)
+    }
+}
+
+/// depth, score and elapsed milliseconds separated by spaces, e.g. `5 3200 241000`
+impl Display for RunResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            self.depth, self.score, self.elapsed_milliseconds
+        )
+    }
+}
+
+impl FromStr for RunResult {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("parsing error: invalid run result '{s}'");
+        let mut fields = s.split(' ');
+        let mut next = || fields.next().ok_or_else(invalid);
+        Ok(RunResult {
+            depth: next()?.parse().map_err(|_| invalid())?,
+            score: next()?.parse().map_err(|_| invalid())?,
+            elapsed_milliseconds: next()?.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+/// Best runs, best first, at most [`LEADERBOARD_SIZE`] of them
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Leaderboard(Vec<RunResult>);
+
+impl Leaderboard {
+    pub fn runs(&self) -> &[RunResult] {
+        &self.0
+    }
+
+    /// Enters the run, returns its place starting at 1 if it made it onto the leaderboard
+    ///
+    /// runs ranking equal to an entered run are placed below it
+    pub fn insert(&mut self, run: RunResult) -> Option<usize> {
+        let index = self.0.iter().take_while(|entry| !run.beats(entry)).count();
+        if index >= LEADERBOARD_SIZE {
+            return None;
+        }
+        self.0.insert(index, run);
+        self.0.truncate(LEADERBOARD_SIZE);
+        Some(index + 1)
+    }
+}
+
+/// one run per line, best first
+impl Display for Leaderboard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let lines = self.0.iter().map(RunResult::to_string).collect::<Vec<_>>();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+/// unreadable lines are skipped, the runs are ranked again
+impl FromStr for Leaderboard {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut leaderboard = Leaderboard::default();
+        for run in s.lines().filter_map(|line| line.parse().ok()) {
+            leaderboard.insert(run);
+        }
+        Ok(leaderboard)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::model::coordinate::Coordinate;
+    use crate::scoring::Scoring;
+    use crate::solver::difficulty::Difficulty;
+
+    use super::{
+        stage, Descent, Leaderboard, RunResult, DENSE_DEPTH, LEADERBOARD_SIZE, MIN_SIZE,
+        START_SIZE, START_TIME, TIME_BONUS,
+    };
+
+    #[test]
+    fn levels_shrink_and_get_harder() {
+        let stages = (0..10).map(stage).collect::<Vec<_>>();
+        assert_eq!(stages[0].dimension, Coordinate::new(START_SIZE, START_SIZE));
+        assert_eq!(stages[9].dimension, Coordinate::new(MIN_SIZE, MIN_SIZE));
+        assert_eq!(stages[9].difficulty, Some(Difficulty::Hard));
+        assert!(stages.windows(2).all(|pair| {
+            pair[1].dimension <= pair[0].dimension
+                && pair[1].difficulty >= pair[0].difficulty
+                && pair[1].max_wall_density <= pair[0].max_wall_density
+                && pair[1].tiles.len() <= pair[0].tiles.len()
+        }));
+        assert!(stages[DENSE_DEPTH as usize]
+            .tiles
+            .iter()
+            .all(|tile| tile.0.len() != 1));
+    }
+
+    #[quickcheck]
+    fn levels_of_a_run_are_solvable(seed: u64) -> bool {
+        let descent = Descent {
+            depth: (seed % 6) as u32,
+            ..Descent::new(seed)
+        };
+        let level = descent.level();
+        level.dimensions() == stage(descent.depth).dimension && level.solve().next().is_some()
+    }
+
+    #[test]
+    fn solved_levels_add_time() {
+        let scoring = |elapsed_milliseconds| Scoring {
+            par: 10,
+            rotations: 10,
+            elapsed_milliseconds,
+            ..Scoring::default()
+        };
+        let descent = Descent::new(0).descend(&scoring(100_000)).unwrap();
+        assert_eq!(
+            descent.remaining_milliseconds(0),
+            START_TIME + TIME_BONUS - 100_000
+        );
+        assert!(descent.descend(&scoring(49_000)).is_ok());
+        assert!(descent.is_over(50_000));
+        assert!(descent.descend(&scoring(60_000)).is_err());
+    }
+
+    #[test]
+    fn scores_of_deep_runs_saturate() {
+        let deep = Descent {
+            depth: u32::MAX / 2,
+            score: u32::MAX - 1,
+            ..Descent::new(0)
+        };
+        let scoring = Scoring {
+            par: 10,
+            rotations: 10,
+            ..Scoring::default()
+        };
+        assert_eq!(deep.descend(&scoring).unwrap().score, u32::MAX);
+    }
+
+    #[test]
+    fn leaderboard_ranks_depth_before_score() {
+        let run = |depth, score| RunResult {
+            depth,
+            score,
+            elapsed_milliseconds: 200_000,
+        };
+        let mut leaderboard = Leaderboard::default();
+        assert_eq!(leaderboard.insert(run(3, 900)), Some(1));
+        assert_eq!(leaderboard.insert(run(4, 100)), Some(1));
+        assert_eq!(leaderboard.insert(run(3, 1200)), Some(2));
+        assert_eq!(leaderboard.insert(run(3, 900)), Some(4));
+        for _ in 0..LEADERBOARD_SIZE {
+            leaderboard.insert(run(5, 0));
+        }
+        assert_eq!(leaderboard.insert(run(1, 5000)), None);
+        assert_eq!(leaderboard.runs().len(), LEADERBOARD_SIZE);
+        assert_eq!(leaderboard.to_string().parse(), Ok(leaderboard));
+    }
+}
@@ -0,0 +1,1195 @@
+//! Wire structs exchanged between frontend and backend
+//!
+//! both sides serialize exactly these structs, so their schemas cannot drift apart.
+//!
+//! ## Schema evolution
+//!
+//! every struct carries the [`VERSION`] it was written with
+//!
+//! * adding an optional field annotated with `#[serde(default)]` keeps the version,
+//!   a field added to [`LevelDto`] increments its [`FORMAT_VERSION`] instead
+//! * renaming, removing or reinterpreting a field increments [`VERSION`],
+//!   conversions into internal types reject versions they do not understand
+//!
+//! levels and packs keep the fields they do not know in [`UnknownFields`] and write them again,
+//! so older clients open levels of newer format versions and save them without losing data
+
+use std::{
+    collections::BTreeMap,
+    hash::{Hash, Hasher},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::{finite::Finite, hash::fnv1a};
+use crate::generator::{
+    fastgen::{generate, generate_torus, generate_unique_with_solution},
+    levelstream::builtin_levels,
+    wfc::WfcGenerator,
+};
+use crate::lint::lint;
+use crate::model::{
+    collab::{CellEdit, Cursor, EditorId, SharedDraft, Version},
+    constraint::{validate, Constraint},
+    coordinate::Coordinate,
+    gameboard::WinRule,
+    grid::Grid,
+    symmetry::{canonical_hash, content_checksum},
+    tile::{Geometry, Square, Tile, Topology},
+};
+use crate::solver::cache::SolverResult;
+use crate::solver::difficulty::Difficulty;
+use crate::solver::propagationsolver::Superposition;
+use crate::solver::snapshot::{generation_snapshots, solving_snapshots};
+
+/// current schema version written by this crate
+pub const VERSION: u32 = 1;
+
+/// current revision of the level format written by this crate, levels of any revision are read
+pub const FORMAT_VERSION: u32 = 3;
+
+/// upper bound of the rows and columns of levels read from a [`LevelDto`], checked before the level is built
+pub const MAX_LEVEL_SIZE: usize = 256;
+
+/// levels written before the format version was introduced belong to the first revision
+fn first_format_version() -> u32 {
+    1
+}
+
+/// Fields written by a newer format version, re-emitted unchanged when serializing again
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct UnknownFields(pub BTreeMap<String, serde_json::Value>);
+
+impl UnknownFields {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// hashes the JSON text of the values, as [`serde_json::Value`] does not implement [`Hash`]
+impl Hash for UnknownFields {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for (name, value) in &self.0 {
+            name.hash(state);
+            value.to_string().hash(state);
+        }
+    }
+}
+
+fn check_version(version: u32) -> Result<(), String> {
+    if version == VERSION {
+        Ok(())
+    } else {
+        Err(format!(
+            "unsupported schema version {version}, expected {VERSION}"
+        ))
+    }
+}
+
+/// Single tile of a level
+///
+/// `connections` is a bitmask of the directions: up = 1, right = 2, down = 4, left = 8.
+/// Other geometries number their directions in the clockwise order of their [`Topology`], starting at 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CellDto {
+    pub row: isize,
+    pub column: isize,
+    pub connections: u8,
+}
+
+/// Level as given by its tiles in their current rotation
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct LevelDto {
+    pub version: u32,
+    /// revision of the level format the level was written with, see [`FORMAT_VERSION`]
+    #[serde(default = "first_format_version")]
+    pub format_version: u32,
+    pub rows: usize,
+    pub columns: usize,
+    /// omitted cells are empty
+    pub cells: Vec<CellDto>,
+    /// designer constraints in their textual form, see [`Constraint`]
+    #[serde(default)]
+    pub constraints: Vec<String>,
+    /// win rule of the level in its textual form, see [`WinRule`], the default rule if absent
+    #[serde(default)]
+    pub win_rule: Option<String>,
+    /// shape of the cells in its textual form, see [`Geometry`], square if absent. Since format version 2
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub geometry: Option<String>,
+    /// edges wrap around, see [`Grid::into_torus`]. Since format version 3
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub torus: bool,
+    #[serde(flatten)]
+    pub unknown: UnknownFields,
+}
+
+impl LevelDto {
+    pub fn with_constraints(grid: &Grid<Tile<Square>>, constraints: &[Constraint]) -> Self {
+        LevelDto {
+            constraints: constraints.iter().map(Constraint::to_string).collect(),
+            ..LevelDto::from(grid)
+        }
+    }
+
+    /// Level of any geometry given by its tiles, records the geometry unless square
+    pub fn from_tiles<A: Topology>(grid: &Grid<Tile<A>>) -> Self {
+        LevelDto {
+            version: VERSION,
+            format_version: FORMAT_VERSION,
+            rows: grid.rows(),
+            columns: grid.columns(),
+            cells: grid
+                .coordinates()
+                .map(|coordinate| CellDto {
+                    row: coordinate.row,
+                    column: coordinate.column,
+                    connections: grid[coordinate].enum_to_index() as u8,
+                })
+                .collect(),
+            constraints: vec![],
+            win_rule: None,
+            geometry: (A::GEOMETRY != Geometry::Square).then(|| A::GEOMETRY.to_string()),
+            torus: grid.is_torus(),
+            unknown: UnknownFields::default(),
+        }
+    }
+
+    pub fn to_grid(&self) -> Result<Grid<Tile<Square>>, String> {
+        self.to_tiles()
+    }
+
+    /// Tiles of the level, fails unless the level has the geometry of the topology
+    /// and at most [`MAX_LEVEL_SIZE`] rows and columns
+    pub fn to_tiles<A: Topology>(&self) -> Result<Grid<Tile<A>>, String> {
+        check_version(self.version)?;
+        self.check_size(MAX_LEVEL_SIZE)?;
+        let geometry = self.to_geometry()?;
+        if geometry != A::GEOMETRY {
+            return Err(format!(
+                "level of geometry {geometry}, expected {}",
+                A::GEOMETRY
+            ));
+        }
+        let mut grid = Grid::filled_with(
+            Coordinate::new(self.rows, self.columns),
+            Tile::NO_CONNECTIONS,
+        );
+        for cell in &self.cells {
+            let tile = Tile::index_to_enum(cell.connections as u64)
+                .ok_or_else(|| format!("invalid connections {}", cell.connections))?;
+            *grid
+                .get_mut(Coordinate::new(cell.row, cell.column))
+                .ok_or_else(|| "cell outside of level dimensions".to_string())? = tile;
+        }
+        Ok(if self.torus { grid.into_torus() } else { grid })
+    }
+
+    /// fails if the level has more than `max` rows or columns, checked without building the level
+    pub fn check_size(&self, max: usize) -> Result<(), String> {
+        if self.rows > max || self.columns > max {
+            return Err(format!("levels have at most {max} rows and columns"));
+        }
+        Ok(())
+    }
+
+    pub fn to_geometry(&self) -> Result<Geometry, String> {
+        check_version(self.version)?;
+        self.geometry
+            .as_deref()
+            .map_or(Ok(Geometry::default()), str::parse)
+    }
+
+    pub fn to_constraints(&self) -> Result<Vec<Constraint>, String> {
+        check_version(self.version)?;
+        self.constraints.iter().map(|c| c.parse()).collect()
+    }
+
+    pub fn to_win_rule(&self) -> Result<WinRule, String> {
+        check_version(self.version)?;
+        self.win_rule
+            .as_deref()
+            .map_or(Ok(WinRule::default()), str::parse)
+    }
+}
+
+impl From<&Grid<Tile<Square>>> for LevelDto {
+    fn from(grid: &Grid<Tile<Square>>) -> Self {
+        LevelDto::from_tiles(grid)
+    }
+}
+
+impl TryFrom<LevelDto> for Grid<Tile<Square>> {
+    type Error = String;
+
+    fn try_from(dto: LevelDto) -> Result<Self, Self::Error> {
+        dto.to_grid()
+    }
+}
+
+/// Progress of a player on a level, for continuing on another device
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SessionDto {
+    pub version: u32,
+    /// level with the tiles rotated by the player so far
+    pub level: LevelDto,
+    pub moves: u32,
+    pub elapsed_milliseconds: u64,
+}
+
+impl SessionDto {
+    pub fn new(grid: &Grid<Tile<Square>>, moves: u32, elapsed_milliseconds: u64) -> Self {
+        SessionDto {
+            version: VERSION,
+            level: grid.into(),
+            moves,
+            elapsed_milliseconds,
+        }
+    }
+
+    pub fn to_grid(&self) -> Result<Grid<Tile<Square>>, String> {
+        check_version(self.version)?;
+        self.level.to_grid()
+    }
+}
+
+/// Result of a player solving a level
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ScoreDto {
+    pub version: u32,
+    /// identifies the level independent of the rotation of its tiles, see [`canonical_hash`]
+    pub level_hash: u64,
+    pub player: String,
+    pub moves: u32,
+    pub elapsed_milliseconds: u64,
+}
+
+impl ScoreDto {
+    pub fn new(
+        grid: &Grid<Tile<Square>>,
+        player: String,
+        moves: u32,
+        elapsed_milliseconds: u64,
+    ) -> Self {
+        ScoreDto {
+            version: VERSION,
+            level_hash: canonical_hash(grid),
+            player,
+            moves,
+            elapsed_milliseconds,
+        }
+    }
+
+    /// Checks whether the score was achieved on the level
+    pub fn is_for(&self, grid: &Grid<Tile<Square>>) -> Result<bool, String> {
+        check_version(self.version)?;
+        Ok(self.level_hash == canonical_hash(grid))
+    }
+}
+
+/// Level of a [`PackDto`] together with the [content checksum](content_checksum) of the level
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PackLevelDto {
+    pub level: LevelDto,
+    /// 16 hexadecimal digits, as JavaScript numbers cannot represent all 64 bit values
+    pub checksum: String,
+    #[serde(flatten)]
+    pub unknown: UnknownFields,
+}
+
+/// Named collection of levels with checksums to detect modified copies
+///
+/// the checksums are no cryptographic signature, a copy is only trustworthy if its
+/// [`PackDto::checksum`] matches one obtained from a trusted source, e.g. [`PackDto::builtin`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PackDto {
+    pub version: u32,
+    pub name: String,
+    pub levels: Vec<PackLevelDto>,
+    /// checksum over the checksums of all levels in their order
+    pub checksum: String,
+    #[serde(flatten)]
+    pub unknown: UnknownFields,
+}
+
+fn format_checksum(checksum: u64) -> String {
+    format!("{checksum:016x}")
+}
+
+fn pack_checksum<'a, I: IntoIterator<Item = &'a PackLevelDto>>(levels: I) -> Result<u64, String> {
+    let checksums = levels
+        .into_iter()
+        .map(|level| {
+            u64::from_str_radix(&level.checksum, 16)
+                .map_err(|_| format!("invalid checksum '{}'", level.checksum))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(fnv1a(checksums))
+}
+
+impl PackDto {
+    pub fn new<I: IntoIterator<Item = Grid<Tile<Square>>>>(name: &str, levels: I) -> Self {
+        let levels = levels
+            .into_iter()
+            .map(|grid| PackLevelDto {
+                checksum: format_checksum(content_checksum(&grid)),
+                level: LevelDto::from(&grid),
+                unknown: UnknownFields::default(),
+            })
+            .collect::<Vec<_>>();
+        PackDto {
+            version: VERSION,
+            name: name.to_string(),
+            checksum: format_checksum(pack_checksum(&levels).expect("formatted checksums")),
+            levels,
+            unknown: UnknownFields::default(),
+        }
+    }
+
+    /// The classic levels compiled into every binary
+    pub fn builtin() -> Self {
+        PackDto::new("builtin", builtin_levels())
+    }
+
+    /// Checks the checksum of every level and of the whole pack, returns the levels
+    pub fn verify(&self) -> Result<Vec<Grid<Tile<Square>>>, String> {
+        check_version(self.version)?;
+        if format_checksum(pack_checksum(&self.levels)?) != self.checksum {
+            return Err(format!("checksum mismatch of pack '{}'", self.name));
+        }
+        self.levels
+            .iter()
+            .enumerate()
+            .map(|(index, level)| {
+                let grid = level.level.to_grid()?;
+                if format_checksum(content_checksum(&grid)) == level.checksum {
+                    Ok(grid)
+                } else {
+                    Err(format!("checksum mismatch of level {index}"))
+                }
+            })
+            .collect()
+    }
+}
+
+/// Mean time of a single benchmark
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BenchResultDto {
+    pub name: String,
+    pub iterations: u32,
+    /// mean wall-clock time per iteration
+    pub nanoseconds: u64,
+}
+
+/// Results of a benchmark run, recorded per crate version to compare performance across versions
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BenchReportDto {
+    pub version: u32,
+    /// version of the game crate that was benchmarked
+    pub crate_version: String,
+    /// seconds since the unix epoch
+    pub timestamp: u64,
+    pub results: Vec<BenchResultDto>,
+}
+
+impl BenchReportDto {
+    pub fn new(timestamp: u64, results: Vec<BenchResultDto>) -> Self {
+        BenchReportDto {
+            version: VERSION,
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            timestamp,
+            results,
+        }
+    }
+
+    /// Rejects reports of unknown schema versions
+    pub fn verify(&self) -> Result<(), String> {
+        check_version(self.version)
+    }
+
+    /// mean time of the benchmark in this run
+    pub fn nanoseconds(&self, name: &str) -> Option<u64> {
+        self.results
+            .iter()
+            .find(|result| result.name == name)
+            .map(|result| result.nanoseconds)
+    }
+}
+
+/// Number of players online, counted from the heartbeats of their browser tabs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PresenceDto {
+    pub version: u32,
+    pub players: usize,
+}
+
+impl PresenceDto {
+    pub fn new(players: usize) -> Self {
+        PresenceDto {
+            version: VERSION,
+            players,
+        }
+    }
+
+    /// Rejects counts of unknown schema versions
+    pub fn verify(&self) -> Result<(), String> {
+        check_version(self.version)
+    }
+}
+
+/// upper bound of the rows and columns of levels generated for a [`GenerateRequestDto`]
+pub const MAX_GENERATED_SIZE: usize = 64;
+
+/// upper bound of the solutions listed for a [`SolveRequestDto`]
+pub const MAX_SOLUTIONS: usize = 100;
+
+/// Algorithm generating the level of a [`GenerateRequestDto`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlgorithmDto {
+    /// fast generation, the level may have several solutions
+    #[default]
+    Fastgen,
+    /// wave function collapse with all tiles
+    Wfc,
+    /// fast generation retried until the level has exactly one solution
+    Unique,
+}
+
+/// Request of a generated level, the same request yields the same level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct GenerateRequestDto {
+    pub version: u32,
+    pub rows: usize,
+    pub columns: usize,
+    #[serde(default)]
+    pub algorithm: AlgorithmDto,
+    #[serde(default)]
+    pub seed: u64,
+    /// generates a level whose edges wrap around, see [`Grid::into_torus`]
+    #[serde(default)]
+    pub torus: bool,
+}
+
+/// Scrambled level together with the solution it was generated from
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct GeneratedLevelDto {
+    pub version: u32,
+    pub level: LevelDto,
+    pub solution: LevelDto,
+}
+
+impl GenerateRequestDto {
+    /// Generates the requested level
+    ///
+    /// fails for unknown versions, more than [`MAX_GENERATED_SIZE`] rows or columns and failed generations
+    pub fn generate(&self) -> Result<GeneratedLevelDto, String> {
+        check_version(self.version)?;
+        if self.rows > MAX_GENERATED_SIZE || self.columns > MAX_GENERATED_SIZE {
+            return Err(format!(
+                "levels are generated with at most {MAX_GENERATED_SIZE} rows and columns"
+            ));
+        }
+        let dimension = Coordinate::new(self.rows, self.columns);
+        let (level, solution) = match self.algorithm {
+            AlgorithmDto::Fastgen => {
+                let solution = if self.torus {
+                    generate_torus(dimension, self.seed)
+                } else {
+                    generate(dimension, self.seed)
+                };
+                (solution.clone().scramble(self.seed), solution)
+            }
+            AlgorithmDto::Wfc => {
+                let generator = WfcGenerator::with_all_tiles(self.columns, self.rows);
+                let generator = if self.torus {
+                    generator.with_torus()
+                } else {
+                    generator
+                };
+                let solution = generator.generate_seeded(self.seed)?;
+                (solution.clone().scramble(self.seed), solution)
+            }
+            AlgorithmDto::Unique if self.torus => {
+                return Err("levels with a unique solution are not generated on a torus".into())
+            }
+            AlgorithmDto::Unique => generate_unique_with_solution(dimension, self.seed)
+                .ok_or_else(|| "no level with a unique solution found".to_string())?,
+        };
+        Ok(GeneratedLevelDto {
+            version: VERSION,
+            level: LevelDto::from(&level),
+            solution: LevelDto::from(&solution),
+        })
+    }
+}
+
+/// Request of the solutions of a level
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SolveRequestDto {
+    pub version: u32,
+    pub level: LevelDto,
+    /// number of solutions listed at most, capped by [`MAX_SOLUTIONS`], a single one if absent
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// Solutions of a level, in the order of the solver
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SolutionsDto {
+    pub version: u32,
+    pub solutions: Vec<LevelDto>,
+    /// whether the level has no further solutions beyond the listed ones
+    pub complete: bool,
+}
+
+impl SolveRequestDto {
+    pub fn solve(&self) -> Result<SolutionsDto, String> {
+        check_version(self.version)?;
+        self.level.check_size(MAX_GENERATED_SIZE)?;
+        let limit = self.limit.unwrap_or(1).min(MAX_SOLUTIONS);
+        let mut solutions = self
+            .level
+            .to_grid()?
+            .solve()
+            .take(limit + 1)
+            .map(|solution| LevelDto::from(&solution))
+            .collect::<Vec<_>>();
+        let complete = solutions.len() <= limit;
+        solutions.truncate(limit);
+        Ok(SolutionsDto {
+            version: VERSION,
+            solutions,
+            complete,
+        })
+    }
+}
+
+/// Report on a level as checked before publishing it
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ValidationDto {
+    pub version: u32,
+    pub solvable: bool,
+    /// whether the level has exactly one solution
+    pub unique: bool,
+    /// difficulty of a solvable level, see [`Difficulty`](crate::solver::difficulty::Difficulty)
+    pub difficulty: Option<String>,
+    /// violated designer constraints with the positions violating them
+    pub violations: Vec<String>,
+    /// warnings of [`lint`]
+    pub lints: Vec<String>,
+}
+
+impl ValidationDto {
+    /// Validates the level with the result of the solver, e.g. looked up in a [`SolverCache`](crate::solver::cache::SolverCache)
+    pub fn new(level: &LevelDto, result: &SolverResult) -> Result<Self, String> {
+        let grid = level.to_grid()?;
+        let violations = match validate(&grid, &level.to_constraints()?) {
+            Ok(()) => vec![],
+            Err(violations) => violations
+                .into_iter()
+                .map(|(constraint, coordinate)| format!("'{constraint}' violated at {coordinate}"))
+                .collect(),
+        };
+        Ok(ValidationDto {
+            version: VERSION,
+            solvable: result.solutions > 0,
+            unique: result.solutions == 1,
+            difficulty: (result.solutions > 0).then(|| result.difficulty.to_string()),
+            violations,
+            lints: lint(&grid).iter().map(ToString::to_string).collect(),
+        })
+    }
+}
+
+/// Level uploaded to the backend to be shared across devices
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SharedLevelDto {
+    pub version: u32,
+    /// assigned by the backend, uploading an equal level again yields the same id
+    pub id: u64,
+    /// rated by the backend, see [`Difficulty`]
+    pub difficulty: String,
+    pub level: LevelDto,
+}
+
+impl SharedLevelDto {
+    pub fn new(id: u64, difficulty: Difficulty, level: LevelDto) -> Self {
+        SharedLevelDto {
+            version: VERSION,
+            id,
+            difficulty: difficulty.to_string(),
+            level,
+        }
+    }
+
+    /// Rejects levels of unknown schema versions or with an unknown difficulty
+    pub fn verify(&self) -> Result<Difficulty, String> {
+        check_version(self.version)?;
+        self.difficulty.parse()
+    }
+}
+
+/// upper bound of the rows and columns of runs streamed for a [`LiveRunRequestDto`]
+pub const MAX_LIVE_SIZE: usize = MAX_LEVEL_SIZE;
+
+/// Request of a run streamed to the visualizer as snapshots, see [`crate::solver::snapshot`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct LiveRunRequestDto {
+    pub version: u32,
+    /// level to solve, if absent a level of the rows and columns is generated with wave function collapse
+    #[serde(default)]
+    pub level: Option<LevelDto>,
+    #[serde(default)]
+    pub rows: usize,
+    #[serde(default)]
+    pub columns: usize,
+    #[serde(default)]
+    pub seed: u64,
+}
+
+impl LiveRunRequestDto {
+    /// Snapshots of the requested run
+    ///
+    /// fails for unknown versions, invalid levels and more than [`MAX_LIVE_SIZE`] rows or columns
+    pub fn snapshots(
+        &self,
+    ) -> Result<Box<dyn Iterator<Item = Grid<Superposition<Square>>>>, String> {
+        check_version(self.version)?;
+        let (rows, columns) = self
+            .level
+            .as_ref()
+            .map_or((self.rows, self.columns), |level| {
+                (level.rows, level.columns)
+            });
+        if rows > MAX_LIVE_SIZE || columns > MAX_LIVE_SIZE {
+            return Err(format!(
+                "runs are streamed with at most {MAX_LIVE_SIZE} rows and columns"
+            ));
+        }
+        let level = self.level.as_ref().map(LevelDto::to_grid).transpose()?;
+        Ok(match level {
+            Some(level) => Box::new(solving_snapshots(&level)),
+            None => Box::new(generation_snapshots(
+                WfcGenerator::with_all_tiles(self.columns, self.rows),
+                self.seed,
+            )),
+        })
+    }
+}
+
+/// upper bound of the rows and columns of shared drafts
+pub const MAX_DRAFT_SIZE: usize = MAX_GENERATED_SIZE;
+
+/// Edit of a cell of a shared draft, see [`CellEdit`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CellEditDto {
+    pub cell: CellDto,
+    pub clock: u64,
+    pub editor: EditorId,
+}
+
+impl From<CellEdit> for CellEditDto {
+    fn from(edit: CellEdit) -> Self {
+        CellEditDto {
+            cell: CellDto {
+                row: edit.index.row,
+                column: edit.index.column,
+                connections: edit.tile.enum_to_index() as u8,
+            },
+            clock: edit.version.clock,
+            editor: edit.version.editor,
+        }
+    }
+}
+
+impl TryFrom<CellEditDto> for CellEdit {
+    type Error = String;
+
+    fn try_from(dto: CellEditDto) -> Result<Self, Self::Error> {
+        Ok(CellEdit {
+            index: Coordinate::new(dto.cell.row, dto.cell.column),
+            tile: Tile::index_to_enum(dto.cell.connections as u64)
+                .ok_or_else(|| format!("invalid connections {}", dto.cell.connections))?,
+            version: Version {
+                clock: dto.clock,
+                editor: dto.editor,
+            },
+        })
+    }
+}
+
+/// Cell selected by an editor of a shared draft, see [`Cursor`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CursorDto {
+    pub editor: EditorId,
+    pub row: isize,
+    pub column: isize,
+}
+
+impl From<Cursor> for CursorDto {
+    fn from(cursor: Cursor) -> Self {
+        CursorDto {
+            editor: cursor.editor,
+            row: cursor.index.row,
+            column: cursor.index.column,
+        }
+    }
+}
+
+impl From<CursorDto> for Cursor {
+    fn from(dto: CursorDto) -> Self {
+        Cursor {
+            editor: dto.editor,
+            index: Coordinate::new(dto.row, dto.column),
+        }
+    }
+}
+
+/// Message between the editors of a shared draft, relayed by the backend
+///
+/// an editor sends [`DraftMessageDto::Join`] first and is answered with [`DraftMessageDto::Joined`]. Afterwards
+/// the edits and cursor moves of every editor are relayed to the others, followed by [`DraftMessageDto::Left`]
+/// once the editor leaves
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DraftMessageDto {
+    /// opens the draft with the level, unless other editors have opened it before
+    Join {
+        version: u32,
+        level: LevelDto,
+    },
+    /// id of the joining editor, the level as edited so far and the latest edit of every edited cell
+    Joined {
+        editor: EditorId,
+        level: LevelDto,
+        edits: Vec<CellEditDto>,
+    },
+    Edit(CellEditDto),
+    Cursor(CursorDto),
+    Left {
+        editor: EditorId,
+    },
+}
+
+impl DraftMessageDto {
+    /// Request opening the draft with the level
+    pub fn join(grid: &Grid<Tile<Square>>) -> Self {
+        DraftMessageDto::Join {
+            version: VERSION,
+            level: LevelDto::from(grid),
+        }
+    }
+
+    /// Answer to the editor joining the draft
+    pub fn joined(editor: EditorId, draft: &SharedDraft) -> Self {
+        DraftMessageDto::Joined {
+            editor,
+            level: LevelDto::from(draft.grid()),
+            edits: draft.edits().map(CellEditDto::from).collect(),
+        }
+    }
+
+    /// Level a [`DraftMessageDto::Join`] opens the draft with
+    ///
+    /// fails for other messages, unknown versions, invalid levels and more than [`MAX_DRAFT_SIZE`] rows or columns
+    pub fn to_level(&self) -> Result<Grid<Tile<Square>>, String> {
+        match self {
+            DraftMessageDto::Join { version, level } => {
+                check_version(*version)?;
+                level.check_size(MAX_DRAFT_SIZE)?;
+                level.to_grid()
+            }
+            _ => Err("expected to join the draft".to_string()),
+        }
+    }
+
+    /// Copy of the editor answered by a [`DraftMessageDto::Joined`], caught up with the edits so far
+    pub fn to_draft(&self) -> Result<SharedDraft, String> {
+        match self {
+            DraftMessageDto::Joined {
+                editor,
+                level,
+                edits,
+            } => {
+                let mut draft = SharedDraft::new(*editor, level.to_grid()?);
+                for &edit in edits {
+                    draft
+                        .apply(edit.try_into()?)
+                        .map_err(|_| "edit outside of the draft".to_string())?;
+                }
+                Ok(draft)
+            }
+            _ => Err("expected to have joined the draft".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::generator::fastgen::generate;
+    use crate::model::{
+        collab::SharedDraft,
+        constraint::Constraint,
+        coordinate::Coordinate,
+        gameboard::{GameBoard, WinRule},
+        grid::Grid,
+        tile::{Geometry, Hex, Square, Tile, Triangle},
+    };
+    use crate::solver::cache::SolverResult;
+    use crate::solver::difficulty::Difficulty;
+
+    use super::{
+        AlgorithmDto, BenchReportDto, BenchResultDto, DraftMessageDto, GenerateRequestDto,
+        LevelDto, LiveRunRequestDto, PackDto, ScoreDto, SessionDto, SharedLevelDto,
+        SolveRequestDto, ValidationDto, FORMAT_VERSION, MAX_DRAFT_SIZE, MAX_GENERATED_SIZE,
+        MAX_LIVE_SIZE, VERSION,
+    };
+
+    #[quickcheck]
+    fn level_roundtrip(grid: Grid<Tile<Square>>) -> bool {
+        LevelDto::from(&grid).to_grid() == Ok(grid)
+    }
+
+    #[quickcheck]
+    fn level_json_roundtrip(grid: Grid<Tile<Square>>, constraints: Vec<Constraint>) -> bool {
+        let dto = LevelDto::with_constraints(&grid, &constraints);
+        let json = serde_json::to_string(&dto).expect("serializable");
+        serde_json::from_str::<LevelDto>(&json).map_or(false, |parsed| {
+            parsed.to_grid() == Ok(grid) && parsed.to_constraints() == Ok(constraints)
+        })
+    }
+
+    #[quickcheck]
+    fn session_roundtrip(grid: Grid<Tile<Square>>, moves: u32, elapsed: u64) -> bool {
+        SessionDto::new(&grid, moves, elapsed).to_grid() == Ok(grid)
+    }
+
+    #[quickcheck]
+    fn score_is_for_scrambled_level(grid: Grid<Tile<Square>>, seed: u64) -> bool {
+        let score = ScoreDto::new(&grid, "player".into(), 0, 0);
+        score.is_for(&grid.clone().scramble(seed)) == Ok(true)
+    }
+
+    #[quickcheck]
+    fn other_versions_are_rejected(grid: Grid<Tile<Square>>, version: u32) -> bool {
+        let dto = LevelDto {
+            version,
+            ..LevelDto::from(&grid)
+        };
+        dto.to_grid().is_ok() == (version == VERSION)
+    }
+
+    #[quickcheck]
+    fn pack_json_roundtrip_verifies(levels: Vec<Grid<Tile<Square>>>) -> bool {
+        let json =
+            serde_json::to_string(&PackDto::new("pack", levels.clone())).expect("serializable");
+        serde_json::from_str::<PackDto>(&json).map_or(false, |pack| pack.verify() == Ok(levels))
+    }
+
+    #[quickcheck]
+    fn modified_levels_fail_verification(levels: Vec<Grid<Tile<Square>>>, seed: u64) -> bool {
+        let mut pack = PackDto::new("pack", levels.clone());
+        let modified = levels
+            .iter()
+            .map(|level| level.clone().scramble(seed))
+            .collect::<Vec<_>>();
+        for (level, grid) in pack.levels.iter_mut().zip(&modified) {
+            level.level = grid.into();
+        }
+        pack.verify().is_ok() == (levels == modified)
+    }
+
+    #[test]
+    fn builtin_pack_verifies() {
+        let pack = PackDto::builtin();
+        assert!(pack.verify().is_ok());
+        assert!(!pack.levels.is_empty());
+    }
+
+    #[test]
+    fn missing_constraints_default_to_empty() {
+        let dto = serde_json::from_str::<LevelDto>(
+            r#"{"version":1,"rows":1,"columns":1,"cells":[{"row":0,"column":0,"connections":15}]}"#,
+        )
+        .expect("valid level");
+        assert_eq!(
+            dto.to_grid(),
+            Ok(Grid::new(1.into(), vec![Tile::ALL_CONNECTIONS]))
+        );
+        assert_eq!(dto.to_constraints(), Ok(vec![]));
+        assert_eq!(dto.to_win_rule(), Ok(WinRule::MatchingConnections));
+    }
+
+    #[test]
+    fn huge_levels_are_rejected_before_they_are_built() {
+        let huge = LevelDto {
+            rows: usize::MAX,
+            columns: usize::MAX,
+            ..LevelDto::from(&Grid::<Tile<Square>>::EMPTY)
+        };
+        assert!(huge.to_grid().is_err());
+        let request = SolveRequestDto {
+            version: VERSION,
+            level: LevelDto {
+                rows: MAX_GENERATED_SIZE + 1,
+                ..LevelDto::from(&Grid::<Tile<Square>>::EMPTY)
+            },
+            limit: None,
+        };
+        assert!(request.solve().is_err());
+    }
+
+    #[test]
+    fn win_rules_are_read_from_the_level() {
+        let dto = LevelDto {
+            win_rule: Some(WinRule::AllSourcesConnected.to_string()),
+            ..LevelDto::from(&Grid::<Tile<Square>>::EMPTY)
+        };
+        let json = serde_json::to_string(&dto).expect("serializable");
+        let parsed = serde_json::from_str::<LevelDto>(&json).expect("valid level");
+        assert_eq!(parsed.to_win_rule(), Ok(WinRule::AllSourcesConnected));
+    }
+
+    #[quickcheck]
+    fn levels_of_other_geometries_keep_their_geometry(grid: Grid<Tile<Hex>>) -> bool {
+        let json = serde_json::to_string(&LevelDto::from_tiles(&grid)).expect("serializable");
+        let dto = serde_json::from_str::<LevelDto>(&json).expect("valid level");
+        dto.to_geometry() == Ok(Geometry::Hex)
+            && dto.to_tiles::<Hex>() == Ok(grid)
+            && dto.to_tiles::<Triangle>().is_err()
+            && dto.to_grid().is_err()
+    }
+
+    #[test]
+    fn levels_without_format_version_belong_to_the_first_revision() {
+        let dto =
+            serde_json::from_str::<LevelDto>(r#"{"version":1,"rows":0,"columns":0,"cells":[]}"#)
+                .expect("valid level");
+        assert_eq!(dto.format_version, 1);
+        assert!(dto.unknown.is_empty());
+    }
+
+    #[test]
+    fn unknown_fields_of_newer_levels_are_preserved() {
+        let newer = serde_json::json!({
+            "version": 1,
+            "format_version": FORMAT_VERSION + 1,
+            "rows": 1,
+            "columns": 1,
+            "cells": [{"row": 0, "column": 0, "connections": 15}],
+            "constraints": [],
+            "win_rule": null,
+            "author": "designer",
+            "par": {"rotations": 3},
+        });
+        let dto = serde_json::from_value::<LevelDto>(newer.clone()).expect("readable level");
+        assert_eq!(
+            dto.to_grid(),
+            Ok(Grid::new(1.into(), vec![Tile::ALL_CONNECTIONS]))
+        );
+        assert_eq!(dto.unknown.0.len(), 2);
+        assert_eq!(serde_json::to_value(&dto).expect("serializable"), newer);
+    }
+
+    #[quickcheck]
+    fn unknown_fields_of_newer_packs_survive_a_roundtrip(
+        levels: Vec<Grid<Tile<Square>>>,
+        note: String,
+    ) -> bool {
+        let mut json =
+            serde_json::to_value(PackDto::new("pack", levels.clone())).expect("serializable");
+        json["curator"] = note.clone().into();
+        for level in json["levels"].as_array_mut().into_iter().flatten() {
+            level["stars"] = 3.into();
+            level["level"]["hint"] = note.clone().into();
+        }
+        serde_json::from_value::<PackDto>(json.clone()).map_or(false, |pack| {
+            pack.verify() == Ok(levels) && serde_json::to_value(&pack).ok() == Some(json)
+        })
+    }
+
+    #[test]
+    fn bench_reports_roundtrip() {
+        let report = BenchReportDto::new(
+            1_700_000_000,
+            vec![BenchResultDto {
+                name: "solve 10x10".to_string(),
+                iterations: 100,
+                nanoseconds: 12_345,
+            }],
+        );
+        let json = serde_json::to_string(&report).expect("serializable report");
+        let parsed = serde_json::from_str::<BenchReportDto>(&json).expect("valid report");
+        assert_eq!(parsed, report);
+        assert_eq!(parsed.verify(), Ok(()));
+        assert_eq!(parsed.nanoseconds("solve 10x10"), Some(12_345));
+        assert_eq!(parsed.nanoseconds("fastgen 10x10"), None);
+    }
+
+    #[test]
+    fn generate_requests_are_reproducible() {
+        for algorithm in [
+            AlgorithmDto::Fastgen,
+            AlgorithmDto::Wfc,
+            AlgorithmDto::Unique,
+        ] {
+            let request = GenerateRequestDto {
+                version: VERSION,
+                rows: 4,
+                columns: 5,
+                algorithm,
+                seed: 7,
+                torus: false,
+            };
+            let generated = request.generate().expect("generated level");
+            assert_eq!(request.generate(), Ok(generated.clone()));
+            let (level, solution) = (
+                generated.level.to_grid().unwrap(),
+                generated.solution.to_grid().unwrap(),
+            );
+            assert!(solution.is_solved());
+            assert!(level.scramble_distance(&solution).is_some());
+        }
+        let json = r#"{"version": 1, "rows": 100, "columns": 3}"#;
+        let oversized = serde_json::from_str::<GenerateRequestDto>(json).expect("valid request");
+        assert_eq!(oversized.algorithm, AlgorithmDto::Fastgen);
+        assert!(oversized.generate().is_err());
+    }
+
+    #[test]
+    fn torus_levels_keep_their_wrap() {
+        let request = GenerateRequestDto {
+            version: VERSION,
+            rows: 4,
+            columns: 4,
+            algorithm: AlgorithmDto::Fastgen,
+            seed: 3,
+            torus: true,
+        };
+        let generated = request.generate().expect("generated level");
+        let json = serde_json::to_string(&generated.solution).expect("serializable");
+        let solution = serde_json::from_str::<LevelDto>(&json)
+            .expect("valid level")
+            .to_grid()
+            .expect("valid grid");
+        assert!(solution.is_torus() && solution.is_solved());
+        assert!(generated
+            .level
+            .to_grid()
+            .map_or(false, |level| level.is_torus()));
+
+        let flat = serde_json::to_string(&LevelDto::from(&generate(Coordinate::new(4, 4), 3)));
+        assert!(!flat.expect("serializable").contains("torus"));
+        let unique = GenerateRequestDto {
+            algorithm: AlgorithmDto::Unique,
+            ..request
+        };
+        assert!(unique.generate().is_err());
+    }
+
+    #[test]
+    fn solutions_are_listed_up_to_the_limit() {
+        let level = generate(Coordinate::new(4, 4), 3).scramble(3);
+        let all = level.solve().take(4).collect::<Vec<_>>();
+        for limit in [None, Some(2), Some(3)] {
+            let request = SolveRequestDto {
+                version: VERSION,
+                level: LevelDto::from(&level),
+                limit,
+            };
+            let limit = limit.unwrap_or(1);
+            let listed = request.solve().expect("solvable level");
+            assert_eq!(listed.complete, all.len() <= limit);
+            assert!(listed
+                .solutions
+                .iter()
+                .map(|solution| solution.to_grid().unwrap())
+                .eq(all.iter().take(limit).cloned()));
+        }
+    }
+
+    #[test]
+    fn validation_reports_solvability() {
+        let validated = |level: &str| {
+            let grid = level.parse::<Grid<Tile<Square>>>().unwrap();
+            ValidationDto::new(&LevelDto::from(&grid), &SolverResult::compute(&grid)).unwrap()
+        };
+        let unique = validated("╺╸");
+        assert!(unique.solvable && unique.unique);
+        assert!(unique.difficulty.is_some());
+        let unsolvable = validated("╺━");
+        assert!(!unsolvable.solvable && !unsolvable.unique);
+        assert_eq!(unsolvable.difficulty, None);
+    }
+
+    #[test]
+    fn shared_levels_carry_their_difficulty() {
+        let level = LevelDto::from(&generate(Coordinate::new(3, 3), 0));
+        let shared = SharedLevelDto::new(7, Difficulty::Medium, level);
+        let json = serde_json::to_string(&shared).expect("serializable");
+        let parsed = serde_json::from_str::<SharedLevelDto>(&json).expect("parsable");
+        assert_eq!(parsed.verify(), Ok(Difficulty::Medium));
+        let unknown = SharedLevelDto {
+            difficulty: "impossible".to_string(),
+            ..parsed
+        };
+        assert!(unknown.verify().is_err());
+    }
+
+    #[test]
+    fn live_runs_solve_the_level_or_generate_one() {
+        let grid = generate(Coordinate::new(5, 5), 3);
+        let solving = serde_json::from_str::<LiveRunRequestDto>(&format!(
+            r#"{{"version": {VERSION}, "level": {}}}"#,
+            serde_json::to_string(&LevelDto::from(&grid.clone().scramble(3))).unwrap()
+        ))
+        .expect("parsable");
+        let last = solving.snapshots().unwrap().last().unwrap();
+        assert!(last.as_slice().iter().all(|s| s.len() == 1));
+        let generating = LiveRunRequestDto {
+            version: VERSION,
+            level: None,
+            rows: 4,
+            columns: 6,
+            seed: 1,
+        };
+        let last = generating.snapshots().unwrap().last().unwrap();
+        assert_eq!(last.dimensions(), Coordinate::new(4, 6));
+        let huge = LiveRunRequestDto {
+            rows: MAX_LIVE_SIZE + 1,
+            ..generating
+        };
+        assert!(huge.snapshots().is_err());
+    }
+
+    #[test]
+    fn joining_editors_receive_the_draft_so_far() {
+        let grid = generate(Coordinate::new(3, 4), 2);
+        let join = serde_json::to_string(&DraftMessageDto::join(&grid)).unwrap();
+        let join = serde_json::from_str::<DraftMessageDto>(&join).expect("parsable");
+        let mut draft = SharedDraft::new(0, join.to_level().unwrap());
+        let index = Coordinate::new(1, 2);
+        let edit = draft.edit(index, grid[index].rotated_clockwise(1)).unwrap();
+        let joined = serde_json::to_string(&DraftMessageDto::joined(1, &draft)).unwrap();
+        let copy = serde_json::from_str::<DraftMessageDto>(&joined)
+            .expect("parsable")
+            .to_draft()
+            .unwrap();
+        let mut copy = copy;
+        assert_eq!(copy.grid(), draft.grid());
+        assert_eq!(copy.apply(edit), Ok(false));
+        let edit = DraftMessageDto::Edit(edit.into());
+        assert!(serde_json::to_string(&edit)
+            .unwrap()
+            .contains(r#""type":"edit""#));
+        let huge = Grid::filled_with(
+            Coordinate::new(MAX_DRAFT_SIZE + 1, 1),
+            Tile::<Square>::NO_CONNECTIONS,
+        );
+        assert!(DraftMessageDto::join(&huge).to_level().is_err());
+        assert!(edit.to_level().is_err());
+    }
+}
@@ -39,41 +39,255 @@ impl<A: Finite> EnumSet<A> {
 
 /// Generates level deterministically
 pub fn generate(dimension: Coordinate<usize>, seed: u64) -> Grid<Tile<Square>> {
-    let minimized_grid = Grid::init(dimension, |_| EnumSet::FULL)
+    generate_with(dimension, &mut StdRng::seed_from_u64(seed))
+}
+
+/// Generates level drawing random numbers from `rng`, e.g. a [`crate::config::RngSource`]
+pub fn generate_with<R: Rng + ?Sized>(
+    dimension: Coordinate<usize>,
+    rng: &mut R,
+) -> Grid<Tile<Square>> {
+    generate_from(Grid::init(dimension, |_| EnumSet::FULL), rng)
+}
+
+/// Generates a level deterministically, whose connections may wrap around the edges
+///
+/// see [`Grid::into_torus`]
+pub fn generate_torus(dimension: Coordinate<usize>, seed: u64) -> Grid<Tile<Square>> {
+    // a single row or column neighbors itself across the wrap, which propagation does not catch
+    let wraps_onto_itself = |tile: &Tile<Square>| {
+        (dimension.row == 1 && tile.0.contains(Square::Up) != tile.0.contains(Square::Down))
+            || (dimension.column == 1
+                && tile.0.contains(Square::Left) != tile.0.contains(Square::Right))
+    };
+    let superposition = EnumSet::<Tile<Square>>::FULL
+        .into_iter()
+        .filter(|tile| !wraps_onto_itself(tile))
+        .collect::<EnumSet<_>>();
+    generate_from(
+        Grid::init(dimension, |_| superposition).into_torus(),
+        &mut StdRng::seed_from_u64(seed),
+    )
+}
+
+/// Generates a level of the shape deterministically, cells outside the mask become holes
+pub fn generate_shaped(mask: &Grid<bool>, seed: u64) -> Grid<Option<Tile<Square>>> {
+    generate_shaped_with(mask, &mut StdRng::seed_from_u64(seed))
+}
+
+/// see [`generate_shaped`], drawing random numbers from `rng`
+pub fn generate_shaped_with<R: Rng + ?Sized>(
+    mask: &Grid<bool>,
+    rng: &mut R,
+) -> Grid<Option<Tile<Square>>> {
+    // holes are fixed to tiles without connections, so no connection leads into them
+    let superpositions = mask.map(|open| {
+        if open {
+            EnumSet::FULL
+        } else {
+            Tile::NO_CONNECTIONS.into()
+        }
+    });
+    generate_from(superpositions, rng).cut(mask)
+}
+
+/// collapses the superpositions to a solved level
+fn generate_from<R: Rng + ?Sized>(
+    superpositions: Grid<EnumSet<Tile<Square>>>,
+    rng: &mut R,
+) -> Grid<Tile<Square>> {
+    let minimized_grid = superpositions
         .with_sentinels(Tile::NO_CONNECTIONS.into())
         .minimize();
+    // an odd dimension of a torus breaks the checkerboard at the wrap, its last row or column is left out
+    let (rows, columns) = minimized_grid.0.dimensions().to_tuple();
+    let torus = minimized_grid.0.is_torus();
+    let wraps_odd = |c: Coordinate<isize>| {
+        torus
+            && (rows % 2 == 1 && c.row as usize == rows - 1
+                || columns % 2 == 1 && c.column as usize == columns - 1)
+    };
     let grid = minimized_grid
         .0 // keep sentinel layer for
         .with_index()
-        .zip(StdRng::seed_from_u64(seed).sample_iter(Standard))
+        .zip(rng.sample_iter(Standard))
         .map(|((c, e), r)| {
-            if c.sum() % 2 == 0 {
+            if c.sum() % 2 == 0 && !wraps_odd(c) {
                 e.collapse_random(r)
             } else {
                 e
             }
         });
-    SentinelGrid(grid)
-        .minimize()
-        .extract_if_collapsed()
-        .expect("error in algorithm")
+    let mut grid = SentinelGrid(grid).minimize();
+    // only reached by the cells left out of the checkerboard
+    while let Some(index) = grid.0.coordinates().find(|&c| grid.0[c].len() > 1) {
+        grid.0[index] = grid.0[index].collapse_random(rng.gen());
+        grid = grid.minimize();
+    }
+    grid.extract_if_collapsed().expect("error in algorithm")
+}
+
+/// levels generated before giving up on a unique solution
+pub const UNIQUE_ATTEMPTS: u64 = 100;
+
+/// scrambled puzzle together with its solution
+pub type PuzzleWithSolution<A = Square> = (Grid<Tile<A>>, Grid<Tile<A>>);
+
+/// Generates a scrambled level with exactly one solution deterministically
+///
+/// ambiguous levels are regenerated with the next seed,
+/// returns [`None`] if no attempt succeeds within [`UNIQUE_ATTEMPTS`]
+pub fn generate_unique(dimension: Coordinate<usize>, seed: u64) -> Option<Grid<Tile<Square>>> {
+    generate_unique_with_solution(dimension, seed).map(|(puzzle, _)| puzzle)
+}
+
+/// Generates a scrambled level with exactly one solution together with its solution
+///
+/// see [`generate_unique`]
+pub fn generate_unique_with_solution(
+    dimension: Coordinate<usize>,
+    seed: u64,
+) -> Option<PuzzleWithSolution> {
+    (0..UNIQUE_ATTEMPTS)
+        .map(|attempt| generate(dimension, seed.wrapping_add(attempt)))
+        .find(Grid::has_unique_solution)
+        .map(|solution| (solution.clone().scramble(seed), solution))
+}
+
+/// Generates level deterministically, which is solved by exactly `rotations` clockwise rotations
+///
+/// preset for onboarding, as small values of `rotations` yield almost solved levels
+///
+/// see [`Grid::scramble_by`]
+pub fn generate_almost_solved(
+    dimension: Coordinate<usize>,
+    rotations: u64,
+    seed: u64,
+) -> Grid<Tile<Square>> {
+    generate(dimension, seed).scramble_by(rotations, seed)
+}
+
+impl<A: Finite> Tile<A> {
+    /// Minimal number of clockwise rotations turning this tile into the target
+    ///
+    /// returns [`None`] if both tiles differ in shape
+    pub fn rotation_distance(self, target: Self) -> Option<u64> {
+        (0..A::CARDINALITY).find(|&n| self.rotated_clockwise(n).0 == target.0)
+    }
 }
 
 impl<A: Finite> Grid<Tile<A>> {
     pub fn scramble(self, seed: u64) -> Self {
+        self.scramble_with(&mut StdRng::seed_from_u64(seed))
+    }
+
+    /// Rotates every tile randomly, drawing random numbers from `rng`
+    pub fn scramble_with<R: Rng + ?Sized>(self, rng: &mut R) -> Self {
         let distribution = Uniform::new(0, A::CARDINALITY);
-        self.zip(StdRng::seed_from_u64(seed).sample_iter(distribution))
+        self.zip(rng.sample_iter(distribution))
             .map(|(t, r)| t.rotated_clockwise(r))
     }
+
+    /// Minimal number of clockwise rotations turning this level into the target
+    ///
+    /// returns [`None`] if the dimensions or the shape of any tile differ
+    pub fn scramble_distance(&self, target: &Self) -> Option<u64> {
+        if self.dimensions() != target.dimensions() {
+            return None;
+        }
+        self.as_slice()
+            .iter()
+            .zip(target.as_slice())
+            .map(|(&tile, &target)| tile.rotation_distance(target))
+            .sum()
+    }
+
+    /// Scrambles by applying `rotations` counterclockwise rotations to random tiles
+    ///
+    /// the [scramble distance](Grid::scramble_distance) to the original level is exactly `rotations`,
+    /// unless it exceeds the maximal distance, in which case every tile is rotated as far as possible
+    pub fn scramble_by(self, rotations: u64, seed: u64) -> Self {
+        self.scramble_by_with(rotations, &mut StdRng::seed_from_u64(seed))
+    }
+
+    /// see [`Grid::scramble_by`], drawing random numbers from `rng`
+    pub fn scramble_by_with<R: Rng + ?Sized>(self, rotations: u64, random: &mut R) -> Self {
+        // remaining counterclockwise rotations each tile can absorb without returning to its original orientation
+        let mut capacity = self
+            .as_slice()
+            .iter()
+            .map(|&tile| tile.superimpose().len() as u64 - 1)
+            .collect::<Vec<_>>();
+        let mut applied = vec![0; capacity.len()];
+
+        for _ in 0..rotations {
+            let candidates = (0..capacity.len())
+                .filter(|&i| capacity[i] > 0)
+                .collect::<Vec<_>>();
+            if candidates.is_empty() {
+                break;
+            }
+            let index = candidates[random.gen_range(0..candidates.len())];
+            capacity[index] -= 1;
+            applied[index] += 1;
+        }
+
+        self.zip(applied)
+            .map(|(tile, n)| tile.rotated_counterclockwise(n))
+    }
 }
 
 #[cfg(test)]
 mod test {
 
     use crate::core::interval::Max;
+    use crate::model::gameboard::GameBoard;
 
     use super::*;
 
+    #[quickcheck]
+    fn scramble_by_has_exact_distance(
+        dimension: Coordinate<Max<10>>,
+        rotations: Max<20>,
+        seed: u64,
+    ) -> bool {
+        let solved = generate(dimension.map(Max::to_usize), seed);
+        let maximum = solved.clone().scramble_by(u64::MAX, seed);
+        let rotations = rotations.to_usize() as u64;
+        let scrambled = solved.clone().scramble_by(rotations, seed);
+        scrambled.scramble_distance(&solved)
+            == maximum.scramble_distance(&solved).map(|m| m.min(rotations))
+    }
+
+    #[quickcheck]
+    fn scramble_distance_to_itself_is_zero(grid: Grid<Tile<Square>>) -> bool {
+        grid.scramble_distance(&grid) == Some(0)
+    }
+
+    #[quickcheck]
+    fn scramble_distance_after_scramble_is_defined(grid: Grid<Tile<Square>>, seed: u64) -> bool {
+        grid.clone()
+            .scramble(seed)
+            .scramble_distance(&grid)
+            .is_some()
+    }
+
+    #[quickcheck]
+    fn unique_levels_are_solved_by_their_solution_only(
+        dimension: Coordinate<Max<8>>,
+        seed: u64,
+    ) -> bool {
+        generate_unique_with_solution(dimension.map(Max::to_usize), seed)
+            .map_or(true, |(puzzle, solution)| {
+                puzzle.solve().take(2).eq([solution])
+            })
+    }
+
+    #[test]
+    fn unique_levels_are_found() {
+        assert!((0..10).all(|seed| generate_unique(Coordinate::new(6, 6), seed).is_some()));
+    }
+
     #[quickcheck]
     fn generated_levels_are_solvable(dimension: Coordinate<Max<20>>, seed: u64) -> bool {
         generate(dimension.map(Max::to_usize), seed)
@@ -81,4 +295,63 @@ mod test {
             .next()
             .is_some()
     }
+
+    #[quickcheck]
+    fn torus_levels_are_solvable(dimension: Coordinate<Max<8>>, seed: u64) -> bool {
+        let level = generate_torus(dimension.map(Max::to_usize), seed);
+        level.is_torus()
+            && level.is_solved()
+            && level
+                .solve()
+                .next()
+                .map_or(false, |solution| solution.is_torus())
+    }
+
+    #[quickcheck]
+    fn shaped_levels_keep_their_holes(mask: Grid<bool>, seed: u64) -> bool {
+        let level = generate_shaped(&mask, seed);
+        level.mask() == mask
+            && level.is_solved()
+            && level
+                .solve()
+                .next()
+                .map_or(false, |solution| solution.mask() == mask)
+    }
+
+    /// seed, dimension and generated level, pinned so changes to the generator can't silently alter
+    /// the published campaign levels
+    const GOLDEN: [(u64, (usize, usize), &[&str]); 4] = [
+        (0, (3, 3), &["┏┓ ", "┗┻╸", "╺━╸"]),
+        (1, (4, 5), &["╻┏┓ ╻", "┣┛╹╺┛", "┗━━┓╻", "   ┗┛"]),
+        (
+            42,
+            (6, 6),
+            &["╻ ╺┓╻╻", "╹╻╺╋┫┃", "╺┫╻╹┗┫", "╻┗┫┏┳┫", "┗┓╹┣┫╹", "╺┛ ┗┛ "],
+        ),
+        (
+            2023,
+            (8, 5),
+            &[
+                "╻ ╺╸╻",
+                "┗┳━━┛",
+                "┏╋┳┓╻",
+                "┗┛┗┛╹",
+                " ╻╺╸ ",
+                " ╹╻╻╻",
+                "╻┏┻╋┛",
+                "╹┗╸┗╸",
+            ],
+        ),
+    ];
+
+    #[test]
+    fn generated_levels_match_the_golden_corpus() {
+        for (seed, (rows, columns), level) in GOLDEN {
+            assert_eq!(
+                generate(Coordinate::new(rows, columns), seed).to_string(),
+                level.join("\n"),
+                "seed {seed}"
+            );
+        }
+    }
 }
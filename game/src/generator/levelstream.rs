@@ -4,33 +4,136 @@ use quickcheck::Arbitrary;
 
 use crate::model::{
     coordinate::Coordinate,
+    gameboard::{GameBoard, WinRule},
     grid::Grid,
     parser::{ascii_to_tile, parse_level, TEST_LEVELS},
     tile::{Square, Tile},
 };
 
-use super::fastgen::generate;
-use crate::core::interval::Max;
+use super::{fastgen::generate, wfc::WfcGenerator};
+use crate::core::{enumset::EnumSet, interval::Max};
+use crate::solver::difficulty::{rate, Difficulty};
 
 ///! level generator is an infinite stream of functions from integer seed value to level
 ///! and is defined by an anamorphism
 
 /// all relevant metadata about levels in the context of level generation
+///
+/// levels are generated until they meet all constraints, see [`generate_with_property`]
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct LevelProperty {
     pub dimension: Coordinate<usize>,
-    // maybe some notion of difficulty
-    // ...
+    /// requested difficulty, any difficulty if absent
+    pub difficulty: Option<Difficulty>,
+    /// allowed tiles, each in all its rotations
+    pub tiles: EnumSet<Tile<Square>>,
+    /// maximal percentage of walls, i.e. cells without connections, any number of walls if absent
+    pub max_wall_density: Option<u8>,
+    /// rule the generated solution must satisfy
+    pub rule: WinRule,
+}
+
+impl LevelProperty {
+    /// levels of the dimension without further constraints
+    pub fn new(dimension: Coordinate<usize>) -> Self {
+        LevelProperty {
+            dimension,
+            difficulty: None,
+            tiles: EnumSet::FULL,
+            max_wall_density: None,
+            rule: WinRule::default(),
+        }
+    }
+
+    /// Checks whether the level meets all constraints
+    pub fn is_met_by(&self, level: &Grid<Tile<Square>>) -> bool {
+        let alphabet = self.alphabet();
+        let walls = level
+            .as_slice()
+            .iter()
+            .filter(|&&tile| tile == Tile::NO_CONNECTIONS)
+            .count();
+        level.dimensions() == self.dimension
+            && self.rule.is_met_by(level)
+            && level.as_slice().iter().all(|&tile| alphabet.contains(tile))
+            && self.max_wall_density.map_or(true, |density| {
+                walls * 100 <= density as usize * level.size()
+            })
+            && self
+                .difficulty
+                .map_or(true, |difficulty| rate(level) == difficulty)
+    }
+
+    /// allowed tiles closed under rotation, without walls if they are forbidden
+    fn alphabet(&self) -> EnumSet<Tile<Square>> {
+        let alphabet = EnumSet::union_all(self.tiles.iter().map(|tile| tile.superimpose()));
+        if self.max_wall_density == Some(0) {
+            alphabet.removed(Tile::NO_CONNECTIONS)
+        } else {
+            alphabet
+        }
+    }
 }
 
 impl Arbitrary for LevelProperty {
     fn arbitrary(g: &mut quickcheck::Gen) -> Self {
         LevelProperty {
             dimension: Coordinate::<Max<10>>::arbitrary(g).map(Max::to_usize),
+            difficulty: *g
+                .choose(&[
+                    None,
+                    Some(Difficulty::Easy),
+                    Some(Difficulty::Medium),
+                    Some(Difficulty::Hard),
+                ])
+                .unwrap(),
+            // restricted alphabets are the exception
+            tiles: if bool::arbitrary(g) {
+                EnumSet::FULL
+            } else {
+                EnumSet::arbitrary(g)
+            },
+            max_wall_density: *g.choose(&[None, Some(0), Some(25), Some(50)]).unwrap(),
+            rule: *g.choose(&WinRule::ALL).unwrap(),
         }
     }
 }
 
+/// seeds tried for a level meeting the constraints
+const MAX_ATTEMPTS: u64 = 10;
+
+/// Generates a candidate level built from the allowed tiles
+fn generate_candidate(property: &LevelProperty, seed: u64) -> Grid<Tile<Square>> {
+    let dimension = property.dimension;
+    let alphabet = property.alphabet();
+    if alphabet == EnumSet::FULL {
+        generate(dimension, seed)
+    } else {
+        WfcGenerator::new(dimension.column, dimension.row, alphabet, 1000, 40000)
+            .generate_seeded(seed)
+            .ok()
+            // the generator may give up with dangling connections
+            .filter(|level| level.is_solved())
+            // the alphabet may be unable to build a level, which is then rejected by the constraints
+            .unwrap_or_else(|| generate(dimension, seed))
+    }
+}
+
+/// Generates a level meeting the constraints of the property
+///
+/// best effort: returns the level of the last attempt if no level meets the constraints,
+/// e.g. large levels are never easy
+pub fn generate_with_property(property: LevelProperty, seed: u64) -> Grid<Tile<Square>> {
+    let mut level = Grid::EMPTY;
+    for attempt in 0..MAX_ATTEMPTS {
+        level = generate_candidate(&property, seed.wrapping_add(attempt));
+        if property.is_met_by(&level) {
+            break;
+        }
+    }
+    level
+}
+
 /// holds the information to generate successive values in an infinite iterator stream
 ///
 /// represents canonical stream anamorphism / unfold with step function is the coalgebra of the stream type
@@ -88,11 +191,11 @@ fn generate_levels(
     property: LevelProperty,
 ) -> impl Iterator<Item = Box<dyn Fn(u64) -> Grid<Tile<Square>>>> {
     Unfold::new(property, |p| {
-        let dimension = p.dimension;
         (
-            move |seed| generate(dimension, seed),
+            move |seed| generate_with_property(p, seed),
             LevelProperty {
-                dimension: dimension + 1,
+                dimension: p.dimension + 1,
+                ..p
             },
         )
     })
@@ -105,12 +208,30 @@ fn constant<A: Clone + 'static, B>(value: A) -> Box<dyn Fn(B) -> A> {
     Box::new(move |_| value.clone())
 }
 
-/// hardcoded levels ignore seed value
-fn hardcoded_levels() -> impl Iterator<Item = Box<dyn Fn(u64) -> Grid<Tile<Square>>>> {
+/// the hardcoded levels shipped with the game
+pub fn builtin_levels() -> impl Iterator<Item = Grid<Tile<Square>>> {
     TEST_LEVELS
         .into_iter()
         .map(|l| parse_level(l, ascii_to_tile).unwrap())
-        .map(constant)
+}
+
+/// Finds the builtin level most similar to the given one, if its [similarity](Grid::similarity) reaches the threshold
+///
+/// returns the index into [`builtin_levels`] together with the similarity
+pub fn find_builtin_near_duplicate(
+    grid: &Grid<Tile<Square>>,
+    threshold: f32,
+) -> Option<(usize, f32)> {
+    builtin_levels()
+        .map(|level| level.similarity(grid))
+        .enumerate()
+        .filter(|&(_, similarity)| similarity >= threshold)
+        .max_by(|(_, x), (_, y)| x.total_cmp(y))
+}
+
+/// hardcoded levels ignore seed value
+fn hardcoded_levels() -> impl Iterator<Item = Box<dyn Fn(u64) -> Grid<Tile<Square>>>> {
+    builtin_levels().map(constant)
 }
 
 /// stream of the hardcoded levels followed by infinitely many randomly generated levels
@@ -168,6 +289,7 @@ struct IterateMut<A> {
 mod test {
 
     use super::*;
+    use crate::enumset;
 
     #[quickcheck]
     fn level_stream_is_solvable(property: LevelProperty) -> bool {
@@ -177,4 +299,62 @@ mod test {
             .enumerate()
             .all(|(i, f)| f(i as u64).solve().next().is_some())
     }
+
+    #[quickcheck]
+    fn constraints_are_met_if_possible(property: LevelProperty, seed: u64) -> bool {
+        // small levels keep the test fast
+        let property = LevelProperty {
+            dimension: property.dimension.map(|x| x.min(4)),
+            ..property
+        };
+        let level = generate_with_property(property, seed);
+        let possible = (0..MAX_ATTEMPTS).any(|attempt| {
+            property.is_met_by(&generate_candidate(&property, seed.wrapping_add(attempt)))
+        });
+        possible == property.is_met_by(&level)
+    }
+
+    #[test]
+    fn restricted_tiles_are_respected() {
+        let corner = Tile(enumset!(Square::Up, Square::Right));
+        let property = LevelProperty {
+            tiles: enumset!(corner),
+            ..LevelProperty::new(Coordinate::new(4, 4))
+        };
+        let level = generate_with_property(property, 0);
+        assert!(property.is_met_by(&level));
+        assert!(level
+            .as_slice()
+            .iter()
+            .all(|&tile| tile.superimpose() == corner.superimpose()));
+    }
+
+    #[test]
+    fn walls_are_limited() {
+        let property = LevelProperty {
+            max_wall_density: Some(0),
+            ..LevelProperty::new(Coordinate::new(5, 5))
+        };
+        let level = generate_with_property(property, 0);
+        assert!(level
+            .as_slice()
+            .iter()
+            .all(|&tile| tile != Tile::NO_CONNECTIONS));
+    }
+
+    #[test]
+    fn single_loops_are_generated() {
+        let property = LevelProperty {
+            rule: WinRule::SingleLoop,
+            ..LevelProperty::new(Coordinate::new(4, 4))
+        };
+        assert!(generate_with_property(property, 0).is_solved_single_loop());
+    }
+
+    #[quickcheck]
+    fn scrambled_builtin_levels_are_near_duplicates(seed: u64) -> bool {
+        builtin_levels().all(|level| {
+            find_builtin_near_duplicate(&level.scramble(seed), 1.0).map(|(_, s)| s) == Some(1.0)
+        })
+    }
 }
@@ -1,3 +1,4 @@
 pub mod fastgen;
 pub mod levelstream;
+pub mod practice;
 pub mod wfc;
@@ -0,0 +1,104 @@
+//! Practice levels drill the recognition of specific patterns by restricting levels to a chosen alphabet of tile shapes
+
+use crate::core::enumset::EnumSet;
+use crate::model::{
+    coordinate::Coordinate,
+    grid::Grid,
+    tile::{Square, Tile},
+};
+
+use super::wfc::WfcGenerator;
+
+/// seeds tried for a single level before the alphabet is considered unable to build a level
+const MAX_ATTEMPTS: u64 = 100;
+
+/// Endless deterministic sequence of scrambled levels only built from the given tile shapes
+///
+/// every shape is available in all its rotations, e.g. `{ [┗] }` yields levels of corners only.
+/// The sequence ends early if no level of the dimensions can be built from the shapes, e.g. `{ [╋] }`
+#[derive(Clone, PartialEq, Eq)]
+pub struct PracticeLevels {
+    generator: WfcGenerator,
+    seed: u64,
+}
+
+impl PracticeLevels {
+    pub fn new(dimension: Coordinate<usize>, shapes: EnumSet<Tile<Square>>, seed: u64) -> Self {
+        let alphabet = EnumSet::union_all(shapes.iter().map(Tile::superimpose));
+        PracticeLevels {
+            generator: WfcGenerator::new(dimension.column, dimension.row, alphabet, 1000, 40000),
+            seed,
+        }
+    }
+}
+
+impl Iterator for PracticeLevels {
+    type Item = Grid<Tile<Square>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        (0..MAX_ATTEMPTS).find_map(|_| {
+            let seed = self.seed;
+            self.seed = self.seed.wrapping_add(1);
+            self.generator
+                .generate_seeded(seed)
+                .ok()
+                // levels without any connection are nothing to practice on
+                .filter(|grid| {
+                    grid.as_slice()
+                        .iter()
+                        .any(|&tile| tile != Tile::NO_CONNECTIONS)
+                })
+                .map(|grid| grid.scramble(seed))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::core::enumset::EnumSet;
+    use crate::model::{
+        coordinate::Coordinate,
+        tile::{
+            Square::{Down, Left, Right, Up},
+            Tile,
+        },
+    };
+    use crate::{enumset, tile};
+
+    use super::PracticeLevels;
+
+    #[quickcheck]
+    fn practice_levels_are_solvable_and_restricted_to_the_shapes(seed: u64) -> bool {
+        let corner = tile!(Up, Right);
+        PracticeLevels::new(Coordinate::new(4, 4), enumset!(corner), seed)
+            .take(3)
+            .all(|level| {
+                level
+                    .as_slice()
+                    .iter()
+                    .all(|&tile| tile.superimpose() == corner.superimpose())
+                    && level.solve().next().is_some()
+            })
+    }
+
+    #[quickcheck]
+    fn practice_levels_are_deterministic(seed: u64) -> bool {
+        let shapes = enumset!(tile!(Up, Down), tile!(Up, Right), tile!(Up, Right, Down));
+        let levels = PracticeLevels::new(Coordinate::new(5, 5), shapes, seed);
+        levels.clone().take(2).eq(levels.take(2))
+    }
+
+    #[test]
+    fn impossible_shapes_end_the_sequence() {
+        let crossing = tile!(Up, Right, Down, Left);
+        let mut levels = PracticeLevels::new(Coordinate::new(3, 3), enumset!(crossing), 0);
+        assert_eq!(levels.next(), None);
+    }
+
+    #[test]
+    fn empty_shapes_end_the_sequence() {
+        let mut levels = PracticeLevels::new(Coordinate::new(3, 3), EnumSet::EMPTY, 0);
+        assert_eq!(levels.next(), None);
+    }
+}
@@ -1,13 +1,10 @@
 use rand::Rng;
-use std::{fmt::Display, hash::Hash};
+use std::fmt::Display;
 
 use crate::model::{
     coordinate::Coordinate,
     grid::Grid,
-    tile::{
-        Square::{self},
-        Tile,
-    },
+    tile::{Square, Tile, Topology},
 };
 
 use crate::core::{
@@ -16,9 +13,11 @@ use crate::core::{
     finite::{all_enums_ascending, Finite},
 };
 
-use crate::solver::propagationsolver::{
-    propagate_restrictions_to_all_neighbors, Sentinel, Superposition,
-};
+use super::fastgen::{PuzzleWithSolution, UNIQUE_ATTEMPTS};
+
+use crate::config::RngSource;
+
+use crate::solver::propagationsolver::{Sentinel, Superposition};
 
 ///! This file contains a implementation of the wave function collapse (WFC) algorithm for our game.
 ///! Wave function collapse is a constraint-based method of generating a map/level with the given rules
@@ -27,20 +26,18 @@ use crate::solver::propagationsolver::{
 /// This will enable displaying the current uncompleted map after each iteration
 const PRINT_INTERMEDIATE_RESULTS: bool = false;
 
-impl<A: Finite + Eq + Hash + Clone + Copy + Display> EnumSet<A> {
+impl<A: Finite + Copy + Eq> EnumSet<A> {
     fn is_collapsed(&self) -> bool {
         self.len() <= 1
     }
 
-    fn collapse(&mut self, weights: &EnumMap<A, usize>) {
+    fn collapse<R: Rng + ?Sized>(&mut self, weights: &EnumMap<A, usize>, rng: &mut R) {
         let mut weight: f64;
         let mut option_weights: EnumMap<A, f64> = EnumMap::empty();
         let mut total_weight: f64 = 0.0;
 
-        let mut rng = rand::thread_rng();
-
         for cell_option in self.iter() {
-            weight = weights[cell_option].unwrap_or(0) as f64;
+            weight = weights[cell_option] as f64;
             total_weight += weight;
             option_weights.insert(cell_option, weight);
         }
@@ -61,67 +58,130 @@ impl<A: Finite + Eq + Hash + Clone + Copy + Display> EnumSet<A> {
     }
 }
 
+/// Resumable state of a generation in progress, see [`WfcGenerator::step_budgeted`]
+#[derive(Clone, PartialEq, Eq)]
+pub struct WfcState<A = Square> {
+    board: Sentinel<A>,
+    weights: EnumMap<Tile<A>, usize>,
+    passes: usize,
+    finished: bool,
+}
+
+impl<A: Topology> WfcState<A> {
+    /// superpositions of the level generated so far
+    pub fn board(&self) -> &Sentinel<A> {
+        &self.board
+    }
+
+    /// level generated so far, uncollapsed tiles are empty
+    pub fn grid(&self) -> Grid<Tile<A>> {
+        self.board
+            .extract_grid()
+            .map(|set| set.unwrap_if_singleton().unwrap_or(Tile::NO_CONNECTIONS))
+    }
+
+    /// number of iterations performed
+    pub fn passes(&self) -> usize {
+        self.passes
+    }
+
+    /// all superpositions are collapsed or the pass limit of the generator is reached
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+}
+
 /// A generator with fixed settings, which can be reused for multiple level generations.
 #[derive(Clone, PartialEq, Eq)]
-pub struct WfcGenerator {
+pub struct WfcGenerator<A = Square> {
     width: usize,
     height: usize,
-    available_tiles: EnumSet<Tile<Square>>,
+    available_tiles: EnumSet<Tile<A>>,
     prop_limit: usize,
     pass_limit: usize,
+    /// cells outside the shape become holes, see [`WfcGenerator::with_mask`]
+    mask: Option<Grid<bool>>,
+    /// connections wrap around the edges, see [`WfcGenerator::with_torus`]
+    torus: bool,
 }
 
-impl WfcGenerator {
+impl<A: Topology> WfcGenerator<A>
+where
+    Tile<A>: Display,
+{
     pub fn new(
         width: usize,
         height: usize,
-        available_tiles: EnumSet<Tile<Square>>,
+        available_tiles: EnumSet<Tile<A>>,
         pass_limit: usize,
         prop_limit: usize,
-    ) -> WfcGenerator {
+    ) -> WfcGenerator<A> {
         WfcGenerator {
             width,
             height,
             available_tiles,
             prop_limit,
             pass_limit,
+            mask: None,
+            torus: false,
         }
     }
 
-    pub fn with_all_tiles(width: usize, height: usize) -> WfcGenerator {
+    pub fn with_all_tiles(width: usize, height: usize) -> WfcGenerator<A> {
         WfcGenerator {
             width,
             height,
             available_tiles: EnumSet::FULL,
             prop_limit: 40000,
             pass_limit: 1000,
+            mask: None,
+            torus: false,
         }
     }
 
-    fn update_weights(board: &Sentinel<Square>, weights: &mut EnumMap<Tile<Square>, usize>) {
+    /// Generator of levels in the shape of the mask, taking its dimensions
+    ///
+    /// cells that are `false` in the mask are fixed to tiles without connections, the generated levels become
+    /// shaped levels with [`Grid::cut`]
+    pub fn with_mask(self, mask: Grid<bool>) -> WfcGenerator<A> {
+        let (height, width) = mask.dimensions().to_tuple();
+        WfcGenerator {
+            width,
+            height,
+            mask: Some(mask),
+            ..self
+        }
+    }
+
+    /// Generator of levels whose connections wrap around the edges, see [`Grid::into_torus`]
+    pub fn with_torus(self) -> WfcGenerator<A> {
+        WfcGenerator {
+            torus: true,
+            ..self
+        }
+    }
+
+    fn update_weights(board: &Sentinel<A>, weights: &mut EnumMap<Tile<A>, usize>) {
         // initialize all weights to 0
-        weights.clear();
+        *weights = EnumMap::from_fn(|_| 0);
 
         // update weights: only calculate weight for uncollapsed cells
         for cell in board.0.as_slice() {
             if !cell.is_collapsed() {
                 cell.into_iter().for_each(|tile| {
-                    weights[tile] = Some(weights[tile].unwrap_or(0) + 1);
+                    weights[tile] += 1;
                 });
             }
         }
     }
 
-    fn shannon_entropy(
-        cell: &Superposition<Square>,
-        weights: &EnumMap<Tile<Square>, usize>,
-    ) -> f64 {
+    fn shannon_entropy(cell: &Superposition<A>, weights: &EnumMap<Tile<A>, usize>) -> f64 {
         let (mut weight, mut total_weight, mut total_log_weight): (f64, f64, f64);
         total_weight = 0.0;
         total_log_weight = 0.0;
 
         for tile in cell.iter() {
-            weight = weights[tile].unwrap() as f64;
+            weight = weights[tile] as f64;
             total_weight += weight;
             total_log_weight += weight * weight.ln();
         }
@@ -129,9 +189,10 @@ impl WfcGenerator {
         total_weight.ln() - (total_log_weight / total_weight)
     }
 
-    fn find_entropy_cell(
-        board: &Sentinel<Square>,
-        weights: &EnumMap<Tile<Square>, usize>,
+    fn find_entropy_cell<R: Rng + ?Sized>(
+        board: &Sentinel<A>,
+        weights: &EnumMap<Tile<A>, usize>,
+        rng: &mut R,
     ) -> Coordinate<isize> {
         let mut min = std::f64::MAX;
         let mut min_coordinate: Coordinate<isize> = Coordinate { row: 0, column: 0 };
@@ -139,8 +200,6 @@ impl WfcGenerator {
         let mut entropy: f64;
         let mut entropy_rng: f64;
 
-        let mut rng = rand::thread_rng();
-
         for (cell_coordinate, cell) in board
             .0
             .with_index()
@@ -161,25 +220,22 @@ impl WfcGenerator {
     }
 
     /// Collapse the cell with given coordinates
-    fn collapse_cell(
-        board: &mut Sentinel<Square>,
-        weights: &EnumMap<Tile<Square>, usize>,
+    fn collapse_cell<R: Rng + ?Sized>(
+        board: &mut Sentinel<A>,
+        weights: &EnumMap<Tile<A>, usize>,
         cell_coordinate: Coordinate<isize>,
+        rng: &mut R,
     ) {
-        board.0.get_mut(cell_coordinate).unwrap().collapse(weights)
+        board
+            .0
+            .get_mut(cell_coordinate)
+            .unwrap()
+            .collapse(weights, rng)
     }
 
     /// Propagate through all neigbouring cells that are affected by the last collapse
-    fn propagate(
-        board: &mut Sentinel<Square>,
-        cell_coordinate: Coordinate<isize>,
-        prop_limit: usize,
-    ) {
-        fn is_compatible(
-            source_tile: Tile<Square>,
-            dir: Square,
-            target_tile: Tile<Square>,
-        ) -> bool {
+    fn propagate(board: &mut Sentinel<A>, cell_coordinate: Coordinate<isize>, prop_limit: usize) {
+        fn is_compatible<A: Topology>(source_tile: Tile<A>, dir: A, target_tile: Tile<A>) -> bool {
             source_tile.0.contains(dir) == target_tile.0.contains(-dir)
         }
 
@@ -188,7 +244,7 @@ impl WfcGenerator {
         let mut passes = 0_usize;
         while let Some(index) = stack.pop() {
             for dir in all_enums_ascending() {
-                let neighbor_index = index.get_neighbor_index(dir);
+                let neighbor_index = board.0.neighbor_index(index, dir);
                 let neighbor_cell = &board.0.get(neighbor_index).unwrap_or(&EnumSet::EMPTY);
 
                 if neighbor_cell.is_collapsed() {
@@ -225,18 +281,18 @@ impl WfcGenerator {
     }
 
     /// Check if all cells on the board have only a single eigenstate
-    pub fn is_all_collapsed(board: &Sentinel<Square>) -> bool {
+    pub fn is_all_collapsed(board: &Sentinel<A>) -> bool {
         board.0.as_slice().iter().all(|c| c.is_collapsed())
     }
 
-    pub fn extract_grid(board: &Sentinel<Square>) -> Grid<Tile<Square>> {
+    pub fn extract_grid(board: &Sentinel<A>) -> Grid<Tile<A>> {
         board
             .extract_grid()
             .map(|set| set.unwrap_if_singleton().unwrap_or(Tile::NO_CONNECTIONS))
     }
 
     /// Print the incompleted map in the current state
-    pub fn print_map(board: &Sentinel<Square>) {
+    pub fn print_map(board: &Sentinel<A>) {
         let map = board.0.elements();
         let width = board.0.columns();
 
@@ -256,39 +312,92 @@ impl WfcGenerator {
         }
     }
 
-    pub fn init_board(&self) -> (Sentinel<Square>, EnumMap<Tile<Square>, usize>) {
+    pub fn init_board(&self) -> (Sentinel<A>, EnumMap<Tile<A>, usize>) {
         // initialize board with all possiblities, then update edge tiles
-        let board: Sentinel<Square> = Grid::init(
+        let grid = Grid::init(
             Coordinate {
                 row: self.height,
                 column: self.width,
             },
-            |_| self.available_tiles,
-        )
-        .with_sentinels(Tile::NO_CONNECTIONS.into())
-        .minimize();
-
-        // initialize superpositions
-        let board = board
-            .0
-            .coordinates()
-            .fold(board, propagate_restrictions_to_all_neighbors);
+            |index| match &self.mask {
+                Some(mask) if !mask[index] => Tile::NO_CONNECTIONS.into(),
+                _ => self.available_tiles,
+            },
+        );
+        let grid = if self.torus { grid.into_torus() } else { grid };
+        let board: Sentinel<A> = grid.with_sentinels(Tile::NO_CONNECTIONS.into()).minimize();
 
-        let mut weights: EnumMap<Tile<Square>, usize> = EnumMap::empty();
+        let mut weights: EnumMap<Tile<A>, usize> = EnumMap::empty();
         // update weights
         WfcGenerator::update_weights(&board, &mut weights);
 
         (board, weights)
     }
 
+    /// State before the first iteration of a generation performed with [`WfcGenerator::step_budgeted`]
+    pub fn start(&self) -> WfcState<A> {
+        let (board, weights) = self.init_board();
+        WfcState {
+            finished: WfcGenerator::is_all_collapsed(&board),
+            board,
+            weights,
+            passes: 0,
+        }
+    }
+
+    /// Performs as many iterations as fit into the time slice, the generation resumes from the returned state
+    ///
+    /// `now` is a clock in milliseconds, e.g. `Date.now` in the browser where [`std::time::Instant`] is unavailable.
+    /// At least one iteration is performed, so a budget of 0 advances by a single iteration
+    pub fn step_budgeted<C: FnMut() -> f64>(
+        &self,
+        state: WfcState<A>,
+        max_millis: f64,
+        now: C,
+    ) -> WfcState<A> {
+        self.step_budgeted_with(state, max_millis, now, &mut RngSource::from_entropy())
+    }
+
+    /// see [`WfcGenerator::step_budgeted`], drawing random numbers from `rng`
+    pub fn step_budgeted_with<C: FnMut() -> f64, R: Rng + ?Sized>(
+        &self,
+        mut state: WfcState<A>,
+        max_millis: f64,
+        mut now: C,
+        rng: &mut R,
+    ) -> WfcState<A> {
+        let start = now();
+        while !state.finished {
+            (state.board, state.weights) =
+                self.iteration_step_with(state.board, state.weights, rng);
+            state.passes += 1;
+            state.finished =
+                WfcGenerator::is_all_collapsed(&state.board) || state.passes >= self.pass_limit;
+            if now() - start >= max_millis {
+                break;
+            }
+        }
+        state
+    }
+
     // one step in wfc
     pub fn iteration_step(
         &self,
-        mut board: Sentinel<Square>,
-        mut weights: EnumMap<Tile<Square>, usize>,
-    ) -> (Sentinel<Square>, EnumMap<Tile<Square>, usize>) {
-        let current_coordinate = WfcGenerator::find_entropy_cell(&board, &weights);
-        WfcGenerator::collapse_cell(&mut board, &weights, current_coordinate);
+        board: Sentinel<A>,
+        weights: EnumMap<Tile<A>, usize>,
+    ) -> (Sentinel<A>, EnumMap<Tile<A>, usize>) {
+        self.iteration_step_with(board, weights, &mut RngSource::from_entropy())
+    }
+
+    /// one step in wfc, drawing random numbers from `rng`
+    pub fn iteration_step_with<R: Rng + ?Sized>(
+        &self,
+        mut board: Sentinel<A>,
+        mut weights: EnumMap<Tile<A>, usize>,
+        rng: &mut R,
+    ) -> (Sentinel<A>, EnumMap<Tile<A>, usize>) {
+        let current_coordinate = WfcGenerator::find_entropy_cell(&board, &weights, rng);
+        WfcGenerator::collapse_cell(&mut board, &weights, current_coordinate, rng);
         WfcGenerator::propagate(&mut board, current_coordinate, self.prop_limit);
         WfcGenerator::update_weights(&board, &mut weights);
 
@@ -301,12 +410,44 @@ impl WfcGenerator {
     }
 
     /// Generates a level with the predefined settings
-    pub fn generate(&self) -> Result<Grid<Tile<Square>>, String> {
+    pub fn generate(&self) -> Result<Grid<Tile<A>>, String> {
+        self.generate_with(&mut RngSource::from_entropy())
+    }
+
+    /// Generates a level with the predefined settings deterministically
+    pub fn generate_seeded(&self, seed: u64) -> Result<Grid<Tile<A>>, String> {
+        self.generate_with(&mut RngSource::seeded(seed))
+    }
+
+    /// Generates a scrambled level with exactly one solution deterministically
+    ///
+    /// failed and ambiguous generations are retried with the next seed up to [`UNIQUE_ATTEMPTS`] times
+    pub fn generate_unique(&self, seed: u64) -> Result<Grid<Tile<A>>, String> {
+        self.generate_unique_with_solution(seed)
+            .map(|(puzzle, _)| puzzle)
+    }
+
+    /// Generates a scrambled level with exactly one solution together with its solution
+    ///
+    /// see [`WfcGenerator::generate_unique`]
+    pub fn generate_unique_with_solution(
+        &self,
+        seed: u64,
+    ) -> Result<PuzzleWithSolution<A>, String> {
+        (0..UNIQUE_ATTEMPTS)
+            .filter_map(|attempt| self.generate_seeded(seed.wrapping_add(attempt)).ok())
+            .find(Grid::has_unique_solution)
+            .map(|solution| (solution.clone().scramble(seed), solution))
+            .ok_or_else(|| format!("no level with a unique solution in {UNIQUE_ATTEMPTS} attempts"))
+    }
+
+    /// Generates a level with the predefined settings, drawing random numbers from `rng`
+    pub fn generate_with<R: Rng + ?Sized>(&self, rng: &mut R) -> Result<Grid<Tile<A>>, String> {
         let (mut board, mut weights) = self.init_board();
 
         let mut passes: usize = 0;
         loop {
-            (board, weights) = self.iteration_step(board, weights);
+            (board, weights) = self.iteration_step_with(board, weights, rng);
 
             passes += 1;
 
@@ -327,8 +468,13 @@ impl WfcGenerator {
 #[cfg(test)]
 mod tests {
 
+    use crate::config::RngSource;
     use crate::generator::wfc::WfcGenerator;
+    use crate::model::coordinate::Coordinate;
+    use crate::model::gameboard::GameBoard;
+    use crate::model::grid::Grid;
     use crate::model::tile::{
+        Hex,
         Square::{self, Down, Left, Right, Up},
         Tile,
     };
@@ -354,6 +500,102 @@ mod tests {
         wfc_test(6, 6, available_tiles, 40000, 1000)
     }
 
+    #[quickcheck]
+    fn generate_seeded_is_deterministic(seed: u64) -> bool {
+        let wfc_generator = WfcGenerator::<Square>::with_all_tiles(5, 4);
+        wfc_generator.generate_seeded(seed) == wfc_generator.generate_seeded(seed)
+    }
+
+    #[quickcheck]
+    fn unique_levels_are_solved_by_their_solution_only(seed: u64) -> bool {
+        let wfc_generator = WfcGenerator::<Square>::with_all_tiles(5, 4);
+        wfc_generator
+            .generate_unique_with_solution(seed)
+            .map_or(true, |(puzzle, solution)| {
+                puzzle.solve().take(2).eq([solution])
+            })
+    }
+
+    #[quickcheck]
+    fn budgeted_steps_agree_with_the_generation(seed: u64, budget: u8) -> bool {
+        let wfc_generator = WfcGenerator::<Square>::with_all_tiles(5, 4);
+        let mut rng = RngSource::seeded(seed);
+        // every call of the clock advances it by a millisecond
+        let mut time = 0.0;
+        let mut clock = || {
+            time += 1.0;
+            time
+        };
+        let mut state = wfc_generator.start();
+        while !state.is_finished() {
+            let passes = state.passes();
+            state = wfc_generator.step_budgeted_with(state, budget as f64, &mut clock, &mut rng);
+            if !state.is_finished() && state.passes() - passes != budget.max(1) as usize {
+                return false;
+            }
+        }
+        let generated = wfc_generator.generate_seeded(seed);
+        generated.map_or(true, |level| state.grid() == level)
+    }
+
+    #[test]
+    fn wfc_generates_solvable_hex_levels() {
+        let wfc_generator = WfcGenerator::<Hex>::with_all_tiles(4, 4);
+        let level = (0..)
+            .find_map(|seed| wfc_generator.generate_seeded(seed).ok())
+            .expect("generated level");
+        assert!(level.is_solved());
+        assert!(level.solve().next().is_some());
+    }
+
+    #[test]
+    fn wfc_generates_shaped_levels() {
+        // ring around a hole in the middle
+        let mask = Grid::init(Coordinate::new(5, 5), |index| {
+            index != Coordinate::new(2, 2)
+        });
+        let wfc_generator = WfcGenerator::<Square>::with_all_tiles(3, 3).with_mask(mask.clone());
+        let level = (0..)
+            .find_map(|seed| wfc_generator.generate_seeded(seed).ok())
+            .expect("generated level")
+            .cut(&mask);
+        assert_eq!(level.mask(), mask);
+        assert!(level.is_solved());
+        assert!(level.solve().next().is_some());
+    }
+
+    #[test]
+    fn wfc_generates_torus_levels() {
+        let wfc_generator = WfcGenerator::<Square>::with_all_tiles(3, 4).with_torus();
+        let level = (0..)
+            .find_map(|seed| wfc_generator.generate_seeded(seed).ok())
+            .expect("generated level");
+        assert!(level.is_torus());
+        assert!(level.is_solved());
+    }
+
+    /// seed, width, height and generated level, pinned so changes to the generator can't silently
+    /// alter seeded levels
+    const GOLDEN: [(u64, (usize, usize), &[&str]); 3] = [
+        (0, (3, 3), &["╻┏╸", "┗┫╻", "╺┻┛"]),
+        (1, (5, 4), &[" ╻╻╻╻", " ╹╹┣┫", "┏╸ ╹╹", "╹ ╺╸ "]),
+        (
+            42,
+            (6, 6),
+            &["╺┳┓╺━╸", "╻╹┗┓╻╻", "╹╻╻┗┛┃", "╻╹╹┏━┛", "┗┓╻╹╻╻", "╺┻┻━┻┛"],
+        ),
+    ];
+
+    #[test]
+    fn seeded_levels_match_the_golden_corpus() {
+        for (seed, (width, height), level) in GOLDEN {
+            let generated = WfcGenerator::<Square>::with_all_tiles(width, height)
+                .generate_seeded(seed)
+                .map(|level| level.to_string());
+            assert_eq!(generated, Ok(level.join("\n")), "seed {seed}");
+        }
+    }
+
     fn wfc_test(
         width: usize,
         height: usize,
@@ -0,0 +1,529 @@
+//! Level packs bundle many levels together with their metadata into a single binary stream
+//!
+//! `.ilpack` files are zip archives storing every level in the packed format of [`write_packed_level`],
+//! see the `archive` module of the `archive` feature
+//!
+//! ## File format
+//!
+//! all integers are big endian, strings are prefixed with their length in bytes as `u16` and encoded in UTF-8
+//!
+//! | Field        | Encoding                      |
+//! |:-------------|:------------------------------|
+//! | magic        | `ILPK`                        |
+//! | version      | `u8`, see [`FORMAT_VERSION`]  |
+//! | name         | string                        |
+//! | author       | string                        |
+//! | level count  | `u32`                         |
+//! | levels       | level count times a level     |
+//!
+//! every level consists of its difficulty as `u8` (easy = 0, medium = 1, hard = 2), rows and columns as `u16`
+//! followed by its tiles in row-major order, two tiles per byte with the first tile in the high nibble.
+//! The nibble of a tile is its [`Finite::enum_to_index`]. The highest bit of the rows flags a level whose edges
+//! wrap around, see [`Grid::into_torus`], so levels have at most 32767 rows
+//!
+//! packs and single packed levels, see [`write_packed_level`], are encoded and decoded as streams in chunks,
+//! so huge levels are never held as a whole in their encoded form
+//!
+//! ## URL codes
+//!
+//! levels are shared in links as their packed format encoded in the URL-safe base64 alphabet without padding,
+//! see [`Grid::to_url_code`]
+
+use std::collections::HashSet;
+use std::io::{ErrorKind, Read, Write};
+
+use crate::core::finite::Finite;
+use crate::dto::PackDto;
+use crate::model::{
+    coordinate::Coordinate,
+    grid::Grid,
+    tile::{Square, Tile},
+};
+use crate::solver::difficulty::{rate, Difficulty};
+
+/// current version of the file format written by this crate
+pub const FORMAT_VERSION: u8 = 1;
+
+const MAGIC: &[u8; 4] = b"ILPK";
+
+/// tiles encoded or decoded at once, two tiles per byte
+const CHUNK_TILES: usize = 8192;
+
+/// bit of the rows flagging a torus
+const TORUS_FLAG: u16 = 0x8000;
+
+/// Level of a [`LevelPack`] together with its metadata
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PackedLevel {
+    pub grid: Grid<Tile<Square>>,
+    /// rated once when the level is added, so loading a pack does not solve its levels
+    pub difficulty: Difficulty,
+}
+
+impl PackedLevel {
+    pub fn dimensions(&self) -> Coordinate<usize> {
+        self.grid.dimensions()
+    }
+}
+
+/// Named collection of levels in a fixed order
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LevelPack {
+    pub name: String,
+    pub author: String,
+    levels: Vec<PackedLevel>,
+}
+
+impl LevelPack {
+    pub fn new(name: &str, author: &str) -> Self {
+        LevelPack {
+            name: name.to_string(),
+            author: author.to_string(),
+            levels: vec![],
+        }
+    }
+
+    /// Appends the level and rates its difficulty
+    pub fn push(&mut self, grid: Grid<Tile<Square>>) {
+        let difficulty = rate(&grid);
+        self.levels.push(PackedLevel { grid, difficulty });
+    }
+
+    /// Appends the level with the difficulty it was rated with before
+    pub fn push_rated(&mut self, level: PackedLevel) {
+        self.levels.push(level);
+    }
+
+    pub fn len(&self) -> usize {
+        self.levels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.levels.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&PackedLevel> {
+        self.levels.get(index)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, PackedLevel> {
+        self.levels.iter()
+    }
+
+    /// Removes levels equal up to symmetry to an earlier level, see [`Grid::canonical_form`]
+    ///
+    /// returns the number of removed levels
+    pub fn dedup(&mut self) -> usize {
+        let mut seen = HashSet::new();
+        let before = self.len();
+        self.levels
+            .retain(|level| seen.insert(level.grid.canonical_form()));
+        before - self.len()
+    }
+
+    /// Levels of the given difficulty in pack order
+    pub fn with_difficulty(&self, difficulty: Difficulty) -> impl Iterator<Item = &PackedLevel> {
+        self.iter()
+            .filter(move |level| level.difficulty == difficulty)
+    }
+
+    /// Converts into the checksummed wire format served to the frontend
+    pub fn to_dto(&self) -> PackDto {
+        PackDto::new(&self.name, self.iter().map(|level| level.grid.clone()))
+    }
+
+    /// Writes the pack as single binary stream
+    pub fn save<W: Write>(&self, mut writer: W) -> Result<(), String> {
+        let mut header = MAGIC.to_vec();
+        header.push(FORMAT_VERSION);
+        write_string(&mut header, &self.name)?;
+        write_string(&mut header, &self.author)?;
+        let count = u32::try_from(self.len()).map_err(|_| "too many levels".to_string())?;
+        header.extend(count.to_be_bytes());
+        write_all(&mut writer, &header)?;
+        for level in self {
+            write_all(&mut writer, &[difficulty_to_byte(level.difficulty)])?;
+            write_packed_level(&level.grid, &mut writer)?;
+        }
+        Ok(())
+    }
+
+    /// Reads a pack from a single binary stream
+    pub fn load<R: Read>(reader: R) -> Result<Self, String> {
+        let mut input = Input(reader);
+        if input.take(MAGIC.len())? != MAGIC {
+            return Err("parsing error: not a level pack".into());
+        }
+        let version = input.byte()?;
+        if version != FORMAT_VERSION {
+            return Err(format!(
+                "parsing error: unsupported format version {version}, expected {FORMAT_VERSION}"
+            ));
+        }
+        let mut pack = LevelPack::new(&input.string()?, &input.string()?);
+        for _ in 0..input.u32()? {
+            let difficulty = difficulty_from_byte(input.byte()?)?;
+            pack.push_rated(PackedLevel {
+                grid: read_packed_level(&mut input.0)?,
+                difficulty,
+            });
+        }
+        if input.is_empty()? {
+            Ok(pack)
+        } else {
+            Err("parsing error: trailing bytes after the last level".into())
+        }
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        let mut bytes = vec![];
+        self.save(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        LevelPack::load(bytes)
+    }
+}
+
+/// Writes a single level in the packed format of the levels in a pack: rows and columns followed by the tiles
+pub fn write_packed_level<W: Write>(
+    grid: &Grid<Tile<Square>>,
+    mut writer: W,
+) -> Result<(), String> {
+    let too_large = || "level too large".to_string();
+    let rows = u16::try_from(grid.rows())
+        .ok()
+        .filter(|rows| rows & TORUS_FLAG == 0)
+        .ok_or_else(too_large)?;
+    let rows = if grid.is_torus() {
+        rows | TORUS_FLAG
+    } else {
+        rows
+    };
+    let columns = u16::try_from(grid.columns()).map_err(|_| too_large())?;
+    write_all(&mut writer, &rows.to_be_bytes())?;
+    write_all(&mut writer, &columns.to_be_bytes())?;
+    for chunk in grid.as_slice().chunks(CHUNK_TILES) {
+        let bytes = chunk
+            .chunks(2)
+            .map(|pair| {
+                let nibble = |index| {
+                    pair.get(index)
+                        .map_or(0, |t: &Tile<Square>| t.enum_to_index())
+                };
+                (nibble(0) << 4 | nibble(1)) as u8
+            })
+            .collect::<Vec<_>>();
+        write_all(&mut writer, &bytes)?;
+    }
+    Ok(())
+}
+
+/// Reads a single level in the packed format, see [`write_packed_level`]
+pub fn read_packed_level<R: Read>(reader: R) -> Result<Grid<Tile<Square>>, String> {
+    let mut input = Input(reader);
+    let rows = input.u16()?;
+    let dimensions = Coordinate::new((rows & !TORUS_FLAG) as usize, input.u16()? as usize);
+    // the header is untrusted, the tiles grow with the bytes actually read
+    let mut tiles = Vec::with_capacity(dimensions.product().min(CHUNK_TILES));
+    while tiles.len() < dimensions.product() {
+        let remaining = dimensions.product() - tiles.len();
+        let count = remaining.min(CHUNK_TILES);
+        tiles.extend(
+            input
+                .take((count + 1) / 2)?
+                .iter()
+                .flat_map(|&byte| [byte >> 4, byte & 0x0F])
+                .take(count)
+                .map(|nibble| Tile::unchecked_index_to_enum(nibble as u64)),
+        );
+    }
+    let grid = Grid::new(dimensions, tiles);
+    Ok(if rows & TORUS_FLAG != 0 {
+        grid.into_torus()
+    } else {
+        grid
+    })
+}
+
+/// URL-safe base64 alphabet
+const URL_DIGITS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+impl Grid<Tile<Square>> {
+    /// Code of the level for links, its packed format in URL-safe base64, see [`write_packed_level`]
+    ///
+    /// fails for levels with more than 32767 rows or 65535 columns
+    pub fn to_url_code(&self) -> Result<String, String> {
+        let mut bytes = vec![];
+        write_packed_level(self, &mut bytes)?;
+        let digits = bytes.chunks(3).flat_map(|chunk| {
+            let bits = chunk
+                .iter()
+                .chain(std::iter::repeat(&0))
+                .take(3)
+                .fold(0_u32, |bits, &byte| bits << 8 | byte as u32);
+            (0..=chunk.len())
+                .map(move |i| URL_DIGITS[(bits >> (18 - 6 * i) & 0x3F) as usize] as char)
+        });
+        Ok(digits.collect())
+    }
+
+    /// Parses the code of a level, see [`Grid::to_url_code`]
+    pub fn from_url_code(code: &str) -> Result<Self, String> {
+        let invalid = || format!("parsing error: invalid level code '{code}'");
+        let digits = code
+            .bytes()
+            .map(|digit| {
+                URL_DIGITS
+                    .iter()
+                    .position(|&d| d == digit)
+                    .map(|d| d as u32)
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(invalid)?;
+        if digits.len() % 4 == 1 {
+            return Err(invalid());
+        }
+        let bytes = digits
+            .chunks(4)
+            .flat_map(|chunk| {
+                let bits = chunk
+                    .iter()
+                    .chain(std::iter::repeat(&0))
+                    .take(4)
+                    .fold(0, |bits, &digit| bits << 6 | digit);
+                (0..chunk.len() - 1).map(move |i| (bits >> (16 - 8 * i)) as u8)
+            })
+            .collect::<Vec<_>>();
+        // check the length before reading, so codes of huge dimensions are never allocated
+        let tiles = match bytes.as_slice() {
+            [r0, r1, c0, c1, ..] => {
+                (u16::from_be_bytes([*r0, *r1]) & !TORUS_FLAG) as usize
+                    * u16::from_be_bytes([*c0, *c1]) as usize
+            }
+            _ => return Err(invalid()),
+        };
+        if bytes.len() != 4 + (tiles + 1) / 2 {
+            return Err(invalid());
+        }
+        read_packed_level(bytes.as_slice())
+    }
+}
+
+fn write_all<W: Write>(writer: &mut W, bytes: &[u8]) -> Result<(), String> {
+    writer
+        .write_all(bytes)
+        .map_err(|e| format!("writing error: {e}"))
+}
+
+impl<'a> IntoIterator for &'a LevelPack {
+    type Item = &'a PackedLevel;
+
+    type IntoIter = std::slice::Iter<'a, PackedLevel>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for LevelPack {
+    type Item = PackedLevel;
+
+    type IntoIter = std::vec::IntoIter<PackedLevel>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.levels.into_iter()
+    }
+}
+
+impl Extend<Grid<Tile<Square>>> for LevelPack {
+    fn extend<I: IntoIterator<Item = Grid<Tile<Square>>>>(&mut self, levels: I) {
+        levels.into_iter().for_each(|grid| self.push(grid));
+    }
+}
+
+fn difficulty_to_byte(difficulty: Difficulty) -> u8 {
+    match difficulty {
+        Difficulty::Easy => 0,
+        Difficulty::Medium => 1,
+        Difficulty::Hard => 2,
+    }
+}
+
+fn difficulty_from_byte(byte: u8) -> Result<Difficulty, String> {
+    match byte {
+        0 => Ok(Difficulty::Easy),
+        1 => Ok(Difficulty::Medium),
+        2 => Ok(Difficulty::Hard),
+        _ => Err(format!("parsing error: unknown difficulty {byte}")),
+    }
+}
+
+fn write_string(bytes: &mut Vec<u8>, string: &str) -> Result<(), String> {
+    let length = u16::try_from(string.len()).map_err(|_| format!("string too long: {string}"))?;
+    bytes.extend(length.to_be_bytes());
+    bytes.extend(string.as_bytes());
+    Ok(())
+}
+
+/// remaining bytes of a pack being parsed
+struct Input<R>(R);
+
+impl<R: Read> Input<R> {
+    fn take(&mut self, count: usize) -> Result<Vec<u8>, String> {
+        let mut bytes = vec![0; count];
+        self.0.read_exact(&mut bytes).map_err(|e| match e.kind() {
+            ErrorKind::UnexpectedEof => "parsing error: unexpected end of level pack".to_string(),
+            _ => format!("reading error: {e}"),
+        })?;
+        Ok(bytes)
+    }
+
+    fn is_empty(&mut self) -> Result<bool, String> {
+        let mut byte = [0];
+        loop {
+            match self.0.read(&mut byte) {
+                Ok(read) => return Ok(read == 0),
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(format!("reading error: {e}")),
+            }
+        }
+    }
+
+    fn byte(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        Ok(u16::from_be_bytes([self.byte()?, self.byte()?]))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_be_bytes([
+            self.byte()?,
+            self.byte()?,
+            self.byte()?,
+            self.byte()?,
+        ]))
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let length = self.u16()? as usize;
+        String::from_utf8(self.take(length)?)
+            .map_err(|_| "parsing error: invalid UTF-8 in string".into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::core::interval::Max;
+    use crate::generator::{fastgen::generate, levelstream::builtin_levels};
+    use crate::model::{
+        coordinate::Coordinate,
+        grid::Grid,
+        tile::{Square, Tile},
+    };
+
+    use super::{read_packed_level, write_packed_level, LevelPack};
+
+    /// restricts the number and size of levels, so rating them is quick
+    fn pack_of(levels: Vec<Grid<Tile<Square>>>) -> LevelPack {
+        let mut pack = LevelPack::new("pack", "author");
+        pack.extend(
+            levels
+                .into_iter()
+                .take(8)
+                .map(|grid| Grid::init(grid.dimensions().map(|x| x.min(6)), |c| grid[c])),
+        );
+        pack
+    }
+
+    #[quickcheck]
+    fn save_then_load_is_identity(levels: Vec<Grid<Tile<Square>>>) -> bool {
+        let pack = pack_of(levels);
+        let mut file = vec![];
+        pack.save(&mut file).is_ok() && LevelPack::load(file.as_slice()) == Ok(pack)
+    }
+
+    #[quickcheck]
+    fn truncated_packs_are_rejected(levels: Vec<Grid<Tile<Square>>>, cut: usize) -> bool {
+        let bytes = pack_of(levels).to_bytes().expect("encodable pack");
+        let cut = cut % bytes.len();
+        LevelPack::from_bytes(&bytes[..cut]).is_err()
+    }
+
+    #[test]
+    fn builtin_levels_roundtrip_and_keep_their_order() {
+        let mut pack = LevelPack::new("builtin", "");
+        pack.extend(builtin_levels());
+        let loaded = LevelPack::from_bytes(&pack.to_bytes().unwrap()).unwrap();
+        assert!(loaded
+            .into_iter()
+            .map(|level| level.grid)
+            .eq(builtin_levels()));
+        assert_eq!(pack.to_dto().verify(), Ok(builtin_levels().collect()));
+    }
+
+    #[test]
+    fn levels_larger_than_a_chunk_are_streamed() {
+        let level = generate(Coordinate::new(151, 99), 7).scramble(7);
+        let mut bytes = vec![];
+        write_packed_level(&level, &mut bytes).unwrap();
+        assert_eq!(bytes.len(), 4 + (151 * 99 + 1) / 2);
+        assert_eq!(read_packed_level(bytes.as_slice()), Ok(level));
+        assert!(read_packed_level(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn huge_headers_without_tiles_are_rejected() {
+        assert!(read_packed_level([0xff; 4].as_slice()).is_err());
+        assert!(LevelPack::from_bytes(&[0xff; 4]).is_err());
+    }
+
+    #[quickcheck]
+    fn dedup_drops_turned_copies(dimension: Coordinate<Max<6>>, seed: u64) -> bool {
+        let level = generate(dimension.map(Max::to_usize), seed);
+        let mut pack = pack_of(vec![level.clone(), level.clone().scramble(seed)]);
+        let removed = pack.dedup();
+        removed == 1 && pack.len() == 1 && pack.get(0).map(|l| &l.grid) == Some(&level)
+    }
+
+    #[quickcheck]
+    fn url_codes_roundtrip(grid: Grid<Tile<Square>>) -> bool {
+        let code = grid.to_url_code().unwrap();
+        code.bytes()
+            .all(|c| c.is_ascii_alphanumeric() || c == b'-' || c == b'_')
+            && Grid::from_url_code(&code) == Ok(grid)
+    }
+
+    #[quickcheck]
+    fn torus_levels_keep_their_wrap(grid: Grid<Tile<Square>>) -> bool {
+        let torus = grid.into_torus();
+        let mut bytes = vec![];
+        write_packed_level(&torus, &mut bytes).is_ok()
+            && read_packed_level(bytes.as_slice()) == Ok(torus.clone())
+            && Grid::from_url_code(&torus.to_url_code().unwrap()) == Ok(torus)
+    }
+
+    #[test]
+    fn url_codes_pack_two_tiles_per_byte() {
+        let grid = "┏┓\n┗┛".parse::<Grid<Tile<Square>>>().unwrap();
+        assert_eq!(grid.to_url_code(), Ok("AAIAAmw5".to_string()));
+        let large = generate(Coordinate::new(20, 20), 3);
+        assert_eq!(large.to_url_code().unwrap().len(), (4 + 200) * 4 / 3);
+        assert!(Grid::from_url_code("AAIAAmw").is_err());
+        assert!(Grid::from_url_code("AAIAAmw5+").is_err());
+        // dimensions of a huge level without its tiles
+        assert!(Grid::from_url_code("__9__w").is_err());
+    }
+
+    #[test]
+    fn other_files_are_rejected() {
+        assert_eq!(
+            LevelPack::from_bytes(b"PK\x03\x04"),
+            Err("parsing error: not a level pack".into())
+        );
+    }
+}
@@ -7,9 +7,24 @@ extern crate quickcheck;
 #[macro_use(quickcheck)]
 extern crate quickcheck_macros;
 
+#[cfg(feature = "archive")]
+pub mod archive;
+#[cfg(feature = "batch")]
+pub mod batch;
+pub mod campaign;
+pub mod config;
 pub mod core;
+pub mod descent;
+pub mod dto;
 pub mod generator;
+pub mod levelpack;
+pub mod lint;
 pub mod model;
+pub mod prelude;
+pub mod render;
+pub mod savegame;
+pub mod scoring;
+pub mod sim;
 pub mod solver;
 
 use model::{
@@ -28,9 +43,7 @@ fn lib() {
         env::set_var("RUST_BACKTRACE", "1");
     }
 
-    let property = LevelProperty {
-        dimension: 5.into(),
-    };
+    let property = LevelProperty::new(5.into());
 
     level_stream(property)
         .zip(1..)
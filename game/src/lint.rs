@@ -0,0 +1,229 @@
+//! Warnings about levels that are valid, but dull or unfair to play
+//!
+//! unlike [`validate`](crate::model::constraint::validate), lints never reject a level,
+//! they are shown to designers before publishing
+
+use std::fmt::Display;
+
+use crate::core::finite::all_enums_ascending;
+use crate::model::{
+    coordinate::Coordinate,
+    grid::Grid,
+    tile::{Square, Tile},
+};
+
+/// empty regions of at least this many tiles are trivial
+pub const TRIVIAL_REGION_SIZE: usize = 9;
+
+/// levels with a larger percentage of crosses are excessive
+pub const MAX_CROSS_PERCENTAGE: usize = 25;
+
+/// solutions compared to find ambiguity hotspots
+const SOLUTION_SAMPLE: usize = 8;
+
+/// Axis or center the shapes of a level are symmetric to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    /// left half mirrors the right half
+    Vertical,
+    /// top half mirrors the bottom half
+    Horizontal,
+    /// unchanged by a half turn
+    Point,
+}
+
+/// Warning about a level
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Lint {
+    /// connected empty tiles, which leave nothing to solve
+    TrivialRegion(Vec<Coordinate<isize>>),
+    /// symmetric shapes force every solution to have a mirrored duplicate
+    SymmetricDuplicates(Symmetry),
+    /// too many crosses, which never need to be turned
+    ExcessiveCrosses { crosses: usize, tiles: usize },
+    /// tiles differing between solutions, the player has to guess here
+    AmbiguityHotspots(Vec<Coordinate<isize>>),
+}
+
+impl Display for Lint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Lint::TrivialRegion(cells) => write!(
+                f,
+                "trivial region of {} empty tiles starting at {}",
+                cells.len(),
+                cells[0]
+            ),
+            Lint::SymmetricDuplicates(symmetry) => {
+                let symmetry = match symmetry {
+                    Symmetry::Vertical => "mirrored left to right",
+                    Symmetry::Horizontal => "mirrored top to bottom",
+                    Symmetry::Point => "turned by half",
+                };
+                write!(
+                    f,
+                    "shapes are unchanged when {symmetry}, solutions have symmetric duplicates"
+                )
+            }
+            Lint::ExcessiveCrosses { crosses, tiles } => {
+                write!(f, "{crosses} of {tiles} tiles are crosses")
+            }
+            Lint::AmbiguityHotspots(cells) => {
+                let cells = cells
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(f, "ambiguous tiles differing between solutions: {cells}")
+            }
+        }
+    }
+}
+
+/// Finds all warnings about the level, ordered by kind
+pub fn lint(grid: &Grid<Tile<Square>>) -> Vec<Lint> {
+    let mut lints = trivial_regions(grid)
+        .into_iter()
+        .map(Lint::TrivialRegion)
+        .collect::<Vec<_>>();
+    lints.extend(symmetry(grid).map(Lint::SymmetricDuplicates));
+    let crosses = grid
+        .as_slice()
+        .iter()
+        .filter(|&&tile| tile == Tile::ALL_CONNECTIONS)
+        .count();
+    if crosses * 100 > MAX_CROSS_PERCENTAGE * grid.size() {
+        lints.push(Lint::ExcessiveCrosses {
+            crosses,
+            tiles: grid.size(),
+        });
+    }
+    let hotspots = ambiguity_hotspots(grid);
+    if !hotspots.is_empty() {
+        lints.push(Lint::AmbiguityHotspots(hotspots));
+    }
+    lints
+}
+
+/// connected regions of empty tiles with at least [`TRIVIAL_REGION_SIZE`] tiles, in row-major order
+fn trivial_regions(grid: &Grid<Tile<Square>>) -> Vec<Vec<Coordinate<isize>>> {
+    let mut visited = grid.map(|_| false);
+    let mut regions = vec![];
+    for start in grid.coordinates() {
+        if visited[start] || grid[start] != Tile::NO_CONNECTIONS {
+            continue;
+        }
+        visited[start] = true;
+        let mut region = vec![start];
+        let mut next = 0;
+        while let Some(&index) = region.get(next) {
+            next += 1;
+            for direction in all_enums_ascending::<Square>() {
+                let neighbor = grid.neighbor_index(index, direction);
+                if grid.get(neighbor) == Some(&Tile::NO_CONNECTIONS) && !visited[neighbor] {
+                    visited[neighbor] = true;
+                    region.push(neighbor);
+                }
+            }
+        }
+        if region.len() >= TRIVIAL_REGION_SIZE {
+            region.sort();
+            regions.push(region);
+        }
+    }
+    regions
+}
+
+/// first symmetry of the shapes, levels with less than two non-empty tiles are never symmetric
+fn symmetry(grid: &Grid<Tile<Square>>) -> Option<Symmetry> {
+    let filled = grid
+        .as_slice()
+        .iter()
+        .filter(|tile| !tile.0.is_empty())
+        .count();
+    if filled < 2 {
+        return None;
+    }
+    let (rows, columns) = (grid.rows() as isize, grid.columns() as isize);
+    let mirror = |symmetry, index: Coordinate<isize>| match symmetry {
+        Symmetry::Vertical => Coordinate::new(index.row, columns - 1 - index.column),
+        Symmetry::Horizontal => Coordinate::new(rows - 1 - index.row, index.column),
+        Symmetry::Point => Coordinate::new(rows - 1 - index.row, columns - 1 - index.column),
+    };
+    [Symmetry::Vertical, Symmetry::Horizontal, Symmetry::Point]
+        .into_iter()
+        .find(|&symmetry| {
+            grid.coordinates().all(|index| {
+                grid[index].superimpose() == grid[mirror(symmetry, index)].superimpose()
+            })
+        })
+}
+
+/// tiles differing between the first solutions of the level, in row-major order
+fn ambiguity_hotspots(grid: &Grid<Tile<Square>>) -> Vec<Coordinate<isize>> {
+    let solutions = grid.solve().take(SOLUTION_SAMPLE).collect::<Vec<_>>();
+    match solutions.split_first() {
+        Some((first, others)) => grid
+            .coordinates()
+            .filter(|&index| others.iter().any(|other| other[index] != first[index]))
+            .collect(),
+        None => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::core::interval::Max;
+    use crate::generator::fastgen::generate;
+    use crate::model::{
+        coordinate::Coordinate,
+        grid::Grid,
+        parser::{parse_level, unicode_to_tile},
+        tile::{Square, Tile},
+    };
+
+    use super::{lint, Lint, Symmetry};
+
+    #[quickcheck]
+    fn empty_levels_are_a_single_trivial_region(dimensions: Coordinate<Max<8>>) -> bool {
+        let dimensions = dimensions.map(|x| x.to_usize() + 3);
+        let grid = Grid::filled_with(dimensions, Tile::<Square>::NO_CONNECTIONS);
+        lint(&grid) == vec![Lint::TrivialRegion(grid.coordinates().collect())]
+    }
+
+    #[quickcheck]
+    fn hotspots_are_reported_iff_the_level_is_ambiguous(
+        dimensions: Coordinate<Max<5>>,
+        seed: u64,
+    ) -> bool {
+        let level = generate(dimensions.map(Max::to_usize), seed);
+        let ambiguous = level.solve().take(2).count() == 2;
+        lint(&level)
+            .iter()
+            .any(|lint| matches!(lint, Lint::AmbiguityHotspots(_)))
+            == ambiguous
+    }
+
+    #[test]
+    fn symmetric_shapes_are_reported() {
+        let level = parse_level("┏┓ \n┗┛ ", unicode_to_tile).unwrap();
+        assert_eq!(
+            lint(&level),
+            vec![Lint::SymmetricDuplicates(Symmetry::Horizontal)]
+        );
+    }
+
+    #[test]
+    fn crosses_are_limited() {
+        let level = parse_level("┏┳┓\n┣╋┫\n┗┻┛", unicode_to_tile).unwrap();
+        assert!(!lint(&level)
+            .iter()
+            .any(|lint| matches!(lint, Lint::ExcessiveCrosses { .. })));
+        let crosses = Grid::filled_with(Coordinate::new(2, 2), Tile::<Square>::ALL_CONNECTIONS);
+        assert!(lint(&crosses).contains(&Lint::ExcessiveCrosses {
+            crosses: 4,
+            tiles: 4
+        }));
+    }
+}
@@ -1,4 +1,6 @@
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
 pub enum AccessError {
     IndexOutOfBounds,
+    /// the tile is locked and cannot be rotated, see [`crate::model::lock`]
+    TileLocked,
 }
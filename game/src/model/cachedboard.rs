@@ -0,0 +1,242 @@
+//! Level with a cached solved state
+//!
+//! [`GameBoard::is_solved`] on a [`Grid`] inspects every tile. [`CachedBoard`] counts the unanswered connections
+//! instead and updates the count with the edges adjacent to a rotated tile, so checking after a rotation is O(1)
+
+use crate::core::finite::all_enums_ascending;
+
+use super::{
+    accesserror::AccessError,
+    coordinate::Coordinate,
+    gameboard::{AllSourcesConnected, GameBoard, WinCondition},
+    grid::Grid,
+    tile::{Tile, Topology},
+};
+
+/// Grid together with the number of its unanswered connections
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct CachedBoard<A: Topology> {
+    grid: Grid<Tile<A>>,
+    /// mismatching edges, edges between two tiles are counted from both sides
+    unanswered: usize,
+}
+
+impl<A: Topology> CachedBoard<A> {
+    pub fn new(grid: Grid<Tile<A>>) -> Self {
+        let unanswered = grid
+            .coordinates()
+            .map(|index| unanswered_around(&grid, index))
+            .sum();
+        CachedBoard { grid, unanswered }
+    }
+
+    pub fn grid(&self) -> &Grid<Tile<A>> {
+        &self.grid
+    }
+
+    pub fn into_grid(self) -> Grid<Tile<A>> {
+        self.grid
+    }
+
+    /// number of connections not answered by the neighbor, zero iff the level is solved
+    ///
+    /// a connection between two tiles is counted once from each side
+    pub fn unanswered(&self) -> usize {
+        self.unanswered
+    }
+
+    /// replaces the tile in place, only the edges of the tile are reevaluated
+    pub fn set(&mut self, index: Coordinate<isize>, tile: Tile<A>) -> Result<(), AccessError> {
+        let before = unanswered_touching(&self.grid, index);
+        *self
+            .grid
+            .get_mut(index)
+            .ok_or(AccessError::IndexOutOfBounds)? = tile;
+        self.unanswered = self.unanswered + unanswered_touching(&self.grid, index) - before;
+        Ok(())
+    }
+
+    /// in-place variant of [`GameBoard::rotate_clockwise`]
+    pub fn rotate_clockwise_mut(&mut self, index: Coordinate<isize>) -> Result<(), AccessError> {
+        let tile = self.tile_at(index)?;
+        self.set(index, tile.rotated_clockwise(1))
+    }
+
+    /// in-place variant of [`GameBoard::rotate_counterclockwise`]
+    pub fn rotate_counterclockwise_mut(
+        &mut self,
+        index: Coordinate<isize>,
+    ) -> Result<(), AccessError> {
+        let tile = self.tile_at(index)?;
+        self.set(index, tile.rotated_counterclockwise(1))
+    }
+
+    fn tile_at(&self, index: Coordinate<isize>) -> Result<Tile<A>, AccessError> {
+        self.grid
+            .get(index)
+            .copied()
+            .ok_or(AccessError::IndexOutOfBounds)
+    }
+}
+
+/// unanswered connections on the edges of the tile, positions outside the grid have no connections
+fn unanswered_around<A: Topology>(grid: &Grid<Tile<A>>, index: Coordinate<isize>) -> usize {
+    mismatching_neighbors(grid, index).count()
+}
+
+/// contribution of the edges of the tile to the count, seen from the tile and from its neighbors
+///
+/// edges of a tile to itself, e.g. on a torus of a single row, are seen from the tile in both directions already
+fn unanswered_touching<A: Topology>(grid: &Grid<Tile<A>>, index: Coordinate<isize>) -> usize {
+    mismatching_neighbors(grid, index)
+        .map(|neighbor| 1 + usize::from(neighbor != index && grid.get(neighbor).is_some()))
+        .sum()
+}
+
+/// neighbors of the tile on mismatching edges
+fn mismatching_neighbors<A: Topology>(
+    grid: &Grid<Tile<A>>,
+    index: Coordinate<isize>,
+) -> impl Iterator<Item = Coordinate<isize>> + '_ {
+    let connected = move |index: Coordinate<isize>, direction: A| {
+        grid.get(index)
+            .map_or(false, |tile| tile.0.contains(direction))
+    };
+    all_enums_ascending()
+        .filter(move |&direction: &A| {
+            grid.get(index).is_some()
+                && connected(index, direction)
+                    != connected(grid.neighbor_index(index, direction), -direction)
+        })
+        .map(move |direction| grid.neighbor_index(index, direction))
+}
+
+impl<A: Topology> From<Grid<Tile<A>>> for CachedBoard<A> {
+    fn from(grid: Grid<Tile<A>>) -> Self {
+        CachedBoard::new(grid)
+    }
+}
+
+impl<A: Topology> GameBoard for CachedBoard<A> {
+    type Index = Coordinate<isize>;
+
+    type Tile = Tile<A>;
+
+    fn rotate_clockwise(&self, index: Self::Index) -> Result<Self, AccessError> {
+        let mut board = self.clone();
+        board.rotate_clockwise_mut(index)?;
+        Ok(board)
+    }
+
+    fn rotate_counterclockwise(&self, index: Self::Index) -> Result<Self, AccessError> {
+        let mut board = self.clone();
+        board.rotate_counterclockwise_mut(index)?;
+        Ok(board)
+    }
+
+    fn is_solved(&self) -> bool {
+        self.unanswered == 0
+    }
+
+    /// connectivity is not cached, only solved boards are inspected
+    fn is_solved_single_loop(&self) -> bool {
+        self.is_solved() && self.grid.is_solved_single_loop()
+    }
+
+    fn to_cells(&self) -> Vec<(Self::Index, Self::Tile)> {
+        self.grid.to_cells()
+    }
+}
+
+impl<A: Topology> WinCondition<CachedBoard<A>> for AllSourcesConnected {
+    /// connectivity is not cached, only solved boards are inspected
+    fn is_met_by(&self, board: &CachedBoard<A>) -> bool {
+        board.is_solved() && self.is_met_by(&board.grid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::model::{
+        coordinate::Coordinate,
+        gameboard::GameBoard,
+        grid::Grid,
+        tile::{Square, Tile},
+    };
+
+    use super::CachedBoard;
+
+    #[quickcheck]
+    fn cache_agrees_with_the_grid_after_rotations(
+        grid: Grid<Tile<Square>>,
+        rotations: Vec<(usize, bool)>,
+    ) -> bool {
+        let mut board = CachedBoard::new(grid.clone());
+        let mut grid = grid;
+        let coordinates = grid.coordinates().collect::<Vec<_>>();
+        let mut agrees = board.is_solved() == grid.is_solved();
+        for (index, clockwise) in rotations {
+            let index = match coordinates.get(index % coordinates.len().max(1)) {
+                Some(&index) => index,
+                None => break,
+            };
+            if clockwise {
+                board.rotate_clockwise_mut(index).unwrap();
+                grid = grid.rotate_clockwise(index).unwrap();
+            } else {
+                board = board.rotate_counterclockwise(index).unwrap();
+                grid = grid.rotate_counterclockwise(index).unwrap();
+            }
+            agrees &= board.is_solved() == grid.is_solved() && board.grid() == &grid;
+        }
+        agrees && board.unanswered() == CachedBoard::new(grid).unanswered()
+    }
+
+    #[quickcheck]
+    fn cache_agrees_on_tori_after_rotations(
+        grid: Grid<Tile<Square>>,
+        line: Vec<Tile<Square>>,
+        rotations: Vec<usize>,
+    ) -> bool {
+        let row = Grid::new(Coordinate::new(1, line.len()), line.clone());
+        let column = Grid::new(Coordinate::new(line.len(), 1), line);
+        [grid, row, column].into_iter().all(|grid| {
+            let coordinates = grid.coordinates().collect::<Vec<_>>();
+            let mut board = CachedBoard::new(grid.into_torus());
+            rotations.iter().all(
+                |&index| match coordinates.get(index % coordinates.len().max(1)) {
+                    Some(&index) => {
+                        board.rotate_clockwise_mut(index).unwrap();
+                        board.unanswered() == CachedBoard::new(board.grid().clone()).unanswered()
+                    }
+                    None => true,
+                },
+            )
+        })
+    }
+
+    #[test]
+    fn self_neighbors_on_tori_are_counted_once() {
+        let grid = "╹ ".parse::<Grid<Tile<Square>>>().unwrap().into_torus();
+        let mut board = CachedBoard::new(grid);
+        assert_eq!(board.unanswered(), 2);
+
+        board.rotate_clockwise_mut(Coordinate::new(0, 0)).unwrap();
+        assert_eq!(
+            board.unanswered(),
+            CachedBoard::new(board.grid().clone()).unanswered()
+        );
+        assert!(!board.is_solved());
+    }
+
+    #[test]
+    fn positions_outside_are_rejected() {
+        let mut board = CachedBoard::new(Grid::filled_with(
+            Coordinate::new(2, 2),
+            Tile::<Square>::NO_CONNECTIONS,
+        ));
+        assert!(board.rotate_clockwise_mut(Coordinate::new(2, 0)).is_err());
+        assert!(board.is_solved());
+    }
+}
@@ -0,0 +1,178 @@
+//! Levels with several connection channels
+//!
+//! a tile of a channel level carries the connections of each of its channels, e.g. of two colors, which turn
+//! together with the tile. A channel level is solved if every channel on its own is solved, so the connections
+//! of each color must form valid loops independently of the others. A level with `N` channels is a
+//! `Grid<Channels<A, N>>`, the solver propagates every channel and keeps the orientations consistent with all of them
+
+use std::fmt::Debug;
+
+use crate::core::finite::Finite;
+
+use super::{
+    accesserror::AccessError,
+    coordinate::Coordinate,
+    gameboard::{AllSourcesConnected, GameBoard, WinCondition},
+    grid::Grid,
+    tile::{Square, Tile, Topology},
+};
+
+/// Tile with the connections of `N` independent channels
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Channels<A = Square, const N: usize = 2>(pub [Tile<A>; N]);
+
+impl<A: Debug + Finite, const N: usize> Debug for Channels<A, N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Channels").field(&self.0).finish()
+    }
+}
+
+impl<A: Finite, const N: usize> Channels<A, N> {
+    /// Rotates the connections of all channels clockwise, see [`Tile::rotated_clockwise`]
+    pub fn rotated_clockwise(&self, repetitions: u64) -> Self {
+        Channels(self.0.map(|tile| tile.rotated_clockwise(repetitions)))
+    }
+
+    /// Rotates the connections of all channels counterclockwise, see [`Tile::rotated_counterclockwise`]
+    pub fn rotated_counterclockwise(&self, repetitions: u64) -> Self {
+        Channels(
+            self.0
+                .map(|tile| tile.rotated_counterclockwise(repetitions)),
+        )
+    }
+}
+
+impl<A: Topology, const N: usize> Channels<A, N> {
+    /// Distinct rotations of the tile, starting with the tile itself
+    ///
+    /// a rotation may only repeat the tile if it repeats the tiles of all channels
+    pub fn orientations(self) -> Vec<Self> {
+        (0..A::CARDINALITY).fold(vec![], |mut orientations, repetitions| {
+            let rotated = self.rotated_clockwise(repetitions);
+            if !orientations.contains(&rotated) {
+                orientations.push(rotated);
+            }
+            orientations
+        })
+    }
+}
+
+impl<A: Topology, const N: usize> Grid<Channels<A, N>> {
+    /// Level of the channels stacked onto each other, wrapping around like the first channel
+    ///
+    /// fails if the channels differ in their dimensions
+    pub fn from_channels(channels: [Grid<Tile<A>>; N]) -> Result<Self, String> {
+        let dimensions = channels
+            .first()
+            .map_or(Coordinate::new(0, 0), Grid::dimensions);
+        if channels.iter().any(|grid| grid.dimensions() != dimensions) {
+            return Err("channels differ in their dimensions".to_string());
+        }
+        let level = Grid::init(dimensions, |index| {
+            let mut tiles = [Tile::NO_CONNECTIONS; N];
+            for (tile, channel) in tiles.iter_mut().zip(&channels) {
+                *tile = channel[index];
+            }
+            Channels(tiles)
+        });
+        Ok(match channels.first() {
+            Some(first) if first.is_torus() => level.into_torus(),
+            _ => level,
+        })
+    }
+
+    /// level of the connections of the channel alone
+    ///
+    /// # Panics
+    ///
+    /// if the level has no such channel
+    pub fn channel(&self, channel: usize) -> Grid<Tile<A>> {
+        self.map(|tiles| tiles.0[channel])
+    }
+}
+
+impl<A: Topology, const N: usize> GameBoard for Grid<Channels<A, N>> {
+    type Index = Coordinate<isize>;
+
+    type Tile = Channels<A, N>;
+
+    /// turns the connections of all channels
+    fn rotate_clockwise(&self, index: Self::Index) -> Result<Self, AccessError> {
+        self.adjust_at(index, |tiles| tiles.rotated_clockwise(1))
+    }
+
+    /// turns the connections of all channels
+    fn rotate_counterclockwise(&self, index: Self::Index) -> Result<Self, AccessError> {
+        self.adjust_at(index, |tiles| tiles.rotated_counterclockwise(1))
+    }
+
+    fn is_solved(&self) -> bool {
+        (0..N).all(|channel| self.channel(channel).is_solved())
+    }
+
+    /// every channel forms a single loop of its own
+    fn is_solved_single_loop(&self) -> bool {
+        (0..N).all(|channel| self.channel(channel).is_solved_single_loop())
+    }
+
+    fn to_cells(&self) -> Vec<(Self::Index, Self::Tile)> {
+        self.coordinates()
+            .map(|index| (index, self[index]))
+            .collect()
+    }
+}
+
+impl<A: Topology, const N: usize> WinCondition<Grid<Channels<A, N>>> for AllSourcesConnected {
+    fn is_met_by(&self, grid: &Grid<Channels<A, N>>) -> bool {
+        (0..N).all(|channel| self.is_met_by(&grid.channel(channel)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::model::{
+        coordinate::Coordinate,
+        gameboard::GameBoard,
+        grid::Grid,
+        tile::{Square, Tile},
+    };
+    use crate::{enumset, tile};
+
+    use super::Channels;
+
+    #[quickcheck]
+    fn channels_turn_together(red: Grid<Tile<Square>>, index: Coordinate<isize>) -> bool {
+        let level = Grid::from_channels([red.clone(), red.clone()]).unwrap();
+        let rotated = level.rotate_clockwise(index);
+        rotated.map(|level| [level.channel(0), level.channel(1)])
+            == red.rotate_clockwise(index).map(|red| [red.clone(), red])
+    }
+
+    #[test]
+    fn every_channel_must_be_solved() {
+        let red = "┏┓\n┗┛".parse::<Grid<Tile<Square>>>().unwrap();
+        let blue = "╺╸\n╺╸".parse::<Grid<Tile<Square>>>().unwrap();
+        let level = Grid::from_channels([red.clone(), blue]).unwrap();
+        assert!(level.is_solved());
+        assert!(!level.is_solved_single_loop());
+
+        let crossed = level.rotate_clockwise(Coordinate::new(0, 0)).unwrap();
+        assert!(!crossed.is_solved());
+        assert!(Grid::from_channels([red, Grid::EMPTY]).is_err());
+    }
+
+    #[test]
+    fn orientations_repeat_only_with_all_channels() {
+        let straight = tile!(Square::Left, Square::Right);
+        let corner = tile!(Square::Right, Square::Down);
+        assert_eq!(Channels([straight, straight]).orientations().len(), 2);
+        assert_eq!(Channels([straight, corner]).orientations().len(), 4);
+        assert_eq!(
+            Channels([straight, Tile::NO_CONNECTIONS])
+                .orientations()
+                .len(),
+            2
+        );
+    }
+}
@@ -1,45 +1,225 @@
-use crate::{enumset, tile};
+use std::fmt::Display;
 
-use crate::core::finite::Finite;
+use crate::core::{
+    enumset::EnumSet,
+    finite::{all_enums_ascending, Finite},
+};
 
 use super::{
+    channel::Channels,
+    coordinate::Coordinate,
+    gameboard::GameBoard,
     grid::Grid,
-    tile::{
-        Square,
-        Square::{Down, Left, Right, Up},
-        Tile,
-    },
+    tile::{Square, Tile},
 };
 
-// this function takes a square Tile and decodes it depending on its position UNICODE_TILES
-// e.g. for the char '┣' in position 7 in the array it is decoded into the cnf formula of tiles with 3 open ends
-// this formula originates in the 4 positions the tile can have where all but one of the sides have open ends
-// let's say the sides are A, B, C and D:
-// A ∧ B ∧ C ∧ ¬D ∨ ¬A ∧ B ∧ C ∧ D ∨ A ∧ ¬B ∧ C ∧ D ∨ A ∧ B ∧ ¬C ∧ D
-// becomes
-// (B ∨ A) ∧ (C ∨ A) ∧ (D ∨ A) ∧ (C ∨ B) ∧ (D ∨ B) ∧ (¬C ∨ ¬D ∨ ¬A ∨ ¬B) ∧ (D ∨ C)
+/// clauses over the variables of a single tile: 1 = up, 2 = right, 3 = down and 4 = left
+///
+/// a positive literal demands a connection in the direction, a negative literal forbids it
+type ClauseTemplate = Vec<Clause>;
+
+fn satisfies(tile: Tile<Square>, clause: &[Lit]) -> bool {
+    clause.iter().any(|&literal| {
+        let direction = Square::unchecked_index_to_enum(literal.unsigned_abs() as u64 - 1);
+        tile.0.contains(direction) == (literal > 0)
+    })
+}
+
+/// all tiles satisfying the clauses
+fn models(template: &[Clause]) -> EnumSet<Tile<Square>> {
+    all_enums_ascending::<Tile<Square>>()
+        .filter(|&tile| template.iter().all(|clause| satisfies(tile, clause)))
+        .collect()
+}
+
+/// Generates the clauses admitting exactly the rotations of the tile
+///
+/// starts from the prime implicates, i.e. the minimal clauses satisfied by every rotation,
+/// and successively drops the clauses implied by the remaining ones
+fn clause_template(tile: Tile<Square>) -> ClauseTemplate {
+    let orientations = tile.superimpose();
+    // every clause picks for each variable whether it is absent, positive or negative
+    let implied = (1..3_u32.pow(4))
+        .map(|code| {
+            (1..=4)
+                .filter_map(|variable| match code / 3_u32.pow(variable - 1) % 3 {
+                    0 => None,
+                    1 => Some(variable as i32),
+                    _ => Some(-(variable as i32)),
+                })
+                .collect::<Vec<_>>()
+        })
+        .filter(|clause| orientations.iter().all(|t| satisfies(t, clause)))
+        .collect::<Vec<_>>();
+    let prime = implied
+        .iter()
+        .filter(|clause| {
+            !implied
+                .iter()
+                .any(|other| other.len() < clause.len() && other.iter().all(|l| clause.contains(l)))
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    prime.iter().fold(prime.clone(), |template, clause| {
+        let without = template
+            .iter()
+            .filter(|&c| c != clause)
+            .cloned()
+            .collect::<Vec<_>>();
+        if models(&without) == orientations {
+            without
+        } else {
+            template
+        }
+    })
+}
+
+/// Table of the clause templates indexed by [`Finite::enum_to_index`] of the tiles
+fn clause_templates() -> Vec<ClauseTemplate> {
+    all_enums_ascending::<Tile<Square>>()
+        .map(clause_template)
+        .collect()
+}
+
+/// signed variable in DIMACS convention, a negative literal is the negated variable
+pub type Lit = i32;
+
+/// disjunction of literals
+pub type Clause = Vec<Lit>;
+
+/// Formula in conjunctive normal form over the variables `1..=num_vars`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Cnf {
+    pub num_vars: usize,
+    pub clauses: Vec<Clause>,
+}
+
+impl Cnf {
+    pub fn new(num_vars: usize) -> Self {
+        Cnf {
+            num_vars,
+            clauses: vec![],
+        }
+    }
+
+    /// adds the clause, extending the variables if the clause mentions a larger one
+    pub fn add_clause(&mut self, clause: impl IntoIterator<Item = Lit>) {
+        let clause = clause.into_iter().collect::<Clause>();
+        let largest = clause.iter().map(|literal| literal.unsigned_abs() as usize);
+        self.num_vars = largest.fold(self.num_vars, usize::max);
+        self.clauses.push(clause);
+    }
+
+    /// Formats the formula in DIMACS format with header
+    pub fn to_dimacs(&self) -> String {
+        format!("p cnf {} {}\n", self.num_vars, self.clauses.len())
+            + &clauses_to_dimacs(&self.clauses)
+    }
+
+    /// Parses a formula in DIMACS format, comments are skipped
+    ///
+    /// fails on a missing header and on literals exceeding the variables of the header
+    pub fn from_dimacs(formula: &str) -> Result<Self, String> {
+        let mut cnf = None;
+        for line in formula.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('c') {
+                continue;
+            }
+            if let Some(header) = line.strip_prefix("p cnf") {
+                let num_vars = header
+                    .split_whitespace()
+                    .next()
+                    .and_then(|count| count.parse().ok())
+                    .ok_or_else(|| format!("parsing error: invalid header '{line}'"))?;
+                cnf = Some(Cnf::new(num_vars));
+                continue;
+            }
+            let cnf = cnf
+                .as_mut()
+                .ok_or_else(|| "parsing error: clause before the header".to_string())?;
+            let clause = line
+                .split_whitespace()
+                .map(|literal| {
+                    literal
+                        .parse::<Lit>()
+                        .map_err(|_| format!("parsing error: invalid literal '{literal}'"))
+                })
+                .take_while(|literal| *literal != Ok(0))
+                .collect::<Result<Clause, _>>()?;
+            if let Some(literal) = clause
+                .iter()
+                .find(|literal| literal.unsigned_abs() as usize > cnf.num_vars)
+            {
+                return Err(format!(
+                    "parsing error: literal {literal} exceeds the {} variables of the header",
+                    cnf.num_vars
+                ));
+            }
+            cnf.clauses.push(clause);
+        }
+        cnf.ok_or_else(|| "parsing error: missing header".to_string())
+    }
+}
+
+/// Formats the clauses in DIMACS format without header, one clause per line
+pub fn clauses_to_dimacs(clauses: &[Clause]) -> String {
+    clauses
+        .iter()
+        .map(|clause| {
+            clause
+                .iter()
+                .map(|literal| format!("{literal} "))
+                .collect::<String>()
+                + "0\n"
+        })
+        .collect()
+}
+
 // since there are many tiles the sides are given numbers, beginning in the top left with 1-4, then moving to the right 5-8 and so forth
 // to achieve this the number of each tile is given to the function and multiplied by 4 before adding 1 to 4 to it. The most up left tile is numbered 0
-fn tile_to_literals(tile: Tile<Square>, num: i32) -> String {
-    match tile.enum_to_index(){
-        0 => format!("-{} 0\n-{} 0\n-{} 0\n-{} 0\n",num*4+1,num*4+2,num*4+3,num*4+4),
-        1 => format!("-{} -{} 0\n-{} -{} 0\n-{} -{} 0\n{} {} {} {} 0\n-{} -{} 0\n-{} -{} 0\n-{} -{} 0\n",num*4+2,num*4+1,num*4+1,num*4+3,num*4+1,num*4+4,num*4+1,num*4+2,num*4+3,num*4+4,num*4+2,num*4+3,num*4+4,num*4+3,num*4+2,num*4+4),
-        2 => format!("-{} -{} 0\n-{} -{} 0\n-{} -{} 0\n{} {} {} {} 0\n-{} -{} 0\n-{} -{} 0\n-{} -{} 0\n",num*4+2,num*4+1,num*4+1,num*4+3,num*4+1,num*4+4,num*4+1,num*4+2,num*4+3,num*4+4,num*4+2,num*4+3,num*4+4,num*4+3,num*4+2,num*4+4),
-        3 => format!("{} {} 0\n-{} -{} 0\n{} {} 0\n-{} -{} 0\n",num*4+4,num*4+2,num*4+2,num*4+4,num*4+3,num*4+1,num*4+1,num*4+3),
-        4 => format!("-{} -{} 0\n-{} -{} 0\n-{} -{} 0\n{} {} {} {} 0\n-{} -{} 0\n-{} -{} 0\n-{} -{} 0\n",num*4+2,num*4+1,num*4+1,num*4+3,num*4+1,num*4+4,num*4+1,num*4+2,num*4+3,num*4+4,num*4+2,num*4+3,num*4+4,num*4+3,num*4+2,num*4+4),
-        5 => format!("-{} {} 0\n-{} {} 0\n-{} {} 0\n-{} {} 0\n{} {} 0\n{} {} 0\n-{} -{} 0\n-{} -{} 0\n{} {} 0\n{} {} 0\n-{} -{} 0\n-{} -{} 0\n",num*4+3,num*4+1,num*4+1,num*4+3,num*4+4,num*4+2,num*4+2,num*4+4,num*4+2,num*4+1,num*4+4,num*4+1,num*4+1,num*4+2,num*4+3,num*4+2,num*4+2,num*4+3,num*4+4,num*4+3,num*4+1,num*4+4,num*4+3,num*4+4),
-        6 => format!("{} {} 0\n-{} -{} 0\n{} {} 0\n-{} -{} 0\n",num*4+4,num*4+2,num*4+2,num*4+4,num*4+3,num*4+1,num*4+1,num*4+3),
-        7 => format!("{} {} 0\n{} {} 0\n{} {} 0\n{} {} 0\n{} {} 0\n-{} -{} -{} -{} 0\n{} {} 0\n",num*4+2,num*4+1,num*4+3,num*4+1,num*4+4,num*4+1,num*4+3,num*4+2,num*4+4,num*4+2,num*4+3,num*4+4,num*4+1,num*4+2,num*4+4,num*4+3),
-        8 => format!("-{} -{} 0\n-{} -{} 0\n-{} -{} 0\n{} {} {} {} 0\n-{} -{} 0\n-{} -{} 0\n-{} -{} 0\n",num*4+2,num*4+1,num*4+1,num*4+3,num*4+1,num*4+4,num*4+1,num*4+2,num*4+3,num*4+4,num*4+2,num*4+3,num*4+4,num*4+3,num*4+2,num*4+4),
-        9 => format!("{} {} 0\n-{} -{} 0\n{} {} 0\n-{} -{} 0\n",num*4+4,num*4+2,num*4+2,num*4+4,num*4+3,num*4+1,num*4+1,num*4+3),
-        10=> format!("-{} {} 0\n-{} {} 0\n-{} {} 0\n-{} {} 0\n{} {} 0\n{} {} 0\n-{} -{} 0\n-{} -{} 0\n{} {} 0\n{} {} 0\n-{} -{} 0\n-{} -{} 0\n",num*4+3,num*4+1,num*4+1,num*4+3,num*4+4,num*4+2,num*4+2,num*4+4,num*4+2,num*4+1,num*4+4,num*4+1,num*4+1,num*4+2,num*4+3,num*4+2,num*4+2,num*4+3,num*4+4,num*4+3,num*4+1,num*4+4,num*4+3,num*4+4),
-        11 => format!("{} {} 0\n{} {} 0\n{} {} 0\n{} {} 0\n{} {} 0\n-{} -{} -{} -{} 0\n{} {} 0\n",num*4+2,num*4+1,num*4+3,num*4+1,num*4+4,num*4+1,num*4+3,num*4+2,num*4+4,num*4+2,num*4+3,num*4+4,num*4+1,num*4+2,num*4+4,num*4+3),
-        12 => format!("{} {} 0\n-{} -{} 0\n{} {} 0\n-{} -{} 0\n",num*4+4,num*4+2,num*4+2,num*4+4,num*4+3,num*4+1,num*4+1,num*4+3),
-        13 => format!("{} {} 0\n{} {} 0\n{} {} 0\n{} {} 0\n{} {} 0\n-{} -{} -{} -{} 0\n{} {} 0\n",num*4+2,num*4+1,num*4+3,num*4+1,num*4+4,num*4+1,num*4+3,num*4+2,num*4+4,num*4+2,num*4+3,num*4+4,num*4+1,num*4+2,num*4+4,num*4+3),
-        14 => format!("{} {} 0\n{} {} 0\n{} {} 0\n{} {} 0\n{} {} 0\n-{} -{} -{} -{} 0\n{} {} 0\n",num*4+2,num*4+1,num*4+3,num*4+1,num*4+4,num*4+1,num*4+3,num*4+2,num*4+4,num*4+2,num*4+3,num*4+4,num*4+1,num*4+2,num*4+4,num*4+3),
-        15 => format!("{} 0\n{} 0\n{} 0\n{} 0\n",num*4+1,num*4+2,num*4+3,num*4+4),
-
-        _ => "".to_string()
+fn tile_to_literals(template: &[Clause], num: i32) -> Vec<Clause> {
+    template
+        .iter()
+        .map(|clause| {
+            clause
+                .iter()
+                .map(|literal| literal.signum() * (num * 4 + literal.abs()))
+                .collect()
+        })
+        .collect()
+}
+
+/// origin of clauses in the encoding of a level, positions are grid coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClauseGroup {
+    /// the tile is in one of its rotations
+    Tile(Coordinate<isize>),
+    /// the tile has no connection pointing outside the level
+    Border(Coordinate<isize>),
+    /// the shared side of neighboring tiles either connects both or neither
+    Adjacency(Coordinate<isize>, Coordinate<isize>),
+}
+
+impl ClauseGroup {
+    /// Returns the positions of the tiles constrained by the clauses
+    pub fn cells(&self) -> Vec<Coordinate<isize>> {
+        match *self {
+            ClauseGroup::Tile(c) | ClauseGroup::Border(c) => vec![c],
+            ClauseGroup::Adjacency(c1, c2) => vec![c1, c2],
+        }
+    }
+}
+
+impl Display for ClauseGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let position = |c: Coordinate<isize>| format!("({}, {})", c.row, c.column);
+        match *self {
+            ClauseGroup::Tile(c) => write!(f, "tile {}", position(c)),
+            ClauseGroup::Border(c) => write!(f, "border {}", position(c)),
+            ClauseGroup::Adjacency(c1, c2) => {
+                write!(f, "adjacency {} - {}", position(c1), position(c2))
+            }
+        }
     }
 }
 
@@ -47,172 +227,474 @@ fn tile_to_literals(tile: Tile<Square>, num: i32) -> String {
 // this also sets all literals that are at the edge of the puzzle to false
 // this also assures that adjacent sides of tiles have the same value, so they either connect or they don't but not that one tile has a connection and one doesn't
 
-pub fn level_to_cnf(level: &Grid<Tile<Square>>) -> Result<String, String> {
-    let mut num = 0;
-    let mut cnf = String::from("");
+/// Encodes the level as clauses grouped by their origin, in the order of [`level_to_formula`]
+pub fn level_to_clause_groups(level: &Grid<Tile<Square>>) -> Vec<(ClauseGroup, Vec<Clause>)> {
+    let templates = clause_templates();
+    let mut groups = vec![];
     //run tile_to_literal for all tiles
-    for l in level.clone().into_iter() {
-        let char_cnf = tile_to_literals(l, num);
-        num += 1;
-
-        cnf.push_str(&char_cnf);
+    for (num, (position, tile)) in level.with_index().into_iter().enumerate() {
+        groups.push((
+            ClauseGroup::Tile(position),
+            tile_to_literals(&templates[tile.enum_to_index() as usize], num as i32),
+        ));
     }
 
     let columns = level.columns();
     let rows = level.rows();
+    let position = |x: usize, y: usize| Coordinate::new(y as isize, x as isize);
+    // variable of the side of the tile: 1 = up, 2 = right, 3 = down and 4 = left
+    let side = |x: usize, y: usize, side: usize| ((x + columns * y) * 4 + side) as Lit;
 
     for x in 0..columns {
         for y in 0..rows {
             //set literals at the edge of the puzzle false
             if y == 0 {
-                cnf.push_str(&format!("-{} 0\n", { (x + columns * y) * 4 + 1 }))
+                groups.push((
+                    ClauseGroup::Border(position(x, y)),
+                    vec![vec![-side(x, y, 1)]],
+                ))
             }
 
             if y == rows - 1 {
-                cnf.push_str(&format!("-{} 0\n", { (x + columns * y) * 4 + 3 }))
+                groups.push((
+                    ClauseGroup::Border(position(x, y)),
+                    vec![vec![-side(x, y, 3)]],
+                ))
             }
 
             if x == 0 {
-                cnf.push_str(&format!("-{} 0\n", { (x + columns * y) * 4 + 4 }))
+                groups.push((
+                    ClauseGroup::Border(position(x, y)),
+                    vec![vec![-side(x, y, 4)]],
+                ))
             }
 
             if x == columns - 1 {
-                cnf.push_str(&format!("-{} 0\n", (x + columns * y) * 4 + 2))
+                groups.push((
+                    ClauseGroup::Border(position(x, y)),
+                    vec![vec![-side(x, y, 2)]],
+                ))
             }
 
             //assure that adjacent tiles have the same value
             if x < columns - 1 {
-                cnf.push_str(&format!(
-                    "{} -{} 0\n-{} {} 0\n",
-                    { (x + columns * y) * 4 + 2 },
-                    { ((x + 1) + columns * y) * 4 + 4 },
-                    { (x + columns * y) * 4 + 2 },
-                    { ((x + 1) + columns * y) * 4 + 4 }
+                let (own, other) = (side(x, y, 2), side(x + 1, y, 4));
+                groups.push((
+                    ClauseGroup::Adjacency(position(x, y), position(x + 1, y)),
+                    vec![vec![own, -other], vec![-own, other]],
                 ))
             }
 
             if y < rows - 1 {
-                cnf.push_str(&format!(
-                    "{} -{} 0\n-{} {} 0\n",
-                    (x + columns * y) * 4 + 3,
-                    (x + columns * (y + 1)) * 4 + 1,
-                    (x + columns * y) * 4 + 3,
-                    (x + columns * (y + 1)) * 4 + 1
+                let (own, other) = (side(x, y, 3), side(x, y + 1, 1));
+                groups.push((
+                    ClauseGroup::Adjacency(position(x, y), position(x, y + 1)),
+                    vec![vec![own, -other], vec![-own, other]],
                 ))
             }
         }
     }
+    groups
+}
 
-    //add header for the cnf file
-    let header = format!(
-        "p cnf {} {} \n",
-        rows * columns * 4,
-        cnf.matches(" 0\n").count()
-    );
-    let combine = header + &cnf;
+/// Encodes the level as formula, the variables are the sides of the tiles, see [`tile_to_literals`]
+pub fn level_to_formula(level: &Grid<Tile<Square>>) -> Cnf {
+    let mut cnf = Cnf::new(level.rows() * level.columns() * 4);
+    level_to_clause_groups(level)
+        .into_iter()
+        .flat_map(|(_, clauses)| clauses)
+        .for_each(|clause| cnf.add_clause(clause));
+    cnf
+}
+
+/// Encodes the level in DIMACS format, see [`level_to_formula`]
+pub fn level_to_cnf(level: &Grid<Tile<Square>>) -> Result<String, String> {
+    level_to_cnf_excluding(level, &[])
+}
+
+/// Clause forbidding the solution, any further model differs from it in some connection
+///
+/// negates the literals of all sides of all tiles, tiles are numbered like in [`level_to_formula`]
+pub fn blocking_clause(solution: &Grid<Tile<Square>>) -> Clause {
+    solution
+        .as_slice()
+        .iter()
+        .enumerate()
+        .flat_map(|(num, tile)| {
+            all_enums_ascending::<Square>().map(move |direction| {
+                let variable = num as Lit * 4 + direction.enum_to_index() as Lit + 1;
+                if tile.0.contains(direction) {
+                    -variable
+                } else {
+                    variable
+                }
+            })
+        })
+        .collect()
+}
 
+/// Encodes the level like [`level_to_formula`] together with a [`blocking_clause`] for each of the solutions
+///
+/// adding every found solution and solving again enumerates all solutions like [`Grid::solve`]
+pub fn level_to_formula_excluding(
+    level: &Grid<Tile<Square>>,
+    solutions: &[Grid<Tile<Square>>],
+) -> Result<Cnf, String> {
+    if let Some(solution) = solutions
+        .iter()
+        .find(|solution| solution.dimensions() != level.dimensions())
+    {
+        return Err(format!(
+            "solution has dimensions {:?}, expected {:?}",
+            solution.dimensions().to_tuple(),
+            level.dimensions().to_tuple()
+        ));
+    }
+    let mut cnf = level_to_formula(level);
+    solutions
+        .iter()
+        .for_each(|solution| cnf.add_clause(blocking_clause(solution)));
+    Ok(cnf)
+}
+
+/// Encodes the level in DIMACS format, see [`level_to_formula_excluding`]
+pub fn level_to_cnf_excluding(
+    level: &Grid<Tile<Square>>,
+    solutions: &[Grid<Tile<Square>>],
+) -> Result<String, String> {
+    let combine = level_to_formula_excluding(level, solutions)?.to_dimacs();
     log::info!("{}", combine);
     Ok(combine)
 }
 
-// this function takes a string of signed literals and creates the corresponding tiles
-// first it puts all literals into an array and sorts them by their absolute values
-// then it runs through that array by increments of 4 and depending on which literals are set to true it creates tiles with connections on these sides
-pub fn solved_to_tiles(solved: &str) -> Result<Vec<Tile<Square>>, String> {
-    let mut literals = vec![];
-    let mut literal = String::from("");
-    for c in solved.chars() {
-        if c == ' ' {
-            let lit = literal.parse::<i32>();
-            match lit {
-                Ok(i) => literals.push(i),
-                Err(_e) => return Ok(vec![]),
+/// Encodes the level of several channels as formula
+///
+/// the sides of the tiles of each channel are numbered like in [`level_to_formula`], one channel after another.
+/// In place of the clauses of the tiles, each tile gets a variable per orientation following the sides of all
+/// channels: the tile takes one of its orientations, which fixes the sides of every channel
+pub fn channels_to_formula<const N: usize>(level: &Grid<Channels<Square, N>>) -> Cnf {
+    let sides = level.size() * 4;
+    let mut cnf = Cnf::new(sides * (N + 1));
+    for channel in 0..N {
+        let offset = (channel * sides) as Lit;
+        level_to_clause_groups(&level.channel(channel))
+            .into_iter()
+            .filter(|(group, _)| !matches!(group, ClauseGroup::Tile(_)))
+            .flat_map(|(_, clauses)| clauses)
+            .for_each(|clause| {
+                cnf.add_clause(clause.into_iter().map(|l| l.signum() * (l.abs() + offset)))
+            });
+    }
+    for (num, tiles) in level.as_slice().iter().enumerate() {
+        // square tiles have at most four orientations
+        let orientation = |index: usize| (sides * N + num * 4 + index + 1) as Lit;
+        let orientations = tiles.orientations();
+        cnf.add_clause((0..orientations.len()).map(orientation));
+        for (index, oriented) in orientations.into_iter().enumerate() {
+            for (channel, tile) in oriented.0.into_iter().enumerate() {
+                for direction in all_enums_ascending::<Square>() {
+                    let side =
+                        (channel * sides + num * 4) as Lit + direction.enum_to_index() as Lit + 1;
+                    let literal = if tile.0.contains(direction) {
+                        side
+                    } else {
+                        -side
+                    };
+                    cnf.add_clause([-orientation(index), literal]);
+                }
             }
-            literal = String::from("");
-            continue;
-        } else {
-            literal.push(c)
         }
     }
-    if !literal.is_empty() {
-        let last = literal.parse::<i32>();
-        match last {
-            Ok(i) => literals.push(i),
-            Err(_e) => return Ok(vec![]),
+    cnf
+}
+
+/// Reasons a model in DIMACS format is no solution of a level
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CnfError {
+    /// the token is no signed integer
+    BadLiteral(String),
+    /// the model assigns a different number of variables than the level has sides of tiles
+    VariableCount { expected: usize, found: usize },
+    /// the variable is assigned twice or not at all
+    MissingVariable(usize),
+    /// the decoded tile is no rotation of the tile of the level at the position
+    ShapeMismatch(Coordinate<isize>),
+    /// the decoded tiles are rotations of the level, but their connections do not match
+    Unsolved,
+}
+
+impl Display for CnfError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CnfError::BadLiteral(token) => write!(f, "'{token}' is no literal"),
+            CnfError::VariableCount { expected, found } => {
+                write!(f, "model assigns {found} variables, expected {expected}")
+            }
+            CnfError::MissingVariable(variable) => {
+                write!(f, "variable {variable} is not assigned exactly once")
+            }
+            CnfError::ShapeMismatch(c) => write!(
+                f,
+                "tile in row {} and column {} differs from the level",
+                c.row + 1,
+                c.column + 1
+            ),
+            CnfError::Unsolved => write!(f, "model is no solution of the level"),
         }
     }
+}
 
-    literals.sort_by_key(|a| a.abs());
+/// Parses the signed literals of a model, separated by whitespace
+///
+/// the terminating `0` of DIMACS models may be given
+pub fn parse_literals(model: &str) -> Result<Vec<Lit>, CnfError> {
+    model
+        .split_whitespace()
+        .map(|token| {
+            token
+                .parse::<Lit>()
+                .map_err(|_| CnfError::BadLiteral(token.to_string()))
+        })
+        .filter(|literal| *literal != Ok(0))
+        .collect()
+}
 
-    log::info!("literals: {}", literals.len());
+/// Decodes the literals into tiles, four consecutive variables per tile like in [`level_to_formula`]
+///
+/// the literals may be given in any order, but each variable must be assigned exactly once
+pub fn model_to_tiles(model: &[Lit]) -> Result<Vec<Tile<Square>>, CnfError> {
+    let mut literals = model.to_vec();
+    literals.sort_by_key(|literal| literal.unsigned_abs());
+    if let Some(position) = literals
+        .iter()
+        .zip(1..)
+        .position(|(literal, variable)| literal.unsigned_abs() != variable)
+    {
+        return Err(CnfError::MissingVariable(position + 1));
+    }
+    if literals.len() % 4 != 0 {
+        return Err(CnfError::VariableCount {
+            expected: (literals.len() / 4 + 1) * 4,
+            found: literals.len(),
+        });
+    }
+    Ok(literals
+        .chunks(4)
+        .map(|sides| {
+            Tile(
+                all_enums_ascending::<Square>()
+                    .zip(sides)
+                    .filter(|(_, &literal)| literal > 0)
+                    .map(|(direction, _)| direction)
+                    .collect(),
+            )
+        })
+        .collect())
+}
 
-    //run through the literals in increments of 4, creating the tiles depending on those
+// takes a string of signed literals and creates the corresponding tiles, see [`model_to_tiles`]
+pub fn solved_to_tiles(solved: &str) -> Result<Vec<Tile<Square>>, CnfError> {
+    model_to_tiles(&parse_literals(solved)?)
+}
 
-    let mut tiles = vec![];
+/// Decodes the model into the solved level
+///
+/// the solution is checked, so a misbehaving solver or a mistyped model does not produce an invalid level
+pub fn model_to_level(
+    level: &Grid<Tile<Square>>,
+    model: &[Lit],
+) -> Result<Grid<Tile<Square>>, CnfError> {
+    if model.len() != level.size() * 4 {
+        return Err(CnfError::VariableCount {
+            expected: level.size() * 4,
+            found: model.len(),
+        });
+    }
+    let solution = Grid::new(level.dimensions(), model_to_tiles(model)?);
+    if let Some(position) = level
+        .coordinates()
+        .find(|&c| !level[c].superimpose().contains(solution[c]))
+    {
+        return Err(CnfError::ShapeMismatch(position));
+    }
+    if solution.is_solved() {
+        Ok(solution)
+    } else {
+        Err(CnfError::Unsolved)
+    }
+}
 
-    for i in 0..literals.len() / 4 {
-        if literals[i * 4] > 0 {
-            if literals[i * 4 + 1] > 0 {
-                if literals[i * 4 + 2] > 0 {
-                    if literals[i * 4 + 3] > 0 {
-                        tiles.push(Tile::ALL_CONNECTIONS)
-                    } else {
-                        tiles.push(tile!(Up, Right, Down))
-                    }
-                } else {
-                    if literals[i * 4 + 3] > 0 {
-                        tiles.push(tile!(Up, Right, Left))
-                    } else {
-                        tiles.push(tile!(Up, Right))
-                    }
-                }
-            } else {
-                if literals[i * 4 + 2] > 0 {
-                    if literals[i * 4 + 3] > 0 {
-                        tiles.push(tile!(Up, Down, Left))
-                    } else {
-                        tiles.push(tile!(Up, Down))
-                    }
-                } else {
-                    if literals[i * 4 + 3] > 0 {
-                        tiles.push(tile!(Up, Left))
-                    } else {
-                        tiles.push(tile!(Up))
-                    }
-                }
-            }
-        } else {
-            if literals[i * 4 + 1] > 0 {
-                if literals[i * 4 + 2] > 0 {
-                    if literals[i * 4 + 3] > 0 {
-                        tiles.push(tile!(Right, Down, Left))
-                    } else {
-                        tiles.push(tile!(Right, Down))
-                    }
-                } else {
-                    if literals[i * 4 + 3] > 0 {
-                        tiles.push(tile!(Right, Left))
-                    } else {
-                        tiles.push(tile!(Right))
-                    }
-                }
-            } else {
-                if literals[i * 4 + 2] > 0 {
-                    if literals[i * 4 + 3] > 0 {
-                        tiles.push(tile!(Down, Left))
-                    } else {
-                        tiles.push(tile!(Down))
-                    }
-                } else {
-                    if literals[i * 4 + 3] > 0 {
-                        tiles.push(tile!(Left))
-                    } else {
-                        tiles.push(Tile::NO_CONNECTIONS)
-                    }
-                }
+/// Decodes the model of [`channels_to_formula`] into the solved level
+///
+/// the variables of the orientations are skipped, the solution is checked like in [`model_to_level`]
+pub fn model_to_channels<const N: usize>(
+    level: &Grid<Channels<Square, N>>,
+    model: &[Lit],
+) -> Result<Grid<Channels<Square, N>>, CnfError> {
+    let sides = level.size() * 4;
+    if model.len() != sides * (N + 1) {
+        return Err(CnfError::VariableCount {
+            expected: sides * (N + 1),
+            found: model.len(),
+        });
+    }
+    let tiles = model_to_tiles(
+        &model
+            .iter()
+            .copied()
+            .filter(|literal| literal.unsigned_abs() as usize <= sides * N)
+            .collect::<Vec<_>>(),
+    )?;
+    // the tiles of each channel follow the tiles of the previous channel
+    let solution = Grid::init(level.dimensions(), |c| {
+        let num = c.row as usize * level.columns() + c.column as usize;
+        let mut channels = [Tile::NO_CONNECTIONS; N];
+        for (channel, tile) in channels.iter_mut().enumerate() {
+            *tile = tiles[channel * level.size() + num];
+        }
+        Channels(channels)
+    });
+    if let Some(position) = level
+        .coordinates()
+        .find(|&c| !level[c].orientations().contains(&solution[c]))
+    {
+        return Err(CnfError::ShapeMismatch(position));
+    }
+    if solution.is_solved() {
+        Ok(solution)
+    } else {
+        Err(CnfError::Unsolved)
+    }
+}
+
+/// Parses the model in DIMACS format and decodes it into the solved level, see [`model_to_level`]
+pub fn parse_solution(
+    level: &Grid<Tile<Square>>,
+    model: &str,
+) -> Result<Grid<Tile<Square>>, CnfError> {
+    model_to_level(level, &parse_literals(model)?)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::core::finite::{all_enums_ascending, Finite};
+    use crate::model::{
+        coordinate::Coordinate,
+        grid::Grid,
+        tile::{Square, Tile},
+    };
+
+    use super::{
+        blocking_clause, clause_template, clause_templates, level_to_cnf_excluding,
+        level_to_formula, models, parse_solution, Cnf, CnfError,
+    };
+
+    /// model in DIMACS format assigning the connections of the tiles
+    fn model_of(tiles: &str) -> Vec<i32> {
+        let grid = tiles.parse::<Grid<Tile<Square>>>().unwrap();
+        blocking_clause(&grid).into_iter().map(|l| -l).collect()
+    }
+
+    fn format_model(model: &[i32]) -> String {
+        model.iter().map(|l| format!("{l} ")).collect::<String>() + "0"
+    }
+
+    #[test]
+    fn solutions_are_validated_against_the_level() {
+        let level = "┛┗\n┓┏".parse::<Grid<Tile<Square>>>().unwrap();
+        let solution = "┏┓\n┗┛".parse::<Grid<Tile<Square>>>().unwrap();
+        let parse = |model: &[i32]| parse_solution(&level, &format_model(model));
+
+        assert_eq!(parse(&model_of("┏┓\n┗┛")), Ok(solution));
+        assert_eq!(
+            parse_solution(&level, "1 x 3"),
+            Err(CnfError::BadLiteral("x".into()))
+        );
+        assert_eq!(
+            parse(&[1, -2]),
+            Err(CnfError::VariableCount {
+                expected: 16,
+                found: 2
+            })
+        );
+        let mut duplicate = model_of("┏┓\n┗┛");
+        duplicate[1] = 1;
+        assert_eq!(parse(&duplicate), Err(CnfError::MissingVariable(2)));
+        assert_eq!(
+            parse(&model_of("╋┓\n┗┛")),
+            Err(CnfError::ShapeMismatch(Coordinate::new(0, 0)))
+        );
+        assert_eq!(parse(&model_of("┛┗\n┓┏")), Err(CnfError::Unsolved));
+    }
+
+    #[quickcheck]
+    fn clause_templates_admit_exactly_the_rotations(tile: Tile<Square>) -> bool {
+        models(&clause_template(tile)) == tile.superimpose()
+    }
+
+    #[test]
+    fn blocking_clauses_negate_the_solution() {
+        let solution = "╺╸".parse::<Grid<Tile<Square>>>().unwrap();
+        assert_eq!(blocking_clause(&solution), vec![1, -2, 3, 4, 5, 6, 7, -8]);
+
+        let formula = level_to_cnf_excluding(&solution, std::slice::from_ref(&solution)).unwrap();
+        assert!(formula.starts_with("p cnf 8 "));
+        assert!(formula.ends_with("1 -2 3 4 5 6 7 -8 0\n"));
+        let other = "╺\n╸".parse::<Grid<Tile<Square>>>().unwrap();
+        assert!(level_to_cnf_excluding(&solution, &[other]).is_err());
+    }
+
+    #[test]
+    fn formulas_round_trip_through_dimacs() {
+        let mut cnf = Cnf::new(2);
+        cnf.add_clause([1, -2]);
+        cnf.add_clause([3]);
+        assert_eq!(cnf.num_vars, 3);
+        assert_eq!(cnf.to_dimacs(), "p cnf 3 2\n1 -2 0\n3 0\n");
+        assert_eq!(Cnf::from_dimacs(&cnf.to_dimacs()), Ok(cnf));
+
+        assert!(Cnf::from_dimacs("p cnf 1 1\n2 0\n").is_err());
+        assert!(Cnf::from_dimacs("1 0\n").is_err());
+        assert_eq!(
+            Cnf::from_dimacs("c comment\np cnf 1 1\n-1 0\n").map(|cnf| cnf.clauses),
+            Ok(vec![vec![-1]])
+        );
+    }
+
+    #[test]
+    fn borders_and_adjacencies_are_encoded_per_clause() {
+        let level = "╺╸".parse::<Grid<Tile<Square>>>().unwrap();
+        let cnf = level_to_formula(&level);
+        assert_eq!(cnf.num_vars, 8);
+        // the shared side of both tiles connects both or neither
+        assert!(cnf.clauses.contains(&vec![2, -8]));
+        assert!(cnf.clauses.contains(&vec![-2, 8]));
+        // no tile points outside the level
+        for outside in [1, 3, 4, 5, 6, 7] {
+            assert!(cnf.clauses.contains(&vec![-outside]));
+        }
+    }
+
+    #[test]
+    fn clause_templates_are_irredundant() {
+        for template in clause_templates() {
+            for index in 0..template.len() {
+                let mut without = template.clone();
+                without.remove(index);
+                assert_ne!(models(&without), models(&template));
             }
         }
     }
-    log::info!("tiles: {}", tiles.len());
-    Ok(tiles)
+
+    #[test]
+    fn clause_templates_are_smaller_than_the_handwritten_ones() {
+        // clause counts of the former handwritten encoding per tile index
+        const HANDWRITTEN: [usize; 16] = [4, 7, 7, 4, 7, 12, 4, 7, 7, 4, 12, 7, 4, 7, 7, 4];
+        let templates = clause_templates();
+        assert!(all_enums_ascending::<Tile<Square>>()
+            .all(|tile| templates[tile.enum_to_index() as usize].len()
+                <= HANDWRITTEN[tile.enum_to_index() as usize]));
+        assert!(templates.iter().map(Vec::len).sum::<usize>() < HANDWRITTEN.iter().sum());
+    }
 }
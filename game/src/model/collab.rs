@@ -0,0 +1,234 @@
+//! Drafts edited by several editors at once
+//!
+//! every editor keeps its own copy of the draft and sends its edits and cursor moves to the others.
+//! Each cell is a last-writer-wins register: an edit carries the version of the cell it writes, a Lamport clock
+//! with ties broken by the editor, and replaces the tile only if it is newer than the version of the cell.
+//! Copies receiving the same edits in any order therefore agree. The dimensions of a shared draft are fixed
+
+use std::collections::BTreeMap;
+
+use super::{
+    accesserror::AccessError,
+    coordinate::Coordinate,
+    grid::Grid,
+    tile::{Square, Tile},
+};
+
+/// editor taking part in a shared draft, unique among the editors of the draft
+pub type EditorId = u32;
+
+/// Version of a cell, later edits have greater versions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Version {
+    /// Lamport clock of the editor at the time of the edit
+    pub clock: u64,
+    pub editor: EditorId,
+}
+
+/// Edit of a single cell, sent to the other editors of the draft
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CellEdit {
+    pub index: Coordinate<isize>,
+    pub tile: Tile<Square>,
+    pub version: Version,
+}
+
+/// Cell selected by an editor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cursor {
+    pub editor: EditorId,
+    pub index: Coordinate<isize>,
+}
+
+/// Copy of a draft of one of its editors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SharedDraft {
+    editor: EditorId,
+    clock: u64,
+    grid: Grid<Tile<Square>>,
+    versions: Grid<Version>,
+    /// cursors of the other editors
+    cursors: BTreeMap<EditorId, Coordinate<isize>>,
+}
+
+impl SharedDraft {
+    /// Copy of the editor, all editors start from the same grid
+    pub fn new(editor: EditorId, grid: Grid<Tile<Square>>) -> Self {
+        SharedDraft {
+            editor,
+            clock: 0,
+            versions: Grid::filled_with(grid.dimensions(), Version::default()),
+            grid,
+            cursors: BTreeMap::new(),
+        }
+    }
+
+    pub fn grid(&self) -> &Grid<Tile<Square>> {
+        &self.grid
+    }
+
+    /// Sets the tile of the cell, returns the edit to send to the other editors
+    pub fn edit(
+        &mut self,
+        index: Coordinate<isize>,
+        tile: Tile<Square>,
+    ) -> Result<CellEdit, AccessError> {
+        let edit = CellEdit {
+            index,
+            tile,
+            version: Version {
+                clock: self.clock + 1,
+                editor: self.editor,
+            },
+        };
+        self.apply(edit).map(|_| edit)
+    }
+
+    /// Applies the edit of any editor, returns whether the tile was replaced
+    ///
+    /// edits not newer than the version of the cell are ignored, so applying an edit twice changes nothing
+    pub fn apply(&mut self, edit: CellEdit) -> Result<bool, AccessError> {
+        let version = *self
+            .versions
+            .get(edit.index)
+            .ok_or(AccessError::IndexOutOfBounds)?;
+        self.clock = self.clock.max(edit.version.clock);
+        if edit.version <= version {
+            return Ok(false);
+        }
+        self.grid.set(edit.index, edit.tile)?;
+        self.versions.set(edit.index, edit.version)?;
+        Ok(true)
+    }
+
+    /// Selects the cell, returns the cursor to send to the other editors
+    pub fn move_cursor(&self, index: Coordinate<isize>) -> Cursor {
+        Cursor {
+            editor: self.editor,
+            index,
+        }
+    }
+
+    /// Shows the cursor of another editor, replacing its previous position
+    pub fn apply_cursor(&mut self, cursor: Cursor) {
+        if cursor.editor != self.editor {
+            self.cursors.insert(cursor.editor, cursor.index);
+        }
+    }
+
+    /// Removes the cursor of an editor who left the draft
+    pub fn leave(&mut self, editor: EditorId) {
+        self.cursors.remove(&editor);
+    }
+
+    /// cursors of the other editors, ordered by editor
+    pub fn cursors(&self) -> impl Iterator<Item = Cursor> + '_ {
+        self.cursors
+            .iter()
+            .map(|(&editor, &index)| Cursor { editor, index })
+    }
+
+    /// latest edit of every edited cell, applying them to a copy of the grid as it is catches up with this copy
+    pub fn edits(&self) -> impl Iterator<Item = CellEdit> + '_ {
+        self.versions
+            .coordinates()
+            .filter(|&index| self.versions[index] != Version::default())
+            .map(|index| CellEdit {
+                index,
+                tile: self.grid[index],
+                version: self.versions[index],
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::model::{
+        coordinate::Coordinate,
+        grid::Grid,
+        tile::{Square, Tile},
+    };
+
+    use super::{CellEdit, Cursor, SharedDraft};
+
+    #[quickcheck]
+    fn copies_receiving_the_same_edits_agree(
+        edits: Vec<(bool, Coordinate<u8>, Tile<Square>)>,
+    ) -> bool {
+        let start = Grid::filled_with(Coordinate::new(3, 3), Tile::NO_CONNECTIONS);
+        let mut copies = [
+            SharedDraft::new(1, start.clone()),
+            SharedDraft::new(2, start),
+        ];
+        let mut sent = [vec![], vec![]];
+        for (second, index, tile) in edits {
+            let index = index.map(|x| (x % 3) as isize);
+            let editor = usize::from(second);
+            sent[editor].push(copies[editor].edit(index, tile).unwrap());
+        }
+        // each copy receives the edits of the other one late and in reverse order
+        let [first, second] = &mut copies;
+        let receive = |copy: &mut SharedDraft, edits: &[CellEdit]| {
+            edits.iter().rev().all(|&edit| copy.apply(edit).is_ok())
+        };
+        receive(first, &sent[1]) && receive(second, &sent[0]) && first.grid() == second.grid()
+    }
+
+    #[test]
+    fn later_edits_win() {
+        let start = "┏┓\n┗┛".parse::<Grid<Tile<Square>>>().unwrap();
+        let mut ada = SharedDraft::new(1, start.clone());
+        let mut bob = SharedDraft::new(2, start);
+        let corner = Coordinate::new(0, 0);
+
+        let first = ada.edit(corner, Tile::NO_CONNECTIONS).unwrap();
+        assert_eq!(bob.apply(first), Ok(true));
+        let second = bob.edit(corner, Tile::ALL_CONNECTIONS).unwrap();
+        assert_eq!(ada.apply(second), Ok(true));
+        // the edit bob saw before making their own is outdated
+        assert_eq!(bob.apply(first), Ok(false));
+        assert_eq!(ada.grid()[corner], Tile::ALL_CONNECTIONS);
+        assert!(ada
+            .apply(CellEdit {
+                index: Coordinate::new(5, 5),
+                ..second
+            })
+            .is_err());
+    }
+
+    #[quickcheck]
+    fn joining_copies_catch_up(edits: Vec<(Coordinate<u8>, Tile<Square>)>) -> bool {
+        let mut ada = SharedDraft::new(
+            1,
+            Grid::filled_with(Coordinate::new(3, 3), Tile::NO_CONNECTIONS),
+        );
+        for (index, tile) in edits {
+            ada.edit(index.map(|x| (x % 3) as isize), tile).unwrap();
+        }
+        let mut bob = SharedDraft::new(2, ada.grid().clone());
+        let caught_up = ada.edits().all(|edit| bob.apply(edit).is_ok());
+        let later = bob
+            .edit(Coordinate::new(0, 0), Tile::ALL_CONNECTIONS)
+            .unwrap();
+        caught_up && ada.apply(later) == Ok(true) && ada.grid() == bob.grid()
+    }
+
+    #[test]
+    fn cursors_of_other_editors_are_shown() {
+        let mut ada = SharedDraft::new(1, Grid::EMPTY);
+        let bob = SharedDraft::new(2, Grid::EMPTY);
+        ada.apply_cursor(bob.move_cursor(Coordinate::new(0, 1)));
+        ada.apply_cursor(bob.move_cursor(Coordinate::new(1, 1)));
+        ada.apply_cursor(ada.move_cursor(Coordinate::new(0, 0)));
+        assert_eq!(
+            ada.cursors().collect::<Vec<_>>(),
+            vec![Cursor {
+                editor: 2,
+                index: Coordinate::new(1, 1)
+            }]
+        );
+        ada.leave(2);
+        assert_eq!(ada.cursors().count(), 0);
+    }
+}
@@ -0,0 +1,224 @@
+//! Connected components of a level
+//!
+//! two neighboring tiles are linked if both have a connection towards each other.
+//! Components are maintained incrementally: turning a tile only relabels the components it touches
+
+use std::collections::VecDeque;
+
+use crate::core::finite::all_enums_ascending;
+
+use super::{
+    coordinate::Coordinate,
+    grid::Grid,
+    tile::{Tile, Topology},
+};
+
+/// label of cells not yet assigned to a component
+const UNLABELED: usize = usize::MAX;
+
+/// Checks whether the tile at the index is linked to its neighbor in the direction
+pub fn is_linked<A: Topology>(
+    grid: &Grid<Tile<A>>,
+    index: Coordinate<isize>,
+    direction: A,
+) -> bool {
+    let connects = |index: Coordinate<isize>, direction: A| {
+        grid.get(index)
+            .map_or(false, |tile| tile.0.contains(direction))
+    };
+    connects(index, direction) && connects(grid.neighbor_index(index, direction), -direction)
+}
+
+/// linked neighbors of the tile at the index
+fn linked_neighbors<A: Topology>(
+    grid: &Grid<Tile<A>>,
+    index: Coordinate<isize>,
+) -> impl Iterator<Item = Coordinate<isize>> + '_ {
+    all_enums_ascending()
+        .filter(move |&direction: &A| is_linked(grid, index, direction))
+        .map(move |direction| grid.neighbor_index(index, direction))
+}
+
+/// Number of links between the origin and every tile of its component, found by breadth-first search
+///
+/// tiles outside the component of the origin have no distance
+pub fn distances_from<A: Topology>(
+    grid: &Grid<Tile<A>>,
+    origin: Coordinate<isize>,
+) -> Grid<Option<usize>> {
+    let mut distances = grid.map(|_| None);
+    if let Some(distance) = distances.get_mut(origin) {
+        *distance = Some(0);
+    }
+    let mut queue = VecDeque::from([(origin, 0)]);
+    while let Some((index, distance)) = queue.pop_front() {
+        for neighbor in linked_neighbors(grid, index) {
+            if let Some(unvisited @ None) = distances.get_mut(neighbor) {
+                *unvisited = Some(distance + 1);
+                queue.push_back((neighbor, distance + 1));
+            }
+        }
+    }
+    distances
+}
+
+/// Component labels of all tiles of a level
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Connectivity {
+    labels: Grid<usize>,
+    next_label: usize,
+}
+
+impl Connectivity {
+    pub fn new<A: Topology>(grid: &Grid<Tile<A>>) -> Self {
+        let mut connectivity = Connectivity {
+            labels: grid.map(|_| UNLABELED),
+            next_label: 0,
+        };
+        let indices = grid.coordinates().collect::<Vec<_>>();
+        connectivity.label(grid, &indices);
+        connectivity
+    }
+
+    /// label of the component containing the tile, tiles share a label iff they are in the same component
+    pub fn component(&self, index: Coordinate<isize>) -> Option<usize> {
+        self.labels.get(index).copied()
+    }
+
+    /// all tiles in the component of the tile
+    pub fn members(&self, index: Coordinate<isize>) -> Vec<Coordinate<isize>> {
+        match self.component(index) {
+            Some(label) => self
+                .labels
+                .coordinates()
+                .filter(|&c| self.labels[c] == label)
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    /// Updates the components after the tile at the index changed
+    ///
+    /// only the components of the tile and its neighbors are relabeled
+    pub fn update<A: Topology>(&mut self, grid: &Grid<Tile<A>>, index: Coordinate<isize>) {
+        if grid.dimensions() != self.labels.dimensions() {
+            *self = Connectivity::new(grid);
+            return;
+        }
+        let touched = all_enums_ascending()
+            .map(|direction: A| grid.neighbor_index(index, direction))
+            .chain([index])
+            .filter_map(|c| self.component(c))
+            .collect::<Vec<_>>();
+        let affected = self
+            .labels
+            .coordinates()
+            .filter(|&c| touched.contains(&self.labels[c]))
+            .collect::<Vec<_>>();
+        for &c in &affected {
+            self.labels[c] = UNLABELED;
+        }
+        self.label(grid, &affected);
+    }
+
+    /// assigns fresh labels to the components of the unlabeled tiles among the indices
+    fn label<A: Topology>(&mut self, grid: &Grid<Tile<A>>, indices: &[Coordinate<isize>]) {
+        for &start in indices {
+            if self.labels[start] != UNLABELED {
+                continue;
+            }
+            let label = self.next_label;
+            self.next_label += 1;
+            self.labels[start] = label;
+            let mut stack = vec![start];
+            while let Some(index) = stack.pop() {
+                for neighbor in linked_neighbors(grid, index) {
+                    if self.labels[neighbor] == UNLABELED {
+                        self.labels[neighbor] = label;
+                        stack.push(neighbor);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::core::interval::Max;
+    use crate::generator::fastgen::generate;
+    use crate::model::{
+        coordinate::Coordinate,
+        gameboard::GameBoard,
+        grid::Grid,
+        parser::{parse_level, unicode_to_tile},
+        tile::{Square, Tile},
+    };
+
+    use super::{distances_from, Connectivity};
+
+    /// labels renumbered by first occurrence in row-major order, equal iff both partitions are equal
+    fn partition(connectivity: &Connectivity, grid: &Grid<Tile<Square>>) -> Vec<usize> {
+        let mut seen = vec![];
+        grid.positions()
+            .map(|position| {
+                let label = connectivity.component(position.into()).unwrap();
+                seen.iter().position(|&l| l == label).unwrap_or_else(|| {
+                    seen.push(label);
+                    seen.len() - 1
+                })
+            })
+            .collect()
+    }
+
+    #[quickcheck]
+    fn incremental_updates_match_recomputation(
+        grid: Grid<Tile<Square>>,
+        turns: Vec<Coordinate<Max<8>>>,
+    ) -> bool {
+        let mut grid = grid;
+        let mut connectivity = Connectivity::new(&grid);
+        turns.into_iter().all(|turn| {
+            let index = turn.map(|x| x.to_usize() as isize);
+            if let Ok(turned) = grid.rotate_clockwise(index) {
+                grid = turned;
+                connectivity.update(&grid, index);
+            }
+            partition(&connectivity, &grid) == partition(&Connectivity::new(&grid), &grid)
+        })
+    }
+
+    #[quickcheck]
+    fn components_are_reachable_by_links(dimension: Coordinate<Max<8>>, seed: u64) -> bool {
+        let grid = generate(dimension.map(Max::to_usize), seed);
+        let connectivity = Connectivity::new(&grid);
+        grid.coordinates().all(|index| {
+            let distances = distances_from(&grid, index);
+            connectivity.members(index).len()
+                == distances.as_slice().iter().filter(|d| d.is_some()).count()
+        })
+    }
+
+    #[test]
+    fn distances_follow_the_links() {
+        let grid = parse_level("┏━┓\n┗━┛\n╺━╸", unicode_to_tile).unwrap();
+        let distances = distances_from(&grid, Coordinate::new(0, 0));
+        assert_eq!(
+            distances.as_slice(),
+            [
+                Some(0),
+                Some(1),
+                Some(2),
+                Some(1),
+                Some(2),
+                Some(3),
+                None,
+                None,
+                None
+            ]
+        );
+        let connectivity = Connectivity::new(&grid);
+        assert_eq!(connectivity.members(Coordinate::new(2, 1)).len(), 3);
+    }
+}
@@ -0,0 +1,181 @@
+use std::{fmt::Display, str::FromStr};
+
+use quickcheck::{Arbitrary, Gen};
+
+use super::{
+    coordinate::Coordinate,
+    grid::Grid,
+    tile::{from_glyph, Square, Tile},
+};
+
+/// Designer-specified requirement on a level
+///
+/// constraints only consider the shape of a tile, not its rotation,
+/// so they hold for a level regardless of whether it is scrambled or solved
+///
+/// # Text format
+///
+/// | Constraint    | Text             | Meaning                                 |
+/// |:--------------|:-----------------|:----------------------------------------|
+/// | `Cell`        | `cell 2 3 ╋`     | cell at row 2, column 3 must be a cross |
+/// | `NotInRow`    | `row 3 not ┃`    | row 3 must contain no straights         |
+/// | `NotInColumn` | `column 1 not ┗` | column 1 must contain no corners        |
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Constraint {
+    /// the cell must contain a tile of the given shape
+    Cell(Coordinate<isize>, Tile<Square>),
+    /// the row must not contain any tile of the given shape
+    NotInRow(isize, Tile<Square>),
+    /// the column must not contain any tile of the given shape
+    NotInColumn(isize, Tile<Square>),
+}
+
+/// Indicates if both tiles are equal under rotation
+fn same_shape(first: Tile<Square>, second: Tile<Square>) -> bool {
+    first.superimpose().contains(second)
+}
+
+impl Constraint {
+    /// Returns the coordinates of all tiles violating the constraint
+    ///
+    /// a [`Constraint::Cell`] outside of the grid is violated at its coordinate
+    pub fn violations(&self, grid: &Grid<Tile<Square>>) -> Vec<Coordinate<isize>> {
+        match *self {
+            Constraint::Cell(coordinate, shape) => match grid.get(coordinate) {
+                Some(&tile) if same_shape(tile, shape) => vec![],
+                _ => vec![coordinate],
+            },
+            Constraint::NotInRow(row, shape) => grid
+                .coordinates()
+                .filter(|c| c.row == row && same_shape(grid[*c], shape))
+                .collect(),
+            Constraint::NotInColumn(column, shape) => grid
+                .coordinates()
+                .filter(|c| c.column == column && same_shape(grid[*c], shape))
+                .collect(),
+        }
+    }
+
+    /// Indicates if the grid satisfies the constraint
+    pub fn is_satisfied_by(&self, grid: &Grid<Tile<Square>>) -> bool {
+        self.violations(grid).is_empty()
+    }
+}
+
+/// Validation pass checking that the grid satisfies all constraints
+///
+/// generators and mutators should run this on their output to preserve the intent of the designer
+pub fn validate(
+    grid: &Grid<Tile<Square>>,
+    constraints: &[Constraint],
+) -> Result<(), Vec<(Constraint, Coordinate<isize>)>> {
+    let violations = constraints
+        .iter()
+        .flat_map(|constraint| {
+            constraint
+                .violations(grid)
+                .into_iter()
+                .map(move |coordinate| (*constraint, coordinate))
+        })
+        .collect::<Vec<_>>();
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(violations)
+    }
+}
+
+impl Display for Constraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Constraint::Cell(c, tile) => write!(f, "cell {} {} {tile}", c.row, c.column),
+            Constraint::NotInRow(row, tile) => write!(f, "row {row} not {tile}"),
+            Constraint::NotInColumn(column, tile) => write!(f, "column {column} not {tile}"),
+        }
+    }
+}
+
+impl FromStr for Constraint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("parsing error: unknown constraint '{s}'");
+        let number = |n: &str| n.parse::<isize>().map_err(|_| error());
+        // the tile glyph may be a space, so it is always the last character
+        let glyph = s.chars().last().ok_or_else(error)?;
+        let tile = from_glyph(glyph).ok_or_else(error)?;
+        let words = s[..s.len() - glyph.len_utf8()]
+            .split_whitespace()
+            .collect::<Vec<_>>();
+        match words.as_slice() {
+            ["cell", row, column] => Ok(Constraint::Cell(
+                Coordinate::new(number(row)?, number(column)?),
+                tile,
+            )),
+            ["row", row, "not"] => Ok(Constraint::NotInRow(number(row)?, tile)),
+            ["column", column, "not"] => Ok(Constraint::NotInColumn(number(column)?, tile)),
+            _ => Err(error()),
+        }
+    }
+}
+
+impl Arbitrary for Constraint {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let tile = Tile::arbitrary(g);
+        match g.choose(&[0, 1, 2]).unwrap() {
+            0 => Constraint::Cell(Coordinate::arbitrary(g), tile),
+            1 => Constraint::NotInRow(isize::arbitrary(g), tile),
+            _ => Constraint::NotInColumn(isize::arbitrary(g), tile),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::generator::fastgen::generate;
+    use crate::model::{
+        coordinate::Coordinate,
+        grid::Grid,
+        tile::{Square, Tile},
+    };
+
+    use crate::core::interval::{Interval, Max};
+
+    use super::{validate, Constraint};
+
+    #[quickcheck]
+    fn display_then_parse_is_identity(constraint: Constraint) -> bool {
+        constraint.to_string().parse() == Ok(constraint)
+    }
+
+    // constraints describe shapes, so rotating tiles never changes the outcome
+    #[quickcheck]
+    fn validation_is_independent_of_rotation(
+        dimension: Coordinate<Max<10>>,
+        seed: u64,
+        constraints: Vec<Constraint>,
+    ) -> bool {
+        let grid = generate(dimension.map(Interval::to_usize), seed);
+        validate(&grid, &constraints).is_ok()
+            == validate(&grid.scramble(seed), &constraints).is_ok()
+    }
+
+    #[quickcheck]
+    fn grid_satisfies_its_own_cells(grid: Grid<Tile<Square>>) -> bool {
+        let constraints = grid
+            .coordinates()
+            .map(|c| Constraint::Cell(c, grid[c].rotated_clockwise(1)))
+            .collect::<Vec<_>>();
+        validate(&grid, &constraints).is_ok()
+    }
+
+    #[quickcheck]
+    fn excluded_shape_is_reported(grid: Grid<Tile<Square>>) -> bool {
+        grid.coordinates().all(|c| {
+            Constraint::NotInRow(c.row, grid[c])
+                .violations(&grid)
+                .contains(&c)
+        })
+    }
+}
@@ -151,6 +151,42 @@ impl<A> From<[A; 2]> for Coordinate<A> {
     }
 }
 
+/// Row index into a [`Grid`](super::grid::Grid)
+///
+/// distinct from [`Column`] to prevent accidentally swapping both, see [`Grid::at`](super::grid::Grid::at)
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default)]
+pub struct Row(pub usize);
+
+/// Column index into a [`Grid`](super::grid::Grid)
+///
+/// distinct from [`Row`] to prevent accidentally swapping both, see [`Grid::at`](super::grid::Grid::at)
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default)]
+pub struct Column(pub usize);
+
+impl From<(Row, Column)> for Coordinate<isize> {
+    fn from((Row(row), Column(column)): (Row, Column)) -> Self {
+        Self::new(row as isize, column as isize)
+    }
+}
+
+impl Coordinate<isize> {
+    /// Splits into typed indices, if both components are non-negative
+    pub fn to_row_column(self) -> Option<(Row, Column)> {
+        Some((
+            Row(usize::try_from(self.row).ok()?),
+            Column(usize::try_from(self.column).ok()?),
+        ))
+    }
+
+    /// Orientation of the triangle at this position in a triangular grid
+    ///
+    /// triangles alternate between pointing upwards and downwards along rows and columns, starting with an
+    /// upward pointing triangle at the origin
+    pub fn points_up(self) -> bool {
+        (self.row + self.column).rem_euclid(2) == 0
+    }
+}
+
 // impl<A, F: Fn(bool) -> A> From<F> for Coordinate<A> {
 //     /// As representable functor Coordinate is isomorphic to [`Fn(bool) -> A`]
 //     fn from(f: F) -> Self {
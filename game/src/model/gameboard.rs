@@ -1,11 +1,11 @@
-use std::collections::HashMap;
+use std::{fmt::Display, str::FromStr};
 
 use super::accesserror::AccessError;
 
 /// complete interface to interact with game
 // sending information to the view:
 //  1. return changed grid parts as list of actions
-//  2. send whole grid as `HasMap Coordinate Tile`
+//  2. send whole grid as `Vec (Coordinate, Tile)`
 pub trait GameBoard: Sized {
     /// defines indexing system for the given model
     type Index;
@@ -26,6 +26,105 @@ pub trait GameBoard: Sized {
     /// queries if gameboard is solved
     fn is_solved(&self) -> bool;
 
-    /// query the current status
-    fn serialize_board(&self) -> HashMap<Self::Index, &Self::Tile>;
+    /// queries if gameboard is solved and all its non-empty tiles form a single connected component
+    ///
+    /// stricter than [`GameBoard::is_solved`], which accepts multiple disjoint loops
+    fn is_solved_single_loop(&self) -> bool;
+
+    /// queries if gameboard is solved according to the win condition of a game mode
+    fn is_solved_with(&self, condition: &dyn WinCondition<Self>) -> bool {
+        condition.is_met_by(self)
+    }
+
+    /// query the current status as list of all cells in row-major order
+    fn to_cells(&self) -> Vec<(Self::Index, Self::Tile)>;
+}
+
+/// Strategy deciding when a gameboard counts as solved
+///
+/// game modes implement this trait instead of adding queries to [`GameBoard`]
+pub trait WinCondition<B> {
+    fn is_met_by(&self, board: &B) -> bool;
+}
+
+/// every connection is answered by its neighbor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct AllEdgesMatched;
+
+impl<B: GameBoard> WinCondition<B> for AllEdgesMatched {
+    fn is_met_by(&self, board: &B) -> bool {
+        board.is_solved()
+    }
+}
+
+/// all edges matched and all non-empty tiles form a single connected component
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct SingleLoop;
+
+impl<B: GameBoard> WinCondition<B> for SingleLoop {
+    fn is_met_by(&self, board: &B) -> bool {
+        board.is_solved_single_loop()
+    }
+}
+
+/// all edges matched and all sources, i.e. tiles with a single connection, in the same connected component
+///
+/// other components without sources may remain, implemented by the boards knowing their connectivity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct AllSourcesConnected;
+
+/// rule deciding when a gameboard counts as solved, selectable per level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum WinRule {
+    /// every connection is answered by its neighbor, see [`GameBoard::is_solved`]
+    #[default]
+    MatchingConnections,
+    /// additionally a single connected component, see [`GameBoard::is_solved_single_loop`]
+    SingleLoop,
+    /// additionally all sources connected, see [`AllSourcesConnected`]
+    AllSourcesConnected,
+}
+
+impl WinRule {
+    pub const ALL: [WinRule; 3] = [
+        WinRule::MatchingConnections,
+        WinRule::SingleLoop,
+        WinRule::AllSourcesConnected,
+    ];
+
+    pub fn is_met_by<B>(self, board: &B) -> bool
+    where
+        B: GameBoard,
+        AllSourcesConnected: WinCondition<B>,
+    {
+        match self {
+            WinRule::MatchingConnections => board.is_solved_with(&AllEdgesMatched),
+            WinRule::SingleLoop => board.is_solved_with(&SingleLoop),
+            WinRule::AllSourcesConnected => board.is_solved_with(&AllSourcesConnected),
+        }
+    }
+}
+
+impl Display for WinRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            WinRule::MatchingConnections => "matching connections",
+            WinRule::SingleLoop => "single loop",
+            WinRule::AllSourcesConnected => "all sources connected",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for WinRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "matching connections" => Ok(WinRule::MatchingConnections),
+            "single loop" => Ok(WinRule::SingleLoop),
+            "all sources connected" => Ok(WinRule::AllSourcesConnected),
+            _ => Err(format!("parsing error: unknown win rule '{s}'")),
+        }
+    }
 }
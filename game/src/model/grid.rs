@@ -6,13 +6,15 @@ use std::{
 
 use quickcheck::{Arbitrary, Gen};
 
+use crate::core::{enummap::EnumMap, finite::all_enums_ascending};
 use crate::{enumset, tile};
 
-use super::coordinate::Coordinate;
-use super::gameboard::GameBoard;
+use super::connectivity::Connectivity;
+use super::coordinate::{Column, Coordinate, Row};
+use super::gameboard::{AllSourcesConnected, GameBoard, WinCondition};
 use super::{
     accesserror::AccessError,
-    tile::{Square, Tile},
+    tile::{Square, Tile, Topology},
 };
 
 /// Defines a fully filled 2D-grid with coordinate-based access
@@ -32,12 +34,18 @@ use super::{
 /// 1. [`Grid<A>`] forms a rectangle entirely filled with elements of type `A`
 /// 2. `∀g : Grid. g.rows * g.columns ≡ g.elements.len()`
 /// 3. [`Grid`] is positioned at Coordinate (0, 0) and extends in positive directions
+///
+/// ## Torus
+///
+/// the edges of a torus wrap around: the leftmost column neighbors the rightmost and the top row the bottom row,
+/// see [`Grid::neighbor_index`]. Transformations of the elements keep the wrap, grids constructed anew are bounded
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Hash)]
 pub struct Grid<A> {
     rows: usize,
     columns: usize,
     /// layout: `[(0, 0), (0, 1), (0, 2), ..., (1, 0), (1, 1), (1, 2), ...]`
     elements: Vec<A>,
+    torus: bool,
 }
 
 impl<A> Grid<A> {
@@ -46,6 +54,7 @@ impl<A> Grid<A> {
         rows: 0,
         columns: 0,
         elements: vec![],
+        torus: false,
     };
 
     /// Constructs a new grid
@@ -65,6 +74,7 @@ impl<A> Grid<A> {
             rows,
             columns,
             elements,
+            torus: false,
         }
     }
 
@@ -72,9 +82,9 @@ impl<A> Grid<A> {
     pub fn init<F: Fn(Coordinate<isize>) -> A>(dimensions: Coordinate<usize>, init: F) -> Self {
         let mut elements = Vec::with_capacity(dimensions.product());
 
-        for row in 0..dimensions.row as isize {
-            for column in 0..dimensions.column as isize {
-                elements.push(init(Coordinate { row, column }));
+        for row in 0..dimensions.row {
+            for column in 0..dimensions.column {
+                elements.push(init((Row(row), Column(column)).into()));
             }
         }
 
@@ -99,6 +109,40 @@ impl<A> Grid<A> {
         }
     }
 
+    /// Grid whose edges wrap around, see [`Grid::neighbor_index`]
+    ///
+    /// triangular levels require an even number of rows and columns for the orientations to match across the edges
+    pub fn into_torus(self) -> Self {
+        Grid {
+            torus: true,
+            ..self
+        }
+    }
+
+    /// Checks whether the edges wrap around
+    pub const fn is_torus(&self) -> bool {
+        self.torus
+    }
+
+    /// Position of the neighbor of the cell in the direction
+    ///
+    /// wraps around the edges of a torus, otherwise the position may lie outside the grid
+    pub fn neighbor_index<D: Topology>(
+        &self,
+        index: Coordinate<isize>,
+        direction: D,
+    ) -> Coordinate<isize> {
+        let neighbor = index.get_neighbor_index(direction);
+        if self.torus && self.size() > 0 {
+            Coordinate::new(
+                neighbor.row.rem_euclid(self.rows as isize),
+                neighbor.column.rem_euclid(self.columns as isize),
+            )
+        } else {
+            neighbor
+        }
+    }
+
     /// Returns the number of elements the grid can hold
     pub const fn size(&self) -> usize {
         self.rows * self.columns
@@ -136,6 +180,7 @@ impl<A> Grid<A> {
             rows: self.rows,
             columns: self.columns,
             elements: self.elements.clone().into_iter().zip(iter).collect(),
+            torus: self.torus,
         }
     }
 
@@ -150,12 +195,41 @@ impl<A> Grid<A> {
             rows: self.rows,
             columns: self.columns,
             elements: coordinates.zip(self.elements.clone()).collect(),
+            torus: self.torus,
         }
     }
 
     /// see [Grid::elements] for memory layout
     fn get_vec_index(&self, index: Coordinate<isize>) -> usize {
-        index.column as usize + self.columns * index.row as usize
+        self.vec_index(Row(index.row as usize), Column(index.column as usize))
+    }
+
+    fn vec_index(&self, Row(row): Row, Column(column): Column) -> usize {
+        column + self.columns * row
+    }
+
+    /// Element at the typed position, if in bounds
+    ///
+    /// preferred over [`Grid::get`] where row and column are computed separately and could be mixed up
+    pub fn at(&self, row: Row, column: Column) -> Option<&A> {
+        (row.0 < self.rows && column.0 < self.columns)
+            .then(|| &self.elements[self.vec_index(row, column)])
+    }
+
+    pub fn at_mut(&mut self, row: Row, column: Column) -> Option<&mut A> {
+        if row.0 < self.rows && column.0 < self.columns {
+            let vec_index = self.vec_index(row, column);
+            Some(&mut self.elements[vec_index])
+        } else {
+            None
+        }
+    }
+
+    /// Returns all typed positions in row-major order, matching the memory layout
+    pub fn positions(&self) -> impl Iterator<Item = (Row, Column)> {
+        let columns = self.columns; // erase reference to grid for independent lifetime
+        (0..self.rows)
+            .flat_map(move |row| (0..columns).map(move |column| (Row(row), Column(column))))
     }
 
     pub fn get(&self, index: Coordinate<isize>) -> Option<&A> {
@@ -165,10 +239,10 @@ impl<A> Grid<A> {
     }
 
     pub fn get_mut(&mut self, index: Coordinate<isize>) -> Option<&mut A> {
+        // the index of coordinates out of bounds may overflow
+        self.ensure_index_in_bounds(index).ok()?;
         let vec_index = self.get_vec_index(index);
-        self.ensure_index_in_bounds(index)
-            .map(|_| &mut self.elements[vec_index])
-            .ok()
+        Some(&mut self.elements[vec_index])
     }
 
     /// applies transformation to element at supplied index, if possible
@@ -201,6 +275,25 @@ impl<A> Grid<A> {
         }
     }
 
+    /// applies transformation in place to element at supplied index
+    ///
+    /// unlike [`Grid::adjust_at`] the grid is not copied, preferred in loops changing many elements
+    pub fn adjust_at_mut<F: FnOnce(&mut A)>(
+        &mut self,
+        index: Coordinate<isize>,
+        transformation: F,
+    ) -> Result<(), AccessError> {
+        let element = self.get_mut(index).ok_or(AccessError::IndexOutOfBounds)?;
+        transformation(element);
+        Ok(())
+    }
+
+    /// replaces the element at supplied index in place and returns the previous one
+    pub fn set(&mut self, index: Coordinate<isize>, element: A) -> Result<A, AccessError> {
+        let previous = self.get_mut(index).ok_or(AccessError::IndexOutOfBounds)?;
+        Ok(std::mem::replace(previous, element))
+    }
+
     fn ensure_index_in_bounds(&self, index: Coordinate<isize>) -> Result<(), String> {
         if index.row >= 0
             && index.column >= 0
@@ -235,10 +328,12 @@ impl<A: Clone> Grid<A> {
 
     // vec cannot be safely mapped over in-place, therefore map for Grid creates a new instance
     pub fn map<B, F: Fn(A) -> B>(&self, transform: F) -> Grid<B> {
-        Grid::new(
-            self.dimensions(),
-            self.elements.clone().into_iter().map(transform).collect(),
-        )
+        Grid {
+            rows: self.rows,
+            columns: self.columns,
+            elements: self.elements.clone().into_iter().map(transform).collect(),
+            torus: self.torus,
+        }
     }
 
     fn map_mut<F: FnMut(&mut A) -> A>(mut self, mut transform: F) -> Self {
@@ -255,6 +350,7 @@ impl<A: Clone> Grid<Option<A>> {
             rows: self.rows,
             columns: self.columns,
             elements: self.elements.clone().into_iter().collect::<Option<_>>()?,
+            torus: self.torus,
         })
     }
 }
@@ -269,14 +365,15 @@ impl<A: Clone, E: Clone> Grid<Result<A, E>> {
                 .clone()
                 .into_iter()
                 .collect::<Result<_, _>>()?,
+            torus: self.torus,
         })
     }
 }
 
-impl GameBoard for Grid<Tile<Square>> {
+impl<A: Topology> GameBoard for Grid<Tile<A>> {
     type Index = Coordinate<isize>;
 
-    type Tile = Tile<Square>;
+    type Tile = Tile<A>;
 
     fn rotate_clockwise(&self, index: Self::Index) -> Result<Self, AccessError> {
         self.adjust_at(index, |x| x.rotated_clockwise(1))
@@ -287,79 +384,56 @@ impl GameBoard for Grid<Tile<Square>> {
     }
 
     fn is_solved(&self) -> bool {
-        // currently implemented as pure function on gameboard without caching
-        // in case of performance issues use caching of already solved grid regions
-        //
-        // algorithm
+        // pure function on the whole gameboard, see CachedBoard for checks after single rotations
         //
-        // split up grid in all complete horizontal and vertical sections by coordinates
-        // transform coordinates into respective tiles and enclose each section with empty sentinel tiles
-        // to ensure absence of connections pointing outside the grid
-        // ensure all neighboring tiles in sections have matching connections, either both or neither connection pointing to each other
-        //
-        // Tile is newtype around integer, owned vs borrowed irrelevant
-
-        // better algorithm
-        // copy into new grid with borde of sentinel values
-        // for each (coord, v) except last column check connection to right neighbor
-        // for each (coord, v) except last row check connection to down neighbor
-
-        let Coordinate {
-            row: rows,
-            column: columns,
-        } = self.dimensions().map(|x| x as isize);
-
-        let enclose_sentinels = |mut v: Vec<Self::Tile>| {
-            v.insert(0, Self::Tile::NO_CONNECTIONS);
-            v.push(Self::Tile::NO_CONNECTIONS);
-            v
-        };
-        let row_slice = |r| {
-            (0..columns)
-                .map(|c| Coordinate { row: r, column: c })
-                .collect::<Vec<_>>()
-        };
-        let column_slice = |c| {
-            (0..rows)
-                .map(|r| Coordinate { row: r, column: c })
-                .collect::<Vec<_>>()
+        // every connection must be answered by the neighbor in that direction,
+        // positions outside the grid behave like tiles without connections
+        let connected = |index: Coordinate<isize>, direction: A| {
+            self.get(index)
+                .map_or(false, |tile| tile.0.contains(direction))
         };
+        self.coordinates().all(|index| {
+            all_enums_ascending().all(|direction: A| {
+                connected(index, direction)
+                    == connected(self.neighbor_index(index, direction), -direction)
+            })
+        })
+    }
 
-        let to_tile =
-            |v: Vec<Coordinate<_>>| enclose_sentinels(v.into_iter().map(|c| self[c]).collect());
+    fn is_solved_single_loop(&self) -> bool {
+        let connectivity = Connectivity::new(self);
+        let mut components = self
+            .coordinates()
+            .filter(|&index| !self[index].0.is_empty())
+            .filter_map(|index| connectivity.component(index));
+        self.is_solved()
+            && components
+                .next()
+                .map_or(true, |first| components.all(|label| label == first))
+    }
 
-        let rows_solved = (0..rows).map(row_slice).map(to_tile).all(|v| {
-            v[0..]
-                .iter()
-                .zip(v[1..].iter())
-                .all(|(tl, tr)| tl.0.contains(Square::Right) == tr.0.contains(Square::Left))
-        });
-        let columns_solved = (0..columns).map(column_slice).map(to_tile).all(|v| {
-            v[0..]
-                .iter()
-                .zip(v[1..].iter())
-                .all(|(tu, td)| tu.0.contains(Square::Down) == td.0.contains(Square::Up))
-        });
-        rows_solved && columns_solved
-    }
-
-    fn serialize_board(&self) -> std::collections::HashMap<Self::Index, &Self::Tile> {
-        self.as_slice()
-            .iter()
-            .zip(0..)
-            .map(|(x, i)| {
-                (
-                    Coordinate {
-                        row: i / self.rows as isize,
-                        column: i % self.rows as isize,
-                    },
-                    x,
-                )
-            })
+    fn to_cells(&self) -> Vec<(Self::Index, Self::Tile)> {
+        self.positions()
+            .zip(self.as_slice())
+            .map(|(position, &tile)| (position.into(), tile))
             .collect()
     }
 }
 
+impl<A: Topology> WinCondition<Grid<Tile<A>>> for AllSourcesConnected {
+    fn is_met_by(&self, grid: &Grid<Tile<A>>) -> bool {
+        let connectivity = Connectivity::new(grid);
+        let mut components = grid
+            .coordinates()
+            .filter(|&index| grid[index].0.len() == 1)
+            .filter_map(|index| connectivity.component(index));
+        grid.is_solved()
+            && components
+                .next()
+                .map_or(true, |first| components.all(|label| label == first))
+    }
+}
+
 impl Grid<Tile<Square>> {
     pub fn resize(&mut self, size: Coordinate<usize>) {
         // Sets the grid to a new size and maps the tiles from the old-sized-element-vector to the new-sized one.
@@ -370,12 +444,8 @@ impl Grid<Tile<Square>> {
 
         for r in 0..size.row {
             for c in 0..size.column {
-                if r < self.rows && c < self.columns {
-                    let new_index = c + size.column * r;
-                    new_elements[new_index] = self.elements[self.get_vec_index(Coordinate {
-                        column: c as isize,
-                        row: r as isize,
-                    })];
+                if let Some(&tile) = self.at(Row(r), Column(c)) {
+                    new_elements[c + size.column * r] = tile;
                 }
             }
         }
@@ -385,6 +455,22 @@ impl Grid<Tile<Square>> {
         self.elements = new_elements;
     }
 
+    /// inverse of [`GameBoard::to_cells`]
+    ///
+    /// omitted cells are empty, later cells replace earlier ones at the same coordinate
+    pub fn from_cells<I>(dimensions: Coordinate<usize>, cells: I) -> Result<Self, AccessError>
+    where
+        I: IntoIterator<Item = (Coordinate<isize>, Tile<Square>)>,
+    {
+        let mut grid = Self::filled_with(dimensions, Tile::NO_CONNECTIONS);
+        for (coordinate, tile) in cells {
+            *grid
+                .get_mut(coordinate)
+                .ok_or(AccessError::IndexOutOfBounds)? = tile;
+        }
+        Ok(grid)
+    }
+
     pub fn change_tile_shape(&self, index: Coordinate<isize>) -> Result<Self, AccessError> {
         // Depending on the number and (if the number is two) on the arrangement of the tile's arms, it will reshaped in the following order:
         // ╹ -> -> ┗ -> ┃ -> ┣ -> ╋ -> ' '
@@ -409,6 +495,62 @@ impl Grid<Tile<Square>> {
         })
     }
 
+    /// Fraction of positions holding tiles of the same shape, independent of their rotation
+    ///
+    /// tolerates noise of imported levels, e.g. misrecognized tiles or cropped borders:
+    /// only the overlapping region is compared, relative to the size of the larger grid
+    pub fn similarity(&self, other: &Self) -> f32 {
+        let size = self.size().max(other.size());
+        if size == 0 {
+            return 1.0;
+        }
+        let matching = self
+            .coordinates()
+            .filter(|&c| {
+                other
+                    .get(c)
+                    .map_or(false, |&tile| tile.superimpose().contains(self[c]))
+            })
+            .count();
+        matching as f32 / size as f32
+    }
+
+    /// Renders the grid using only 7-bit characters, see [`ASCII_GLYPHS`](super::tile::ASCII_GLYPHS)
+    ///
+    /// for logs, CI output and terminals that mangle unicode
+    pub fn to_ascii(&self) -> String {
+        format!("{self:#}")
+    }
+
+    /// Connections leaving the grid through each of its edges
+    ///
+    /// decides whether grids can be composed side by side, the connections of the `Up` and `Down` edges
+    /// are ordered by column, those of the `Left` and `Right` edges by row. Edges of grids without rows
+    /// or columns have no connections
+    pub fn border_connections(&self) -> EnumMap<Square, Vec<bool>> {
+        let (rows, columns) = (self.rows as isize, self.columns as isize);
+        all_enums_ascending::<Square>()
+            .map(|direction| {
+                let edge = match direction {
+                    Square::Up => (0..columns)
+                        .map(|c| Coordinate::new(0, c))
+                        .collect::<Vec<_>>(),
+                    Square::Down => (0..columns).map(|c| Coordinate::new(rows - 1, c)).collect(),
+                    Square::Left => (0..rows).map(|r| Coordinate::new(r, 0)).collect(),
+                    Square::Right => (0..rows).map(|r| Coordinate::new(r, columns - 1)).collect(),
+                };
+                let connections = edge
+                    .into_iter()
+                    .map(|index| {
+                        self.get(index)
+                            .map_or(false, |tile| tile.0.contains(direction))
+                    })
+                    .collect();
+                (direction, connections)
+            })
+            .collect()
+    }
+
     pub fn rotate_clockwise_n_times(
         &self,
         index: Coordinate<isize>,
@@ -452,8 +594,10 @@ impl<A> IntoIterator for Grid<A> {
     }
 }
 
+/// the alternate flag `{:#}` is passed on to the elements, e.g. selecting [`Grid::to_ascii`] for tiles
 impl<A: Display> Display for Grid<A> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let alternate = f.alternate();
         write!(
             f,
             "{}",
@@ -462,7 +606,13 @@ impl<A: Display> Display for Grid<A> {
             } else {
                 self.elements
                     .iter()
-                    .map(ToString::to_string)
+                    .map(|element| {
+                        if alternate {
+                            format!("{element:#}")
+                        } else {
+                            element.to_string()
+                        }
+                    })
                     .collect::<Vec<String>>()
                     .chunks_exact(self.columns)
                     .map(|s| s.join(""))
@@ -484,6 +634,7 @@ impl<A: Arbitrary> Arbitrary for Grid<A> {
             rows,
             columns,
             elements,
+            torus: false,
         }
     }
 }
@@ -492,10 +643,52 @@ impl<A: Arbitrary> Arbitrary for Grid<A> {
 mod grid_tests {
 
     use crate::core::interval::Max;
-    use crate::model::coordinate::Coordinate;
+    use crate::model::coordinate::{Column, Coordinate, Row};
 
     use super::Grid;
 
+    #[quickcheck]
+    fn at_agrees_with_get(grid: Grid<u8>, row: Max<20>, column: Max<20>) -> bool {
+        let (row, column) = (row.to_usize(), column.to_usize());
+        grid.at(Row(row), Column(column))
+            == grid.get(Coordinate::new(row as isize, column as isize))
+    }
+
+    #[quickcheck]
+    fn positions_follow_memory_layout(grid: Grid<u8>) -> bool {
+        grid.positions()
+            .map(|(row, column)| grid.at(row, column))
+            .eq(grid.as_slice().iter().map(Some))
+    }
+
+    #[quickcheck]
+    fn in_place_adjustment_agrees_with_adjust_at(
+        grid: Grid<u8>,
+        row: Max<20>,
+        column: Max<20>,
+    ) -> bool {
+        let index = Coordinate::new(row.to_isize(), column.to_isize());
+        let increment = |x: u8| x.wrapping_add(1);
+        let mut adjusted = grid.clone();
+        let in_place = adjusted.adjust_at_mut(index, |x| *x = increment(*x));
+        let mut replaced = grid.clone();
+        let previous = replaced.set(index, grid.get(index).copied().map_or(0, increment));
+        match grid.adjust_at(index, increment) {
+            Ok(copy) => {
+                in_place.is_ok()
+                    && adjusted == copy
+                    && replaced == copy
+                    && previous.ok() == grid.get(index).copied()
+            }
+            Err(error) => {
+                in_place == Err(error)
+                    && previous == Err(error)
+                    && adjusted == grid
+                    && replaced == grid
+            }
+        }
+    }
+
     // restrict size grid to avoid excessive vector allocation
     #[quickcheck]
     fn ensure_dimensions(dimensions: Coordinate<Max<100>>) -> bool {
@@ -507,11 +700,20 @@ mod grid_tests {
 #[cfg(test)]
 mod gameboard_tests {
 
+    use crate::core::interval::Max;
+    use crate::generator::fastgen::generate;
+    use crate::model::{
+        accesserror::AccessError,
+        coordinate::Coordinate,
+        gameboard::{AllEdgesMatched, AllSourcesConnected, SingleLoop, WinRule},
+        parser::{parse_level, unicode_to_tile},
+    };
+
     use super::{GameBoard, Grid, Square, Tile};
 
     #[quickcheck]
     fn empty_gameboard_is_solved() -> bool {
-        Grid::EMPTY.is_solved()
+        Grid::<Tile<Square>>::EMPTY.is_solved()
     }
 
     // single tile gameboard is solved iff tile has no connections
@@ -520,5 +722,137 @@ mod gameboard_tests {
         Grid::new(1.into(), vec![tile]).is_solved() == tile.0.is_empty()
     }
 
+    #[quickcheck]
+    fn solved_grids_have_no_border_connections(dimension: Coordinate<Max<10>>, seed: u64) -> bool {
+        generate(dimension.map(Max::to_usize), seed)
+            .border_connections()
+            .into_values()
+            .all(|edge| edge.iter().all(|&connected| !connected))
+    }
+
+    #[quickcheck]
+    fn border_connections_cover_the_edges(grid: Grid<Tile<Square>>) -> bool {
+        let connections = grid.border_connections();
+        connections.get(Square::Up).map(Vec::len) == Some(grid.columns())
+            && connections.get(Square::Down).map(Vec::len) == Some(grid.columns())
+            && connections.get(Square::Left).map(Vec::len) == Some(grid.rows())
+            && connections.get(Square::Right).map(Vec::len) == Some(grid.rows())
+    }
+
+    #[test]
+    fn border_connections_exit_the_edges() {
+        let grid = parse_level("╹━\n┏┫", unicode_to_tile).unwrap();
+        let connections = grid.border_connections();
+        assert_eq!(connections.get(Square::Up), Some(&vec![true, false]));
+        assert_eq!(connections.get(Square::Right), Some(&vec![true, false]));
+        assert_eq!(connections.get(Square::Down), Some(&vec![true, true]));
+        assert_eq!(connections.get(Square::Left), Some(&vec![false, false]));
+    }
+
+    #[test]
+    fn connections_wrap_around_a_torus() {
+        let grid = parse_level("┛╺\n╻ ", unicode_to_tile).unwrap();
+        let torus = grid.clone().into_torus();
+        assert_eq!(
+            torus.neighbor_index(Coordinate::new(0, 0), Square::Left),
+            Coordinate::new(0, 1)
+        );
+        assert_eq!(
+            torus.neighbor_index(Coordinate::new(0, 0), Square::Up),
+            Coordinate::new(1, 0)
+        );
+        assert!(!grid.is_solved());
+        assert!(torus.is_solved());
+    }
+
+    #[quickcheck]
+    fn single_loop_implies_solved(grid: Grid<Tile<Square>>) -> bool {
+        !grid.is_solved_single_loop() || grid.is_solved()
+    }
+
+    #[test]
+    fn disjoint_loops_are_no_single_loop() {
+        let two_loops = parse_level("┏┓ ┏┓\n┗┛ ┗┛", unicode_to_tile).unwrap();
+        assert!(two_loops.is_solved());
+        assert!(!two_loops.is_solved_single_loop());
+        let one_loop = parse_level("┏┳┓\n┗┻┛", unicode_to_tile).unwrap();
+        assert!(one_loop.is_solved_single_loop());
+    }
+
+    #[test]
+    fn sources_must_share_a_component() {
+        let connected = parse_level("╺━╸┏┓\n   ┗┛", unicode_to_tile).unwrap();
+        assert!(connected.is_solved_with(&AllSourcesConnected));
+        assert!(!connected.is_solved_with(&SingleLoop));
+        let disjoint = parse_level("╺╸┏┓\n╺╸┗┛", unicode_to_tile).unwrap();
+        assert!(disjoint.is_solved_with(&AllEdgesMatched));
+        assert!(!disjoint.is_solved_with(&AllSourcesConnected));
+    }
+
+    #[quickcheck]
+    fn win_rules_agree_with_their_conditions(grid: Grid<Tile<Square>>) -> bool {
+        WinRule::MatchingConnections.is_met_by(&grid) == grid.is_solved()
+            && WinRule::SingleLoop.is_met_by(&grid) == grid.is_solved_single_loop()
+            && (!WinRule::AllSourcesConnected.is_met_by(&grid) || grid.is_solved())
+    }
+
+    #[quickcheck]
+    fn to_cells_maps_coordinates_to_their_tiles(grid: Grid<Tile<Square>>) -> bool {
+        let cells = grid.to_cells();
+        cells.len() == grid.size()
+            && cells
+                .into_iter()
+                .all(|(c, tile)| grid.get(c) == Some(&tile))
+    }
+
+    #[quickcheck]
+    fn from_cells_is_inverse_of_to_cells(grid: Grid<Tile<Square>>) -> bool {
+        Grid::from_cells(grid.dimensions(), grid.to_cells()) == Ok(grid)
+    }
+
+    #[quickcheck]
+    fn from_cells_without_cells_is_empty(dimensions: Coordinate<Max<20>>) -> bool {
+        let dimensions = dimensions.map(Max::to_usize);
+        Grid::from_cells(dimensions, vec![])
+            == Ok(Grid::filled_with(dimensions, Tile::NO_CONNECTIONS))
+    }
+
+    #[quickcheck]
+    fn from_cells_rejects_cells_out_of_bounds(
+        grid: Grid<Tile<Square>>,
+        tile: Tile<Square>,
+    ) -> bool {
+        let outside = grid.dimensions().map(|x| x as isize);
+        Grid::from_cells(grid.dimensions(), vec![(outside, tile)])
+            == Err(AccessError::IndexOutOfBounds)
+    }
+
+    #[quickcheck]
+    fn similarity_to_scrambled_self_is_one(grid: Grid<Tile<Square>>, seed: u64) -> bool {
+        grid.similarity(&grid.clone().scramble(seed)) == 1.0
+    }
+
+    #[quickcheck]
+    fn similarity_is_symmetric_fraction(
+        first: Grid<Tile<Square>>,
+        second: Grid<Tile<Square>>,
+    ) -> bool {
+        let similarity = first.similarity(&second);
+        similarity == second.similarity(&first) && (0.0..=1.0).contains(&similarity)
+    }
+
+    #[quickcheck]
+    fn to_ascii_is_7_bit(grid: Grid<Tile<Square>>) -> bool {
+        grid.to_ascii().is_ascii()
+    }
+
+    #[quickcheck]
+    fn to_ascii_preserves_layout(grid: Grid<Tile<Square>>) -> bool {
+        grid.to_ascii()
+            .lines()
+            .map(|line| line.chars().count())
+            .eq(grid.to_string().lines().map(|line| line.chars().count()))
+    }
+
     // all non-mutating methods preserve the invariants by virtue of calling the invariant-preserving constructors for initialization
 }
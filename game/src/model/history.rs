@@ -0,0 +1,120 @@
+//! Undo and redo of state transitions
+//!
+//! [`History`] stores complete states instead of inverse operations, so every transition is revertible,
+//! e.g. rotations, shuffles and editor edits, without knowing how it was made
+
+use std::collections::VecDeque;
+
+/// Bounded undo stack together with its redo stack
+///
+/// recording a new state forgets the redo stack, beyond the capacity the oldest states are forgotten
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct History<A> {
+    /// previous states, most recent last
+    undo: VecDeque<A>,
+    /// undone states, most recently undone last
+    redo: Vec<A>,
+    capacity: usize,
+}
+
+impl<A> History<A> {
+    /// history remembering up to `capacity` previous states
+    pub fn new(capacity: usize) -> Self {
+        History {
+            undo: VecDeque::with_capacity(capacity),
+            redo: vec![],
+            capacity,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Remembers the state before a transition
+    pub fn record(&mut self, previous: A) {
+        self.redo.clear();
+        if self.capacity == 0 {
+            return;
+        }
+        if self.undo.len() == self.capacity {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(previous);
+    }
+
+    /// Returns the state before the current one, which becomes redoable
+    pub fn undo(&mut self, current: A) -> Option<A> {
+        let previous = self.undo.pop_back()?;
+        self.redo.push(current);
+        Some(previous)
+    }
+
+    /// Returns the most recently undone state, the current one becomes undoable again
+    pub fn redo(&mut self, current: A) -> Option<A> {
+        let next = self.redo.pop()?;
+        self.undo.push_back(current);
+        Some(next)
+    }
+
+    /// forgets all states
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::History;
+
+    #[quickcheck]
+    fn undo_then_redo_is_identity(states: Vec<u8>, current: u8) -> bool {
+        let mut history = History::new(states.len());
+        states.iter().for_each(|&state| history.record(state));
+        let before = history.clone();
+        match history.undo(current) {
+            Some(previous) => history.redo(previous) == Some(current) && history == before,
+            None => states.is_empty(),
+        }
+    }
+
+    #[quickcheck]
+    fn history_is_bounded(states: Vec<u8>, capacity: u8) -> bool {
+        let capacity = capacity as usize % 8;
+        let mut history = History::new(capacity);
+        states.iter().for_each(|&state| history.record(state));
+        let mut undone = vec![];
+        let mut current = 0;
+        while let Some(previous) = history.undo(current) {
+            undone.push(previous);
+            current = previous;
+        }
+        undone.len() == states.len().min(capacity)
+            && undone.iter().eq(states.iter().rev().take(capacity))
+    }
+
+    #[test]
+    fn recording_forgets_undone_states() {
+        let mut history = History::new(10);
+        history.record(1);
+        history.record(2);
+        assert_eq!(history.undo(3), Some(2));
+        assert!(history.can_redo());
+        history.record(2);
+        assert!(!history.can_redo());
+        assert_eq!(history.redo(4), None);
+        assert_eq!(history.undo(4), Some(2));
+        assert_eq!(history.undo(2), Some(1));
+        assert_eq!(history.undo(1), None);
+    }
+}
@@ -0,0 +1,169 @@
+//! Levels with locked tiles
+//!
+//! a locked tile is fixed in its rotation, which allows hand-crafted puzzles with pre-placed anchors.
+//! A level with locked tiles is a `Grid<Cell<A>>`, the solver starts with the locked tiles already collapsed
+
+use std::fmt::Debug;
+
+use crate::core::finite::Finite;
+
+use super::{
+    accesserror::AccessError,
+    coordinate::Coordinate,
+    gameboard::{AllSourcesConnected, GameBoard, WinCondition},
+    grid::Grid,
+    overlay::Overlay,
+    tile::{Square, Tile, Topology},
+};
+
+/// Tile of a level together with its lock flag
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Cell<A = Square> {
+    pub tile: Tile<A>,
+    /// locked tiles cannot be rotated
+    pub locked: bool,
+}
+
+impl<A> Cell<A> {
+    pub const fn new(tile: Tile<A>) -> Self {
+        Cell {
+            tile,
+            locked: false,
+        }
+    }
+
+    pub const fn locked(tile: Tile<A>) -> Self {
+        Cell { tile, locked: true }
+    }
+}
+
+impl<A: Debug + Finite> Debug for Cell<A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Cell")
+            .field("tile", &self.tile)
+            .field("locked", &self.locked)
+            .finish()
+    }
+}
+
+impl<A: Topology> Grid<Tile<A>> {
+    /// Level whose tiles annotated in the overlay are locked
+    pub fn with_locks<B>(&self, locks: &Overlay<B>) -> Grid<Cell<A>> {
+        Grid::init(self.dimensions(), |index| Cell {
+            tile: self[index],
+            locked: locks.contains(index),
+        })
+    }
+}
+
+impl<A: Topology> Grid<Cell<A>> {
+    /// level without the lock flags
+    pub fn tiles(&self) -> Grid<Tile<A>> {
+        self.map(|cell| cell.tile)
+    }
+
+    /// locked cells of the level
+    pub fn locks(&self) -> Overlay<()> {
+        Overlay::from_annotations(
+            self.dimensions(),
+            self.coordinates()
+                .filter(|&index| self[index].locked)
+                .map(|index| (index, ())),
+        )
+        .expect("coordinates of the grid lie within its dimensions")
+    }
+
+    /// Locks an unlocked tile and unlocks a locked one
+    pub fn toggle_lock(&self, index: Coordinate<isize>) -> Result<Self, AccessError> {
+        self.adjust_at(index, |cell| Cell {
+            locked: !cell.locked,
+            ..cell
+        })
+    }
+}
+
+impl<A: Topology> GameBoard for Grid<Cell<A>> {
+    type Index = Coordinate<isize>;
+
+    type Tile = Cell<A>;
+
+    /// fails with [`AccessError::TileLocked`] for locked tiles
+    fn rotate_clockwise(&self, index: Self::Index) -> Result<Self, AccessError> {
+        match self.get(index) {
+            Some(cell) if cell.locked => Err(AccessError::TileLocked),
+            _ => self.adjust_at(index, |cell| Cell {
+                tile: cell.tile.rotated_clockwise(1),
+                ..cell
+            }),
+        }
+    }
+
+    /// fails with [`AccessError::TileLocked`] for locked tiles
+    fn rotate_counterclockwise(&self, index: Self::Index) -> Result<Self, AccessError> {
+        match self.get(index) {
+            Some(cell) if cell.locked => Err(AccessError::TileLocked),
+            _ => self.adjust_at(index, |cell| Cell {
+                tile: cell.tile.rotated_counterclockwise(1),
+                ..cell
+            }),
+        }
+    }
+
+    fn is_solved(&self) -> bool {
+        self.tiles().is_solved()
+    }
+
+    fn is_solved_single_loop(&self) -> bool {
+        self.tiles().is_solved_single_loop()
+    }
+
+    fn to_cells(&self) -> Vec<(Self::Index, Self::Tile)> {
+        self.coordinates()
+            .map(|index| (index, self[index]))
+            .collect()
+    }
+}
+
+impl<A: Topology> WinCondition<Grid<Cell<A>>> for AllSourcesConnected {
+    fn is_met_by(&self, grid: &Grid<Cell<A>>) -> bool {
+        self.is_met_by(&grid.tiles())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::model::{
+        accesserror::AccessError,
+        coordinate::Coordinate,
+        gameboard::GameBoard,
+        grid::Grid,
+        overlay::Overlay,
+        tile::{Square, Tile},
+    };
+
+    #[quickcheck]
+    fn unlocked_levels_rotate_like_their_tiles(grid: Grid<Tile<Square>>) -> bool {
+        let cells = grid.with_locks(&Overlay::<()>::for_grid(&grid));
+        cells.tiles() == grid
+            && cells.locks().is_empty()
+            && grid.coordinates().all(|index| {
+                cells.rotate_clockwise(index).map(|rotated| rotated.tiles())
+                    == grid.rotate_clockwise(index)
+            })
+    }
+
+    #[test]
+    fn locked_tiles_cannot_be_rotated() {
+        let grid = "┏┓\n┗┛".parse::<Grid<Tile<Square>>>().unwrap();
+        let anchor = Coordinate::new(0, 1);
+        let cells = grid
+            .with_locks(&Overlay::<()>::for_grid(&grid))
+            .toggle_lock(anchor)
+            .unwrap();
+        assert!(cells.locks().contains(anchor));
+        assert_eq!(cells.rotate_clockwise(anchor), Err(AccessError::TileLocked));
+        assert!(cells.rotate_clockwise(Coordinate::new(0, 0)).is_ok());
+        assert!(cells.toggle_lock(anchor).unwrap().locks().is_empty());
+    }
+}
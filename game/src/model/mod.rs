@@ -1,7 +1,18 @@
 pub mod accesserror;
+pub mod cachedboard;
+pub mod channel;
+pub mod collab;
+#[cfg(feature = "sat")]
 pub mod cnf;
+pub mod connectivity;
+pub mod constraint;
 pub mod coordinate;
 pub mod gameboard;
 pub mod grid;
+pub mod history;
+pub mod lock;
+pub mod overlay;
 pub mod parser;
+pub mod shape;
+pub mod symmetry;
 pub mod tile;
@@ -0,0 +1,207 @@
+//! Sparse annotations of the cells of a level
+//!
+//! hints, error highlights, locked flags or designer notes concern few cells, so an [`Overlay`] stores them
+//! apart from the [`Grid`] of tiles and validates every coordinate against its dimensions
+
+use std::collections::HashMap;
+
+use super::{accesserror::AccessError, coordinate::Coordinate, grid::Grid};
+
+/// Sparse map from the cells of a grid to annotations
+///
+/// # Invariant
+///
+/// every annotated coordinate lies within the dimensions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Overlay<A> {
+    dimensions: Coordinate<usize>,
+    annotations: HashMap<Coordinate<isize>, A>,
+}
+
+impl<A> Overlay<A> {
+    /// Overlay without annotations for a grid of the dimensions
+    pub fn new(dimensions: Coordinate<usize>) -> Self {
+        Overlay {
+            dimensions,
+            annotations: HashMap::new(),
+        }
+    }
+
+    /// Overlay without annotations matching the grid
+    pub fn for_grid<B>(grid: &Grid<B>) -> Self {
+        Overlay::new(grid.dimensions())
+    }
+
+    /// Overlay annotating the given cells
+    ///
+    /// fails if a coordinate lies outside the dimensions, later annotations replace earlier ones of the same cell
+    pub fn from_annotations<I>(
+        dimensions: Coordinate<usize>,
+        annotations: I,
+    ) -> Result<Self, AccessError>
+    where
+        I: IntoIterator<Item = (Coordinate<isize>, A)>,
+    {
+        let mut overlay = Overlay::new(dimensions);
+        for (coordinate, annotation) in annotations {
+            overlay.insert(coordinate, annotation)?;
+        }
+        Ok(overlay)
+    }
+
+    pub fn dimensions(&self) -> Coordinate<usize> {
+        self.dimensions
+    }
+
+    /// Checks if the coordinate lies within the dimensions
+    pub fn in_bounds(&self, coordinate: Coordinate<isize>) -> bool {
+        (0..self.dimensions.row as isize).contains(&coordinate.row)
+            && (0..self.dimensions.column as isize).contains(&coordinate.column)
+    }
+
+    /// Annotates the cell and returns its previous annotation
+    pub fn insert(
+        &mut self,
+        coordinate: Coordinate<isize>,
+        annotation: A,
+    ) -> Result<Option<A>, AccessError> {
+        if self.in_bounds(coordinate) {
+            Ok(self.annotations.insert(coordinate, annotation))
+        } else {
+            Err(AccessError::IndexOutOfBounds)
+        }
+    }
+
+    /// Removes the annotation of the cell and returns it
+    pub fn remove(&mut self, coordinate: Coordinate<isize>) -> Option<A> {
+        self.annotations.remove(&coordinate)
+    }
+
+    pub fn get(&self, coordinate: Coordinate<isize>) -> Option<&A> {
+        self.annotations.get(&coordinate)
+    }
+
+    pub fn get_mut(&mut self, coordinate: Coordinate<isize>) -> Option<&mut A> {
+        self.annotations.get_mut(&coordinate)
+    }
+
+    pub fn contains(&self, coordinate: Coordinate<isize>) -> bool {
+        self.annotations.contains_key(&coordinate)
+    }
+
+    /// number of annotated cells
+    pub fn len(&self) -> usize {
+        self.annotations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.annotations.is_empty()
+    }
+
+    /// Removes all annotations
+    pub fn clear(&mut self) {
+        self.annotations.clear()
+    }
+
+    /// Keeps only the annotations satisfying the predicate
+    pub fn retain<F: FnMut(Coordinate<isize>, &mut A) -> bool>(&mut self, mut predicate: F) {
+        self.annotations
+            .retain(|&coordinate, annotation| predicate(coordinate, annotation))
+    }
+
+    /// Adapts the overlay to a resized grid, annotations outside the new dimensions are dropped
+    pub fn resize(&mut self, dimensions: Coordinate<usize>) {
+        self.dimensions = dimensions;
+        let (rows, columns) = (dimensions.row as isize, dimensions.column as isize);
+        self.annotations
+            .retain(|c, _| c.row < rows && c.column < columns)
+    }
+
+    /// Returns an iterator over the annotated cells in row-major order
+    pub fn iter(&self) -> impl Iterator<Item = (Coordinate<isize>, &A)> {
+        let mut annotations = self
+            .annotations
+            .iter()
+            .map(|(&coordinate, annotation)| (coordinate, annotation))
+            .collect::<Vec<_>>();
+        annotations.sort_by_key(|&(coordinate, _)| coordinate);
+        annotations.into_iter()
+    }
+
+    /// Returns an iterator over the annotated coordinates in row-major order
+    pub fn coordinates(&self) -> impl Iterator<Item = Coordinate<isize>> + '_ {
+        self.iter().map(|(coordinate, _)| coordinate)
+    }
+}
+
+impl<A> Default for Overlay<A> {
+    fn default() -> Self {
+        Overlay::new(Coordinate::default())
+    }
+}
+
+impl<A: Clone> Overlay<A> {
+    /// Grid of the annotations, `None` for cells without annotation
+    pub fn to_grid(&self) -> Grid<Option<A>> {
+        Grid::init(self.dimensions, |coordinate| self.get(coordinate).cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::core::interval::{Interval, Max};
+    use crate::model::{accesserror::AccessError, coordinate::Coordinate, grid::Grid};
+
+    use super::Overlay;
+
+    #[quickcheck]
+    fn annotations_stay_within_bounds(
+        dimension: Coordinate<Max<10>>,
+        row: isize,
+        column: isize,
+    ) -> bool {
+        let dimensions = dimension.map(Max::to_usize);
+        let mut overlay = Overlay::new(dimensions);
+        let coordinate = Coordinate::new(row % 12, column % 12);
+        let inserted = overlay.insert(coordinate, ());
+        let inside = Grid::filled_with(dimensions, ()).get(coordinate).is_some();
+        match inserted {
+            Ok(None) => inside && overlay.contains(coordinate),
+            Err(AccessError::IndexOutOfBounds) => !inside && overlay.is_empty(),
+            Ok(Some(())) | Err(AccessError::TileLocked) => false,
+        }
+    }
+
+    #[quickcheck]
+    fn resize_drops_annotations_outside(dimension: Coordinate<Interval<1, 10>>) -> bool {
+        let dimensions = dimension.map(Interval::to_usize);
+        let grid = Grid::filled_with(dimensions, ());
+        let mut overlay =
+            Overlay::from_annotations(dimensions, grid.coordinates().map(|c| (c, c))).unwrap();
+        let smaller = Coordinate::new(dimensions.row - 1, dimensions.column);
+        overlay.resize(smaller);
+        overlay.len() == smaller.row * smaller.column
+            && overlay
+                .iter()
+                .all(|(c, &annotation)| c == annotation && overlay.in_bounds(c))
+    }
+
+    #[test]
+    fn annotations_are_listed_in_row_major_order() {
+        let overlay = Overlay::from_annotations(
+            Coordinate::new(2, 2),
+            [
+                (Coordinate::new(1, 0), "c"),
+                (Coordinate::new(0, 1), "b"),
+                (Coordinate::new(0, 0), "a"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(overlay.iter().map(|(_, &a)| a).collect::<String>(), "abc");
+        assert_eq!(
+            overlay.to_grid().as_slice(),
+            &[Some("a"), Some("b"), Some("c"), None]
+        );
+    }
+}
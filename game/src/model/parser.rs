@@ -1,17 +1,19 @@
-use crate::{enumset, tile};
+use std::{
+    io::{BufRead, Write},
+    str::FromStr,
+};
 
 use super::{
+    constraint::Constraint,
     coordinate::Coordinate,
     grid::Grid,
-    tile::{
-        Square::{self, Down, Left, Right, Up},
-        Tile,
-    },
+    tile::{from_ascii_glyph, from_glyph, Square, Tile},
 };
 
-/// char to tile mapping
+/// char to tile mapping based on the 7-bit table, see [`tile::ASCII_GLYPHS`](super::tile::ASCII_GLYPHS)
 ///
-/// assign a character to represent each equivalence class under rotational symmetry
+/// assign a character to represent each equivalence class under rotational symmetry, the other characters of the
+/// table denote the other orientations
 ///
 /// | Connections | Character | Unicode                 |
 /// |:------------|:----------|:------------------------|
@@ -22,37 +24,14 @@ use super::{
 /// | 3           | 'T'       | `[┣]`/`[┻]`/`[┫]`/`[┳]` |
 /// | 4           | '+'       | `[╋]`                   |
 pub fn ascii_to_tile(tile_character: char) -> Result<Tile<Square>, String> {
-    Ok(match tile_character {
-        ' ' => Tile::NO_CONNECTIONS,
-        '-' => tile!(Up),
-        'I' => tile!(Up, Down),
-        'L' => tile!(Up, Right),
-        'T' => tile!(Up, Right, Down),
-        '+' => Tile::ALL_CONNECTIONS,
-        c => Err(format!("parsing error: unknown character '{c}'"))?,
-    })
+    from_ascii_glyph(tile_character)
+        .ok_or_else(|| format!("parsing error: unknown character '{tile_character}'"))
 }
 
+/// char to tile mapping based on the glyph table, see [`tile::GLYPHS`](super::tile::GLYPHS)
 pub fn unicode_to_tile(tile_character: char) -> Result<Tile<Square>, String> {
-    Ok(match tile_character {
-        ' ' => Tile::NO_CONNECTIONS,
-        '╹' => tile!(Up),
-        '╺' => tile!(Right),
-        '┗' => tile!(Up, Right),
-        '╻' => tile!(Down),
-        '┃' => tile!(Up, Down),
-        '┏' => tile!(Right, Down),
-        '┣' => tile!(Up, Right, Down),
-        '╸' => tile!(Left),
-        '┛' => tile!(Up, Left),
-        '━' => tile!(Right, Left),
-        '┻' => tile!(Up, Right, Left),
-        '┓' => tile!(Down, Left),
-        '┫' => tile!(Up, Down, Left),
-        '┳' => tile!(Right, Down, Left),
-        '╋' => Tile::ALL_CONNECTIONS,
-        c => Err(format!("parsing error: unknown character '{c}'"))?,
-    })
+    from_glyph(tile_character)
+        .ok_or_else(|| format!("parsing error: unknown character '{tile_character}'"))
 }
 
 /// parses level from string representation
@@ -82,6 +61,42 @@ where
         .map(|v| Grid::new(Coordinate::new(rows, columns), v))
 }
 
+/// canonical text format of levels: the output of [`Display`](std::fmt::Display) for both the unicode glyphs
+/// and the ASCII fallback `{:#}`, see [`GLYPHS`](super::tile::GLYPHS) and [`ASCII_GLYPHS`](super::tile::ASCII_GLYPHS)
+///
+/// errors report the position of the offending character, rows and columns start at 1
+impl FromStr for Grid<Tile<Square>> {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines = s.lines().collect::<Vec<_>>();
+        let columns = lines.first().map_or(0, |line| line.chars().count());
+        let mut tiles = Vec::with_capacity(lines.len() * columns);
+        for (row, line) in lines.iter().enumerate() {
+            let length = line.chars().count();
+            if length != columns {
+                return Err(format!(
+                    "parsing error at row {}: expected {columns} tiles, found {length}",
+                    row + 1
+                ));
+            }
+            for (column, character) in line.chars().enumerate() {
+                let tile = from_glyph(character)
+                    .or_else(|| from_ascii_glyph(character))
+                    .ok_or_else(|| {
+                        format!(
+                            "parsing error at row {}, column {}: unknown character '{character}'",
+                            row + 1,
+                            column + 1
+                        )
+                    })?;
+                tiles.push(tile);
+            }
+        }
+        Ok(Grid::new(Coordinate::new(lines.len(), columns), tiles))
+    }
+}
+
 /// current implementation relies on internal vector layout in grid
 pub fn serialize_level<A: Clone, F: Fn(A) -> char>(grid: Grid<A>, converter: F) -> String {
     grid.elements()
@@ -94,6 +109,125 @@ pub fn serialize_level<A: Clone, F: Fn(A) -> char>(grid: Grid<A>, converter: F)
         .join("\n")
 }
 
+/// marks a metadata line holding a [`Constraint`] in front of the level data
+const CONSTRAINT_PREFIX: &str = "# ";
+
+/// parses level together with the designer constraints stored in its metadata
+///
+/// constraints precede the level data, one per line and prefixed with `# `, e.g.
+///
+/// ```text
+/// # cell 0 1 ╋
+/// # row 1 not ┃
+/// ┏┳┓
+/// ┗┻┛
+/// ```
+pub fn parse_annotated_level<A, F>(
+    leveldata: &str,
+    converter: F,
+) -> Result<(Grid<A>, Vec<Constraint>), String>
+where
+    F: Fn(char) -> Result<A, String>,
+{
+    let (metadata, level): (Vec<_>, Vec<_>) = leveldata
+        .lines()
+        .partition(|line| line.starts_with(CONSTRAINT_PREFIX));
+    let constraints = metadata
+        .into_iter()
+        .map(|line| line[CONSTRAINT_PREFIX.len()..].parse())
+        .collect::<Result<_, _>>()?;
+    parse_level(&level.join("\n"), converter).map(|grid| (grid, constraints))
+}
+
+/// strips the line breaks at the end of a level file
+///
+/// trailing spaces are kept, they are the glyph of the empty tile
+pub fn trim_trailing_newlines(leveldata: &str) -> &str {
+    leveldata.trim_end_matches(['\n', '\r'])
+}
+
+/// inverse of [`parse_annotated_level`]
+pub fn serialize_annotated_level<A: Clone, F: Fn(A) -> char>(
+    grid: Grid<A>,
+    constraints: &[Constraint],
+    converter: F,
+) -> String {
+    constraints
+        .iter()
+        .map(|constraint| format!("{CONSTRAINT_PREFIX}{constraint}\n"))
+        .collect::<String>()
+        + &serialize_level(grid, converter)
+}
+
+/// streaming variant of [`parse_annotated_level`] for huge levels, holds a single line of text at a time
+///
+/// empty lines are skipped, e.g. the trailing newline of a file
+pub fn read_annotated_level<R, A, F>(
+    reader: R,
+    converter: F,
+) -> Result<(Grid<A>, Vec<Constraint>), String>
+where
+    R: BufRead,
+    F: Fn(char) -> Result<A, String>,
+{
+    let mut constraints = vec![];
+    let mut tiles = vec![];
+    let mut rows = 0;
+    let mut columns = None;
+    for line in reader.lines() {
+        let line = line.map_err(|e| format!("reading error: {e}"))?;
+        if let Some(constraint) = line.strip_prefix(CONSTRAINT_PREFIX) {
+            constraints.push(constraint.parse()?);
+            continue;
+        }
+        if line.is_empty() {
+            continue;
+        }
+        let before = tiles.len();
+        for character in line.chars() {
+            tiles.push(converter(character)?);
+        }
+        let length = tiles.len() - before;
+        if *columns.get_or_insert(length) != length {
+            return Err(format!(
+                "All rows must have same length: row {} has {length} tiles",
+                rows + 1
+            ));
+        }
+        rows += 1;
+    }
+    let dimensions = Coordinate::new(rows, columns.unwrap_or(0));
+    Ok((Grid::new(dimensions, tiles), constraints))
+}
+
+/// streaming variant of [`serialize_annotated_level`] for huge levels, writes a single row at a time
+pub fn write_annotated_level<W, A, F>(
+    grid: &Grid<A>,
+    constraints: &[Constraint],
+    mut writer: W,
+    converter: F,
+) -> Result<(), String>
+where
+    W: Write,
+    A: Clone,
+    F: Fn(A) -> char,
+{
+    let mut write = |line: &str| {
+        writer
+            .write_all(line.as_bytes())
+            .map_err(|e| format!("writing error: {e}"))
+    };
+    for constraint in constraints {
+        write(&format!("{CONSTRAINT_PREFIX}{constraint}\n"))?;
+    }
+    for (index, row) in grid.as_slice().chunks(grid.columns().max(1)).enumerate() {
+        let separator = if index == 0 { "" } else { "\n" };
+        let row = row.iter().cloned().map(&converter).collect::<String>();
+        write(&format!("{separator}{row}"))?;
+    }
+    Ok(())
+}
+
 /// first levels of android game infinity loop
 #[rustfmt::skip]
 pub const TEST_LEVELS: [&str; 30] = [
@@ -136,10 +270,20 @@ mod tests {
     use quickcheck::TestResult;
 
     use crate::model::{
+        constraint::Constraint,
         grid::Grid,
-        parser::{parse_level, unicode_to_tile},
-        tile::{Square, Tile},
+        parser::{
+            ascii_to_tile, parse_annotated_level, parse_level, read_annotated_level,
+            serialize_annotated_level, trim_trailing_newlines, unicode_to_tile,
+            write_annotated_level, TEST_LEVELS,
+        },
+        tile::{
+            glyph, Square,
+            Square::{Down, Right, Up},
+            Tile,
+        },
     };
+    use crate::{enumset, tile};
 
     #[quickcheck]
     fn tile_display_then_unicode_to_tile_is_identity(tile: Tile<Square>) -> bool {
@@ -162,4 +306,89 @@ mod tests {
             TestResult::from_bool(parse_level(&grid.to_string(), unicode_to_tile) == Ok(grid))
         }
     }
+
+    #[quickcheck]
+    fn grid_display_then_from_str_is_identity(grid: Grid<Tile<Square>>) -> TestResult {
+        if grid.rows() == 0 || grid.columns() == 0 {
+            // see grid_display_then_parse_level_is_identity
+            TestResult::discard()
+        } else {
+            TestResult::from_bool(
+                grid.to_string().parse() == Ok(grid.clone()) && grid.to_ascii().parse() == Ok(grid),
+            )
+        }
+    }
+
+    #[test]
+    fn from_str_reports_error_positions() {
+        assert_eq!(
+            "┏┓\n┗x".parse::<Grid<Tile<Square>>>(),
+            Err("parsing error at row 2, column 2: unknown character 'x'".into())
+        );
+        assert_eq!(
+            "r7\nL-J".parse::<Grid<Tile<Square>>>(),
+            Err("parsing error at row 2: expected 2 tiles, found 3".into())
+        );
+        assert_eq!("".parse(), Ok(Grid::<Tile<Square>>::EMPTY));
+    }
+
+    #[quickcheck]
+    fn serialize_then_parse_annotated_level_is_identity(
+        grid: Grid<Tile<Square>>,
+        constraints: Vec<Constraint>,
+    ) -> TestResult {
+        if grid.rows() == 0 || grid.columns() == 0 {
+            // see grid_display_then_parse_level_is_identity
+            TestResult::discard()
+        } else {
+            let serialized = serialize_annotated_level(grid.clone(), &constraints, glyph);
+            TestResult::from_bool(
+                parse_annotated_level(&serialized, unicode_to_tile) == Ok((grid, constraints)),
+            )
+        }
+    }
+
+    #[quickcheck]
+    fn streaming_matches_the_textual_format(
+        grid: Grid<Tile<Square>>,
+        constraints: Vec<Constraint>,
+    ) -> TestResult {
+        if grid.rows() == 0 || grid.columns() == 0 {
+            // see grid_display_then_parse_level_is_identity
+            return TestResult::discard();
+        }
+        let serialized = serialize_annotated_level(grid.clone(), &constraints, glyph);
+        let mut written = vec![];
+        TestResult::from_bool(
+            write_annotated_level(&grid, &constraints, &mut written, glyph).is_ok()
+                && written == serialized.as_bytes()
+                && read_annotated_level(written.as_slice(), unicode_to_tile)
+                    == Ok((grid, constraints)),
+        )
+    }
+
+    #[quickcheck]
+    fn trailing_empty_tiles_survive_trimming(grid: Grid<Tile<Square>>) -> TestResult {
+        if grid.rows() == 0 || grid.columns() == 0 {
+            // see grid_display_then_parse_level_is_identity
+            return TestResult::discard();
+        }
+        let file = format!(
+            "{}\r\n\n",
+            serialize_annotated_level(grid.clone(), &[], glyph)
+        );
+        TestResult::from_bool(
+            parse_annotated_level(trim_trailing_newlines(&file), unicode_to_tile)
+                == Ok((grid, vec![])),
+        )
+    }
+
+    #[test]
+    fn ascii_levels_parse_the_same_through_every_entry_point() {
+        assert_eq!(ascii_to_tile('-'), Ok(tile!(Up)));
+        assert_eq!(ascii_to_tile('T'), Ok(tile!(Up, Right, Down)));
+        for level in TEST_LEVELS {
+            assert_eq!(parse_level(level, ascii_to_tile), level.parse());
+        }
+    }
 }
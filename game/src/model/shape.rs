@@ -0,0 +1,112 @@
+//! Non-rectangular levels
+//!
+//! a shaped level is a `Grid<Option<Tile<A>>>`, whose cells hold either a tile or a hole marked by [`None`].
+//! Holes behave like tiles without connections that never turn: just as at the border of the grid, no connection
+//! may lead into a hole. Filling the holes with empty tiles therefore yields a rectangular level with the same
+//! solutions, which is how the solver and the generators treat shaped levels
+
+use super::{
+    accesserror::AccessError,
+    coordinate::Coordinate,
+    gameboard::{AllSourcesConnected, GameBoard, WinCondition},
+    grid::Grid,
+    tile::{Tile, Topology},
+};
+
+impl<A: Topology> Grid<Option<Tile<A>>> {
+    /// rectangular level with tiles without connections in place of the holes
+    pub fn fill_holes(&self) -> Grid<Tile<A>> {
+        self.map(|cell| cell.unwrap_or(Tile::NO_CONNECTIONS))
+    }
+
+    /// mask of the shape, `true` for the cells holding a tile
+    pub fn mask(&self) -> Grid<bool> {
+        self.map(|cell| cell.is_some())
+    }
+}
+
+impl<A: Topology> Grid<Tile<A>> {
+    /// Shaped level of the tiles within the mask
+    ///
+    /// cells that are `false` in the mask or lie outside of it become holes
+    pub fn cut(&self, mask: &Grid<bool>) -> Grid<Option<Tile<A>>> {
+        Grid::init(self.dimensions(), |index| {
+            mask.get(index)
+                .copied()
+                .unwrap_or(false)
+                .then(|| self[index])
+        })
+    }
+}
+
+impl<A: Topology> GameBoard for Grid<Option<Tile<A>>> {
+    type Index = Coordinate<isize>;
+
+    type Tile = Option<Tile<A>>;
+
+    /// holes stay as they are
+    fn rotate_clockwise(&self, index: Self::Index) -> Result<Self, AccessError> {
+        self.adjust_at(index, |cell| cell.map(|tile| tile.rotated_clockwise(1)))
+    }
+
+    /// holes stay as they are
+    fn rotate_counterclockwise(&self, index: Self::Index) -> Result<Self, AccessError> {
+        self.adjust_at(index, |cell| {
+            cell.map(|tile| tile.rotated_counterclockwise(1))
+        })
+    }
+
+    fn is_solved(&self) -> bool {
+        self.fill_holes().is_solved()
+    }
+
+    fn is_solved_single_loop(&self) -> bool {
+        self.fill_holes().is_solved_single_loop()
+    }
+
+    fn to_cells(&self) -> Vec<(Self::Index, Self::Tile)> {
+        self.coordinates()
+            .map(|index| (index, self[index]))
+            .collect()
+    }
+}
+
+impl<A: Topology> WinCondition<Grid<Option<Tile<A>>>> for AllSourcesConnected {
+    fn is_met_by(&self, grid: &Grid<Option<Tile<A>>>) -> bool {
+        self.is_met_by(&grid.fill_holes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::model::{
+        coordinate::Coordinate,
+        gameboard::{GameBoard, WinRule},
+        grid::Grid,
+        tile::{Square, Tile},
+    };
+
+    #[quickcheck]
+    fn cutting_the_full_mask_keeps_the_level(grid: Grid<Tile<Square>>) -> bool {
+        let shaped = grid.cut(&Grid::filled_with(grid.dimensions(), true));
+        shaped.fill_holes() == grid
+            && shaped.is_solved() == grid.is_solved()
+            && WinRule::ALL
+                .into_iter()
+                .all(|rule| rule.is_met_by(&shaped) == rule.is_met_by(&grid))
+    }
+
+    #[test]
+    fn connections_must_not_lead_into_holes() {
+        let grid = "╺╸\n  ".parse::<Grid<Tile<Square>>>().unwrap();
+        let mask = Grid::init(Coordinate::new(2, 2), |index| index.column == 0);
+        assert!(grid.is_solved());
+        assert!(!grid.cut(&mask).is_solved());
+        assert_eq!(grid.cut(&mask).mask(), mask);
+        assert_eq!(
+            grid.cut(&mask).rotate_clockwise(Coordinate::new(0, 1)),
+            Ok(grid.cut(&mask))
+        );
+    }
+}
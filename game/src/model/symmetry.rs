@@ -0,0 +1,135 @@
+//! Symmetries of levels and the hashes identifying levels
+//!
+//! levels are equal up to symmetry if they only differ in the rotation of their tiles or in turning or mirroring
+//! the whole level
+
+use std::collections::HashSet;
+
+use crate::core::{cardinality::Cardinality, finite::Finite, hash::fnv1a};
+
+use super::{
+    coordinate::Coordinate,
+    grid::Grid,
+    tile::{Square, Tile},
+};
+
+/// Rotates every tile into the first orientation of its rotation class
+///
+/// levels only differing in the rotation of their tiles share their canonical tiles
+pub fn canonical_tiles(grid: &Grid<Tile<Square>>) -> Grid<Tile<Square>> {
+    grid.map(|tile| tile.superimpose().next().unwrap_or(tile))
+}
+
+impl Grid<Tile<Square>> {
+    /// Canonical representative of the level up to symmetry
+    ///
+    /// levels are equivalent if they only differ in the rotation of their tiles or in turning or mirroring the whole
+    /// level, i.e. by an element of the dihedral group. Tiles are reduced to their [canonical tiles], as rotation
+    /// classes of square tiles are closed under mirroring, and the least of the 8 turned and mirrored arrangements
+    /// is chosen
+    pub fn canonical_form(&self) -> Self {
+        let tiles = canonical_tiles(self);
+        let (rows, columns) = (self.rows() as isize, self.columns() as isize);
+        let transposed = Coordinate::new(self.columns(), self.rows());
+        // source of every cell of an arrangement, the last 4 arrangements swap rows and columns
+        let source = |symmetry: usize, c: Coordinate<isize>| match symmetry {
+            0 => c,
+            1 => Coordinate::new(c.row, columns - 1 - c.column),
+            2 => Coordinate::new(rows - 1 - c.row, c.column),
+            3 => Coordinate::new(rows - 1 - c.row, columns - 1 - c.column),
+            4 => Coordinate::new(c.column, c.row),
+            5 => Coordinate::new(rows - 1 - c.column, c.row),
+            6 => Coordinate::new(c.column, columns - 1 - c.row),
+            _ => Coordinate::new(rows - 1 - c.column, columns - 1 - c.row),
+        };
+        (0..8)
+            .map(|symmetry| {
+                let dimensions = if symmetry < 4 {
+                    self.dimensions()
+                } else {
+                    transposed
+                };
+                Grid::init(dimensions, |c| tiles[source(symmetry, c)])
+            })
+            .min()
+            .unwrap_or(tiles)
+    }
+
+    /// Checks if the levels are equal up to symmetry, see [`Grid::canonical_form`]
+    pub fn is_equivalent(&self, other: &Self) -> bool {
+        self.canonical_form() == other.canonical_form()
+    }
+}
+
+/// Keeps the first of all levels equal up to symmetry in the given order, see [`Grid::canonical_form`]
+pub fn distinct_up_to_symmetry<I>(levels: I) -> Vec<Grid<Tile<Square>>>
+where
+    I: IntoIterator<Item = Grid<Tile<Square>>>,
+{
+    let mut seen = HashSet::new();
+    levels
+        .into_iter()
+        .filter(|level| seen.insert(level.canonical_form()))
+        .collect()
+}
+
+/// Stable 64 bit FNV-1a hash of the [canonical tiles](canonical_tiles) and dimensions of the level
+pub fn canonical_hash(grid: &Grid<Tile<Square>>) -> u64 {
+    content_checksum(&canonical_tiles(grid))
+}
+
+/// Stable 64 bit FNV-1a hash of the tiles in their current rotation and the dimensions of the level
+///
+/// a torus hashes an extra marker after its tiles, the checksums of other levels are unaffected
+pub fn content_checksum(grid: &Grid<Tile<Square>>) -> u64 {
+    let dimensions = [grid.rows() as u64, grid.columns() as u64];
+    let tiles = grid.as_slice().iter().map(|tile| tile.enum_to_index());
+    let torus = grid.is_torus().then_some(Tile::<Square>::CARDINALITY);
+    fnv1a(dimensions.into_iter().chain(tiles).chain(torus))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::model::{
+        coordinate::Coordinate,
+        grid::Grid,
+        tile::{Square, Tile},
+    };
+
+    use super::{canonical_hash, distinct_up_to_symmetry};
+
+    #[quickcheck]
+    fn canonical_hash_is_independent_of_rotation(grid: Grid<Tile<Square>>, seed: u64) -> bool {
+        canonical_hash(&grid) == canonical_hash(&grid.clone().scramble(seed))
+    }
+
+    #[quickcheck]
+    fn canonical_form_is_invariant_under_turning_and_mirroring(
+        grid: Grid<Tile<Square>>,
+        seed: u64,
+    ) -> bool {
+        let (rows, columns) = (grid.rows() as isize, grid.columns() as isize);
+        let turned = Grid::init(Coordinate::new(grid.columns(), grid.rows()), |c| {
+            grid[Coordinate::new(rows - 1 - c.column, c.row)]
+        });
+        let mirrored = Grid::init(grid.dimensions(), |c| {
+            grid[Coordinate::new(c.row, columns - 1 - c.column)]
+        });
+        let canonical = grid.canonical_form();
+        canonical == turned.scramble(seed).canonical_form()
+            && canonical == mirrored.canonical_form()
+            && canonical == canonical.canonical_form()
+            && canonical.is_equivalent(&grid)
+    }
+
+    #[test]
+    fn distinct_levels_stay_distinct() {
+        let corner = "┏╸\n╹ ".parse::<Grid<Tile<Square>>>().unwrap();
+        let mirrored = "╺┓\n ╹".parse::<Grid<Tile<Square>>>().unwrap();
+        let line = "━━\n  ".parse::<Grid<Tile<Square>>>().unwrap();
+        let distinct = distinct_up_to_symmetry([corner.clone(), line.clone(), mirrored.clone()]);
+        assert!(corner.is_equivalent(&mirrored));
+        assert_eq!(distinct, vec![corner, line]);
+    }
+}
@@ -1,15 +1,71 @@
 use std::{
-    fmt::Display,
+    fmt::{Debug, Display},
     ops::{BitAnd, BitOr, Neg, Not},
+    str::FromStr,
 };
 
 use quickcheck::{Arbitrary, Gen};
+use Hex::{East, NorthEast, NorthWest, SouthEast, SouthWest, West};
 use Square::{Down, Left, Right, Up};
+use Triangle::{Base, LeftSide, RightSide};
 
 use crate::core::lattice::*;
 
 use crate::core::{cardinality::Cardinality, enumset::EnumSet, finite::Finite};
 
+use super::coordinate::Coordinate;
+
+/// Directions towards the neighbors of a cell, determines the shape of tiles and how the cells of a grid are linked
+///
+/// # Invariants
+///
+/// 1. directions are enumerated in clockwise order, so rotating a tile rotates the bits of its connections
+/// 2. the neighborhood is symmetric: `∀d : A, i : Coordinate<isize>. (-d).neighbor_index(d.neighbor_index(i)) == i`
+pub trait Topology: Finite + Copy + Eq + Neg<Output = Self> {
+    /// shape of the cells, recorded with levels to choose their renderer
+    const GEOMETRY: Geometry;
+
+    /// Returns the position of the neighboring cell of the cell at `index` in this direction
+    ///
+    /// the neighborhood may depend on the position, e.g. for alternating triangles
+    fn neighbor_index(self, index: Coordinate<isize>) -> Coordinate<isize>;
+}
+
+/// Shape of the cells of a level, one for each [`Topology`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Geometry {
+    #[default]
+    Square,
+    Hex,
+    Triangle,
+}
+
+impl Geometry {
+    pub const ALL: [Geometry; 3] = [Geometry::Square, Geometry::Hex, Geometry::Triangle];
+}
+
+impl Display for Geometry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Geometry::Square => "square",
+            Geometry::Hex => "hex",
+            Geometry::Triangle => "triangle",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for Geometry {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Geometry::ALL
+            .into_iter()
+            .find(|geometry| geometry.to_string() == s)
+            .ok_or_else(|| format!("parsing error: unknown geometry '{s}'"))
+    }
+}
+
 /// Represents a direction for a tile connection
 #[derive(Debug, Hash, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Square {
@@ -58,6 +114,26 @@ impl Arbitrary for Square {
     }
 }
 
+impl Square {
+    /// Converts a direction to the respective delta coordinate
+    pub fn to_coordinate(self) -> Coordinate<isize> {
+        match self {
+            Up => (-1, 0).into(),
+            Right => (0, 1).into(),
+            Down => (1, 0).into(),
+            Left => (0, -1).into(),
+        }
+    }
+}
+
+impl Topology for Square {
+    const GEOMETRY: Geometry = Geometry::Square;
+
+    fn neighbor_index(self, index: Coordinate<isize>) -> Coordinate<isize> {
+        index + self.to_coordinate()
+    }
+}
+
 impl Cardinality for Square {
     const CARDINALITY: u64 = 4;
 }
@@ -83,6 +159,199 @@ impl Finite for Square {
     }
 }
 
+/// Represents a direction for a connection of a hexagonal tile
+///
+/// hexagonal levels use axial coordinates: every row of hexagons is shifted half a cell to the right
+/// of the row above, so the grid of a hexagonal level takes the shape of a rhombus
+#[derive(Debug, Hash, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Hex {
+    /// [`Coordinate::new(-1, 1)`](super::coordinate::Coordinate)
+    NorthEast,
+    /// [`Coordinate::new(0, 1)`](super::coordinate::Coordinate)
+    East,
+    /// [`Coordinate::new(1, 0)`](super::coordinate::Coordinate)
+    SouthEast,
+    /// [`Coordinate::new(1, -1)`](super::coordinate::Coordinate)
+    SouthWest,
+    /// [`Coordinate::new(0, -1)`](super::coordinate::Coordinate)
+    West,
+    /// [`Coordinate::new(-1, 0)`](super::coordinate::Coordinate)
+    NorthWest,
+}
+
+impl Neg for Hex {
+    type Output = Hex;
+
+    fn neg(self) -> Self::Output {
+        match self {
+            NorthEast => SouthWest,
+            East => West,
+            SouthEast => NorthWest,
+            SouthWest => NorthEast,
+            West => East,
+            NorthWest => SouthEast,
+        }
+    }
+}
+
+impl Display for Hex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                NorthEast => "NorthEast",
+                East => "East",
+                SouthEast => "SouthEast",
+                SouthWest => "SouthWest",
+                West => "West",
+                NorthWest => "NorthWest",
+            }
+        )
+    }
+}
+
+impl Arbitrary for Hex {
+    fn arbitrary(g: &mut Gen) -> Self {
+        *g.choose(&[NorthEast, East, SouthEast, SouthWest, West, NorthWest])
+            .unwrap()
+    }
+}
+
+impl Cardinality for Hex {
+    const CARDINALITY: u64 = 6;
+}
+
+impl Finite for Hex {
+    fn unchecked_index_to_enum(value: u64) -> Self {
+        match value % Self::CARDINALITY {
+            0 => NorthEast,
+            1 => East,
+            2 => SouthEast,
+            3 => SouthWest,
+            4 => West,
+            _ => NorthWest,
+        }
+    }
+
+    fn enum_to_index(&self) -> u64 {
+        match self {
+            NorthEast => 0,
+            East => 1,
+            SouthEast => 2,
+            SouthWest => 3,
+            West => 4,
+            NorthWest => 5,
+        }
+    }
+}
+
+impl Hex {
+    /// Converts a direction to the respective delta coordinate in axial coordinates
+    pub fn to_coordinate(self) -> Coordinate<isize> {
+        match self {
+            NorthEast => (-1, 1).into(),
+            East => (0, 1).into(),
+            SouthEast => (1, 0).into(),
+            SouthWest => (1, -1).into(),
+            West => (0, -1).into(),
+            NorthWest => (-1, 0).into(),
+        }
+    }
+}
+
+impl Topology for Hex {
+    const GEOMETRY: Geometry = Geometry::Hex;
+
+    fn neighbor_index(self, index: Coordinate<isize>) -> Coordinate<isize> {
+        index + self.to_coordinate()
+    }
+}
+
+/// Represents a direction for a connection of a triangular tile
+///
+/// triangular levels alternate between upward and downward pointing triangles along rows and columns,
+/// see [`Coordinate::points_up`](super::coordinate::Coordinate::points_up).
+/// The directions are enumerated in clockwise order for upward pointing triangles, which reverses the
+/// visual direction of rotations on downward pointing triangles
+#[derive(Debug, Hash, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Triangle {
+    /// horizontal side, towards the next row for upward pointing and the previous row for downward pointing triangles
+    Base,
+    /// [`Coordinate::new(0, -1)`](super::coordinate::Coordinate)
+    LeftSide,
+    /// [`Coordinate::new(0, 1)`](super::coordinate::Coordinate)
+    RightSide,
+}
+
+/// neighboring triangles share their base or the left side of one is the right side of the other
+impl Neg for Triangle {
+    type Output = Triangle;
+
+    fn neg(self) -> Self::Output {
+        match self {
+            Base => Base,
+            LeftSide => RightSide,
+            RightSide => LeftSide,
+        }
+    }
+}
+
+impl Display for Triangle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Base => "Base",
+                LeftSide => "LeftSide",
+                RightSide => "RightSide",
+            }
+        )
+    }
+}
+
+impl Arbitrary for Triangle {
+    fn arbitrary(g: &mut Gen) -> Self {
+        *g.choose(&[Base, LeftSide, RightSide]).unwrap()
+    }
+}
+
+impl Cardinality for Triangle {
+    const CARDINALITY: u64 = 3;
+}
+
+impl Finite for Triangle {
+    fn unchecked_index_to_enum(value: u64) -> Self {
+        match value % Self::CARDINALITY {
+            0 => Base,
+            1 => LeftSide,
+            _ => RightSide,
+        }
+    }
+
+    fn enum_to_index(&self) -> u64 {
+        match self {
+            Base => 0,
+            LeftSide => 1,
+            RightSide => 2,
+        }
+    }
+}
+
+impl Topology for Triangle {
+    const GEOMETRY: Geometry = Geometry::Triangle;
+
+    fn neighbor_index(self, index: Coordinate<isize>) -> Coordinate<isize> {
+        match self {
+            Base if index.points_up() => index + Coordinate::new(1, 0),
+            Base => index + Coordinate::new(-1, 0),
+            LeftSide => index + Coordinate::new(0, -1),
+            RightSide => index + Coordinate::new(0, 1),
+        }
+    }
+}
+
 /// Tilable 2D shape with individual binary connection indicators towards neighboring tiles
 ///
 /// The possible directions for connections correspond to the number of enum values in the EnumSetType
@@ -90,9 +359,16 @@ impl Finite for Square {
 /// it is guaranteed to implement the [Copy] trait
 ///
 /// Basic operations are checking present connections and rotating tiles
-#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Tile<A>(pub EnumSet<A>);
 
+/// prints the connections of the tile, e.g. `Tile({Up, Right})`
+impl<A: Debug + Finite> Debug for Tile<A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Tile").field(&self.0).finish()
+    }
+}
+
 impl<A> Clone for Tile<A> {
     fn clone(&self) -> Self {
         Self(self.0)
@@ -175,7 +451,27 @@ impl<A: 'static + Clone + Finite> Arbitrary for Tile<A> {
 
 impl Display for Tile<Square> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", Self::UNICODE_TILES[self.enum_to_index() as usize])
+        if f.alternate() {
+            write!(f, "{}", ascii_glyph(*self))
+        } else {
+            write!(f, "{}", glyph(*self))
+        }
+    }
+}
+
+/// hexagonal tiles lack box-drawing characters, they are shown as the base64 digit of their connections
+impl Display for Tile<Hex> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const DIGITS: &[u8; 64] =
+            b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        write!(f, "{}", DIGITS[self.enum_to_index() as usize] as char)
+    }
+}
+
+/// prints the index of the tile as octal digit
+impl Display for Tile<Triangle> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.enum_to_index())
     }
 }
 
@@ -191,11 +487,62 @@ impl<A: Finite> BoundedLattice for Tile<A> {
 
 impl<A: Finite> DistributiveLattice for Tile<A> {}
 
-impl Tile<Square> {
-    /// visualization for each tile state as UNICODE character
-    const UNICODE_TILES: [char; 16] = [
-        ' ', '╹', '╺', '┗', '╻', '┃', '┏', '┣', '╸', '┛', '━', '┻', '┓', '┫', '┳', '╋',
-    ];
+/// visualization for each tile state as UNICODE box-drawing character
+///
+/// indexed by [`Finite::enum_to_index`] of the tile, this order is part of the stable level format
+pub const GLYPHS: [char; 16] = [
+    ' ', '╹', '╺', '┗', '╻', '┃', '┏', '┣', '╸', '┛', '━', '┻', '┓', '┫', '┳', '╋',
+];
+
+/// Returns the UNICODE box-drawing character representing the tile
+///
+/// single source of truth for [`Display`], parsers and renderers
+pub fn glyph(tile: Tile<Square>) -> char {
+    GLYPHS[tile.enum_to_index() as usize]
+}
+
+/// 7-bit fallback for [`GLYPHS`] on terminals without box-drawing support
+///
+/// extends the alphabet of [`ascii_to_tile`](super::parser::ascii_to_tile): the first orientation of every
+/// rotation class keeps its character, the other orientations get their own
+///
+/// | Connections | Characters              |
+/// |:------------|:------------------------|
+/// | 0           | `' '`                   |
+/// | 1           | `-` / `>` / `v` / `<`   |
+/// | 2           | `I` / `=`               |
+/// | 2           | `L` / `r` / `J` / `7`   |
+/// | 3           | `T` / `Y` / `{` / `A`   |
+/// | 4           | `+`                     |
+pub const ASCII_GLYPHS: [char; 16] = [
+    ' ', '-', '>', 'L', 'v', 'I', 'r', 'T', '<', 'J', '=', 'A', '7', '{', 'Y', '+',
+];
+
+/// Returns the 7-bit ASCII character representing the tile
+///
+/// selected by the alternate flag of [`Display`]: `format!("{tile:#}")`
+pub fn ascii_glyph(tile: Tile<Square>) -> char {
+    ASCII_GLYPHS[tile.enum_to_index() as usize]
+}
+
+/// Returns the tile represented by the UNICODE box-drawing character
+///
+/// inverse of [`glyph`]
+pub fn from_glyph(glyph: char) -> Option<Tile<Square>> {
+    GLYPHS
+        .iter()
+        .position(|&g| g == glyph)
+        .map(|i| Tile::unchecked_index_to_enum(i as u64))
+}
+
+/// Returns the tile represented by the 7-bit ASCII character
+///
+/// inverse of [`ascii_glyph`]
+pub fn from_ascii_glyph(glyph: char) -> Option<Tile<Square>> {
+    ASCII_GLYPHS
+        .iter()
+        .position(|&g| g == glyph)
+        .map(|i| Tile::unchecked_index_to_enum(i as u64))
 }
 
 #[macro_export]
@@ -209,8 +556,12 @@ macro_rules! tile {
 mod tests {
 
     use crate::core::{cardinality::Cardinality, finite::Finite, interval::Max};
+    use crate::model::coordinate::Coordinate;
 
-    use super::{Square, Tile};
+    use super::{
+        ascii_glyph, from_glyph, glyph, Geometry, Hex, Square, Tile, Topology, Triangle,
+        ASCII_GLYPHS, GLYPHS,
+    };
 
     /// not necessary, but desirable
     #[quickcheck]
@@ -224,6 +575,79 @@ mod tests {
         (t1 <= t2) == (t1.enum_to_index() <= t2.enum_to_index())
     }
 
+    /// not necessary, but desirable
+    #[test]
+    fn geometries_parse_their_display() {
+        for geometry in Geometry::ALL {
+            assert_eq!(geometry.to_string().parse(), Ok(geometry));
+        }
+        assert!("octagon".parse::<Geometry>().is_err());
+    }
+
+    #[quickcheck]
+    fn hex_finite_defines_order_isomorphism(h1: Hex, h2: Hex) -> bool {
+        (h1 <= h2) == (h1.enum_to_index() <= h2.enum_to_index())
+    }
+
+    #[quickcheck]
+    fn opposite_directions_cancel(square: Square, hex: Hex) -> bool {
+        square.to_coordinate() + (-square).to_coordinate() == (0, 0).into()
+            && hex.to_coordinate() + (-hex).to_coordinate() == (0, 0).into()
+    }
+
+    /// not necessary, but desirable
+    #[quickcheck]
+    fn triangle_finite_defines_order_isomorphism(t1: Triangle, t2: Triangle) -> bool {
+        (t1 <= t2) == (t1.enum_to_index() <= t2.enum_to_index())
+    }
+
+    #[quickcheck]
+    fn triangle_neighbors_point_in_the_opposite_direction(
+        row: i16,
+        column: i16,
+        direction: Triangle,
+    ) -> bool {
+        let index = Coordinate::new(row as isize, column as isize);
+        index.points_up() != direction.neighbor_index(index).points_up()
+    }
+
+    #[quickcheck]
+    fn rotating_hex_tile_rotates_connections_clockwise(tile: Tile<Hex>) -> bool {
+        let rotated = tile.rotated_clockwise(1);
+        tile.0.iter().all(|d| {
+            rotated
+                .0
+                .contains(Hex::unchecked_index_to_enum(d.enum_to_index() + 1))
+        }) && tile.0.len() == rotated.0.len()
+    }
+
+    #[quickcheck]
+    fn glyph_then_from_glyph_is_identity(tile: Tile<Square>) -> bool {
+        from_glyph(glyph(tile)) == Some(tile)
+    }
+
+    #[quickcheck]
+    fn from_glyph_is_none_for_other_characters(character: char) -> bool {
+        GLYPHS.contains(&character) || from_glyph(character).is_none()
+    }
+
+    #[quickcheck]
+    fn ascii_glyphs_are_unique_and_7_bit(tile: Tile<Square>) -> bool {
+        let character = ascii_glyph(tile);
+        character.is_ascii() && ASCII_GLYPHS.iter().filter(|&&c| c == character).count() == 1
+    }
+
+    #[quickcheck]
+    fn alternate_display_is_ascii_glyph(tile: Tile<Square>) -> bool {
+        format!("{tile:#}") == ascii_glyph(tile).to_string()
+    }
+
+    #[test]
+    fn debug_lists_connections() {
+        let tile = Tile::<Square>::unchecked_index_to_enum(3);
+        assert_eq!(format!("{tile:?}"), "Tile({Up, Right})");
+    }
+
     #[quickcheck]
     fn rotate_clockwise_cardinality_times_is_identity(tile: Tile<Square>) -> bool {
         tile == tile.rotated_clockwise(Square::CARDINALITY)
@@ -0,0 +1,29 @@
+//! Commonly used items of the game crate
+//!
+//! `use game::prelude::*;` brings the model of a level, the generators and the entry points of the solver
+//! into scope. Solving and rotating are methods of [`Grid`] and [`GameBoard`], so they come along with the types
+//!
+//! the modules remain the place for everything else, e.g. rendering, level packs or the SAT encoding
+
+pub use crate::core::enumset::EnumSet;
+pub use crate::dto::LevelDto;
+pub use crate::generator::{
+    fastgen::{generate, generate_unique},
+    levelstream::{level_stream, LevelProperty},
+    wfc::WfcGenerator,
+};
+pub use crate::model::{
+    accesserror::AccessError,
+    channel::Channels,
+    coordinate::Coordinate,
+    gameboard::{GameBoard, WinCondition, WinRule},
+    grid::Grid,
+    lock::Cell,
+    overlay::Overlay,
+    tile::{Geometry, Hex, Square, Tile, Topology, Triangle},
+};
+pub use crate::solver::{
+    difficulty::{rate, Difficulty},
+    hint::{get_hint, Hint, HintPolicy},
+    rotation_distance,
+};
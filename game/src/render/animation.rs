@@ -0,0 +1,95 @@
+use gif::{Encoder, Frame as GifFrame, Repeat};
+
+use crate::model::{
+    coordinate::Coordinate,
+    grid::Grid,
+    tile::{Square, Tile},
+};
+use crate::solver::hint::generate_solving_trace;
+
+use super::raster::{rasterize, PALETTE};
+
+/// how long the solved level is shown before the animation loops, in milliseconds
+const FINAL_DELAY: u16 = 2000;
+
+/// level state together with the tile changed last
+pub type Snapshot = (Grid<Tile<Square>>, Option<Coordinate<isize>>);
+
+/// Replays the solving trace of the level, one frame per turned tile
+///
+/// each frame highlights the tile changed last, the first frame shows the level as given
+pub fn solving_frames(grid: &Grid<Tile<Square>>) -> Vec<Snapshot> {
+    let mut frames = vec![(grid.clone(), None)];
+    let mut current = grid.clone();
+    for (coordinate, tile) in generate_solving_trace(grid) {
+        if current[coordinate] != tile {
+            current[coordinate] = tile;
+            frames.push((current.clone(), Some(coordinate)));
+        }
+    }
+    frames
+}
+
+/// Encodes the frames as looping animated GIF
+///
+/// `delay` in milliseconds between frames, the last frame is held longer
+pub fn encode_gif(frames: &[Snapshot], cell_size: usize, delay: u16) -> Result<Vec<u8>, String> {
+    let (first, _) = frames.first().ok_or("no frames to encode")?;
+    let too_large = || String::from("level too large for GIF");
+    let width = u16::try_from(first.columns() * cell_size).map_err(|_| too_large())?;
+    let height = u16::try_from(first.rows() * cell_size).map_err(|_| too_large())?;
+
+    let mut buffer = vec![];
+    {
+        let mut encoder =
+            Encoder::new(&mut buffer, width, height, &PALETTE).map_err(|e| e.to_string())?;
+        encoder
+            .set_repeat(Repeat::Infinite)
+            .map_err(|e| e.to_string())?;
+        for (index, (grid, highlight)) in frames.iter().enumerate() {
+            let raster = rasterize(grid, cell_size, *highlight);
+            let mut frame = GifFrame::from_indexed_pixels(width, height, &raster.pixels, None);
+            // GIF delays are measured in hundredths of a second
+            frame.delay = if index + 1 == frames.len() {
+                FINAL_DELAY
+            } else {
+                delay
+            } / 10;
+            encoder.write_frame(&frame).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(buffer)
+}
+
+/// Renders a clip of the level being solved
+pub fn solve_to_gif(
+    grid: &Grid<Tile<Square>>,
+    cell_size: usize,
+    delay: u16,
+) -> Result<Vec<u8>, String> {
+    encode_gif(&solving_frames(grid), cell_size, delay)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::core::interval::{Interval, Max};
+    use crate::generator::fastgen::generate;
+    use crate::model::{coordinate::Coordinate, gameboard::GameBoard};
+
+    use super::{solve_to_gif, solving_frames};
+
+    #[quickcheck]
+    fn solving_frames_end_solved(dimension: Coordinate<Max<8>>, seed: u64) -> bool {
+        let grid = generate(dimension.map(Interval::to_usize), seed).scramble(seed);
+        let frames = solving_frames(&grid);
+        frames.first().map(|f| &f.0) == Some(&grid)
+            && frames.last().map_or(false, |f| f.0.is_solved())
+    }
+
+    #[quickcheck]
+    fn gif_has_header(dimension: Coordinate<Max<4>>, seed: u64) -> bool {
+        let grid = generate(dimension.map(Interval::to_usize) + 1, seed).scramble(seed);
+        solve_to_gif(&grid, 8, 100).map_or(false, |gif| gif.starts_with(b"GIF89a"))
+    }
+}
@@ -0,0 +1,308 @@
+//! Printable puzzle books
+//!
+//! a book opens with a title page holding the table of contents, followed by a section for every difficulty of
+//! the pack and an appendix of solutions. Puzzles are numbered throughout the book, the appendix lists the
+//! solutions by the numbers of their puzzles. Every page but the title page carries its page number
+
+use crate::levelpack::LevelPack;
+use crate::model::{
+    grid::Grid,
+    tile::{Square, Tile},
+};
+use crate::solver::difficulty::Difficulty;
+
+use super::{
+    pdf::{PdfDocument, PdfPage, A4},
+    theme::Theme,
+};
+
+/// puzzles printed on a page of a difficulty section
+pub const PUZZLES_PER_PAGE: usize = 2;
+
+/// solutions printed on a page of the appendix, in two columns
+pub const SOLUTIONS_PER_PAGE: usize = 6;
+
+/// distance of the content to the edges of the page in points
+const MARGIN: f64 = 56.0;
+
+/// upper bound of the side length of a cell, so small levels are not blown up to fill the page
+const MAX_CELL_SIZE: f64 = 36.0;
+
+/// height of the label above a level
+const LABEL_HEIGHT: f64 = 22.0;
+
+/// Puzzle of the book together with its number
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BookPuzzle {
+    pub number: usize,
+    pub grid: Grid<Tile<Square>>,
+}
+
+/// Section of puzzles of the same difficulty
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BookSection {
+    pub difficulty: Difficulty,
+    pub puzzles: Vec<BookPuzzle>,
+    /// page number of the first page of the section, the title page is page 1
+    pub first_page: usize,
+}
+
+impl BookSection {
+    pub fn title(&self) -> String {
+        let name = self.difficulty.to_string();
+        let mut chars = name.chars();
+        chars.next().map_or_else(String::new, |first| {
+            format!("{}{} puzzles", first.to_ascii_uppercase(), chars.as_str())
+        })
+    }
+
+    pub fn pages(&self) -> usize {
+        (self.puzzles.len() + PUZZLES_PER_PAGE - 1) / PUZZLES_PER_PAGE
+    }
+}
+
+/// Layout of the book: the sections of the non-empty difficulties from easy to hard and the solutions appendix
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Book {
+    pub title: String,
+    pub author: String,
+    pub sections: Vec<BookSection>,
+    /// solutions in the order of the puzzle numbers
+    pub solutions: Vec<Grid<Tile<Square>>>,
+    /// page number of the first page of the appendix
+    pub solutions_page: usize,
+}
+
+impl Book {
+    /// Lays out the levels of the pack and solves them
+    ///
+    /// levels keep their order within their difficulty, fails if a level has no solution
+    pub fn from_pack(pack: &LevelPack) -> Result<Self, String> {
+        let mut sections = vec![];
+        let mut number = 1;
+        let mut page = 2;
+        for difficulty in Difficulty::ALL {
+            let puzzles = pack
+                .with_difficulty(difficulty)
+                .zip(number..)
+                .map(|(level, number)| BookPuzzle {
+                    number,
+                    grid: level.grid.clone(),
+                })
+                .collect::<Vec<_>>();
+            if puzzles.is_empty() {
+                continue;
+            }
+            number += puzzles.len();
+            let section = BookSection {
+                difficulty,
+                puzzles,
+                first_page: page,
+            };
+            page += section.pages();
+            sections.push(section);
+        }
+        let solutions = sections
+            .iter()
+            .flat_map(|section| &section.puzzles)
+            .map(|puzzle| {
+                puzzle
+                    .grid
+                    .solve()
+                    .next()
+                    .ok_or_else(|| format!("puzzle {} has no solution", puzzle.number))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Book {
+            title: pack.name.clone(),
+            author: pack.author.clone(),
+            sections,
+            solutions,
+            solutions_page: page,
+        })
+    }
+
+    /// Entries of the table of contents with their page numbers
+    pub fn contents(&self) -> Vec<(String, usize)> {
+        let mut contents = self
+            .sections
+            .iter()
+            .map(|section| (section.title(), section.first_page))
+            .collect::<Vec<_>>();
+        if !self.solutions.is_empty() {
+            contents.push(("Solutions".to_string(), self.solutions_page));
+        }
+        contents
+    }
+
+    /// Renders the book on A4 pages
+    pub fn to_pdf(&self, theme: &Theme) -> PdfDocument {
+        let mut document = PdfDocument::new(&self.title);
+        document.pages.push(self.title_page());
+
+        for section in &self.sections {
+            for (index, puzzles) in section.puzzles.chunks(PUZZLES_PER_PAGE).enumerate() {
+                let mut page = PdfPage::new(A4);
+                page.text(MARGIN, MARGIN, 18.0, &section.title());
+                let height = (page.height() - 2.0 * MARGIN - 40.0) / PUZZLES_PER_PAGE as f64;
+                let width = page.width() - 2.0 * MARGIN;
+                for (slot, puzzle) in puzzles.iter().enumerate() {
+                    let label = format!(
+                        "Puzzle {} ({} x {})",
+                        puzzle.number,
+                        puzzle.grid.rows(),
+                        puzzle.grid.columns()
+                    );
+                    let top = MARGIN + 40.0 + slot as f64 * height;
+                    draw_in_slot(
+                        &mut page,
+                        &puzzle.grid,
+                        &label,
+                        (MARGIN, top, width, height),
+                        theme,
+                    );
+                }
+                number_page(&mut page, section.first_page + index);
+                document.pages.push(page);
+            }
+        }
+
+        let numbered = self
+            .sections
+            .iter()
+            .flat_map(|section| &section.puzzles)
+            .zip(&self.solutions)
+            .collect::<Vec<_>>();
+        for (index, solutions) in numbered.chunks(SOLUTIONS_PER_PAGE).enumerate() {
+            let mut page = PdfPage::new(A4);
+            page.text(MARGIN, MARGIN, 18.0, "Solutions");
+            let columns = 2;
+            let rows = (SOLUTIONS_PER_PAGE + columns - 1) / columns;
+            let width = (page.width() - 2.0 * MARGIN) / columns as f64;
+            let height = (page.height() - 2.0 * MARGIN - 40.0) / rows as f64;
+            for (slot, (puzzle, solution)) in solutions.iter().enumerate() {
+                let (left, top) = (
+                    MARGIN + (slot % columns) as f64 * width,
+                    MARGIN + 40.0 + (slot / columns) as f64 * height,
+                );
+                let label = format!("Puzzle {}", puzzle.number);
+                draw_in_slot(
+                    &mut page,
+                    solution,
+                    &label,
+                    (left, top, width, height),
+                    theme,
+                );
+            }
+            number_page(&mut page, self.solutions_page + index);
+            document.pages.push(page);
+        }
+        document
+    }
+
+    fn title_page(&self) -> PdfPage {
+        let mut page = PdfPage::new(A4);
+        let center = page.width() / 2.0;
+        page.centered_text(center, 200.0, 28.0, &self.title);
+        if !self.author.is_empty() {
+            page.centered_text(center, 236.0, 14.0, &format!("by {}", self.author));
+        }
+        page.text(MARGIN, 340.0, 16.0, "Contents");
+        for (line, (entry, number)) in self.contents().into_iter().enumerate() {
+            let y = 372.0 + line as f64 * 22.0;
+            page.text(MARGIN, y, 12.0, &entry);
+            page.right_aligned_text(page.width() - MARGIN, y, 12.0, &number.to_string());
+        }
+        page
+    }
+}
+
+/// Creates the puzzle book of the pack, see [`Book`]
+pub fn puzzle_book(pack: &LevelPack, theme: &Theme) -> Result<Vec<u8>, String> {
+    Book::from_pack(pack).map(|book| book.to_pdf(theme).to_bytes())
+}
+
+/// draws the label and below it the level as large as fits, centered horizontally
+fn draw_in_slot(
+    page: &mut PdfPage,
+    grid: &Grid<Tile<Square>>,
+    label: &str,
+    (left, top, width, height): (f64, f64, f64, f64),
+    theme: &Theme,
+) {
+    page.text(left, top + 14.0, 11.0, label);
+    let (rows, columns) = (grid.rows().max(1) as f64, grid.columns().max(1) as f64);
+    let padding = 12.0;
+    let cell_size = ((width - padding) / columns)
+        .min((height - LABEL_HEIGHT - padding) / rows)
+        .min(MAX_CELL_SIZE);
+    let x = left + (width - cell_size * columns) / 2.0;
+    page.level(grid, (x, top + LABEL_HEIGHT), cell_size, theme);
+}
+
+fn number_page(page: &mut PdfPage, number: usize) {
+    let (x, y) = (page.width() / 2.0, page.height() - MARGIN / 2.0);
+    page.centered_text(x, y, 10.0, &number.to_string());
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::generator::fastgen::generate;
+    use crate::levelpack::{LevelPack, PackedLevel};
+    use crate::model::{coordinate::Coordinate, gameboard::GameBoard, grid::Grid};
+    use crate::render::theme::Theme;
+    use crate::solver::difficulty::Difficulty;
+
+    use super::Book;
+
+    fn pack(difficulties: &[Difficulty]) -> LevelPack {
+        let mut pack = LevelPack::new("Loops", "Ada");
+        for (seed, &difficulty) in difficulties.iter().enumerate() {
+            pack.push_rated(PackedLevel {
+                grid: generate(Coordinate::new(4, 4), seed as u64),
+                difficulty,
+            });
+        }
+        pack
+    }
+
+    #[test]
+    fn sections_follow_the_difficulties() {
+        use Difficulty::{Easy, Hard};
+
+        let book = Book::from_pack(&pack(&[Hard, Easy, Easy, Easy, Hard])).unwrap();
+        assert_eq!(
+            book.contents(),
+            vec![
+                ("Easy puzzles".to_string(), 2),
+                ("Hard puzzles".to_string(), 4),
+                ("Solutions".to_string(), 5),
+            ]
+        );
+        // puzzles are numbered in book order, the easy levels come first
+        let numbers = book.sections[1]
+            .puzzles
+            .iter()
+            .map(|puzzle| puzzle.number)
+            .collect::<Vec<_>>();
+        assert_eq!(numbers, vec![4, 5]);
+        assert!(book.solutions.iter().all(|solution| solution.is_solved()));
+
+        // title page, two pages of easy puzzles, one of hard puzzles and one of solutions
+        assert_eq!(book.to_pdf(&Theme::PRINT).pages.len(), 5);
+    }
+
+    #[test]
+    fn unsolvable_puzzles_are_rejected() {
+        let mut pack = pack(&[]);
+        pack.push_rated(PackedLevel {
+            grid: "╺━".parse::<Grid<_>>().unwrap(),
+            difficulty: Difficulty::Easy,
+        });
+        assert_eq!(
+            Book::from_pack(&pack),
+            Err("puzzle 1 has no solution".to_string())
+        );
+    }
+}
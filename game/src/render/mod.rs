@@ -0,0 +1,7 @@
+#[cfg(feature = "gif")]
+pub mod animation;
+pub mod book;
+pub mod pdf;
+pub mod raster;
+pub mod svg;
+pub mod theme;
@@ -0,0 +1,287 @@
+//! Minimal writer of PDF documents
+//!
+//! pages hold vector graphics and text in the standard font Helvetica, which readers provide without embedding.
+//! Positions are given in points from the top left corner of the page, 72 points make an inch
+
+use crate::model::{
+    grid::Grid,
+    tile::{Square, Tile},
+};
+
+use super::{
+    svg::{tile_shapes, Shape},
+    theme::{Rgb, Theme, CELL_UNITS},
+};
+
+/// width and height of an A4 page in points
+pub const A4: (f64, f64) = (595.0, 842.0);
+
+/// control point distance of the cubic Bézier curves approximating a quarter circle
+const KAPPA: f64 = 0.552_284_75;
+
+/// Drawing instructions of a single page
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfPage {
+    width: f64,
+    height: f64,
+    content: String,
+}
+
+impl PdfPage {
+    pub fn new((width, height): (f64, f64)) -> Self {
+        PdfPage {
+            width,
+            height,
+            content: String::new(),
+        }
+    }
+
+    pub fn width(&self) -> f64 {
+        self.width
+    }
+
+    pub fn height(&self) -> f64 {
+        self.height
+    }
+
+    /// Writes a line of text starting at the baseline position
+    ///
+    /// characters outside of ASCII are replaced by `?`
+    pub fn text(&mut self, x: f64, y: f64, size: f64, text: &str) {
+        self.content.push_str(&format!(
+            "BT /F1 {size:.1} Tf {x:.2} {:.2} Td ({}) Tj ET\n",
+            self.height - y,
+            escape(text)
+        ));
+    }
+
+    /// Writes a line of text centered horizontally on `x`
+    ///
+    /// the width is estimated, as the metrics of the font are not at hand
+    pub fn centered_text(&mut self, x: f64, y: f64, size: f64, text: &str) {
+        self.text(x - estimated_width(text, size) / 2.0, y, size, text);
+    }
+
+    /// Writes a line of text ending at `x`, see [`PdfPage::centered_text`]
+    pub fn right_aligned_text(&mut self, x: f64, y: f64, size: f64, text: &str) {
+        self.text(x - estimated_width(text, size), y, size, text);
+    }
+
+    pub fn stroke_color(&mut self, Rgb(r, g, b): Rgb) {
+        self.content.push_str(&format!(
+            "{:.3} {:.3} {:.3} RG\n",
+            r as f64 / 255.0,
+            g as f64 / 255.0,
+            b as f64 / 255.0
+        ));
+    }
+
+    pub fn line_width(&mut self, width: f64) {
+        self.content.push_str(&format!("{width:.2} w\n"));
+    }
+
+    pub fn line(&mut self, (x1, y1): (f64, f64), (x2, y2): (f64, f64)) {
+        self.content.push_str(&format!(
+            "{x1:.2} {:.2} m {x2:.2} {:.2} l S\n",
+            self.height - y1,
+            self.height - y2
+        ));
+    }
+
+    pub fn rectangle(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        self.content.push_str(&format!(
+            "{x:.2} {:.2} {width:.2} {height:.2} re S\n",
+            self.height - y - height
+        ));
+    }
+
+    /// Strokes a circle made of four Bézier curves
+    pub fn circle(&mut self, (cx, cy): (f64, f64), r: f64) {
+        let (cx, cy, k) = (cx, self.height - cy, r * KAPPA);
+        self.content.push_str(&format!(
+            "{:.2} {cy:.2} m {:.2} {:.2} {:.2} {:.2} {cx:.2} {:.2} c {:.2} {:.2} {:.2} {:.2} {:.2} {cy:.2} c {:.2} {:.2} {:.2} {:.2} {cx:.2} {:.2} c {:.2} {:.2} {:.2} {:.2} {:.2} {cy:.2} c S\n",
+            cx + r,
+            cx + r, cy + k, cx + k, cy + r, cy + r,
+            cx - k, cy + r, cx - r, cy + k, cx - r,
+            cx - r, cy - k, cx - k, cy - r, cy - r,
+            cx + k, cy - r, cx + r, cy - k, cx + r,
+        ));
+    }
+
+    /// Draws the level with its top left corner at the position, every cell measures `cell_size` points
+    ///
+    /// cells are outlined in the highlight color of the theme, the connections are drawn as by [`tile_shapes`]
+    pub fn level(
+        &mut self,
+        grid: &Grid<Tile<Square>>,
+        (x, y): (f64, f64),
+        cell_size: f64,
+        theme: &Theme,
+    ) {
+        let scale = cell_size / CELL_UNITS as f64;
+        self.stroke_color(theme.highlight);
+        self.line_width(0.5);
+        for c in grid.coordinates() {
+            let (left, top) = (
+                x + c.column as f64 * cell_size,
+                y + c.row as f64 * cell_size,
+            );
+            self.rectangle(left, top, cell_size, cell_size);
+        }
+        self.stroke_color(theme.connection);
+        self.line_width(theme.stroke_width as f64 * scale);
+        for c in grid.coordinates() {
+            let (left, top) = (
+                x + c.column as f64 * cell_size,
+                y + c.row as f64 * cell_size,
+            );
+            let at = |u: u32, v: u32| (left + u as f64 * scale, top + v as f64 * scale);
+            for shape in tile_shapes(grid[c], theme) {
+                match shape {
+                    Shape::Line { x1, y1, x2, y2 } => self.line(at(x1, y1), at(x2, y2)),
+                    Shape::Circle { cx, cy, r } => self.circle(at(cx, cy), r as f64 * scale),
+                }
+            }
+        }
+    }
+}
+
+/// Document of pages in the order they were added
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PdfDocument {
+    pub title: String,
+    pub pages: Vec<PdfPage>,
+}
+
+impl PdfDocument {
+    pub fn new(title: &str) -> Self {
+        PdfDocument {
+            title: title.to_string(),
+            pages: vec![],
+        }
+    }
+
+    /// Encodes the document in PDF 1.4
+    ///
+    /// objects: 1 catalog, 2 page tree, 3 font, 4 document information, then each page followed by its content
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let page_object = |index: usize| 5 + 2 * index;
+        let kids = (0..self.pages.len())
+            .map(|index| format!("{} 0 R", page_object(index)))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mut objects = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            format!(
+                "<< /Type /Pages /Kids [{kids}] /Count {} >>",
+                self.pages.len()
+            ),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+            format!(
+                "<< /Title ({}) /Producer (infinity-loop) >>",
+                escape(&self.title)
+            ),
+        ];
+        for (index, page) in self.pages.iter().enumerate() {
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.0} {:.0}] /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+                page.width,
+                page.height,
+                page_object(index) + 1
+            ));
+            objects.push(format!(
+                "<< /Length {} >>\nstream\n{}\nendstream",
+                page.content.len(),
+                page.content
+            ));
+        }
+
+        let mut pdf = String::from("%PDF-1.4\n");
+        let mut offsets = vec![];
+        for (index, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.push_str(&format!("{} 0 obj\n{object}\nendobj\n", index + 1));
+        }
+        let xref = pdf.len();
+        pdf.push_str(&format!(
+            "xref\n0 {}\n0000000000 65535 f \n",
+            objects.len() + 1
+        ));
+        for offset in offsets {
+            pdf.push_str(&format!("{offset:010} 00000 n \n"));
+        }
+        pdf.push_str(&format!(
+            "trailer\n<< /Size {} /Root 1 0 R /Info 4 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+            objects.len() + 1
+        ));
+        pdf.into_bytes()
+    }
+}
+
+/// string literal of PDF without the enclosing parentheses
+fn escape(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '(' | ')' | '\\' => format!("\\{c}"),
+            c if c.is_ascii() && !c.is_ascii_control() => c.to_string(),
+            _ => "?".to_string(),
+        })
+        .collect()
+}
+
+/// width of the text in Helvetica, assuming an average character width of half the font size
+fn estimated_width(text: &str, size: f64) -> f64 {
+    text.chars().count() as f64 * size * 0.5
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::model::grid::Grid;
+
+    use super::{escape, PdfDocument, PdfPage, A4};
+
+    #[test]
+    fn cross_references_point_to_their_objects() {
+        let mut document = PdfDocument::new("book (draft)");
+        for n in 0..3 {
+            let mut page = PdfPage::new(A4);
+            page.text(72.0, 72.0, 12.0, &format!("page {n}"));
+            page.level(
+                &"┏┓\n┗┛".parse::<Grid<_>>().unwrap(),
+                (72.0, 100.0),
+                30.0,
+                &crate::render::theme::Theme::PRINT,
+            );
+            document.pages.push(page);
+        }
+        let pdf = String::from_utf8(document.to_bytes()).unwrap();
+        assert!(pdf.starts_with("%PDF-1.4\n"));
+        assert!(pdf.contains("/Count 3"));
+        assert!(pdf.contains("/Title (book \\(draft\\))"));
+
+        let xref = pdf[pdf.rfind("startxref\n").unwrap() + 10..]
+            .lines()
+            .next()
+            .unwrap()
+            .parse::<usize>()
+            .unwrap();
+        let offsets = pdf[xref..]
+            .lines()
+            .skip(3)
+            .take_while(|line| line.ends_with(" n "))
+            .map(|line| line[..10].parse::<usize>().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(offsets.len(), 4 + 2 * 3);
+        for (index, offset) in offsets.into_iter().enumerate() {
+            assert!(pdf[offset..].starts_with(&format!("{} 0 obj", index + 1)));
+        }
+    }
+
+    #[test]
+    fn text_is_escaped() {
+        assert_eq!(escape(r"a (b) \ c"), r"a \(b\) \\ c");
+        assert_eq!(escape("Zoë"), "Zo?");
+    }
+}
@@ -0,0 +1,172 @@
+use crate::model::{
+    coordinate::Coordinate,
+    grid::Grid,
+    tile::{Square, Tile},
+};
+
+use super::theme::{Theme, CELL_UNITS};
+
+/// colors of rendered images as consecutive RGB triples, indexed by the pixels of a [`Frame`]
+pub const PALETTE: [u8; 9] = Theme::DEFAULT.palette();
+
+/// palette index of the background
+pub const BACKGROUND: u8 = 0;
+/// palette index of tile connections
+pub const CONNECTION: u8 = 1;
+/// palette index of the background of a highlighted cell
+pub const HIGHLIGHT: u8 = 2;
+
+/// Image with palette-indexed pixels in row-major order
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Frame {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u8>,
+}
+
+impl Frame {
+    fn filled(width: usize, height: usize, color: u8) -> Self {
+        Frame {
+            width,
+            height,
+            pixels: vec![color; width * height],
+        }
+    }
+
+    /// Returns the palette index of the pixel, if it exists
+    pub fn get(&self, x: usize, y: usize) -> Option<u8> {
+        (x < self.width && y < self.height).then(|| self.pixels[y * self.width + x])
+    }
+
+    /// Draws the circle with the given line width around the center
+    fn ring(&mut self, center: (usize, usize), radius: usize, stroke: usize, color: u8) {
+        let reach = radius + stroke;
+        let (outer, inner) = (
+            (radius as f64 + stroke as f64 / 2.0).powi(2),
+            (radius as f64 - stroke as f64 / 2.0).max(0.0).powi(2),
+        );
+        for y in center.1.saturating_sub(reach)..(center.1 + reach).min(self.height) {
+            for x in center.0.saturating_sub(reach)..(center.0 + reach).min(self.width) {
+                // distance between the centers of the pixels
+                let dx = x as f64 - center.0 as f64;
+                let dy = y as f64 - center.1 as f64;
+                let distance = dx * dx + dy * dy;
+                if inner <= distance && distance <= outer {
+                    self.pixels[y * self.width + x] = color;
+                }
+            }
+        }
+    }
+
+    /// Fills the rectangle spanned by the half-open ranges with the color
+    fn fill(&mut self, xs: (usize, usize), ys: (usize, usize), color: u8) {
+        for y in ys.0..ys.1.min(self.height) {
+            for x in xs.0..xs.1.min(self.width) {
+                self.pixels[y * self.width + x] = color;
+            }
+        }
+    }
+}
+
+/// Draws the grid with each tile occupying a square of `cell_size` pixels
+///
+/// the optional highlighted cell is drawn with a different background, e.g. the tile changed last
+pub fn rasterize(
+    grid: &Grid<Tile<Square>>,
+    cell_size: usize,
+    highlight: Option<Coordinate<isize>>,
+) -> Frame {
+    let mut frame = Frame::filled(
+        grid.columns() * cell_size,
+        grid.rows() * cell_size,
+        BACKGROUND,
+    );
+    let theme = Theme::DEFAULT;
+    let scale = |units: u32| cell_size * units as usize / CELL_UNITS as usize;
+    let stroke = scale(theme.stroke_width).max(1);
+    let radius = scale(theme.knob_radius);
+    let center = cell_size / 2;
+    // band of width `stroke` around the center line
+    let band = (
+        center.saturating_sub(stroke / 2),
+        center + stroke - stroke / 2,
+    );
+
+    for coordinate in grid.coordinates() {
+        let x = coordinate.column as usize * cell_size;
+        let y = coordinate.row as usize * cell_size;
+        let offset = |(start, end): (usize, usize), origin: usize| (origin + start, origin + end);
+
+        if highlight == Some(coordinate) {
+            frame.fill((x, x + cell_size), (y, y + cell_size), HIGHLIGHT);
+        }
+
+        let tile = grid[coordinate];
+        // dead ends end in a ring like in [`crate::render::svg::tile_shapes`]
+        let dead_end = tile.0.len() == 1;
+        let inner = if dead_end { radius } else { 0 };
+        for direction in tile.0 {
+            let (xs, ys) = match direction {
+                Square::Up => (band, (0, band.1.saturating_sub(inner))),
+                Square::Right => (((band.0 + inner).min(cell_size), cell_size), band),
+                Square::Down => (band, ((band.0 + inner).min(cell_size), cell_size)),
+                Square::Left => ((0, band.1.saturating_sub(inner)), band),
+            };
+            frame.fill(offset(xs, x), offset(ys, y), CONNECTION);
+        }
+
+        if dead_end {
+            frame.ring((x + center, y + center), radius, stroke, CONNECTION);
+        }
+    }
+    frame
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::core::interval::Max;
+    use crate::model::{
+        coordinate::Coordinate,
+        grid::Grid,
+        tile::{Square, Tile},
+    };
+    use crate::{enumset, tile};
+
+    use super::{rasterize, BACKGROUND, CONNECTION, HIGHLIGHT};
+
+    #[quickcheck]
+    fn frame_size_matches_grid(grid: Grid<Tile<Square>>, cell_size: Max<20>) -> bool {
+        let cell_size = cell_size.to_usize();
+        let frame = rasterize(&grid, cell_size, None);
+        frame.width == grid.columns() * cell_size
+            && frame.height == grid.rows() * cell_size
+            && frame.pixels.len() == frame.width * frame.height
+    }
+
+    #[quickcheck]
+    fn empty_tiles_are_background(dimension: Coordinate<Max<10>>) -> bool {
+        let grid = Grid::filled_with(dimension.map(Max::to_usize), Tile::NO_CONNECTIONS);
+        rasterize(&grid, 9, None)
+            .pixels
+            .into_iter()
+            .all(|p| p == BACKGROUND)
+    }
+
+    #[test]
+    fn connection_reaches_edge() {
+        let grid = Grid::new(Coordinate::new(1, 1), vec![tile!(Square::Up)]);
+        let frame = rasterize(&grid, 9, None);
+        assert_eq!(frame.get(4, 0), Some(CONNECTION));
+        assert_eq!(frame.get(4, 8), Some(BACKGROUND));
+        assert_eq!(frame.get(0, 4), Some(BACKGROUND));
+    }
+
+    #[test]
+    fn highlighted_cell_has_highlight_background() {
+        let grid = Grid::filled_with(Coordinate::new(1, 2), Tile::NO_CONNECTIONS);
+        let frame = rasterize(&grid, 9, Some(Coordinate::new(0, 1)));
+        assert_eq!(frame.get(0, 0), Some(BACKGROUND));
+        assert_eq!(frame.get(9, 0), Some(HIGHLIGHT));
+    }
+}
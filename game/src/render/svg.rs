@@ -0,0 +1,493 @@
+//! Vector drawing of tiles, styled by a [`Theme`]
+//!
+//! the shapes of a tile are given in a cell of [`CELL_UNITS`] on each side, the frontend draws them as
+//! inline SVG elements styled by [`Theme::css_properties`], standalone images embed [`Theme::svg_style`].
+//! [`level_svg`] exports standalone documents sized in pixels, optionally with the solution drawn over the level
+
+use std::cmp::Ordering;
+
+use crate::model::{
+    channel::Channels,
+    coordinate::Coordinate,
+    grid::Grid,
+    tile::{Hex, Square, Tile, Triangle},
+};
+
+use super::theme::{Theme, CELL_UNITS};
+
+/// height of the cell of a hexagonal tile, the hexagon points upwards and is [`CELL_UNITS`] wide
+pub const HEX_UNITS: u32 = 115;
+
+/// height of the cell of a triangular tile with sides of [`CELL_UNITS`]
+pub const TRIANGLE_UNITS: u32 = 87;
+
+/// Options of the documents exported by [`level_svg`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SvgOptions {
+    pub theme: Theme,
+    /// side length of a cell in pixels of the document
+    pub cell_size: u32,
+    /// width of the connection lines in units of a cell of [`CELL_UNITS`]
+    pub stroke_width: u32,
+    /// draws the first solution of the level in the highlight color over the level
+    pub solution: bool,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        SvgOptions {
+            theme: Theme::DEFAULT,
+            cell_size: 32,
+            stroke_width: Theme::DEFAULT.stroke_width,
+            solution: false,
+        }
+    }
+}
+
+/// Outline drawn with the stroke of a connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Shape {
+    Line { x1: u32, y1: u32, x2: u32, y2: u32 },
+    Circle { cx: u32, cy: u32, r: u32 },
+}
+
+impl Shape {
+    /// SVG element with the class styled by [`Theme::svg_style`]
+    pub fn to_svg(self) -> String {
+        match self {
+            Shape::Line { x1, y1, x2, y2 } => {
+                format!(r#"<line class="connection" x1="{x1}" y1="{y1}" x2="{x2}" y2="{y2}"/>"#)
+            }
+            Shape::Circle { cx, cy, r } => {
+                format!(r#"<circle class="connection" cx="{cx}" cy="{cy}" r="{r}"/>"#)
+            }
+        }
+    }
+}
+
+/// Shapes of the tile: a line from the center to every connected side,
+/// dead ends are drawn as ring in the center with the line ending at the ring
+pub fn tile_shapes(tile: Tile<Square>, theme: &Theme) -> Vec<Shape> {
+    let center = CELL_UNITS / 2;
+    let dead_end = tile.0.len() == 1;
+    let inner = if dead_end { theme.knob_radius } else { 0 };
+    let mut shapes = tile
+        .0
+        .iter()
+        .map(|direction| {
+            let (x, y) = match direction {
+                Square::Up => (center, 0),
+                Square::Right => (CELL_UNITS, center),
+                Square::Down => (center, CELL_UNITS),
+                Square::Left => (0, center),
+            };
+            // the line stops `inner` units before the center
+            let toward = |from: u32| match from.cmp(&center) {
+                Ordering::Less => center - inner,
+                Ordering::Equal => center,
+                Ordering::Greater => center + inner,
+            };
+            Shape::Line {
+                x1: x,
+                y1: y,
+                x2: toward(x),
+                y2: toward(y),
+            }
+        })
+        .collect::<Vec<_>>();
+    if dead_end {
+        shapes.push(Shape::Circle {
+            cx: center,
+            cy: center,
+            r: theme.knob_radius,
+        });
+    }
+    shapes
+}
+
+/// Corners of the hexagonal cell in clockwise order, starting at the top
+pub fn hex_outline() -> Vec<(u32, u32)> {
+    let (width, height) = (CELL_UNITS, HEX_UNITS);
+    vec![
+        (width / 2, 0),
+        (width, height / 4),
+        (width, height * 3 / 4),
+        (width / 2, height),
+        (0, height * 3 / 4),
+        (0, height / 4),
+    ]
+}
+
+/// Shapes of the hexagonal tile, drawn like [`tile_shapes`] towards the middle of the sides
+pub fn hex_tile_shapes(tile: Tile<Hex>, theme: &Theme) -> Vec<Shape> {
+    let outline = hex_outline();
+    let center = (CELL_UNITS / 2, HEX_UNITS / 2);
+    // every side lies between the corner of the same index and the next one
+    let ends = tile.0.iter().map(|direction| {
+        let (from, to) = match direction {
+            Hex::NorthEast => (0, 1),
+            Hex::East => (1, 2),
+            Hex::SouthEast => (2, 3),
+            Hex::SouthWest => (3, 4),
+            Hex::West => (4, 5),
+            Hex::NorthWest => (5, 0),
+        };
+        midpoint(outline[from], outline[to])
+    });
+    spokes(center, ends, tile.0.len() == 1, theme)
+}
+
+/// Corners of the triangular cell in clockwise order, starting at the tip
+pub fn triangle_outline(points_up: bool) -> Vec<(u32, u32)> {
+    let (width, height) = (CELL_UNITS, TRIANGLE_UNITS);
+    if points_up {
+        vec![(width / 2, 0), (width, height), (0, height)]
+    } else {
+        vec![(width / 2, height), (0, 0), (width, 0)]
+    }
+}
+
+/// Shapes of the triangular tile, drawn like [`tile_shapes`] from the centroid towards the middle of the sides
+///
+/// the orientation of the triangle follows from its position, see [`Coordinate::points_up`]
+pub fn triangle_tile_shapes(tile: Tile<Triangle>, points_up: bool, theme: &Theme) -> Vec<Shape> {
+    let outline = triangle_outline(points_up);
+    let center = if points_up {
+        (CELL_UNITS / 2, TRIANGLE_UNITS * 2 / 3)
+    } else {
+        (CELL_UNITS / 2, TRIANGLE_UNITS / 3)
+    };
+    // the tip is opposite to the base, the left side ends at the tip on upward pointing triangles
+    let (left, right) = if points_up { (2, 1) } else { (1, 2) };
+    let ends = tile.0.iter().map(|direction| match direction {
+        Triangle::Base => midpoint(outline[1], outline[2]),
+        Triangle::LeftSide => midpoint(outline[0], outline[left]),
+        Triangle::RightSide => midpoint(outline[0], outline[right]),
+    });
+    spokes(center, ends, tile.0.len() == 1, theme)
+}
+
+fn midpoint((x1, y1): (u32, u32), (x2, y2): (u32, u32)) -> (u32, u32) {
+    ((x1 + x2) / 2, (y1 + y2) / 2)
+}
+
+/// lines from the ends to the center, stopping at the ring of a dead end
+fn spokes<I>(center: (u32, u32), ends: I, dead_end: bool, theme: &Theme) -> Vec<Shape>
+where
+    I: IntoIterator<Item = (u32, u32)>,
+{
+    let inner = if dead_end { theme.knob_radius } else { 0 } as f64;
+    let (cx, cy) = (center.0 as f64, center.1 as f64);
+    let mut shapes = ends
+        .into_iter()
+        .map(|(x, y)| {
+            let (dx, dy) = (x as f64 - cx, y as f64 - cy);
+            let length = dx.hypot(dy).max(1.0);
+            Shape::Line {
+                x1: x,
+                y1: y,
+                x2: (cx + dx * inner / length).round() as u32,
+                y2: (cy + dy * inner / length).round() as u32,
+            }
+        })
+        .collect::<Vec<_>>();
+    if dead_end {
+        shapes.push(Shape::Circle {
+            cx: center.0,
+            cy: center.1,
+            r: theme.knob_radius,
+        });
+    }
+    shapes
+}
+
+/// Standalone SVG image of the tile
+pub fn tile_svg(tile: Tile<Square>, theme: &Theme) -> String {
+    grid_svg(&Grid::new(Coordinate::new(1, 1), vec![tile]), theme)
+}
+
+/// Standalone SVG image of the level on the background of the theme, e.g. as thumbnail
+pub fn grid_svg(grid: &Grid<Tile<Square>>, theme: &Theme) -> String {
+    let tiles = grid
+        .coordinates()
+        .map(|c| cell_group(c, &shapes_svg(grid[c], theme)))
+        .collect::<String>();
+    standalone_svg(grid.dimensions(), None, &theme.svg_style(), &tiles, theme)
+}
+
+/// Standalone SVG image of a level of several channels, the channels are styled by [`Theme::channel_style`]
+pub fn channel_grid_svg<const N: usize>(grid: &Grid<Channels<Square, N>>, theme: &Theme) -> String {
+    let tiles = grid
+        .coordinates()
+        .map(|c| {
+            let channels = grid[c]
+                .0
+                .into_iter()
+                .enumerate()
+                .map(|(channel, tile)| {
+                    format!(
+                        r#"<g class="channel-{channel}">{}</g>"#,
+                        shapes_svg(tile, theme)
+                    )
+                })
+                .collect::<String>();
+            cell_group(c, &channels)
+        })
+        .collect::<String>();
+    let style = theme.svg_style() + &theme.channel_style(N);
+    standalone_svg(grid.dimensions(), None, &style, &tiles, theme)
+}
+
+/// Standalone SVG document of the level, e.g. for documentation or previews served by the backend
+///
+/// fails if the solution is requested, but the level has none, or if the document exceeds `u32::MAX` pixels
+pub fn level_svg(grid: &Grid<Tile<Square>>, options: &SvgOptions) -> Result<String, String> {
+    let solution = options.solution.then(|| grid.solve().next()).flatten();
+    level_svg_with_solution(grid, solution.as_ref(), options)
+}
+
+/// [`level_svg`] with a solution known beforehand, e.g. from the [`SolverCache`](crate::solver::cache::SolverCache)
+///
+/// the solution is only drawn if requested by the options
+pub fn level_svg_with_solution(
+    grid: &Grid<Tile<Square>>,
+    solution: Option<&Grid<Tile<Square>>>,
+    options: &SvgOptions,
+) -> Result<String, String> {
+    let pixels = |cells: usize| {
+        u32::try_from(cells)
+            .ok()
+            .and_then(|cells| cells.checked_mul(options.cell_size))
+            .ok_or_else(|| format!("cell size {} is too large", options.cell_size))
+    };
+    let size = (pixels(grid.columns())?, pixels(grid.rows())?);
+    let theme = Theme {
+        stroke_width: options.stroke_width,
+        ..options.theme
+    };
+    let draw = |grid: &Grid<Tile<Square>>| {
+        grid.coordinates()
+            .map(|c| cell_group(c, &shapes_svg(grid[c], &theme)))
+            .collect::<String>()
+    };
+    let mut style = theme.svg_style();
+    let mut content = draw(grid);
+    if options.solution {
+        let solution = solution.ok_or_else(|| "level has no solution".to_string())?;
+        style += &format!(
+            ".solution .connection{{stroke:{};stroke-width:{};opacity:0.6}}",
+            theme.highlight.to_hex(),
+            (theme.stroke_width / 2).max(1)
+        );
+        content += &format!(r#"<g class="solution">{}</g>"#, draw(solution));
+    }
+    Ok(standalone_svg(
+        grid.dimensions(),
+        Some(size),
+        &style,
+        &content,
+        &theme,
+    ))
+}
+
+fn shapes_svg(tile: Tile<Square>, theme: &Theme) -> String {
+    tile_shapes(tile, theme)
+        .into_iter()
+        .map(Shape::to_svg)
+        .collect()
+}
+
+/// group of the content moved to the cell
+fn cell_group(c: Coordinate<isize>, content: &str) -> String {
+    format!(
+        r#"<g transform="translate({} {})">{content}</g>"#,
+        c.column as u32 * CELL_UNITS,
+        c.row as u32 * CELL_UNITS
+    )
+}
+
+/// document of the content, scaling with its container unless its width and height in pixels are given
+fn standalone_svg(
+    dimensions: Coordinate<usize>,
+    size: Option<(u32, u32)>,
+    style: &str,
+    content: &str,
+    theme: &Theme,
+) -> String {
+    let (width, height) = (
+        dimensions.column as u32 * CELL_UNITS,
+        dimensions.row as u32 * CELL_UNITS,
+    );
+    let size = size
+        .map(|(width, height)| format!(r#" width="{width}" height="{height}""#))
+        .unwrap_or_default();
+    format!(
+        r#"<svg{size} xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {width} {height}"><style>{style}</style><rect width="{width}" height="{height}" fill="{}"/>{content}</svg>"#,
+        theme.background.to_hex()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::model::{
+        coordinate::Coordinate,
+        grid::Grid,
+        tile::{Hex, Square, Tile, Triangle},
+    };
+    use crate::{enumset, tile};
+
+    use super::{
+        channel_grid_svg, grid_svg, hex_outline, hex_tile_shapes, level_svg, tile_shapes, tile_svg,
+        triangle_outline, triangle_tile_shapes, Shape, SvgOptions, Theme,
+    };
+
+    /// ends of the connection lines on the border of the cell
+    fn line_ends(shapes: Vec<Shape>) -> Vec<(u32, u32)> {
+        shapes
+            .into_iter()
+            .filter_map(|shape| match shape {
+                Shape::Line { x1, y1, .. } => Some((x1, y1)),
+                Shape::Circle { .. } => None,
+            })
+            .collect()
+    }
+
+    #[quickcheck]
+    fn polygon_connections_are_drawn_once(hex: Tile<Hex>, triangle: Tile<Triangle>) -> bool {
+        let theme = Theme::DEFAULT;
+        line_ends(hex_tile_shapes(hex, &theme)).len() as u32 == hex.0.len()
+            && [true, false].into_iter().all(|points_up| {
+                line_ends(triangle_tile_shapes(triangle, points_up, &theme)).len() as u32
+                    == triangle.0.len()
+            })
+    }
+
+    #[test]
+    fn connections_end_in_the_middle_of_the_sides() {
+        let theme = Theme::DEFAULT;
+        let hexagon = hex_outline();
+        assert_eq!(
+            line_ends(hex_tile_shapes(Tile::ALL_CONNECTIONS, &theme)).len(),
+            hexagon.len()
+        );
+        assert_eq!(
+            line_ends(hex_tile_shapes(tile!(Hex::East), &theme)),
+            vec![(100, 57)]
+        );
+        // neighboring triangles meet at the middle of their shared side
+        let up = line_ends(triangle_tile_shapes(
+            tile!(Triangle::RightSide),
+            true,
+            &theme,
+        ));
+        let down = line_ends(triangle_tile_shapes(
+            tile!(Triangle::LeftSide),
+            false,
+            &theme,
+        ));
+        assert_eq!((up[0].0 - 50, up[0].1), down[0]);
+        assert_eq!(triangle_outline(true).len(), 3);
+    }
+
+    #[quickcheck]
+    fn connections_are_drawn_once(tile: Tile<Square>) -> bool {
+        let lines = tile_shapes(tile, &Theme::DEFAULT)
+            .into_iter()
+            .filter(|shape| matches!(shape, Shape::Line { .. }))
+            .count();
+        lines as u32 == tile.0.len()
+    }
+
+    #[test]
+    fn dead_ends_end_in_a_ring() {
+        let theme = Theme::DEFAULT;
+        assert_eq!(
+            tile_shapes(tile!(Square::Up), &theme),
+            vec![
+                Shape::Line {
+                    x1: 50,
+                    y1: 0,
+                    x2: 50,
+                    y2: 50 - theme.knob_radius
+                },
+                Shape::Circle {
+                    cx: 50,
+                    cy: 50,
+                    r: theme.knob_radius
+                },
+            ]
+        );
+        assert_eq!(tile_shapes(Tile::NO_CONNECTIONS, &theme), vec![]);
+    }
+
+    #[test]
+    fn images_embed_the_theme() {
+        let theme = Theme::DEFAULT;
+        let image = tile_svg(tile!(Square::Up, Square::Down), &theme);
+        assert!(image.contains(&theme.svg_style()));
+        assert!(image.contains(&theme.background.to_hex()));
+        let level = Grid::filled_with(Coordinate::new(2, 3), Tile::NO_CONNECTIONS);
+        assert!(grid_svg(&level, &theme).contains(r#"viewBox="0 0 300 200""#));
+    }
+
+    #[test]
+    fn channels_are_drawn_in_their_own_groups() {
+        let red = "┏┓\n┗┛".parse::<Grid<Tile<Square>>>().unwrap();
+        let blue = "╺╸\n  ".parse::<Grid<Tile<Square>>>().unwrap();
+        let level = Grid::from_channels([red, blue]).unwrap();
+        let image = channel_grid_svg(&level, &Theme::DEFAULT);
+        assert!(image.contains(&Theme::DEFAULT.channel_style(2)));
+        assert_eq!(image.matches(r#"<g class="channel-1">"#).count(), 4);
+        // eight lines of the loop and two dead ends
+        assert_eq!(image.matches("<line").count(), 10);
+    }
+
+    #[test]
+    fn exported_documents_are_sized_in_pixels_and_overlay_the_solution() {
+        let level = "┗┓\n┛┏".parse::<Grid<Tile<Square>>>().unwrap();
+        let options = SvgOptions {
+            cell_size: 20,
+            stroke_width: 10,
+            ..SvgOptions::default()
+        };
+        let image = level_svg(&level, &options).unwrap();
+        assert!(image.starts_with(r#"<svg width="40" height="40" xmlns="#));
+        assert!(image.contains(r#"viewBox="0 0 200 200""#));
+        assert!(image.contains("stroke-width:10"));
+        assert!(!image.contains("solution"));
+
+        let solved = level_svg(
+            &level,
+            &SvgOptions {
+                solution: true,
+                ..options
+            },
+        )
+        .unwrap();
+        assert!(solved.contains(r#"<g class="solution">"#));
+        assert_eq!(solved.matches("<line").count(), 16);
+
+        let unsolvable = "╺ \n  ".parse::<Grid<Tile<Square>>>().unwrap();
+        assert!(level_svg(&unsolvable, &options).is_ok());
+        assert!(level_svg(
+            &unsolvable,
+            &SvgOptions {
+                solution: true,
+                ..options
+            }
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn documents_too_large_for_their_pixels_are_rejected() {
+        let level = "┗┓\n┛┏".parse::<Grid<Tile<Square>>>().unwrap();
+        let options = SvgOptions {
+            cell_size: 3_000_000_000,
+            ..SvgOptions::default()
+        };
+        assert!(level_svg(&level, &options).is_err());
+        assert!(level_svg(&Grid::EMPTY, &options).is_ok());
+    }
+}
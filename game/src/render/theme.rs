@@ -0,0 +1,148 @@
+//! Render tokens shared by all renderers of levels
+//!
+//! the rasterizer, the SVG renderer and the stylesheet of the frontend derive their colors and
+//! proportions from a [`Theme`], so thumbnails, clips and live boards look the same.
+//! Lengths are given in units of a cell measuring [`CELL_UNITS`] on each side
+
+/// side length of a cell in the units of [`Theme::stroke_width`] and [`Theme::knob_radius`]
+pub const CELL_UNITS: u32 = 100;
+
+/// colors of the channels after the first, which is drawn in the connection color of the theme
+pub const CHANNEL_COLORS: [Rgb; 3] = [
+    Rgb(0xc8, 0x4b, 0x4b),
+    Rgb(0x4b, 0xa8, 0x6a),
+    Rgb(0xd8, 0xa8, 0x32),
+];
+
+/// Color without transparency
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    /// hexadecimal notation of CSS and SVG, e.g. `#555a8d`
+    pub fn to_hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+/// Colors and proportions of rendered tiles
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Theme {
+    pub background: Rgb,
+    pub connection: Rgb,
+    /// background of a highlighted cell, e.g. the tile changed last
+    pub highlight: Rgb,
+    /// width of the connection lines
+    pub stroke_width: u32,
+    /// radius of the ring ending a dead end
+    pub knob_radius: u32,
+}
+
+impl Theme {
+    /// look of the web frontend
+    pub const DEFAULT: Theme = Theme {
+        background: Rgb(0x1d, 0x1d, 0x1d),
+        connection: Rgb(0x55, 0x5a, 0x8d),
+        highlight: Rgb(0xe0, 0xe0, 0xff),
+        stroke_width: 6,
+        knob_radius: 22,
+    };
+
+    /// black ink on white paper, e.g. for puzzle books
+    pub const PRINT: Theme = Theme {
+        background: Rgb(0xff, 0xff, 0xff),
+        connection: Rgb(0x00, 0x00, 0x00),
+        highlight: Rgb(0xc0, 0xc0, 0xc0),
+        stroke_width: 8,
+        knob_radius: 22,
+    };
+
+    /// colors as consecutive RGB triples in the order background, connection, highlight
+    pub const fn palette(&self) -> [u8; 9] {
+        let Theme {
+            background: Rgb(r0, g0, b0),
+            connection: Rgb(r1, g1, b1),
+            highlight: Rgb(r2, g2, b2),
+            ..
+        } = *self;
+        [r0, g0, b0, r1, g1, b1, r2, g2, b2]
+    }
+
+    /// Stylesheet of the classes used by [`crate::render::svg`]
+    pub fn svg_style(&self) -> String {
+        format!(
+            ".connection{{fill:none;stroke:{};stroke-width:{};stroke-linecap:square}}",
+            self.connection.to_hex(),
+            self.stroke_width
+        )
+    }
+
+    /// Stylesheet of the channels of a level with several channels, see [`crate::model::channel`]
+    ///
+    /// earlier channels are drawn wider below the later ones, so connections shared by channels stay visible
+    pub fn channel_style(&self, channels: usize) -> String {
+        (0..channels)
+            .map(|channel| {
+                let color = match channel {
+                    0 => self.connection,
+                    _ => CHANNEL_COLORS[(channel - 1) % CHANNEL_COLORS.len()],
+                };
+                let width = self.stroke_width * 2 * (channels - channel) as u32 / channels as u32;
+                format!(
+                    ".channel-{channel} .connection{{stroke:{};stroke-width:{width}}}",
+                    color.to_hex()
+                )
+            })
+            .collect()
+    }
+
+    /// Stylesheet declaring the tokens as CSS custom properties on the root element
+    ///
+    /// the stroke width is unitless, it applies to SVG elements with a viewBox of [`CELL_UNITS`]
+    pub fn css_properties(&self) -> String {
+        format!(
+            ":root {{\n  --tile-background: {};\n  --tile-connection: {};\n  --tile-highlight: {};\n  --tile-stroke-width: {};\n}}\n",
+            self.background.to_hex(),
+            self.connection.to_hex(),
+            self.highlight.to_hex(),
+            self.stroke_width
+        )
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::DEFAULT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{Rgb, Theme};
+
+    #[test]
+    fn colors_are_written_in_hex() {
+        assert_eq!(Rgb(0x55, 0x5a, 0x8d).to_hex(), "#555a8d");
+        assert_eq!(Rgb(0, 0, 0).to_hex(), "#000000");
+    }
+
+    #[test]
+    fn stylesheets_share_the_tokens() {
+        let theme = Theme::DEFAULT;
+        let connection = theme.connection.to_hex();
+        assert!(theme.svg_style().contains(&format!("stroke:{connection}")));
+        assert!(theme
+            .css_properties()
+            .contains(&format!("--tile-connection: {connection};")));
+        assert!(theme.css_properties().contains("--tile-stroke-width: 6;"));
+        assert_eq!(&theme.palette()[3..6], &[0x55, 0x5a, 0x8d]);
+    }
+
+    #[test]
+    fn later_channels_are_drawn_thinner() {
+        let style = Theme::DEFAULT.channel_style(2);
+        assert!(style.contains(".channel-0 .connection{stroke:#555a8d;stroke-width:12}"));
+        assert!(style.contains(".channel-1 .connection{stroke:#c84b4b;stroke-width:6}"));
+    }
+}
@@ -0,0 +1,134 @@
+//! Save games keep the progress of a player on a level, so they can continue where they left off
+//!
+//! save games are stored as JSON carrying the [`SAVEGAME_VERSION`] they were written with. Levels are stored as
+//! [`LevelDto`], so they follow its schema evolution. Fields added later are optional, save games written before
+//! read them as their default, e.g. no hints used. Save games of unknown versions are rejected
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::{
+    grid::Grid,
+    tile::{Square, Tile},
+};
+
+/// current version of the save games written by this crate
+pub const SAVEGAME_VERSION: u32 = 1;
+
+/// Progress of a player on a level
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SaveGame {
+    pub version: u32,
+    /// level with the tiles rotated by the player so far
+    #[serde(with = "level")]
+    pub current: Grid<Tile<Square>>,
+    /// level as it was scrambled when the player started
+    #[serde(with = "level")]
+    pub start: Grid<Tile<Square>>,
+    /// playing time in milliseconds up to the last move
+    pub elapsed_milliseconds: u64,
+    pub moves: u32,
+    #[serde(default)]
+    pub hints: u32,
+}
+
+impl SaveGame {
+    /// Progress at the start of the level
+    pub fn new(start: Grid<Tile<Square>>) -> Self {
+        SaveGame {
+            version: SAVEGAME_VERSION,
+            current: start.clone(),
+            start,
+            elapsed_milliseconds: 0,
+            moves: 0,
+            hints: 0,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Logical error: save game is not serializable")
+    }
+
+    /// Reads a save game, fails for malformed JSON and unknown versions
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let save_game = serde_json::from_str::<SaveGame>(json).map_err(|e| e.to_string())?;
+        if save_game.version != SAVEGAME_VERSION {
+            return Err(format!(
+                "unsupported save game version {}, expected {SAVEGAME_VERSION}",
+                save_game.version
+            ));
+        }
+        Ok(save_game)
+    }
+}
+
+/// grids stored as [`LevelDto`](crate::dto::LevelDto)
+mod level {
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::dto::LevelDto;
+    use crate::model::{
+        grid::Grid,
+        tile::{Square, Tile},
+    };
+
+    pub fn serialize<S: Serializer>(
+        grid: &Grid<Tile<Square>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        LevelDto::from(grid).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Grid<Tile<Square>>, D::Error> {
+        LevelDto::deserialize(deserializer)?
+            .to_grid()
+            .map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::generator::fastgen::generate;
+    use crate::model::{
+        coordinate::Coordinate,
+        grid::Grid,
+        tile::{Square, Tile},
+    };
+
+    use super::{SaveGame, SAVEGAME_VERSION};
+
+    #[quickcheck]
+    fn json_roundtrip(
+        current: Grid<Tile<Square>>,
+        elapsed_milliseconds: u64,
+        moves: u32,
+        hints: u32,
+    ) -> bool {
+        let save_game = SaveGame {
+            start: current.clone().scramble(moves as u64),
+            current,
+            elapsed_milliseconds,
+            moves,
+            hints,
+            ..SaveGame::new(Grid::EMPTY)
+        };
+        SaveGame::from_json(&save_game.to_json()) == Ok(save_game)
+    }
+
+    #[test]
+    fn unknown_versions_are_rejected_and_later_fields_default() {
+        let save_game = SaveGame::new(generate(Coordinate::new(3, 3), 5));
+        let newer = SaveGame {
+            version: SAVEGAME_VERSION + 1,
+            ..save_game.clone()
+        };
+        assert!(SaveGame::from_json(&newer.to_json()).is_err());
+        assert!(SaveGame::from_json("3 1200\n┏┓\n┗┛").is_err());
+
+        let without_hints = save_game.to_json().replace(r#","hints":0"#, "");
+        assert!(!without_hints.contains("hints"));
+        assert_eq!(SaveGame::from_json(&without_hints), Ok(save_game));
+    }
+}
@@ -0,0 +1,199 @@
+//! Scores of solved levels relative to their par
+//!
+//! the par of a level is the minimal number of rotations solving it, see [`minimal_rotations`].
+//! Solving at par without hints yields [`MAX_SCORE`], every further rotation and every hint lowers the score
+
+use crate::model::{
+    grid::Grid,
+    tile::{Square, Tile},
+};
+use crate::solver::{hint::HintPolicy, rotation_distance};
+
+/// score of a level solved at par without hints
+pub const MAX_SCORE: u32 = 1000;
+
+/// points deducted for the first hint, every further hint costs more, see [`HintPolicy`]
+pub const HINT_PENALTY: u32 = HintPolicy::DEFAULT.base_cost;
+
+/// Minimal number of rotations turning the scrambled level into the solution
+///
+/// players turn tiles clockwise, so these are clockwise rotations, see [`rotation_distance`].
+/// Returns [`None`] if the levels differ in dimensions or in the shape of any tile
+pub fn minimal_rotations(
+    scrambled: &Grid<Tile<Square>>,
+    solution: &Grid<Tile<Square>>,
+) -> Option<u64> {
+    rotation_distance(scrambled, solution).map(|distance| distance.total())
+}
+
+/// Progress of a player on a level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Scoring {
+    /// minimal number of rotations solving the level
+    pub par: u64,
+    pub rotations: u64,
+    pub hints: u32,
+    pub elapsed_milliseconds: u64,
+}
+
+impl Scoring {
+    /// scoring of a level before the first rotation
+    ///
+    /// returns [`None`] if the solution does not belong to the scrambled level
+    pub fn new(scrambled: &Grid<Tile<Square>>, solution: &Grid<Tile<Square>>) -> Option<Self> {
+        minimal_rotations(scrambled, solution).map(Scoring::with_par)
+    }
+
+    pub fn with_par(par: u64) -> Self {
+        Scoring {
+            par,
+            ..Scoring::default()
+        }
+    }
+
+    pub fn record_rotation(&mut self) {
+        self.rotations += 1;
+    }
+
+    pub fn record_hint(&mut self) {
+        self.hints += 1;
+    }
+
+    pub fn record_elapsed(&mut self, elapsed_milliseconds: u64) {
+        self.elapsed_milliseconds = elapsed_milliseconds;
+    }
+
+    /// rotations beyond par
+    pub fn excess_rotations(&self) -> u64 {
+        self.rotations.saturating_sub(self.par)
+    }
+
+    /// [`MAX_SCORE`] scaled by the ratio of par to the rotations made, less the cost of the hints by [`HintPolicy::DEFAULT`]
+    ///
+    /// the elapsed time is tracked for leaderboards, but does not affect the score
+    pub fn score(&self) -> u32 {
+        self.score_with(&HintPolicy::DEFAULT)
+    }
+
+    /// like [`Scoring::score`], with the hints costing points by the policy
+    pub fn score_with(&self, policy: &HintPolicy) -> u32 {
+        let rotations = self.rotations.max(self.par);
+        let efficiency = (MAX_SCORE as u64 * self.par)
+            .checked_div(rotations)
+            .unwrap_or(MAX_SCORE as u64);
+        (efficiency as u32).saturating_sub(policy.total_cost(self.hints))
+    }
+
+    /// Checks that the recorded hints were allowed by the policy
+    ///
+    /// `times` are the milliseconds since the start of the level at which the hints were given
+    pub fn verify_hints(&self, policy: &HintPolicy, times: &[u64]) -> Result<(), String> {
+        if times.len() != self.hints as usize {
+            return Err(format!(
+                "{} hints recorded, but {} hint times given",
+                self.hints,
+                times.len()
+            ));
+        }
+        if times.windows(2).any(|pair| pair[0] > pair[1])
+            || times.iter().any(|&time| time > self.elapsed_milliseconds)
+        {
+            return Err("hint times are not within the playthrough in order".to_string());
+        }
+        policy.verify(times).map_err(|refusal| refusal.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::core::interval::Max;
+    use crate::generator::fastgen::{generate, generate_almost_solved};
+    use crate::model::{
+        coordinate::Coordinate,
+        grid::Grid,
+        tile::{Square, Tile},
+    };
+
+    use crate::solver::hint::HintPolicy;
+
+    use super::{minimal_rotations, Scoring, HINT_PENALTY, MAX_SCORE};
+
+    #[quickcheck]
+    fn solving_at_par_scores_maximal(par: u32) -> bool {
+        let mut scoring = Scoring::with_par(par as u64);
+        scoring.rotations = par as u64;
+        scoring.score() == MAX_SCORE
+    }
+
+    #[quickcheck]
+    fn more_rotations_and_hints_never_raise_the_score(par: u16, extra: u16, hints: u8) -> bool {
+        let mut scoring = Scoring::with_par(par as u64);
+        scoring.rotations = par as u64;
+        let at_par = scoring.score();
+        scoring.rotations += extra as u64;
+        let slower = scoring.score();
+        scoring.hints += hints as u32;
+        let hinted = scoring.score();
+        slower <= at_par && hinted <= slower
+    }
+
+    #[quickcheck]
+    fn par_of_solved_levels_is_zero(grid: Grid<Tile<Square>>) -> bool {
+        minimal_rotations(&grid, &grid) == Some(0)
+    }
+
+    #[quickcheck]
+    fn par_of_almost_solved_levels_is_their_rotations(
+        dimension: Coordinate<Max<6>>,
+        seed: u64,
+    ) -> bool {
+        let dimension = dimension.map(Max::to_usize);
+        let solution = generate(dimension, seed);
+        let scrambled = generate_almost_solved(dimension, 1, seed);
+        // rotating crosses and empty tiles does not change them
+        let expected = solution
+            .as_slice()
+            .iter()
+            .any(|&tile| tile.rotated_clockwise(1) != tile) as u64;
+        Scoring::new(&scrambled, &solution).map(|scoring| scoring.par) == Some(expected)
+    }
+
+    #[test]
+    fn hints_cost_points() {
+        let scoring = Scoring {
+            par: 4,
+            rotations: 8,
+            hints: 1,
+            elapsed_milliseconds: 0,
+        };
+        assert_eq!(scoring.excess_rotations(), 4);
+        assert_eq!(scoring.score(), MAX_SCORE / 2 - HINT_PENALTY);
+    }
+
+    #[test]
+    fn hints_are_verified_against_the_policy() {
+        let scoring = Scoring {
+            par: 4,
+            rotations: 4,
+            hints: 2,
+            elapsed_milliseconds: 30000,
+        };
+        let policy = HintPolicy::DEFAULT;
+        assert_eq!(scoring.verify_hints(&policy, &[1000, 20000]), Ok(()));
+        assert!(scoring.verify_hints(&policy, &[1000, 2000]).is_err());
+        assert!(scoring.verify_hints(&policy, &[1000]).is_err());
+        assert!(scoring.verify_hints(&policy, &[1000, 40000]).is_err());
+        assert_eq!(
+            scoring.score(),
+            MAX_SCORE - policy.base_cost * 2 - policy.cost_increment
+        );
+    }
+
+    #[test]
+    fn levels_of_other_shapes_have_no_par() {
+        let scrambled = Grid::new(Coordinate::new(1, 1), vec![Tile::<Square>::NO_CONNECTIONS]);
+        let solution = Grid::new(Coordinate::new(1, 1), vec![Tile::<Square>::ALL_CONNECTIONS]);
+        assert_eq!(Scoring::new(&scrambled, &solution), None);
+    }
+}
@@ -0,0 +1,334 @@
+//! Headless simulation of players
+//!
+//! bots following a simple [`Policy`] play levels without a frontend, each playthrough is recorded as a [`Scoring`].
+//! The [`Report`] over many playthroughs validates difficulty estimates and serves as synthetic telemetry.
+//! All randomness, including the simulated time per move, is drawn from the [`RngSource`] of a [`GameConfig`]
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::config::{GameConfig, RngSource};
+use crate::model::{
+    cachedboard::CachedBoard,
+    coordinate::Coordinate,
+    gameboard::GameBoard,
+    grid::Grid,
+    tile::{Square, Tile},
+};
+use crate::scoring::Scoring;
+use crate::solver::hint::{Hint, HintEngine};
+
+/// simulated time a player takes for a single rotation
+pub const MOVE_MILLISECONDS: std::ops::RangeInclusive<u64> = 300..=1500;
+
+/// simulated time a player takes to read a hint
+pub const HINT_MILLISECONDS: u64 = 3000;
+
+/// Clockwise rotation of a tile, possibly after asking for a hint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Move {
+    pub coordinate: Coordinate<isize>,
+    /// the player asked for a hint before the rotation
+    pub hinted: bool,
+}
+
+impl Move {
+    pub fn rotate(coordinate: Coordinate<isize>) -> Self {
+        Move {
+            coordinate,
+            hinted: false,
+        }
+    }
+}
+
+/// Strategy of a simulated player
+pub trait Policy {
+    /// next rotation on the level, `None` if the player gives up
+    fn next_move(&mut self, board: &CachedBoard<Square>, rng: &mut RngSource) -> Option<Move>;
+}
+
+/// Rotates uniformly chosen tiles, a baseline without any insight
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct RandomRotator;
+
+/// Rotates the tile answering the most connections, rotates randomly if no rotation helps
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct GreedyEdgeMatcher;
+
+/// Asks for a hint and turns the hinted tile until it is in the suggested state
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct HintFollower {
+    hint: Option<Hint>,
+    engine: HintEngine,
+}
+
+/// tiles changing under rotation, rotating any other tile is a wasted move
+fn rotatable(board: &CachedBoard<Square>) -> Vec<Coordinate<isize>> {
+    let grid = board.grid();
+    grid.coordinates()
+        .filter(|&c| grid[c].superimpose().len() > 1)
+        .collect()
+}
+
+impl Policy for RandomRotator {
+    fn next_move(&mut self, board: &CachedBoard<Square>, rng: &mut RngSource) -> Option<Move> {
+        rotatable(board).choose(rng).copied().map(Move::rotate)
+    }
+}
+
+impl Policy for GreedyEdgeMatcher {
+    fn next_move(&mut self, board: &CachedBoard<Square>, rng: &mut RngSource) -> Option<Move> {
+        let candidates = rotatable(board);
+        let mut trial = board.clone();
+        let unanswered = candidates
+            .iter()
+            .map(|&c| {
+                trial.rotate_clockwise_mut(c).ok()?;
+                let unanswered = trial.unanswered();
+                trial.rotate_counterclockwise_mut(c).ok()?;
+                Some(unanswered)
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let best = unanswered.iter().copied().min()?;
+        let choices = if best < board.unanswered() {
+            candidates
+                .into_iter()
+                .zip(unanswered)
+                .filter(|&(_, n)| n == best)
+                .map(|(c, _)| c)
+                .collect()
+        } else {
+            candidates
+        };
+        choices.choose(rng).copied().map(Move::rotate)
+    }
+}
+
+impl Policy for HintFollower {
+    fn next_move(&mut self, board: &CachedBoard<Square>, _rng: &mut RngSource) -> Option<Move> {
+        let grid = board.grid();
+        if let Some(hint) = self.hint {
+            if grid[hint.coordinate] != hint.suggested_tile {
+                return Some(Move::rotate(hint.coordinate));
+            }
+        }
+        let hint = self.engine.hint(grid).ok()?;
+        self.hint = Some(hint);
+        Some(Move {
+            coordinate: hint.coordinate,
+            hinted: true,
+        })
+    }
+}
+
+/// Outcome of a bot playing a level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Playthrough {
+    pub scoring: Scoring,
+    pub solved: bool,
+}
+
+/// Plays the level until it is solved, the policy gives up or `max_moves` rotations are made
+///
+/// the par of the scoring is zero for unsolvable levels
+pub fn play(
+    level: &Grid<Tile<Square>>,
+    policy: &mut dyn Policy,
+    config: &mut GameConfig,
+    max_moves: u64,
+) -> Playthrough {
+    let mut scoring = level
+        .solve()
+        .next()
+        .and_then(|solution| Scoring::new(level, &solution))
+        .unwrap_or_default();
+    let mut board = CachedBoard::new(level.clone());
+    let mut elapsed = 0;
+
+    while !board.is_solved() && scoring.rotations < max_moves {
+        let next = match policy.next_move(&board, &mut config.rng) {
+            Some(next) => next,
+            None => break,
+        };
+        if next.hinted {
+            scoring.record_hint();
+            elapsed += HINT_MILLISECONDS;
+        }
+        if board.rotate_clockwise_mut(next.coordinate).is_err() {
+            break;
+        }
+        scoring.record_rotation();
+        elapsed += config.rng.gen_range(MOVE_MILLISECONDS);
+    }
+
+    scoring.record_elapsed(elapsed);
+    Playthrough {
+        scoring,
+        solved: board.is_solved(),
+    }
+}
+
+/// Summary of measurements, e.g. the rotations of playthroughs
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Distribution {
+    pub min: u64,
+    pub median: u64,
+    pub mean: f64,
+    pub max: u64,
+}
+
+impl Distribution {
+    /// summary of the values, all zero if there are none
+    pub fn of(values: impl IntoIterator<Item = u64>) -> Self {
+        let mut values = values.into_iter().collect::<Vec<_>>();
+        if values.is_empty() {
+            return Distribution::default();
+        }
+        values.sort_unstable();
+        Distribution {
+            min: values[0],
+            median: values[values.len() / 2],
+            mean: values.iter().sum::<u64>() as f64 / values.len() as f64,
+            max: values[values.len() - 1],
+        }
+    }
+}
+
+/// Summary of the playthroughs of a policy
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub playthroughs: Vec<Playthrough>,
+    /// rotations of the solved playthroughs
+    pub moves: Distribution,
+    /// elapsed time of the solved playthroughs
+    pub milliseconds: Distribution,
+}
+
+impl Report {
+    pub fn new(playthroughs: Vec<Playthrough>) -> Self {
+        let solved = || playthroughs.iter().filter(|p| p.solved).map(|p| p.scoring);
+        Report {
+            moves: Distribution::of(solved().map(|s| s.rotations)),
+            milliseconds: Distribution::of(solved().map(|s| s.elapsed_milliseconds)),
+            playthroughs,
+        }
+    }
+
+    pub fn solved(&self) -> usize {
+        self.playthroughs.iter().filter(|p| p.solved).count()
+    }
+
+    /// fraction of solved playthroughs, zero without playthroughs
+    pub fn solve_rate(&self) -> f64 {
+        if self.playthroughs.is_empty() {
+            0.0
+        } else {
+            self.solved() as f64 / self.playthroughs.len() as f64
+        }
+    }
+}
+
+/// Plays every level with a fresh policy, see [`play`]
+pub fn simulate<P: Policy>(
+    levels: &[Grid<Tile<Square>>],
+    mut policy: impl FnMut() -> P,
+    config: &mut GameConfig,
+    max_moves: u64,
+) -> Report {
+    Report::new(
+        levels
+            .iter()
+            .map(|level| play(level, &mut policy(), config, max_moves))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::config::GameConfig;
+    use crate::core::interval::Max;
+    use crate::generator::fastgen::generate;
+    use crate::model::{
+        coordinate::Coordinate,
+        grid::Grid,
+        tile::{Square, Tile},
+    };
+
+    use super::{
+        play, simulate, Distribution, GreedyEdgeMatcher, HintFollower, RandomRotator,
+        HINT_MILLISECONDS,
+    };
+
+    #[quickcheck]
+    fn hint_follower_solves_generated_levels(dimension: Coordinate<Max<5>>, seed: u64) -> bool {
+        let level = generate(dimension.map(Max::to_usize), seed).scramble(seed);
+        let playthrough = play(
+            &level,
+            &mut HintFollower::default(),
+            &mut GameConfig::seeded(seed),
+            u64::MAX,
+        );
+        playthrough.solved
+            && playthrough.scoring.rotations >= playthrough.scoring.par
+            && playthrough.scoring.elapsed_milliseconds
+                >= playthrough.scoring.hints as u64 * HINT_MILLISECONDS
+    }
+
+    #[quickcheck]
+    fn simulations_are_reproducible(seed: u64) -> bool {
+        let levels = (0..4)
+            .map(|n| {
+                generate(Coordinate::new(3, 3), seed.wrapping_add(n)).scramble(seed.wrapping_add(n))
+            })
+            .collect::<Vec<_>>();
+        let report = |seed| {
+            simulate(
+                &levels,
+                || GreedyEdgeMatcher,
+                &mut GameConfig::seeded(seed),
+                50,
+            )
+        };
+        report(seed) == report(seed)
+    }
+
+    #[test]
+    fn moves_are_capped() {
+        let level = "┛┗\n┓┏".parse::<Grid<Tile<Square>>>().unwrap();
+        let report = simulate(
+            &[level.clone(), level],
+            || RandomRotator,
+            &mut GameConfig::seeded(0),
+            0,
+        );
+        assert_eq!(report.solved(), 0);
+        assert_eq!(report.solve_rate(), 0.0);
+        assert!(report
+            .playthroughs
+            .iter()
+            .all(|p| p.scoring.rotations == 0 && p.scoring.par == 8));
+    }
+
+    #[test]
+    fn greedy_matcher_answers_connections() {
+        // rotating the dead end on the right answers both connections
+        let level = "╺╻".parse::<Grid<Tile<Square>>>().unwrap();
+        let playthrough = play(
+            &level,
+            &mut GreedyEdgeMatcher,
+            &mut GameConfig::seeded(0),
+            10,
+        );
+        assert!(playthrough.solved);
+        assert_eq!(playthrough.scoring.rotations, 1);
+    }
+
+    #[test]
+    fn distributions_summarize_values() {
+        let distribution = Distribution::of([4, 1, 3, 2]);
+        assert_eq!((distribution.min, distribution.max), (1, 4));
+        assert_eq!(distribution.median, 3);
+        assert_eq!(distribution.mean, 2.5);
+        assert_eq!(Distribution::of([]), Distribution::default());
+    }
+}
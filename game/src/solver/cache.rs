@@ -0,0 +1,224 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::model::{
+    coordinate::Coordinate,
+    grid::Grid,
+    symmetry::{canonical_hash, canonical_tiles},
+    tile::{from_glyph, glyph, Square, Tile},
+};
+
+use super::difficulty::{rate, Difficulty};
+
+/// solutions are only counted up to this limit, enough to decide uniqueness
+pub const SOLUTION_LIMIT: usize = 2;
+
+/// number of entries kept by default before the oldest ones are evicted
+pub const DEFAULT_CAPACITY: usize = 10_000;
+
+/// file extension of cache entries
+const ENTRY_EXTENSION: &str = "entry";
+
+/// Results of solving a level, independent of the current rotation of its tiles
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolverResult {
+    /// number of solutions, counted up to [`SOLUTION_LIMIT`]
+    pub solutions: usize,
+    pub solution: Option<Grid<Tile<Square>>>,
+    pub difficulty: Difficulty,
+}
+
+impl SolverResult {
+    /// Invokes the solver
+    pub fn compute(grid: &Grid<Tile<Square>>) -> Self {
+        let mut solutions = grid.solve().take(SOLUTION_LIMIT);
+        let solution = solutions.next();
+        SolverResult {
+            solutions: solution.iter().count() + solutions.count(),
+            solution,
+            difficulty: rate(grid),
+        }
+    }
+}
+
+/// Persistent cache of [`SolverResult`]s in a directory, one file per level
+///
+/// entries are keyed by [`canonical_hash`], once more than `capacity` entries are stored the least recently
+/// written ones are evicted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolverCache {
+    directory: PathBuf,
+    capacity: usize,
+}
+
+impl SolverCache {
+    pub fn new(directory: impl Into<PathBuf>, capacity: usize) -> Self {
+        SolverCache {
+            directory: directory.into(),
+            capacity,
+        }
+    }
+
+    /// Cache shared by all tools of the user in the temporary directory
+    pub fn in_temp_dir() -> Self {
+        Self::new(
+            std::env::temp_dir().join("infinity-loop-solver-cache"),
+            DEFAULT_CAPACITY,
+        )
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    fn entry_path(&self, grid: &Grid<Tile<Square>>) -> PathBuf {
+        self.directory
+            .join(format!("{:016x}.{ENTRY_EXTENSION}", canonical_hash(grid)))
+    }
+
+    /// Returns the cached result, unreadable entries and hash collisions count as miss
+    pub fn get(&self, grid: &Grid<Tile<Square>>) -> Option<SolverResult> {
+        let data = fs::read_to_string(self.entry_path(grid)).ok()?;
+        let (level, result) = parse_entry(&data)?;
+        (level == canonical_tiles(grid)).then_some(result)
+    }
+
+    /// Stores the result and evicts the oldest entries exceeding the capacity
+    pub fn insert(&self, grid: &Grid<Tile<Square>>, result: &SolverResult) -> io::Result<()> {
+        fs::create_dir_all(&self.directory)?;
+        fs::write(
+            self.entry_path(grid),
+            serialize_entry(&canonical_tiles(grid), result),
+        )?;
+        self.evict()
+    }
+
+    /// Consults the cache before invoking the solver
+    ///
+    /// failing to store the result is not an error, the cache is only an optimization
+    pub fn get_or_compute(&self, grid: &Grid<Tile<Square>>) -> SolverResult {
+        self.get(grid).unwrap_or_else(|| {
+            let result = SolverResult::compute(grid);
+            if let Err(e) = self.insert(grid, &result) {
+                log::warn!("cannot write solver cache: {e}");
+            }
+            result
+        })
+    }
+
+    fn evict(&self) -> io::Result<()> {
+        let mut entries = fs::read_dir(&self.directory)?
+            .filter_map(Result::ok)
+            .filter(|entry| {
+                entry.path().extension().and_then(|e| e.to_str()) == Some(ENTRY_EXTENSION)
+            })
+            .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+            .collect::<Vec<_>>();
+        if entries.len() > self.capacity {
+            entries.sort();
+            let excess = entries.len() - self.capacity;
+            for (_, path) in entries.into_iter().take(excess) {
+                fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn glyphs(grid: &Grid<Tile<Square>>) -> String {
+    grid.as_slice().iter().copied().map(glyph).collect()
+}
+
+fn grid_from_glyphs(dimensions: Coordinate<usize>, glyphs: &str) -> Option<Grid<Tile<Square>>> {
+    let tiles = glyphs.chars().map(from_glyph).collect::<Option<Vec<_>>>()?;
+    (tiles.len() == dimensions.product()).then(|| Grid::new(dimensions, tiles))
+}
+
+/// line based format of a cache entry, tiles are stored as glyphs in row-major order
+///
+/// ```text
+/// size 2 2
+/// level ┗┗┗┗
+/// solutions 1
+/// difficulty easy
+/// solution ┏┓┗┛
+/// ```
+fn serialize_entry(level: &Grid<Tile<Square>>, result: &SolverResult) -> String {
+    let mut entry = format!(
+        "size {} {}\nlevel {}\nsolutions {}\ndifficulty {}\n",
+        level.rows(),
+        level.columns(),
+        glyphs(level),
+        result.solutions,
+        result.difficulty
+    );
+    if let Some(solution) = &result.solution {
+        entry += &format!("solution {}\n", glyphs(solution));
+    }
+    entry
+}
+
+/// inverse of [`serialize_entry`], returns [`None`] for malformed entries
+fn parse_entry(data: &str) -> Option<(Grid<Tile<Square>>, SolverResult)> {
+    let mut lines = data.lines();
+    let mut field = |name: &str| lines.next()?.strip_prefix(name)?.strip_prefix(' ');
+
+    let mut size = field("size")?.split(' ').map(str::parse::<usize>);
+    let dimensions = Coordinate::new(size.next()?.ok()?, size.next()?.ok()?);
+    let level = grid_from_glyphs(dimensions, field("level")?)?;
+    let solutions = field("solutions")?.parse().ok()?;
+    let difficulty = field("difficulty")?.parse().ok()?;
+    let solution = match field("solution") {
+        Some(glyphs) => Some(grid_from_glyphs(dimensions, glyphs)?),
+        None => None,
+    };
+
+    Some((
+        level,
+        SolverResult {
+            solutions,
+            solution,
+            difficulty,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::core::interval::{Interval, Max};
+    use crate::generator::fastgen::generate;
+    use crate::model::{coordinate::Coordinate, symmetry::canonical_tiles};
+
+    use super::{parse_entry, serialize_entry, SolverCache, SolverResult};
+
+    #[quickcheck]
+    fn parse_entry_is_inverse_of_serialize_entry(dimension: Coordinate<Max<6>>, seed: u64) -> bool {
+        let grid = generate(dimension.map(Interval::to_usize), seed).scramble(seed);
+        let level = canonical_tiles(&grid);
+        let result = SolverResult::compute(&grid);
+        parse_entry(&serialize_entry(&level, &result)) == Some((level, result))
+    }
+
+    #[test]
+    fn cache_returns_stored_result_and_evicts_beyond_capacity() {
+        let directory = std::env::temp_dir().join(format!(
+            "infinity-loop-solver-cache-test-{}",
+            std::process::id()
+        ));
+        let cache = SolverCache::new(&directory, 1);
+        let first = generate(Coordinate::new(3, 3), 1);
+        let second = generate(Coordinate::new(4, 4), 2);
+
+        let result = cache.get_or_compute(&first);
+        assert_eq!(cache.get(&first.clone().scramble(7)), Some(result));
+        cache.get_or_compute(&second);
+        let entries = std::fs::read_dir(&directory)
+            .map(Iterator::count)
+            .unwrap_or(0);
+        std::fs::remove_dir_all(&directory).ok();
+        assert_eq!(entries, 1);
+    }
+}
@@ -0,0 +1,181 @@
+//! Explanations why a level is unsolvable
+//!
+//! a conflict is a minimal set of tiles which cannot be solved together, whatever the other tiles are.
+//! Tiles outside the set are relaxed to any shape in any rotation. The set is found by deletion:
+//! starting from all tiles, a tile is dropped if the remaining tiles still conflict, so every tile of the result is necessary
+
+use crate::core::{enumset::EnumSet, finite::all_enums_ascending};
+use crate::model::{
+    coordinate::Coordinate,
+    grid::Grid,
+    tile::{Square, Tile},
+};
+
+use super::hint::{position_name, shape_name};
+use super::propagationsolver::Superposition;
+
+/// Minimal set of tiles without a solution together with the changes resolving it
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Conflict {
+    /// conflicting tiles in row-major order
+    pub tiles: Vec<Coordinate<isize>>,
+    /// single tile changes making the level solvable, each on its own
+    pub fixes: Vec<Fix>,
+}
+
+/// Replacement of a conflicting tile by another shape
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fix {
+    pub coordinate: Coordinate<isize>,
+    /// new shape of the tile, in an arbitrary rotation
+    pub tile: Tile<Square>,
+}
+
+impl Conflict {
+    /// names the conflicting tiles by shape and position, e.g. "the cross in the top-left"
+    pub fn describe(&self, level: &Grid<Tile<Square>>) -> Vec<String> {
+        self.tiles
+            .iter()
+            .map(|&index| tile_name(level, index))
+            .collect()
+    }
+}
+
+impl Fix {
+    /// e.g. "change the cross in the top-left to a junction"
+    pub fn describe(&self, level: &Grid<Tile<Square>>) -> String {
+        format!(
+            "change {} to a {}",
+            tile_name(level, self.coordinate),
+            shape_name(self.tile)
+        )
+    }
+
+    /// level with the tile replaced
+    pub fn apply(&self, level: &Grid<Tile<Square>>) -> Grid<Tile<Square>> {
+        let mut fixed = level.clone();
+        if let Some(tile) = fixed.get_mut(self.coordinate) {
+            *tile = self.tile;
+        }
+        fixed
+    }
+}
+
+fn tile_name(level: &Grid<Tile<Square>>, index: Coordinate<isize>) -> String {
+    format!(
+        "the {} {}",
+        shape_name(level[index]),
+        position_name(level, index)
+    )
+}
+
+/// Finds a minimal set of conflicting tiles, `None` if the level is solvable
+pub fn find_conflict(level: &Grid<Tile<Square>>) -> Option<Conflict> {
+    let mut kept = level.map(|_| true);
+    if is_solvable_keeping(level, &kept) {
+        return None;
+    }
+    for index in level.coordinates() {
+        kept[index] = false;
+        if is_solvable_keeping(level, &kept) {
+            kept[index] = true;
+        }
+    }
+    let tiles = level
+        .coordinates()
+        .filter(|&index| kept[index])
+        .collect::<Vec<_>>();
+    let fixes = tiles
+        .iter()
+        .flat_map(|&coordinate| {
+            shapes()
+                .filter(move |&tile| tile.superimpose() != level[coordinate].superimpose())
+                .map(move |tile| Fix { coordinate, tile })
+        })
+        .filter(|fix| fix.apply(level).solve().next().is_some())
+        .collect();
+    Some(Conflict { tiles, fixes })
+}
+
+/// one tile per shape, the smallest rotation
+fn shapes() -> impl Iterator<Item = Tile<Square>> {
+    all_enums_ascending::<Tile<Square>>()
+        .filter(|&tile| tile.superimpose().iter().next() == Some(tile))
+}
+
+/// whether the level has a solution if the tiles not kept may be anything
+fn is_solvable_keeping(level: &Grid<Tile<Square>>, kept: &Grid<bool>) -> bool {
+    Grid::init(level.dimensions(), |index| -> Superposition<Square> {
+        if kept[index] {
+            level[index].superimpose()
+        } else {
+            EnumSet::FULL
+        }
+    })
+    .with_sentinels(Tile::NO_CONNECTIONS.into())
+    .solutions()
+    .next()
+    .is_some()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::model::{
+        grid::Grid,
+        tile::{Square, Tile},
+    };
+
+    use super::{find_conflict, is_solvable_keeping};
+
+    #[test]
+    fn solvable_levels_have_no_conflict() {
+        let level = "┛┗\n┓┏".parse::<Grid<Tile<Square>>>().unwrap();
+        assert_eq!(find_conflict(&level), None);
+    }
+
+    #[test]
+    fn conflicts_are_described_with_fixes() {
+        let level = "╺╸╸".parse::<Grid<Tile<Square>>>().unwrap();
+        let conflict = find_conflict(&level).unwrap();
+        assert_eq!(conflict.tiles, level.coordinates().collect::<Vec<_>>());
+        assert_eq!(
+            conflict.describe(&level)[0],
+            "the end in the top-left".to_string()
+        );
+        let straight = conflict
+            .fixes
+            .iter()
+            .find(|fix| fix.coordinate.column == 1 && fix.tile.0.len() == 2)
+            .unwrap();
+        assert_eq!(
+            straight.describe(&level),
+            "change the end on the top edge in column 2 to a straight"
+        );
+    }
+
+    #[quickcheck]
+    fn conflicts_are_minimal_and_fixes_solve(level: Grid<Tile<Square>>) -> bool {
+        // small levels keep the repeated solving fast
+        let level = Grid::init(level.dimensions().map(|x| x.min(3)), |c| level[c]);
+        match find_conflict(&level) {
+            None => level.solve().next().is_some(),
+            Some(conflict) => {
+                let kept = |dropped: Option<usize>| {
+                    let mut kept = level.map(|_| false);
+                    for (n, &index) in conflict.tiles.iter().enumerate() {
+                        kept[index] = Some(n) != dropped;
+                    }
+                    kept
+                };
+                !is_solvable_keeping(&level, &kept(None))
+                    && (0..conflict.tiles.len())
+                        .all(|n| is_solvable_keeping(&level, &kept(Some(n))))
+                    && conflict
+                        .fixes
+                        .iter()
+                        .all(|fix| fix.apply(&level).solve().next().is_some())
+            }
+        }
+    }
+}
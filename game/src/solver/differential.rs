@@ -0,0 +1,171 @@
+//! Differential testing of the solvers
+//!
+//! independent implementations must agree on the solutions of a level:
+//!
+//! 1. the propagation solver, see [`Grid::solve`]
+//! 2. a brute-force enumerator trying every rotation of every tile, feasible for tiny levels only
+//! 3. the SAT pipeline, which encodes the level with [`cnf::level_to_formula`], enumerates the models with a
+//!    naive backtracking SAT solver and decodes them with [`cnf::model_to_tiles`]
+//! 4. the embedded SAT solver, which excludes every solution found with a blocking clause, see [`solutions_with_sat`]
+//!
+//! a disagreement points to a bug in one of them, most likely in the deductions of the propagation solver
+
+use std::collections::BTreeSet;
+
+use crate::core::interval::Interval;
+use crate::generator::fastgen::generate;
+#[cfg(feature = "sat")]
+use crate::model::cnf;
+use crate::model::{
+    coordinate::Coordinate,
+    gameboard::GameBoard,
+    grid::Grid,
+    tile::{Square, Tile},
+};
+#[cfg(feature = "sat")]
+use crate::solver::sat::solutions_with_sat;
+
+/// tiny level, so enumerating all rotations stays feasible: at most 4^6 candidates
+type TinyDimension = Coordinate<Interval<1, 2>>;
+
+fn tiny_level(dimension: TinyDimension, tiles: Vec<Tile<Square>>) -> Grid<Tile<Square>> {
+    let dimension = Coordinate::new(dimension.row.to_usize(), dimension.column.to_usize() + 1);
+    Grid::init(dimension, |c| {
+        tiles
+            .get((c.row * dimension.column as isize + c.column) as usize)
+            .copied()
+            .unwrap_or(Tile::NO_CONNECTIONS)
+    })
+}
+
+/// solutions as reported by the propagation solver
+fn propagation_solutions(grid: &Grid<Tile<Square>>) -> Vec<Grid<Tile<Square>>> {
+    grid.solve().collect()
+}
+
+/// tries every combination of tile rotations
+fn brute_force_solutions(grid: &Grid<Tile<Square>>) -> Vec<Grid<Tile<Square>>> {
+    grid.as_slice()
+        .iter()
+        .fold(vec![vec![]], |candidates: Vec<Vec<Tile<Square>>>, tile| {
+            candidates
+                .into_iter()
+                .flat_map(|candidate| {
+                    tile.superimpose().into_iter().map(move |rotated| {
+                        let mut candidate = candidate.clone();
+                        candidate.push(rotated);
+                        candidate
+                    })
+                })
+                .collect()
+        })
+        .into_iter()
+        .map(|tiles| Grid::new(grid.dimensions(), tiles))
+        .filter(GameBoard::is_solved)
+        .collect()
+}
+
+/// enumerates all models of the clauses over the variables `1..=variables` by backtracking
+///
+/// each model is given as signed literals in DIMACS format
+#[cfg(feature = "sat")]
+fn all_models(clauses: &[Vec<i32>], variables: usize) -> Vec<Vec<i32>> {
+    fn falsified(clause: &[i32], assignment: &[i32]) -> bool {
+        clause.iter().all(|literal| {
+            let variable = literal.unsigned_abs() as usize;
+            variable <= assignment.len() && assignment[variable - 1] == -literal
+        })
+    }
+
+    fn extend(
+        clauses: &[Vec<i32>],
+        variables: usize,
+        assignment: &mut Vec<i32>,
+        models: &mut Vec<Vec<i32>>,
+    ) {
+        if clauses.iter().any(|clause| falsified(clause, assignment)) {
+            return;
+        }
+        if assignment.len() == variables {
+            models.push(assignment.clone());
+            return;
+        }
+        let variable = assignment.len() as i32 + 1;
+        for literal in [variable, -variable] {
+            assignment.push(literal);
+            extend(clauses, variables, assignment, models);
+            assignment.pop();
+        }
+    }
+
+    let mut models = vec![];
+    extend(clauses, variables, &mut vec![], &mut models);
+    models
+}
+
+/// solutions as found through the SAT pipeline
+#[cfg(feature = "sat")]
+fn sat_solutions(grid: &Grid<Tile<Square>>) -> Vec<Grid<Tile<Square>>> {
+    let formula = cnf::level_to_formula(grid);
+    all_models(&formula.clauses, formula.num_vars)
+        .into_iter()
+        .map(|model| {
+            let tiles = cnf::model_to_tiles(&model).expect("decodable model");
+            Grid::new(grid.dimensions(), tiles)
+        })
+        .collect()
+}
+
+/// solutions as set, reports duplicates as disagreement through the length
+fn distinct(solutions: &[Grid<Tile<Square>>]) -> BTreeSet<&Grid<Tile<Square>>> {
+    solutions.iter().collect()
+}
+
+/// checks that all solvers agree on the solutions of the level
+fn solvers_agree(grid: &Grid<Tile<Square>>) -> bool {
+    let expected = brute_force_solutions(grid);
+    let propagation = propagation_solutions(grid);
+    let agree =
+        propagation.len() == expected.len() && distinct(&propagation) == distinct(&expected);
+
+    #[cfg(feature = "sat")]
+    let agree = agree && {
+        let sat = sat_solutions(grid);
+        let blocked = solutions_with_sat(grid)
+            .collect::<Result<Vec<_>, _>>()
+            .expect("solvable by the embedded SAT solver");
+        sat.len() == expected.len()
+            && distinct(&sat) == distinct(&expected)
+            && blocked.len() == expected.len()
+            && distinct(&blocked) == distinct(&expected)
+    };
+
+    agree
+}
+
+#[quickcheck]
+fn solvers_agree_on_arbitrary_levels(dimension: TinyDimension, tiles: Vec<Tile<Square>>) -> bool {
+    solvers_agree(&tiny_level(dimension, tiles))
+}
+
+#[quickcheck]
+fn solvers_agree_on_generated_levels(dimension: TinyDimension, seed: u64) -> bool {
+    let dimension = dimension.map(Interval::to_usize) + Coordinate::new(0, 1);
+    solvers_agree(&generate(dimension, seed).scramble(seed))
+}
+
+/// levels consisting of a single tile shape have many symmetric solutions
+#[quickcheck]
+fn solvers_agree_on_uniform_levels(dimension: TinyDimension, tile: Tile<Square>) -> bool {
+    solvers_agree(&tiny_level(dimension, vec![tile; 6]))
+}
+
+#[test]
+fn solvers_agree_on_builtin_examples() {
+    // solvable with a unique, two and no solutions
+    for level in ["┏┓\n┗┛", "┃┃\n┃┃", "╋"] {
+        let grid = crate::model::parser::parse_level(level, crate::model::parser::unicode_to_tile)
+            .expect("valid level");
+        assert!(solvers_agree(&grid), "solvers disagree on\n{grid}");
+    }
+}
@@ -0,0 +1,215 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::model::{
+    grid::Grid,
+    tile::{Square, Tile},
+};
+
+use super::propagationsolver::{most_superimposed_states, Sentinel};
+
+/// Ordinal scale for how hard a level is to solve
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Difficulty {
+    /// every tile follows from logical deductions on a small board
+    #[default]
+    Easy,
+    /// every tile follows from logical deductions
+    Medium,
+    /// some tiles require guessing
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard];
+
+    /// next higher difficulty, hard levels stay hard
+    pub fn harder(self) -> Self {
+        match self {
+            Difficulty::Easy => Difficulty::Medium,
+            Difficulty::Medium | Difficulty::Hard => Difficulty::Hard,
+        }
+    }
+
+    /// next lower difficulty, easy levels stay easy
+    pub fn easier(self) -> Self {
+        match self {
+            Difficulty::Easy | Difficulty::Medium => Difficulty::Easy,
+            Difficulty::Hard => Difficulty::Medium,
+        }
+    }
+}
+
+/// boards with at most this many bits of entropy are considered small
+const EASY_ENTROPY: f64 = 40.0;
+
+/// Measurements of solving a level with the propagation solver
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Analysis {
+    /// tiles with several rotations which are determined by logical deductions alone
+    pub forced_deductions: usize,
+    /// guesses made by the solver until finding the first solution
+    pub branch_points: usize,
+    /// bits needed to describe the rotations of all tiles, i.e. the sum of `log2(rotations)` over all tiles
+    pub entropy: f64,
+}
+
+/// Solves the level and measures how it was solved
+///
+/// independent of the current rotation of the tiles
+pub fn analyze(grid: &Grid<Tile<Square>>) -> Analysis {
+    let superposition = grid.with_sentinels(Tile::NO_CONNECTIONS).superimpose();
+    let entropy = entropy(&superposition);
+    let minimized = superposition.minimize();
+    let forced_deductions = grid
+        .coordinates()
+        .filter(|&c| {
+            grid[c].superimpose().len() > 1 && minimized.0[minimized.sentinel_index(c)].len() == 1
+        })
+        .count();
+
+    // depth-first search in the order of the solver until the first solution
+    let mut branch_points = 0;
+    let mut stack = vec![minimized];
+    while let Some(candidate) = stack.pop() {
+        let candidate = candidate.minimize();
+        if candidate.extract_if_collapsed().is_some() {
+            break;
+        }
+        if let Some(candidate) = candidate.check_no_empty_superposition() {
+            branch_points += 1;
+            stack.extend(candidate.branch(most_superimposed_states));
+        }
+    }
+
+    Analysis {
+        forced_deductions,
+        branch_points,
+        entropy,
+    }
+}
+
+fn entropy(superposition: &Sentinel<Square>) -> f64 {
+    superposition
+        .extract_grid()
+        .into_iter()
+        .map(|states| (states.len() as f64).log2())
+        .sum()
+}
+
+/// Rates the difficulty of the level
+///
+/// levels requiring guesses are hard, the others are rated by their entropy.
+/// Equivalent to rating the [`analyze`]d level, but skips the search for a solution
+///
+/// independent of the current rotation of the tiles
+pub fn rate(grid: &Grid<Tile<Square>>) -> Difficulty {
+    let superposition = grid.with_sentinels(Tile::NO_CONNECTIONS).superimpose();
+    let entropy = entropy(&superposition);
+    let minimized = superposition.minimize();
+    // the solver branches at least once iff the deductions neither solve the level nor end in a contradiction
+    let guessing = minimized.extract_if_collapsed().is_none()
+        && minimized.check_no_empty_superposition().is_some();
+    Difficulty::from(Analysis {
+        forced_deductions: 0,
+        branch_points: guessing as usize,
+        entropy,
+    })
+}
+
+impl From<Analysis> for Difficulty {
+    fn from(analysis: Analysis) -> Self {
+        if analysis.branch_points > 0 {
+            Difficulty::Hard
+        } else if analysis.entropy <= EASY_ENTROPY {
+            Difficulty::Easy
+        } else {
+            Difficulty::Medium
+        }
+    }
+}
+
+impl Display for Difficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "easy" => Ok(Difficulty::Easy),
+            "medium" => Ok(Difficulty::Medium),
+            "hard" => Ok(Difficulty::Hard),
+            _ => Err(format!("parsing error: unknown difficulty '{s}'")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::generator::fastgen::generate;
+    use crate::model::coordinate::Coordinate;
+
+    use crate::core::interval::{Interval, Max};
+
+    use crate::solver::hint::remaining_states;
+
+    use super::{analyze, rate, Difficulty};
+
+    #[quickcheck]
+    fn rating_is_independent_of_rotation(dimension: Coordinate<Max<10>>, seed: u64) -> bool {
+        let grid = generate(dimension.map(Interval::to_usize), seed);
+        rate(&grid) == rate(&grid.clone().scramble(seed))
+    }
+
+    #[quickcheck]
+    fn rating_agrees_with_analysis(dimension: Coordinate<Max<8>>, seed: u64) -> bool {
+        let grid = generate(dimension.map(Interval::to_usize), seed);
+        rate(&grid) == Difficulty::from(analyze(&grid))
+    }
+
+    #[quickcheck]
+    fn guessing_is_necessary_iff_deductions_leave_ambiguity(
+        dimension: Coordinate<Max<8>>,
+        seed: u64,
+    ) -> bool {
+        let grid = generate(dimension.map(Interval::to_usize), seed);
+        let determined = remaining_states(&grid)
+            .into_iter()
+            .all(|states| states.len() == 1);
+        determined == (analyze(&grid).branch_points == 0)
+    }
+
+    #[test]
+    fn ambiguous_level_is_hard() {
+        // the dead ends pair up either horizontally or vertically
+        let analysis = analyze(&" ╺╸\n ╺╸".parse().unwrap());
+        assert_eq!(analysis.forced_deductions, 0);
+        assert!(analysis.branch_points > 0);
+        assert_eq!(analysis.entropy, 8.0);
+        assert_eq!(Difficulty::from(analysis), Difficulty::Hard);
+    }
+
+    #[test]
+    fn small_determined_level_is_easy() {
+        let analysis = analyze(&"┏┓\n┗┛".parse().unwrap());
+        assert_eq!(analysis.forced_deductions, 4);
+        assert_eq!(analysis.branch_points, 0);
+        assert_eq!(Difficulty::from(analysis), Difficulty::Easy);
+    }
+
+    #[test]
+    fn display_then_parse_is_identity() {
+        for difficulty in Difficulty::ALL {
+            assert_eq!(difficulty.to_string().parse(), Ok(difficulty));
+        }
+    }
+}
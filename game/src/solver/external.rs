@@ -0,0 +1,206 @@
+//! Solving levels with an external SAT solver, e.g. minisat or kissat, for boards too large for the builtin solver
+//!
+//! the level is encoded with [`level_to_cnf`] and piped to the solver on its standard input.
+//! The model is read from its standard output in one of the formats
+//!
+//! - SAT competition: `s SATISFIABLE` followed by value lines `v 1 -2 3 ... 0` (kissat, cadical, glucose)
+//! - minisat result file: `SAT` followed by a line `1 -2 3 ... 0`, e.g. with the arguments `/dev/stdin /dev/stdout`
+//!
+//! and decoded with [`model_to_level`]
+
+use std::{
+    io::Write,
+    path::PathBuf,
+    process::{Command, Stdio},
+};
+
+use crate::model::{
+    cnf::{level_to_cnf, model_to_level},
+    grid::Grid,
+    tile::{Square, Tile},
+};
+
+/// environment variable naming the solver binary, used if no solver is configured otherwise
+pub const SOLVER_ENV: &str = "INFINITY_LOOP_SAT_SOLVER";
+
+/// Solver binary together with its arguments
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ExternalSolver {
+    pub program: PathBuf,
+    pub args: Vec<String>,
+}
+
+impl ExternalSolver {
+    pub fn new(program: impl Into<PathBuf>) -> Self {
+        ExternalSolver {
+            program: program.into(),
+            args: vec![],
+        }
+    }
+
+    pub fn with_args<S: Into<String>>(mut self, args: impl IntoIterator<Item = S>) -> Self {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    /// Solver named by [`SOLVER_ENV`], if set
+    pub fn from_env() -> Option<Self> {
+        std::env::var_os(SOLVER_ENV).map(ExternalSolver::new)
+    }
+
+    /// Finds a solution of the level
+    ///
+    /// `Ok(None)` if the solver proves the level unsolvable
+    pub fn solve(&self, level: &Grid<Tile<Square>>) -> Result<Option<Grid<Tile<Square>>>, String> {
+        let output = self.run(&level_to_cnf(level)?)?;
+        match parse_model(&output)? {
+            Some(model) => model_to_level(level, &model)
+                .map(Some)
+                .map_err(|e| e.to_string()),
+            None => Ok(None),
+        }
+    }
+
+    /// pipes the formula through the solver and returns its standard output
+    fn run(&self, formula: &str) -> Result<String, String> {
+        let program = self.program.display();
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("cannot start solver '{program}': {e}"))?;
+        // dropping stdin closes the pipe, so the solver sees the end of the formula
+        child
+            .stdin
+            .take()
+            .ok_or_else(|| format!("cannot write to solver '{program}'"))?
+            .write_all(formula.as_bytes())
+            .map_err(|e| format!("cannot write to solver '{program}': {e}"))?;
+        // exit codes differ between solvers (10 and 20 for SAT competition solvers), so only the output counts
+        let output = child
+            .wait_with_output()
+            .map_err(|e| format!("solver '{program}' failed: {e}"))?;
+        String::from_utf8(output.stdout)
+            .map_err(|_| format!("solver '{program}' printed invalid UTF-8"))
+    }
+}
+
+/// Parses the model printed by a solver
+///
+/// returns the signed literals in DIMACS format, `None` if the formula is unsatisfiable
+pub fn parse_model(output: &str) -> Result<Option<Vec<i32>>, String> {
+    let mut satisfiable = None;
+    let mut literals = vec![];
+    for line in output.lines().map(str::trim) {
+        let values = match line {
+            "s SATISFIABLE" | "SAT" => {
+                satisfiable = Some(true);
+                continue;
+            }
+            "s UNSATISFIABLE" | "UNSAT" => {
+                satisfiable = Some(false);
+                continue;
+            }
+            _ if line.starts_with("v ") => &line[1..],
+            // minisat prints the model on the line after its verdict
+            _ if satisfiable == Some(true)
+                && line.starts_with(|c: char| c == '-' || c.is_ascii_digit()) =>
+            {
+                line
+            }
+            _ => continue,
+        };
+        for value in values.split_whitespace() {
+            let literal = value
+                .parse::<i32>()
+                .map_err(|_| format!("parsing error: invalid literal '{value}' in model"))?;
+            if literal != 0 {
+                literals.push(literal);
+            }
+        }
+    }
+    match satisfiable {
+        Some(true) => Ok(Some(literals)),
+        Some(false) => Ok(None),
+        None => Err("parsing error: solver reported neither SAT nor UNSAT".into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::model::{
+        grid::Grid,
+        parser::{parse_level, unicode_to_tile},
+        tile::{Square, Tile},
+    };
+
+    use crate::model::cnf::model_to_level;
+
+    use super::parse_model;
+
+    fn level(level: &str) -> Grid<Tile<Square>> {
+        parse_level(level, unicode_to_tile).expect("valid level")
+    }
+
+    /// model of the solved level in DIMACS format, variables per tile are up, right, down, left
+    fn model_of(solution: &Grid<Tile<Square>>) -> Vec<i32> {
+        solution
+            .as_slice()
+            .iter()
+            .zip(0..)
+            .flat_map(|(tile, index)| {
+                [Square::Up, Square::Right, Square::Down, Square::Left]
+                    .into_iter()
+                    .zip(1..)
+                    .map(move |(direction, offset)| {
+                        let variable = index * 4 + offset;
+                        if tile.0.contains(direction) {
+                            variable
+                        } else {
+                            -variable
+                        }
+                    })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn competition_output_is_parsed() {
+        let output = "c kissat\ns SATISFIABLE\nv 1 -2 3\nv -4 0\nc done\n";
+        assert_eq!(parse_model(output), Ok(Some(vec![1, -2, 3, -4])));
+        assert_eq!(parse_model("c kissat\ns UNSATISFIABLE\n"), Ok(None));
+    }
+
+    #[test]
+    fn minisat_output_is_parsed() {
+        assert_eq!(
+            parse_model("SAT\n-1 2 -3 4 0\n"),
+            Ok(Some(vec![-1, 2, -3, 4]))
+        );
+        assert_eq!(parse_model("UNSAT\n"), Ok(None));
+    }
+
+    #[test]
+    fn missing_verdict_is_rejected() {
+        assert!(parse_model("c interrupted\n").is_err());
+    }
+
+    #[test]
+    fn model_of_solution_decodes_to_solution() {
+        let solution = level("┏┓\n┗┛");
+        let scrambled = level("┛┗\n┓┏");
+        assert_eq!(
+            model_to_level(&scrambled, &model_of(&solution)),
+            Ok(solution)
+        );
+    }
+
+    #[test]
+    fn model_of_other_level_is_rejected() {
+        let solution = level("┏┓\n┗┛");
+        assert!(model_to_level(&level("┃┃\n┃┃"), &model_of(&solution)).is_err());
+    }
+}
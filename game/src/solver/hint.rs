@@ -1,45 +1,112 @@
+use std::fmt::Display;
+
 use crate::model::{
     coordinate::Coordinate,
     grid::Grid,
-    tile::{Square, Tile},
+    tile::{glyph, Square, Tile},
 };
 
+use crate::core::{enumset::EnumSet, finite::all_enums_ascending};
+
 use super::propagationsolver::{
-    iter_fix, most_superimposed_states, propagate_restrictions_to_all_neighbors2,
+    iter_fix, most_superimposed_states, propagate_restrictions_to_all_neighbors2, SentinelGrid,
+    Superposition,
 };
 
 // algorithm:
 // 1. solve level with a trace of the collapsed superpositions in order
-// 2. return first trace entry which is unequal to current configuration
+// 2. score the trace entries unequal to current configuration by their obviousness
+// 3. return the most obvious entry, falling back to the first one
+
+/// tile solved in a single step of a solving trace together with its solved state
+pub type SolveStep = (Coordinate<isize>, Tile<Square>);
+
+/// Hint for the player, explaining why the tile must be turned to the suggested state
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Hint {
+    pub coordinate: Coordinate<isize>,
+    pub reason: HintReason,
+    /// solved state of the tile
+    pub suggested_tile: Tile<Square>,
+}
+
+/// Neighbor constraints forcing the state of a hinted tile
+///
+/// constraints stem from the border and from tiles solved before in the solving trace
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HintReason {
+    /// the state follows from the tiles within the radius, see [`deduction_radius`]
+    Deduced {
+        radius: usize,
+        /// directions facing the border or a solved tile not pointing back
+        forbidden: EnumSet<Square>,
+        /// directions facing a solved tile pointing back
+        required: EnumSet<Square>,
+    },
+    /// the state is only determined by trying out alternatives
+    Branched,
+}
+
+impl Display for HintReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            HintReason::Deduced {
+                forbidden,
+                required,
+                ..
+            } => match (forbidden.is_empty(), required.is_empty()) {
+                (false, false) => write!(
+                    f,
+                    "cannot point {} and must point {}",
+                    direction_names(forbidden, " or "),
+                    direction_names(required, " and ")
+                ),
+                (false, true) => write!(f, "cannot point {}", direction_names(forbidden, " or ")),
+                (true, false) => write!(f, "must point {}", direction_names(required, " and ")),
+                (true, true) => write!(f, "follows from the tiles further away"),
+            },
+            HintReason::Branched => write!(f, "follows only from trying out alternatives"),
+        }
+    }
+}
 
 /// Generates a trace of the successively solved tiles
 ///
 /// conceptually independent of solving algorithm
 ///
-/// deterministic, can be memoized
-pub fn generate_solving_trace(grid: &Grid<Tile<Square>>) -> Vec<(Coordinate<isize>, Tile<Square>)> {
+/// deterministic, can be memoized, see [`HintEngine`] for repeated hints on the same level
+pub fn generate_solving_trace(grid: &Grid<Tile<Square>>) -> Vec<SolveStep> {
+    search_solving_trace(grid, &mut vec![])
+}
+
+/// branches of the search that together lead to a contradiction, in the coordinates of the level
+pub type Nogood = Vec<SolveStep>;
+
+/// depth-first search for the solving trace, skipping branches containing a nogood
+///
+/// every newly found dead end is added to the nogoods. The trace does not depend on the nogoods,
+/// as dead ends never contribute to it
+fn search_solving_trace(grid: &Grid<Tile<Square>>, nogoods: &mut Vec<Nogood>) -> Vec<SolveStep> {
     let mut stack = vec![(
         grid.with_sentinels(Tile::NO_CONNECTIONS).superimpose(),
         vec![],
+        vec![],
     )];
 
-    loop {
-        let v = stack.pop();
-        if v.is_none() {
-            return vec![];
-        }
-        let (mut sentinel, mut trace) = v.unwrap();
+    while let Some((mut sentinel, mut trace, branches)) = stack.pop() {
         sentinel = iter_fix(
             sentinel,
             |s| {
-                s.0.coordinates().into_iter().fold(s.clone(), |g, c| {
+                s.0.coordinates().fold(s.clone(), |g, c| {
                     let (s_new, v) = propagate_restrictions_to_all_neighbors2(g, c, |old, new| {
                         old.len() != 1 && new.len() == 1
                     });
-                    trace.extend(
-                        v.into_iter()
-                            .map(|c| (c - 1, s_new.0[c].unwrap_if_singleton().unwrap())),
-                    ); // grid vs sentinelgrid indexing
+                    trace.extend(v.into_iter().map(|c| {
+                        (
+                            s_new.grid_index(c),
+                            s_new.0[c].unwrap_if_singleton().unwrap(),
+                        )
+                    })); // grid vs sentinelgrid indexing
                     s_new
                 })
             },
@@ -50,40 +117,446 @@ pub fn generate_solving_trace(grid: &Grid<Tile<Square>>) -> Vec<(Coordinate<isiz
         }
 
         // distinguish between no and several solutions
-        if let Some(grid) = sentinel.clone().check_no_empty_superposition() {
-            let c = most_superimposed_states(&grid);
-            let v = grid.branch(most_superimposed_states).into_iter().map(|g| {
-                let mut new_trace = trace.clone();
-                new_trace.push((c - 1, g.0[c].unwrap_if_singleton().unwrap()));
-                (g, new_trace)
-            });
-            stack.extend(v);
+        match sentinel.check_no_empty_superposition() {
+            Some(grid) => {
+                let c = most_superimposed_states(&grid);
+                let v = grid
+                    .branch(most_superimposed_states)
+                    .into_iter()
+                    .map(|g| {
+                        let step = (g.grid_index(c), g.0[c].unwrap_if_singleton().unwrap());
+                        let mut new_trace = trace.clone();
+                        new_trace.push(step);
+                        let mut new_branches = branches.clone();
+                        new_branches.push(step);
+                        (g, new_trace, new_branches)
+                    })
+                    .filter(|(_, _, branches)| {
+                        !nogoods
+                            .iter()
+                            .any(|nogood| nogood.iter().all(|step| branches.contains(step)))
+                    });
+                stack.extend(v);
+            }
+            None if !branches.is_empty() => nogoods.push(branches),
+            None => {}
         }
     }
+    vec![]
+}
+
+/// Hints for a single level, remembering the dead ends of the search across hints
+///
+/// the search only depends on the shapes of the tiles, so the dead ends found for one hint are skipped
+/// for all further hints until the shapes change, e.g. because another level is played
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct HintEngine {
+    /// level the nogoods belong to, with every tile in its superposition of rotations
+    shapes: Grid<Superposition<Square>>,
+    nogoods: Vec<Nogood>,
+}
+
+impl HintEngine {
+    /// Generates the trace of the successively solved tiles like [`generate_solving_trace`]
+    pub fn solving_trace(&mut self, grid: &Grid<Tile<Square>>) -> Vec<SolveStep> {
+        let shapes = grid.map(Tile::superimpose);
+        if shapes != self.shapes {
+            self.shapes = shapes;
+            self.nogoods.clear();
+        }
+        search_solving_trace(grid, &mut self.nogoods)
+    }
+
+    /// Returns hint for the level like [`get_hint`]
+    pub fn hint(&mut self, grid: &Grid<Tile<Square>>) -> Result<Hint, String> {
+        let trace = self.solving_trace(grid);
+        get_hint(grid, trace)
+    }
+
+    /// combinations of branches known to lead to a contradiction, in the order they were found
+    pub fn learned_constraints(&self) -> &[Nogood] {
+        &self.nogoods
+    }
 }
 
 /// Returns hint based on given trace
+///
+/// prefers the most obvious deduction, which requires looking at the smallest part of the board,
+/// see [`deduction_radius`]. Ties and tiles only determined through branching follow the order of the trace
 pub fn get_hint(
     grid: &Grid<Tile<Square>>,
     trace: Vec<(Coordinate<isize>, Tile<Square>)>,
-) -> Result<Coordinate<isize>, String> {
-    trace
-        .into_iter()
-        .find(|(c, t)| grid[*c] != *t)
-        .map(|(c, _)| c)
-        .ok_or_else(|| "No hint available".into())
+) -> Result<Hint, String> {
+    // steps of the trace unequal to the current configuration
+    let open = (0..trace.len())
+        .filter(|&step| grid[trace[step].0] != trace[step].1)
+        .collect::<Vec<_>>();
+    // increasing the radius stepwise scores only as many deductions as necessary
+    let deduced = (0..=max_radius(grid)).find_map(|radius| {
+        open.iter()
+            .copied()
+            .find(|&step| is_deducible_within(grid, trace[step].0, radius))
+            .map(|step| (step, radius))
+    });
+    let (step, reason) = match deduced {
+        Some((step, radius)) => {
+            let mut known = initially_known(grid);
+            for &(index, tile) in &trace[..step] {
+                known[index] = Some(tile);
+            }
+            let (forbidden, required) = neighbor_constraints(&known, trace[step].0);
+            let reason = HintReason::Deduced {
+                radius,
+                forbidden,
+                required,
+            };
+            (step, reason)
+        }
+        None => {
+            let step = open
+                .first()
+                .ok_or_else(|| "No hint available".to_string())?;
+            (*step, HintReason::Branched)
+        }
+    };
+    let (coordinate, suggested_tile) = trace[step];
+    Ok(Hint {
+        coordinate,
+        reason,
+        suggested_tile,
+    })
+}
+
+/// Rules restricting the hints of a level
+///
+/// evaluated wherever hints are granted or accounted for, so the frontend and the verification of scores agree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HintPolicy {
+    /// minimal number of seconds between two hints
+    pub cooldown_seconds: u32,
+    /// hints available per level, `None` for unlimited hints
+    pub max_hints: Option<u32>,
+    /// points the first hint costs
+    pub base_cost: u32,
+    /// points every further hint costs more than its predecessor
+    pub cost_increment: u32,
+}
+
+/// Reason for refusing a hint, see [`HintPolicy::check`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HintRefusal {
+    /// the previous hint is too recent
+    CoolingDown { remaining_milliseconds: u64 },
+    /// all hints of the level are used up
+    Exhausted { max_hints: u32 },
+}
+
+impl Display for HintRefusal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            HintRefusal::CoolingDown {
+                remaining_milliseconds,
+            } => write!(
+                f,
+                "next hint available in {} seconds",
+                remaining_milliseconds.saturating_add(999) / 1000
+            ),
+            HintRefusal::Exhausted { max_hints } => {
+                write!(f, "all {max_hints} hints of this level are used")
+            }
+        }
+    }
+}
+
+impl HintPolicy {
+    /// policy of the game
+    pub const DEFAULT: HintPolicy = HintPolicy {
+        cooldown_seconds: 10,
+        max_hints: Some(5),
+        base_cost: 100,
+        cost_increment: 50,
+    };
+
+    /// hints at any time for a constant cost
+    pub const UNRESTRICTED: HintPolicy = HintPolicy {
+        cooldown_seconds: 0,
+        max_hints: None,
+        base_cost: 100,
+        cost_increment: 0,
+    };
+
+    /// Checks if another hint may be given at the time, given the times of the previous hints of the level
+    ///
+    /// times are in milliseconds, measured from any fixed point in time
+    pub fn check(&self, given: &[u64], now: u64) -> Result<(), HintRefusal> {
+        if let Some(max_hints) = self.max_hints.filter(|&max| given.len() >= max as usize) {
+            return Err(HintRefusal::Exhausted { max_hints });
+        }
+        let cooldown = self.cooldown_seconds as u64 * 1000;
+        match given
+            .iter()
+            .max()
+            .map(|&last| last.saturating_add(cooldown))
+        {
+            Some(ready) if now < ready => Err(HintRefusal::CoolingDown {
+                remaining_milliseconds: ready - now,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks if every hint of a recorded playthrough was allowed, times in ascending order
+    pub fn verify(&self, times: &[u64]) -> Result<(), HintRefusal> {
+        (0..times.len()).try_for_each(|hint| self.check(&times[..hint], times[hint]))
+    }
+
+    /// points the hint with the 0-based number costs
+    pub fn cost(&self, hint: u32) -> u32 {
+        self.base_cost
+            .saturating_add(hint.saturating_mul(self.cost_increment))
+    }
+
+    /// points the given number of hints cost together
+    pub fn total_cost(&self, hints: u32) -> u32 {
+        (0..hints).fold(0, |total, hint| total.saturating_add(self.cost(hint)))
+    }
+}
+
+impl Default for HintPolicy {
+    fn default() -> Self {
+        HintPolicy::DEFAULT
+    }
+}
+
+/// Returns hint for the level if the policy allows another one, see [`HintPolicy::check`] and [`get_hint`]
+pub fn request_hint(
+    grid: &Grid<Tile<Square>>,
+    policy: &HintPolicy,
+    given: &[u64],
+    now: u64,
+) -> Result<Hint, String> {
+    policy
+        .check(given, now)
+        .map_err(|refusal| refusal.to_string())?;
+    get_hint(grid, generate_solving_trace(grid))
+}
+
+/// Returns the radius of the smallest square window around the tile from which its solved state follows
+///
+/// tiles outside the window are disregarded as if they could be in any state, so the radius measures how much
+/// of the board one must look at to make the deduction. Only the shapes of the tiles matter, the score is
+/// invariant under rotating any tile
+///
+/// `None` if the tile is not determined by logical deductions alone
+pub fn deduction_radius(grid: &Grid<Tile<Square>>, index: Coordinate<isize>) -> Option<usize> {
+    (0..=max_radius(grid)).find(|&radius| is_deducible_within(grid, index, radius))
+}
+
+/// radius of a window covering the whole board from any position
+fn max_radius(grid: &Grid<Tile<Square>>) -> usize {
+    grid.rows().max(grid.columns())
+}
+
+fn is_deducible_within(grid: &Grid<Tile<Square>>, index: Coordinate<isize>, radius: usize) -> bool {
+    // the window is enclosed by a ring of unknown tiles, which is empty beyond the border of the level
+    let ring = radius as isize + 1;
+    let window = Grid::init(Coordinate::of(2 * radius + 3), |c| {
+        let position = index + c - ring;
+        let on_ring = c.row == 0 || c.column == 0 || c.row == 2 * ring || c.column == 2 * ring;
+        match grid.get(position) {
+            None => Superposition::from(Tile::NO_CONNECTIONS),
+            Some(_) if on_ring => EnumSet::FULL,
+            Some(tile) => tile.superimpose(),
+        }
+    });
+    SentinelGrid(window).minimize().0[Coordinate::of(ring)].len() == 1
+}
+
+/// Returns the states each tile can still be in after applying all logical deductions
+///
+/// explains why a designed level is ambiguous: tiles with more than one remaining state
+/// are not determined by the surrounding tiles alone
+pub fn remaining_states(grid: &Grid<Tile<Square>>) -> Grid<Superposition<Square>> {
+    grid.with_sentinels(Tile::NO_CONNECTIONS)
+        .superimpose()
+        .minimize()
+        .extract_grid()
+}
+
+/// Natural-language explanations of a solving trace, e.g. for screen readers and tutorials
+pub trait Describe {
+    /// one sentence per step, explaining the step by the border and the tiles solved before
+    ///
+    /// "Because the corner tile in the top-left cannot point up or left, it must be the ┏ corner"
+    fn describe(&self, grid: &Grid<Tile<Square>>) -> Vec<String>;
+}
+
+impl Describe for [SolveStep] {
+    fn describe(&self, grid: &Grid<Tile<Square>>) -> Vec<String> {
+        let mut known = initially_known(grid);
+        self.iter()
+            .map(|&(index, tile)| {
+                let description = describe_step(grid, &known, index, tile);
+                if let Some(state) = known.get_mut(index) {
+                    *state = Some(tile);
+                }
+                description
+            })
+            .collect()
+    }
+}
+
+/// tiles without rotations are known from the start
+fn initially_known(grid: &Grid<Tile<Square>>) -> Grid<Option<Tile<Square>>> {
+    grid.map(|tile| (tile.superimpose().len() == 1).then_some(tile))
+}
+
+/// forbidden and required directions of the tile imposed by the border and the known neighbors
+fn neighbor_constraints(
+    known: &Grid<Option<Tile<Square>>>,
+    index: Coordinate<isize>,
+) -> (EnumSet<Square>, EnumSet<Square>) {
+    let mut forbidden = EnumSet::EMPTY;
+    let mut required = EnumSet::EMPTY;
+    for direction in all_enums_ascending::<Square>() {
+        let neighbor = known.neighbor_index(index, direction);
+        match known.get(neighbor) {
+            None => forbidden.insert(direction),
+            Some(Some(other)) if other.0.contains(-direction) => required.insert(direction),
+            Some(Some(_)) => forbidden.insert(direction),
+            Some(None) => false,
+        };
+    }
+    (forbidden, required)
+}
+
+fn describe_step(
+    grid: &Grid<Tile<Square>>,
+    known: &Grid<Option<Tile<Square>>>,
+    index: Coordinate<isize>,
+    tile: Tile<Square>,
+) -> String {
+    let (forbidden, required) = neighbor_constraints(known, index);
+    let shape = shape_name(tile);
+    let subject = format!("the {shape} tile {}", position_name(grid, index));
+    let conclusion = format!("it must be the {} {shape}", glyph(tile));
+    match (forbidden.is_empty(), required.is_empty()) {
+        (false, false) => format!(
+            "Because {subject} cannot point {} and must point {}, {conclusion}",
+            direction_names(forbidden, " or "),
+            direction_names(required, " and ")
+        ),
+        (false, true) => format!(
+            "Because {subject} cannot point {}, {conclusion}",
+            direction_names(forbidden, " or ")
+        ),
+        (true, false) => format!(
+            "Because {subject} must point {}, {conclusion}",
+            direction_names(required, " and ")
+        ),
+        (true, true) => format!("Considering the tiles further away from {subject}, {conclusion}"),
+    }
+}
+
+/// name of the shape of the tile independent of its rotation, e.g. "corner"
+pub fn shape_name(tile: Tile<Square>) -> &'static str {
+    match tile.0.len() {
+        0 => "empty",
+        1 => "end",
+        2 if tile.0.contains(Square::Up) == tile.0.contains(Square::Down) => "straight",
+        2 => "corner",
+        3 => "junction",
+        _ => "cross",
+    }
+}
+
+fn direction_names(directions: EnumSet<Square>, conjunction: &str) -> String {
+    directions
+        .iter()
+        .map(direction_name)
+        .collect::<Vec<_>>()
+        .join(conjunction)
+}
+
+fn direction_name(direction: Square) -> &'static str {
+    match direction {
+        Square::Up => "up",
+        Square::Right => "right",
+        Square::Down => "down",
+        Square::Left => "left",
+    }
+}
+
+/// position of the tile relative to the border, e.g. "in the top-left", rows and columns count from 1
+pub fn position_name(grid: &Grid<Tile<Square>>, index: Coordinate<isize>) -> String {
+    let top = index.row == 0;
+    let bottom = index.row + 1 == grid.rows() as isize;
+    let left = index.column == 0;
+    let right = index.column + 1 == grid.columns() as isize;
+    let (row, column) = (index.row + 1, index.column + 1);
+    match (top, bottom, left, right) {
+        (true, _, true, _) => "in the top-left".into(),
+        (true, _, _, true) => "in the top-right".into(),
+        (_, true, true, _) => "in the bottom-left".into(),
+        (_, true, _, true) => "in the bottom-right".into(),
+        (true, _, _, _) => format!("on the top edge in column {column}"),
+        (_, true, _, _) => format!("on the bottom edge in column {column}"),
+        (_, _, true, _) => format!("on the left edge in row {row}"),
+        (_, _, _, true) => format!("on the right edge in row {row}"),
+        _ => format!("in row {row}, column {column}"),
+    }
 }
 
 #[cfg(test)]
 mod test {
     use crate::{
         generator::fastgen::generate,
-        model::{coordinate::Coordinate, tile::Tile},
+        model::{
+            coordinate::Coordinate,
+            tile::{glyph, Square, Tile},
+        },
     };
 
+    use crate::core::enumset::EnumSet;
     use crate::core::interval::{Interval, Max};
 
-    use super::generate_solving_trace;
+    use super::{
+        deduction_radius, generate_solving_trace, get_hint, remaining_states, Describe, Hint,
+        HintEngine, HintPolicy, HintReason, HintRefusal,
+    };
+
+    #[quickcheck]
+    fn engine_agrees_with_the_solving_trace(dimension: Coordinate<Max<6>>, seed: u64) -> bool {
+        let grid = generate(dimension.map(Interval::to_usize), seed).scramble(seed);
+        let trace = generate_solving_trace(&grid);
+        let mut engine = HintEngine::default();
+        engine.solving_trace(&grid) == trace
+            && engine.solving_trace(&grid) == trace
+            && engine.hint(&grid) == get_hint(&grid, trace.clone())
+            && engine
+                .learned_constraints()
+                .iter()
+                .all(|nogood| !nogood.iter().all(|step| trace.contains(step)))
+    }
+
+    #[test]
+    fn dead_ends_are_remembered_across_hints() {
+        let solved = generate(Coordinate::new(5, 5), 137);
+        let mut engine = HintEngine::default();
+        engine.hint(&solved.clone().scramble(1)).unwrap();
+        let learned = engine.learned_constraints().to_vec();
+        assert!(!learned.is_empty());
+
+        engine.hint(&solved.clone().scramble(2)).unwrap();
+        assert_eq!(
+            engine.learned_constraints(),
+            learned,
+            "nothing new to learn"
+        );
+
+        engine.hint(&generate(Coordinate::new(5, 5), 1)).ok();
+        assert_ne!(engine.learned_constraints(), learned, "another level");
+    }
 
     #[quickcheck]
     fn number_of_hints(dimension: Coordinate<Max<20>>, seed: u64) -> bool {
@@ -95,4 +568,142 @@ mod test {
             .count()
             == trace.len()
     }
+
+    #[quickcheck]
+    fn hint_is_most_obvious_open_deduction(dimension: Coordinate<Max<6>>, seed: u64) -> bool {
+        let solved = generate(dimension.map(Interval::to_usize), seed);
+        let grid = solved.clone().scramble(seed);
+        let trace = generate_solving_trace(&grid);
+        let open = trace
+            .iter()
+            .filter(|(c, t)| grid[*c] != *t)
+            .map(|(c, _)| *c)
+            .collect::<Vec<_>>();
+        match get_hint(&grid, trace) {
+            Ok(Hint { coordinate, .. }) => {
+                let radius = deduction_radius(&grid, coordinate);
+                open.contains(&coordinate)
+                    && open.iter().all(|&c| {
+                        deduction_radius(&grid, c)
+                            .map_or(true, |r| radius.map_or(false, |h| h <= r))
+                    })
+            }
+            Err(_) => open.is_empty(),
+        }
+    }
+
+    #[test]
+    fn corner_is_deducible_from_the_tile_alone() {
+        let grid = "┛┗\n┓┏".parse().unwrap();
+        assert_eq!(deduction_radius(&grid, Coordinate::new(0, 0)), Some(0));
+        let hint = get_hint(&grid, generate_solving_trace(&grid)).unwrap();
+        assert_eq!(
+            hint,
+            Hint {
+                coordinate: Coordinate::new(0, 0),
+                reason: HintReason::Deduced {
+                    radius: 0,
+                    forbidden: EnumSet::from_iter([Square::Up, Square::Left]),
+                    required: EnumSet::EMPTY,
+                },
+                suggested_tile: Tile(EnumSet::from_iter([Square::Right, Square::Down])),
+            }
+        );
+        assert_eq!(hint.reason.to_string(), "cannot point up or left");
+    }
+
+    #[quickcheck]
+    fn hints_suggest_the_traced_state(dimension: Coordinate<Max<6>>, seed: u64) -> bool {
+        let grid = generate(dimension.map(Interval::to_usize), seed).scramble(seed);
+        let trace = generate_solving_trace(&grid);
+        match get_hint(&grid, trace.clone()) {
+            Ok(hint) => {
+                trace.contains(&(hint.coordinate, hint.suggested_tile))
+                    && grid[hint.coordinate] != hint.suggested_tile
+                    && match hint.reason {
+                        HintReason::Deduced { radius, .. } => {
+                            deduction_radius(&grid, hint.coordinate) == Some(radius)
+                        }
+                        HintReason::Branched => deduction_radius(&grid, hint.coordinate).is_none(),
+                    }
+            }
+            Err(_) => true,
+        }
+    }
+
+    #[test]
+    fn corners_are_described_by_the_border() {
+        let grid = "┛┗\n┓┏".parse().unwrap();
+        let trace = generate_solving_trace(&grid);
+        let descriptions = trace.describe(&grid);
+        let top_left = trace
+            .iter()
+            .position(|&(c, _)| c == Coordinate::new(0, 0))
+            .unwrap();
+        assert_eq!(
+            descriptions[top_left],
+            "Because the corner tile in the top-left cannot point up or left, it must be the ┏ corner"
+        );
+    }
+
+    #[quickcheck]
+    fn every_step_is_described(dimension: Coordinate<Max<8>>, seed: u64) -> bool {
+        let grid = generate(dimension.map(Interval::to_usize), seed).scramble(seed);
+        let trace = generate_solving_trace(&grid);
+        let descriptions = trace.describe(&grid);
+        descriptions.len() == trace.len()
+            && descriptions
+                .iter()
+                .zip(&trace)
+                .all(|(description, &(_, tile))| description.contains(glyph(tile)))
+    }
+
+    // minimization never discards the state of a solved level
+    #[quickcheck]
+    fn remaining_states_contain_solution(dimension: Coordinate<Max<20>>, seed: u64) -> bool {
+        let grid = generate(dimension.map(Interval::to_usize), seed);
+        let remaining = remaining_states(&grid);
+        grid.coordinates().all(|c| {
+            remaining[c].contains(grid[c]) && remaining[c].is_subset(grid[c].superimpose())
+        })
+    }
+
+    #[test]
+    fn hints_respect_cooldown_and_limit() {
+        let policy = HintPolicy {
+            cooldown_seconds: 10,
+            max_hints: Some(2),
+            ..HintPolicy::DEFAULT
+        };
+        assert_eq!(policy.check(&[], 0), Ok(()));
+        assert_eq!(
+            policy.check(&[0], 4000),
+            Err(HintRefusal::CoolingDown {
+                remaining_milliseconds: 6000
+            })
+        );
+        assert_eq!(policy.check(&[0], 10000), Ok(()));
+        assert_eq!(
+            policy.check(&[0, 10000], 60000),
+            Err(HintRefusal::Exhausted { max_hints: 2 })
+        );
+        assert_eq!(policy.verify(&[0, 10000]), Ok(()));
+        assert!(policy.verify(&[0, 9999]).is_err());
+    }
+
+    #[quickcheck]
+    fn hints_cost_increasingly(hints: u8) -> bool {
+        let policy = HintPolicy::DEFAULT;
+        let hints = hints as u32;
+        policy.cost(hints + 1) >= policy.cost(hints)
+            && policy.total_cost(hints + 1) == policy.total_cost(hints) + policy.cost(hints)
+            && HintPolicy::UNRESTRICTED.total_cost(hints)
+                == hints * HintPolicy::UNRESTRICTED.base_cost
+    }
+
+    #[quickcheck]
+    fn unrestricted_policy_allows_every_playthrough(mut times: Vec<u64>) -> bool {
+        times.sort_unstable();
+        HintPolicy::UNRESTRICTED.verify(&times).is_ok()
+    }
 }
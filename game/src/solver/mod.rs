@@ -1,2 +1,16 @@
+pub mod cache;
+pub mod conflict;
+#[cfg(test)]
+mod differential;
+pub mod difficulty;
+#[cfg(feature = "external")]
+pub mod external;
 pub mod hint;
 pub mod propagationsolver;
+pub mod rotation;
+pub mod snapshot;
+pub mod trace;
+#[cfg(feature = "sat")]
+pub mod sat;
+
+pub use rotation::rotation_distance;
@@ -1,14 +1,12 @@
 use crate::model::tile::Square::{Down, Left, Right, Up};
 use core::fmt::Debug;
 
-use std::{
-    fmt::Display,
-    hash::Hash,
-    ops::{Neg, Not},
-};
+use std::{cmp::Reverse, collections::VecDeque, fmt::Display, hash::Hash, ops::Neg};
 
 use quickcheck::Arbitrary;
+use rand::seq::IteratorRandom;
 
+use crate::config::RngSource;
 use crate::core::{
     enummap::EnumMap,
     enumset::EnumSet,
@@ -16,11 +14,15 @@ use crate::core::{
     lattice::{BoundedLattice, BoundedLatticeExt},
 };
 
+#[cfg(feature = "sat")]
+use crate::model::cnf;
 use crate::model::{
-    cnf,
+    channel::Channels,
     coordinate::Coordinate,
+    gameboard::GameBoard,
     grid::Grid,
-    tile::{Square, Tile},
+    lock::Cell,
+    tile::{Square, Tile, Topology},
 };
 
 ///! This file contains a solver algorithm
@@ -91,10 +93,33 @@ impl<A: Clone> SentinelGrid<A> {
     pub const EMPTY: Self = SentinelGrid(Grid::EMPTY);
 
     /// Deletes the layer of sentinel values and returns the original grid
+    ///
+    /// a torus has no sentinel layer, see [`Grid::with_sentinels`]
     pub fn extract_grid(&self) -> Grid<A> {
+        if self.0.is_torus() {
+            return self.0.clone();
+        }
         Grid::init(self.0.dimensions() - 2, |c| self.0[c + 1].clone())
     }
 
+    /// Position in the sentinel grid of the cell of the original grid
+    pub fn sentinel_index(&self, index: Coordinate<isize>) -> Coordinate<isize> {
+        if self.0.is_torus() {
+            index
+        } else {
+            index + 1
+        }
+    }
+
+    /// Position in the original grid of the cell of the sentinel grid, inverse of [`SentinelGrid::sentinel_index`]
+    pub fn grid_index(&self, index: Coordinate<isize>) -> Coordinate<isize> {
+        if self.0.is_torus() {
+            index
+        } else {
+            index - 1
+        }
+    }
+
     /// Applies a function to all elements of the SentinelGrid
     fn map<B, F: Fn(A) -> B>(&self, transform: F) -> SentinelGrid<B> {
         SentinelGrid(self.0.map(transform))
@@ -137,30 +162,18 @@ impl<A: Copy> Grid<A> {
     /// `extract_grid` ∘ `with_sentinels` == identity
     ///
     /// function is specific to Square
+    ///
+    /// a torus has no border and therefore gets no sentinels
     pub fn with_sentinels(&self, sentinel: A) -> SentinelGrid<A> {
+        if self.is_torus() {
+            return SentinelGrid(self.clone());
+        }
         SentinelGrid(Grid::init(self.dimensions() + 2, |c| {
             self.get(c - 1).copied().unwrap_or(sentinel)
         }))
     }
 }
 
-impl Square {
-    /// Converts a direction to the respective delta coordinate
-    fn to_coordinate(self) -> Coordinate<isize> {
-        match self {
-            Up => (-1, 0).into(),
-            Right => (0, 1).into(),
-            Down => (1, 0).into(),
-            Left => (0, -1).into(),
-        }
-    }
-
-    // /// directions are equivalent to adding delta coordinates
-    // fn functionalize(self) -> impl Fn(Coordinate<isize>) -> Coordinate<isize> {
-    //     move |c| c + self.to_coordinate()
-    // }
-}
-
 impl Coordinate<isize> {
     /// Converts a coordinate to the respective direction, if it is a delta coordinate
     fn to_square(self) -> Option<Square> {
@@ -175,15 +188,15 @@ impl Coordinate<isize> {
 
     /// Returns the position of the neighboring tile in the given direction
     ///
-    /// primitive operation linking [Coordinate<usize>] and [Square]
-    /// directions are fundamentally defunctionalized representations of adding delta coordinates
-    pub fn get_neighbor_index(self, direction: Square) -> Coordinate<isize> {
-        self + direction.to_coordinate()
+    /// primitive operation linking [Coordinate<usize>] and the [`Topology`]
+    /// directions are fundamentally defunctionalized representations of moving to a neighboring cell
+    pub fn get_neighbor_index<A: Topology>(self, direction: A) -> Coordinate<isize> {
+        direction.neighbor_index(self)
     }
 
     /// Returns the position of all neighboring tiles in arbitrary order
-    pub fn all_neighbor_indices(self) -> impl Iterator<Item = Coordinate<isize>> {
-        all_enums_ascending().map(move |dir| self.get_neighbor_index(dir))
+    pub fn all_neighbor_indices<A: Topology>(self) -> impl Iterator<Item = Coordinate<isize>> {
+        all_enums_ascending().map(move |dir: A| self.get_neighbor_index(dir))
     }
 }
 
@@ -249,17 +262,19 @@ fn memoize<A: Finite, B: Copy, F: Fn(A) -> B>(f: F) -> impl FnMut(A) -> B {
 impl<A: Copy + Finite + Neg<Output = A> + PartialEq> Superposition<A> {
     /// Generates all propagation information from on a single superposition
     pub fn extract_common_connections(self) -> EnumMap<A, Superposition<A>> {
-        let present_evidence = self.and().0.map(|x| (x, subset_containing(-x)));
-        let absent_evidence = self.or().0.not().map(|x| (x, !subset_containing(-x)));
+        let connections = self.map(|tile| tile.0);
+        let present_evidence =
+            EnumSet::intersect_all(connections.clone()).map(|x| (x, subset_containing(-x)));
+        let absent_evidence =
+            (!EnumSet::union_all(connections)).map(|x| (x, !subset_containing(-x)));
         present_evidence.chain(absent_evidence).collect()
     }
 }
 
 fn subset_containing<A: Copy + Finite>(value: A) -> Superposition<A> {
-    EnumSet::FULL
-        .into_iter()
-        .filter(|s: &Tile<A>| s.0.contains(value))
-        .collect()
+    let mut subset = EnumSet::FULL;
+    subset.retain(|s: &Tile<A>| s.0.contains(value));
+    subset
 }
 
 /// iterative fixed point of a function
@@ -282,24 +297,50 @@ where
     }
 }
 
-impl Sentinel<Square> {
+impl<A: Topology> Sentinel<A> {
     /// Minimizes the superpositions of the grid as far as possible through applying the logical deductions
     ///
     /// notably, how the function achieves this is an implementation detail
     ///
     /// # Postcondition
     ///
-    /// idempotency: `∀s : Sentinel<Square>. s.minimize()` ≡ `s.minimize().minimize()`
-    pub fn minimize(self) -> Sentinel<Square> {
-        iter_fix(
-            self,
-            |g| {
-                g.0.coordinates().into_iter().fold(g.clone(), |g, c| {
-                    propagate_restrictions_to_all_neighbors2(g, c, PartialEq::ne).0
-                })
-            },
-            SentinelGrid::eq,
-        )
+    /// idempotency: `∀s : Sentinel<A>. s.minimize()` ≡ `s.minimize().minimize()`
+    pub fn minimize(mut self) -> Sentinel<A> {
+        // worklist of the superpositions whose evidence has not been propagated yet,
+        // initially all of them, later only those restricted by a neighbor
+        let mut worklist = self.0.coordinates().collect::<VecDeque<_>>();
+        let mut queued = Grid::filled_with(self.0.dimensions(), true);
+        while let Some(index) = worklist.pop_front() {
+            queued[index] = false;
+            for (direction, restriction) in self.0[index].extract_common_connections() {
+                let neighbor = self.0.neighbor_index(index, direction);
+                if let Some(superposition) = self.0.get_mut(neighbor) {
+                    let restricted = *superposition & restriction;
+                    if restricted != *superposition {
+                        *superposition = restricted;
+                        if !queued[neighbor] {
+                            queued[neighbor] = true;
+                            worklist.push_back(neighbor);
+                        }
+                    }
+                }
+            }
+        }
+        self
+    }
+
+    /// Yields all collapses of the superpositions solving the level lazily, see [`Grid::solve`]
+    pub fn solutions(self) -> impl Iterator<Item = Grid<Tile<A>>> {
+        SolutionIterator::new(self, MostStates, true)
+    }
+
+    /// Collapses the superpositions to the decided states
+    fn decided(&self, decisions: &[Decision<A>]) -> Sentinel<A> {
+        let mut grid = self.clone();
+        for &(coordinate, tile) in decisions {
+            grid.0[coordinate] = tile.into();
+        }
+        grid
     }
 }
 
@@ -316,7 +357,11 @@ impl<A: Finite> Sentinel<A> {
             .copied()
             .unwrap_or(EnumSet::EMPTY)
             .iter()
-            .map(|t| SentinelGrid(self.0.try_adjust_at(coordinate, |_| t.into())))
+            .map(|t| {
+                let mut branch = self.clone();
+                branch.0[coordinate] = t.into();
+                branch
+            })
             .collect()
     }
 }
@@ -329,35 +374,100 @@ pub fn most_superimposed_states<A: Finite>(grid: &Sentinel<A>) -> Coordinate<isi
         .expect("Logical error: attempted to branch, but was unable")
 }
 
+/// Chooses the superposition to split when propagation gets stuck
+///
+/// the choice decides how many candidates the solver explores, see [`Grid::solve_with`]
+pub trait BranchHeuristic<A> {
+    /// coordinate of a superposition with more than one state
+    fn choose(&mut self, grid: &Sentinel<A>) -> Coordinate<isize>;
+}
+
+/// Splits the superposition with the most states, the default of [`Grid::solve`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct MostStates;
+
+/// Splits the superposition with the fewest states, known as minimum remaining values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct FewestStates;
+
+/// Splits the superposition with the most collapsed neighbors, ties are broken by fewest states
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct MostConstrainedNeighbors;
+
+/// Splits a uniformly chosen superposition, reproducible through the seed
+#[derive(Debug)]
+pub struct RandomChoice(RngSource);
+
+impl RandomChoice {
+    /// draws the choices from `rng`, e.g. the [`RngSource`] of a [`crate::config::GameConfig`]
+    pub fn new(rng: RngSource) -> Self {
+        RandomChoice(rng)
+    }
+
+    /// same seed chooses the same superpositions
+    pub fn with_seed(seed: u64) -> Self {
+        RandomChoice::new(RngSource::seeded(seed))
+    }
+}
+
+/// superpositions with more than one state
+fn uncollapsed<A: Finite>(grid: &Sentinel<A>) -> impl Iterator<Item = Coordinate<isize>> + '_ {
+    grid.0.coordinates().filter(|c| grid.0[*c].len() > 1)
+}
+
+impl<A: Finite> BranchHeuristic<A> for MostStates {
+    fn choose(&mut self, grid: &Sentinel<A>) -> Coordinate<isize> {
+        most_superimposed_states(grid)
+    }
+}
+
+impl<A: Finite> BranchHeuristic<A> for FewestStates {
+    fn choose(&mut self, grid: &Sentinel<A>) -> Coordinate<isize> {
+        uncollapsed(grid)
+            .min_by_key(|c| grid.0[*c].len())
+            .unwrap_or_else(|| most_superimposed_states(grid))
+    }
+}
+
+impl<A: Topology> BranchHeuristic<A> for MostConstrainedNeighbors {
+    fn choose(&mut self, grid: &Sentinel<A>) -> Coordinate<isize> {
+        let collapsed_neighbors = |c: Coordinate<isize>| {
+            all_enums_ascending()
+                .map(|direction: A| grid.0.neighbor_index(c, direction))
+                .filter(|&n| grid.0.get(n).map_or(false, |s| s.len() == 1))
+                .count()
+        };
+        uncollapsed(grid)
+            .min_by_key(|&c| (Reverse(collapsed_neighbors(c)), grid.0[c].len()))
+            .unwrap_or_else(|| most_superimposed_states(grid))
+    }
+}
+
+impl<A: Finite> BranchHeuristic<A> for RandomChoice {
+    fn choose(&mut self, grid: &Sentinel<A>) -> Coordinate<isize> {
+        uncollapsed(grid)
+            .choose(&mut self.0)
+            .unwrap_or_else(|| most_superimposed_states(grid))
+    }
+}
+
 /// Propagates all constraints from the chosen tile to all neighboring ones
-pub fn propagate_restrictions_to_all_neighbors(
-    grid: Sentinel<Square>,
+pub fn propagate_restrictions_to_all_neighbors<A: Topology>(
+    grid: Sentinel<A>,
     index: Coordinate<isize>,
-) -> Sentinel<Square> {
-    // determine common connections
-    let evidence = grid
-        .0
-        .get(index)
-        .copied()
-        .map(Superposition::extract_common_connections)
-        .unwrap_or_default();
-
-    // propagate connection information to neighbors
-    evidence.into_iter().fold(grid, |acc, c| {
-        SentinelGrid(
-            acc.0
-                .try_adjust_at(index.get_neighbor_index(c.0), |s| s & c.1),
-        )
-    })
+) -> Sentinel<A> {
+    propagate_restrictions_to_all_neighbors2(grid, index, |_, _| false).0
 }
+
 // for solving change_test is inequality, for hint it is collapse
 pub fn propagate_restrictions_to_all_neighbors2<
-    F: FnMut(&Superposition<Square>, &Superposition<Square>) -> bool,
+    A: Topology,
+    F: FnMut(&Superposition<A>, &Superposition<A>) -> bool,
 >(
-    grid: Sentinel<Square>,
+    mut grid: Sentinel<A>,
     index: Coordinate<isize>,
     mut change_test: F,
-) -> (Sentinel<Square>, Vec<Coordinate<isize>>) {
+) -> (Sentinel<A>, Vec<Coordinate<isize>>) {
     // determine common connections
     let evidence = grid
         .0
@@ -366,68 +476,260 @@ pub fn propagate_restrictions_to_all_neighbors2<
         .map(Superposition::extract_common_connections)
         .unwrap_or_default();
 
-    // propagate connection information to neighbors
-    // pushing to vec as side effect is unclean, but easiest implementation
-    evidence.into_iter().fold((grid, vec![]), |(g, mut v), c| {
-        let neighbor_index = index.get_neighbor_index(c.0);
-        let sg = SentinelGrid(g.0.try_adjust_at(neighbor_index, |s| {
-            let merged = s & c.1;
-            if change_test(&s, &merged) {
-                // pushing to vec as side effect is unclean, but easiest to implement
-                v.push(neighbor_index);
-            }
-            merged
-        }));
-        (sg, v)
-    })
+    // propagate connection information to neighbors in place, the grid is owned
+    let mut changed = vec![];
+    for (direction, restriction) in evidence {
+        let neighbor_index = grid.0.neighbor_index(index, direction);
+        // neighbors outside the sentinel grid are not restricted
+        grid.0
+            .adjust_at_mut(neighbor_index, |s| {
+                let merged = *s & restriction;
+                if change_test(&*s, &merged) {
+                    changed.push(neighbor_index);
+                }
+                *s = merged;
+            })
+            .ok();
+    }
+    (grid, changed)
 }
 
-impl Grid<Tile<Square>> {
+impl<A: Topology> Grid<Tile<A>> {
     /// Yields all puzzle solutions lazily
     // hide concrete iterator implementation
     // solves puzzles up to 20x20 reasonably fast
-    pub fn solve(&self) -> impl Iterator<Item = Grid<Tile<Square>>> {
-        SolutionIterator(vec![self
-            .with_sentinels(Tile::NO_CONNECTIONS)
-            .superimpose()])
-    }
-
-    //takes a user supplied input and runs solved_to_tiles
-    //if that did not generate a sufficient solution an unsolvable puzzle is generated to handle this error
-    pub fn solve_with_input(&self, input: &str) -> Grid<Tile<Square>> {
-        let tiles = cnf::solved_to_tiles(input).unwrap();
-        if tiles.len() == self.columns() * self.rows() {
-            Grid::new(Coordinate::new(self.columns(), self.rows()), tiles)
-        } else {
-            let mut unsolvable = vec![];
-            for _i in 0..self.columns() * self.rows() {
-                unsolvable.push(Tile::ALL_CONNECTIONS);
+    pub fn solve(&self) -> impl Iterator<Item = Grid<Tile<A>>> {
+        self.solve_with(MostStates)
+    }
+
+    /// Yields all puzzle solutions lazily, branching where the heuristic chooses
+    ///
+    /// all heuristics yield the same solutions, possibly in a different order
+    pub fn solve_with<H: BranchHeuristic<A>>(
+        &self,
+        heuristic: H,
+    ) -> impl Iterator<Item = Grid<Tile<A>>> {
+        SolutionIterator::new(
+            self.with_sentinels(Tile::NO_CONNECTIONS).superimpose(),
+            heuristic,
+            false,
+        )
+    }
+
+    /// Yields all puzzle solutions lazily, learning from contradictions
+    ///
+    /// same solutions in the same order as [`Grid::solve`]. Explores fewer candidates of hard levels, but every
+    /// contradiction costs a propagation per decision leading to it, so it is opt-in, see `bench-report`
+    pub fn solve_learning(&self) -> impl Iterator<Item = Grid<Tile<A>>> {
+        SolutionIterator::new(
+            self.with_sentinels(Tile::NO_CONNECTIONS).superimpose(),
+            MostStates,
+            true,
+        )
+    }
+
+    /// Checks whether the puzzle has exactly one solution
+    pub fn has_unique_solution(&self) -> bool {
+        self.solve().take(2).count() == 1
+    }
+}
+
+impl<A: Topology> Grid<Option<Tile<A>>> {
+    /// Yields all solutions of the shaped puzzle lazily
+    ///
+    /// holes are solved as tiles without connections and stay holes in the solutions
+    pub fn solve(&self) -> impl Iterator<Item = Grid<Option<Tile<A>>>> {
+        let mask = self.mask();
+        self.fill_holes()
+            .solve()
+            .map(move |solution| solution.cut(&mask))
+    }
+}
+
+impl<A: Topology> Grid<Cell<A>> {
+    /// Yields all solutions of the puzzle with locked tiles lazily
+    ///
+    /// locked tiles start collapsed and keep their rotation and their lock in the solutions
+    pub fn solve(&self) -> impl Iterator<Item = Grid<Cell<A>>> {
+        let locks = self.locks();
+        let superpositions = self.map(|cell| {
+            if cell.locked {
+                EnumSet::from(cell.tile)
+            } else {
+                cell.tile.superimpose()
+            }
+        });
+        SolutionIterator::new(
+            superpositions.with_sentinels(Tile::NO_CONNECTIONS.into()),
+            MostStates,
+            true,
+        )
+        .map(move |solution| solution.with_locks(&locks))
+    }
+}
+
+impl<A: Topology, const N: usize> Grid<Channels<A, N>> {
+    /// Yields all solutions of the puzzle with several channels lazily
+    ///
+    /// every tile keeps the orientations consistent with the propagation of all channels, see [`restrict_channels`].
+    /// When propagation gets stuck, the tile with the most orientations is split
+    pub fn solve(&self) -> impl Iterator<Item = Grid<Channels<A, N>>> {
+        let mut candidates = vec![self.map(Channels::orientations)];
+        std::iter::from_fn(move || {
+            while let Some(candidate) = candidates.pop() {
+                let orientations = match restrict_channels(candidate) {
+                    Some(orientations) => orientations,
+                    None => continue,
+                };
+                let split = orientations
+                    .coordinates()
+                    .filter(|&index| orientations[index].len() > 1)
+                    .max_by_key(|&index| orientations[index].len());
+                match split {
+                    Some(index) => {
+                        // explore the orientations in the order of their rotations
+                        for &orientation in orientations[index].iter().rev() {
+                            let mut branch = orientations.clone();
+                            branch[index] = vec![orientation];
+                            candidates.push(branch);
+                        }
+                    }
+                    None => {
+                        let solution = orientations.map(|collapsed| collapsed[0]);
+                        if solution.is_solved() {
+                            return Some(solution);
+                        }
+                    }
+                }
             }
-            Grid::new(Coordinate::new(self.columns(), self.rows()), unsolvable)
+            None
+        })
+    }
+}
+
+/// Restricts the orientations of the tiles until every orientation agrees with the minimized superpositions of all channels
+///
+/// returns [`None`] if a tile has no orientation left
+fn restrict_channels<A: Topology, const N: usize>(
+    mut orientations: Grid<Vec<Channels<A, N>>>,
+) -> Option<Grid<Vec<Channels<A, N>>>> {
+    loop {
+        let channels = (0..N)
+            .map(|channel| {
+                orientations
+                    .map(|tiles| tiles.iter().map(|t| t.0[channel]).collect())
+                    .with_sentinels(Tile::NO_CONNECTIONS.into())
+                    .minimize()
+                    .extract_grid()
+            })
+            .collect::<Vec<Grid<Superposition<A>>>>();
+        let restricted = orientations.with_index().map(|(index, tiles)| {
+            tiles
+                .into_iter()
+                .filter(|t| (0..N).all(|channel| channels[channel][index].contains(t.0[channel])))
+                .collect::<Vec<_>>()
+        });
+        if restricted.as_slice().iter().any(Vec::is_empty) {
+            return None;
+        }
+        if restricted == orientations {
+            return Some(restricted);
         }
+        orientations = restricted;
     }
 }
 
+impl Grid<Tile<Square>> {
+    /// Decodes a model of an external SAT solver in DIMACS format into the solved level
+    ///
+    /// fails with the reason if the model is malformed or no solution of the level, see [`cnf::parse_solution`]
+    #[cfg(feature = "sat")]
+    pub fn solve_with_input(&self, input: &str) -> Result<Self, cnf::CnfError> {
+        cnf::parse_solution(self, input)
+    }
+}
+
+/// state chosen for a superposition when branching
+pub type Decision<A> = (Coordinate<isize>, Tile<A>);
+
 /// lazy generation of solutions to unify API for querying single and multiple solutions
 ///
-/// stores a stack of solution candidates, which are successively refined
-struct SolutionIterator<A>(Vec<A>);
+/// stores a stack of solution candidates, which are successively refined, together with the decisions leading to them
+///
+/// # Learning
+///
+/// a candidate ending in a contradiction yields a nogood: the decisions of the candidate reduced by deletion
+/// to those still contradicting the level together. Candidates deciding all states of a nogood are never pushed,
+/// as propagation only restricts further with more decisions
+struct SolutionIterator<A, H> {
+    level: Sentinel<A>,
+    candidates: Vec<(Sentinel<A>, Vec<Decision<A>>)>,
+    heuristic: H,
+    learning: bool,
+    nogoods: Vec<Vec<Decision<A>>>,
+    /// number of popped candidates
+    explored: usize,
+}
+
+impl<A: Topology, H> SolutionIterator<A, H> {
+    fn new(level: Sentinel<A>, heuristic: H, learning: bool) -> Self {
+        SolutionIterator {
+            candidates: vec![(level.clone(), vec![])],
+            level,
+            heuristic,
+            learning,
+            nogoods: vec![],
+            explored: 0,
+        }
+    }
 
-impl Iterator for SolutionIterator<Sentinel<Square>> {
-    type Item = Grid<Tile<Square>>;
+    /// checks if the decisions contradict the level after propagation
+    fn contradicts(&self, decisions: &[Decision<A>]) -> bool {
+        self.level
+            .decided(decisions)
+            .minimize()
+            .check_no_empty_superposition()
+            .is_none()
+    }
+
+    /// drops every decision not needed for the contradiction
+    ///
+    /// the latest decision is always needed, as its candidate was pushed without contradiction
+    fn learn(&mut self, mut decisions: Vec<Decision<A>>) {
+        let mut index = 0;
+        while index + 1 < decisions.len() {
+            let removed = decisions.remove(index);
+            if !self.contradicts(&decisions) {
+                decisions.insert(index, removed);
+                index += 1;
+            }
+        }
+        self.nogoods.push(decisions);
+    }
+
+    /// checks if the decisions include all decisions of a nogood
+    fn is_excluded(&self, decisions: &[Decision<A>]) -> bool {
+        self.nogoods
+            .iter()
+            .any(|nogood| nogood.iter().all(|decision| decisions.contains(decision)))
+    }
+}
+
+impl<A: Topology, H: BranchHeuristic<A>> Iterator for SolutionIterator<A, H> {
+    type Item = Grid<Tile<A>>;
 
     /// Algorithm uses backtracking with explicit stack
     ///
     /// pops solutions candidate from stack if available and attempts to solve it
     ///
-    /// * __contradiction__: pop next element from stack and repeat procedure
+    /// * __contradiction__: learn a nogood, pop next element from stack and repeat procedure
     /// * __unique solution__: return in solved state
-    /// * __branching__: select 1 candidate and repeat procedure, push rest on stack
-    ///
-    /// it is yet to be determined if a contradiction after branching can actually occur
+    /// * __branching__: select 1 candidate and repeat procedure, push rest not excluded by a nogood on stack
     fn next(&mut self) -> Option<Self::Item> {
         loop {
-            let minimized_grid = self.0.pop()?.minimize();
+            let (candidate, decisions) = self.candidates.pop()?;
+            self.explored += 1;
+            let minimized_grid = candidate.minimize();
 
             // yield, if unique solution
             if let Some(grid) = minimized_grid.extract_if_collapsed() {
@@ -435,9 +737,21 @@ impl Iterator for SolutionIterator<Sentinel<Square>> {
             }
 
             // distinguish between no and several solutions
-            if let Some(grid) = minimized_grid.check_no_empty_superposition() {
-                // INFO: certain candidates after branching are unsolvable in rare cases
-                self.0.extend(grid.branch(most_superimposed_states))
+            match minimized_grid.check_no_empty_superposition() {
+                Some(grid) => {
+                    let coordinate = self.heuristic.choose(&grid);
+                    for tile in grid.0[coordinate].iter() {
+                        let mut branch = decisions.clone();
+                        branch.push((coordinate, tile));
+                        if !self.is_excluded(&branch) {
+                            let mut candidate = grid.clone();
+                            candidate.0[coordinate] = tile.into();
+                            self.candidates.push((candidate, branch));
+                        }
+                    }
+                }
+                None if self.learning && !decisions.is_empty() => self.learn(decisions),
+                None => {}
             }
         }
     }
@@ -451,6 +765,9 @@ mod tests {
 
     use super::*;
     use crate::core::{finite::all_enums_ascending, interval::Max};
+    use crate::generator::fastgen::generate;
+    use crate::model::gameboard::GameBoard;
+    use crate::model::tile::{Hex, Triangle};
 
     #[quickcheck]
     fn tile_configurations_have_same_number_of_connections(tile: Tile<Square>) -> bool {
@@ -473,7 +790,136 @@ mod tests {
     fn neighborhood_is_euclidian(index: Coordinate<Max<100>>) -> bool {
         // restrict coordinates to a range resembling actual values used in grid and avoid integer over- / underflows
         let index = index.map(Max::to_isize);
-        all_enums_ascending().fold(index, Coordinate::get_neighbor_index) == index
+        all_enums_ascending::<Square>().fold(index, Coordinate::get_neighbor_index) == index
+            && all_enums_ascending::<Hex>().fold(index, Coordinate::get_neighbor_index) == index
+    }
+
+    #[quickcheck]
+    fn hex_neighborhood_is_symmetric(index: Coordinate<Max<100>>, direction: Hex) -> bool {
+        let index = index.map(Max::to_isize);
+        let neighbor_index = index.get_neighbor_index(direction);
+        index == neighbor_index.get_neighbor_index(-direction)
+    }
+
+    #[quickcheck]
+    fn hex_tile_configurations_have_same_number_of_connections(tile: Tile<Hex>) -> bool {
+        let connections = tile.0.len();
+        tile.superimpose()
+            .into_iter()
+            .all(|t| t.0.len() == connections)
+    }
+
+    #[quickcheck]
+    fn heuristics_agree_on_the_solutions(dimension: Coordinate<Max<6>>, seed: u64) -> bool {
+        let grid = generate(dimension.map(Max::to_usize), seed).scramble(seed);
+        let solutions = |iterator: &mut dyn Iterator<Item = Grid<Tile<Square>>>| {
+            let mut solutions = iterator.take(8).collect::<Vec<_>>();
+            solutions.sort();
+            solutions
+        };
+        let expected = solutions(&mut grid.solve());
+        // compare complete sets only, as the order of the solutions may differ
+        expected.len() == 8
+            || [
+                solutions(&mut grid.solve_with(FewestStates)),
+                solutions(&mut grid.solve_with(MostConstrainedNeighbors)),
+                solutions(&mut grid.solve_with(RandomChoice::with_seed(seed))),
+            ]
+            .iter()
+            .all(|other| *other == expected)
+    }
+
+    #[test]
+    fn heuristics_choose_uncollapsed_superpositions() {
+        let grid = "┃┏\n╋┛".parse::<Grid<Tile<Square>>>().unwrap();
+        let sentinel = grid.with_sentinels(Tile::NO_CONNECTIONS).superimpose();
+        // ┃ has 2 states, ┏ and ┛ have 4 states, ╋ is collapsed
+        assert_eq!(FewestStates.choose(&sentinel), Coordinate::new(1, 1));
+        // ┃ and ┛ both have three collapsed neighbors, but ┃ has fewer states
+        assert_eq!(
+            MostConstrainedNeighbors.choose(&sentinel),
+            Coordinate::new(1, 1)
+        );
+        let chosen = RandomChoice::with_seed(0).choose(&sentinel);
+        assert!(sentinel.0[chosen].len() > 1);
+    }
+
+    #[quickcheck]
+    fn hex_solutions_are_rotations_of_the_level(grid: Grid<Tile<Hex>>) -> bool {
+        // restrict to small levels, as hexagonal tiles have more rotations to explore
+        let grid = Grid::init(grid.dimensions().map(|x| x.min(3)), |c| grid[c]);
+        grid.solve().take(1).all(|solution| {
+            solution.dimensions() == grid.dimensions()
+                && solution
+                    .as_slice()
+                    .iter()
+                    .zip(grid.as_slice())
+                    .all(|(s, t)| t.superimpose().contains(*s))
+        })
+    }
+
+    #[test]
+    fn hex_rhombus_is_solved() {
+        use crate::enumset;
+        use crate::model::tile::Hex::*;
+
+        // rhombus of two rows with two hexagons each, all neighboring hexagons are connected
+        let rhombus = Grid::new(
+            Coordinate::new(2, 2),
+            vec![
+                Tile(enumset!(East, SouthEast)),
+                Tile(enumset!(West, SouthWest, SouthEast)),
+                Tile(enumset!(NorthWest, NorthEast, East)),
+                Tile(enumset!(West, NorthWest)),
+            ],
+        );
+        let scrambled = rhombus.map(|tile| tile.rotated_clockwise(2));
+        assert!(scrambled.solve().any(|solution| solution == rhombus));
+    }
+
+    #[quickcheck]
+    fn triangle_neighborhood_is_symmetric(
+        index: Coordinate<Max<100>>,
+        direction: Triangle,
+    ) -> bool {
+        let index = index.map(Max::to_isize);
+        let neighbor_index = index.get_neighbor_index(direction);
+        index == neighbor_index.get_neighbor_index(-direction)
+    }
+
+    #[quickcheck]
+    fn triangle_solutions_are_solved_rotations_of_the_level(grid: Grid<Tile<Triangle>>) -> bool {
+        let grid = Grid::init(grid.dimensions().map(|x| x.min(4)), |c| grid[c]);
+        grid.solve().take(2).all(|solution| {
+            solution.is_solved()
+                && solution
+                    .as_slice()
+                    .iter()
+                    .zip(grid.as_slice())
+                    .all(|(s, t)| t.superimpose().contains(*s))
+        })
+    }
+
+    #[test]
+    fn triangle_ring_is_solved() {
+        use crate::enumset;
+        use crate::model::tile::Triangle::*;
+
+        // two rows of three alternating triangles, all connected as a ring
+        let ring = Grid::new(
+            Coordinate::new(2, 3),
+            vec![
+                Tile(enumset!(Base, RightSide)),
+                Tile(enumset!(LeftSide, RightSide)),
+                Tile(enumset!(LeftSide, Base)),
+                Tile(enumset!(Base, RightSide)),
+                Tile(enumset!(LeftSide, RightSide)),
+                Tile(enumset!(LeftSide, Base)),
+            ],
+        );
+        assert!(ring.is_solved());
+        let scrambled = ring.map(|tile| tile.rotated_clockwise(1));
+        assert!(scrambled.solve().any(|solution| solution == ring));
     }
 
     #[quickcheck]
@@ -489,6 +935,109 @@ mod tests {
         sentinel.clone().minimize().minimize() == sentinel.minimize()
     }
 
+    #[quickcheck]
+    fn minimize_reaches_the_fixpoint_of_full_sweeps(sentinel: Sentinel<Square>) -> bool {
+        let sweeps = iter_fix(
+            sentinel.clone(),
+            |g| {
+                g.0.coordinates().fold(g.clone(), |g, c| {
+                    propagate_restrictions_to_all_neighbors2(g, c, PartialEq::ne).0
+                })
+            },
+            SentinelGrid::eq,
+        );
+        // evidence of an empty superposition is contradictory, so the order decides which contradiction is reached
+        match (
+            sentinel.minimize().check_no_empty_superposition(),
+            sweeps.check_no_empty_superposition(),
+        ) {
+            (Some(worklist), Some(sweeps)) => worklist == sweeps,
+            (worklist, sweeps) => worklist.is_none() && sweeps.is_none(),
+        }
+    }
+
+    #[quickcheck]
+    fn learning_keeps_the_solutions_and_their_order(grid: Grid<Tile<Square>>) -> bool {
+        let grid = Grid::init(grid.dimensions().map(|x| x.min(6)), |c| grid[c]);
+        grid.solve_learning().take(16).eq(grid.solve().take(16))
+    }
+
+    #[test]
+    fn learning_explores_fewer_candidates_of_hard_levels() {
+        use crate::solver::difficulty::{rate, Difficulty};
+
+        let hard = (0..100)
+            .map(|seed| generate(Coordinate::new(12, 12), seed))
+            .filter(|level| rate(level) == Difficulty::Hard)
+            .take(10)
+            .collect::<Vec<_>>();
+        let explored = |learning| {
+            hard.iter()
+                .map(|level| {
+                    let sentinel = level.with_sentinels(Tile::NO_CONNECTIONS).superimpose();
+                    let mut solutions = SolutionIterator::new(sentinel, MostStates, learning);
+                    solutions.by_ref().take(100).for_each(drop);
+                    solutions.explored
+                })
+                .collect::<Vec<_>>()
+        };
+        let (learned, plain) = (explored(true), explored(false));
+        assert!(learned.iter().zip(&plain).all(|(l, p)| l <= p));
+        assert!(learned.iter().sum::<usize>() < plain.iter().sum());
+    }
+
+    #[quickcheck]
+    fn fully_locked_solutions_are_their_only_solution(
+        dimension: Coordinate<Max<8>>,
+        seed: u64,
+    ) -> bool {
+        let solution = generate(dimension.map(Max::to_usize), seed);
+        let locked = solution.map(Cell::locked);
+        locked.solve().eq([locked.clone()])
+    }
+
+    #[test]
+    fn locked_tiles_keep_their_rotation() {
+        let level = "╸╸".parse::<Grid<Tile<Square>>>().unwrap();
+        assert!(level.solve().next().is_some());
+        let locked = level
+            .map(Cell::new)
+            .toggle_lock(Coordinate::new(0, 0))
+            .unwrap();
+        assert_eq!(locked.solve().next(), None);
+        let locked = level
+            .map(Cell::new)
+            .toggle_lock(Coordinate::new(0, 1))
+            .unwrap();
+        let solution = locked.solve().next().expect("solution");
+        assert_eq!(solution.tiles().to_string(), "╺╸");
+        assert!(solution[Coordinate::new(0, 1)].locked);
+    }
+
+    #[test]
+    fn channel_levels_are_solved_in_all_channels() {
+        let dimension = Coordinate::new(4, 4);
+        let level = Grid::from_channels([generate(dimension, 1), generate(dimension, 2)]).unwrap();
+        let scrambled = Grid::init(dimension, |c| {
+            level[c].rotated_clockwise((c.row + c.column) as u64)
+        });
+        let solutions = scrambled.solve().take(16).collect::<Vec<_>>();
+        assert!(solutions.iter().all(|solution| solution.is_solved()));
+        assert!(solutions.contains(&level));
+    }
+
+    #[test]
+    fn channels_restrict_each_other() {
+        // each channel alone is solvable, but not with the same rotations
+        let red = "╺╸".parse::<Grid<Tile<Square>>>().unwrap();
+        let blue = "╻╸".parse::<Grid<Tile<Square>>>().unwrap();
+        assert!(red.solve().next().is_some() && blue.solve().next().is_some());
+        assert_eq!(
+            Grid::from_channels([red, blue]).unwrap().solve().next(),
+            None
+        );
+    }
+
     #[quickcheck]
     fn coordinates_is_stable(grid: Sentinel<Square>) -> bool {
         grid.0.coordinates().collect::<Vec<_>>() == grid.clone().0.coordinates().collect::<Vec<_>>()
@@ -0,0 +1,102 @@
+//! Minimal clockwise rotations turning a scrambled level into its solution
+//!
+//! tiles of a rotational symmetry class need fewer rotations, e.g. `┃` becomes `━` after a single rotation
+//! and back after another one, while `╋` never needs to be turned
+
+use crate::core::finite::Finite;
+use crate::model::{coordinate::Coordinate, grid::Grid, tile::Tile};
+
+/// Clockwise rotations needed per tile
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RotationDistance {
+    /// rotations of the tile at the same position, less than the number of directions
+    pub rotations: Grid<u64>,
+}
+
+impl RotationDistance {
+    /// rotations of all tiles together
+    pub fn total(&self) -> u64 {
+        self.rotations.as_slice().iter().sum()
+    }
+
+    /// number of tiles needing at least one rotation
+    pub fn misaligned(&self) -> usize {
+        self.rotations
+            .as_slice()
+            .iter()
+            .filter(|&&rotations| rotations > 0)
+            .count()
+    }
+
+    /// Shortest sequence of clockwise rotations solving the level
+    ///
+    /// tiles are turned in reading order, every tile as often as it needs to
+    pub fn clicks(&self) -> impl Iterator<Item = Coordinate<isize>> + '_ {
+        self.rotations
+            .coordinates()
+            .flat_map(move |index| (0..self.rotations[index]).map(move |_| index))
+    }
+}
+
+/// Computes the clockwise rotations per tile turning the scrambled level into the solution
+///
+/// returns [`None`] if the levels differ in dimensions or in the shape of any tile
+pub fn rotation_distance<A: Finite>(
+    scrambled: &Grid<Tile<A>>,
+    solution: &Grid<Tile<A>>,
+) -> Option<RotationDistance> {
+    if scrambled.dimensions() != solution.dimensions() {
+        return None;
+    }
+    let rotations = scrambled
+        .as_slice()
+        .iter()
+        .zip(solution.as_slice())
+        .map(|(&tile, &target)| tile.rotation_distance(target))
+        .collect::<Option<Vec<_>>>()?;
+    Some(RotationDistance {
+        rotations: Grid::new(scrambled.dimensions(), rotations),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::model::{
+        gameboard::GameBoard,
+        grid::Grid,
+        parser::{parse_level, unicode_to_tile},
+        tile::{Square, Tile},
+    };
+
+    use super::rotation_distance;
+
+    #[quickcheck]
+    fn clicks_solve_the_level(solution: Grid<Tile<Square>>, seed: u64) -> bool {
+        let scrambled = solution.clone().scramble(seed);
+        let distance = rotation_distance(&scrambled, &solution).expect("same shapes");
+        let clicked = distance.clicks().try_fold(scrambled.clone(), |grid, index| {
+            grid.rotate_clockwise(index)
+        });
+        clicked == Ok(solution.clone())
+            && distance.total() == distance.clicks().count() as u64
+            && Some(distance.total()) == scrambled.scramble_distance(&solution)
+    }
+
+    #[test]
+    fn symmetric_tiles_need_fewer_rotations() {
+        let scrambled = parse_level("━╋┗╹", unicode_to_tile).unwrap();
+        let solution = parse_level("┃╋┏╻", unicode_to_tile).unwrap();
+        let distance = rotation_distance(&scrambled, &solution).unwrap();
+        assert_eq!(distance.rotations.as_slice(), &[1, 0, 1, 2]);
+        assert_eq!(distance.total(), 4);
+        assert_eq!(distance.misaligned(), 3);
+    }
+
+    #[test]
+    fn other_shapes_have_no_distance() {
+        let scrambled = parse_level("━", unicode_to_tile).unwrap();
+        let solution = parse_level("┏", unicode_to_tile).unwrap();
+        assert_eq!(rotation_distance(&scrambled, &solution), None);
+    }
+}
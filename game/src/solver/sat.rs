@@ -0,0 +1,245 @@
+//! Embedded SAT solver for the CNF encoding of levels, runs wherever the crate runs including the web
+//!
+//! a plain DPLL solver with unit propagation, branching on the variables in ascending order.
+//! Variables of a tile are adjacent, so it assigns the tiles in row-major order like the propagation solver.
+//! Industrial solvers are available natively, see the `external` feature
+
+use std::iter;
+
+use crate::model::{
+    channel::Channels,
+    cnf::{
+        blocking_clause, channels_to_formula, level_to_formula, model_to_channels, model_to_level,
+        Clause, Cnf, Lit,
+    },
+    grid::Grid,
+    tile::{Square, Tile},
+};
+
+/// Finds a solution of the level with the embedded SAT solver
+///
+/// `Ok(None)` if the level is unsolvable
+pub fn solve_with_sat(level: &Grid<Tile<Square>>) -> Result<Option<Grid<Tile<Square>>>, String> {
+    solve_cnf(&level_to_formula(level))
+        .map(|model| model_to_level(level, &model).map_err(|e| e.to_string()))
+        .transpose()
+}
+
+/// Enumerates all solutions of the level lazily with the embedded SAT solver
+///
+/// every solution found is excluded by a [`blocking_clause`] added to the formula before solving again.
+/// Yields the same solutions as [`Grid::solve`], stops after the first error
+pub fn solutions_with_sat(
+    level: &Grid<Tile<Square>>,
+) -> impl Iterator<Item = Result<Grid<Tile<Square>>, String>> + '_ {
+    let mut formula = level_to_formula(level);
+    let mut exhausted = false;
+    iter::from_fn(move || {
+        if exhausted {
+            return None;
+        }
+        let solution = solve_cnf(&formula)
+            .map(|model| model_to_level(level, &model).map_err(|e| e.to_string()))
+            .transpose();
+        match solution {
+            Ok(Some(solution)) => {
+                formula.add_clause(blocking_clause(&solution));
+                Some(Ok(solution))
+            }
+            Ok(None) => {
+                exhausted = true;
+                None
+            }
+            Err(error) => {
+                exhausted = true;
+                Some(Err(error))
+            }
+        }
+    })
+}
+
+/// Finds a solution of the level of several channels with the embedded SAT solver, see [`channels_to_formula`]
+///
+/// `Ok(None)` if the level is unsolvable
+pub fn solve_channels_with_sat<const N: usize>(
+    level: &Grid<Channels<Square, N>>,
+) -> Result<Option<Grid<Channels<Square, N>>>, String> {
+    solve_cnf(&channels_to_formula(level))
+        .map(|model| model_to_channels(level, &model).map_err(|e| e.to_string()))
+        .transpose()
+}
+
+impl Grid<Tile<Square>> {
+    /// Encodes the level as CNF and solves it with the embedded SAT solver
+    ///
+    /// see [`solve_with_sat`], unlike [`Grid::solve_with_input`] no external solver is needed
+    pub fn solve_with_sat(&self) -> Result<Option<Self>, String> {
+        solve_with_sat(self)
+    }
+}
+
+/// Finds a model of the formula in DIMACS format, see [`solve_cnf`]
+pub fn solve_dimacs(formula: &str) -> Result<Option<Vec<Lit>>, String> {
+    Cnf::from_dimacs(formula).map(|formula| solve_cnf(&formula))
+}
+
+/// Finds a model of the formula
+///
+/// returns the signed literals of all variables in ascending order, `None` if the formula is unsatisfiable
+pub fn solve_cnf(formula: &Cnf) -> Option<Vec<Lit>> {
+    let mut assignment = vec![0_i8; formula.num_vars + 1];
+    dpll(&formula.clauses, &mut assignment).then(|| {
+        (1..=formula.num_vars)
+            .map(|variable| {
+                // variables without constraints may remain unassigned
+                if assignment[variable] > 0 {
+                    variable as Lit
+                } else {
+                    -(variable as Lit)
+                }
+            })
+            .collect()
+    })
+}
+
+/// value of the literal under the assignment, `None` if its variable is unassigned
+fn value(assignment: &[i8], literal: Lit) -> Option<bool> {
+    match assignment[literal.unsigned_abs() as usize] {
+        0 => None,
+        sign => Some((sign > 0) == (literal > 0)),
+    }
+}
+
+fn assign(assignment: &mut [i8], literal: Lit) {
+    assignment[literal.unsigned_abs() as usize] = if literal > 0 { 1 } else { -1 };
+}
+
+/// extends the assignment to a model, leaves the assignment unchanged if there is none
+fn dpll(clauses: &[Clause], assignment: &mut Vec<i8>) -> bool {
+    // variables assigned by unit propagation, undone on conflict
+    let mut propagated = vec![];
+    let undo = |assignment: &mut Vec<i8>, propagated: &[Lit]| {
+        propagated
+            .iter()
+            .for_each(|literal| assignment[literal.unsigned_abs() as usize] = 0)
+    };
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for clause in clauses {
+            let mut unassigned = clause
+                .iter()
+                .filter(|&&literal| value(assignment, literal) != Some(false));
+            match (unassigned.next(), unassigned.next()) {
+                (None, _) => {
+                    undo(assignment, &propagated);
+                    return false;
+                }
+                (Some(&literal), None) if value(assignment, literal).is_none() => {
+                    assign(assignment, literal);
+                    propagated.push(literal);
+                    changed = true;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let variable = match (1..assignment.len()).find(|&variable| assignment[variable] == 0) {
+        None => return true,
+        Some(variable) => variable as Lit,
+    };
+    for literal in [variable, -variable] {
+        assign(assignment, literal);
+        if dpll(clauses, assignment) {
+            return true;
+        }
+        assignment[variable as usize] = 0;
+    }
+    undo(assignment, &propagated);
+    false
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::core::interval::Max;
+    use crate::generator::fastgen::generate;
+    use crate::model::{
+        coordinate::Coordinate,
+        gameboard::GameBoard,
+        grid::Grid,
+        tile::{Square, Tile},
+    };
+
+    use super::{solutions_with_sat, solve_channels_with_sat, solve_dimacs, solve_with_sat};
+
+    #[test]
+    fn formulas_are_solved() {
+        assert_eq!(
+            solve_dimacs("p cnf 3 3\n1 2 0\n-1 0\n-2 3 0\n"),
+            Ok(Some(vec![-1, 2, 3]))
+        );
+        assert_eq!(solve_dimacs("p cnf 1 2\n1 0\n-1 0\n"), Ok(None));
+        assert!(solve_dimacs("p cnf 1 1\n2 0\n").is_err());
+    }
+
+    #[quickcheck]
+    fn solves_generated_levels(dimension: Coordinate<Max<6>>, seed: u64) -> bool {
+        let level = generate(dimension.map(Max::to_usize), seed).scramble(seed);
+        solve_with_sat(&level).map_or(false, |solution| {
+            solution.map_or(false, |solution| solution.is_solved())
+        })
+    }
+
+    #[test]
+    fn levels_are_solved_end_to_end() {
+        let level = "┛┗\n┓┏".parse::<Grid<Tile<Square>>>().unwrap();
+        let solution = level.solve_with_sat().unwrap().unwrap();
+        assert!(solution.is_solved());
+        assert_eq!(solution, level.solve().next().unwrap());
+        let unsolvable = "╺╸╸".parse::<Grid<Tile<Square>>>().unwrap();
+        assert_eq!(unsolvable.solve_with_sat(), Ok(None));
+    }
+
+    #[quickcheck]
+    fn enumerates_the_solutions_of_the_propagation_solver(level: Grid<Tile<Square>>) -> bool {
+        // compare complete sets only, as the order of the solutions differs
+        const LIMIT: usize = 8;
+        let level = Grid::init(level.dimensions().map(|x| x.min(4)), |c| level[c]);
+        let mut expected = level.solve().take(LIMIT + 1).collect::<Vec<_>>();
+        let sat = solutions_with_sat(&level)
+            .take(LIMIT + 1)
+            .collect::<Result<Vec<_>, _>>();
+        sat.map_or(false, |mut sat| {
+            expected.sort();
+            sat.sort();
+            sat.len() == expected.len() && (sat.len() > LIMIT || sat == expected)
+        })
+    }
+
+    #[quickcheck]
+    fn agrees_with_propagation_solver_on_solvability(level: Grid<Tile<Square>>) -> bool {
+        // small levels keep the propagation solver fast
+        let level = Grid::init(level.dimensions().map(|x| x.min(4)), |c| level[c]);
+        solve_with_sat(&level).map(|solution| solution.is_some())
+            == Ok(level.solve().next().is_some())
+    }
+
+    #[quickcheck]
+    fn agrees_with_propagation_solver_on_channel_levels(
+        red: Grid<Tile<Square>>,
+        blue: Grid<Tile<Square>>,
+    ) -> bool {
+        let dimensions = red.dimensions().map(|x| x.min(3));
+        let channel = |grid: &Grid<Tile<Square>>| {
+            Grid::init(dimensions, |c| {
+                grid.get(c).copied().unwrap_or(Tile::NO_CONNECTIONS)
+            })
+        };
+        let level = Grid::from_channels([channel(&red), channel(&blue)]).unwrap();
+        solve_channels_with_sat(&level).map(|solution| solution.is_some())
+            == Ok(level.solve().next().is_some())
+    }
+}
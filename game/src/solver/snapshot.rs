@@ -0,0 +1,379 @@
+//! Compact binary snapshots of the superpositions of a level, e.g. for streaming a solver or generator run
+//!
+//! a run is a sequence of snapshots, every snapshot is encoded as a frame. The first frame is a keyframe
+//! with all superpositions, later frames only carry the cells changed since the previous snapshot.
+//! Runs are sampled by [`solving_snapshots`] and [`generation_snapshots`]
+//!
+//! ## Frame format
+//!
+//! all integers are big endian
+//!
+//! | Field    | Encoding                                                        |
+//! |:---------|:----------------------------------------------------------------|
+//! | kind     | `u8`, keyframe = 0, diff = 1                                    |
+//! | keyframe | rows and columns as `u16`, all superpositions in row-major order |
+//! | diff     | count as `u32`, count times a position and a superposition      |
+//!
+//! the position of a cell is its index in row-major order as `u32`. A superposition is its
+//! [`Finite::enum_to_index`] in as few bytes as fit all tiles of the topology, e.g. two bytes for squares
+
+use std::fmt::Display;
+
+use crate::config::RngSource;
+use crate::core::{cardinality::Cardinality, enumset::EnumSet, finite::Finite};
+use crate::generator::wfc::WfcGenerator;
+use crate::model::{
+    coordinate::Coordinate,
+    grid::Grid,
+    tile::{Tile, Topology},
+};
+
+use super::propagationsolver::Superposition;
+use super::trace::{trace, SolverEvent, SolverTrace};
+
+const KEYFRAME: u8 = 0;
+
+const DIFF: u8 = 1;
+
+/// bytes of a superposition of the topology
+fn width<A: Topology>() -> usize {
+    ((Tile::<A>::CARDINALITY + 7) / 8) as usize
+}
+
+/// Encodes the snapshots of a run into frames
+#[derive(Clone, PartialEq, Eq)]
+pub struct SnapshotEncoder<A> {
+    previous: Option<Grid<Superposition<A>>>,
+}
+
+impl<A: Topology> Default for SnapshotEncoder<A> {
+    fn default() -> Self {
+        SnapshotEncoder { previous: None }
+    }
+}
+
+impl<A: Topology> SnapshotEncoder<A> {
+    pub fn new() -> Self {
+        SnapshotEncoder::default()
+    }
+
+    /// Frame of the next snapshot of the run
+    ///
+    /// a keyframe for the first snapshot, after the dimensions changed or if it is not larger than the diff
+    pub fn encode(&mut self, snapshot: &Grid<Superposition<A>>) -> Result<Vec<u8>, String> {
+        let width = width::<A>();
+        let changed = self
+            .previous
+            .as_ref()
+            .filter(|previous| previous.dimensions() == snapshot.dimensions())
+            .map(|previous| {
+                previous
+                    .as_slice()
+                    .iter()
+                    .zip(snapshot.as_slice())
+                    .enumerate()
+                    .filter(|(_, (before, after))| before != after)
+                    .map(|(position, (_, &after))| (position, after))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|changed| changed.len() * (4 + width) < snapshot.as_slice().len() * width);
+        let mut frame = vec![];
+        match changed {
+            Some(changed) => {
+                frame.push(DIFF);
+                frame.extend((changed.len() as u32).to_be_bytes());
+                for (position, superposition) in changed {
+                    let position = u32::try_from(position).map_err(|_| "level too large")?;
+                    frame.extend(position.to_be_bytes());
+                    push_superposition::<A>(&mut frame, superposition);
+                }
+            }
+            None => {
+                frame.push(KEYFRAME);
+                for size in [snapshot.rows(), snapshot.columns()] {
+                    let size = u16::try_from(size).map_err(|_| "level too large".to_string())?;
+                    frame.extend(size.to_be_bytes());
+                }
+                for &superposition in snapshot.as_slice() {
+                    push_superposition::<A>(&mut frame, superposition);
+                }
+            }
+        }
+        self.previous = Some(snapshot.clone());
+        Ok(frame)
+    }
+}
+
+fn push_superposition<A: Topology>(frame: &mut Vec<u8>, superposition: Superposition<A>) {
+    frame.extend(&superposition.enum_to_index().to_be_bytes()[8 - width::<A>()..]);
+}
+
+/// Decodes the frames of a run back into its snapshots
+#[derive(Clone, PartialEq, Eq)]
+pub struct SnapshotDecoder<A> {
+    snapshot: Option<Grid<Superposition<A>>>,
+}
+
+impl<A: Topology> Default for SnapshotDecoder<A> {
+    fn default() -> Self {
+        SnapshotDecoder { snapshot: None }
+    }
+}
+
+impl<A: Topology> SnapshotDecoder<A> {
+    pub fn new() -> Self {
+        SnapshotDecoder::default()
+    }
+
+    /// latest decoded snapshot, if there is one
+    pub fn snapshot(&self) -> Option<&Grid<Superposition<A>>> {
+        self.snapshot.as_ref()
+    }
+
+    /// Decodes the next frame of the run, returns the snapshot
+    ///
+    /// fails for truncated frames, diffs before the first keyframe and positions outside of the level
+    pub fn decode(&mut self, frame: &[u8]) -> Result<&Grid<Superposition<A>>, String> {
+        let mut input = Frame(frame);
+        match input.take(1)?[0] {
+            KEYFRAME => {
+                let dimensions = Coordinate::new(input.u16()? as usize, input.u16()? as usize);
+                let superpositions = (0..dimensions.product())
+                    .map(|_| input.superposition())
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.snapshot = Some(Grid::new(dimensions, superpositions));
+            }
+            DIFF => {
+                let mut snapshot = self
+                    .snapshot
+                    .take()
+                    .ok_or_else(|| "diff before the first keyframe".to_string())?;
+                for _ in 0..input.u32()? {
+                    let position = input.u32()? as usize;
+                    let superposition = input.superposition()?;
+                    let index = Coordinate::new(
+                        position / snapshot.columns().max(1),
+                        position % snapshot.columns().max(1),
+                    );
+                    snapshot
+                        .set(index.map(|x| x as isize), superposition)
+                        .map_err(|_| format!("position {position} outside of the level"))?;
+                }
+                self.snapshot = Some(snapshot);
+            }
+            kind => return Err(format!("unknown frame kind {kind}")),
+        }
+        if !input.0.is_empty() {
+            return Err("trailing bytes after the frame".to_string());
+        }
+        Ok(self
+            .snapshot
+            .as_ref()
+            .expect("Logical error: no snapshot decoded"))
+    }
+}
+
+/// remaining bytes of a frame being decoded
+struct Frame<'a>(&'a [u8]);
+
+impl<'a> Frame<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], String> {
+        if self.0.len() < count {
+            return Err("truncated frame".to_string());
+        }
+        let (bytes, rest) = self.0.split_at(count);
+        self.0 = rest;
+        Ok(bytes)
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn superposition<A: Topology>(&mut self) -> Result<Superposition<A>, String> {
+        let bits = self
+            .take(width::<A>())?
+            .iter()
+            .fold(0_u64, |bits, &byte| bits << 8 | byte as u64);
+        Ok(EnumSet::unchecked_index_to_enum(bits))
+    }
+}
+
+/// Snapshots of solving the level, see [`trace`]
+///
+/// starts with the superimposed level, followed by a snapshot after every sweep of the solver over the level
+/// and after every branch, backtrack and the solution
+pub fn solving_snapshots<A: Topology>(
+    grid: &Grid<Tile<A>>,
+) -> impl Iterator<Item = Grid<Superposition<A>>> {
+    let start = grid.map(Tile::superimpose);
+    std::iter::once(start.clone()).chain(SolvingSnapshots {
+        trace: trace(grid),
+        snapshot: start,
+        from: None,
+        changed: false,
+    })
+}
+
+struct SolvingSnapshots<A> {
+    trace: SolverTrace<A>,
+    snapshot: Grid<Superposition<A>>,
+    /// origin of the latest propagated constraint of the current sweep
+    from: Option<Coordinate<isize>>,
+    /// whether the current sweep changed the snapshot
+    changed: bool,
+}
+
+impl<A: Topology> Iterator for SolvingSnapshots<A> {
+    type Item = Grid<Superposition<A>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.trace.next() {
+                None => {
+                    return std::mem::take(&mut self.changed).then(|| self.snapshot.clone());
+                }
+                Some(SolverEvent::ConstraintPropagated { from }) => {
+                    // sweeps propagate the constraints in row-major order
+                    let sweep_ended = self.from.map_or(false, |previous| from <= previous);
+                    self.from = Some(from);
+                    if sweep_ended && std::mem::take(&mut self.changed) {
+                        return Some(self.snapshot.clone());
+                    }
+                }
+                Some(SolverEvent::SuperpositionReduced { index, remaining }) => {
+                    self.changed |= self.snapshot.set(index, remaining).is_ok();
+                }
+                Some(SolverEvent::BranchTaken { index, tile, .. }) => {
+                    self.snapshot.set(index, EnumSet::from(tile)).ok();
+                    return Some(self.taken());
+                }
+                Some(SolverEvent::Backtracked { resumed, .. }) => {
+                    self.snapshot = resumed;
+                    return Some(self.taken());
+                }
+                Some(SolverEvent::Solved(solution)) => {
+                    self.snapshot = solution.map(EnumSet::from);
+                    return Some(self.taken());
+                }
+            }
+        }
+    }
+}
+
+impl<A: Topology> SolvingSnapshots<A> {
+    /// snapshot after the search took a new direction, the next event starts a new sweep
+    fn taken(&mut self) -> Grid<Superposition<A>> {
+        self.from = None;
+        self.changed = false;
+        self.snapshot.clone()
+    }
+}
+
+/// Snapshots of generating a level with wave function collapse, one per iteration of the generator
+pub fn generation_snapshots<A: Topology>(
+    generator: WfcGenerator<A>,
+    seed: u64,
+) -> impl Iterator<Item = Grid<Superposition<A>>>
+where
+    Tile<A>: Display,
+{
+    let mut rng = RngSource::seeded(seed);
+    std::iter::successors(Some(generator.start()), move |state| {
+        (!state.is_finished())
+            .then(|| generator.step_budgeted_with(state.clone(), 0.0, || 0.0, &mut rng))
+    })
+    .map(|state| state.board().extract_grid())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::core::interval::{Interval, Max};
+    use crate::generator::{fastgen::generate, wfc::WfcGenerator};
+    use crate::model::{
+        coordinate::Coordinate,
+        grid::Grid,
+        tile::{Hex, Square, Tile},
+    };
+    use crate::solver::propagationsolver::Superposition;
+
+    use super::{generation_snapshots, solving_snapshots, SnapshotDecoder, SnapshotEncoder};
+
+    /// whether decoding the encoded snapshots yields them again
+    fn roundtrips<A: crate::model::tile::Topology>(snapshots: &[Grid<Superposition<A>>]) -> bool {
+        let mut encoder = SnapshotEncoder::new();
+        let mut decoder = SnapshotDecoder::new();
+        snapshots.iter().all(|snapshot| {
+            let frame = encoder.encode(snapshot).unwrap();
+            decoder.decode(&frame) == Ok(snapshot)
+        })
+    }
+
+    #[quickcheck]
+    fn decoding_reproduces_arbitrary_snapshots(
+        snapshots: Vec<Grid<Superposition<Square>>>,
+    ) -> bool {
+        roundtrips(&snapshots)
+    }
+
+    #[quickcheck]
+    fn decoding_reproduces_solving_snapshots(dimension: Coordinate<Max<8>>, seed: u64) -> bool {
+        let grid = generate(dimension.map(Interval::to_usize), seed).scramble(seed);
+        let snapshots = solving_snapshots(&grid).collect::<Vec<_>>();
+        let solution = grid.solve().next().map(|s| s.map(Into::into));
+        roundtrips(&snapshots) && snapshots.last() == solution.as_ref()
+    }
+
+    #[quickcheck]
+    fn decoding_reproduces_snapshots_of_other_topologies(grid: Grid<Tile<Hex>>) -> bool {
+        roundtrips(&solving_snapshots(&grid).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn generation_ends_collapsed() {
+        let snapshots = generation_snapshots(WfcGenerator::<Square>::with_all_tiles(6, 4), 7)
+            .collect::<Vec<_>>();
+        assert!(snapshots.len() > 1);
+        assert!(snapshots
+            .iter()
+            .all(|s| s.dimensions() == Coordinate::new(4, 6)));
+        assert!(snapshots
+            .last()
+            .unwrap()
+            .as_slice()
+            .iter()
+            .all(|superposition| superposition.len() == 1));
+        assert!(roundtrips(&snapshots));
+    }
+
+    #[test]
+    fn small_changes_are_encoded_as_diffs() {
+        let mut snapshot = Grid::new(
+            Coordinate::new(10, 10),
+            vec![Tile::superimpose(Tile::<Square>::ALL_CONNECTIONS); 100],
+        );
+        let mut encoder = SnapshotEncoder::new();
+        let keyframe = encoder.encode(&snapshot).unwrap();
+        assert_eq!(keyframe.len(), 1 + 4 + 100 * 2);
+        snapshot
+            .set(
+                Coordinate::new(3, 4),
+                Tile::superimpose(Tile::NO_CONNECTIONS),
+            )
+            .unwrap();
+        let diff = encoder.encode(&snapshot).unwrap();
+        assert_eq!(diff, vec![1, 0, 0, 0, 1, 0, 0, 0, 34, 0, 1]);
+        let mut decoder = SnapshotDecoder::<Square>::new();
+        assert!(decoder.decode(&diff).is_err());
+        decoder.decode(&keyframe).unwrap();
+        assert_eq!(decoder.decode(&diff), Ok(&snapshot));
+        assert!(decoder.decode(&diff[..5]).is_err());
+    }
+}
@@ -0,0 +1,234 @@
+//! Step-by-step trace of the propagation solver, e.g. for animating how a level is solved
+//!
+//! follows the same search as [`Grid::solve`], but reports its internals as [`SolverEvent`]s.
+//! Coordinates refer to the level, sources of constraints outside of it are the empty border
+
+use std::collections::VecDeque;
+use std::fmt::Debug;
+
+use crate::core::finite::Finite;
+use crate::model::{
+    coordinate::Coordinate,
+    grid::Grid,
+    tile::{Tile, Topology},
+};
+
+use super::propagationsolver::{
+    most_superimposed_states, propagate_restrictions_to_all_neighbors2, Sentinel, Superposition,
+};
+
+/// Internal step of the propagation solver
+#[derive(Clone, PartialEq, Eq)]
+pub enum SolverEvent<A> {
+    /// the connections common to all states of the tile restricted its neighbors
+    ConstraintPropagated { from: Coordinate<isize> },
+    /// the superposition of the tile lost states through the latest propagated constraint
+    SuperpositionReduced {
+        index: Coordinate<isize>,
+        remaining: Superposition<A>,
+    },
+    /// propagation is stuck, the tile is fixed tentatively, the other states are alternatives
+    BranchTaken {
+        index: Coordinate<isize>,
+        tile: Tile<A>,
+        alternatives: usize,
+    },
+    /// contradiction, the search resumes with the most recent alternative
+    Backtracked {
+        resumed: Grid<Superposition<A>>,
+        alternatives: usize,
+    },
+    /// all superpositions collapsed, ends the trace
+    Solved(Grid<Tile<A>>),
+}
+
+impl<A: Finite + Debug> Debug for SolverEvent<A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolverEvent::ConstraintPropagated { from } => {
+                write!(f, "ConstraintPropagated {{ from: {from:?} }}")
+            }
+            SolverEvent::SuperpositionReduced { index, remaining } => write!(
+                f,
+                "SuperpositionReduced {{ index: {index:?}, remaining: {remaining:?} }}"
+            ),
+            SolverEvent::BranchTaken {
+                index,
+                tile,
+                alternatives,
+            } => write!(
+                f,
+                "BranchTaken {{ index: {index:?}, tile: {tile:?}, alternatives: {alternatives} }}"
+            ),
+            SolverEvent::Backtracked {
+                resumed,
+                alternatives,
+            } => write!(
+                f,
+                "Backtracked {{ resumed: {resumed:?}, alternatives: {alternatives} }}"
+            ),
+            SolverEvent::Solved(grid) => write!(f, "Solved({grid:?})"),
+        }
+    }
+}
+
+/// Lazy trace of the search for the first solution
+///
+/// ends after [`SolverEvent::Solved`], or without it if the level has no solution
+pub struct SolverTrace<A> {
+    /// candidate being minimized
+    current: Option<Sentinel<A>>,
+    /// alternatives of the branches taken, most recent last
+    alternatives: Vec<Sentinel<A>>,
+    /// events of the latest step not yet yielded
+    pending: VecDeque<SolverEvent<A>>,
+}
+
+/// Traces solving the level
+pub fn trace<A: Topology>(grid: &Grid<Tile<A>>) -> SolverTrace<A> {
+    SolverTrace {
+        current: Some(grid.with_sentinels(Tile::NO_CONNECTIONS).superimpose()),
+        alternatives: vec![],
+        pending: VecDeque::new(),
+    }
+}
+
+impl<A: Topology> SolverTrace<A> {
+    /// sweeps the candidate once, branches, backtracks or finishes if the sweep changed nothing
+    fn step(&mut self, grid: Sentinel<A>) {
+        let (grid, changed) = self.sweep(grid);
+        if changed {
+            self.current = Some(grid);
+            return;
+        }
+        if let Some(solution) = grid.extract_if_collapsed() {
+            self.pending.push_back(SolverEvent::Solved(solution));
+            return;
+        }
+        match grid.check_no_empty_superposition() {
+            Some(grid) => {
+                let index = most_superimposed_states(&grid);
+                let mut branches = grid.branch(most_superimposed_states);
+                let taken = branches
+                    .pop()
+                    .expect("Logical error: superposition of uncollapsed tile is empty");
+                self.pending.push_back(SolverEvent::BranchTaken {
+                    index: grid.grid_index(index),
+                    tile: taken.0[index]
+                        .unwrap_if_singleton()
+                        .expect("Logical error: branch is not collapsed"),
+                    alternatives: branches.len(),
+                });
+                self.alternatives.extend(branches);
+                self.current = Some(taken);
+            }
+            None => {
+                self.current = self.alternatives.pop();
+                if let Some(resumed) = &self.current {
+                    self.pending.push_back(SolverEvent::Backtracked {
+                        resumed: resumed.extract_grid(),
+                        alternatives: self.alternatives.len(),
+                    });
+                }
+            }
+        }
+    }
+
+    /// propagates the constraints of every tile once, returns whether any superposition changed
+    fn sweep(&mut self, grid: Sentinel<A>) -> (Sentinel<A>, bool) {
+        let mut changed = false;
+        let grid = grid.0.coordinates().fold(grid.clone(), |grid, from| {
+            let (grid, reduced) =
+                propagate_restrictions_to_all_neighbors2(grid, from, PartialEq::ne);
+            if !reduced.is_empty() {
+                changed = true;
+                self.pending.push_back(SolverEvent::ConstraintPropagated {
+                    from: grid.grid_index(from),
+                });
+                self.pending.extend(reduced.into_iter().map(|index| {
+                    SolverEvent::SuperpositionReduced {
+                        index: grid.grid_index(index),
+                        remaining: grid.0[index],
+                    }
+                }));
+            }
+            grid
+        });
+        (grid, changed)
+    }
+}
+
+impl<A: Topology> Iterator for SolverTrace<A> {
+    type Item = SolverEvent<A>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(event);
+            }
+            let grid = self.current.take()?;
+            self.step(grid);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::core::interval::{Interval, Max};
+    use crate::generator::fastgen::generate;
+    use crate::model::{
+        coordinate::Coordinate,
+        grid::Grid,
+        parser::{parse_level, unicode_to_tile},
+        tile::{Hex, Square, Tile},
+    };
+
+    use super::{trace, SolverEvent};
+
+    #[quickcheck]
+    fn trace_ends_with_the_first_solution(dimension: Coordinate<Max<8>>, seed: u64) -> bool {
+        let grid = generate(dimension.map(Interval::to_usize), seed).scramble(seed);
+        trace(&grid).last() == grid.solve().next().map(SolverEvent::Solved)
+    }
+
+    #[quickcheck]
+    fn traces_of_other_topologies_end_with_the_first_solution(grid: Grid<Tile<Hex>>) -> bool {
+        trace(&grid)
+            .last()
+            .filter(|event| matches!(event, SolverEvent::Solved(_)))
+            == grid.solve().next().map(SolverEvent::Solved)
+    }
+
+    #[quickcheck]
+    fn ambiguous_levels_require_branching(dimension: Coordinate<Max<5>>, seed: u64) -> bool {
+        let grid = generate(dimension.map(Interval::to_usize), seed);
+        let ambiguous = grid.solve().take(2).count() == 2;
+        !ambiguous || trace(&grid).any(|event| matches!(event, SolverEvent::BranchTaken { .. }))
+    }
+
+    #[test]
+    fn reductions_follow_their_constraint() {
+        let grid = parse_level("┛┗\n┓┏", unicode_to_tile).unwrap();
+        let events = trace(&grid).collect::<Vec<_>>();
+        assert!(matches!(
+            events.first(),
+            Some(SolverEvent::ConstraintPropagated { .. })
+        ));
+        assert!(events.iter().all(|event| !matches!(
+            event,
+            SolverEvent::BranchTaken { .. } | SolverEvent::Backtracked { .. }
+        )));
+        assert!(events.iter().any(|event| matches!(
+            event,
+            SolverEvent::SuperpositionReduced { index, remaining }
+                if *index == Coordinate::new(0, 0) && remaining.len() == 1
+        )));
+    }
+
+    #[test]
+    fn unsolvable_levels_are_never_solved() {
+        let grid = Grid::new(Coordinate::new(1, 1), vec![Tile::<Square>::ALL_CONNECTIONS]);
+        assert!(!trace(&grid).any(|event| matches!(event, SolverEvent::Solved(_))));
+    }
+}